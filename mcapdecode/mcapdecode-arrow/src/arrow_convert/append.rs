@@ -1,173 +1,354 @@
-use arrow::{
-    array::{
-        ArrayBuilder, BinaryBuilder, BooleanBuilder, FixedSizeListBuilder, Float32Builder,
-        Float64Builder, Int8Builder, Int16Builder, Int32Builder, Int64Builder, ListBuilder,
-        MapBuilder, NullBuilder, StringBuilder, StructBuilder, TimestampNanosecondBuilder,
-        UInt8Builder, UInt16Builder, UInt32Builder, UInt64Builder,
-    },
-    datatypes::DataType,
-};
+use std::{cmp::Ordering, collections::HashSet};
+
 use mcapdecode_core::{Value, ValueTypeError};
 
-use super::scalar::{ScalarValue, scalar_value_for_datatype};
+use super::{
+    ConvertOptions, DuplicateMapKeys, StructMismatch, WideningPolicy,
+    builder::{ColumnBuilder, StructColumnBuilder},
+};
+use crate::error::ArrowConvertError;
 
-macro_rules! cast_builder {
-    ($b:expr, $T:ty) => {
-        $b.as_any_mut()
-            .downcast_mut::<$T>()
-            .expect(concat!("expected builder type: ", stringify!($T)))
-    };
+/// Options and counters shared by the [`ColumnBuilder::append`] calls of
+/// one batch.
+pub(super) struct AppendContext {
+    widening: WideningPolicy,
+    struct_mismatch: StructMismatch,
+    /// Struct values aligned to their schema by [`StructMismatch::Align`].
+    pub(super) aligned_structs: u64,
 }
 
-fn append_scalar_dyn(builder: &mut Box<dyn ArrayBuilder>, scalar: &ScalarValue<'_>) {
-    match scalar {
-        ScalarValue::Null => cast_builder!(builder, NullBuilder).append_null(),
-        ScalarValue::Boolean(v) => cast_builder!(builder, BooleanBuilder).append_option(*v),
-        ScalarValue::Int8(v) => cast_builder!(builder, Int8Builder).append_option(*v),
-        ScalarValue::Int16(v) => cast_builder!(builder, Int16Builder).append_option(*v),
-        ScalarValue::Int32(v) => cast_builder!(builder, Int32Builder).append_option(*v),
-        ScalarValue::Int64(v) => cast_builder!(builder, Int64Builder).append_option(*v),
-        ScalarValue::UInt8(v) => cast_builder!(builder, UInt8Builder).append_option(*v),
-        ScalarValue::UInt16(v) => cast_builder!(builder, UInt16Builder).append_option(*v),
-        ScalarValue::UInt32(v) => cast_builder!(builder, UInt32Builder).append_option(*v),
-        ScalarValue::UInt64(v) => cast_builder!(builder, UInt64Builder).append_option(*v),
-        ScalarValue::Float32(v) => cast_builder!(builder, Float32Builder).append_option(*v),
-        ScalarValue::Float64(v) => cast_builder!(builder, Float64Builder).append_option(*v),
-        ScalarValue::Utf8(v) => cast_builder!(builder, StringBuilder).append_option(*v),
-        ScalarValue::Binary(v) => cast_builder!(builder, BinaryBuilder).append_option(*v),
-        ScalarValue::TimestampNanosecond(v) => {
-            cast_builder!(builder, TimestampNanosecondBuilder).append_option(*v)
+impl AppendContext {
+    pub(super) fn new(options: &ConvertOptions) -> Self {
+        Self {
+            widening: options.widening,
+            struct_mismatch: options.struct_mismatch,
+            aligned_structs: 0,
         }
     }
-}
 
-fn append_list_elements(
-    child_builder: &mut Box<dyn ArrayBuilder>,
-    elem_dt: &DataType,
-    value: &Value,
-) -> Result<bool, ValueTypeError> {
-    match value {
-        Value::List(items) => {
-            for item in items {
-                append_value_to_builder(child_builder, elem_dt, item)?;
+    /// Check a struct value with `actual` fields against a schema of
+    /// `expected` fields.
+    pub(super) fn check_struct_len(
+        &mut self,
+        expected: usize,
+        actual: usize,
+    ) -> Result<(), ArrowConvertError> {
+        if expected == actual {
+            return Ok(());
+        }
+        match self.struct_mismatch {
+            StructMismatch::Error => Err(ArrowConvertError::StructFieldCount { expected, actual }),
+            StructMismatch::Align => {
+                self.aligned_structs += 1;
+                Ok(())
             }
-            Ok(true)
         }
-        Value::Null => Ok(false),
-        _ => Err(value.type_mismatch("List")),
     }
 }
 
-fn append_map_entries(
-    map_builder: &mut MapBuilder<Box<dyn ArrayBuilder>, Box<dyn ArrayBuilder>>,
-    fields: &arrow::datatypes::Fields,
-    value: &Value,
-) -> Result<bool, ValueTypeError> {
-    match value {
-        Value::Map(entries) => {
-            for (key, map_value) in entries {
-                append_value_to_builder(map_builder.keys(), fields[0].data_type(), key)?;
-                append_value_to_builder(map_builder.values(), fields[1].data_type(), map_value)?;
+impl ColumnBuilder {
+    /// Append `value`, converted to the column's data type.
+    pub(super) fn append(
+        &mut self,
+        value: &Value,
+        cx: &mut AppendContext,
+    ) -> Result<(), ArrowConvertError> {
+        let widening = cx.widening;
+        match self {
+            Self::Null(b) => b.append_null(),
+            Self::Boolean(b) => b.append_option(value.try_bool()?),
+            Self::Int8(b) => b.append_option(value.try_i8()?),
+            Self::Int16(b) => {
+                b.append_option(widen_int(value.try_i16(), value, 16, true, widening)?)
+            }
+            Self::Int32(b) => {
+                b.append_option(widen_int(value.try_i32(), value, 32, true, widening)?)
+            }
+            Self::Int64(b) => {
+                b.append_option(widen_int(value.try_i64(), value, 64, true, widening)?)
+            }
+            Self::UInt8(b) => b.append_option(value.try_u8()?),
+            Self::UInt16(b) => {
+                b.append_option(widen_int(value.try_u16(), value, 16, false, widening)?)
+            }
+            Self::UInt32(b) => {
+                b.append_option(widen_int(value.try_u32(), value, 32, false, widening)?)
+            }
+            Self::UInt64(b) => {
+                b.append_option(widen_int(value.try_u64(), value, 64, false, widening)?)
+            }
+            Self::Float32(b) => {
+                let exact = value.try_f32().map(|v| v.map(f64::from));
+                b.append_option(
+                    widen_float(exact, value, f32::MANTISSA_DIGITS, widening)?.map(|v| v as f32),
+                );
+            }
+            Self::Float64(b) => b.append_option(widen_float(
+                value.try_f64(),
+                value,
+                f64::MANTISSA_DIGITS,
+                widening,
+            )?),
+            Self::Utf8(b) => b.append_option(value.try_str()?),
+            Self::Binary(b) => b.append_option(value.try_bytes()?),
+            Self::FixedSizeBinary(b, width) => match value.try_bytes()? {
+                Some(bytes) => {
+                    if bytes.len() != *width as usize {
+                        return Err(ValueTypeError::new(
+                            format!("FixedSizeBinary(length={width})"),
+                            format!("Bytes(length={})", bytes.len()),
+                        )
+                        .into());
+                    }
+                    b.append_value(bytes)?;
+                }
+                None => b.append_null(),
+            },
+            Self::TimestampNanosecond(b) => {
+                b.append_option(widen_int(value.try_i64(), value, 64, true, widening)?)
             }
-            Ok(true)
+            Self::List(b) => match value {
+                Value::List(items) => {
+                    for item in items {
+                        b.values().append(item, cx)?;
+                    }
+                    b.append(true);
+                }
+                Value::Null => b.append(false),
+                _ => return Err(value.type_mismatch("List").into()),
+            },
+            Self::FixedSizeList(b, elem_field) => {
+                let size = b.value_length();
+                match value {
+                    Value::Array(items) => {
+                        if items.len() != size as usize {
+                            return Err(ValueTypeError::new(
+                                format!("FixedSizeList(length={size})"),
+                                format!("Array(length={})", items.len()),
+                            )
+                            .into());
+                        }
+                        for item in items {
+                            b.values().append(item, cx)?;
+                        }
+                        b.append(true);
+                    }
+                    Value::Null => {
+                        for _ in 0..size {
+                            b.values().append_masked(elem_field.is_nullable())?;
+                        }
+                        b.append(false);
+                    }
+                    _ => return Err(value.type_mismatch("Array").into()),
+                }
+            }
+            Self::Struct(b) => match value {
+                Value::Struct(children) => {
+                    cx.check_struct_len(b.children.len(), children.len())?;
+                    for (i, (child, field)) in
+                        b.children.iter_mut().zip(b.fields.iter()).enumerate()
+                    {
+                        match children.get(i) {
+                            Some(child_value) => child.append(child_value, cx)?,
+                            None => child.append_masked(field.is_nullable())?,
+                        }
+                    }
+                    b.nulls.append_non_null();
+                }
+                Value::Null => {
+                    b.append_masked_children()?;
+                    b.nulls.append_null();
+                }
+                _ => return Err(value.type_mismatch("Struct").into()),
+            },
+            Self::Map(b, keys_sorted, duplicate_keys) => match value {
+                Value::Map(entries) => {
+                    let mut entries = unique_map_entries(entries, *duplicate_keys)?;
+                    if *keys_sorted {
+                        entries.sort_by(|(a, _), (b, _)| compare_keys(a, b));
+                    }
+                    for (key, map_value) in entries {
+                        b.keys().append(key, cx)?;
+                        b.values().append(map_value, cx)?;
+                    }
+                    b.append(true).expect("MapBuilder::append");
+                }
+                Value::Null => b.append(false).expect("MapBuilder::append"),
+                _ => return Err(value.type_mismatch("Map").into()),
+            },
         }
-        Value::Null => Ok(false),
-        _ => Err(value.type_mismatch("Map")),
+        Ok(())
     }
-}
 
-fn append_fixed_size_list_elements(
-    child_builder: &mut Box<dyn ArrayBuilder>,
-    elem_dt: &DataType,
-    size: i32,
-    value: &Value,
-) -> Result<bool, ValueTypeError> {
-    match value {
-        Value::Array(items) => {
-            if items.len() != size as usize {
-                return Err(ValueTypeError::new(
-                    format!("FixedSizeList(length={size})"),
-                    format!("Array(length={})", items.len()),
-                ));
+    /// Append the child slot of a null parent (struct or fixed-size list).
+    ///
+    /// The slot is hidden by the parent's validity, so non-nullable fields get
+    /// a default value instead of a null; their arrays then need no validity
+    /// buffer.
+    pub(super) fn append_masked(&mut self, nullable: bool) -> Result<(), ArrowConvertError> {
+        if nullable {
+            return self.append(
+                &Value::Null,
+                &mut AppendContext::new(&ConvertOptions::default()),
+            );
+        }
+        match self {
+            Self::Null(b) => b.append_null(),
+            Self::Boolean(b) => b.append_value(false),
+            Self::Int8(b) => b.append_value(0),
+            Self::Int16(b) => b.append_value(0),
+            Self::Int32(b) => b.append_value(0),
+            Self::Int64(b) => b.append_value(0),
+            Self::UInt8(b) => b.append_value(0),
+            Self::UInt16(b) => b.append_value(0),
+            Self::UInt32(b) => b.append_value(0),
+            Self::UInt64(b) => b.append_value(0),
+            Self::Float32(b) => b.append_value(0.0),
+            Self::Float64(b) => b.append_value(0.0),
+            Self::Utf8(b) => b.append_value(""),
+            Self::Binary(b) => b.append_value(b""),
+            Self::FixedSizeBinary(b, width) => b.append_value(vec![0; *width as usize])?,
+            Self::TimestampNanosecond(b) => b.append_value(0),
+            Self::List(b) => b.append(true),
+            Self::FixedSizeList(b, elem_field) => {
+                for _ in 0..b.value_length() {
+                    b.values().append_masked(elem_field.is_nullable())?;
+                }
+                b.append(true);
             }
-            for item in items {
-                append_value_to_builder(child_builder, elem_dt, item)?;
+            Self::Struct(b) => {
+                b.append_masked_children()?;
+                b.nulls.append_non_null();
             }
-            Ok(true)
+            Self::Map(b, ..) => b.append(true).expect("MapBuilder::append"),
         }
-        Value::Null => {
-            for _ in 0..size {
-                append_value_to_builder(child_builder, elem_dt, &Value::Null)?;
-            }
-            Ok(false)
+        Ok(())
+    }
+}
+
+impl StructColumnBuilder {
+    /// Fill the fields of a struct slot that is null or itself masked.
+    fn append_masked_children(&mut self) -> Result<(), ArrowConvertError> {
+        for (child, field) in self.children.iter_mut().zip(self.fields.iter()) {
+            child.append_masked(field.is_nullable())?;
         }
-        _ => Err(value.type_mismatch("Array")),
+        Ok(())
     }
 }
 
-pub(super) fn append_value_to_builder(
-    builder: &mut Box<dyn ArrayBuilder>,
-    dt: &DataType,
+/// `exact`, or on a type mismatch, `value` converted to an integer column of
+/// `bits` bits if it is an integer that always fits and `widening` allows it.
+fn widen_int<T: TryFrom<i128>>(
+    exact: Result<Option<T>, ValueTypeError>,
     value: &Value,
-) -> Result<(), ValueTypeError> {
-    if let Some(scalar) = scalar_value_for_datatype(dt, value)? {
-        append_scalar_dyn(builder, &scalar);
-        return Ok(());
+    bits: u32,
+    signed: bool,
+    widening: WideningPolicy,
+) -> Result<Option<T>, ValueTypeError> {
+    let Err(err) = exact else {
+        return exact;
+    };
+    if widening != WideningPolicy::Lossless {
+        return Err(err);
     }
-
-    match dt {
-        DataType::List(field) => {
-            let b = cast_builder!(builder, ListBuilder<Box<dyn ArrayBuilder>>);
-            let valid = append_list_elements(b.values(), field.data_type(), value)?;
-            b.append(valid);
-        }
-        DataType::FixedSizeList(field, size) => {
-            let b = cast_builder!(builder, FixedSizeListBuilder<Box<dyn ArrayBuilder>>);
-            let valid =
-                append_fixed_size_list_elements(b.values(), field.data_type(), *size, value)?;
-            b.append(valid);
+    match int_parts(value) {
+        // Unsigned fits into signed only with a spare bit for the sign.
+        Some((v, src_bits, src_signed))
+            if (src_signed == signed && src_bits <= bits)
+                || (!src_signed && signed && src_bits < bits) =>
+        {
+            T::try_from(v).map(Some).map_err(|_| err)
         }
-        DataType::Struct(fields) => {
-            let b = cast_builder!(builder, StructBuilder);
-            match value {
-                Value::Struct(children) => {
-                    for (i, field) in fields.iter().enumerate() {
-                        let child_value = children.get(i).unwrap_or(&Value::Null);
-                        append_value_to_struct_field(b, i, field.data_type(), child_value)?;
-                    }
-                    b.append(true);
-                }
-                Value::Null => {
-                    for (i, field) in fields.iter().enumerate() {
-                        append_value_to_struct_field(b, i, field.data_type(), &Value::Null)?;
-                    }
-                    b.append(false);
-                }
-                _ => return Err(value.type_mismatch("Struct")),
+        _ => Err(err),
+    }
+}
+
+/// `exact`, or on a type mismatch, `value` converted to a float column with
+/// `mantissa` significand bits if it is a number that always fits and
+/// `widening` allows it.
+fn widen_float(
+    exact: Result<Option<f64>, ValueTypeError>,
+    value: &Value,
+    mantissa: u32,
+    widening: WideningPolicy,
+) -> Result<Option<f64>, ValueTypeError> {
+    let Err(err) = exact else {
+        return exact;
+    };
+    if widening != WideningPolicy::Lossless {
+        return Err(err);
+    }
+    match (value, int_parts(value)) {
+        (Value::F32(v), _) if mantissa >= f32::MANTISSA_DIGITS => Ok(Some(f64::from(*v))),
+        (_, Some((v, bits, signed))) if bits - u32::from(signed) <= mantissa => Ok(Some(v as f64)),
+        _ => Err(err),
+    }
+}
+
+/// Integer `value` with the bit width and signedness of its type.
+fn int_parts(value: &Value) -> Option<(i128, u32, bool)> {
+    match value {
+        Value::I8(v) => Some(((*v).into(), 8, true)),
+        Value::I16(v) => Some(((*v).into(), 16, true)),
+        Value::I32(v) => Some(((*v).into(), 32, true)),
+        Value::I64(v) => Some(((*v).into(), 64, true)),
+        Value::U8(v) => Some(((*v).into(), 8, false)),
+        Value::U16(v) => Some(((*v).into(), 16, false)),
+        Value::U32(v) => Some(((*v).into(), 32, false)),
+        Value::U64(v) => Some(((*v).into(), 64, false)),
+        _ => None,
+    }
+}
+
+/// Entries of a map with repeated keys handled according to `policy`.
+fn unique_map_entries(
+    entries: &[(Value, Value)],
+    policy: DuplicateMapKeys,
+) -> Result<Vec<&(Value, Value)>, ArrowConvertError> {
+    if entries.len() < 2 {
+        return Ok(entries.iter().collect());
+    }
+    let mut seen = HashSet::with_capacity(entries.len());
+    match policy {
+        DuplicateMapKeys::Error => {
+            if let Some((key, _)) = entries.iter().find(|(key, _)| !seen.insert(key)) {
+                return Err(ArrowConvertError::DuplicateMapKey {
+                    key: format!("{key:?}"),
+                });
             }
+            Ok(entries.iter().collect())
         }
-        DataType::Map(entry_field, _) => {
-            let b = cast_builder!(
-                builder,
-                MapBuilder<Box<dyn ArrayBuilder>, Box<dyn ArrayBuilder>>
-            );
-            let fields = match entry_field.data_type() {
-                DataType::Struct(fields) if fields.len() == 2 => fields,
-                other => panic!("Map entry field must be Struct with 2 fields, got: {other:?}"),
-            };
-            let valid = append_map_entries(b, fields, value)?;
-            b.append(valid).expect("MapBuilder::append");
+        DuplicateMapKeys::KeepFirst => {
+            Ok(entries.iter().filter(|(key, _)| seen.insert(key)).collect())
+        }
+        DuplicateMapKeys::KeepLast => {
+            let mut kept: Vec<_> = entries
+                .iter()
+                .rev()
+                .filter(|(key, _)| seen.insert(key))
+                .collect();
+            kept.reverse();
+            Ok(kept)
         }
-        other => panic!("unsupported DataType in append_value_to_builder: {other:?}"),
     }
-    Ok(())
 }
 
-fn append_value_to_struct_field(
-    sb: &mut StructBuilder,
-    index: usize,
-    dt: &DataType,
-    value: &Value,
-) -> Result<(), ValueTypeError> {
-    append_value_to_builder(&mut sb.field_builders_mut()[index], dt, value)
+/// Order of map keys of the same scalar type; other keys keep their order.
+fn compare_keys(a: &Value, b: &Value) -> Ordering {
+    match (a, b) {
+        (Value::Bool(a), Value::Bool(b)) => a.cmp(b),
+        (Value::I8(a), Value::I8(b)) => a.cmp(b),
+        (Value::I16(a), Value::I16(b)) => a.cmp(b),
+        (Value::I32(a), Value::I32(b)) => a.cmp(b),
+        (Value::I64(a), Value::I64(b)) => a.cmp(b),
+        (Value::U8(a), Value::U8(b)) => a.cmp(b),
+        (Value::U16(a), Value::U16(b)) => a.cmp(b),
+        (Value::U32(a), Value::U32(b)) => a.cmp(b),
+        (Value::U64(a), Value::U64(b)) => a.cmp(b),
+        (Value::F32(a), Value::F32(b)) => a.total_cmp(b),
+        (Value::F64(a), Value::F64(b)) => a.total_cmp(b),
+        (Value::String(a), Value::String(b)) => a.cmp(b),
+        (Value::Bytes(a), Value::Bytes(b)) => a.cmp(b),
+        _ => Ordering::Equal,
+    }
 }
@@ -1,59 +1,115 @@
+use std::{any::Any, sync::Arc};
+
 use arrow::{
     array::{
-        ArrayBuilder, BinaryBuilder, BooleanBuilder, FixedSizeListBuilder, Float32Builder,
-        Float64Builder, Int8Builder, Int16Builder, Int32Builder, Int64Builder, ListBuilder,
-        MapBuilder, MapFieldNames, NullBuilder, StringBuilder, StructBuilder,
-        TimestampNanosecondBuilder, UInt8Builder, UInt16Builder, UInt32Builder, UInt64Builder,
+        ArrayBuilder, ArrayRef, BinaryBuilder, BooleanBuilder, FixedSizeBinaryBuilder,
+        FixedSizeListBuilder, Float32Builder, Float64Builder, Int8Builder, Int16Builder,
+        Int32Builder, Int64Builder, ListBuilder, MapArray, MapBuilder, MapFieldNames,
+        NullBufferBuilder, NullBuilder, StringBuilder, StructArray, TimestampNanosecondBuilder,
+        UInt8Builder, UInt16Builder, UInt32Builder, UInt64Builder,
     },
-    datatypes::{DataType, Field, TimeUnit},
+    datatypes::{DataType, FieldRef, Fields, TimeUnit},
 };
 
-pub(super) fn make_builder(dt: &DataType, capacity: usize) -> Box<dyn ArrayBuilder> {
-    match dt {
-        DataType::Null => Box::new(NullBuilder::new()),
-        DataType::Boolean => Box::new(BooleanBuilder::with_capacity(capacity)),
-        DataType::Int8 => Box::new(Int8Builder::with_capacity(capacity)),
-        DataType::Int16 => Box::new(Int16Builder::with_capacity(capacity)),
-        DataType::Int32 => Box::new(Int32Builder::with_capacity(capacity)),
-        DataType::Int64 => Box::new(Int64Builder::with_capacity(capacity)),
-        DataType::UInt8 => Box::new(UInt8Builder::with_capacity(capacity)),
-        DataType::UInt16 => Box::new(UInt16Builder::with_capacity(capacity)),
-        DataType::UInt32 => Box::new(UInt32Builder::with_capacity(capacity)),
-        DataType::UInt64 => Box::new(UInt64Builder::with_capacity(capacity)),
-        DataType::Float32 => Box::new(Float32Builder::with_capacity(capacity)),
-        DataType::Float64 => Box::new(Float64Builder::with_capacity(capacity)),
-        DataType::Utf8 => Box::new(StringBuilder::with_capacity(capacity, 64)),
-        DataType::Binary => Box::new(BinaryBuilder::with_capacity(capacity, 64)),
-        DataType::Timestamp(TimeUnit::Nanosecond, _) => {
-            Box::new(TimestampNanosecondBuilder::with_capacity(capacity))
-        }
-        DataType::List(field) => {
-            let child = make_builder(field.data_type(), capacity);
-            Box::new(ListBuilder::new(child).with_field(field.clone()))
-        }
-        DataType::FixedSizeList(field, size) => {
-            let child = make_builder(field.data_type(), capacity * (*size as usize));
-            Box::new(FixedSizeListBuilder::new(child, *size).with_field(field.clone()))
-        }
-        DataType::Struct(fields) => {
-            let child_builders: Vec<Box<dyn ArrayBuilder>> = fields
-                .iter()
-                .map(|f| make_builder(f.data_type(), capacity))
-                .collect();
-            let fields_vec: Vec<Field> = fields.iter().map(|f| f.as_ref().clone()).collect();
-            Box::new(StructBuilder::new(fields_vec, child_builders))
-        }
-        DataType::Map(entry_field, _) => {
-            let (key_field, value_field) = match entry_field.data_type() {
-                DataType::Struct(fields) if fields.len() == 2 => {
-                    (fields[0].clone(), fields[1].clone())
-                }
-                other => panic!("Map entry field must be Struct with 2 fields, got: {other:?}"),
-            };
-            let key_builder = make_builder(key_field.data_type(), capacity);
-            let value_builder = make_builder(value_field.data_type(), capacity);
-            Box::new(
-                MapBuilder::new(
+use super::{ConvertOptions, DuplicateMapKeys};
+
+/// Typed builder for one column, created once per schema so that appending
+/// a value is a `match` on the builder rather than an `Any` downcast.
+///
+/// Implements [`ArrayBuilder`] so that it can be nested as the child of
+/// Arrow's list and map builders.
+pub(super) enum ColumnBuilder {
+    Null(NullBuilder),
+    Boolean(BooleanBuilder),
+    Int8(Int8Builder),
+    Int16(Int16Builder),
+    Int32(Int32Builder),
+    Int64(Int64Builder),
+    UInt8(UInt8Builder),
+    UInt16(UInt16Builder),
+    UInt32(UInt32Builder),
+    UInt64(UInt64Builder),
+    Float32(Float32Builder),
+    Float64(Float64Builder),
+    Utf8(StringBuilder),
+    Binary(BinaryBuilder),
+    /// Builder and value length.
+    FixedSizeBinary(FixedSizeBinaryBuilder, i32),
+    TimestampNanosecond(TimestampNanosecondBuilder),
+    List(Box<ListBuilder<ColumnBuilder>>),
+    /// Builder and element field, whose nullability decides how the
+    /// elements of null arrays are filled.
+    FixedSizeList(Box<FixedSizeListBuilder<ColumnBuilder>>, FieldRef),
+    Struct(StructColumnBuilder),
+    /// Builder, whether entries are sorted by key (`keys_sorted`) and how
+    /// repeated keys are handled.
+    Map(
+        Box<MapBuilder<ColumnBuilder, ColumnBuilder>>,
+        bool,
+        DuplicateMapKeys,
+    ),
+}
+
+/// Struct builder with typed field builders.
+pub(super) struct StructColumnBuilder {
+    pub(super) fields: Fields,
+    pub(super) children: Vec<ColumnBuilder>,
+    pub(super) nulls: NullBufferBuilder,
+}
+
+impl ColumnBuilder {
+    pub(super) fn new(dt: &DataType, capacity: usize, options: &ConvertOptions) -> Self {
+        match dt {
+            DataType::Null => Self::Null(NullBuilder::new()),
+            DataType::Boolean => Self::Boolean(BooleanBuilder::with_capacity(capacity)),
+            DataType::Int8 => Self::Int8(Int8Builder::with_capacity(capacity)),
+            DataType::Int16 => Self::Int16(Int16Builder::with_capacity(capacity)),
+            DataType::Int32 => Self::Int32(Int32Builder::with_capacity(capacity)),
+            DataType::Int64 => Self::Int64(Int64Builder::with_capacity(capacity)),
+            DataType::UInt8 => Self::UInt8(UInt8Builder::with_capacity(capacity)),
+            DataType::UInt16 => Self::UInt16(UInt16Builder::with_capacity(capacity)),
+            DataType::UInt32 => Self::UInt32(UInt32Builder::with_capacity(capacity)),
+            DataType::UInt64 => Self::UInt64(UInt64Builder::with_capacity(capacity)),
+            DataType::Float32 => Self::Float32(Float32Builder::with_capacity(capacity)),
+            DataType::Float64 => Self::Float64(Float64Builder::with_capacity(capacity)),
+            DataType::Utf8 => Self::Utf8(StringBuilder::with_capacity(capacity, 64)),
+            DataType::Binary => Self::Binary(BinaryBuilder::with_capacity(capacity, 64)),
+            DataType::FixedSizeBinary(width) => Self::FixedSizeBinary(
+                FixedSizeBinaryBuilder::with_capacity(capacity, *width),
+                *width,
+            ),
+            DataType::Timestamp(TimeUnit::Nanosecond, _) => {
+                Self::TimestampNanosecond(TimestampNanosecondBuilder::with_capacity(capacity))
+            }
+            DataType::List(field) => {
+                let child = Self::new(field.data_type(), capacity, options);
+                Self::List(Box::new(ListBuilder::new(child).with_field(field.clone())))
+            }
+            DataType::FixedSizeList(field, size) => {
+                let child = Self::new(field.data_type(), capacity * (*size as usize), options);
+                Self::FixedSizeList(
+                    Box::new(FixedSizeListBuilder::new(child, *size).with_field(field.clone())),
+                    field.clone(),
+                )
+            }
+            DataType::Struct(fields) => Self::Struct(StructColumnBuilder {
+                fields: fields.clone(),
+                children: fields
+                    .iter()
+                    .map(|f| Self::new(f.data_type(), capacity, options))
+                    .collect(),
+                nulls: NullBufferBuilder::new(capacity),
+            }),
+            DataType::Map(entry_field, keys_sorted) => {
+                let (key_field, value_field) = match entry_field.data_type() {
+                    DataType::Struct(fields) if fields.len() == 2 => {
+                        (fields[0].clone(), fields[1].clone())
+                    }
+                    other => panic!("Map entry field must be Struct with 2 fields, got: {other:?}"),
+                };
+                let key_builder = Self::new(key_field.data_type(), capacity, options);
+                let value_builder = Self::new(value_field.data_type(), capacity, options);
+                let builder = MapBuilder::new(
                     Some(MapFieldNames {
                         entry: entry_field.name().to_string(),
                         key: key_field.name().to_string(),
@@ -63,9 +119,135 @@ pub(super) fn make_builder(dt: &DataType, capacity: usize) -> Box<dyn ArrayBuild
                     value_builder,
                 )
                 .with_keys_field(key_field)
-                .with_values_field(value_field),
-            )
+                .with_values_field(value_field);
+                Self::Map(Box::new(builder), *keys_sorted, options.duplicate_map_keys)
+            }
+            other => panic!("unsupported DataType for builder: {other:?}"),
         }
-        other => panic!("unsupported DataType for builder: {other:?}"),
     }
+
+    fn inner(&self) -> &dyn ArrayBuilder {
+        match self {
+            Self::Null(b) => b,
+            Self::Boolean(b) => b,
+            Self::Int8(b) => b,
+            Self::Int16(b) => b,
+            Self::Int32(b) => b,
+            Self::Int64(b) => b,
+            Self::UInt8(b) => b,
+            Self::UInt16(b) => b,
+            Self::UInt32(b) => b,
+            Self::UInt64(b) => b,
+            Self::Float32(b) => b,
+            Self::Float64(b) => b,
+            Self::Utf8(b) => b,
+            Self::Binary(b) => b,
+            Self::FixedSizeBinary(b, _) => b,
+            Self::TimestampNanosecond(b) => b,
+            Self::List(b) => b.as_ref(),
+            Self::FixedSizeList(b, _) => b.as_ref(),
+            Self::Struct(b) => b,
+            Self::Map(b, ..) => b.as_ref(),
+        }
+    }
+
+    fn inner_mut(&mut self) -> &mut dyn ArrayBuilder {
+        match self {
+            Self::Null(b) => b,
+            Self::Boolean(b) => b,
+            Self::Int8(b) => b,
+            Self::Int16(b) => b,
+            Self::Int32(b) => b,
+            Self::Int64(b) => b,
+            Self::UInt8(b) => b,
+            Self::UInt16(b) => b,
+            Self::UInt32(b) => b,
+            Self::UInt64(b) => b,
+            Self::Float32(b) => b,
+            Self::Float64(b) => b,
+            Self::Utf8(b) => b,
+            Self::Binary(b) => b,
+            Self::FixedSizeBinary(b, _) => b,
+            Self::TimestampNanosecond(b) => b,
+            Self::List(b) => b.as_mut(),
+            Self::FixedSizeList(b, _) => b.as_mut(),
+            Self::Struct(b) => b,
+            Self::Map(b, ..) => b.as_mut(),
+        }
+    }
+}
+
+impl ArrayBuilder for ColumnBuilder {
+    fn len(&self) -> usize {
+        self.inner().len()
+    }
+
+    fn finish(&mut self) -> ArrayRef {
+        match self {
+            // `MapBuilder` always produces maps with unsorted keys.
+            Self::Map(b, true, _) => Arc::new(mark_keys_sorted(b.finish())),
+            _ => self.inner_mut().finish(),
+        }
+    }
+
+    fn finish_cloned(&self) -> ArrayRef {
+        match self {
+            Self::Map(b, true, _) => Arc::new(mark_keys_sorted(b.finish_cloned())),
+            _ => self.inner().finish_cloned(),
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn into_box_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+impl ArrayBuilder for StructColumnBuilder {
+    fn len(&self) -> usize {
+        self.nulls.len()
+    }
+
+    fn finish(&mut self) -> ArrayRef {
+        let len = self.len();
+        let nulls = self.nulls.finish();
+        if self.fields.is_empty() {
+            return Arc::new(StructArray::new_empty_fields(len, nulls));
+        }
+        let arrays = self.children.iter_mut().map(|c| c.finish()).collect();
+        Arc::new(StructArray::new(self.fields.clone(), arrays, nulls))
+    }
+
+    fn finish_cloned(&self) -> ArrayRef {
+        let nulls = self.nulls.finish_cloned();
+        if self.fields.is_empty() {
+            return Arc::new(StructArray::new_empty_fields(self.len(), nulls));
+        }
+        let arrays = self.children.iter().map(|c| c.finish_cloned()).collect();
+        Arc::new(StructArray::new(self.fields.clone(), arrays, nulls))
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn into_box_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+fn mark_keys_sorted(map: MapArray) -> MapArray {
+    let (field, offsets, entries, nulls, _) = map.into_parts();
+    MapArray::new(field, offsets, entries, nulls, true)
 }
@@ -5,19 +5,137 @@
 
 mod append;
 mod builder;
-mod scalar;
 
-use std::sync::Arc;
+use std::{str::FromStr, sync::Arc};
 
 use arrow::{
-    array::{ArrayRef, TimestampNanosecondArray},
-    datatypes::{DataType, Schema},
+    array::{ArrayBuilder, ArrayRef, TimestampNanosecondArray},
+    datatypes::{DataType, Field, Schema},
     record_batch::RecordBatch,
 };
-use mcapdecode_core::{DecodedMessage, Value};
+use mcapdecode_core::{DecodedMessage, Value, timestamp_to_i64};
 
+use self::{append::AppendContext, builder::ColumnBuilder};
 use crate::error::ArrowConvertError;
 
+/// Handling of map entries whose key repeats an earlier key of the same map.
+///
+/// Well-formed payloads never repeat keys, but a malformed protobuf payload
+/// may carry the same map key several times.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum DuplicateMapKeys {
+    /// Fail the conversion with [`ArrowConvertError::DuplicateMapKey`].
+    Error,
+    /// Keep the first entry of each key.
+    KeepFirst,
+    /// Keep the last entry of each key, as protobuf parsers do.
+    #[default]
+    KeepLast,
+}
+
+impl FromStr for DuplicateMapKeys {
+    type Err = String;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        match raw.to_ascii_lowercase().as_str() {
+            "error" => Ok(Self::Error),
+            "keep-first" => Ok(Self::KeepFirst),
+            "keep-last" => Ok(Self::KeepLast),
+            _ => Err(format!(
+                "invalid duplicate map keys policy '{raw}' (expected: error, keep-first, keep-last)"
+            )),
+        }
+    }
+}
+
+/// Handling of numeric values whose type differs from their column's.
+///
+/// Decoders normally emit exactly the column type; a mismatch means the
+/// schema and the decoder disagree, e.g. a `U8` value for an `Int32` column.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum WideningPolicy {
+    /// Fail the conversion with [`ArrowConvertError::ValueType`].
+    #[default]
+    Exact,
+    /// Convert values whose type fits the column without loss (`U8` into
+    /// `Int32`, `I32` into `Float64`, `F32` into `Float64`, ...); others
+    /// still fail.
+    Lossless,
+}
+
+impl FromStr for WideningPolicy {
+    type Err = String;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        match raw.to_ascii_lowercase().as_str() {
+            "exact" => Ok(Self::Exact),
+            "lossless" => Ok(Self::Lossless),
+            _ => Err(format!(
+                "invalid widening policy '{raw}' (expected: exact, lossless)"
+            )),
+        }
+    }
+}
+
+/// Handling of struct values with more or fewer fields than their schema,
+/// e.g. messages of a newer firmware whose definition gained a field.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum StructMismatch {
+    /// Fail the conversion with [`ArrowConvertError::StructFieldCount`].
+    #[default]
+    Error,
+    /// Align the fields by position: surplus fields are dropped and missing
+    /// ones are null (or zero, if not nullable). The number of aligned
+    /// values is reported by
+    /// [`try_arrow_value_rows_to_record_batch_with_report`].
+    Align,
+}
+
+impl FromStr for StructMismatch {
+    type Err = String;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        match raw.to_ascii_lowercase().as_str() {
+            "error" => Ok(Self::Error),
+            "align" => Ok(Self::Align),
+            _ => Err(format!(
+                "invalid struct mismatch policy '{raw}' (expected: error, align)"
+            )),
+        }
+    }
+}
+
+/// Options for [`try_arrow_value_rows_to_record_batch_with_options`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConvertOptions {
+    pub duplicate_map_keys: DuplicateMapKeys,
+    pub widening: WideningPolicy,
+    pub struct_mismatch: StructMismatch,
+}
+
+/// What [`try_arrow_value_rows_to_record_batch_with_report`] had to adjust.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConvertReport {
+    /// Struct values (message roots included) aligned to their schema by
+    /// [`StructMismatch::Align`].
+    pub aligned_structs: u64,
+}
+
 /// Convert decoded rows to a RecordBatch.
 ///
 /// `body_schema` must describe only the message body fields (no timestamp columns).
@@ -27,6 +145,7 @@ use crate::error::ArrowConvertError;
 /// Panics if:
 /// - `rows` is empty.
 /// - a row root value is neither `Struct` nor `Null`.
+/// - a timestamp exceeds `i64::MAX` nanoseconds.
 /// - a value shape does not match the provided Arrow data type.
 /// - an unsupported Arrow data type is present in `body_schema`.
 pub fn arrow_value_rows_to_record_batch(
@@ -40,6 +159,27 @@ pub fn try_arrow_value_rows_to_record_batch(
     body_schema: &Schema,
     rows: &[DecodedMessage],
 ) -> Result<RecordBatch, ArrowConvertError> {
+    try_arrow_value_rows_to_record_batch_with_options(body_schema, rows, &ConvertOptions::default())
+}
+
+/// Convert decoded rows to a RecordBatch, as
+/// [`try_arrow_value_rows_to_record_batch`] does, with non-default `options`.
+pub fn try_arrow_value_rows_to_record_batch_with_options(
+    body_schema: &Schema,
+    rows: &[DecodedMessage],
+    options: &ConvertOptions,
+) -> Result<RecordBatch, ArrowConvertError> {
+    try_arrow_value_rows_to_record_batch_with_report(body_schema, rows, options)
+        .map(|(batch, _)| batch)
+}
+
+/// Convert decoded rows to a RecordBatch with `options`, also returning how
+/// many values had to be adjusted to fit the schema.
+pub fn try_arrow_value_rows_to_record_batch_with_report(
+    body_schema: &Schema,
+    rows: &[DecodedMessage],
+    options: &ConvertOptions,
+) -> Result<(RecordBatch, ConvertReport), ArrowConvertError> {
     if rows.is_empty() {
         return Err(ArrowConvertError::EmptyRows);
     }
@@ -50,47 +190,73 @@ pub fn try_arrow_value_rows_to_record_batch(
     let body_fields = body_schema.fields();
     let mut arrays: Vec<ArrayRef> = Vec::with_capacity(body_fields.len() + 2);
 
-    arrays.push(Arc::new(
-        rows.iter()
-            .map(|r| Some(i64::try_from(r.log_time).expect("log_time exceeds i64::MAX")))
-            .collect::<TimestampNanosecondArray>()
-            .with_timezone(crate::TIMESTAMP_TZ),
-    ) as ArrayRef);
-
-    arrays.push(Arc::new(
-        rows.iter()
-            .map(|r| Some(i64::try_from(r.publish_time).expect("publish_time exceeds i64::MAX")))
-            .collect::<TimestampNanosecondArray>()
-            .with_timezone(crate::TIMESTAMP_TZ),
-    ) as ArrayRef);
+    arrays.push(timestamp_array(
+        "@log_time",
+        rows.iter().map(|r| r.log_time),
+    )?);
+    arrays.push(timestamp_array(
+        "@publish_time",
+        rows.iter().map(|r| r.publish_time),
+    )?);
 
+    let mut cx = AppendContext::new(options);
+    for row in rows {
+        if let Value::Struct(children) = &row.value {
+            cx.check_struct_len(body_fields.len(), children.len())?;
+        }
+    }
     for (i, field) in body_fields.iter().enumerate() {
-        let values: Vec<&Value> = rows.iter().map(|r| extract_field(&r.value, i)).collect();
-        arrays.push(build_array_from_values(field.data_type(), &values)?);
+        let values: Vec<Option<&Value>> = rows.iter().map(|r| extract_field(&r.value, i)).collect();
+        arrays.push(build_array_from_values(field, &values, options, &mut cx)?);
     }
 
-    Ok(RecordBatch::try_new(full_schema, arrays)?)
+    let report = ConvertReport {
+        aligned_structs: cx.aligned_structs,
+    };
+    Ok((RecordBatch::try_new(full_schema, arrays)?, report))
+}
+
+fn timestamp_array(
+    column: &'static str,
+    times: impl Iterator<Item = u64>,
+) -> Result<ArrayRef, ArrowConvertError> {
+    let values = times
+        .map(|value| {
+            timestamp_to_i64(value).ok_or(ArrowConvertError::TimestampOutOfRange { column, value })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Arc::new(
+        TimestampNanosecondArray::from(values).with_timezone(crate::TIMESTAMP_TZ),
+    ))
 }
 
-fn extract_field(root: &Value, field_index: usize) -> &Value {
+/// Field `field_index` of a message root, or `None` if the root struct has
+/// fewer fields.
+fn extract_field(root: &Value, field_index: usize) -> Option<&Value> {
     match root {
-        Value::Struct(children) => children.get(field_index).unwrap_or(&Value::Null),
-        Value::Null => &Value::Null,
+        Value::Struct(children) => children.get(field_index),
+        Value::Null => Some(&Value::Null),
         other => panic!("expected Struct or Null as message root, got {other:?}"),
     }
 }
 
 fn build_array_from_values(
-    dt: &DataType,
-    values: &[&Value],
+    field: &Field,
+    values: &[Option<&Value>],
+    options: &ConvertOptions,
+    cx: &mut AppendContext,
 ) -> Result<ArrayRef, ArrowConvertError> {
+    let dt = field.data_type();
     let capacity = match dt {
         DataType::List(_) | DataType::Map(_, _) => values.len().saturating_mul(4),
         _ => values.len(),
     };
-    let mut builder = builder::make_builder(dt, capacity);
+    let mut builder = ColumnBuilder::new(dt, capacity, options);
     for value in values {
-        append::append_value_to_builder(&mut builder, dt, value)?;
+        match value {
+            Some(value) => builder.append(value, cx)?,
+            None => builder.append_masked(field.is_nullable())?,
+        }
     }
     Ok(builder.finish())
 }
@@ -0,0 +1,387 @@
+//! Streaming per-column statistics of record batches.
+
+use std::{
+    collections::HashMap,
+    fmt,
+    hash::{DefaultHasher, Hash, Hasher},
+};
+
+use arrow::{
+    array::{Array, ArrayRef, AsArray},
+    buffer::NullBuffer,
+    compute::cast,
+    datatypes::{
+        DataType, Field, Float32Type, Float64Type, Int8Type, Int16Type, Int32Type, Int64Type,
+        UInt8Type, UInt16Type, UInt32Type, UInt64Type,
+    },
+    record_batch::RecordBatch,
+};
+
+/// Smallest or largest value of a column.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(untagged))]
+pub enum StatValue {
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+    String(String),
+}
+
+impl fmt::Display for StatValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bool(v) => write!(f, "{v}"),
+            Self::Int(v) => write!(f, "{v}"),
+            Self::UInt(v) => write!(f, "{v}"),
+            Self::Float(v) => write!(f, "{v}"),
+            Self::String(v) => write!(f, "{v:?}"),
+        }
+    }
+}
+
+/// Statistics of one column, as collected by [`ColumnStatsAccumulator`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ColumnStats {
+    /// Path of the column, struct fields joined with `.` (e.g.
+    /// `pose.position.x`).
+    pub column: String,
+    /// Arrow data type, e.g. `Float64`.
+    pub data_type: String,
+    pub rows: u64,
+    /// Null values, including the fields of null structs.
+    pub null_count: u64,
+    /// `None` for columns of nulls (or NaNs) only and for types without an
+    /// order: binaries, lists, maps and other nested types.
+    pub min: Option<StatValue>,
+    pub max: Option<StatValue>,
+    /// Estimated number of distinct non-null values (HyperLogLog, about 1.6 %
+    /// standard error); `None` for lists, maps and other nested types.
+    pub distinct_estimate: Option<u64>,
+}
+
+/// One line per column: `<column> (<type>): rows=…, nulls=…, min=…, max=…, distinct≈…`.
+impl fmt::Display for ColumnStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ({}): rows={}, nulls={}",
+            self.column, self.data_type, self.rows, self.null_count
+        )?;
+        if let (Some(min), Some(max)) = (&self.min, &self.max) {
+            write!(f, ", min={min}, max={max}")?;
+        }
+        if let Some(distinct) = self.distinct_estimate {
+            write!(f, ", distinct≈{distinct}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Collects [`ColumnStats`] of the leaf columns of a stream of batches, one
+/// batch at a time, without keeping the batches.
+///
+/// Struct columns are descended into; columns are matched across batches
+/// by path, and reported in the order they were first seen.
+#[derive(Debug, Clone, Default)]
+pub struct ColumnStatsAccumulator {
+    columns: Vec<ColumnAccumulator>,
+    index: HashMap<String, usize>,
+}
+
+impl ColumnStatsAccumulator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the values of `batch`.
+    pub fn update(&mut self, batch: &RecordBatch) {
+        for (field, column) in batch.schema().fields().iter().zip(batch.columns()) {
+            self.update_column(field.name().clone(), field, column, None);
+        }
+    }
+
+    /// Statistics of the values added so far.
+    pub fn stats(&self) -> Vec<ColumnStats> {
+        self.columns.iter().map(ColumnAccumulator::stats).collect()
+    }
+
+    fn update_column(
+        &mut self,
+        path: String,
+        field: &Field,
+        array: &ArrayRef,
+        parent_nulls: Option<&NullBuffer>,
+    ) {
+        let nulls = NullBuffer::union(parent_nulls, array.logical_nulls().as_ref());
+        if let DataType::Struct(children) = field.data_type() {
+            let st = array.as_struct();
+            for (child, column) in children.iter().zip(st.columns()) {
+                let child_path = format!("{path}.{}", child.name());
+                self.update_column(child_path, child, column, nulls.as_ref());
+            }
+            return;
+        }
+
+        let idx = *self.index.entry(path.clone()).or_insert_with(|| {
+            self.columns
+                .push(ColumnAccumulator::new(path, field.data_type()));
+            self.columns.len() - 1
+        });
+        self.columns[idx].update(array, nulls.as_ref());
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ColumnAccumulator {
+    column: String,
+    data_type: String,
+    rows: u64,
+    null_count: u64,
+    min: Option<StatValue>,
+    max: Option<StatValue>,
+    distinct: Option<DistinctSketch>,
+}
+
+impl ColumnAccumulator {
+    fn new(column: String, data_type: &DataType) -> Self {
+        let distinct = has_distinct(data_type).then(DistinctSketch::new);
+        Self {
+            column,
+            data_type: data_type.to_string(),
+            rows: 0,
+            null_count: 0,
+            min: None,
+            max: None,
+            distinct,
+        }
+    }
+
+    fn update(&mut self, array: &ArrayRef, nulls: Option<&NullBuffer>) {
+        self.rows += array.len() as u64;
+        self.null_count += nulls.map_or(0, |n| n.null_count()) as u64;
+        let valid = |i: usize| nulls.is_none_or(|n| n.is_valid(i));
+        let values = (0..array.len()).filter(|i| valid(*i));
+
+        macro_rules! primitive {
+            ($ty:ty, $variant:ident, $convert:ty) => {{
+                let array = array.as_primitive::<$ty>();
+                for i in values {
+                    self.observe(StatValue::$variant(<$convert>::from(array.value(i))));
+                }
+            }};
+        }
+        match array.data_type() {
+            DataType::Boolean => {
+                let array = array.as_boolean();
+                for i in values {
+                    self.observe(StatValue::Bool(array.value(i)));
+                }
+            }
+            DataType::Int8 => primitive!(Int8Type, Int, i64),
+            DataType::Int16 => primitive!(Int16Type, Int, i64),
+            DataType::Int32 => primitive!(Int32Type, Int, i64),
+            DataType::Int64 => primitive!(Int64Type, Int, i64),
+            DataType::UInt8 => primitive!(UInt8Type, UInt, u64),
+            DataType::UInt16 => primitive!(UInt16Type, UInt, u64),
+            DataType::UInt32 => primitive!(UInt32Type, UInt, u64),
+            DataType::UInt64 => primitive!(UInt64Type, UInt, u64),
+            DataType::Float32 => primitive!(Float32Type, Float, f64),
+            DataType::Float64 => primitive!(Float64Type, Float, f64),
+            DataType::Timestamp(_, _) => {
+                let Ok(array) = cast(array, &DataType::Int64) else {
+                    return;
+                };
+                let array = array.as_primitive::<Int64Type>();
+                for i in values {
+                    self.observe(StatValue::Int(array.value(i)));
+                }
+            }
+            DataType::Utf8 => {
+                let array = array.as_string::<i32>();
+                for i in values {
+                    self.observe_str(array.value(i));
+                }
+            }
+            DataType::LargeUtf8 => {
+                let array = array.as_string::<i64>();
+                for i in values {
+                    self.observe_str(array.value(i));
+                }
+            }
+            DataType::Utf8View => {
+                let array = array.as_string_view();
+                for i in values {
+                    self.observe_str(array.value(i));
+                }
+            }
+            DataType::Binary => {
+                let array = array.as_binary::<i32>();
+                for i in values {
+                    self.observe_hash(array.value(i));
+                }
+            }
+            DataType::LargeBinary => {
+                let array = array.as_binary::<i64>();
+                for i in values {
+                    self.observe_hash(array.value(i));
+                }
+            }
+            DataType::BinaryView => {
+                let array = array.as_binary_view();
+                for i in values {
+                    self.observe_hash(array.value(i));
+                }
+            }
+            DataType::FixedSizeBinary(_) => {
+                let array = array.as_fixed_size_binary();
+                for i in values {
+                    self.observe_hash(array.value(i));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn observe(&mut self, value: StatValue) {
+        match &value {
+            StatValue::Float(v) if v.is_nan() => return,
+            // -0.0 and 0.0 are one distinct value.
+            StatValue::Float(v) => self.observe_hash(if *v == 0.0 { 0.0f64 } else { *v }.to_bits()),
+            StatValue::Bool(v) => self.observe_hash(v),
+            StatValue::Int(v) => self.observe_hash(v),
+            StatValue::UInt(v) => self.observe_hash(v),
+            StatValue::String(v) => self.observe_hash(v),
+        }
+        if self.min.as_ref().is_none_or(|min| less(&value, min)) {
+            self.min = Some(value.clone());
+        }
+        if self.max.as_ref().is_none_or(|max| less(max, &value)) {
+            self.max = Some(value);
+        }
+    }
+
+    /// Like [`Self::observe`], cloning `value` only when it is a new bound.
+    fn observe_str(&mut self, value: &str) {
+        self.observe_hash(value);
+        let is_min = self
+            .min
+            .as_ref()
+            .is_none_or(|min| matches!(min, StatValue::String(min) if value < min.as_str()));
+        if is_min {
+            self.min = Some(StatValue::String(value.to_string()));
+        }
+        let is_max = self
+            .max
+            .as_ref()
+            .is_none_or(|max| matches!(max, StatValue::String(max) if value > max.as_str()));
+        if is_max {
+            self.max = Some(StatValue::String(value.to_string()));
+        }
+    }
+
+    fn observe_hash(&mut self, value: impl Hash) {
+        if let Some(sketch) = &mut self.distinct {
+            let mut hasher = DefaultHasher::new();
+            value.hash(&mut hasher);
+            sketch.insert(hasher.finish());
+        }
+    }
+
+    fn stats(&self) -> ColumnStats {
+        ColumnStats {
+            column: self.column.clone(),
+            data_type: self.data_type.clone(),
+            rows: self.rows,
+            null_count: self.null_count,
+            min: self.min.clone(),
+            max: self.max.clone(),
+            distinct_estimate: self.distinct.as_ref().map(DistinctSketch::estimate),
+        }
+    }
+}
+
+fn has_distinct(data_type: &DataType) -> bool {
+    matches!(
+        data_type,
+        DataType::Boolean
+            | DataType::Int8
+            | DataType::Int16
+            | DataType::Int32
+            | DataType::Int64
+            | DataType::UInt8
+            | DataType::UInt16
+            | DataType::UInt32
+            | DataType::UInt64
+            | DataType::Float32
+            | DataType::Float64
+            | DataType::Timestamp(_, _)
+            | DataType::Utf8
+            | DataType::LargeUtf8
+            | DataType::Utf8View
+            | DataType::Binary
+            | DataType::LargeBinary
+            | DataType::BinaryView
+            | DataType::FixedSizeBinary(_)
+    )
+}
+
+/// Whether `a` orders before `b`; values of a column share one variant.
+fn less(a: &StatValue, b: &StatValue) -> bool {
+    match (a, b) {
+        (StatValue::Bool(a), StatValue::Bool(b)) => a < b,
+        (StatValue::Int(a), StatValue::Int(b)) => a < b,
+        (StatValue::UInt(a), StatValue::UInt(b)) => a < b,
+        (StatValue::Float(a), StatValue::Float(b)) => a < b,
+        (StatValue::String(a), StatValue::String(b)) => a < b,
+        _ => false,
+    }
+}
+
+/// Bits of a hash selecting the register of a [`DistinctSketch`].
+const SKETCH_PRECISION: u32 = 12;
+
+/// HyperLogLog sketch of the distinct values of a column.
+#[derive(Debug, Clone)]
+struct DistinctSketch {
+    registers: Box<[u8]>,
+}
+
+impl DistinctSketch {
+    fn new() -> Self {
+        Self {
+            registers: vec![0; 1 << SKETCH_PRECISION].into_boxed_slice(),
+        }
+    }
+
+    fn insert(&mut self, hash: u64) {
+        let register = (hash >> (64 - SKETCH_PRECISION)) as usize;
+        // Leading zeros of the remaining bits, plus one; a sentinel bit
+        // bounds the rank.
+        let rest = (hash << SKETCH_PRECISION) | (1 << (SKETCH_PRECISION - 1));
+        let rank = rest.leading_zeros() as u8 + 1;
+        if rank > self.registers[register] {
+            self.registers[register] = rank;
+        }
+    }
+
+    fn estimate(&self) -> u64 {
+        let m = self.registers.len() as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let sum: f64 = self
+            .registers
+            .iter()
+            .map(|rank| 2f64.powi(-i32::from(*rank)))
+            .sum();
+        let raw = alpha * m * m / sum;
+        let zeros = self.registers.iter().filter(|rank| **rank == 0).count();
+        let estimate = if raw <= 2.5 * m && zeros > 0 {
+            // Linear counting is more accurate for small cardinalities.
+            m * (m / zeros as f64).ln()
+        } else {
+            raw
+        };
+        estimate.round() as u64
+    }
+}
@@ -0,0 +1,219 @@
+//! Validated bundle of the options shaping converted record batches.
+//!
+//! ```rust
+//! use mcapdecode_arrow::{ConversionOptions, TimeColumns};
+//!
+//! let options = ConversionOptions::csv()
+//!     .with_separator('/')
+//!     .with_time_columns(TimeColumns::Elapsed)
+//!     .build()
+//!     .unwrap();
+//! assert_eq!(options.separator(), '/');
+//! ```
+
+use crate::{
+    ConvertOptions, DuplicateMapKeys, FlattenPolicy, ListPolicy, StructMismatch, TimeColumns,
+    WideningPolicy, error::ConversionOptionsError,
+};
+
+/// Flattening, column naming, time columns, value conversion policies and
+/// limits of a conversion, checked for consistency by
+/// [`ConversionOptionsBuilder::build`].
+///
+/// With the `serde` feature, options (de)serialize as a table with the
+/// kebab-case names of the builder methods; missing keys keep the
+/// [`ConversionOptions::builder`] defaults and deserialized options are
+/// validated like built ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "ConversionOptionsBuilder", rename_all = "kebab-case")
+)]
+pub struct ConversionOptions {
+    flatten: FlattenPolicy,
+    separator: char,
+    time_columns: TimeColumns,
+    duplicate_map_keys: DuplicateMapKeys,
+    widening: WideningPolicy,
+    struct_mismatch: StructMismatch,
+    max_depth: Option<usize>,
+}
+
+impl ConversionOptions {
+    /// Builder starting from nested output ([`FlattenPolicy::jsonl`]) and
+    /// the default of every other option.
+    pub fn builder() -> ConversionOptionsBuilder {
+        ConversionOptionsBuilder::default()
+    }
+
+    /// Builder for JSON Lines output: every column kept nested.
+    pub fn jsonl() -> ConversionOptionsBuilder {
+        Self::builder().with_flatten(FlattenPolicy::jsonl())
+    }
+
+    /// Builder for CSV output: scalar columns only.
+    pub fn csv() -> ConversionOptionsBuilder {
+        Self::builder().with_flatten(FlattenPolicy::csv())
+    }
+
+    /// Builder for Parquet output: structs flattened, lists and maps kept.
+    pub fn parquet() -> ConversionOptionsBuilder {
+        Self::builder().with_flatten(FlattenPolicy::parquet())
+    }
+
+    pub fn flatten(&self) -> &FlattenPolicy {
+        &self.flatten
+    }
+
+    /// Separator of flattened column paths.
+    pub fn separator(&self) -> char {
+        self.separator
+    }
+
+    pub fn time_columns(&self) -> TimeColumns {
+        self.time_columns
+    }
+
+    /// Options of [`try_arrow_value_rows_to_record_batch_with_options`](crate::try_arrow_value_rows_to_record_batch_with_options).
+    pub fn convert_options(&self) -> ConvertOptions {
+        ConvertOptions {
+            duplicate_map_keys: self.duplicate_map_keys,
+            widening: self.widening,
+            struct_mismatch: self.struct_mismatch,
+        }
+    }
+
+    /// Struct nesting depth beyond which structs are written as JSON strings.
+    pub fn max_depth(&self) -> Option<usize> {
+        self.max_depth
+    }
+
+    /// Builder holding these options, to derive modified ones.
+    pub fn to_builder(&self) -> ConversionOptionsBuilder {
+        ConversionOptionsBuilder {
+            flatten: self.flatten,
+            separator: self.separator,
+            time_columns: self.time_columns,
+            duplicate_map_keys: self.duplicate_map_keys,
+            widening: self.widening,
+            struct_mismatch: self.struct_mismatch,
+            max_depth: self.max_depth,
+        }
+    }
+}
+
+/// Builder of [`ConversionOptions`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize),
+    serde(default, deny_unknown_fields, rename_all = "kebab-case")
+)]
+pub struct ConversionOptionsBuilder {
+    flatten: FlattenPolicy,
+    separator: char,
+    time_columns: TimeColumns,
+    duplicate_map_keys: DuplicateMapKeys,
+    widening: WideningPolicy,
+    struct_mismatch: StructMismatch,
+    max_depth: Option<usize>,
+}
+
+impl Default for ConversionOptionsBuilder {
+    fn default() -> Self {
+        Self {
+            flatten: FlattenPolicy::default(),
+            separator: '.',
+            time_columns: TimeColumns::default(),
+            duplicate_map_keys: DuplicateMapKeys::default(),
+            widening: WideningPolicy::default(),
+            struct_mismatch: StructMismatch::default(),
+            max_depth: None,
+        }
+    }
+}
+
+impl ConversionOptionsBuilder {
+    pub fn with_flatten(mut self, flatten: FlattenPolicy) -> Self {
+        self.flatten = flatten;
+        self
+    }
+
+    /// Expand lists into `size` columns each ([`ListPolicy::FlattenFixed`]).
+    pub fn with_list_flatten_size(mut self, size: usize) -> Self {
+        self.flatten.list = ListPolicy::FlattenFixed;
+        self.flatten.list_flatten_fixed_size = size;
+        self
+    }
+
+    /// Separator of flattened column paths (default: `.`).
+    pub fn with_separator(mut self, separator: char) -> Self {
+        self.separator = separator;
+        self
+    }
+
+    pub fn with_time_columns(mut self, time_columns: TimeColumns) -> Self {
+        self.time_columns = time_columns;
+        self
+    }
+
+    pub fn with_duplicate_map_keys(mut self, policy: DuplicateMapKeys) -> Self {
+        self.duplicate_map_keys = policy;
+        self
+    }
+
+    pub fn with_widening(mut self, policy: WideningPolicy) -> Self {
+        self.widening = policy;
+        self
+    }
+
+    pub fn with_struct_mismatch(mut self, policy: StructMismatch) -> Self {
+        self.struct_mismatch = policy;
+        self
+    }
+
+    /// Write structs nested deeper than `max_depth` field levels as JSON
+    /// strings (top-level fields are level 1).
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    /// Check the options for consistency.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConversionOptionsError`] for a zero list flatten size or
+    /// maximum depth, and for separators that can occur in field names.
+    pub fn build(self) -> Result<ConversionOptions, ConversionOptionsError> {
+        if self.flatten.list == ListPolicy::FlattenFixed
+            && self.flatten.list_flatten_fixed_size == 0
+        {
+            return Err(ConversionOptionsError::ZeroListFlattenSize);
+        }
+        if self.separator.is_alphanumeric() || matches!(self.separator, '_' | '@') {
+            return Err(ConversionOptionsError::InvalidSeparator(self.separator));
+        }
+        if self.max_depth == Some(0) {
+            return Err(ConversionOptionsError::ZeroMaxDepth);
+        }
+        Ok(ConversionOptions {
+            flatten: self.flatten,
+            separator: self.separator,
+            time_columns: self.time_columns,
+            duplicate_map_keys: self.duplicate_map_keys,
+            widening: self.widening,
+            struct_mismatch: self.struct_mismatch,
+            max_depth: self.max_depth,
+        })
+    }
+}
+
+impl TryFrom<ConversionOptionsBuilder> for ConversionOptions {
+    type Error = ConversionOptionsError;
+
+    fn try_from(builder: ConversionOptionsBuilder) -> Result<Self, Self::Error> {
+        builder.build()
+    }
+}
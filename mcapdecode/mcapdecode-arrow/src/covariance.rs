@@ -0,0 +1,123 @@
+//! Named columns for the elements of covariance matrices.
+
+use std::{str::FromStr, sync::Arc};
+
+use arrow::{
+    array::{Array, ArrayRef, FixedSizeListArray, Float64Array, StructArray},
+    datatypes::{DataType, Field, FieldRef, Fields},
+    error::ArrowError,
+    record_batch::RecordBatch,
+};
+
+use crate::nested::{downcast, rewrite_fields};
+
+/// Axes of 6x6 covariances (`geometry_msgs/msg/PoseWithCovariance`,
+/// `TwistWithCovariance`): position, then rotation about X, Y and Z.
+const AXES_6: [&str; 6] = ["x", "y", "z", "rx", "ry", "rz"];
+
+/// Axes of 3x3 covariances (`sensor_msgs/msg/Imu`, `NavSatFix`).
+const AXES_3: [&str; 3] = ["x", "y", "z"];
+
+/// Which elements [`name_covariance_columns`] keeps.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum CovarianceColumns {
+    /// Every element, named after its row and column axes (`xx`, `xy`, …).
+    #[default]
+    Named,
+    /// The variances on the diagonal only (`xx`, `yy`, …).
+    Diagonal,
+}
+
+impl FromStr for CovarianceColumns {
+    type Err = String;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        let lower = raw.to_ascii_lowercase();
+        match lower.as_str() {
+            "named" => Ok(Self::Named),
+            "diagonal" => Ok(Self::Diagonal),
+            _ => Err(format!(
+                "invalid covariance columns '{raw}' (expected: named, diagonal)"
+            )),
+        }
+    }
+}
+
+/// Whether `field` is a row-major covariance matrix: a `covariance` or
+/// `*_covariance` field holding 36 (6x6) or 9 (3x3) `Float64` values.
+pub fn is_covariance_field(field: &Field) -> bool {
+    let name = field.name();
+    (name == "covariance" || name.ends_with("_covariance")) && axes(field.data_type()).is_some()
+}
+
+/// Replace every covariance matrix of `batch` with a struct of one `Float64`
+/// field per element, named after the axes of its row and column: `x`, `y`,
+/// `z` and, for 6x6 matrices, `rx`, `ry`, `rz` for rotation about X, Y and Z
+/// (e.g. `covariance.xy`, `covariance.rxrz`).
+///
+/// Covariances nested in structs and lists are handled too, and batches
+/// without covariances are returned unchanged.
+pub fn name_covariance_columns(
+    batch: &RecordBatch,
+    columns: CovarianceColumns,
+) -> Result<RecordBatch, ArrowError> {
+    rewrite_fields(batch, &is_covariance_field, &|field, array| {
+        name_elements(field, array, columns)
+    })
+}
+
+fn axes(data_type: &DataType) -> Option<&'static [&'static str]> {
+    match data_type {
+        DataType::FixedSizeList(item, 36) if item.data_type() == &DataType::Float64 => {
+            Some(&AXES_6)
+        }
+        DataType::FixedSizeList(item, 9) if item.data_type() == &DataType::Float64 => Some(&AXES_3),
+        _ => None,
+    }
+}
+
+fn name_elements(
+    field: &FieldRef,
+    array: &ArrayRef,
+    columns: CovarianceColumns,
+) -> Result<(FieldRef, ArrayRef), ArrowError> {
+    let axes = axes(field.data_type()).unwrap_or_default();
+    let list = downcast::<FixedSizeListArray>(array)?;
+    let values = downcast::<Float64Array>(list.values())?;
+    let size = axes.len();
+
+    let mut fields = Vec::new();
+    let mut arrays: Vec<ArrayRef> = Vec::new();
+    for (row, a) in axes.iter().enumerate() {
+        for (col, b) in axes.iter().enumerate() {
+            if columns == CovarianceColumns::Diagonal && row != col {
+                continue;
+            }
+            let k = row * size + col;
+            let element: Float64Array = (0..list.len())
+                .map(|i| {
+                    let idx = list.value_offset(i) as usize + k;
+                    (list.is_valid(i) && values.is_valid(idx)).then(|| values.value(idx))
+                })
+                .collect();
+            fields.push(Field::new(format!("{a}{b}"), DataType::Float64, true));
+            arrays.push(Arc::new(element));
+        }
+    }
+    let fields = Fields::from(fields);
+    let named = StructArray::try_new(fields.clone(), arrays, list.nulls().cloned())?;
+    Ok((
+        Arc::new(
+            field
+                .as_ref()
+                .clone()
+                .with_data_type(DataType::Struct(fields)),
+        ),
+        Arc::new(named),
+    ))
+}
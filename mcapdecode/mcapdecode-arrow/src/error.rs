@@ -1,4 +1,4 @@
-use arrow::error::ArrowError;
+use arrow::{datatypes::DataType, error::ArrowError};
 use mcapdecode_core::ValueTypeError;
 use thiserror::Error;
 
@@ -6,8 +6,30 @@ use thiserror::Error;
 pub enum ArrowConvertError {
     #[error("Cannot create RecordBatch from empty rows")]
     EmptyRows,
+    #[error("{column} value {value} exceeds the i64 nanosecond range")]
+    TimestampOutOfRange { column: &'static str, value: u64 },
+    #[error("duplicate map key {key}")]
+    DuplicateMapKey { key: String },
+    #[error("struct value has {actual} fields, but the schema has {expected}")]
+    StructFieldCount { expected: usize, actual: usize },
+    #[error("field '{field}' has Arrow type {data_type}, which has no schema IR counterpart")]
+    UnsupportedArrowType { field: String, data_type: DataType },
     #[error("value type mismatch: {0}")]
     ValueType(#[from] ValueTypeError),
     #[error(transparent)]
     Arrow(#[from] ArrowError),
 }
+
+/// Inconsistent options rejected by
+/// [`ConversionOptionsBuilder::build`](crate::ConversionOptionsBuilder::build).
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ConversionOptionsError {
+    #[error("list flatten size must be at least 1")]
+    ZeroListFlattenSize,
+    #[error(
+        "invalid column path separator '{0}' (letters, digits, '_' and '@' occur in field names)"
+    )]
+    InvalidSeparator(char),
+    #[error("max depth must be at least 1")]
+    ZeroMaxDepth,
+}
@@ -0,0 +1,96 @@
+//! Arrow extension type names of semantic columns.
+
+use std::{collections::HashMap, sync::Arc};
+
+use arrow::{
+    datatypes::{DataType, Field, FieldRef, Fields, Schema},
+    error::ArrowError,
+    record_batch::RecordBatch,
+};
+use mcapdecode_core::{TIMESTAMP_METADATA_KEY, UUID_METADATA_KEY};
+
+use crate::quaternion::is_quaternion_field;
+
+/// Arrow field metadata key holding the name of an extension type.
+pub const EXTENSION_NAME_KEY: &str = "ARROW:extension:name";
+
+/// Canonical extension type of `FixedSizeBinary(16)` fields marked with
+/// [`UUID_METADATA_KEY`].
+pub const UUID_EXTENSION: &str = "arrow.uuid";
+
+/// Extension type of quaternion structs (see [`is_quaternion_field`]).
+pub const QUATERNION_EXTENSION: &str = "ros2.quaternion";
+
+/// Extension type of structs of the `Float64` fields `latitude`, `longitude`
+/// and optionally `altitude` in degrees and meters (WGS 84), as
+/// `geographic_msgs/msg/GeoPoint`.
+pub const GEO_POINT_EXTENSION: &str = "ros2.geo_point";
+
+/// Extension type of time structs of seconds and nanoseconds marked with
+/// [`TIMESTAMP_METADATA_KEY`], e.g. `builtin_interfaces/msg/Time`.
+pub const TIME_EXTENSION: &str = "ros2.time";
+
+/// Extension type that [`add_extension_types`] registers for `field`, if
+/// any.
+pub fn extension_type_name(field: &Field) -> Option<&'static str> {
+    let marked = |key: &str| field.metadata().get(key).is_some_and(|v| v == "true");
+    match field.data_type() {
+        DataType::FixedSizeBinary(16) if marked(UUID_METADATA_KEY) => Some(UUID_EXTENSION),
+        DataType::Struct(_) if is_quaternion_field(field) => Some(QUATERNION_EXTENSION),
+        DataType::Struct(children) if is_geo_point(children) => Some(GEO_POINT_EXTENSION),
+        DataType::Struct(_) if marked(TIMESTAMP_METADATA_KEY) => Some(TIME_EXTENSION),
+        _ => None,
+    }
+}
+
+/// Returns `schema` with the [`extension_type_name`] of every field, also
+/// nested ones, registered under [`EXTENSION_NAME_KEY`], so that
+/// extension-aware consumers (e.g. pyarrow or GeoArrow readers of written
+/// files) can restore their semantics. Storage types are unchanged.
+pub fn with_extension_types(schema: &Schema) -> Schema {
+    Schema::new_with_metadata(tag_fields(schema.fields()), schema.metadata().clone())
+}
+
+/// Like [`with_extension_types`], for the schema of `batch`.
+///
+/// Apply it after transforms that change the fields it recognizes (e.g.
+/// [`add_quaternion_columns`](crate::add_quaternion_columns) or
+/// [`render_uuid_columns`](crate::render_uuid_columns)): the registered
+/// names are field metadata, which those transforms keep.
+pub fn add_extension_types(batch: &RecordBatch) -> Result<RecordBatch, ArrowError> {
+    let schema = with_extension_types(&batch.schema());
+    if schema == *batch.schema() {
+        return Ok(batch.clone());
+    }
+    RecordBatch::try_new(Arc::new(schema), batch.columns().to_vec())
+}
+
+fn tag_fields(fields: &Fields) -> Fields {
+    fields.iter().map(tag_field).collect()
+}
+
+fn tag_field(field: &FieldRef) -> FieldRef {
+    let data_type = match field.data_type() {
+        DataType::Struct(children) => DataType::Struct(tag_fields(children)),
+        DataType::List(item) => DataType::List(tag_field(item)),
+        DataType::LargeList(item) => DataType::LargeList(tag_field(item)),
+        DataType::FixedSizeList(item, size) => DataType::FixedSizeList(tag_field(item), *size),
+        DataType::Map(entries, sorted) => DataType::Map(tag_field(entries), *sorted),
+        other => other.clone(),
+    };
+    let mut tagged = field.as_ref().clone().with_data_type(data_type);
+    if let Some(name) = extension_type_name(&tagged) {
+        let mut metadata: HashMap<String, String> = tagged.metadata().clone();
+        metadata.insert(EXTENSION_NAME_KEY.to_string(), name.to_string());
+        tagged = tagged.with_metadata(metadata);
+    }
+    Arc::new(tagged)
+}
+
+fn is_geo_point(children: &Fields) -> bool {
+    let names: Vec<&str> = children.iter().map(|f| f.name().as_str()).collect();
+    matches!(
+        names.as_slice(),
+        ["latitude", "longitude"] | ["latitude", "longitude", "altitude"]
+    ) && children.iter().all(|f| f.data_type() == &DataType::Float64)
+}
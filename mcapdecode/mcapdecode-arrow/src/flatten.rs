@@ -30,7 +30,7 @@ impl CommonPostProcess {
     ) {
         match self {
             CommonPostProcess::Drop => out.drop(path),
-            CommonPostProcess::Keep => out.keep(path, field.data_type(), effective_nullable, col),
+            CommonPostProcess::Keep => out.keep(path, field, effective_nullable, col),
             CommonPostProcess::None => (),
         }
     }
@@ -68,6 +68,11 @@ impl CommonPostProcess {
 
 /// Policy for [`DataType::List`] columns during [`flatten_record_batch`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
 pub enum ListPolicy {
     /// Drop the column entirely (e.g. for CSV output).
     Drop,
@@ -95,8 +100,55 @@ impl FromStr for ListPolicy {
     }
 }
 
+/// A [`ListPolicy`] optionally paired with a flatten-fixed column count.
+///
+/// Parses the same names as [`ListPolicy`] plus the `flatten-fixed:<N>`
+/// shorthand, where `N` must be at least 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ListPolicySpec {
+    pub policy: ListPolicy,
+    /// Column count given via `flatten-fixed:<N>`, if any.
+    pub flatten_fixed_size: Option<usize>,
+}
+
+impl FromStr for ListPolicySpec {
+    type Err = String;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        let Some((name, size)) = raw.split_once(':') else {
+            return Ok(Self {
+                policy: raw.parse()?,
+                flatten_fixed_size: None,
+            });
+        };
+
+        let policy: ListPolicy = name.parse()?;
+        if policy != ListPolicy::FlattenFixed {
+            return Err(format!(
+                "invalid list policy '{raw}' (a size is only allowed for flatten-fixed)"
+            ));
+        }
+        let size = size
+            .parse::<usize>()
+            .ok()
+            .filter(|&n| n > 0)
+            .ok_or_else(|| {
+                format!("invalid list policy '{raw}' (size must be a positive integer)")
+            })?;
+        Ok(Self {
+            policy,
+            flatten_fixed_size: Some(size),
+        })
+    }
+}
+
 /// Policy for [`DataType::FixedSizeList`] columns during [`flatten_record_batch`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
 pub enum ArrayPolicy {
     /// Drop the column entirely.
     Drop,
@@ -124,6 +176,11 @@ impl FromStr for ArrayPolicy {
 
 /// Policy for [`DataType::Map`] columns during [`flatten_record_batch`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
 pub enum MapPolicy {
     /// Drop the column entirely (e.g. for CSV output).
     Drop,
@@ -145,6 +202,11 @@ impl FromStr for MapPolicy {
 
 /// Policy for [`DataType::Struct`] columns during [`flatten_record_batch`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
 pub enum StructPolicy {
     /// Pass the column through unchanged.
     Keep,
@@ -155,14 +217,64 @@ pub enum StructPolicy {
 /// Aggregate policy controlling how each compound type is handled during
 /// [`flatten_record_batch`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default, rename_all = "kebab-case")
+)]
 pub struct FlattenPolicy {
     pub list: ListPolicy,
     pub list_flatten_fixed_size: usize,
     pub array: ArrayPolicy,
     pub map: MapPolicy,
+    #[cfg_attr(feature = "serde", serde(rename = "struct"))]
     pub struct_: StructPolicy,
 }
 
+impl FlattenPolicy {
+    /// Every column kept nested, e.g. for JSON Lines output.
+    pub const fn jsonl() -> Self {
+        Self {
+            list: ListPolicy::Keep,
+            list_flatten_fixed_size: 1,
+            array: ArrayPolicy::Keep,
+            map: MapPolicy::Keep,
+            struct_: StructPolicy::Keep,
+        }
+    }
+
+    /// Scalar columns only, e.g. for CSV output: structs flattened, lists,
+    /// arrays and maps dropped.
+    pub const fn csv() -> Self {
+        Self {
+            list: ListPolicy::Drop,
+            list_flatten_fixed_size: 1,
+            array: ArrayPolicy::Drop,
+            map: MapPolicy::Drop,
+            struct_: StructPolicy::Flatten,
+        }
+    }
+
+    /// Structs flattened, lists, arrays and maps kept, e.g. for Parquet
+    /// output.
+    pub const fn parquet() -> Self {
+        Self {
+            list: ListPolicy::Keep,
+            list_flatten_fixed_size: 1,
+            array: ArrayPolicy::Keep,
+            map: MapPolicy::Keep,
+            struct_: StructPolicy::Flatten,
+        }
+    }
+}
+
+/// [`FlattenPolicy::jsonl`]: flattening nothing.
+impl Default for FlattenPolicy {
+    fn default() -> Self {
+        Self::jsonl()
+    }
+}
+
 /// Accumulates the output of [`collect_columns`].
 #[derive(Default)]
 struct Collector {
@@ -177,9 +289,11 @@ impl Collector {
         self.arrays.push(array);
     }
 
-    /// Keep `col` under a new field whose name is `path`.
-    fn keep(&mut self, path: &str, data_type: &DataType, nullable: bool, col: &ArrayRef) {
-        self.push(Field::new(path, data_type.clone(), nullable), col.clone());
+    /// Keep `col` under a copy of `field` whose name is `path`.
+    fn keep(&mut self, path: &str, field: &Field, nullable: bool, col: &ArrayRef) {
+        let field = Field::new(path, field.data_type().clone(), nullable)
+            .with_metadata(field.metadata().clone());
+        self.push(field, col.clone());
     }
 
     fn drop(&mut self, path: &str) {
@@ -283,6 +397,8 @@ fn collect_columns(
                 for (f, a) in
                     expand_list_fixed(list_arr, path, policy.list_flatten_fixed_size, sep)?
                 {
+                    // Element columns inherit the list's metadata (e.g. its unit).
+                    let f = f.with_metadata(field.metadata().clone());
                     collect_columns(&f, f.name(), &a, sep, policy, effective_nullable, out)?;
                 }
             };
@@ -295,6 +411,7 @@ fn collect_columns(
                     .downcast_ref::<FixedSizeListArray>()
                     .expect("DataType::FixedSizeList matches FixedSizeListArray");
                 for (f, a) in expand_fixed_size_list(fsl_arr, path, sep)? {
+                    let f = f.with_metadata(field.metadata().clone());
                     collect_columns(&f, f.name(), &a, sep, policy, effective_nullable, out)?;
                 }
             };
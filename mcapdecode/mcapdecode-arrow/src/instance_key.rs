@@ -0,0 +1,62 @@
+//! Composite instance-key column derived from `@key` fields.
+
+use std::{
+    hash::{DefaultHasher, Hasher},
+    sync::Arc,
+};
+
+use arrow::{
+    array::{ArrayRef, UInt64Array},
+    datatypes::{Field, FieldRef},
+    error::ArrowError,
+    record_batch::RecordBatch,
+    row::{RowConverter, SortField},
+};
+use mcapdecode_core::KEY_METADATA_KEY;
+
+/// Name of the column holding a hash of the instance key fields of a row.
+pub const INSTANCE_KEY_COLUMN: &str = "@instance_key";
+
+/// Whether `field` carries the [`KEY_METADATA_KEY`] marker.
+pub fn is_key_field(field: &Field) -> bool {
+    field
+        .metadata()
+        .get(KEY_METADATA_KEY)
+        .is_some_and(|v| v == "true")
+}
+
+/// Hash the top-level key columns of each row of `batch` into a `UInt64`
+/// column, so that rows of the same instance share a value.
+///
+/// Returns `None` when no column of `batch` is marked as a key. Hashes are
+/// stable within a build of the crate but not guaranteed across Rust versions.
+pub fn instance_key_column(batch: &RecordBatch) -> Result<Option<ArrayRef>, ArrowError> {
+    let schema = batch.schema();
+    let (fields, columns): (Vec<&FieldRef>, Vec<ArrayRef>) = schema
+        .fields()
+        .iter()
+        .zip(batch.columns())
+        .filter(|(field, _)| is_key_field(field))
+        .map(|(field, column)| (field, column.clone()))
+        .unzip();
+    if columns.is_empty() {
+        return Ok(None);
+    }
+
+    let converter = RowConverter::new(
+        fields
+            .iter()
+            .map(|field| SortField::new(field.data_type().clone()))
+            .collect(),
+    )?;
+    let rows = converter.convert_columns(&columns)?;
+    let hashes: UInt64Array = rows
+        .iter()
+        .map(|row| {
+            let mut hasher = DefaultHasher::new();
+            hasher.write(row.as_ref());
+            hasher.finish()
+        })
+        .collect();
+    Ok(Some(Arc::new(hashes)))
+}
@@ -0,0 +1,198 @@
+//! Cartesian points derived from `sensor_msgs/msg/LaserScan` batches.
+
+use std::{str::FromStr, sync::Arc};
+
+use arrow::{
+    array::{Array, ArrayRef, Float32Array, ListArray, StructArray, UInt32Array},
+    buffer::OffsetBuffer,
+    compute::take,
+    datatypes::{DataType, Field, Fields, Schema},
+    error::ArrowError,
+    record_batch::RecordBatch,
+};
+
+use crate::nested::downcast;
+
+/// Name of the list column [`LaserScanPoints::List`] appends.
+pub const POINTS_COLUMN: &str = "points";
+
+const LIST_COLUMNS: [&str; 2] = ["ranges", "intensities"];
+
+/// How [`laser_scan_to_points`] writes the points of a scan.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum LaserScanPoints {
+    /// Append a `points` column holding a list of `{x, y}` structs per scan.
+    #[default]
+    List,
+    /// Write one row per point with `x` and `y` columns, repeating the other
+    /// columns of the scan and dropping `ranges` / `intensities`.
+    Explode,
+}
+
+impl FromStr for LaserScanPoints {
+    type Err = String;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        let lower = raw.to_ascii_lowercase();
+        match lower.as_str() {
+            "list" => Ok(Self::List),
+            "explode" => Ok(Self::Explode),
+            _ => Err(format!(
+                "invalid laser scan points '{raw}' (expected: list, explode)"
+            )),
+        }
+    }
+}
+
+/// Whether `schema` has the top-level `angle_min` and `angle_increment`
+/// `Float32` fields and `ranges` `Float32` list of a
+/// `sensor_msgs/msg/LaserScan`.
+pub fn is_laser_scan_schema(schema: &Schema) -> bool {
+    let is_f32 = |name: &str| {
+        schema
+            .field_with_name(name)
+            .is_ok_and(|f| f.data_type() == &DataType::Float32)
+    };
+    is_f32("angle_min")
+        && is_f32("angle_increment")
+        && schema.field_with_name("ranges").is_ok_and(|f| {
+            matches!(f.data_type(), DataType::List(item) if item.data_type() == &DataType::Float32)
+        })
+}
+
+/// Convert the ranges of every scan in `batch` to points in the sensor frame
+/// (`x = r cos θ`, `y = r sin θ` with `θ = angle_min + i * angle_increment`),
+/// written as `points` says.
+///
+/// Ranges that are not finite or lie outside `[range_min, range_max]` (when
+/// those columns are present) are skipped, as `laser_geometry` does.
+/// Batches that are not laser scans (see [`is_laser_scan_schema`]) are
+/// returned unchanged.
+///
+/// # Errors
+///
+/// Returns [`ArrowError::InvalidArgumentError`] if `batch` already has the
+/// columns the points would be written to.
+pub fn laser_scan_to_points(
+    batch: &RecordBatch,
+    points: LaserScanPoints,
+) -> Result<RecordBatch, ArrowError> {
+    let schema = batch.schema();
+    if !is_laser_scan_schema(&schema) {
+        return Ok(batch.clone());
+    }
+    let output_columns: &[&str] = match points {
+        LaserScanPoints::List => &[POINTS_COLUMN],
+        LaserScanPoints::Explode => &["x", "y"],
+    };
+    if let Some(name) = output_columns
+        .iter()
+        .find(|name| schema.field_with_name(name).is_ok())
+    {
+        return Err(ArrowError::InvalidArgumentError(format!(
+            "laser scan batch already has a '{name}' column"
+        )));
+    }
+
+    let column =
+        |name: &str| -> Result<&ArrayRef, ArrowError> { Ok(batch.column(schema.index_of(name)?)) };
+    let optional = |name: &str| -> Result<Option<&Float32Array>, ArrowError> {
+        column(name).ok().map(downcast::<Float32Array>).transpose()
+    };
+    let angle_min = downcast::<Float32Array>(column("angle_min")?)?;
+    let angle_increment = downcast::<Float32Array>(column("angle_increment")?)?;
+    let range_min = optional("range_min")?;
+    let range_max = optional("range_max")?;
+    let ranges = downcast::<ListArray>(column("ranges")?)?;
+
+    // Points of every scan, and the scan (row) each point belongs to.
+    let mut xs = Vec::new();
+    let mut ys = Vec::new();
+    let mut rows = Vec::new();
+    let mut offsets = vec![0usize];
+    for row in 0..batch.num_rows() {
+        if ranges.is_valid(row) && angle_min.is_valid(row) && angle_increment.is_valid(row) {
+            let bound = |column: Option<&Float32Array>| {
+                column.filter(|c| c.is_valid(row)).map(|c| c.value(row))
+            };
+            let (min, max) = (bound(range_min), bound(range_max));
+            let scan = ranges.value(row);
+            let scan = downcast::<Float32Array>(&scan)?;
+            for (i, range) in scan.iter().enumerate() {
+                let Some(range) = range.filter(|r| {
+                    r.is_finite()
+                        && min.is_none_or(|min| *r >= min)
+                        && max.is_none_or(|max| *r <= max)
+                }) else {
+                    continue;
+                };
+                let angle = f64::from(angle_min.value(row))
+                    + i as f64 * f64::from(angle_increment.value(row));
+                let range = f64::from(range);
+                xs.push((range * angle.cos()) as f32);
+                ys.push((range * angle.sin()) as f32);
+                rows.push(row as u32);
+            }
+        }
+        offsets.push(xs.len());
+    }
+
+    let point_fields = Fields::from(vec![
+        Field::new("x", DataType::Float32, false),
+        Field::new("y", DataType::Float32, false),
+    ]);
+    let (xs, ys): (ArrayRef, ArrayRef) = (
+        Arc::new(Float32Array::from(xs)),
+        Arc::new(Float32Array::from(ys)),
+    );
+    match points {
+        LaserScanPoints::List => {
+            let item = Arc::new(Field::new(
+                "item",
+                DataType::Struct(point_fields.clone()),
+                false,
+            ));
+            let points = ListArray::try_new(
+                item.clone(),
+                OffsetBuffer::from_lengths(offsets.windows(2).map(|w| w[1] - w[0])),
+                Arc::new(StructArray::try_new(point_fields, vec![xs, ys], None)?),
+                ranges.nulls().cloned(),
+            )?;
+            let mut fields: Vec<_> = schema.fields().iter().cloned().collect();
+            fields.push(Arc::new(Field::new(
+                POINTS_COLUMN,
+                DataType::List(item),
+                schema.field_with_name("ranges")?.is_nullable(),
+            )));
+            let mut columns = batch.columns().to_vec();
+            columns.push(Arc::new(points));
+            RecordBatch::try_new(
+                Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone())),
+                columns,
+            )
+        }
+        LaserScanPoints::Explode => {
+            let indices = UInt32Array::from(rows);
+            let mut fields = Vec::new();
+            let mut columns = Vec::new();
+            for (field, column) in schema.fields().iter().zip(batch.columns()) {
+                if LIST_COLUMNS.contains(&field.name().as_str()) {
+                    continue;
+                }
+                fields.push(field.clone());
+                columns.push(take(column, &indices, None)?);
+            }
+            fields.extend(point_fields.iter().cloned());
+            columns.extend([xs, ys]);
+            RecordBatch::try_new(
+                Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone())),
+                columns,
+            )
+        }
+    }
+}
@@ -1,12 +1,15 @@
 //! Arrow integration layer for `mcapdecode`.
 //!
 //! This crate focuses on two responsibilities:
-//! 1. Convert `mcapdecode-core` schema IR (`FieldDef`) to Arrow `Schema`.
+//! 1. Convert `mcapdecode-core` schema IR (`FieldDef`) to Arrow `Schema`, and
+//!    back.
 //! 2. Convert decoded `DecodedMessage` rows into Arrow `RecordBatch`.
 //!
 //! `mcapdecode-arrow` intentionally keeps the public API small and exposes only
 //! two entry points:
-//! - [`field_defs_to_arrow_schema`] for schema conversion.
+//! - [`field_defs_to_arrow_schema`] (or the memoized
+//!   [`field_defs_to_arrow_schema_cached`]) for schema conversion, and
+//!   [`arrow_schema_to_field_defs`] for the reverse.
 //! - [`arrow_value_rows_to_record_batch`] / [`try_arrow_value_rows_to_record_batch`]
 //!   for row-to-batch conversion.
 //!
@@ -28,24 +31,74 @@
 //! }
 //! ```
 pub mod arrow_convert;
+pub mod column_stats;
+pub mod conversion_options;
+pub mod covariance;
 pub mod error;
+pub mod extension;
 pub mod flatten;
+pub mod instance_key;
+pub mod laser_scan;
+mod nested;
 pub mod projection;
+pub mod quaternion;
+pub mod schema_compat;
 pub mod schema_convert;
+pub mod snapshot;
+pub mod time_columns;
+pub mod uuid;
 
 /// Re-export of [`arrow_convert::arrow_value_rows_to_record_batch`].
 pub use arrow_convert::arrow_value_rows_to_record_batch;
 /// Re-exports from [`arrow_convert`].
-pub use arrow_convert::try_arrow_value_rows_to_record_batch;
-/// Re-export of [`error::ArrowConvertError`].
-pub use error::ArrowConvertError;
+pub use arrow_convert::{
+    ConvertOptions, ConvertReport, DuplicateMapKeys, StructMismatch, WideningPolicy,
+    try_arrow_value_rows_to_record_batch, try_arrow_value_rows_to_record_batch_with_options,
+    try_arrow_value_rows_to_record_batch_with_report,
+};
+/// Re-exports from [`column_stats`].
+pub use column_stats::{ColumnStats, ColumnStatsAccumulator, StatValue};
+/// Re-exports from [`conversion_options`].
+pub use conversion_options::{ConversionOptions, ConversionOptionsBuilder};
+/// Re-exports from [`covariance`].
+pub use covariance::{CovarianceColumns, is_covariance_field, name_covariance_columns};
+/// Re-exports from [`error`].
+pub use error::{ArrowConvertError, ConversionOptionsError};
+/// Re-exports from [`extension`].
+pub use extension::{
+    EXTENSION_NAME_KEY, GEO_POINT_EXTENSION, QUATERNION_EXTENSION, TIME_EXTENSION, UUID_EXTENSION,
+    add_extension_types, extension_type_name, with_extension_types,
+};
 /// Re-exports from [`flatten`].
 pub use flatten::{
-    ArrayPolicy, FlattenPolicy, ListPolicy, MapPolicy, StructPolicy, flatten_record_batch,
+    ArrayPolicy, FlattenPolicy, ListPolicy, ListPolicySpec, MapPolicy, StructPolicy,
+    flatten_record_batch,
 };
+/// Re-exports from [`instance_key`].
+pub use instance_key::{INSTANCE_KEY_COLUMN, instance_key_column, is_key_field};
+/// Re-exports from [`laser_scan`].
+pub use laser_scan::{LaserScanPoints, POINTS_COLUMN, is_laser_scan_schema, laser_scan_to_points};
 /// Re-export of [`projection::project_record_batch`].
 pub use projection::project_record_batch;
-/// Re-export of [`schema_convert::field_defs_to_arrow_schema`].
-pub use schema_convert::field_defs_to_arrow_schema;
+/// Re-exports from [`quaternion`].
+pub use quaternion::{QuaternionColumns, add_quaternion_columns, is_quaternion_field};
+/// Re-exports from [`schema_compat`].
+pub use schema_compat::{
+    SchemaEvolution, SchemaIncompatibility, check_append_compatibility, conform_to_schema,
+    merge_append_schema, schema_fingerprint,
+};
+/// Re-exports from [`schema_convert`].
+pub use schema_convert::{
+    arrow_schema_to_field_defs, field_defs_to_arrow_schema, field_defs_to_arrow_schema_cached,
+    field_defs_to_record_batch_schema, with_sorted_map_keys,
+};
+/// Re-export of [`snapshot::LatestSnapshot`].
+pub use snapshot::LatestSnapshot;
+/// Re-exports from [`time_columns`].
+pub use time_columns::{
+    ELAPSED_NS_COLUMN, LATENCY_NS_COLUMN, TimeColumns, apply_time_columns, with_latency_column,
+};
+/// Re-export of [`uuid::render_uuid_columns`].
+pub use uuid::render_uuid_columns;
 
 pub(crate) const TIMESTAMP_TZ: &str = "+00:00";
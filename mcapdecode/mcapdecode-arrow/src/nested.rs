@@ -0,0 +1,132 @@
+//! Rewriting of fields nested anywhere in a `RecordBatch`.
+
+use std::sync::Arc;
+
+use arrow::{
+    array::{Array, ArrayRef, FixedSizeListArray, LargeListArray, ListArray, StructArray},
+    datatypes::{DataType, Field, FieldRef, Fields, Schema},
+    error::ArrowError,
+    record_batch::RecordBatch,
+};
+
+/// Field predicate of [`rewrite_fields`].
+pub(crate) type FieldFilter<'a> = &'a dyn Fn(&Field) -> bool;
+
+/// Field and column rewrite of [`rewrite_fields`].
+pub(crate) type FieldRewrite<'a> =
+    &'a dyn Fn(&FieldRef, &ArrayRef) -> Result<(FieldRef, ArrayRef), ArrowError>;
+
+/// Apply `rewrite` to every field of `batch` that `matches`, at the top
+/// level or inside structs and lists (the matched fields themselves are not
+/// descended into). Batches without a matching field are returned unchanged.
+pub(crate) fn rewrite_fields(
+    batch: &RecordBatch,
+    matches: FieldFilter<'_>,
+    rewrite: FieldRewrite<'_>,
+) -> Result<RecordBatch, ArrowError> {
+    let schema = batch.schema();
+    if !schema.fields().iter().any(|f| contains(f, matches)) {
+        return Ok(batch.clone());
+    }
+    let (fields, columns) = rewrite_children(schema.fields(), batch.columns(), matches, rewrite)?;
+    RecordBatch::try_new(
+        Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone())),
+        columns,
+    )
+}
+
+/// Downcast `array` to the array type of its field.
+pub(crate) fn downcast<T: 'static>(array: &ArrayRef) -> Result<&T, ArrowError> {
+    array.as_any().downcast_ref::<T>().ok_or_else(|| {
+        ArrowError::InvalidArgumentError(format!(
+            "column of type {} does not match its field",
+            array.data_type()
+        ))
+    })
+}
+
+fn contains(field: &Field, matches: FieldFilter<'_>) -> bool {
+    if matches(field) {
+        return true;
+    }
+    match field.data_type() {
+        DataType::Struct(children) => children.iter().any(|f| contains(f, matches)),
+        DataType::List(item) | DataType::LargeList(item) | DataType::FixedSizeList(item, _) => {
+            contains(item, matches)
+        }
+        _ => false,
+    }
+}
+
+fn rewrite_children(
+    fields: &Fields,
+    columns: &[ArrayRef],
+    matches: FieldFilter<'_>,
+    rewrite: FieldRewrite<'_>,
+) -> Result<(Fields, Vec<ArrayRef>), ArrowError> {
+    let (fields, columns): (Vec<FieldRef>, Vec<ArrayRef>) = fields
+        .iter()
+        .zip(columns)
+        .map(|(field, column)| rewrite_field(field, column, matches, rewrite))
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .unzip();
+    Ok((fields.into(), columns))
+}
+
+fn rewrite_field(
+    field: &FieldRef,
+    array: &ArrayRef,
+    matches: FieldFilter<'_>,
+    rewrite: FieldRewrite<'_>,
+) -> Result<(FieldRef, ArrayRef), ArrowError> {
+    if matches(field) {
+        return rewrite(field, array);
+    }
+    if !contains(field, matches) {
+        return Ok((field.clone(), array.clone()));
+    }
+    let with_type =
+        |data_type: DataType| Arc::new(field.as_ref().clone().with_data_type(data_type));
+    match field.data_type() {
+        DataType::Struct(children) => {
+            let st = downcast::<StructArray>(array)?;
+            let (children, columns) = rewrite_children(children, st.columns(), matches, rewrite)?;
+            let rewritten = StructArray::try_new(children.clone(), columns, st.nulls().cloned())?;
+            Ok((with_type(DataType::Struct(children)), Arc::new(rewritten)))
+        }
+        DataType::List(item) => {
+            let list = downcast::<ListArray>(array)?;
+            let (item, values) = rewrite_field(item, list.values(), matches, rewrite)?;
+            let rewritten = ListArray::try_new(
+                item.clone(),
+                list.offsets().clone(),
+                values,
+                list.nulls().cloned(),
+            )?;
+            Ok((with_type(DataType::List(item)), Arc::new(rewritten)))
+        }
+        DataType::LargeList(item) => {
+            let list = downcast::<LargeListArray>(array)?;
+            let (item, values) = rewrite_field(item, list.values(), matches, rewrite)?;
+            let rewritten = LargeListArray::try_new(
+                item.clone(),
+                list.offsets().clone(),
+                values,
+                list.nulls().cloned(),
+            )?;
+            Ok((with_type(DataType::LargeList(item)), Arc::new(rewritten)))
+        }
+        DataType::FixedSizeList(item, size) => {
+            let list = downcast::<FixedSizeListArray>(array)?;
+            let (item, values) = rewrite_field(item, list.values(), matches, rewrite)?;
+            let rewritten =
+                FixedSizeListArray::try_new(item.clone(), *size, values, list.nulls().cloned())?;
+            Ok((
+                with_type(DataType::FixedSizeList(item, *size)),
+                Arc::new(rewritten),
+            ))
+        }
+        _ => Ok((field.clone(), array.clone())),
+    }
+}
@@ -0,0 +1,198 @@
+//! Euler angle and rotation matrix columns derived from quaternions.
+
+use std::{collections::HashMap, str::FromStr, sync::Arc};
+
+use arrow::{
+    array::{Array, ArrayRef, Float64Array, StructArray},
+    datatypes::{DataType, Field, FieldRef, Fields},
+    error::ArrowError,
+    record_batch::RecordBatch,
+};
+use mcapdecode_core::UNIT_METADATA_KEY;
+
+use crate::nested::{downcast, rewrite_fields};
+
+const COMPONENTS: [&str; 4] = ["x", "y", "z", "w"];
+
+const EULER_FIELDS: [&str; 3] = ["roll", "pitch", "yaw"];
+
+const MATRIX_FIELDS: [&str; 9] = [
+    "r00", "r01", "r02", "r10", "r11", "r12", "r20", "r21", "r22",
+];
+
+/// Which columns [`add_quaternion_columns`] derives from each quaternion.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum QuaternionColumns {
+    /// `roll`, `pitch` and `yaw` in radians.
+    #[default]
+    Euler,
+    /// `roll`, `pitch` and `yaw` in degrees.
+    EulerDegrees,
+    /// The row-major rotation matrix `r00` … `r22`.
+    RotationMatrix,
+}
+
+impl FromStr for QuaternionColumns {
+    type Err = String;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        let lower = raw.to_ascii_lowercase();
+        match lower.as_str() {
+            "euler" => Ok(Self::Euler),
+            "euler-degrees" => Ok(Self::EulerDegrees),
+            "rotation-matrix" => Ok(Self::RotationMatrix),
+            _ => Err(format!(
+                "invalid quaternion columns '{raw}' (expected: euler, euler-degrees, rotation-matrix)"
+            )),
+        }
+    }
+}
+
+/// Whether `field` is a quaternion: a struct of exactly the `Float64`
+/// fields `x`, `y`, `z` and `w`, as `geometry_msgs/msg/Quaternion` and
+/// `foxglove.Quaternion`.
+pub fn is_quaternion_field(field: &Field) -> bool {
+    let DataType::Struct(children) = field.data_type() else {
+        return false;
+    };
+    children.len() == COMPONENTS.len()
+        && COMPONENTS.iter().all(|name| {
+            children
+                .find(name)
+                .is_some_and(|(_, f)| f.data_type() == &DataType::Float64)
+        })
+}
+
+/// Append the `columns` derived from every quaternion of `batch` to the
+/// quaternion struct, next to `x`, `y`, `z` and `w` (e.g.
+/// `pose.orientation.yaw`).
+///
+/// Euler angles follow the ROS convention: rotations about the fixed X, Y
+/// and Z axes, applied in that order (`tf2::Matrix3x3::getRPY`). Quaternions
+/// are normalized first; derived values are null for null or zero-length
+/// quaternions. Quaternions nested in structs and lists are handled too, and
+/// batches without quaternions are returned unchanged.
+pub fn add_quaternion_columns(
+    batch: &RecordBatch,
+    columns: QuaternionColumns,
+) -> Result<RecordBatch, ArrowError> {
+    rewrite_fields(batch, &is_quaternion_field, &|field, array| {
+        derive_columns(field, array, columns)
+    })
+}
+
+fn derive_columns(
+    field: &FieldRef,
+    array: &ArrayRef,
+    columns: QuaternionColumns,
+) -> Result<(FieldRef, ArrayRef), ArrowError> {
+    let st = downcast::<StructArray>(array)?;
+    let component = |name: &str| {
+        st.column_by_name(name)
+            .ok_or_else(|| ArrowError::SchemaError(format!("quaternion has no field {name}")))
+            .and_then(downcast::<Float64Array>)
+    };
+    let [x, y, z, w] = [
+        component("x")?,
+        component("y")?,
+        component("z")?,
+        component("w")?,
+    ];
+
+    let matrices: Vec<Option<[f64; 9]>> = (0..st.len())
+        .map(|i| {
+            if st.is_null(i) || [x, y, z, w].iter().any(|c| c.is_null(i)) {
+                return None;
+            }
+            rotation_matrix(x.value(i), y.value(i), z.value(i), w.value(i))
+        })
+        .collect();
+
+    let (names, unit, values): (&[&str], _, Vec<Float64Array>) = match columns {
+        QuaternionColumns::Euler | QuaternionColumns::EulerDegrees => {
+            let degrees = columns == QuaternionColumns::EulerDegrees;
+            let angles: Vec<Option<[f64; 3]>> = matrices
+                .iter()
+                .map(|m| {
+                    m.map(|m| {
+                        euler(&m).map(|angle| if degrees { angle.to_degrees() } else { angle })
+                    })
+                })
+                .collect();
+            let values = (0..EULER_FIELDS.len())
+                .map(|k| angles.iter().map(|a| a.map(|a| a[k])).collect())
+                .collect();
+            (
+                &EULER_FIELDS[..],
+                Some(if degrees { "deg" } else { "rad" }),
+                values,
+            )
+        }
+        QuaternionColumns::RotationMatrix => {
+            let values = (0..MATRIX_FIELDS.len())
+                .map(|k| matrices.iter().map(|m| m.map(|m| m[k])).collect())
+                .collect();
+            (&MATRIX_FIELDS[..], None, values)
+        }
+    };
+
+    let mut fields: Vec<FieldRef> = st.fields().iter().cloned().collect();
+    let mut arrays: Vec<ArrayRef> = st.columns().to_vec();
+    for (name, values) in names.iter().zip(values) {
+        let mut derived = Field::new(*name, DataType::Float64, true);
+        if let Some(unit) = unit {
+            derived = derived.with_metadata(HashMap::from([(
+                UNIT_METADATA_KEY.to_string(),
+                unit.to_string(),
+            )]));
+        }
+        fields.push(Arc::new(derived));
+        arrays.push(Arc::new(values));
+    }
+    let fields = Fields::from(fields);
+    let derived = StructArray::try_new(fields.clone(), arrays, st.nulls().cloned())?;
+    Ok((
+        Arc::new(
+            field
+                .as_ref()
+                .clone()
+                .with_data_type(DataType::Struct(fields)),
+        ),
+        Arc::new(derived),
+    ))
+}
+
+/// Row-major rotation matrix of the normalized quaternion, `None` for
+/// zero-length or non-finite quaternions.
+fn rotation_matrix(x: f64, y: f64, z: f64, w: f64) -> Option<[f64; 9]> {
+    let norm = (x * x + y * y + z * z + w * w).sqrt();
+    if !norm.is_finite() || norm == 0.0 {
+        return None;
+    }
+    let (x, y, z, w) = (x / norm, y / norm, z / norm, w / norm);
+    Some([
+        1.0 - 2.0 * (y * y + z * z),
+        2.0 * (x * y - z * w),
+        2.0 * (x * z + y * w),
+        2.0 * (x * y + z * w),
+        1.0 - 2.0 * (x * x + z * z),
+        2.0 * (y * z - x * w),
+        2.0 * (x * z - y * w),
+        2.0 * (y * z + x * w),
+        1.0 - 2.0 * (x * x + y * y),
+    ])
+}
+
+/// Roll, pitch and yaw of a rotation matrix.
+fn euler(m: &[f64; 9]) -> [f64; 3] {
+    [
+        m[7].atan2(m[8]),
+        (-m[6]).clamp(-1.0, 1.0).asin(),
+        m[3].atan2(m[0]),
+    ]
+}
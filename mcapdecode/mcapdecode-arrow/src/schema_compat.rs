@@ -0,0 +1,403 @@
+//! Append compatibility between a derived Arrow schema and the schema of an
+//! existing dataset (e.g. a Parquet file written by an earlier export),
+//! schema merging for datasets that gain fields, and schema fingerprints for
+//! recognizing identical schemas.
+
+use std::{fmt::Write as _, str::FromStr, sync::Arc};
+
+use arrow::{
+    array::{
+        Array, ArrayRef, FixedSizeListArray, LargeListArray, ListArray, MapArray, StructArray,
+        new_null_array,
+    },
+    datatypes::{DataType, Field, Fields, Schema, SchemaRef},
+    error::ArrowError,
+    record_batch::{RecordBatch, RecordBatchOptions},
+};
+use thiserror::Error;
+
+use crate::nested::downcast;
+
+/// Difference that makes appending batches of a new schema to a dataset of
+/// an existing schema unsafe.
+///
+/// Field paths join struct members with `.`; list items are `name[]`.
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum SchemaIncompatibility {
+    #[error("field '{path}' is missing from the new schema")]
+    MissingField { path: String },
+    #[error("field '{path}' does not exist in the existing schema")]
+    ExtraField { path: String },
+    #[error("field '{path}' changed type from {existing} to {new}")]
+    TypeMismatch {
+        path: String,
+        existing: DataType,
+        new: DataType,
+    },
+    #[error("field '{path}' is nullable in the new schema but not in the existing one")]
+    NullableField { path: String },
+}
+
+/// Check whether batches of `new` can be appended to a dataset of `existing`.
+///
+/// Appending is safe when both schemas have the same fields (in any order)
+/// with the same types, and no field that is non-nullable in `existing` is
+/// nullable in `new`. Field metadata is ignored. Returns every difference
+/// found; an empty result means appending is safe.
+pub fn check_append_compatibility(existing: &Schema, new: &Schema) -> Vec<SchemaIncompatibility> {
+    let mut issues = Vec::new();
+    compare_fields(existing.fields(), new.fields(), "", &mut issues);
+    issues
+}
+
+fn compare_fields(
+    existing: &Fields,
+    new: &Fields,
+    prefix: &str,
+    issues: &mut Vec<SchemaIncompatibility>,
+) {
+    for existing_field in existing {
+        let path = format!("{prefix}{}", existing_field.name());
+        match new.find(existing_field.name()) {
+            Some((_, new_field)) => compare_field(existing_field, new_field, path, issues),
+            None => issues.push(SchemaIncompatibility::MissingField { path }),
+        }
+    }
+    for new_field in new {
+        if existing.find(new_field.name()).is_none() {
+            issues.push(SchemaIncompatibility::ExtraField {
+                path: format!("{prefix}{}", new_field.name()),
+            });
+        }
+    }
+}
+
+fn compare_field(
+    existing: &Field,
+    new: &Field,
+    path: String,
+    issues: &mut Vec<SchemaIncompatibility>,
+) {
+    if new.is_nullable() && !existing.is_nullable() {
+        issues.push(SchemaIncompatibility::NullableField { path: path.clone() });
+    }
+    match (existing.data_type(), new.data_type()) {
+        (DataType::Struct(a), DataType::Struct(b)) => {
+            compare_fields(a, b, &format!("{path}."), issues);
+        }
+        (DataType::List(a), DataType::List(b))
+        | (DataType::LargeList(a), DataType::LargeList(b)) => {
+            compare_field(a, b, format!("{path}[]"), issues);
+        }
+        (DataType::FixedSizeList(a, n), DataType::FixedSizeList(b, m)) if n == m => {
+            compare_field(a, b, format!("{path}[]"), issues);
+        }
+        (DataType::Map(a, sorted_a), DataType::Map(b, sorted_b)) if sorted_a == sorted_b => {
+            compare_field(a, b, path, issues);
+        }
+        (a, b) if a == b => {}
+        (a, b) => issues.push(SchemaIncompatibility::TypeMismatch {
+            path,
+            existing: a.clone(),
+            new: b.clone(),
+        }),
+    }
+}
+
+/// How [`merge_append_schema`] treats fields that exist in only one of the
+/// schemas.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum SchemaEvolution {
+    /// Require the same fields, as [`check_append_compatibility`] does.
+    #[default]
+    Strict,
+    /// Widen the dataset: fields only in the new schema are added as
+    /// nullable columns, and nullable fields only in the existing schema are
+    /// kept and filled with nulls.
+    AddColumns,
+}
+
+impl FromStr for SchemaEvolution {
+    type Err = String;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        let lower = raw.to_ascii_lowercase();
+        match lower.as_str() {
+            "strict" => Ok(Self::Strict),
+            "add-columns" => Ok(Self::AddColumns),
+            _ => Err(format!(
+                "invalid schema evolution '{raw}' (expected: strict, add-columns)"
+            )),
+        }
+    }
+}
+
+/// Schema to write batches of `new` with when appending them to a dataset of
+/// `existing`, or every difference that prevents appending.
+///
+/// With [`SchemaEvolution::Strict`] this is `new` itself, provided
+/// [`check_append_compatibility`] finds no difference. With
+/// [`SchemaEvolution::AddColumns`] the merged schema has the fields of
+/// `existing` in their order, with their nullability, followed by the fields
+/// only `new` has (as nullable); the same applies to struct members, also
+/// inside lists and maps. Fields missing from `new` are only allowed if they
+/// are nullable, and type changes or fields becoming nullable are still
+/// rejected. Use [`conform_to_schema`] to write batches of `new` with the
+/// merged schema.
+pub fn merge_append_schema(
+    existing: &Schema,
+    new: &Schema,
+    evolution: SchemaEvolution,
+) -> Result<Schema, Vec<SchemaIncompatibility>> {
+    let mut issues = Vec::new();
+    let merged = match evolution {
+        SchemaEvolution::Strict => {
+            compare_fields(existing.fields(), new.fields(), "", &mut issues);
+            new.clone()
+        }
+        SchemaEvolution::AddColumns => {
+            let fields = merge_fields(existing.fields(), new.fields(), "", &mut issues);
+            Schema::new_with_metadata(fields, new.metadata().clone())
+        }
+    };
+    if issues.is_empty() {
+        Ok(merged)
+    } else {
+        Err(issues)
+    }
+}
+
+/// Rearrange `batch` to `schema`, a schema returned by
+/// [`merge_append_schema`] for the schema of `batch`: columns are reordered
+/// by name, and fields `batch` does not have are filled with nulls.
+///
+/// # Errors
+///
+/// Returns an [`ArrowError`] if a column of `batch` does not match the type
+/// of its field in `schema`.
+pub fn conform_to_schema(
+    batch: &RecordBatch,
+    schema: SchemaRef,
+) -> Result<RecordBatch, ArrowError> {
+    if batch.schema() == schema {
+        return Ok(batch.clone());
+    }
+    let columns = conform_children(
+        schema.fields(),
+        batch.schema().fields(),
+        batch.columns(),
+        batch.num_rows(),
+    )?;
+    RecordBatch::try_new_with_options(
+        schema,
+        columns,
+        &RecordBatchOptions::new().with_row_count(Some(batch.num_rows())),
+    )
+}
+
+fn merge_fields(
+    existing: &Fields,
+    new: &Fields,
+    prefix: &str,
+    issues: &mut Vec<SchemaIncompatibility>,
+) -> Fields {
+    let mut merged = Vec::with_capacity(existing.len().max(new.len()));
+    for existing_field in existing {
+        let path = format!("{prefix}{}", existing_field.name());
+        match new.find(existing_field.name()) {
+            Some((_, new_field)) => {
+                merged.push(Arc::new(merge_field(
+                    existing_field,
+                    new_field,
+                    path,
+                    issues,
+                )));
+            }
+            None if existing_field.is_nullable() => merged.push(existing_field.clone()),
+            None => issues.push(SchemaIncompatibility::MissingField { path }),
+        }
+    }
+    for new_field in new {
+        if existing.find(new_field.name()).is_none() {
+            merged.push(Arc::new(new_field.as_ref().clone().with_nullable(true)));
+        }
+    }
+    merged.into()
+}
+
+fn merge_field(
+    existing: &Field,
+    new: &Field,
+    path: String,
+    issues: &mut Vec<SchemaIncompatibility>,
+) -> Field {
+    if new.is_nullable() && !existing.is_nullable() {
+        issues.push(SchemaIncompatibility::NullableField { path: path.clone() });
+    }
+    let data_type = match (existing.data_type(), new.data_type()) {
+        (DataType::Struct(a), DataType::Struct(b)) => {
+            DataType::Struct(merge_fields(a, b, &format!("{path}."), issues))
+        }
+        (DataType::List(a), DataType::List(b)) => {
+            DataType::List(Arc::new(merge_field(a, b, format!("{path}[]"), issues)))
+        }
+        (DataType::LargeList(a), DataType::LargeList(b)) => {
+            DataType::LargeList(Arc::new(merge_field(a, b, format!("{path}[]"), issues)))
+        }
+        (DataType::FixedSizeList(a, n), DataType::FixedSizeList(b, m)) if n == m => {
+            DataType::FixedSizeList(Arc::new(merge_field(a, b, format!("{path}[]"), issues)), *n)
+        }
+        (DataType::Map(a, sorted_a), DataType::Map(b, sorted_b)) if sorted_a == sorted_b => {
+            DataType::Map(Arc::new(merge_field(a, b, path, issues)), *sorted_a)
+        }
+        (a, b) if a == b => a.clone(),
+        (a, b) => {
+            issues.push(SchemaIncompatibility::TypeMismatch {
+                path,
+                existing: a.clone(),
+                new: b.clone(),
+            });
+            a.clone()
+        }
+    };
+    new.clone()
+        .with_data_type(data_type)
+        .with_nullable(existing.is_nullable())
+}
+
+fn conform_children(
+    target: &Fields,
+    fields: &Fields,
+    columns: &[ArrayRef],
+    len: usize,
+) -> Result<Vec<ArrayRef>, ArrowError> {
+    target
+        .iter()
+        .map(|field| match fields.find(field.name()) {
+            Some((index, _)) => conform_array(&columns[index], field.data_type()),
+            None => Ok(new_null_array(field.data_type(), len)),
+        })
+        .collect()
+}
+
+fn conform_array(array: &ArrayRef, data_type: &DataType) -> Result<ArrayRef, ArrowError> {
+    if array.data_type() == data_type {
+        return Ok(array.clone());
+    }
+    Ok(match data_type {
+        DataType::Struct(target) => {
+            let array = downcast::<StructArray>(array)?;
+            let columns = conform_children(target, array.fields(), array.columns(), array.len())?;
+            Arc::new(StructArray::try_new_with_length(
+                target.clone(),
+                columns,
+                array.nulls().cloned(),
+                array.len(),
+            )?)
+        }
+        DataType::List(item) => {
+            let array = downcast::<ListArray>(array)?;
+            Arc::new(ListArray::try_new(
+                item.clone(),
+                array.offsets().clone(),
+                conform_array(array.values(), item.data_type())?,
+                array.nulls().cloned(),
+            )?)
+        }
+        DataType::LargeList(item) => {
+            let array = downcast::<LargeListArray>(array)?;
+            Arc::new(LargeListArray::try_new(
+                item.clone(),
+                array.offsets().clone(),
+                conform_array(array.values(), item.data_type())?,
+                array.nulls().cloned(),
+            )?)
+        }
+        DataType::FixedSizeList(item, size) => {
+            let array = downcast::<FixedSizeListArray>(array)?;
+            Arc::new(FixedSizeListArray::try_new(
+                item.clone(),
+                *size,
+                conform_array(array.values(), item.data_type())?,
+                array.nulls().cloned(),
+            )?)
+        }
+        DataType::Map(entries, sorted) => {
+            let array = downcast::<MapArray>(array)?;
+            let values: ArrayRef = Arc::new(array.entries().clone());
+            let values = conform_array(&values, entries.data_type())?;
+            Arc::new(MapArray::try_new(
+                entries.clone(),
+                array.offsets().clone(),
+                downcast::<StructArray>(&values)?.clone(),
+                array.nulls().cloned(),
+                *sorted,
+            )?)
+        }
+        _ => {
+            return Err(ArrowError::InvalidArgumentError(format!(
+                "column of type {} cannot be written as {data_type}",
+                array.data_type()
+            )));
+        }
+    })
+}
+
+/// Fingerprint of the field names, order, types and nullability of `schema`.
+///
+/// Schema and field metadata are ignored. The fingerprint is a 64-bit
+/// FNV-1a hash, stable across runs and platforms for the same Arrow version.
+pub fn schema_fingerprint(schema: &Schema) -> u64 {
+    let mut text = String::new();
+    write_fields(&mut text, schema.fields());
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+fn write_fields(out: &mut String, fields: &Fields) {
+    out.push('{');
+    for field in fields {
+        write_field(out, field);
+        out.push(';');
+    }
+    out.push('}');
+}
+
+fn write_field(out: &mut String, field: &Field) {
+    let _ = write!(out, "{:?}:", field.name());
+    if field.is_nullable() {
+        out.push('?');
+    }
+    match field.data_type() {
+        DataType::Struct(fields) => write_fields(out, fields),
+        DataType::List(item) => {
+            out.push_str("List<");
+            write_field(out, item);
+            out.push('>');
+        }
+        DataType::LargeList(item) => {
+            out.push_str("LargeList<");
+            write_field(out, item);
+            out.push('>');
+        }
+        DataType::FixedSizeList(item, size) => {
+            let _ = write!(out, "FixedSizeList[{size}]<");
+            write_field(out, item);
+            out.push('>');
+        }
+        DataType::Map(entries, sorted) => {
+            let _ = write!(out, "Map[{sorted}]<");
+            write_field(out, entries);
+            out.push('>');
+        }
+        other => {
+            let _ = write!(out, "{other:?}");
+        }
+    }
+}
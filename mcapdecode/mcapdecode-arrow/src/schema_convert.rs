@@ -1,7 +1,14 @@
-use std::sync::Arc;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, OnceLock, PoisonError},
+};
 
-use arrow::datatypes::{DataType, Field, Schema, TimeUnit};
-use mcapdecode_core::{DataTypeDef, ElementDef, FieldDef, FieldDefs};
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use mcapdecode_core::{
+    BYTE_WIDTH_METADATA_KEY, DataTypeDef, ElementDef, FieldDef, FieldDefs, TIMESTAMP_METADATA_KEY,
+};
+
+use crate::error::ArrowConvertError;
 
 // ---------------------------------------------------------------------------
 // Convert FieldDef schema IR to Arrow schema (without timestamp prefix)
@@ -10,18 +17,99 @@ use mcapdecode_core::{DataTypeDef, ElementDef, FieldDef, FieldDefs};
 /// Converts `mcapdecode-core` schema IR into an Arrow `Schema`.
 ///
 /// The input is expected to represent message body fields only. Timestamp
-/// system columns are not included in the returned schema.
+/// system columns are not included in the returned schema. Field metadata
+/// (e.g. units) is carried over to the Arrow fields, `i64` fields marked
+/// with [`TIMESTAMP_METADATA_KEY`] become nanosecond `Timestamp` columns and
+/// `bytes` fields marked with [`BYTE_WIDTH_METADATA_KEY`] `FixedSizeBinary`
+/// columns.
 pub fn field_defs_to_arrow_schema(fields: &FieldDefs) -> Schema {
     let arrow_fields: Vec<Field> = fields.iter().map(field_def_to_arrow_field).collect();
     Schema::new(arrow_fields)
 }
 
+/// Like [`field_defs_to_arrow_schema`], but returns a shared schema that is
+/// converted once per distinct `fields` and reused for later calls.
+///
+/// Converted schemas are kept for the lifetime of the process, so this suits
+/// pipelines reading the same topics from many files.
+pub fn field_defs_to_arrow_schema_cached(fields: &FieldDefs) -> SchemaRef {
+    static CACHE: OnceLock<Mutex<HashMap<FieldDefs, SchemaRef>>> = OnceLock::new();
+    let mut cache = CACHE
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    if let Some(schema) = cache.get(fields) {
+        return Arc::clone(schema);
+    }
+    let schema = Arc::new(field_defs_to_arrow_schema(fields));
+    cache.insert(fields.clone(), Arc::clone(&schema));
+    schema
+}
+
+/// Returns the Arrow schema of `RecordBatch`es built from `fields`, i.e.
+/// [`field_defs_to_arrow_schema`] with the `@log_time` / `@publish_time`
+/// columns prepended.
+pub fn field_defs_to_record_batch_schema(fields: &FieldDefs) -> Schema {
+    with_timestamp_fields(field_defs_to_arrow_schema(fields))
+}
+
+/// Returns `schema` with every `Map` type, also nested ones, marked as
+/// `keys_sorted`.
+///
+/// Batches built for such a schema by
+/// [`arrow_value_rows_to_record_batch`](crate::arrow_value_rows_to_record_batch)
+/// hold their map entries sorted by key, so maps with the same entries compare
+/// and hash equal regardless of the decoder's entry order.
+pub fn with_sorted_map_keys(schema: &Schema) -> Schema {
+    let fields: Vec<Field> = schema
+        .fields()
+        .iter()
+        .map(|f| sorted_map_keys_field(f))
+        .collect();
+    Schema::new_with_metadata(fields, schema.metadata().clone())
+}
+
+fn sorted_map_keys_field(field: &Field) -> Field {
+    field
+        .clone()
+        .with_data_type(sorted_map_keys_datatype(field.data_type()))
+}
+
+fn sorted_map_keys_datatype(dt: &DataType) -> DataType {
+    match dt {
+        DataType::Struct(fields) => DataType::Struct(
+            fields
+                .iter()
+                .map(|f| sorted_map_keys_field(f))
+                .collect::<Vec<_>>()
+                .into(),
+        ),
+        DataType::List(field) => DataType::List(Arc::new(sorted_map_keys_field(field))),
+        DataType::FixedSizeList(field, size) => {
+            DataType::FixedSizeList(Arc::new(sorted_map_keys_field(field)), *size)
+        }
+        DataType::Map(entry_field, _) => {
+            DataType::Map(Arc::new(sorted_map_keys_field(entry_field)), true)
+        }
+        other => other.clone(),
+    }
+}
+
 fn field_def_to_arrow_field(f: &FieldDef) -> Field {
-    Field::new(
-        &f.name,
-        element_def_to_datatype(&f.element),
-        f.element.nullable,
-    )
+    let data_type = match f.element.data_type {
+        DataTypeDef::I64 if f.is_timestamp() => {
+            DataType::Timestamp(TimeUnit::Nanosecond, Some(Arc::from(crate::TIMESTAMP_TZ)))
+        }
+        DataTypeDef::Bytes if let Some(width) = f.byte_width() => {
+            DataType::FixedSizeBinary(width as i32)
+        }
+        _ => element_def_to_datatype(&f.element),
+    };
+    let field = Field::new(&f.name, data_type, f.element.nullable);
+    if f.metadata.is_empty() {
+        return field;
+    }
+    field.with_metadata(f.metadata.clone().into_iter().collect())
 }
 
 fn element_def_to_datatype(elem: &ElementDef) -> DataType {
@@ -65,6 +153,106 @@ fn element_def_to_datatype(elem: &ElementDef) -> DataType {
     }
 }
 
+// ---------------------------------------------------------------------------
+// Convert Arrow schema back to FieldDef schema IR
+// ---------------------------------------------------------------------------
+
+/// Converts an Arrow `Schema` into `mcapdecode-core` schema IR, the inverse of
+/// [`field_defs_to_arrow_schema`].
+///
+/// The schema is expected to hold message body fields only; drop the
+/// `@log_time` / `@publish_time` columns of a `RecordBatch` schema first.
+/// Field metadata is carried over, nanosecond `Timestamp` fields become
+/// `i64` fields marked with [`TIMESTAMP_METADATA_KEY`] and `FixedSizeBinary`
+/// fields `bytes` fields marked with [`BYTE_WIDTH_METADATA_KEY`]. The large
+/// and view variants of strings, binaries and lists convert like their plain
+/// counterparts; other types without a `DataTypeDef` counterpart (e.g.
+/// decimals) return [`ArrowConvertError::UnsupportedArrowType`].
+pub fn arrow_schema_to_field_defs(schema: &Schema) -> Result<FieldDefs, ArrowConvertError> {
+    let fields = schema
+        .fields()
+        .iter()
+        .map(|f| arrow_field_to_field_def(f))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(fields.into())
+}
+
+fn arrow_field_to_field_def(field: &Field) -> Result<FieldDef, ArrowConvertError> {
+    let mut def = FieldDef {
+        name: field.name().clone(),
+        element: arrow_field_to_element_def(field)?,
+        metadata: field
+            .metadata()
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect(),
+    };
+    match field.data_type() {
+        DataType::Timestamp(TimeUnit::Nanosecond, _) => {
+            def = def.with_metadata(TIMESTAMP_METADATA_KEY, "true");
+        }
+        DataType::FixedSizeBinary(width) => {
+            def = def.with_metadata(BYTE_WIDTH_METADATA_KEY, width.to_string());
+        }
+        _ => {}
+    }
+    Ok(def)
+}
+
+fn arrow_field_to_element_def(field: &Field) -> Result<ElementDef, ArrowConvertError> {
+    let data_type = match field.data_type() {
+        DataType::Null => DataTypeDef::Null,
+        DataType::Boolean => DataTypeDef::Bool,
+        DataType::Int8 => DataTypeDef::I8,
+        DataType::Int16 => DataTypeDef::I16,
+        DataType::Int32 => DataTypeDef::I32,
+        DataType::Int64 | DataType::Timestamp(TimeUnit::Nanosecond, _) => DataTypeDef::I64,
+        DataType::UInt8 => DataTypeDef::U8,
+        DataType::UInt16 => DataTypeDef::U16,
+        DataType::UInt32 => DataTypeDef::U32,
+        DataType::UInt64 => DataTypeDef::U64,
+        DataType::Float32 => DataTypeDef::F32,
+        DataType::Float64 => DataTypeDef::F64,
+        DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View => DataTypeDef::String,
+        DataType::Binary
+        | DataType::LargeBinary
+        | DataType::BinaryView
+        | DataType::FixedSizeBinary(_) => DataTypeDef::Bytes,
+        DataType::Struct(fields) => DataTypeDef::Struct(
+            fields
+                .iter()
+                .map(|f| arrow_field_to_field_def(f))
+                .collect::<Result<Vec<_>, _>>()?
+                .into(),
+        ),
+        DataType::List(item)
+        | DataType::LargeList(item)
+        | DataType::ListView(item)
+        | DataType::LargeListView(item) => {
+            DataTypeDef::List(Box::new(arrow_field_to_element_def(item)?))
+        }
+        DataType::FixedSizeList(item, size) => {
+            DataTypeDef::Array(Box::new(arrow_field_to_element_def(item)?), *size as usize)
+        }
+        DataType::Map(entries, _) => match entries.data_type() {
+            DataType::Struct(kv) if kv.len() == 2 => DataTypeDef::Map {
+                key: Box::new(arrow_field_to_element_def(&kv[0])?),
+                value: Box::new(arrow_field_to_element_def(&kv[1])?),
+            },
+            _ => return Err(unsupported(field)),
+        },
+        _ => return Err(unsupported(field)),
+    };
+    Ok(ElementDef::new(data_type, field.is_nullable()))
+}
+
+fn unsupported(field: &Field) -> ArrowConvertError {
+    ArrowConvertError::UnsupportedArrowType {
+        field: field.name().clone(),
+        data_type: field.data_type().clone(),
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Prepend log_time / publish_time timestamp columns to a schema
 // ---------------------------------------------------------------------------
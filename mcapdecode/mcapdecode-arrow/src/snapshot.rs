@@ -0,0 +1,170 @@
+//! Latest-value-per-key snapshots of keyed topics.
+
+use std::{
+    collections::{HashMap, hash_map::Entry},
+    time::Duration,
+};
+
+use arrow::{
+    array::{ArrayRef, TimestampNanosecondArray},
+    compute::interleave_record_batch,
+    error::ArrowError,
+    record_batch::RecordBatch,
+    row::{OwnedRow, RowConverter, SortField},
+};
+
+use crate::instance_key::{INSTANCE_KEY_COLUMN, is_key_field};
+
+const LOG_TIME_COLUMN: &str = "@log_time";
+
+/// Reduces a stream of batches to the last row of every key, either over the
+/// whole stream (the final state) or per `@log_time` bucket.
+///
+/// Key columns are the ones given to [`Self::with_key_columns`]; by default
+/// the [`INSTANCE_KEY_COLUMN`] if present, else the columns marked as keys
+/// (see [`is_key_field`]). Snapshot rows are ordered by the first appearance
+/// of their key in the bucket.
+///
+/// Rows are expected in `@log_time` order: a row older than the open bucket
+/// is folded into it.
+#[derive(Debug, Default)]
+pub struct LatestSnapshot {
+    key_columns: Option<Vec<String>>,
+    interval_ns: Option<i64>,
+    converter: Option<RowConverter>,
+    /// Latest row of each key of the open bucket.
+    state: Option<RecordBatch>,
+    /// Row of `state` holding each key.
+    index: HashMap<OwnedRow, usize>,
+    /// Start of the open bucket, in `interval_ns` units.
+    bucket: Option<i64>,
+}
+
+impl LatestSnapshot {
+    /// Keep the final state of every key.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Identify keys by the values of these top-level columns.
+    pub fn with_key_columns(mut self, columns: Vec<String>) -> Self {
+        self.key_columns = Some(columns);
+        self
+    }
+
+    /// Emit one snapshot per `interval` of `@log_time` instead of the final
+    /// state only.
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        let interval_ns = i64::try_from(interval.as_nanos()).unwrap_or(i64::MAX);
+        self.interval_ns = Some(interval_ns.max(1));
+        self
+    }
+
+    /// Fold `batch` into the snapshot, returning the snapshots of the
+    /// buckets it closes.
+    pub fn push(&mut self, batch: &RecordBatch) -> Result<Vec<RecordBatch>, ArrowError> {
+        let keys = self.key_arrays(batch)?;
+        let buckets = self.buckets(batch)?;
+        let converter = match &mut self.converter {
+            Some(converter) => converter,
+            None => self.converter.insert(RowConverter::new(
+                keys.iter()
+                    .map(|key| SortField::new(key.data_type().clone()))
+                    .collect(),
+            )?),
+        };
+        let rows = converter.convert_columns(&keys)?;
+
+        let state = self
+            .state
+            .take()
+            .unwrap_or_else(|| RecordBatch::new_empty(batch.schema()));
+        let sources = [&state, batch];
+        let mut slots: Vec<(usize, usize)> = (0..state.num_rows()).map(|row| (0, row)).collect();
+        let mut snapshots = Vec::new();
+        for row in 0..batch.num_rows() {
+            if let Some(bucket) = buckets.as_ref().and_then(|buckets| buckets[row]) {
+                match self.bucket {
+                    Some(open) if bucket > open => {
+                        snapshots.push(interleave_record_batch(&sources, &slots)?);
+                        slots.clear();
+                        self.index.clear();
+                        self.bucket = Some(bucket);
+                    }
+                    Some(_) => {}
+                    None => self.bucket = Some(bucket),
+                }
+            }
+            match self.index.entry(rows.row(row).owned()) {
+                Entry::Occupied(entry) => slots[*entry.get()] = (1, row),
+                Entry::Vacant(entry) => {
+                    entry.insert(slots.len());
+                    slots.push((1, row));
+                }
+            }
+        }
+        self.state = Some(interleave_record_batch(&sources, &slots)?);
+        Ok(snapshots)
+    }
+
+    /// Take the snapshot of the open bucket (the final state without an
+    /// interval), if it has any rows, and start over.
+    pub fn finish(&mut self) -> Option<RecordBatch> {
+        let state = self.state.take();
+        self.converter = None;
+        self.index.clear();
+        self.bucket = None;
+        state.filter(|state| state.num_rows() > 0)
+    }
+
+    fn key_arrays(&self, batch: &RecordBatch) -> Result<Vec<ArrayRef>, ArrowError> {
+        let schema = batch.schema();
+        let keys: Vec<ArrayRef> = match &self.key_columns {
+            Some(names) => names
+                .iter()
+                .map(|name| {
+                    batch.column_by_name(name).cloned().ok_or_else(|| {
+                        ArrowError::InvalidArgumentError(format!("key column '{name}' not found"))
+                    })
+                })
+                .collect::<Result<_, _>>()?,
+            None => match batch.column_by_name(INSTANCE_KEY_COLUMN) {
+                Some(column) => vec![column.clone()],
+                None => schema
+                    .fields()
+                    .iter()
+                    .zip(batch.columns())
+                    .filter(|(field, _)| is_key_field(field))
+                    .map(|(_, column)| column.clone())
+                    .collect(),
+            },
+        };
+        if keys.is_empty() {
+            return Err(ArrowError::InvalidArgumentError(
+                "no key columns: the schema marks no field as a key".to_string(),
+            ));
+        }
+        Ok(keys)
+    }
+
+    /// `@log_time` bucket of every row, or `None` without an interval.
+    fn buckets(&self, batch: &RecordBatch) -> Result<Option<Vec<Option<i64>>>, ArrowError> {
+        let Some(interval_ns) = self.interval_ns else {
+            return Ok(None);
+        };
+        let log_time = batch
+            .column_by_name(LOG_TIME_COLUMN)
+            .and_then(|column| column.as_any().downcast_ref::<TimestampNanosecondArray>())
+            .ok_or_else(|| {
+                ArrowError::InvalidArgumentError(format!(
+                    "snapshot interval requires a '{LOG_TIME_COLUMN}' timestamp column"
+                ))
+            })?;
+        Ok(Some(
+            log_time
+                .iter()
+                .map(|time| time.map(|t| t.div_euclid(interval_ns)))
+                .collect(),
+        ))
+    }
+}
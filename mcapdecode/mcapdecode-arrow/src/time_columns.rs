@@ -0,0 +1,159 @@
+//! Relative-time columns derived from `@log_time` and `@publish_time`.
+
+use std::{str::FromStr, sync::Arc};
+
+use arrow::{
+    array::{ArrayRef, Int64Array, TimestampNanosecondArray},
+    datatypes::{DataType, Field, Schema, TimeUnit},
+    error::ArrowError,
+    record_batch::RecordBatch,
+};
+use mcapdecode_core::elapsed_ns;
+
+/// Name of the column holding `@log_time` minus the recording start, in nanoseconds.
+pub const ELAPSED_NS_COLUMN: &str = "@elapsed_ns";
+
+/// Name of the column holding `@log_time - @publish_time`, in nanoseconds.
+pub const LATENCY_NS_COLUMN: &str = "@latency_ns";
+
+const TIMESTAMP_COLUMNS: [&str; 2] = ["@log_time", "@publish_time"];
+
+/// Which time columns [`apply_time_columns`] leaves in a `RecordBatch`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "kebab-case")
+)]
+pub enum TimeColumns {
+    /// Keep `@log_time` / `@publish_time` only.
+    #[default]
+    Absolute,
+    /// Replace `@log_time` / `@publish_time` with `@elapsed_ns`.
+    Elapsed,
+    /// Keep the absolute columns and append `@elapsed_ns` after them.
+    Both,
+}
+
+impl FromStr for TimeColumns {
+    type Err = String;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        let lower = raw.to_ascii_lowercase();
+        match lower.as_str() {
+            "absolute" => Ok(Self::Absolute),
+            "elapsed" => Ok(Self::Elapsed),
+            "both" => Ok(Self::Both),
+            _ => Err(format!(
+                "invalid time columns '{raw}' (expected: absolute, elapsed, both)"
+            )),
+        }
+    }
+}
+
+/// Add or substitute the `@elapsed_ns` column (`@log_time - start_ns`)
+/// according to `time_columns`.
+///
+/// `@elapsed_ns` is an `Int64` column; values beyond the `i64` range are null.
+/// Batches without a `@log_time` timestamp column are returned unchanged.
+pub fn apply_time_columns(
+    batch: &RecordBatch,
+    time_columns: TimeColumns,
+    start_ns: u64,
+) -> Result<RecordBatch, ArrowError> {
+    if time_columns == TimeColumns::Absolute {
+        return Ok(batch.clone());
+    }
+    let schema = batch.schema();
+    let Some(log_time) = schema.index_of(TIMESTAMP_COLUMNS[0]).ok().and_then(|idx| {
+        batch
+            .column(idx)
+            .as_any()
+            .downcast_ref::<TimestampNanosecondArray>()
+    }) else {
+        return Ok(batch.clone());
+    };
+
+    let elapsed: Int64Array = log_time
+        .iter()
+        .map(|time| time.and_then(|t| elapsed_ns(u64::try_from(t).ok()?, start_ns)))
+        .collect();
+    let elapsed_field = Field::new(ELAPSED_NS_COLUMN, DataType::Int64, true);
+
+    let mut fields = Vec::with_capacity(schema.fields().len() + 1);
+    let mut columns: Vec<ArrayRef> = Vec::with_capacity(fields.capacity());
+    let mut elapsed = Some((elapsed_field, Arc::new(elapsed) as ArrayRef));
+    for (field, column) in schema.fields().iter().zip(batch.columns()) {
+        let is_timestamp = TIMESTAMP_COLUMNS.contains(&field.name().as_str())
+            && matches!(
+                field.data_type(),
+                DataType::Timestamp(TimeUnit::Nanosecond, _)
+            );
+        if !is_timestamp {
+            // Place `@elapsed_ns` where the leading timestamp columns end.
+            if let Some((field, column)) = elapsed.take() {
+                fields.push(Arc::new(field));
+                columns.push(column);
+            }
+        }
+        if !is_timestamp || time_columns == TimeColumns::Both {
+            fields.push(field.clone());
+            columns.push(column.clone());
+        }
+    }
+    if let Some((field, column)) = elapsed {
+        fields.push(Arc::new(field));
+        columns.push(column);
+    }
+
+    RecordBatch::try_new(
+        Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone())),
+        columns,
+    )
+}
+
+/// Insert the `@latency_ns` column (`@log_time - @publish_time`) after the
+/// leading timestamp columns.
+///
+/// `@latency_ns` is an `Int64` column, negative when the publish time is
+/// later than the log time; differences beyond the `i64` range are null.
+/// Batches without both timestamp columns are returned unchanged.
+pub fn with_latency_column(batch: &RecordBatch) -> Result<RecordBatch, ArrowError> {
+    let timestamp = |name: &str| {
+        batch
+            .column_by_name(name)?
+            .as_any()
+            .downcast_ref::<TimestampNanosecondArray>()
+    };
+    let (Some(log_time), Some(publish_time)) = (
+        timestamp(TIMESTAMP_COLUMNS[0]),
+        timestamp(TIMESTAMP_COLUMNS[1]),
+    ) else {
+        return Ok(batch.clone());
+    };
+
+    let latency: Int64Array = log_time
+        .iter()
+        .zip(publish_time.iter())
+        .map(|(log, publish)| log?.checked_sub(publish?))
+        .collect();
+
+    let schema = batch.schema();
+    let position = schema
+        .fields()
+        .iter()
+        .position(|field| !TIMESTAMP_COLUMNS.contains(&field.name().as_str()))
+        .unwrap_or(schema.fields().len());
+    let mut fields: Vec<_> = schema.fields().iter().cloned().collect();
+    let mut columns = batch.columns().to_vec();
+    fields.insert(
+        position,
+        Arc::new(Field::new(LATENCY_NS_COLUMN, DataType::Int64, true)),
+    );
+    columns.insert(position, Arc::new(latency));
+
+    RecordBatch::try_new(
+        Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone())),
+        columns,
+    )
+}
@@ -0,0 +1,41 @@
+//! Rendering of UUID columns as canonical strings.
+
+use std::sync::Arc;
+
+use arrow::{
+    array::{FixedSizeBinaryArray, StringArray},
+    datatypes::{DataType, Field},
+    error::ArrowError,
+    record_batch::RecordBatch,
+};
+use mcapdecode_core::{UUID_METADATA_KEY, format_uuid};
+
+use crate::nested::{downcast, rewrite_fields};
+
+/// Replace every `FixedSizeBinary(16)` column marked with
+/// [`UUID_METADATA_KEY`] with a `Utf8` column of hyphenated UUIDs
+/// (`xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx`), e.g. the goal IDs of ROS 2
+/// action topics decoded with `UuidRepresentation::Binary`.
+///
+/// UUIDs nested in structs and lists are rendered too; the field metadata
+/// is kept. Batches without UUID columns are returned unchanged.
+pub fn render_uuid_columns(batch: &RecordBatch) -> Result<RecordBatch, ArrowError> {
+    rewrite_fields(batch, &is_uuid_field, &|field, array| {
+        let strings: StringArray = downcast::<FixedSizeBinaryArray>(array)?
+            .iter()
+            .map(|value| value.and_then(format_uuid))
+            .collect();
+        Ok((
+            Arc::new(field.as_ref().clone().with_data_type(DataType::Utf8)),
+            Arc::new(strings),
+        ))
+    })
+}
+
+fn is_uuid_field(field: &Field) -> bool {
+    field.data_type() == &DataType::FixedSizeBinary(16)
+        && field
+            .metadata()
+            .get(UUID_METADATA_KEY)
+            .is_some_and(|v| v == "true")
+}
@@ -2,13 +2,16 @@ use std::sync::Arc;
 
 use arrow::{
     array::{
-        Array, FixedSizeListArray, Float32Array, Float64Array, Int32Array, ListArray, MapArray,
-        StringArray, StructArray, TimestampNanosecondArray,
+        Array, FixedSizeBinaryArray, FixedSizeListArray, Float32Array, Float64Array, Int32Array,
+        ListArray, MapArray, StringArray, StructArray, TimestampNanosecondArray,
     },
     datatypes::{DataType, Field, Schema},
 };
 use mcapdecode_arrow::{
-    ArrowConvertError, arrow_value_rows_to_record_batch, try_arrow_value_rows_to_record_batch,
+    ArrowConvertError, ConvertOptions, DuplicateMapKeys, StructMismatch, WideningPolicy,
+    arrow_value_rows_to_record_batch, try_arrow_value_rows_to_record_batch,
+    try_arrow_value_rows_to_record_batch_with_options,
+    try_arrow_value_rows_to_record_batch_with_report, with_sorted_map_keys,
 };
 use mcapdecode_core::{DecodedMessage, Value};
 
@@ -314,6 +317,66 @@ fn fixed_size_list_non_array_returns_error_in_try_api() {
     );
 }
 
+#[test]
+fn fixed_size_binary_columns_check_value_length() {
+    let schema = Arc::new(Schema::new(vec![Field::new(
+        "uuid",
+        DataType::FixedSizeBinary(4),
+        true,
+    )]));
+
+    let rows = vec![
+        make_row(
+            1,
+            1,
+            Value::Struct(vec![Value::Bytes(vec![1, 2, 3, 4].into())]),
+        ),
+        make_row(2, 2, Value::Struct(vec![Value::Null])),
+    ];
+    let batch = try_arrow_value_rows_to_record_batch(&schema, &rows).unwrap();
+    let col = batch
+        .column(2)
+        .as_any()
+        .downcast_ref::<FixedSizeBinaryArray>()
+        .unwrap();
+    assert_eq!(col.value(0), &[1, 2, 3, 4]);
+    assert!(col.is_null(1));
+
+    let rows = vec![make_row(
+        1,
+        1,
+        Value::Struct(vec![Value::Bytes(vec![1, 2].into())]),
+    )];
+    let err = try_arrow_value_rows_to_record_batch(&schema, &rows).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "value type mismatch: expected FixedSizeBinary(length=4), got Bytes(length=2)"
+    );
+}
+
+#[test]
+fn timestamp_beyond_i64_returns_error_in_try_api() {
+    let schema = Arc::new(Schema::new(vec![Field::new("x", DataType::Int32, true)]));
+    let rows = vec![make_row(
+        u64::MAX,
+        2_u64,
+        Value::Struct(vec![Value::I32(1)]),
+    )];
+
+    let err = try_arrow_value_rows_to_record_batch(&schema, &rows).unwrap_err();
+    assert!(matches!(
+        err,
+        ArrowConvertError::TimestampOutOfRange {
+            column: "@log_time",
+            value: u64::MAX
+        }
+    ));
+    assert_eq!(
+        err.to_string(),
+        "@log_time value 18446744073709551615 exceeds the i64 nanosecond range"
+    );
+}
+
 #[test]
 fn list_item_nullability_is_preserved() {
     let schema = Arc::new(Schema::new(vec![Field::new(
@@ -397,3 +460,330 @@ fn scalar_type_mismatch_returns_error_in_try_api() {
     assert!(matches!(err, ArrowConvertError::ValueType(_)));
     assert_eq!(err.to_string(), "value type mismatch: expected I8, got I16");
 }
+
+#[test]
+fn non_nullable_fields_have_no_validity_buffer() {
+    let cov = DataType::FixedSizeList(Arc::new(Field::new("item", DataType::Float64, false)), 2);
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("seq", DataType::Int32, false),
+        Field::new(
+            "pose",
+            DataType::Struct(
+                vec![
+                    Field::new("x", DataType::Float64, false),
+                    Field::new("cov", cov, false),
+                    Field::new("frame", DataType::Utf8, true),
+                ]
+                .into(),
+            ),
+            true,
+        ),
+    ]));
+    let rows = vec![
+        make_row(
+            1_u64,
+            1_u64,
+            Value::Struct(vec![
+                Value::I32(1),
+                Value::Struct(vec![
+                    Value::F64(0.5),
+                    Value::Array(vec![Value::F64(1.0), Value::F64(2.0)]),
+                    Value::string("map"),
+                ]),
+            ]),
+        ),
+        make_row(
+            2_u64,
+            2_u64,
+            Value::Struct(vec![Value::I32(2), Value::Null]),
+        ),
+    ];
+
+    let batch = arrow_value_rows_to_record_batch(&schema, &rows);
+    assert!(batch.column(2).nulls().is_none());
+    let pose = batch
+        .column(3)
+        .as_any()
+        .downcast_ref::<StructArray>()
+        .unwrap();
+    assert_eq!(pose.null_count(), 1);
+    assert!(pose.column(0).nulls().is_none());
+    let cov = pose
+        .column(1)
+        .as_any()
+        .downcast_ref::<FixedSizeListArray>()
+        .unwrap();
+    assert!(cov.nulls().is_none());
+    assert!(cov.values().nulls().is_none());
+    assert!(pose.column(2).is_null(1));
+}
+
+#[test]
+fn sorted_map_keys_orders_entries_by_key() {
+    let entries = Field::new(
+        "entries",
+        DataType::Struct(
+            vec![
+                Field::new("key", DataType::Utf8, false),
+                Field::new("value", DataType::Int32, true),
+            ]
+            .into(),
+        ),
+        false,
+    );
+    let schema = Schema::new(vec![Field::new(
+        "tags",
+        DataType::List(Arc::new(Field::new(
+            "item",
+            DataType::Map(Arc::new(entries), false),
+            true,
+        ))),
+        true,
+    )]);
+    let schema = Arc::new(with_sorted_map_keys(&schema));
+    let rows = vec![make_row(
+        1_u64,
+        1_u64,
+        Value::Struct(vec![Value::List(vec![Value::Map(vec![
+            (Value::string("b"), Value::I32(2)),
+            (Value::string("c"), Value::I32(3)),
+            (Value::string("a"), Value::I32(1)),
+        ])])]),
+    )];
+
+    let batch = arrow_value_rows_to_record_batch(&schema, &rows);
+    let list = batch
+        .column(2)
+        .as_any()
+        .downcast_ref::<ListArray>()
+        .unwrap();
+    let DataType::List(item) = list.data_type() else {
+        panic!("expected List, got {:?}", list.data_type());
+    };
+    assert!(matches!(item.data_type(), DataType::Map(_, true)));
+    let map = list.values().as_any().downcast_ref::<MapArray>().unwrap();
+    let keys = map.keys().as_any().downcast_ref::<StringArray>().unwrap();
+    let values = map.values().as_any().downcast_ref::<Int32Array>().unwrap();
+    assert_eq!(
+        keys.iter().collect::<Vec<_>>(),
+        [Some("a"), Some("b"), Some("c")]
+    );
+    assert_eq!(values.values().as_ref(), [1, 2, 3]);
+}
+
+#[test]
+fn duplicate_map_keys_follow_policy() {
+    let schema = Schema::new(vec![Field::new(
+        "counts",
+        DataType::Map(
+            Arc::new(Field::new(
+                "entries",
+                DataType::Struct(
+                    vec![
+                        Field::new("key", DataType::Utf8, false),
+                        Field::new("value", DataType::Int32, true),
+                    ]
+                    .into(),
+                ),
+                false,
+            )),
+            false,
+        ),
+        true,
+    )]);
+    let rows = vec![make_row(
+        1_u64,
+        1_u64,
+        Value::Struct(vec![Value::Map(vec![
+            (Value::string("a"), Value::I32(1)),
+            (Value::string("b"), Value::I32(2)),
+            (Value::string("a"), Value::I32(3)),
+        ])]),
+    )];
+    let convert = |duplicate_map_keys| {
+        let options = ConvertOptions {
+            duplicate_map_keys,
+            ..ConvertOptions::default()
+        };
+        try_arrow_value_rows_to_record_batch_with_options(&schema, &rows, &options)
+    };
+    let entries = |policy| {
+        let batch = convert(policy).unwrap();
+        let map = batch
+            .column(2)
+            .as_any()
+            .downcast_ref::<MapArray>()
+            .unwrap()
+            .clone();
+        let keys = map.keys().as_any().downcast_ref::<StringArray>().unwrap();
+        let values = map.values().as_any().downcast_ref::<Int32Array>().unwrap();
+        keys.iter()
+            .map(|k| k.unwrap().to_string())
+            .zip(values.values().iter().copied())
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        entries(DuplicateMapKeys::KeepFirst),
+        [("a".to_string(), 1), ("b".to_string(), 2)]
+    );
+    assert_eq!(
+        entries(DuplicateMapKeys::KeepLast),
+        [("b".to_string(), 2), ("a".to_string(), 3)]
+    );
+    let err = convert(DuplicateMapKeys::Error).unwrap_err();
+    assert!(matches!(err, ArrowConvertError::DuplicateMapKey { .. }));
+    assert!(err.to_string().contains("\"a\""), "{err}");
+
+    assert_eq!(
+        "keep-first".parse::<DuplicateMapKeys>().unwrap(),
+        DuplicateMapKeys::KeepFirst
+    );
+    assert_eq!(DuplicateMapKeys::default(), DuplicateMapKeys::KeepLast);
+    assert!("first".parse::<DuplicateMapKeys>().is_err());
+}
+
+#[test]
+fn lossless_widening_converts_narrower_numbers() {
+    let schema = Schema::new(vec![
+        Field::new("count", DataType::Int32, true),
+        Field::new("ratio", DataType::Float64, true),
+    ]);
+    let rows = vec![
+        make_row(1, 1, Value::Struct(vec![Value::U8(200), Value::F32(0.5)])),
+        make_row(2, 2, Value::Struct(vec![Value::I16(-3), Value::I32(7)])),
+    ];
+    let convert = |widening| {
+        let options = ConvertOptions {
+            widening,
+            ..ConvertOptions::default()
+        };
+        try_arrow_value_rows_to_record_batch_with_options(&schema, &rows, &options)
+    };
+
+    let err = convert(WideningPolicy::Exact).unwrap_err();
+    assert!(matches!(err, ArrowConvertError::ValueType(_)), "{err}");
+
+    let batch = convert(WideningPolicy::Lossless).unwrap();
+    let count = batch
+        .column(2)
+        .as_any()
+        .downcast_ref::<Int32Array>()
+        .unwrap();
+    assert_eq!(count.values().as_ref(), [200, -3]);
+    let ratio = batch
+        .column(3)
+        .as_any()
+        .downcast_ref::<Float64Array>()
+        .unwrap();
+    assert_eq!(ratio.values().as_ref(), [0.5, 7.0]);
+}
+
+#[test]
+fn lossless_widening_rejects_lossy_conversions() {
+    let options = ConvertOptions {
+        widening: WideningPolicy::Lossless,
+        ..ConvertOptions::default()
+    };
+    let lossy = [
+        (DataType::Int32, Value::I64(1)),
+        (DataType::Int64, Value::U64(1)),
+        (DataType::UInt32, Value::I8(1)),
+        (DataType::Float32, Value::I32(1)),
+        (DataType::Float32, Value::F64(1.0)),
+        (DataType::Int32, Value::F32(1.0)),
+    ];
+    for (data_type, value) in lossy {
+        let schema = Schema::new(vec![Field::new("v", data_type.clone(), true)]);
+        let rows = vec![make_row(1, 1, Value::Struct(vec![value.clone()]))];
+        let result = try_arrow_value_rows_to_record_batch_with_options(&schema, &rows, &options);
+        assert!(result.is_err(), "{value:?} into {data_type}");
+    }
+
+    assert_eq!(
+        "lossless".parse::<WideningPolicy>().unwrap(),
+        WideningPolicy::Lossless
+    );
+    assert_eq!(WideningPolicy::default(), WideningPolicy::Exact);
+    assert!("widen".parse::<WideningPolicy>().is_err());
+}
+
+#[test]
+fn struct_mismatch_aligns_fields_by_position() {
+    let point = DataType::Struct(
+        vec![
+            Field::new("x", DataType::Float32, true),
+            Field::new("y", DataType::Float32, true),
+        ]
+        .into(),
+    );
+    let schema = Schema::new(vec![
+        Field::new("id", DataType::Int32, false),
+        Field::new("point", point, true),
+    ]);
+    let rows = vec![
+        // A newer definition with an extra root field and point.z.
+        make_row(
+            1,
+            1,
+            Value::Struct(vec![
+                Value::I32(1),
+                Value::Struct(vec![Value::F32(1.0), Value::F32(2.0), Value::F32(3.0)]),
+                Value::String("extra".into()),
+            ]),
+        ),
+        // An older definition without point.y, and a row without any field.
+        make_row(
+            2,
+            2,
+            Value::Struct(vec![Value::I32(2), Value::Struct(vec![Value::F32(4.0)])]),
+        ),
+        make_row(3, 3, Value::Struct(vec![])),
+    ];
+
+    let err = try_arrow_value_rows_to_record_batch(&schema, &rows).unwrap_err();
+    assert!(
+        matches!(
+            err,
+            ArrowConvertError::StructFieldCount {
+                expected: 2,
+                actual: 3
+            }
+        ),
+        "{err}"
+    );
+
+    let options = ConvertOptions {
+        struct_mismatch: StructMismatch::Align,
+        ..ConvertOptions::default()
+    };
+    let (batch, report) =
+        try_arrow_value_rows_to_record_batch_with_report(&schema, &rows, &options).unwrap();
+    assert_eq!(report.aligned_structs, 4);
+    let id = batch
+        .column(2)
+        .as_any()
+        .downcast_ref::<Int32Array>()
+        .unwrap();
+    assert_eq!(id.values().as_ref(), [1, 2, 0]);
+    assert_eq!(id.null_count(), 0);
+    let point = batch
+        .column(3)
+        .as_any()
+        .downcast_ref::<StructArray>()
+        .unwrap();
+    assert!(point.is_valid(0) && point.is_valid(1) && point.is_null(2));
+    let y = point
+        .column(1)
+        .as_any()
+        .downcast_ref::<Float32Array>()
+        .unwrap();
+    assert_eq!(y.value(0), 2.0);
+    assert!(y.is_null(1));
+
+    assert_eq!(
+        "align".parse::<StructMismatch>().unwrap(),
+        StructMismatch::Align
+    );
+    assert!("pad".parse::<StructMismatch>().is_err());
+}
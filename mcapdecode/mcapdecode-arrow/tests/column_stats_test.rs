@@ -0,0 +1,164 @@
+use std::sync::Arc;
+
+use arrow::{
+    array::{
+        Array, ArrayRef, BinaryArray, Float64Array, Int32Array, ListArray, StringArray,
+        StructArray, TimestampNanosecondArray,
+    },
+    buffer::NullBuffer,
+    datatypes::{DataType, Field, Fields, Int32Type, Schema},
+    record_batch::RecordBatch,
+};
+use mcapdecode_arrow::{ColumnStats, ColumnStatsAccumulator, StatValue};
+
+fn batch(columns: Vec<(&str, ArrayRef)>) -> RecordBatch {
+    RecordBatch::try_from_iter(columns).unwrap()
+}
+
+fn column<'a>(stats: &'a [ColumnStats], name: &str) -> &'a ColumnStats {
+    stats.iter().find(|s| s.column == name).unwrap()
+}
+
+#[test]
+fn accumulates_min_max_nulls_and_distinct_across_batches() {
+    let mut acc = ColumnStatsAccumulator::new();
+    acc.update(&batch(vec![
+        (
+            "speed",
+            Arc::new(Float64Array::from(vec![Some(1.5), None, Some(f64::NAN)])),
+        ),
+        (
+            "label",
+            Arc::new(StringArray::from(vec![Some("b"), Some("a"), None])),
+        ),
+        (
+            "stamp",
+            Arc::new(TimestampNanosecondArray::from(vec![30, 10, 20]).with_timezone("+00:00")),
+        ),
+    ]));
+    acc.update(&batch(vec![
+        (
+            "speed",
+            Arc::new(Float64Array::from(vec![Some(-2.0), Some(1.5), Some(4.0)])),
+        ),
+        (
+            "label",
+            Arc::new(StringArray::from(vec![Some("c"), Some("a"), Some("b")])),
+        ),
+        (
+            "stamp",
+            Arc::new(TimestampNanosecondArray::from(vec![40, 50, 60]).with_timezone("+00:00")),
+        ),
+    ]));
+
+    let stats = acc.stats();
+    assert_eq!(
+        stats.iter().map(|s| s.column.as_str()).collect::<Vec<_>>(),
+        vec!["speed", "label", "stamp"]
+    );
+    assert_eq!(
+        column(&stats, "speed"),
+        &ColumnStats {
+            column: "speed".to_string(),
+            data_type: "Float64".to_string(),
+            rows: 6,
+            null_count: 1,
+            min: Some(StatValue::Float(-2.0)),
+            max: Some(StatValue::Float(4.0)),
+            distinct_estimate: Some(3),
+        }
+    );
+    let label = column(&stats, "label");
+    assert_eq!(
+        (
+            &label.min,
+            &label.max,
+            label.null_count,
+            label.distinct_estimate
+        ),
+        (
+            &Some(StatValue::String("a".to_string())),
+            &Some(StatValue::String("c".to_string())),
+            1,
+            Some(3)
+        )
+    );
+    let stamp = column(&stats, "stamp");
+    assert_eq!(
+        (&stamp.min, &stamp.max),
+        (&Some(StatValue::Int(10)), &Some(StatValue::Int(60)))
+    );
+}
+
+#[test]
+fn descends_into_structs_and_counts_nested_nulls() {
+    let fields = Fields::from(vec![
+        Field::new("x", DataType::Int32, false),
+        Field::new("raw", DataType::Binary, false),
+    ]);
+    let point = StructArray::new(
+        fields.clone(),
+        vec![
+            Arc::new(Int32Array::from(vec![1, 99, 3])),
+            Arc::new(BinaryArray::from(vec![b"a".as_slice(), b"b", b"a"])),
+        ],
+        Some(NullBuffer::from(vec![true, false, true])),
+    );
+    let samples = ListArray::from_iter_primitive::<Int32Type, _, _>(vec![
+        Some(vec![Some(1)]),
+        None,
+        Some(vec![]),
+    ]);
+    let schema = Schema::new(vec![
+        Field::new("point", DataType::Struct(fields), true),
+        Field::new("samples", samples.data_type().clone(), true),
+    ]);
+    let batch =
+        RecordBatch::try_new(Arc::new(schema), vec![Arc::new(point), Arc::new(samples)]).unwrap();
+
+    let mut acc = ColumnStatsAccumulator::new();
+    acc.update(&batch);
+    let stats = acc.stats();
+
+    // The value under the null struct is not counted.
+    let x = column(&stats, "point.x");
+    assert_eq!(
+        (x.null_count, &x.min, &x.max, x.distinct_estimate),
+        (
+            1,
+            &Some(StatValue::Int(1)),
+            &Some(StatValue::Int(3)),
+            Some(2)
+        )
+    );
+    let raw = column(&stats, "point.raw");
+    assert_eq!((&raw.min, raw.distinct_estimate), (&None, Some(1)));
+    let samples = column(&stats, "samples");
+    assert_eq!(
+        (
+            samples.rows,
+            samples.null_count,
+            &samples.min,
+            samples.distinct_estimate
+        ),
+        (3, 1, &None, None)
+    );
+    assert_eq!(
+        samples.to_string(),
+        "samples (List(Int32)): rows=3, nulls=1"
+    );
+}
+
+#[test]
+fn distinct_estimate_is_close_for_many_values() {
+    let mut acc = ColumnStatsAccumulator::new();
+    for chunk in 0..10 {
+        let values: Vec<i32> = (0..10_000).map(|i| chunk * 10_000 + i).collect();
+        acc.update(&batch(vec![("id", Arc::new(Int32Array::from(values)))]));
+    }
+    let estimate = acc.stats()[0].distinct_estimate.unwrap() as f64;
+    assert!(
+        (estimate - 100_000.0).abs() / 100_000.0 < 0.05,
+        "{estimate}"
+    );
+}
@@ -0,0 +1,102 @@
+use mcapdecode_arrow::{
+    ConversionOptions, ConversionOptionsError, FlattenPolicy, ListPolicy, StructMismatch,
+    TimeColumns,
+};
+
+#[test]
+fn presets_carry_flatten_policies() {
+    assert_eq!(
+        ConversionOptions::csv().build().unwrap().flatten(),
+        &FlattenPolicy::csv()
+    );
+    assert_eq!(
+        ConversionOptions::parquet().build().unwrap().flatten(),
+        &FlattenPolicy::parquet()
+    );
+    assert_eq!(
+        ConversionOptions::jsonl().build().unwrap(),
+        ConversionOptions::builder().build().unwrap()
+    );
+}
+
+#[test]
+fn builder_sets_options() {
+    let options = ConversionOptions::parquet()
+        .with_list_flatten_size(4)
+        .with_separator('/')
+        .with_time_columns(TimeColumns::Both)
+        .with_struct_mismatch(StructMismatch::Align)
+        .with_max_depth(3)
+        .build()
+        .unwrap();
+
+    assert_eq!(options.flatten().list, ListPolicy::FlattenFixed);
+    assert_eq!(options.flatten().list_flatten_fixed_size, 4);
+    assert_eq!(options.separator(), '/');
+    assert_eq!(options.time_columns(), TimeColumns::Both);
+    assert_eq!(
+        options.convert_options().struct_mismatch,
+        StructMismatch::Align
+    );
+    assert_eq!(options.max_depth(), Some(3));
+    assert_eq!(options.to_builder().build().unwrap(), options);
+}
+
+#[test]
+fn build_rejects_inconsistent_options() {
+    assert_eq!(
+        ConversionOptions::builder()
+            .with_list_flatten_size(0)
+            .build()
+            .unwrap_err(),
+        ConversionOptionsError::ZeroListFlattenSize
+    );
+    assert_eq!(
+        ConversionOptions::builder()
+            .with_separator('_')
+            .build()
+            .unwrap_err(),
+        ConversionOptionsError::InvalidSeparator('_')
+    );
+    assert_eq!(
+        ConversionOptions::builder()
+            .with_max_depth(0)
+            .build()
+            .unwrap_err(),
+        ConversionOptionsError::ZeroMaxDepth
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn options_round_trip_through_serde() {
+    let options = ConversionOptions::csv()
+        .with_separator('/')
+        .with_time_columns(TimeColumns::Elapsed)
+        .build()
+        .unwrap();
+    let json = serde_json::to_value(options).unwrap();
+    assert_eq!(json["flatten"]["struct"], "flatten");
+    assert_eq!(json["time-columns"], "elapsed");
+
+    let parsed: ConversionOptions = serde_json::from_value(json).unwrap();
+    assert_eq!(parsed, options);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn deserialized_options_are_defaulted_and_validated() {
+    let parsed: ConversionOptions =
+        serde_json::from_str(r#"{"flatten": {"list": "drop"}, "max-depth": 2}"#).unwrap();
+    assert_eq!(parsed.flatten().list, ListPolicy::Drop);
+    assert_eq!(parsed.flatten().struct_, FlattenPolicy::default().struct_);
+    assert_eq!(parsed.separator(), '.');
+    assert_eq!(parsed.max_depth(), Some(2));
+
+    let err = serde_json::from_str::<ConversionOptions>(r#"{"separator": "a"}"#).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("invalid column path separator 'a'")
+    );
+    assert!(serde_json::from_str::<ConversionOptions>(r#"{"unknown": 1}"#).is_err());
+}
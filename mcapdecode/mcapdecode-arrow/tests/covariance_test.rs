@@ -0,0 +1,98 @@
+use std::sync::Arc;
+
+use arrow::{
+    array::{Array, FixedSizeListArray, Float64Array, StructArray},
+    buffer::NullBuffer,
+    datatypes::{DataType, Field, Schema},
+    record_batch::RecordBatch,
+};
+use mcapdecode_arrow::{CovarianceColumns, is_covariance_field, name_covariance_columns};
+
+fn covariance_field(name: &str, size: i32) -> Field {
+    Field::new(
+        name,
+        DataType::FixedSizeList(Arc::new(Field::new("item", DataType::Float64, false)), size),
+        true,
+    )
+}
+
+fn covariances(size: i32, rows: usize, nulls: Option<NullBuffer>) -> FixedSizeListArray {
+    let values = Float64Array::from_iter_values((0..size as usize * rows).map(|v| v as f64));
+    FixedSizeListArray::new(
+        Arc::new(Field::new("item", DataType::Float64, false)),
+        size,
+        Arc::new(values),
+        nulls,
+    )
+}
+
+fn element<'a>(st: &'a StructArray, name: &str) -> &'a Float64Array {
+    st.column_by_name(name)
+        .unwrap()
+        .as_any()
+        .downcast_ref::<Float64Array>()
+        .unwrap()
+}
+
+#[test]
+fn is_covariance_field_checks_name_and_shape() {
+    assert!(is_covariance_field(&covariance_field("covariance", 36)));
+    assert!(is_covariance_field(&covariance_field(
+        "orientation_covariance",
+        9
+    )));
+    assert!(!is_covariance_field(&covariance_field("covariance", 16)));
+    assert!(!is_covariance_field(&covariance_field("values", 36)));
+}
+
+#[test]
+fn name_covariance_columns_names_every_element() {
+    let schema = Arc::new(Schema::new(vec![covariance_field("covariance", 36)]));
+    let batch = RecordBatch::try_new(
+        schema,
+        vec![Arc::new(covariances(
+            36,
+            2,
+            Some(NullBuffer::from(vec![true, false])),
+        ))],
+    )
+    .unwrap();
+
+    let named = name_covariance_columns(&batch, CovarianceColumns::Named).unwrap();
+    let cov = named
+        .column(0)
+        .as_any()
+        .downcast_ref::<StructArray>()
+        .unwrap();
+    assert_eq!(cov.num_columns(), 36);
+    assert_eq!(
+        cov.column_names()[..7],
+        ["xx", "xy", "xz", "xrx", "xry", "xrz", "yx"]
+    );
+    assert_eq!(element(cov, "xy").value(0), 1.0);
+    assert_eq!(element(cov, "rxrz").value(0), 23.0);
+    assert_eq!(element(cov, "rzrz").value(0), 35.0);
+    assert!(cov.is_null(1));
+    assert!(element(cov, "xx").is_null(1));
+}
+
+#[test]
+fn name_covariance_columns_keeps_diagonal_of_sliced_batches() {
+    let schema = Arc::new(Schema::new(vec![covariance_field(
+        "position_covariance",
+        9,
+    )]));
+    let batch = RecordBatch::try_new(schema, vec![Arc::new(covariances(9, 2, None))])
+        .unwrap()
+        .slice(1, 1);
+
+    let diagonal = name_covariance_columns(&batch, CovarianceColumns::Diagonal).unwrap();
+    let cov = diagonal
+        .column(0)
+        .as_any()
+        .downcast_ref::<StructArray>()
+        .unwrap();
+    assert_eq!(cov.column_names(), ["xx", "yy", "zz"]);
+    assert_eq!(element(cov, "xx").value(0), 9.0);
+    assert_eq!(element(cov, "zz").value(0), 17.0);
+}
@@ -0,0 +1,166 @@
+use std::{collections::HashMap, sync::Arc};
+
+use arrow::{
+    array::{ArrayRef, FixedSizeBinaryArray, Float64Array, Int32Array, StructArray, UInt32Array},
+    datatypes::{DataType, Field, Fields, Schema},
+    record_batch::RecordBatch,
+};
+use mcapdecode_arrow::{
+    EXTENSION_NAME_KEY, GEO_POINT_EXTENSION, QUATERNION_EXTENSION, TIME_EXTENSION, UUID_EXTENSION,
+    add_extension_types, extension_type_name, with_extension_types,
+};
+use mcapdecode_core::{TIMESTAMP_METADATA_KEY, UUID_METADATA_KEY};
+
+fn marked(field: Field, key: &str) -> Field {
+    field.with_metadata(HashMap::from([(key.to_string(), "true".to_string())]))
+}
+
+fn float_struct(name: &str, children: &[&str]) -> Field {
+    let fields: Fields = children
+        .iter()
+        .map(|child| Field::new(*child, DataType::Float64, false))
+        .collect();
+    Field::new(name, DataType::Struct(fields), false)
+}
+
+fn time_field(name: &str) -> Field {
+    let fields = Fields::from(vec![
+        Field::new("sec", DataType::Int32, false),
+        Field::new("nanosec", DataType::UInt32, false),
+    ]);
+    Field::new(name, DataType::Struct(fields), false)
+}
+
+fn extension(field: &Field) -> Option<&str> {
+    field.metadata().get(EXTENSION_NAME_KEY).map(String::as_str)
+}
+
+#[test]
+fn extension_type_name_recognizes_semantic_fields() {
+    let uuid = marked(
+        Field::new("goal_id", DataType::FixedSizeBinary(16), false),
+        UUID_METADATA_KEY,
+    );
+    assert_eq!(extension_type_name(&uuid), Some(UUID_EXTENSION));
+    assert_eq!(
+        extension_type_name(&float_struct("orientation", &["x", "y", "z", "w"])),
+        Some(QUATERNION_EXTENSION)
+    );
+    assert_eq!(
+        extension_type_name(&float_struct("position", &["latitude", "longitude"])),
+        Some(GEO_POINT_EXTENSION)
+    );
+    assert_eq!(
+        extension_type_name(&float_struct(
+            "position",
+            &["latitude", "longitude", "altitude"]
+        )),
+        Some(GEO_POINT_EXTENSION)
+    );
+    assert_eq!(
+        extension_type_name(&marked(time_field("stamp"), TIMESTAMP_METADATA_KEY)),
+        Some(TIME_EXTENSION)
+    );
+
+    // Same shapes without the markers, or with other fields.
+    assert_eq!(
+        extension_type_name(&Field::new("raw", DataType::FixedSizeBinary(16), false)),
+        None
+    );
+    assert_eq!(extension_type_name(&time_field("elapsed")), None);
+    assert_eq!(
+        extension_type_name(&float_struct("point", &["x", "y", "z"])),
+        None
+    );
+    assert_eq!(
+        extension_type_name(&float_struct("fix", &["latitude", "longitude", "status"])),
+        None
+    );
+}
+
+#[test]
+fn with_extension_types_tags_nested_fields_and_keeps_metadata() {
+    let pose = Field::new(
+        "pose",
+        DataType::Struct(Fields::from(vec![
+            float_struct("position", &["x", "y", "z"]),
+            float_struct("orientation", &["x", "y", "z", "w"]),
+        ])),
+        false,
+    );
+    let stamps = Field::new(
+        "stamps",
+        DataType::List(Arc::new(marked(time_field("item"), TIMESTAMP_METADATA_KEY))),
+        false,
+    );
+    let schema = Schema::new_with_metadata(
+        vec![pose, stamps],
+        HashMap::from([("source".to_string(), "test".to_string())]),
+    );
+
+    let tagged = with_extension_types(&schema);
+    assert_eq!(tagged.metadata(), schema.metadata());
+    let DataType::Struct(pose) = tagged.field(0).data_type() else {
+        panic!("pose should stay a struct");
+    };
+    assert_eq!(extension(&pose[0]), None);
+    assert_eq!(extension(&pose[1]), Some(QUATERNION_EXTENSION));
+    let DataType::List(item) = tagged.field(1).data_type() else {
+        panic!("stamps should stay a list");
+    };
+    assert_eq!(extension(item), Some(TIME_EXTENSION));
+    assert_eq!(
+        item.metadata()
+            .get(TIMESTAMP_METADATA_KEY)
+            .map(String::as_str),
+        Some("true")
+    );
+}
+
+#[test]
+fn add_extension_types_keeps_columns() {
+    let uuid = marked(
+        Field::new("goal_id", DataType::FixedSizeBinary(16), false),
+        UUID_METADATA_KEY,
+    );
+    let stamp = marked(time_field("stamp"), TIMESTAMP_METADATA_KEY);
+    let DataType::Struct(stamp_fields) = stamp.data_type().clone() else {
+        unreachable!();
+    };
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(FixedSizeBinaryArray::try_from_iter(std::iter::once([7u8; 16])).unwrap()),
+        Arc::new(StructArray::new(
+            stamp_fields,
+            vec![
+                Arc::new(Int32Array::from(vec![10])),
+                Arc::new(UInt32Array::from(vec![20])),
+            ],
+            None,
+        )),
+        Arc::new(Float64Array::from(vec![1.5])),
+    ];
+    let schema = Schema::new(vec![
+        uuid,
+        stamp,
+        Field::new("range", DataType::Float64, false),
+    ]);
+    let batch = RecordBatch::try_new(Arc::new(schema), columns).unwrap();
+
+    let tagged = add_extension_types(&batch).unwrap();
+    let schema = tagged.schema();
+    assert_eq!(extension(schema.field(0)), Some(UUID_EXTENSION));
+    assert_eq!(extension(schema.field(1)), Some(TIME_EXTENSION));
+    assert_eq!(extension(schema.field(2)), None);
+    assert_eq!(tagged.columns(), batch.columns());
+
+    let untouched = RecordBatch::try_new(
+        Arc::new(Schema::new(vec![Field::new(
+            "range",
+            DataType::Float64,
+            false,
+        )])),
+        vec![Arc::new(Float64Array::from(vec![1.5]))],
+    )
+    .unwrap();
+    assert_eq!(add_extension_types(&untouched).unwrap(), untouched);
+}
@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use arrow::{
     array::{
@@ -11,7 +11,8 @@ use arrow::{
     record_batch::RecordBatch,
 };
 use mcapdecode_arrow::{
-    ArrayPolicy, FlattenPolicy, ListPolicy, MapPolicy, StructPolicy, flatten_record_batch,
+    ArrayPolicy, FlattenPolicy, ListPolicy, ListPolicySpec, MapPolicy, StructPolicy,
+    flatten_record_batch,
 };
 
 fn make_batch(fields: Vec<Field>, arrays: Vec<ArrayRef>) -> RecordBatch {
@@ -51,6 +52,27 @@ fn parse_list_policy_from_str() {
     assert!("flatten-fixed:3".parse::<ListPolicy>().is_err());
 }
 
+#[test]
+fn parse_list_policy_spec_with_size_shorthand() {
+    assert_eq!(
+        "flatten-fixed:24".parse::<ListPolicySpec>().unwrap(),
+        ListPolicySpec {
+            policy: ListPolicy::FlattenFixed,
+            flatten_fixed_size: Some(24),
+        }
+    );
+    assert_eq!(
+        "keep".parse::<ListPolicySpec>().unwrap(),
+        ListPolicySpec {
+            policy: ListPolicy::Keep,
+            flatten_fixed_size: None,
+        }
+    );
+    assert!("flatten-fixed:0".parse::<ListPolicySpec>().is_err());
+    assert!("flatten-fixed:abc".parse::<ListPolicySpec>().is_err());
+    assert!("drop:3".parse::<ListPolicySpec>().is_err());
+}
+
 #[test]
 fn parse_array_and_map_policy_from_str() {
     assert_eq!("drop".parse::<ArrayPolicy>().unwrap(), ArrayPolicy::Drop);
@@ -784,3 +806,40 @@ fn collision_returns_error() {
     );
     assert!(err.to_string().contains("s.a"));
 }
+
+// Field metadata (e.g. units) survives flattening, including expanded elements.
+#[test]
+fn flatten_keeps_field_metadata() {
+    let unit = HashMap::from([("unit".to_string(), "m/s".to_string())]);
+    let (sf, struct_arr) = make_struct(vec![(
+        Field::new("v", DataType::Int32, false).with_metadata(unit.clone()),
+        Arc::new(Int32Array::from(vec![1, 2])) as ArrayRef,
+    )]);
+    let sf = Field::new("s", sf.data_type().clone(), false);
+    let item_field = Arc::new(Field::new("item", DataType::Int32, true));
+    let list_array = ListArray::new(
+        item_field.clone(),
+        OffsetBuffer::new(vec![0i32, 1, 2].into()),
+        Arc::new(Int32Array::from(vec![3, 4])),
+        None,
+    );
+    let batch = make_batch(
+        vec![
+            sf,
+            Field::new("lst", DataType::List(item_field), false).with_metadata(unit.clone()),
+        ],
+        vec![struct_arr, Arc::new(list_array) as ArrayRef],
+    );
+    let policy = FlattenPolicy {
+        list: ListPolicy::FlattenFixed,
+        list_flatten_fixed_size: 1,
+        ..drop_all()
+    };
+
+    let (flat, _) = flatten_record_batch(&batch, None, &policy).unwrap();
+
+    let schema = flat.schema();
+    let names: Vec<_> = schema.fields().iter().map(|f| f.name().as_str()).collect();
+    assert_eq!(names, vec!["s.v", "lst.0"]);
+    assert!(schema.fields().iter().all(|f| f.metadata() == &unit));
+}
@@ -0,0 +1,55 @@
+use std::{collections::HashMap, sync::Arc};
+
+use arrow::{
+    array::{Array, Float64Array, StringArray, UInt32Array, UInt64Array},
+    datatypes::{DataType, Field, Schema},
+    record_batch::RecordBatch,
+};
+use mcapdecode_arrow::instance_key_column;
+use mcapdecode_core::KEY_METADATA_KEY;
+
+fn key_field(name: &str, data_type: DataType) -> Field {
+    Field::new(name, data_type, false).with_metadata(HashMap::from([(
+        KEY_METADATA_KEY.to_string(),
+        "true".to_string(),
+    )]))
+}
+
+#[test]
+fn instance_key_column_hashes_key_fields_only() {
+    let schema = Schema::new(vec![
+        key_field("id", DataType::UInt32),
+        key_field("sensor", DataType::Utf8),
+        Field::new("value", DataType::Float64, false),
+    ]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(UInt32Array::from(vec![1, 1, 2, 1])),
+            Arc::new(StringArray::from(vec!["a", "a", "a", "b"])),
+            Arc::new(Float64Array::from(vec![0.5, 1.5, 2.5, 3.5])),
+        ],
+    )
+    .unwrap();
+
+    let keys = instance_key_column(&batch).unwrap().expect("key column");
+    let keys = keys.as_any().downcast_ref::<UInt64Array>().unwrap();
+    assert_eq!(keys.len(), 4);
+    assert_eq!(keys.null_count(), 0);
+    assert_eq!(keys.value(0), keys.value(1));
+    assert_ne!(keys.value(0), keys.value(2));
+    assert_ne!(keys.value(0), keys.value(3));
+    assert_ne!(keys.value(2), keys.value(3));
+}
+
+#[test]
+fn instance_key_column_is_none_without_key_fields() {
+    let schema = Schema::new(vec![Field::new("value", DataType::Float64, false)]);
+    let batch = RecordBatch::try_new(
+        Arc::new(schema),
+        vec![Arc::new(Float64Array::from(vec![1.0]))],
+    )
+    .unwrap();
+
+    assert!(instance_key_column(&batch).unwrap().is_none());
+}
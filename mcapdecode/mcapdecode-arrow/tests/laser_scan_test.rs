@@ -0,0 +1,128 @@
+use std::{f32::consts::FRAC_PI_2, sync::Arc};
+
+use arrow::{
+    array::{Array, Float32Array, Int32Array, ListArray, StructArray},
+    datatypes::{DataType, Field, Float32Type, Schema},
+    record_batch::RecordBatch,
+};
+use mcapdecode_arrow::{
+    LaserScanPoints, POINTS_COLUMN, is_laser_scan_schema, laser_scan_to_points,
+};
+
+fn f32_field(name: &str) -> Field {
+    Field::new(name, DataType::Float32, false)
+}
+
+fn scan_batch(ranges: Vec<Option<Vec<Option<f32>>>>) -> RecordBatch {
+    let rows = ranges.len();
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("seq", DataType::Int32, false),
+        f32_field("angle_min"),
+        f32_field("angle_increment"),
+        f32_field("range_min"),
+        f32_field("range_max"),
+        Field::new(
+            "ranges",
+            DataType::List(Arc::new(Field::new("item", DataType::Float32, true))),
+            true,
+        ),
+    ]));
+    let constant = |v: f32| Arc::new(Float32Array::from(vec![v; rows]));
+    RecordBatch::try_new(
+        schema,
+        vec![
+            Arc::new(Int32Array::from_iter_values(0..rows as i32)),
+            constant(0.0),
+            constant(FRAC_PI_2),
+            constant(0.1),
+            constant(10.0),
+            Arc::new(ListArray::from_iter_primitive::<Float32Type, _, _>(ranges)),
+        ],
+    )
+    .unwrap()
+}
+
+fn f32_values(array: &dyn Array) -> Vec<f32> {
+    array
+        .as_any()
+        .downcast_ref::<Float32Array>()
+        .unwrap()
+        .values()
+        .to_vec()
+}
+
+fn assert_close(actual: &[f32], expected: &[f32]) {
+    assert_eq!(actual.len(), expected.len(), "{actual:?} != {expected:?}");
+    for (a, e) in actual.iter().zip(expected) {
+        assert!((a - e).abs() < 1e-5, "{actual:?} != {expected:?}");
+    }
+}
+
+#[test]
+fn is_laser_scan_schema_requires_angles_and_ranges() {
+    assert!(is_laser_scan_schema(&scan_batch(vec![]).schema()));
+    assert!(!is_laser_scan_schema(&Schema::new(vec![f32_field(
+        "angle_min"
+    )])));
+}
+
+#[test]
+fn laser_scan_to_points_appends_point_lists() {
+    let batch = scan_batch(vec![
+        // 0.05 is below range_min and NaN is not finite.
+        Some(vec![Some(1.0), Some(2.0), Some(0.05), Some(f32::NAN)]),
+        None,
+    ]);
+
+    let points = laser_scan_to_points(&batch, LaserScanPoints::List).unwrap();
+    assert_eq!(points.num_columns(), batch.num_columns() + 1);
+    let list = points
+        .column_by_name(POINTS_COLUMN)
+        .unwrap()
+        .as_any()
+        .downcast_ref::<ListArray>()
+        .unwrap();
+    assert_eq!(list.value_length(0), 2);
+    assert!(list.is_null(1));
+    let first = list.value(0);
+    let first = first.as_any().downcast_ref::<StructArray>().unwrap();
+    assert_close(&f32_values(first.column(0)), &[1.0, 0.0]);
+    assert_close(&f32_values(first.column(1)), &[0.0, 2.0]);
+}
+
+#[test]
+fn laser_scan_to_points_explodes_rows() {
+    let batch = scan_batch(vec![
+        Some(vec![Some(1.0), Some(11.0)]),
+        Some(vec![Some(3.0), Some(1.0)]),
+    ]);
+
+    let points = laser_scan_to_points(&batch, LaserScanPoints::Explode).unwrap();
+    assert_eq!(points.num_rows(), 3);
+    assert!(points.column_by_name("ranges").is_none());
+    let seq = points
+        .column_by_name("seq")
+        .unwrap()
+        .as_any()
+        .downcast_ref::<Int32Array>()
+        .unwrap();
+    assert_eq!(seq.values().to_vec(), [0, 1, 1]);
+    assert_close(
+        &f32_values(points.column_by_name("x").unwrap()),
+        &[1.0, 3.0, 0.0],
+    );
+    assert_close(
+        &f32_values(points.column_by_name("y").unwrap()),
+        &[0.0, 0.0, 1.0],
+    );
+}
+
+#[test]
+fn laser_scan_to_points_leaves_other_batches_unchanged() {
+    let schema = Arc::new(Schema::new(vec![Field::new("x", DataType::Int32, false)]));
+    let batch = RecordBatch::try_new(schema, vec![Arc::new(Int32Array::from(vec![1]))]).unwrap();
+    assert_eq!(
+        laser_scan_to_points(&batch, LaserScanPoints::Explode).unwrap(),
+        batch
+    );
+}
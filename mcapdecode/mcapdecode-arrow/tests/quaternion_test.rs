@@ -0,0 +1,199 @@
+use std::{f64::consts::FRAC_PI_2, sync::Arc};
+
+use arrow::{
+    array::{Array, Float64Array, Int32Array, ListArray, StructArray},
+    buffer::{NullBuffer, OffsetBuffer},
+    datatypes::{DataType, Field, Fields, Schema},
+    record_batch::RecordBatch,
+};
+use mcapdecode_arrow::{QuaternionColumns, add_quaternion_columns, is_quaternion_field};
+use mcapdecode_core::UNIT_METADATA_KEY;
+
+fn quaternion_fields() -> Fields {
+    ["x", "y", "z", "w"]
+        .into_iter()
+        .map(|name| Field::new(name, DataType::Float64, true))
+        .collect()
+}
+
+fn quaternions(values: &[[f64; 4]], nulls: Option<NullBuffer>) -> StructArray {
+    let columns = (0..4)
+        .map(|k| {
+            Arc::new(Float64Array::from(
+                values.iter().map(|q| q[k]).collect::<Vec<_>>(),
+            )) as _
+        })
+        .collect();
+    StructArray::new(quaternion_fields(), columns, nulls)
+}
+
+fn orientation_batch(values: &[[f64; 4]], nulls: Option<NullBuffer>) -> RecordBatch {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("orientation", DataType::Struct(quaternion_fields()), true),
+        Field::new("seq", DataType::Int32, false),
+    ]));
+    let seq = Int32Array::from((0..values.len() as i32).collect::<Vec<_>>());
+    RecordBatch::try_new(
+        schema,
+        vec![Arc::new(quaternions(values, nulls)), Arc::new(seq)],
+    )
+    .unwrap()
+}
+
+fn child<'a>(st: &'a StructArray, name: &str) -> &'a Float64Array {
+    st.column_by_name(name)
+        .unwrap()
+        .as_any()
+        .downcast_ref::<Float64Array>()
+        .unwrap()
+}
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!(
+        (actual - expected).abs() < 1e-9,
+        "expected {expected}, got {actual}"
+    );
+}
+
+#[test]
+fn is_quaternion_field_requires_exactly_float64_xyzw() {
+    assert!(is_quaternion_field(&Field::new(
+        "q",
+        DataType::Struct(quaternion_fields()),
+        false
+    )));
+    let vector: Fields = ["x", "y", "z"]
+        .into_iter()
+        .map(|name| Field::new(name, DataType::Float64, false))
+        .collect();
+    assert!(!is_quaternion_field(&Field::new(
+        "v",
+        DataType::Struct(vector),
+        false
+    )));
+    let float32: Fields = ["x", "y", "z", "w"]
+        .into_iter()
+        .map(|name| Field::new(name, DataType::Float32, false))
+        .collect();
+    assert!(!is_quaternion_field(&Field::new(
+        "q",
+        DataType::Struct(float32),
+        false
+    )));
+}
+
+#[test]
+fn add_quaternion_columns_appends_euler_angles() {
+    let half = 0.5_f64.sqrt();
+    let batch = orientation_batch(
+        &[
+            [0.0, 0.0, 0.0, 1.0],
+            // 90 degrees about Z, not normalized.
+            [0.0, 0.0, 2.0 * half, 2.0 * half],
+            [0.0, 0.0, 0.0, 0.0],
+        ],
+        None,
+    );
+
+    let derived = add_quaternion_columns(&batch, QuaternionColumns::Euler).unwrap();
+    let schema = derived.schema();
+    let DataType::Struct(fields) = schema.field(0).data_type() else {
+        panic!("orientation is not a struct");
+    };
+    let names: Vec<_> = fields.iter().map(|f| f.name().as_str()).collect();
+    assert_eq!(names, ["x", "y", "z", "w", "roll", "pitch", "yaw"]);
+    assert_eq!(
+        fields[4]
+            .metadata()
+            .get(UNIT_METADATA_KEY)
+            .map(String::as_str),
+        Some("rad")
+    );
+    assert_eq!(schema.field(1).data_type(), &DataType::Int32);
+
+    let orientation = derived
+        .column(0)
+        .as_any()
+        .downcast_ref::<StructArray>()
+        .unwrap();
+    let yaw = child(orientation, "yaw");
+    assert_close(yaw.value(0), 0.0);
+    assert_close(yaw.value(1), FRAC_PI_2);
+    assert_close(child(orientation, "roll").value(1), 0.0);
+    assert!(yaw.is_null(2));
+}
+
+#[test]
+fn add_quaternion_columns_converts_degrees_and_rotation_matrices() {
+    // 90 degrees about X.
+    let half = 0.5_f64.sqrt();
+    let batch = orientation_batch(
+        &[[half, 0.0, 0.0, half], [0.0, 0.0, 0.0, 1.0]],
+        Some(NullBuffer::from(vec![true, false])),
+    );
+
+    let degrees = add_quaternion_columns(&batch, QuaternionColumns::EulerDegrees).unwrap();
+    let orientation = degrees
+        .column(0)
+        .as_any()
+        .downcast_ref::<StructArray>()
+        .unwrap();
+    assert_close(child(orientation, "roll").value(0), 90.0);
+    assert!(orientation.is_null(1));
+    assert!(child(orientation, "roll").is_null(1));
+
+    let matrix = add_quaternion_columns(&batch, QuaternionColumns::RotationMatrix).unwrap();
+    let orientation = matrix
+        .column(0)
+        .as_any()
+        .downcast_ref::<StructArray>()
+        .unwrap();
+    assert_eq!(orientation.num_columns(), 13);
+    for (name, expected) in [("r00", 1.0), ("r11", 0.0), ("r12", -1.0), ("r21", 1.0)] {
+        assert_close(child(orientation, name).value(0), expected);
+    }
+}
+
+#[test]
+fn add_quaternion_columns_handles_quaternions_in_lists() {
+    let item = Arc::new(Field::new(
+        "item",
+        DataType::Struct(quaternion_fields()),
+        false,
+    ));
+    let schema = Arc::new(Schema::new(vec![Field::new(
+        "orientations",
+        DataType::List(item.clone()),
+        false,
+    )]));
+    let values = quaternions(&[[0.0, 0.0, 0.0, 1.0], [0.0, 0.0, 1.0, 0.0]], None);
+    let list = ListArray::new(
+        item,
+        OffsetBuffer::from_lengths([2]),
+        Arc::new(values),
+        None,
+    );
+    let batch = RecordBatch::try_new(schema, vec![Arc::new(list)]).unwrap();
+
+    let derived = add_quaternion_columns(&batch, QuaternionColumns::Euler).unwrap();
+    let list = derived
+        .column(0)
+        .as_any()
+        .downcast_ref::<ListArray>()
+        .unwrap();
+    let items = list
+        .values()
+        .as_any()
+        .downcast_ref::<StructArray>()
+        .unwrap();
+    assert_close(child(items, "yaw").value(1).abs(), std::f64::consts::PI);
+}
+
+#[test]
+fn quaternion_columns_parse_from_str() {
+    assert_eq!(
+        "Euler-Degrees".parse::<QuaternionColumns>(),
+        Ok(QuaternionColumns::EulerDegrees)
+    );
+    assert!("matrix".parse::<QuaternionColumns>().is_err());
+}
@@ -0,0 +1,268 @@
+use std::sync::Arc;
+
+use arrow::{
+    array::{
+        Array, ArrayRef, AsArray, Float64Array, Int32Array, ListArray, StringArray, StructArray,
+    },
+    buffer::OffsetBuffer,
+    datatypes::{DataType, Field, Fields, Float64Type, Int32Type, Schema},
+    record_batch::RecordBatch,
+};
+use mcapdecode_arrow::{
+    SchemaEvolution, SchemaIncompatibility, check_append_compatibility, conform_to_schema,
+    merge_append_schema, schema_fingerprint,
+};
+
+fn pose_schema(x_type: DataType, x_nullable: bool) -> Schema {
+    Schema::new(vec![
+        Field::new("id", DataType::Utf8, true),
+        Field::new(
+            "pose",
+            DataType::Struct(Fields::from(vec![
+                Field::new("x", x_type, x_nullable),
+                Field::new("y", DataType::Float64, false),
+            ])),
+            false,
+        ),
+        Field::new(
+            "points",
+            DataType::List(Arc::new(Field::new("item", DataType::Int32, false))),
+            true,
+        ),
+    ])
+}
+
+#[test]
+fn identical_schemas_in_any_order_are_compatible() {
+    let existing = pose_schema(DataType::Float64, false);
+    let mut fields: Vec<_> = existing.fields().iter().cloned().collect();
+    fields.reverse();
+    let new = Schema::new(fields).with_metadata([("k".to_string(), "v".to_string())].into());
+
+    assert!(check_append_compatibility(&existing, &new).is_empty());
+}
+
+#[test]
+fn narrower_nullability_is_compatible_but_wider_is_not() {
+    let nullable = pose_schema(DataType::Float64, true);
+    let required = pose_schema(DataType::Float64, false);
+
+    assert!(check_append_compatibility(&nullable, &required).is_empty());
+    assert_eq!(
+        check_append_compatibility(&required, &nullable),
+        vec![SchemaIncompatibility::NullableField {
+            path: "pose.x".to_string()
+        }]
+    );
+}
+
+#[test]
+fn reports_missing_extra_and_changed_fields() {
+    let existing = pose_schema(DataType::Float64, false);
+    let new = Schema::new(vec![
+        Field::new(
+            "pose",
+            DataType::Struct(Fields::from(vec![
+                Field::new("x", DataType::Float32, false),
+                Field::new("y", DataType::Float64, false),
+            ])),
+            false,
+        ),
+        Field::new(
+            "points",
+            DataType::List(Arc::new(Field::new("item", DataType::Int64, false))),
+            true,
+        ),
+        Field::new("extra", DataType::Boolean, true),
+    ]);
+
+    let issues = check_append_compatibility(&existing, &new);
+    let messages: Vec<String> = issues.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "field 'id' is missing from the new schema",
+            "field 'pose.x' changed type from Float64 to Float32",
+            "field 'points[]' changed type from Int32 to Int64",
+            "field 'extra' does not exist in the existing schema",
+        ]
+    );
+}
+
+#[test]
+fn schema_fingerprint_ignores_metadata_but_not_structure() {
+    let base = pose_schema(DataType::Float64, false);
+    let with_metadata = base
+        .clone()
+        .with_metadata([("k".to_string(), "v".to_string())].into());
+    assert_eq!(
+        schema_fingerprint(&base),
+        schema_fingerprint(&with_metadata)
+    );
+
+    let changed_type = pose_schema(DataType::Float32, false);
+    let changed_nullability = pose_schema(DataType::Float64, true);
+    let mut fields: Vec<_> = base.fields().iter().cloned().collect();
+    fields.reverse();
+    let reordered = Schema::new(fields);
+    for other in [changed_type, changed_nullability, reordered] {
+        assert_ne!(schema_fingerprint(&base), schema_fingerprint(&other));
+    }
+}
+
+/// `pose_schema` without `id`, with a `z` member in `pose` and a `frame`
+/// column.
+fn evolved_pose_schema() -> Schema {
+    Schema::new(vec![
+        Field::new("frame", DataType::Utf8, false),
+        Field::new(
+            "pose",
+            DataType::Struct(Fields::from(vec![
+                Field::new("y", DataType::Float64, false),
+                Field::new("x", DataType::Float64, false),
+                Field::new("z", DataType::Float64, false),
+            ])),
+            false,
+        ),
+        Field::new(
+            "points",
+            DataType::List(Arc::new(Field::new("item", DataType::Int32, false))),
+            true,
+        ),
+    ])
+}
+
+#[test]
+fn strict_evolution_rejects_new_fields() {
+    let existing = pose_schema(DataType::Float64, false);
+    let issues = merge_append_schema(&existing, &evolved_pose_schema(), SchemaEvolution::Strict)
+        .unwrap_err();
+    let messages: Vec<String> = issues.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "field 'id' is missing from the new schema",
+            "field 'pose.z' does not exist in the existing schema",
+            "field 'frame' does not exist in the existing schema",
+        ]
+    );
+    assert_eq!(
+        merge_append_schema(&existing, &existing, SchemaEvolution::Strict),
+        Ok(existing)
+    );
+}
+
+#[test]
+fn add_columns_evolution_appends_new_fields_as_nullable() {
+    let existing = pose_schema(DataType::Float64, false);
+    let merged = merge_append_schema(
+        &existing,
+        &evolved_pose_schema(),
+        SchemaEvolution::AddColumns,
+    )
+    .unwrap();
+
+    let expected = Schema::new(vec![
+        Field::new("id", DataType::Utf8, true),
+        Field::new(
+            "pose",
+            DataType::Struct(Fields::from(vec![
+                Field::new("x", DataType::Float64, false),
+                Field::new("y", DataType::Float64, false),
+                Field::new("z", DataType::Float64, true),
+            ])),
+            false,
+        ),
+        Field::new(
+            "points",
+            DataType::List(Arc::new(Field::new("item", DataType::Int32, false))),
+            true,
+        ),
+        Field::new("frame", DataType::Utf8, true),
+    ]);
+    assert_eq!(merged, expected);
+    // The merged schema only adds columns to the dataset.
+    assert_eq!(
+        check_append_compatibility(&existing, &merged),
+        vec![
+            SchemaIncompatibility::ExtraField {
+                path: "pose.z".to_string()
+            },
+            SchemaIncompatibility::ExtraField {
+                path: "frame".to_string()
+            },
+        ]
+    );
+}
+
+#[test]
+fn add_columns_evolution_still_rejects_type_changes_and_missing_required_fields() {
+    let existing = pose_schema(DataType::Float64, false);
+    let new = Schema::new(vec![Field::new(
+        "pose",
+        DataType::Struct(Fields::from(vec![Field::new(
+            "x",
+            DataType::Float32,
+            false,
+        )])),
+        false,
+    )]);
+    let issues = merge_append_schema(&existing, &new, SchemaEvolution::AddColumns).unwrap_err();
+    let messages: Vec<String> = issues.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "field 'pose.x' changed type from Float64 to Float32",
+            "field 'pose.y' is missing from the new schema",
+        ]
+    );
+}
+
+#[test]
+fn conform_to_schema_reorders_columns_and_fills_nulls() {
+    let existing = pose_schema(DataType::Float64, false);
+    let new = Arc::new(evolved_pose_schema());
+    let merged =
+        Arc::new(merge_append_schema(&existing, &new, SchemaEvolution::AddColumns).unwrap());
+    let f64s = |values: [f64; 2]| -> ArrayRef { Arc::new(Float64Array::from(values.to_vec())) };
+    let DataType::Struct(pose_fields) = new.field(1).data_type() else {
+        panic!("pose should be a struct");
+    };
+    let pose = StructArray::new(
+        pose_fields.clone(),
+        vec![f64s([1.0, 2.0]), f64s([3.0, 4.0]), f64s([5.0, 6.0])],
+        None,
+    );
+    let points = ListArray::new(
+        Arc::new(Field::new("item", DataType::Int32, false)),
+        OffsetBuffer::from_lengths([1, 0]),
+        Arc::new(Int32Array::from(vec![1])),
+        Some(vec![true, false].into()),
+    );
+    let batch = RecordBatch::try_new(
+        new,
+        vec![
+            Arc::new(StringArray::from(vec!["a", "b"])),
+            Arc::new(pose),
+            Arc::new(points),
+        ],
+    )
+    .unwrap();
+
+    let conformed = conform_to_schema(&batch, merged.clone()).unwrap();
+    assert_eq!(conformed.schema(), merged);
+    assert_eq!(conformed.column(0).null_count(), 2);
+    let pose = conformed.column(1).as_struct();
+    assert_eq!(
+        pose.column(0).as_primitive::<Float64Type>().values(),
+        &[3.0, 4.0]
+    );
+    assert_eq!(
+        pose.column(2).as_primitive::<Float64Type>().values(),
+        &[5.0, 6.0]
+    );
+    let points = conformed.column(2).as_list::<i32>();
+    assert_eq!(points.value(0).as_primitive::<Int32Type>().values(), &[1]);
+    assert!(points.is_null(1));
+    assert_eq!(conformed.column(3).as_string::<i32>().value(1), "b");
+}
@@ -1,6 +1,14 @@
-use arrow::datatypes::DataType;
-use mcapdecode_arrow::field_defs_to_arrow_schema;
-use mcapdecode_core::{DataTypeDef, ElementDef, FieldDef, FieldDefs};
+use std::sync::Arc;
+
+use arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+use mcapdecode_arrow::{
+    ArrowConvertError, arrow_schema_to_field_defs, field_defs_to_arrow_schema,
+    field_defs_to_arrow_schema_cached, field_defs_to_record_batch_schema,
+};
+use mcapdecode_core::{
+    BYTE_WIDTH_METADATA_KEY, DataTypeDef, ElementDef, FieldDef, FieldDefs, TIMESTAMP_METADATA_KEY,
+    UNIT_METADATA_KEY,
+};
 
 #[test]
 fn field_defs_to_arrow_schema_converts_nested_types() {
@@ -25,6 +33,7 @@ fn field_defs_to_arrow_schema_converts_nested_types() {
                 DataTypeDef::List(Box::new(ElementDef::new(DataTypeDef::I32, true))),
                 true,
             ),
+            metadata: Default::default(),
         },
         FieldDef {
             name: "array".to_string(),
@@ -32,6 +41,7 @@ fn field_defs_to_arrow_schema_converts_nested_types() {
                 DataTypeDef::Array(Box::new(ElementDef::new(DataTypeDef::F64, false)), 4),
                 false,
             ),
+            metadata: Default::default(),
         },
         FieldDef {
             name: "map".to_string(),
@@ -47,6 +57,7 @@ fn field_defs_to_arrow_schema_converts_nested_types() {
                 },
                 true,
             ),
+            metadata: Default::default(),
         },
         FieldDef {
             name: "st".to_string(),
@@ -60,6 +71,7 @@ fn field_defs_to_arrow_schema_converts_nested_types() {
                 ),
                 false,
             ),
+            metadata: Default::default(),
         },
     ]);
 
@@ -126,3 +138,173 @@ fn field_defs_to_arrow_schema_converts_nested_types() {
         other => panic!("expected struct, got {other:?}"),
     }
 }
+
+#[test]
+fn field_defs_to_record_batch_schema_prepends_timestamps() {
+    let fields = FieldDefs::from(vec![FieldDef::new("x", DataTypeDef::F64, false)]);
+    let schema = field_defs_to_record_batch_schema(&fields);
+
+    let names: Vec<_> = schema.fields().iter().map(|f| f.name().as_str()).collect();
+    assert_eq!(names, vec!["@log_time", "@publish_time", "x"]);
+    assert!(matches!(
+        schema.field(0).data_type(),
+        DataType::Timestamp(_, Some(_))
+    ));
+}
+
+#[test]
+fn field_defs_to_arrow_schema_carries_field_metadata() {
+    let fields = FieldDefs::from(vec![
+        FieldDef::new("speed", DataTypeDef::F64, false).with_metadata(UNIT_METADATA_KEY, "m/s"),
+        FieldDef::new("gear", DataTypeDef::I32, false),
+    ]);
+    let schema = field_defs_to_arrow_schema(&fields);
+
+    assert_eq!(
+        schema
+            .field(0)
+            .metadata()
+            .get(UNIT_METADATA_KEY)
+            .map(String::as_str),
+        Some("m/s")
+    );
+    assert!(schema.field(1).metadata().is_empty());
+}
+
+#[test]
+fn field_defs_to_arrow_schema_cached_reuses_schema_per_field_defs() {
+    let point = |unit: &str| {
+        FieldDefs::from(vec![
+            FieldDef::new("x", DataTypeDef::F64, false).with_metadata(UNIT_METADATA_KEY, unit),
+            FieldDef::new(
+                "tags",
+                DataTypeDef::List(Box::new(ElementDef::new(DataTypeDef::String, false))),
+                true,
+            ),
+        ])
+    };
+
+    let first = field_defs_to_arrow_schema_cached(&point("m"));
+    let again = field_defs_to_arrow_schema_cached(&point("m"));
+    assert!(Arc::ptr_eq(&first, &again));
+    assert_eq!(*first, field_defs_to_arrow_schema(&point("m")));
+
+    let other = field_defs_to_arrow_schema_cached(&point("mm"));
+    assert!(!Arc::ptr_eq(&first, &other));
+    assert_eq!(*other, field_defs_to_arrow_schema(&point("mm")));
+}
+
+#[test]
+fn arrow_schema_to_field_defs_inverts_field_defs_to_arrow_schema() {
+    let fields = FieldDefs::from(vec![
+        FieldDef::new("speed", DataTypeDef::F64, false).with_metadata(UNIT_METADATA_KEY, "m/s"),
+        FieldDef::new("raw", DataTypeDef::Bytes, true),
+        FieldDef::new(
+            "ranges",
+            DataTypeDef::Array(Box::new(ElementDef::new(DataTypeDef::F32, false)), 3),
+            false,
+        ),
+        FieldDef::new(
+            "points",
+            DataTypeDef::List(Box::new(ElementDef::new(
+                DataTypeDef::Struct(
+                    vec![
+                        FieldDef::new("x", DataTypeDef::I32, false),
+                        FieldDef::new("label", DataTypeDef::String, true),
+                    ]
+                    .into(),
+                ),
+                true,
+            ))),
+            true,
+        ),
+        FieldDef::new(
+            "tags",
+            DataTypeDef::Map {
+                key: Box::new(ElementDef::new(DataTypeDef::String, false)),
+                value: Box::new(ElementDef::new(DataTypeDef::U64, true)),
+            },
+            false,
+        ),
+    ]);
+
+    let schema = field_defs_to_arrow_schema(&fields);
+    assert_eq!(arrow_schema_to_field_defs(&schema).unwrap(), fields);
+}
+
+#[test]
+fn arrow_schema_to_field_defs_rejects_types_without_counterpart() {
+    let schema = Schema::new(vec![
+        Field::new("name", DataType::LargeUtf8, true),
+        Field::new(
+            "values",
+            DataType::LargeList(Arc::new(Field::new("item", DataType::Int8, false))),
+            false,
+        ),
+    ]);
+    assert_eq!(
+        arrow_schema_to_field_defs(&schema).unwrap(),
+        FieldDefs::from(vec![
+            FieldDef::new("name", DataTypeDef::String, true),
+            FieldDef::new(
+                "values",
+                DataTypeDef::List(Box::new(ElementDef::new(DataTypeDef::I8, false))),
+                false,
+            ),
+        ])
+    );
+
+    let schema = Schema::new(vec![Field::new(
+        "price",
+        DataType::Decimal128(10, 2),
+        false,
+    )]);
+    let err = arrow_schema_to_field_defs(&schema).unwrap_err();
+    assert!(
+        matches!(
+            &err,
+            ArrowConvertError::UnsupportedArrowType {
+                field,
+                data_type: DataType::Decimal128(10, 2),
+            } if field == "price"
+        ),
+        "{err}"
+    );
+}
+
+#[test]
+fn timestamp_fields_convert_to_timestamp_columns() {
+    let fields = FieldDefs::from(vec![
+        FieldDef::new("stamp", DataTypeDef::I64, true)
+            .with_metadata(TIMESTAMP_METADATA_KEY, "true"),
+        FieldDef::new("count", DataTypeDef::I64, false),
+    ]);
+    let schema = field_defs_to_arrow_schema(&fields);
+
+    assert!(matches!(
+        schema.field(0).data_type(),
+        DataType::Timestamp(TimeUnit::Nanosecond, Some(_))
+    ));
+    assert_eq!(schema.field(1).data_type(), &DataType::Int64);
+    assert_eq!(arrow_schema_to_field_defs(&schema).unwrap(), fields);
+
+    // Record batch timestamp columns convert too.
+    let schema = field_defs_to_record_batch_schema(&FieldDefs::default());
+    let defs = arrow_schema_to_field_defs(&schema).unwrap();
+    assert!(defs.iter().all(|f| f.is_timestamp()));
+}
+
+#[test]
+fn byte_width_fields_convert_to_fixed_size_binary_columns() {
+    let fields = FieldDefs::from(vec![
+        FieldDef::new("uuid", DataTypeDef::Bytes, false)
+            .with_metadata(BYTE_WIDTH_METADATA_KEY, "16"),
+        FieldDef::new("data", DataTypeDef::Bytes, false),
+    ]);
+    let schema = field_defs_to_arrow_schema(&fields);
+
+    assert_eq!(schema.field(0).data_type(), &DataType::FixedSizeBinary(16));
+    assert_eq!(schema.field(1).data_type(), &DataType::Binary);
+    assert_eq!(arrow_schema_to_field_defs(&schema).unwrap(), fields);
+    assert_eq!(fields[0].byte_width(), Some(16));
+}
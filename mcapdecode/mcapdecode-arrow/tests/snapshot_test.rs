@@ -0,0 +1,111 @@
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use arrow::{
+    array::{Array, Float64Array, TimestampNanosecondArray, UInt32Array},
+    datatypes::{DataType, Field, Schema, TimeUnit},
+    record_batch::RecordBatch,
+};
+use mcapdecode_arrow::LatestSnapshot;
+use mcapdecode_core::KEY_METADATA_KEY;
+
+fn make_batch(times: Vec<i64>, ids: Vec<u32>, values: Vec<f64>) -> RecordBatch {
+    let schema = Schema::new(vec![
+        Field::new(
+            "@log_time",
+            DataType::Timestamp(TimeUnit::Nanosecond, Some("UTC".into())),
+            false,
+        ),
+        Field::new("id", DataType::UInt32, false).with_metadata(HashMap::from([(
+            KEY_METADATA_KEY.to_string(),
+            "true".to_string(),
+        )])),
+        Field::new("value", DataType::Float64, false),
+    ]);
+    RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(TimestampNanosecondArray::from(times).with_timezone("UTC")),
+            Arc::new(UInt32Array::from(ids)),
+            Arc::new(Float64Array::from(values)),
+        ],
+    )
+    .unwrap()
+}
+
+fn ids_and_values(batch: &RecordBatch) -> Vec<(u32, f64)> {
+    let ids = batch
+        .column(1)
+        .as_any()
+        .downcast_ref::<UInt32Array>()
+        .unwrap();
+    let values = batch
+        .column(2)
+        .as_any()
+        .downcast_ref::<Float64Array>()
+        .unwrap();
+    (0..batch.num_rows())
+        .map(|row| (ids.value(row), values.value(row)))
+        .collect()
+}
+
+#[test]
+fn latest_snapshot_keeps_final_state_per_key_across_batches() {
+    let mut snapshot = LatestSnapshot::new();
+    assert!(
+        snapshot
+            .push(&make_batch(
+                vec![1, 2, 3],
+                vec![1, 2, 1],
+                vec![0.1, 0.2, 0.3]
+            ))
+            .unwrap()
+            .is_empty()
+    );
+    assert!(
+        snapshot
+            .push(&make_batch(vec![4, 5], vec![3, 2], vec![0.4, 0.5]))
+            .unwrap()
+            .is_empty()
+    );
+
+    let state = snapshot.finish().expect("final state");
+    assert_eq!(ids_and_values(&state), vec![(1, 0.3), (2, 0.5), (3, 0.4)]);
+    assert!(snapshot.finish().is_none());
+}
+
+#[test]
+fn latest_snapshot_emits_one_snapshot_per_interval() {
+    let mut snapshot = LatestSnapshot::new().with_interval(Duration::from_nanos(10));
+    let closed = snapshot
+        .push(&make_batch(
+            vec![0, 5, 9, 12, 25],
+            vec![1, 1, 2, 1, 2],
+            vec![0.0, 0.5, 0.9, 1.2, 2.5],
+        ))
+        .unwrap();
+    let closed: Vec<_> = closed.iter().map(ids_and_values).collect();
+    assert_eq!(closed, vec![vec![(1, 0.5), (2, 0.9)], vec![(1, 1.2)]]);
+
+    let state = snapshot.finish().expect("open bucket");
+    assert_eq!(ids_and_values(&state), vec![(2, 2.5)]);
+}
+
+#[test]
+fn latest_snapshot_uses_explicit_key_columns() {
+    let mut snapshot = LatestSnapshot::new().with_key_columns(vec!["value".to_string()]);
+    snapshot
+        .push(&make_batch(
+            vec![1, 2, 3],
+            vec![1, 2, 3],
+            vec![1.0, 1.0, 2.0],
+        ))
+        .unwrap();
+    let state = snapshot.finish().unwrap();
+    assert_eq!(ids_and_values(&state), vec![(2, 1.0), (3, 2.0)]);
+
+    let mut snapshot = LatestSnapshot::new().with_key_columns(vec!["missing".to_string()]);
+    let err = snapshot
+        .push(&make_batch(vec![1], vec![1], vec![1.0]))
+        .unwrap_err();
+    assert!(err.to_string().contains("key column 'missing' not found"));
+}
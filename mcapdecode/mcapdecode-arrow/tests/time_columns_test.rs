@@ -0,0 +1,144 @@
+use std::sync::Arc;
+
+use arrow::{
+    array::{Array, Int32Array, Int64Array, TimestampNanosecondArray},
+    datatypes::{DataType, Field, Schema, TimeUnit},
+    record_batch::RecordBatch,
+};
+use mcapdecode_arrow::{
+    ELAPSED_NS_COLUMN, LATENCY_NS_COLUMN, TimeColumns, apply_time_columns, with_latency_column,
+};
+
+fn timestamp_field(name: &str) -> Field {
+    Field::new(
+        name,
+        DataType::Timestamp(TimeUnit::Nanosecond, Some("UTC".into())),
+        false,
+    )
+}
+
+fn timestamp_array(values: Vec<i64>) -> Arc<TimestampNanosecondArray> {
+    Arc::new(TimestampNanosecondArray::from(values).with_timezone("UTC"))
+}
+
+fn make_batch() -> RecordBatch {
+    let schema = Schema::new(vec![
+        timestamp_field("@log_time"),
+        timestamp_field("@publish_time"),
+        Field::new("x", DataType::Int32, false),
+    ]);
+    RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            timestamp_array(vec![1_000, 1_250, 2_000]),
+            timestamp_array(vec![900, 1_200, 1_900]),
+            Arc::new(Int32Array::from(vec![1, 2, 3])),
+        ],
+    )
+    .unwrap()
+}
+
+fn column_names(batch: &RecordBatch) -> Vec<String> {
+    batch
+        .schema()
+        .fields()
+        .iter()
+        .map(|f| f.name().clone())
+        .collect()
+}
+
+fn elapsed_values(batch: &RecordBatch) -> Vec<Option<i64>> {
+    let array = batch
+        .column_by_name(ELAPSED_NS_COLUMN)
+        .unwrap()
+        .as_any()
+        .downcast_ref::<Int64Array>()
+        .unwrap();
+    array.iter().collect()
+}
+
+#[test]
+fn absolute_keeps_batch_unchanged() {
+    let batch = make_batch();
+    let out = apply_time_columns(&batch, TimeColumns::Absolute, 1_000).unwrap();
+    assert_eq!(out, batch);
+}
+
+#[test]
+fn elapsed_replaces_timestamp_columns() {
+    let out = apply_time_columns(&make_batch(), TimeColumns::Elapsed, 1_000).unwrap();
+    assert_eq!(column_names(&out), ["@elapsed_ns", "x"]);
+    assert_eq!(elapsed_values(&out), [Some(0), Some(250), Some(1_000)]);
+}
+
+#[test]
+fn both_appends_elapsed_after_timestamp_columns() {
+    let out = apply_time_columns(&make_batch(), TimeColumns::Both, 1_250).unwrap();
+    assert_eq!(
+        column_names(&out),
+        ["@log_time", "@publish_time", "@elapsed_ns", "x"]
+    );
+    assert_eq!(elapsed_values(&out), [Some(-250), Some(0), Some(750)]);
+    assert!(out.schema().field(2).is_nullable());
+}
+
+#[test]
+fn elapsed_is_null_outside_i64_range() {
+    let out = apply_time_columns(&make_batch(), TimeColumns::Elapsed, u64::MAX).unwrap();
+    assert_eq!(elapsed_values(&out), [None, None, None]);
+}
+
+#[test]
+fn batch_without_log_time_is_unchanged() {
+    let schema = Schema::new(vec![Field::new("x", DataType::Int32, false)]);
+    let batch =
+        RecordBatch::try_new(Arc::new(schema), vec![Arc::new(Int32Array::from(vec![1]))]).unwrap();
+    let out = apply_time_columns(&batch, TimeColumns::Elapsed, 0).unwrap();
+    assert_eq!(out, batch);
+}
+
+#[test]
+fn latency_is_inserted_after_timestamp_columns() {
+    let out = with_latency_column(&make_batch()).unwrap();
+    assert_eq!(
+        column_names(&out),
+        ["@log_time", "@publish_time", "@latency_ns", "x"]
+    );
+    let latency = out
+        .column_by_name(LATENCY_NS_COLUMN)
+        .unwrap()
+        .as_any()
+        .downcast_ref::<Int64Array>()
+        .unwrap();
+    assert_eq!(
+        latency.iter().collect::<Vec<_>>(),
+        [Some(100), Some(50), Some(100)]
+    );
+
+    let both = apply_time_columns(&out, TimeColumns::Both, 1_000).unwrap();
+    assert_eq!(
+        column_names(&both),
+        [
+            "@log_time",
+            "@publish_time",
+            "@elapsed_ns",
+            "@latency_ns",
+            "x"
+        ]
+    );
+}
+
+#[test]
+fn latency_requires_both_timestamp_columns() {
+    let batch = apply_time_columns(&make_batch(), TimeColumns::Elapsed, 0).unwrap();
+    assert_eq!(with_latency_column(&batch).unwrap(), batch);
+}
+
+#[test]
+fn time_columns_from_str() {
+    assert_eq!("Both".parse::<TimeColumns>(), Ok(TimeColumns::Both));
+    assert_eq!(
+        "relative".parse::<TimeColumns>().unwrap_err(),
+        "invalid time columns 'relative' (expected: absolute, elapsed, both)"
+    );
+}
@@ -0,0 +1,109 @@
+use std::{collections::HashMap, sync::Arc};
+
+use arrow::{
+    array::{Array, FixedSizeBinaryArray, Int32Array, ListArray, StringArray, StructArray},
+    buffer::OffsetBuffer,
+    datatypes::{DataType, Field, Fields, Schema},
+    record_batch::RecordBatch,
+};
+use mcapdecode_arrow::render_uuid_columns;
+use mcapdecode_core::UUID_METADATA_KEY;
+
+fn uuid_field(name: &str) -> Field {
+    Field::new(name, DataType::FixedSizeBinary(16), true).with_metadata(HashMap::from([(
+        UUID_METADATA_KEY.to_string(),
+        "true".to_string(),
+    )]))
+}
+
+fn uuids(values: Vec<Option<[u8; 16]>>) -> FixedSizeBinaryArray {
+    FixedSizeBinaryArray::try_from_sparse_iter_with_size(values.into_iter(), 16).unwrap()
+}
+
+#[test]
+fn render_uuid_columns_renders_top_level_and_nested_uuids() {
+    let goal_id = uuid_field("goal_id");
+    let status_fields = Fields::from(vec![
+        uuid_field("goal_id"),
+        Field::new("status", DataType::Int32, false),
+    ]);
+    let item = Arc::new(Field::new(
+        "item",
+        DataType::Struct(status_fields.clone()),
+        false,
+    ));
+    let schema = Arc::new(Schema::new(vec![
+        goal_id,
+        Field::new("status_list", DataType::List(item.clone()), false),
+        Field::new("raw", DataType::FixedSizeBinary(16), false),
+    ]));
+    let first: [u8; 16] = std::array::from_fn(|i| i as u8 * 17);
+    let statuses = StructArray::new(
+        status_fields,
+        vec![
+            Arc::new(uuids(vec![Some(first), Some([0xff; 16])])),
+            Arc::new(Int32Array::from(vec![1, 4])),
+        ],
+        None,
+    );
+    let batch = RecordBatch::try_new(
+        schema,
+        vec![
+            Arc::new(uuids(vec![Some(first), None])),
+            Arc::new(ListArray::new(
+                item,
+                OffsetBuffer::from_lengths([2, 0]),
+                Arc::new(statuses),
+                None,
+            )),
+            Arc::new(uuids(vec![Some(first), Some(first)])),
+        ],
+    )
+    .unwrap();
+
+    let rendered = render_uuid_columns(&batch).unwrap();
+    let schema = rendered.schema();
+    assert_eq!(schema.field(0).data_type(), &DataType::Utf8);
+    assert_eq!(
+        schema
+            .field(0)
+            .metadata()
+            .get(UUID_METADATA_KEY)
+            .map(String::as_str),
+        Some("true")
+    );
+    // Unmarked 16-byte columns stay binary.
+    assert_eq!(schema.field(2).data_type(), &DataType::FixedSizeBinary(16));
+
+    let goal_ids = rendered
+        .column(0)
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .unwrap();
+    assert_eq!(goal_ids.value(0), "00112233-4455-6677-8899-aabbccddeeff");
+    assert!(goal_ids.is_null(1));
+
+    let list = rendered
+        .column(1)
+        .as_any()
+        .downcast_ref::<ListArray>()
+        .unwrap();
+    let statuses = list
+        .values()
+        .as_any()
+        .downcast_ref::<StructArray>()
+        .unwrap();
+    let nested = statuses
+        .column(0)
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .unwrap();
+    assert_eq!(nested.value(1), "ffffffff-ffff-ffff-ffff-ffffffffffff");
+}
+
+#[test]
+fn render_uuid_columns_leaves_batches_without_uuids_unchanged() {
+    let schema = Arc::new(Schema::new(vec![Field::new("x", DataType::Int32, false)]));
+    let batch = RecordBatch::try_new(schema, vec![Arc::new(Int32Array::from(vec![1, 2]))]).unwrap();
+    assert_eq!(render_uuid_columns(&batch).unwrap(), batch);
+}
@@ -1,5 +1,7 @@
 //! Decoder trait and encoding key used to register pluggable message decoders.
 
+use std::sync::Arc;
+
 use crate::{
     error::DecoderError, message_encoding::MessageEncoding, schema::FieldDefs,
     schema_encoding::SchemaEncoding, value::Value,
@@ -29,10 +31,37 @@ pub trait TopicDecoder: Send + Sync {
     /// Decode a single message payload into a [`Value`].
     fn decode(&self, message_data: &[u8]) -> Result<Value, DecoderError>;
 
+    /// Decode several message payloads at once, returning one value per
+    /// payload in the same order.
+    ///
+    /// The default implementation calls [`TopicDecoder::decode`] for each
+    /// payload; decoders can override it to amortize per-call setup or to
+    /// decode in parallel. `mcapdecode::McapReader` hands each chunk's
+    /// messages to this method when reading in parallel.
+    fn decode_batch(&self, payloads: &[&[u8]]) -> Result<Vec<Value>, DecoderError> {
+        payloads.iter().map(|data| self.decode(data)).collect()
+    }
+
     /// Return the Arrow-independent schema for decoded values.
     fn field_defs(&self) -> &FieldDefs;
 }
 
+/// Shares one topic decoder between threads or readers, e.g. as the inner
+/// decoder of a wrapper such as [`DepthLimitedDecoder`](crate::DepthLimitedDecoder).
+impl<T: TopicDecoder + ?Sized> TopicDecoder for Arc<T> {
+    fn decode(&self, message_data: &[u8]) -> Result<Value, DecoderError> {
+        (**self).decode(message_data)
+    }
+
+    fn decode_batch(&self, payloads: &[&[u8]]) -> Result<Vec<Value>, DecoderError> {
+        (**self).decode_batch(payloads)
+    }
+
+    fn field_defs(&self) -> &FieldDefs {
+        (**self).field_defs()
+    }
+}
+
 /// Factory trait that builds topic-local decoders from MCAP schema metadata.
 ///
 /// Implementations are registered with `mcapdecode::McapReader` and
@@ -41,6 +70,14 @@ pub trait MessageDecoder: Send + Sync {
     /// Returns the encoding pair this decoder handles.
     fn encoding_key(&self) -> EncodingKey;
 
+    /// Returns every encoding pair this decoder handles, e.g. several
+    /// spellings of the same message encoding.
+    ///
+    /// Defaults to [`MessageDecoder::encoding_key`] alone.
+    fn encoding_keys(&self) -> Vec<EncodingKey> {
+        vec![self.encoding_key()]
+    }
+
     /// Build a topic-local decoder for the given MCAP schema.
     ///
     /// Returns `Err` if the schema cannot be parsed or is structurally invalid.
@@ -50,3 +87,20 @@ pub trait MessageDecoder: Send + Sync {
         schema_data: &[u8],
     ) -> Result<Box<dyn TopicDecoder>, DecoderError>;
 }
+
+/// Hook that can replace MCAP schema data before a [`MessageDecoder`] parses it.
+///
+/// Intended for schemas whose embedded data is incomplete, such as protobuf
+/// stubs whose full descriptors live in a schema registry. `mcapdecode::McapReader`
+/// runs the resolver on the calling thread once per topic read, before any
+/// chunk is decoded, so a blocking lookup never stalls decoding workers.
+/// Resolvers that fetch over the network should cache their results.
+pub trait SchemaResolver: Send + Sync {
+    /// Return replacement schema data, or `None` to keep `schema_data`.
+    fn resolve_schema(
+        &self,
+        schema_encoding: &SchemaEncoding,
+        schema_name: &str,
+        schema_data: &[u8],
+    ) -> Result<Option<Vec<u8>>, DecoderError>;
+}
@@ -0,0 +1,267 @@
+//! Depth-limited decoding: structs nested too deeply are collapsed into JSON strings.
+
+use std::fmt::Write as _;
+
+use crate::{
+    decoder::TopicDecoder,
+    error::DecoderError,
+    schema::{DataTypeDef, ElementDef, FieldDef, FieldDefs},
+    value::Value,
+};
+
+/// Replace structs whose members would be nested deeper than `max_depth`
+/// field levels with [`DataTypeDef::String`] holding the struct as JSON.
+///
+/// Top-level fields are level 1 and the members of a struct are one level
+/// deeper than the struct. Lists, arrays and maps do not add a level.
+/// A `max_depth` of 0 is treated as 1.
+pub fn limit_field_defs_depth(fields: &FieldDefs, max_depth: usize) -> FieldDefs {
+    limit_fields(fields, 1, max_depth.max(1))
+}
+
+fn limit_fields(fields: &FieldDefs, level: usize, max_depth: usize) -> FieldDefs {
+    fields
+        .iter()
+        .map(|field| FieldDef {
+            element: limit_element(&field.element, level, max_depth),
+            ..field.clone()
+        })
+        .collect::<Vec<_>>()
+        .into()
+}
+
+fn limit_element(element: &ElementDef, level: usize, max_depth: usize) -> ElementDef {
+    ElementDef::new(
+        limit_data_type(&element.data_type, level, max_depth),
+        element.nullable,
+    )
+}
+
+fn limit_data_type(data_type: &DataTypeDef, level: usize, max_depth: usize) -> DataTypeDef {
+    match data_type {
+        DataTypeDef::Struct(_) if level >= max_depth => DataTypeDef::String,
+        DataTypeDef::Struct(fields) => {
+            DataTypeDef::Struct(limit_fields(fields, level + 1, max_depth))
+        }
+        DataTypeDef::List(elem) => {
+            DataTypeDef::List(Box::new(limit_element(elem, level, max_depth)))
+        }
+        DataTypeDef::Array(elem, size) => {
+            DataTypeDef::Array(Box::new(limit_element(elem, level, max_depth)), *size)
+        }
+        DataTypeDef::Map { key, value } => DataTypeDef::Map {
+            key: Box::new(limit_element(key, level, max_depth)),
+            value: Box::new(limit_element(value, level, max_depth)),
+        },
+        other => other.clone(),
+    }
+}
+
+/// [`TopicDecoder`] wrapper that applies [`limit_field_defs_depth`] to the
+/// schema and the decoded values of another decoder.
+pub struct DepthLimitedDecoder {
+    inner: Box<dyn TopicDecoder>,
+    field_defs: FieldDefs,
+    max_depth: usize,
+}
+
+impl DepthLimitedDecoder {
+    pub fn new(inner: Box<dyn TopicDecoder>, max_depth: usize) -> Self {
+        let max_depth = max_depth.max(1);
+        let field_defs = limit_field_defs_depth(inner.field_defs(), max_depth);
+        Self {
+            inner,
+            field_defs,
+            max_depth,
+        }
+    }
+
+    fn limit_value(&self, value: Value) -> Value {
+        match value {
+            Value::Struct(values) => Value::Struct(limit_struct_values(
+                values,
+                self.inner.field_defs(),
+                1,
+                self.max_depth,
+            )),
+            other => other,
+        }
+    }
+}
+
+impl TopicDecoder for DepthLimitedDecoder {
+    fn decode(&self, message_data: &[u8]) -> Result<Value, DecoderError> {
+        let value = self.inner.decode(message_data)?;
+        Ok(self.limit_value(value))
+    }
+
+    fn decode_batch(&self, payloads: &[&[u8]]) -> Result<Vec<Value>, DecoderError> {
+        let values = self.inner.decode_batch(payloads)?;
+        Ok(values
+            .into_iter()
+            .map(|value| self.limit_value(value))
+            .collect())
+    }
+
+    fn field_defs(&self) -> &FieldDefs {
+        &self.field_defs
+    }
+}
+
+fn limit_struct_values(
+    values: Vec<Value>,
+    fields: &FieldDefs,
+    level: usize,
+    max_depth: usize,
+) -> Vec<Value> {
+    values
+        .into_iter()
+        .zip(fields.iter())
+        .map(|(value, field)| limit_value(value, &field.element.data_type, level, max_depth))
+        .collect()
+}
+
+fn limit_value(value: Value, data_type: &DataTypeDef, level: usize, max_depth: usize) -> Value {
+    match (value, data_type) {
+        (Value::Null, _) => Value::Null,
+        (value @ Value::Struct(_), DataTypeDef::Struct(_)) if level >= max_depth => {
+            let mut json = String::new();
+            write_json(&mut json, &value, Some(data_type));
+            Value::string(json)
+        }
+        (Value::Struct(values), DataTypeDef::Struct(fields)) => {
+            Value::Struct(limit_struct_values(values, fields, level + 1, max_depth))
+        }
+        (Value::List(items), DataTypeDef::List(elem)) => Value::List(
+            items
+                .into_iter()
+                .map(|item| limit_value(item, &elem.data_type, level, max_depth))
+                .collect(),
+        ),
+        (Value::Array(items), DataTypeDef::Array(elem, _)) => Value::Array(
+            items
+                .into_iter()
+                .map(|item| limit_value(item, &elem.data_type, level, max_depth))
+                .collect(),
+        ),
+        (Value::Map(entries), DataTypeDef::Map { key, value }) => Value::Map(
+            entries
+                .into_iter()
+                .map(|(k, v)| {
+                    (
+                        limit_value(k, &key.data_type, level, max_depth),
+                        limit_value(v, &value.data_type, level, max_depth),
+                    )
+                })
+                .collect(),
+        ),
+        (value, _) => value,
+    }
+}
+
+/// Render `value` as JSON. Struct members are named after `data_type` when
+/// it matches; otherwise structs are rendered as arrays.
+fn write_json(out: &mut String, value: &Value, data_type: Option<&DataTypeDef>) {
+    match value {
+        Value::Null => out.push_str("null"),
+        Value::Bool(v) => out.push_str(if *v { "true" } else { "false" }),
+        Value::I8(v) => push_display(out, v),
+        Value::I16(v) => push_display(out, v),
+        Value::I32(v) => push_display(out, v),
+        Value::I64(v) => push_display(out, v),
+        Value::U8(v) => push_display(out, v),
+        Value::U16(v) => push_display(out, v),
+        Value::U32(v) => push_display(out, v),
+        Value::U64(v) => push_display(out, v),
+        Value::F32(v) if v.is_finite() => push_display(out, v),
+        Value::F64(v) if v.is_finite() => push_display(out, v),
+        Value::F32(_) | Value::F64(_) => out.push_str("null"),
+        Value::String(s) => write_json_string(out, s),
+        Value::Bytes(bytes) => {
+            let items: Vec<_> = bytes.iter().map(|b| Value::U8(*b)).collect();
+            write_json_array(out, &items, None);
+        }
+        Value::Struct(values) => match data_type {
+            Some(DataTypeDef::Struct(fields)) if fields.len() == values.len() => {
+                out.push('{');
+                for (i, (field, value)) in fields.iter().zip(values).enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    write_json_string(out, &field.name);
+                    out.push(':');
+                    write_json(out, value, Some(&field.element.data_type));
+                }
+                out.push('}');
+            }
+            _ => write_json_array(out, values, None),
+        },
+        Value::List(items) | Value::Array(items) => {
+            let elem = match data_type {
+                Some(DataTypeDef::List(elem) | DataTypeDef::Array(elem, _)) => {
+                    Some(&elem.data_type)
+                }
+                _ => None,
+            };
+            write_json_array(out, items, elem);
+        }
+        Value::Map(entries) => {
+            let (key_type, value_type) = match data_type {
+                Some(DataTypeDef::Map { key, value }) => {
+                    (Some(&key.data_type), Some(&value.data_type))
+                }
+                _ => (None, None),
+            };
+            out.push('{');
+            for (i, (k, v)) in entries.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                match k {
+                    Value::String(s) => write_json_string(out, s),
+                    other => {
+                        let mut key = String::new();
+                        write_json(&mut key, other, key_type);
+                        write_json_string(out, &key);
+                    }
+                }
+                out.push(':');
+                write_json(out, v, value_type);
+            }
+            out.push('}');
+        }
+    }
+}
+
+fn write_json_array(out: &mut String, items: &[Value], elem: Option<&DataTypeDef>) {
+    out.push('[');
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        write_json(out, item, elem);
+    }
+    out.push(']');
+}
+
+fn write_json_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if u32::from(c) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", u32::from(c));
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+fn push_display(out: &mut String, value: impl std::fmt::Display) {
+    let _ = write!(out, "{value}");
+}
@@ -1,6 +1,41 @@
 //! Error types for the decoder layer.
+use std::fmt;
+
 use thiserror::Error;
 
+/// Coarse classification of a failure, intended for routing decisions
+/// (e.g., requeue vs. quarantine) without matching on error messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCategory {
+    /// Schema data is missing, unparsable, or unsupported.
+    SchemaIssue,
+    /// Message payload or file content is malformed.
+    PayloadIssue,
+    /// Reading the input failed at the I/O level.
+    IoIssue,
+    /// The caller's configuration does not fit the input
+    /// (unknown topic, missing decoder, ...).
+    ConfigIssue,
+}
+
+impl ErrorCategory {
+    /// Returns a stable, machine-readable name for this category.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCategory::SchemaIssue => "schema",
+            ErrorCategory::PayloadIssue => "payload",
+            ErrorCategory::IoIssue => "io",
+            ErrorCategory::ConfigIssue => "config",
+        }
+    }
+}
+
+impl fmt::Display for ErrorCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// Error returned by [`MessageDecoder`](crate::MessageDecoder) implementations.
 #[derive(Debug, Error)]
 pub enum DecoderError {
@@ -16,6 +51,14 @@ pub enum DecoderError {
     #[error("invalid schema '{schema_name}': {detail}")]
     SchemaInvalid { schema_name: String, detail: String },
 
+    /// A [`SchemaResolver`](crate::SchemaResolver) failed to look up schema data.
+    #[error("failed to resolve schema '{schema_name}': {source}")]
+    SchemaResolve {
+        schema_name: String,
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+
     /// Message payload bytes could not be decoded.
     #[error("failed to decode message for schema '{schema_name}': {source}")]
     MessageDecode {
@@ -25,6 +68,121 @@ pub enum DecoderError {
     },
 }
 
+impl DecoderError {
+    /// Returns a stable, machine-readable code for this error variant.
+    pub fn code(&self) -> &'static str {
+        match self {
+            DecoderError::SchemaParse { .. } => "schema_parse",
+            DecoderError::SchemaInvalid { .. } => "schema_invalid",
+            DecoderError::SchemaResolve { .. } => "schema_resolve",
+            DecoderError::MessageDecode { .. } => "message_decode",
+        }
+    }
+
+    /// Returns the coarse category of this error.
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            DecoderError::SchemaParse { .. }
+            | DecoderError::SchemaInvalid { .. }
+            | DecoderError::SchemaResolve { .. } => ErrorCategory::SchemaIssue,
+            DecoderError::MessageDecode { .. } => ErrorCategory::PayloadIssue,
+        }
+    }
+
+    /// Returns `true` if retrying the same operation could succeed.
+    ///
+    /// Decoding is deterministic, so only schema lookups (which may go over
+    /// the network) are retryable.
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, DecoderError::SchemaResolve { .. })
+    }
+}
+
+/// Syntax error in a text schema, located by line and column.
+///
+/// Decoders for text schemas use this as the `source` of
+/// [`DecoderError::SchemaParse`], so callers can downcast it to point at the
+/// offending text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaSyntaxError {
+    /// Path of the schema bundle section (e.g. `pkg/msg/Type`), if known.
+    pub section: Option<String>,
+    /// 1-based line number within the section.
+    pub line: usize,
+    /// 1-based column (in characters) within the line.
+    pub column: usize,
+    /// The source line the error was found on.
+    pub snippet: String,
+    pub message: String,
+}
+
+impl SchemaSyntaxError {
+    pub fn new(
+        line: usize,
+        column: usize,
+        snippet: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            section: None,
+            line,
+            column,
+            snippet: snippet.into(),
+            message: message.into(),
+        }
+    }
+
+    /// Attribute the error to the bundle section at `section`.
+    pub fn with_section(mut self, section: impl Into<String>) -> Self {
+        self.section = Some(section.into());
+        self
+    }
+
+    /// Render the error as a multi-line diagnostic with a caret under the
+    /// offending column:
+    ///
+    /// ```text
+    /// error: unexpected trailing characters
+    ///  --> pkg/msg/Type:3:12
+    ///   |
+    /// 3 |   int32 x y;
+    ///   |           ^
+    /// ```
+    pub fn render_diagnostic(&self) -> String {
+        let line_no = self.line.to_string();
+        let gutter = " ".repeat(line_no.len());
+        let pad: String = self
+            .snippet
+            .chars()
+            .take(self.column.saturating_sub(1))
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        format!(
+            "error: {}\n{gutter}--> {}:{}:{}\n{gutter} |\n{line_no} | {}\n{gutter} | {pad}^",
+            self.message,
+            self.section.as_deref().unwrap_or("<schema>"),
+            self.line,
+            self.column,
+            self.snippet,
+        )
+    }
+}
+
+impl fmt::Display for SchemaSyntaxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(section) = &self.section {
+            write!(f, "while parsing section '{section}': ")?;
+        }
+        write!(
+            f,
+            "parse error at line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl std::error::Error for SchemaSyntaxError {}
+
 #[derive(Debug, Clone, PartialEq, Eq, Error)]
 #[error("expected {expected}, got {actual}")]
 pub struct ValueTypeError {
@@ -4,17 +4,30 @@
 //! ([`Value`] / [`DataTypeDef`]) and the [`MessageDecoder`] trait.
 
 mod decoder;
+mod depth;
 mod error;
 mod message;
 mod message_encoding;
 mod schema;
 mod schema_encoding;
+mod time;
+mod uuid;
 mod value;
+mod value_convert;
+mod warning;
 
-pub use decoder::{EncodingKey, MessageDecoder, TopicDecoder};
-pub use error::{DecoderError, ValueTypeError};
+pub use decoder::{EncodingKey, MessageDecoder, SchemaResolver, TopicDecoder};
+pub use depth::{DepthLimitedDecoder, limit_field_defs_depth};
+pub use error::{DecoderError, ErrorCategory, SchemaSyntaxError, ValueTypeError};
 pub use message::DecodedMessage;
 pub use message_encoding::MessageEncoding;
-pub use schema::{DataTypeDef, ElementDef, FieldDef, FieldDefs, format_field_defs};
+pub use schema::{
+    BYTE_WIDTH_METADATA_KEY, DOC_METADATA_KEY, DataTypeDef, ElementDef, FieldDef, FieldDefs,
+    KEY_METADATA_KEY, MAX_LEN_METADATA_KEY, TIMESTAMP_METADATA_KEY, UNIT_METADATA_KEY,
+    UUID_METADATA_KEY, format_field_defs, format_field_defs_tree, format_field_defs_with_docs,
+};
 pub use schema_encoding::SchemaEncoding;
+pub use time::{elapsed_ns, timestamp_to_i64};
+pub use uuid::format_uuid;
 pub use value::Value;
+pub use warning::{DecodeWarning, collect_warnings, warn};
@@ -4,6 +4,7 @@ use crate::value::Value;
 
 /// A decoded message payload used for Arrow conversion.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct DecodedMessage {
     pub log_time: u64,
     pub publish_time: u64,
@@ -6,38 +6,94 @@ use super::{DataTypeDef, ElementDef, FieldDef};
 /// primitive fields are rendered in one line, compound fields are pretty-printed.
 /// Nested fields follow the same rule.
 pub fn format_field_defs(fields: impl AsRef<[FieldDef]>) -> std::result::Result<String, Error> {
-    let fields = fields.as_ref();
+    format_fields(fields.as_ref(), false)
+}
+
+/// Like [`format_field_defs`], but render field documentation as `#` comment
+/// lines above each documented field.
+pub fn format_field_defs_with_docs(
+    fields: impl AsRef<[FieldDef]>,
+) -> std::result::Result<String, Error> {
+    format_fields(fields.as_ref(), true)
+}
+
+/// Format field definitions as a tree, one line per field with its full type:
+///
+/// ```text
+/// pose: struct
+/// ├── position: struct?
+/// │   ├── x [m]: f64
+/// │   └── y [m]: f64
+/// └── tags: map<string, f64>
+/// points: list<struct> (max_len 10)
+/// └── x: i32
+/// ```
+///
+/// Nullable types end with `?`. The members of list, array and map element
+/// structs are nested under the field; map keys and values get their own
+/// `key` / `value` nodes when either of them is compound.
+pub fn format_field_defs_tree(
+    fields: impl AsRef<[FieldDef]>,
+) -> std::result::Result<String, Error> {
+    let mut out = String::new();
+    format_tree_fields(fields.as_ref(), None, &mut out)?;
+    Ok(out)
+}
+
+fn format_fields(fields: &[FieldDef], docs: bool) -> std::result::Result<String, Error> {
     let mut out = String::new();
 
     for field in fields.iter() {
-        format_field(field, 0, &mut out)?;
+        format_field(field, 0, docs, &mut out)?;
     }
 
     Ok(out)
 }
 
-fn format_field(field: &FieldDef, indent: usize, out: &mut String) -> Result {
-    format_labeled_element(&field.name, &field.element, indent, out)
+fn format_field(field: &FieldDef, indent: usize, docs: bool, out: &mut String) -> Result {
+    if docs && let Some(doc) = field.doc() {
+        let pad = " ".repeat(indent);
+        for line in doc.lines() {
+            let line = line.trim_end();
+            if line.is_empty() {
+                writeln!(out, "{pad}#")?;
+            } else {
+                writeln!(out, "{pad}# {line}")?;
+            }
+        }
+    }
+    match field.unit() {
+        Some(unit) => {
+            let label = format!("{} [{unit}]", field.name);
+            format_labeled_element(&label, &field.element, indent, docs, out)
+        }
+        None => format_labeled_element(&field.name, &field.element, indent, docs, out),
+    }
 }
 
-fn format_data_type(data_type: &DataTypeDef, indent: usize, out: &mut String) -> Result {
+fn format_data_type(
+    data_type: &DataTypeDef,
+    indent: usize,
+    docs: bool,
+    out: &mut String,
+) -> Result {
     match data_type {
         DataTypeDef::Struct(fields) => {
             for child in fields.iter() {
-                format_field(child, indent, out)?;
+                format_field(child, indent, docs, out)?;
             }
         }
         DataTypeDef::List(elem) => {
-            format_labeled_element("item", elem, indent, out)?;
+            format_labeled_element("item", elem, indent, docs, out)?;
         }
         DataTypeDef::Array(elem, size) => {
             let pad = " ".repeat(indent);
-            format_labeled_element("item", elem, indent, out)?;
+            format_labeled_element("item", elem, indent, docs, out)?;
             writeln!(out, "{pad}size: {}", size)?;
         }
         DataTypeDef::Map { key, value } => {
-            format_labeled_element("key", key, indent, out)?;
-            format_labeled_element("value", value, indent, out)?;
+            format_labeled_element("key", key, indent, docs, out)?;
+            format_labeled_element("value", value, indent, docs, out)?;
         }
         _ => unreachable!("{data_type:?} is not a compound type"),
     }
@@ -49,12 +105,72 @@ fn format_labeled_element(
     label: &str,
     element: &ElementDef,
     indent: usize,
+    docs: bool,
     out: &mut String,
 ) -> Result {
     let pad = " ".repeat(indent);
     writeln!(out, "{pad}{label}: {element}")?;
     if !element.data_type.is_primitive() {
-        format_data_type(&element.data_type, indent + 4, out)?;
+        format_data_type(&element.data_type, indent + 4, docs, out)?;
     }
     Ok(())
 }
+
+/// Write `fields` as tree nodes; `prefix` is `None` for the top level.
+fn format_tree_fields(fields: &[FieldDef], prefix: Option<&str>, out: &mut String) -> Result {
+    for (i, field) in fields.iter().enumerate() {
+        let last = i + 1 == fields.len();
+        let (branch, child_prefix) = match prefix {
+            None => (String::new(), String::new()),
+            Some(prefix) if last => (format!("{prefix}└── "), format!("{prefix}    ")),
+            Some(prefix) => (format!("{prefix}├── "), format!("{prefix}│   ")),
+        };
+        write!(out, "{branch}{}", field.name)?;
+        if let Some(unit) = field.unit() {
+            write!(out, " [{unit}]")?;
+        }
+        write!(out, ": {}", tree_type_label(&field.element))?;
+        if let Some(max_len) = field.max_len() {
+            write!(out, " (max_len {max_len})")?;
+        }
+        writeln!(out)?;
+        if let Some(children) = tree_children(&field.element.data_type) {
+            format_tree_fields(&children, Some(&child_prefix), out)?;
+        }
+    }
+    Ok(())
+}
+
+fn tree_type_label(element: &ElementDef) -> String {
+    let label = match &element.data_type {
+        DataTypeDef::List(elem) => format!("list<{}>", tree_type_label(elem)),
+        DataTypeDef::Array(elem, size) => format!("array[{size}]<{}>", tree_type_label(elem)),
+        DataTypeDef::Map { key, value } => {
+            format!("map<{}, {}>", tree_type_label(key), tree_type_label(value))
+        }
+        other => other.to_string(),
+    };
+    if element.nullable {
+        format!("{label}?")
+    } else {
+        label
+    }
+}
+
+/// Nodes nested under a field of `data_type`, if any.
+fn tree_children(data_type: &DataTypeDef) -> Option<Vec<FieldDef>> {
+    match data_type {
+        DataTypeDef::Struct(fields) => Some(fields.as_slice().to_vec()),
+        DataTypeDef::List(elem) | DataTypeDef::Array(elem, _) => tree_children(&elem.data_type),
+        DataTypeDef::Map { key, value } => {
+            if key.data_type.is_primitive() && value.data_type.is_primitive() {
+                return None;
+            }
+            Some(vec![
+                FieldDef::new("key", key.data_type.clone(), key.nullable),
+                FieldDef::new("value", value.data_type.clone(), value.nullable),
+            ])
+        }
+        _ => None,
+    }
+}
@@ -3,5 +3,9 @@
 mod format;
 mod types;
 
-pub use format::format_field_defs;
-pub use types::{DataTypeDef, ElementDef, FieldDef, FieldDefs};
+pub use format::{format_field_defs, format_field_defs_tree, format_field_defs_with_docs};
+pub use types::{
+    BYTE_WIDTH_METADATA_KEY, DOC_METADATA_KEY, DataTypeDef, ElementDef, FieldDef, FieldDefs,
+    KEY_METADATA_KEY, MAX_LEN_METADATA_KEY, TIMESTAMP_METADATA_KEY, UNIT_METADATA_KEY,
+    UUID_METADATA_KEY,
+};
@@ -1,12 +1,42 @@
 use std::{
+    collections::BTreeMap,
     fmt::{Display, Formatter, Result},
     ops::Deref,
 };
 
+/// [`FieldDef::metadata`] key holding the physical unit of a field (e.g. `m/s`).
+pub const UNIT_METADATA_KEY: &str = "unit";
+
+/// [`FieldDef::metadata`] key holding the documentation of a field.
+pub const DOC_METADATA_KEY: &str = "doc";
+
+/// [`FieldDef::metadata`] key holding the declared upper bound of a bounded
+/// string or sequence field (of its elements, for fixed-size arrays).
+pub const MAX_LEN_METADATA_KEY: &str = "max_len";
+
+/// [`FieldDef::metadata`] key marking a field as a DDS instance key (`@key`),
+/// with the value `"true"`.
+pub const KEY_METADATA_KEY: &str = "key";
+
+/// [`FieldDef::metadata`] key holding the length shared by every value of a
+/// `bytes` field; such fields become Arrow `FixedSizeBinary` columns.
+pub const BYTE_WIDTH_METADATA_KEY: &str = "byte_width";
+
+/// [`FieldDef::metadata`] key marking a 16-byte `bytes` (or a string) field
+/// as a UUID, with the value `"true"`.
+pub const UUID_METADATA_KEY: &str = "uuid";
+
+/// [`FieldDef::metadata`] key marking an `i64` field as nanoseconds since the
+/// Unix epoch, with the value `"true"`; such fields become Arrow `Timestamp`
+/// columns. Struct fields marked with it are points in time split into
+/// seconds and nanoseconds (e.g. `builtin_interfaces/msg/Time`), and keep
+/// their struct type.
+pub const TIMESTAMP_METADATA_KEY: &str = "timestamp";
+
 /// Arrow-independent data type definition for schema intermediate representation.
 ///
 /// Variant names mirror [`Value`](crate::Value) for consistency (values ↔ types).
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum DataTypeDef {
     Null,
     Bool,
@@ -76,7 +106,7 @@ impl Display for DataTypeDef {
 }
 
 /// Typed collection of [`FieldDef`] used for schema bodies and struct members.
-#[derive(Debug, Clone, PartialEq, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
 pub struct FieldDefs(pub Vec<FieldDef>);
 
 impl FieldDefs {
@@ -131,7 +161,7 @@ impl Display for FieldDefs {
 }
 
 /// Arrow-independent nested element definition used in composite types.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ElementDef {
     pub data_type: DataTypeDef,
     pub nullable: bool,
@@ -157,10 +187,13 @@ impl Display for ElementDef {
 }
 
 /// Arrow-independent field definition for schema intermediate representation.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct FieldDef {
     pub name: String,
     pub element: ElementDef,
+    /// Key/value annotations taken from the schema source, exported as Arrow
+    /// field metadata.
+    pub metadata: BTreeMap<String, String>,
 }
 
 impl FieldDef {
@@ -168,6 +201,55 @@ impl FieldDef {
         Self {
             name: name.into(),
             element: ElementDef::new(data_type, nullable),
+            metadata: BTreeMap::new(),
         }
     }
+
+    /// Add a metadata entry.
+    pub fn with_metadata(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.metadata.insert(key.into(), value.into());
+        self
+    }
+
+    /// Physical unit of the field, if the schema declares one.
+    pub fn unit(&self) -> Option<&str> {
+        self.metadata.get(UNIT_METADATA_KEY).map(String::as_str)
+    }
+
+    /// Documentation of the field, if the schema declares one.
+    pub fn doc(&self) -> Option<&str> {
+        self.metadata.get(DOC_METADATA_KEY).map(String::as_str)
+    }
+
+    /// Declared upper bound of a bounded string or sequence field.
+    pub fn max_len(&self) -> Option<usize> {
+        self.metadata.get(MAX_LEN_METADATA_KEY)?.parse().ok()
+    }
+
+    /// Whether the field is part of the instance key of its message.
+    pub fn is_key(&self) -> bool {
+        self.metadata
+            .get(KEY_METADATA_KEY)
+            .is_some_and(|v| v == "true")
+    }
+
+    /// Length of every value of a fixed-size `bytes` field.
+    pub fn byte_width(&self) -> Option<usize> {
+        self.metadata.get(BYTE_WIDTH_METADATA_KEY)?.parse().ok()
+    }
+
+    /// Whether the field holds UUIDs.
+    pub fn is_uuid(&self) -> bool {
+        self.metadata
+            .get(UUID_METADATA_KEY)
+            .is_some_and(|v| v == "true")
+    }
+
+    /// Whether the field holds a point in time: nanoseconds since the Unix
+    /// epoch, or a struct of seconds and nanoseconds.
+    pub fn is_timestamp(&self) -> bool {
+        self.metadata
+            .get(TIMESTAMP_METADATA_KEY)
+            .is_some_and(|v| v == "true")
+    }
 }
@@ -0,0 +1,15 @@
+//! Timestamp arithmetic that stays within `i64` nanoseconds.
+//!
+//! MCAP timestamps are `u64` nanoseconds, while Arrow timestamps and
+//! durations are `i64`. These helpers return `None` instead of wrapping or
+//! panicking when a value does not fit.
+
+/// Convert an MCAP timestamp to `i64` nanoseconds.
+pub fn timestamp_to_i64(time_ns: u64) -> Option<i64> {
+    i64::try_from(time_ns).ok()
+}
+
+/// Nanoseconds from `start_ns` to `time_ns`; negative if `time_ns` is earlier.
+pub fn elapsed_ns(time_ns: u64, start_ns: u64) -> Option<i64> {
+    i64::try_from(i128::from(time_ns) - i128::from(start_ns)).ok()
+}
@@ -1,11 +1,23 @@
 //! Type-safe intermediate representation produced by message decoders.
 
-use std::sync::Arc;
+use std::{
+    hash::{Hash, Hasher},
+    sync::Arc,
+};
 
 use crate::error::ValueTypeError;
 
 /// Value produced by message decoders.
 /// All types are explicit; no lossy conversions.
+///
+/// Equality and hashing compare variants strictly (`I32(1) != I64(1)`).
+/// Floats compare numerically, except that all NaNs are equal to each other,
+/// which makes `Value` usable as `Eq`/`Hash` key. Map entries are compared
+/// in order; see [`Value::eq_ignoring_map_order`].
+///
+/// With the `serde` feature, values serialize as their plain contents:
+/// scalars as scalars, structs, lists and arrays as sequences (struct
+/// members carry no names), and maps as maps.
 #[derive(Debug, Clone)]
 pub enum Value {
     Null,
@@ -137,11 +149,42 @@ impl Value {
         }
     }
 
+    /// Like `==`, but compares the entries of maps (at any depth) as
+    /// unordered collections.
+    pub fn eq_ignoring_map_order(&self, other: &Value) -> bool {
+        match (self, other) {
+            (Value::Struct(a), Value::Struct(b))
+            | (Value::List(a), Value::List(b))
+            | (Value::Array(a), Value::Array(b)) => {
+                a.len() == b.len() && a.iter().zip(b).all(|(a, b)| a.eq_ignoring_map_order(b))
+            }
+            (Value::Map(a), Value::Map(b)) => {
+                if a.len() != b.len() {
+                    return false;
+                }
+                let mut matched = vec![false; b.len()];
+                a.iter().all(|(key, value)| {
+                    let found = b.iter().zip(matched.iter_mut()).find(|((k, v), used)| {
+                        !**used && key.eq_ignoring_map_order(k) && value.eq_ignoring_map_order(v)
+                    });
+                    match found {
+                        Some((_, used)) => {
+                            *used = true;
+                            true
+                        }
+                        None => false,
+                    }
+                })
+            }
+            _ => self == other,
+        }
+    }
+
     pub fn type_mismatch(&self, expected: impl Into<String>) -> ValueTypeError {
         ValueTypeError::new(expected, self.variant_name())
     }
 
-    fn variant_name(&self) -> &'static str {
+    pub(crate) fn variant_name(&self) -> &'static str {
         match self {
             Value::Null => "Null",
             Value::Bool(_) => "Bool",
@@ -164,3 +207,101 @@ impl Value {
         }
     }
 }
+
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Value::Null, Value::Null) => true,
+            (Value::Bool(a), Value::Bool(b)) => a == b,
+            (Value::I8(a), Value::I8(b)) => a == b,
+            (Value::I16(a), Value::I16(b)) => a == b,
+            (Value::I32(a), Value::I32(b)) => a == b,
+            (Value::I64(a), Value::I64(b)) => a == b,
+            (Value::U8(a), Value::U8(b)) => a == b,
+            (Value::U16(a), Value::U16(b)) => a == b,
+            (Value::U32(a), Value::U32(b)) => a == b,
+            (Value::U64(a), Value::U64(b)) => a == b,
+            (Value::F32(a), Value::F32(b)) => a == b || (a.is_nan() && b.is_nan()),
+            (Value::F64(a), Value::F64(b)) => a == b || (a.is_nan() && b.is_nan()),
+            (Value::String(a), Value::String(b)) => a == b,
+            (Value::Bytes(a), Value::Bytes(b)) => a == b,
+            (Value::Struct(a), Value::Struct(b))
+            | (Value::List(a), Value::List(b))
+            | (Value::Array(a), Value::Array(b)) => a == b,
+            (Value::Map(a), Value::Map(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Value {}
+
+impl Hash for Value {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Value::Null => {}
+            Value::Bool(v) => v.hash(state),
+            Value::I8(v) => v.hash(state),
+            Value::I16(v) => v.hash(state),
+            Value::I32(v) => v.hash(state),
+            Value::I64(v) => v.hash(state),
+            Value::U8(v) => v.hash(state),
+            Value::U16(v) => v.hash(state),
+            Value::U32(v) => v.hash(state),
+            Value::U64(v) => v.hash(state),
+            Value::F32(v) => canonical_f64_bits(f64::from(*v)).hash(state),
+            Value::F64(v) => canonical_f64_bits(*v).hash(state),
+            Value::String(v) => v.hash(state),
+            Value::Bytes(v) => v.hash(state),
+            Value::Struct(v) | Value::List(v) | Value::Array(v) => v.hash(state),
+            Value::Map(v) => v.hash(state),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Value {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap as _;
+
+        match self {
+            Value::Null => serializer.serialize_none(),
+            Value::Bool(v) => serializer.serialize_bool(*v),
+            Value::I8(v) => serializer.serialize_i8(*v),
+            Value::I16(v) => serializer.serialize_i16(*v),
+            Value::I32(v) => serializer.serialize_i32(*v),
+            Value::I64(v) => serializer.serialize_i64(*v),
+            Value::U8(v) => serializer.serialize_u8(*v),
+            Value::U16(v) => serializer.serialize_u16(*v),
+            Value::U32(v) => serializer.serialize_u32(*v),
+            Value::U64(v) => serializer.serialize_u64(*v),
+            Value::F32(v) => serializer.serialize_f32(*v),
+            Value::F64(v) => serializer.serialize_f64(*v),
+            Value::String(v) => serializer.serialize_str(v),
+            Value::Bytes(v) => serializer.serialize_bytes(v),
+            Value::Struct(items) | Value::List(items) | Value::Array(items) => {
+                serializer.collect_seq(items)
+            }
+            Value::Map(entries) => {
+                let mut map = serializer.serialize_map(Some(entries.len()))?;
+                for (key, value) in entries {
+                    map.serialize_entry(key, value)?;
+                }
+                map.end()
+            }
+        }
+    }
+}
+
+/// Bits of `v` with all NaNs and both zeros mapped to one representation,
+/// so that values equal under [`PartialEq`] hash alike.
+fn canonical_f64_bits(v: f64) -> u64 {
+    if v.is_nan() {
+        f64::NAN.to_bits()
+    } else if v == 0.0 {
+        0.0f64.to_bits()
+    } else {
+        v.to_bits()
+    }
+}
@@ -0,0 +1,142 @@
+//! Conversions from [`Value`] to common Rust types.
+//!
+//! Conversions are strict like the `try_*` accessors: the variant must match
+//! exactly (`F64` for `f64`, ...) and `Null` is rejected.
+
+use std::collections::HashMap;
+
+use crate::{error::ValueTypeError, schema::FieldDefs, value::Value};
+
+macro_rules! impl_scalar_try_from {
+    ($($ty:ty => $method:ident, $expected:literal;)*) => {
+        $(
+            impl TryFrom<&Value> for $ty {
+                type Error = ValueTypeError;
+
+                fn try_from(value: &Value) -> Result<Self, Self::Error> {
+                    value.$method()?.ok_or_else(|| value.type_mismatch($expected))
+                }
+            }
+        )*
+    };
+}
+
+impl_scalar_try_from! {
+    bool => try_bool, "Bool";
+    i8 => try_i8, "I8";
+    i16 => try_i16, "I16";
+    i32 => try_i32, "I32";
+    i64 => try_i64, "I64";
+    u8 => try_u8, "U8";
+    u16 => try_u16, "U16";
+    u32 => try_u32, "U32";
+    u64 => try_u64, "U64";
+    f32 => try_f32, "F32";
+    f64 => try_f64, "F64";
+}
+
+impl TryFrom<&Value> for String {
+    type Error = ValueTypeError;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        value
+            .try_str()?
+            .map(str::to_string)
+            .ok_or_else(|| value.type_mismatch("String"))
+    }
+}
+
+/// Converts the items of a `List` or `Array` (e.g. `Vec<f64>`).
+impl<T> TryFrom<&Value> for Vec<T>
+where
+    T: for<'a> TryFrom<&'a Value, Error = ValueTypeError>,
+{
+    type Error = ValueTypeError;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::List(items) | Value::Array(items) => items.iter().map(T::try_from).collect(),
+            _ => Err(value.type_mismatch("List or Array")),
+        }
+    }
+}
+
+/// Converts a `Map` with `String` keys.
+impl TryFrom<&Value> for HashMap<String, Value> {
+    type Error = ValueTypeError;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Map(entries) => entries
+                .iter()
+                .map(|(key, value)| Ok((String::try_from(key)?, value.clone())))
+                .collect(),
+            _ => Err(value.type_mismatch("Map")),
+        }
+    }
+}
+
+macro_rules! impl_tuple_try_from {
+    ($len:literal: $($name:ident),+) => {
+        /// Converts the items of a `Struct`, `List` or `Array` with exactly
+        /// as many items as the tuple.
+        impl<$($name),+> TryFrom<&Value> for ($($name,)+)
+        where
+            $($name: for<'a> TryFrom<&'a Value, Error = ValueTypeError>,)+
+        {
+            type Error = ValueTypeError;
+
+            fn try_from(value: &Value) -> Result<Self, Self::Error> {
+                let items = value.items_of_len($len)?;
+                let mut items = items.iter();
+                Ok(($($name::try_from(items.next().expect("length checked"))?,)+))
+            }
+        }
+    };
+}
+
+impl_tuple_try_from!(2: A, B);
+impl_tuple_try_from!(3: A, B, C);
+impl_tuple_try_from!(4: A, B, C, D);
+
+impl Value {
+    /// Pair the members of a `Struct` value with the names in `field_defs`.
+    ///
+    /// `field_defs` must describe this struct, e.g. the
+    /// [`TopicDecoder::field_defs`](crate::TopicDecoder::field_defs) of the
+    /// decoder that produced a root value.
+    pub fn to_struct_fields<'a>(
+        &'a self,
+        field_defs: &'a FieldDefs,
+    ) -> Result<HashMap<&'a str, &'a Value>, ValueTypeError> {
+        match self {
+            Value::Struct(values) if values.len() == field_defs.len() => Ok(field_defs
+                .iter()
+                .map(|field| field.name.as_str())
+                .zip(values)
+                .collect()),
+            Value::Struct(values) => Err(ValueTypeError::new(
+                format!("Struct(length={})", field_defs.len()),
+                format!("Struct(length={})", values.len()),
+            )),
+            _ => Err(self.type_mismatch("Struct")),
+        }
+    }
+
+    fn items_of_len(&self, len: usize) -> Result<&[Value], ValueTypeError> {
+        match self {
+            Value::Struct(items) | Value::List(items) | Value::Array(items)
+                if items.len() == len =>
+            {
+                Ok(items)
+            }
+            Value::Struct(items) | Value::List(items) | Value::Array(items) => {
+                Err(ValueTypeError::new(
+                    format!("{len} items"),
+                    format!("{}(length={})", self.variant_name(), items.len()),
+                ))
+            }
+            _ => Err(self.type_mismatch(format!("{len} items"))),
+        }
+    }
+}
@@ -0,0 +1,55 @@
+//! Non-fatal problems reported by decoders while decoding a message.
+//!
+//! [`TopicDecoder::decode`](crate::TopicDecoder::decode) returns only the
+//! decoded value, so decoders report warnings with [`warn`] to a collector
+//! installed on the decoding thread by [`collect_warnings`]. Warnings
+//! reported while no collector is installed are dropped.
+
+use std::{cell::RefCell, fmt};
+
+/// A value a decoder could decode, but only by guessing or dropping data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodeWarning {
+    /// Field path, or type name when the path is unknown (e.g. `pose.mode`).
+    pub path: String,
+    /// What was wrong and how it was decoded (e.g. `unknown enum value 7`).
+    pub detail: String,
+}
+
+impl DecodeWarning {
+    pub fn new(path: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            detail: detail.into(),
+        }
+    }
+}
+
+impl fmt::Display for DecodeWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.detail)
+    }
+}
+
+thread_local! {
+    static COLLECTOR: RefCell<Option<Vec<DecodeWarning>>> = const { RefCell::new(None) };
+}
+
+/// Report `warning` to the collector of the current thread, if any.
+pub fn warn(warning: DecodeWarning) {
+    COLLECTOR.with(|collector| {
+        if let Some(warnings) = collector.borrow_mut().as_mut() {
+            warnings.push(warning);
+        }
+    });
+}
+
+/// Run `f` and return its result with the warnings it reported with [`warn`].
+///
+/// Calls may nest; warnings go to the innermost collector only.
+pub fn collect_warnings<R>(f: impl FnOnce() -> R) -> (R, Vec<DecodeWarning>) {
+    let outer = COLLECTOR.with(|collector| collector.replace(Some(Vec::new())));
+    let result = f();
+    let warnings = COLLECTOR.with(|collector| collector.replace(outer));
+    (result, warnings.unwrap_or_default())
+}
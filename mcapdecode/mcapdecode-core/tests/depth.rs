@@ -0,0 +1,162 @@
+use std::sync::Arc;
+
+use mcapdecode_core::{
+    DataTypeDef, DecoderError, DepthLimitedDecoder, ElementDef, FieldDef, FieldDefs, TopicDecoder,
+    Value, format_field_defs, limit_field_defs_depth,
+};
+
+/// `pose { position { x, y }, tags: map<string, f64> }, points: list<struct { x }>, id`
+fn nested_field_defs() -> FieldDefs {
+    let position = DataTypeDef::Struct(
+        vec![
+            FieldDef::new("x", DataTypeDef::F64, false),
+            FieldDef::new("y", DataTypeDef::F64, false),
+        ]
+        .into(),
+    );
+    let pose = DataTypeDef::Struct(
+        vec![
+            FieldDef::new("position", position, true),
+            FieldDef::new(
+                "tags",
+                DataTypeDef::Map {
+                    key: Box::new(ElementDef::new(DataTypeDef::String, false)),
+                    value: Box::new(ElementDef::new(DataTypeDef::F64, false)),
+                },
+                false,
+            ),
+        ]
+        .into(),
+    );
+    let point = DataTypeDef::Struct(vec![FieldDef::new("x", DataTypeDef::I32, false)].into());
+    vec![
+        FieldDef::new("pose", pose, false),
+        FieldDef::new(
+            "points",
+            DataTypeDef::List(Box::new(ElementDef::new(point, false))),
+            false,
+        ),
+        FieldDef::new("id", DataTypeDef::String, false),
+    ]
+    .into()
+}
+
+fn nested_value() -> Value {
+    Value::Struct(vec![
+        Value::Struct(vec![
+            Value::Struct(vec![Value::F64(1.5), Value::F64(f64::NAN)]),
+            Value::Map(vec![(Value::string("a\"b"), Value::F64(2.0))]),
+        ]),
+        Value::List(vec![Value::Struct(vec![Value::I32(7)])]),
+        Value::string("id-1"),
+    ])
+}
+
+struct FixedDecoder {
+    field_defs: FieldDefs,
+    value: Value,
+}
+
+impl TopicDecoder for FixedDecoder {
+    fn decode(&self, _message_data: &[u8]) -> Result<Value, DecoderError> {
+        Ok(self.value.clone())
+    }
+
+    fn field_defs(&self) -> &FieldDefs {
+        &self.field_defs
+    }
+}
+
+fn depth_limited(max_depth: usize) -> DepthLimitedDecoder {
+    let inner = FixedDecoder {
+        field_defs: nested_field_defs(),
+        value: nested_value(),
+    };
+    DepthLimitedDecoder::new(Box::new(inner), max_depth)
+}
+
+#[test]
+fn limit_field_defs_depth_replaces_deep_structs_with_strings() -> Result<(), std::fmt::Error> {
+    let limited = limit_field_defs_depth(&nested_field_defs(), 2);
+    let expected = "\
+pose: struct
+    position: optional string
+    tags: map
+        key: string
+        value: f64
+points: list
+    item: struct
+        x: i32
+id: string
+";
+    assert_eq!(format_field_defs(&limited)?, expected);
+    Ok(())
+}
+
+#[test]
+fn limit_field_defs_depth_keeps_shallow_schemas() {
+    let fields = nested_field_defs();
+    assert_eq!(limit_field_defs_depth(&fields, 3), fields);
+}
+
+#[test]
+fn depth_limited_decoder_renders_deep_structs_as_json() {
+    let decoder = depth_limited(2);
+    let Value::Struct(fields) = decoder.decode(b"").unwrap() else {
+        panic!("expected struct value");
+    };
+    let Value::Struct(pose) = &fields[0] else {
+        panic!("expected pose struct");
+    };
+    match &pose[0] {
+        Value::String(json) => assert_eq!(&**json, r#"{"x":1.5,"y":null}"#),
+        other => panic!("unexpected position value: {other:?}"),
+    }
+    assert!(matches!(&fields[1], Value::List(items) if matches!(items[0], Value::Struct(_))));
+}
+
+#[test]
+fn depth_limited_decoder_treats_zero_as_top_level_only() {
+    let decoder = depth_limited(0);
+    assert!(matches!(
+        decoder.field_defs()[0].element.data_type,
+        DataTypeDef::String
+    ));
+
+    let Value::Struct(fields) = decoder.decode(b"").unwrap() else {
+        panic!("expected struct value");
+    };
+    match (&fields[0], &fields[1]) {
+        (Value::String(pose), Value::List(points)) => {
+            assert_eq!(
+                &**pose,
+                r#"{"position":{"x":1.5,"y":null},"tags":{"a\"b":2}}"#
+            );
+            assert!(matches!(&points[0], Value::String(p) if &**p == r#"{"x":7}"#));
+        }
+        other => panic!("unexpected values: {other:?}"),
+    }
+}
+
+#[test]
+fn shared_decoder_is_limited_in_batches_across_threads() {
+    let shared = Arc::new(FixedDecoder {
+        field_defs: nested_field_defs(),
+        value: nested_value(),
+    });
+    let expected = format!("{:?}", depth_limited(1).decode(b"").unwrap());
+
+    std::thread::scope(|scope| {
+        for _ in 0..2 {
+            let decoder = DepthLimitedDecoder::new(Box::new(Arc::clone(&shared)), 1);
+            let expected = &expected;
+            scope.spawn(move || {
+                let values = decoder.decode_batch(&[b"", b""]).unwrap();
+                assert_eq!(values.len(), 2);
+                for value in values {
+                    assert_eq!(format!("{value:?}"), *expected);
+                }
+            });
+        }
+    });
+}
@@ -0,0 +1,73 @@
+use mcapdecode_core::{DecoderError, ErrorCategory, SchemaSyntaxError};
+
+#[test]
+fn decoder_error_schema_variants_are_schema_issues() {
+    let parse = DecoderError::SchemaParse {
+        schema_name: "pkg/msg/Foo".to_string(),
+        source: "unexpected token".into(),
+    };
+    let invalid = DecoderError::SchemaInvalid {
+        schema_name: "pkg/msg/Foo".to_string(),
+        detail: "missing type".to_string(),
+    };
+
+    assert_eq!(parse.code(), "schema_parse");
+    assert_eq!(invalid.code(), "schema_invalid");
+    assert_eq!(parse.category(), ErrorCategory::SchemaIssue);
+    assert_eq!(invalid.category(), ErrorCategory::SchemaIssue);
+    assert!(!parse.is_retryable());
+}
+
+#[test]
+fn decoder_error_message_decode_is_payload_issue() {
+    let err = DecoderError::MessageDecode {
+        schema_name: "pkg/msg/Foo".to_string(),
+        source: "truncated buffer".into(),
+    };
+
+    assert_eq!(err.code(), "message_decode");
+    assert_eq!(err.category(), ErrorCategory::PayloadIssue);
+    assert!(!err.is_retryable());
+}
+
+#[test]
+fn decoder_error_schema_resolve_is_retryable() {
+    let err = DecoderError::SchemaResolve {
+        schema_name: "pkg.Foo".to_string(),
+        source: "connection refused".into(),
+    };
+
+    assert_eq!(err.code(), "schema_resolve");
+    assert_eq!(err.category(), ErrorCategory::SchemaIssue);
+    assert!(err.is_retryable());
+    assert_eq!(
+        err.to_string(),
+        "failed to resolve schema 'pkg.Foo': connection refused"
+    );
+}
+
+#[test]
+fn error_category_display_is_stable() {
+    assert_eq!(ErrorCategory::SchemaIssue.to_string(), "schema");
+    assert_eq!(ErrorCategory::PayloadIssue.as_str(), "payload");
+    assert_eq!(ErrorCategory::IoIssue.as_str(), "io");
+    assert_eq!(ErrorCategory::ConfigIssue.as_str(), "config");
+}
+
+#[test]
+fn schema_syntax_error_renders_caret_under_column() {
+    let err = SchemaSyntaxError::new(3, 11, "\tint32 x y;", "unexpected trailing characters")
+        .with_section("pkg/msg/Foo");
+
+    assert_eq!(
+        err.to_string(),
+        "while parsing section 'pkg/msg/Foo': parse error at line 3, column 11: \
+         unexpected trailing characters"
+    );
+    assert_eq!(
+        err.render_diagnostic(),
+        "error: unexpected trailing characters\n \
+         --> pkg/msg/Foo:3:11\n  |\n\
+         3 | \tint32 x y;\n  | \t         ^"
+    );
+}
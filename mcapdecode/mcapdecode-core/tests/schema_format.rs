@@ -1,4 +1,7 @@
-use mcapdecode_core::{DataTypeDef, ElementDef, FieldDef, FieldDefs, format_field_defs};
+use mcapdecode_core::{
+    DOC_METADATA_KEY, DataTypeDef, ElementDef, FieldDef, FieldDefs, MAX_LEN_METADATA_KEY,
+    UNIT_METADATA_KEY, format_field_defs, format_field_defs_tree, format_field_defs_with_docs,
+};
 
 #[test]
 fn nested_struct_keeps_compact_type_labels_and_indentation() -> Result<(), std::fmt::Error> {
@@ -96,6 +99,50 @@ field_root: struct
     Ok(())
 }
 
+#[test]
+fn field_units_are_rendered_after_the_name() -> Result<(), std::fmt::Error> {
+    let fields = vec![
+        FieldDef::new("speed", DataTypeDef::F64, false).with_metadata(UNIT_METADATA_KEY, "m/s"),
+        FieldDef::new("gear", DataTypeDef::I32, false),
+    ];
+
+    let text = format_field_defs(&fields)?;
+    assert_eq!(text, "speed [m/s]: f64\ngear: i32\n");
+    assert_eq!(fields[0].unit(), Some("m/s"));
+    Ok(())
+}
+
+#[test]
+fn field_docs_are_rendered_as_comments_when_requested() -> Result<(), std::fmt::Error> {
+    let fields = vec![
+        FieldDef::new(
+            "pose",
+            DataTypeDef::Struct(
+                vec![
+                    FieldDef::new("x", DataTypeDef::F64, false)
+                        .with_metadata(DOC_METADATA_KEY, "Forward offset\n\nfrom origin"),
+                ]
+                .into(),
+            ),
+            false,
+        )
+        .with_metadata(DOC_METADATA_KEY, "Vehicle pose"),
+    ];
+
+    assert_eq!(format_field_defs(&fields)?, "pose: struct\n    x: f64\n");
+    let text = format_field_defs_with_docs(&fields)?;
+    let expected = "\
+# Vehicle pose
+pose: struct
+    # Forward offset
+    #
+    # from origin
+    x: f64
+";
+    assert_eq!(text, expected);
+    Ok(())
+}
+
 #[test]
 fn field_defs_display_matches_formatter() -> Result<(), std::fmt::Error> {
     let fields: FieldDefs = vec![FieldDef::new("field_a", DataTypeDef::I32, false)].into();
@@ -119,3 +166,72 @@ fn element_display_prefixes_optional() {
         "array[4]"
     );
 }
+
+#[test]
+fn tree_format_shows_nesting_types_nullability_and_bounds() -> Result<(), std::fmt::Error> {
+    let position = DataTypeDef::Struct(
+        vec![
+            FieldDef::new("x", DataTypeDef::F64, false).with_metadata(UNIT_METADATA_KEY, "m"),
+            FieldDef::new("y", DataTypeDef::F64, false),
+        ]
+        .into(),
+    );
+    let fields: FieldDefs = vec![
+        FieldDef::new(
+            "pose",
+            DataTypeDef::Struct(
+                vec![
+                    FieldDef::new("position", position, true),
+                    FieldDef::new(
+                        "tags",
+                        DataTypeDef::Map {
+                            key: Box::new(ElementDef::new(DataTypeDef::String, false)),
+                            value: Box::new(ElementDef::new(DataTypeDef::F64, true)),
+                        },
+                        false,
+                    ),
+                ]
+                .into(),
+            ),
+            false,
+        ),
+        FieldDef::new(
+            "points",
+            DataTypeDef::List(Box::new(ElementDef::new(
+                DataTypeDef::Struct(vec![FieldDef::new("z", DataTypeDef::I32, false)].into()),
+                false,
+            ))),
+            false,
+        )
+        .with_metadata(MAX_LEN_METADATA_KEY, "10"),
+        FieldDef::new(
+            "lookup",
+            DataTypeDef::Map {
+                key: Box::new(ElementDef::new(DataTypeDef::U32, false)),
+                value: Box::new(ElementDef::new(
+                    DataTypeDef::Array(Box::new(ElementDef::new(DataTypeDef::F32, false)), 3),
+                    false,
+                )),
+            },
+            false,
+        ),
+        FieldDef::new("label", DataTypeDef::String, true),
+    ]
+    .into();
+
+    let expected = "\
+pose: struct
+├── position: struct?
+│   ├── x [m]: f64
+│   └── y: f64
+└── tags: map<string, f64?>
+points: list<struct> (max_len 10)
+└── z: i32
+lookup: map<u32, array[3]<f32>>
+├── key: u32
+└── value: array[3]<f32>
+label: string?
+";
+    assert_eq!(format_field_defs_tree(&fields)?, expected);
+    Ok(())
+}
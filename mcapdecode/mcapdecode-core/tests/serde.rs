@@ -0,0 +1,35 @@
+#![cfg(feature = "serde")]
+
+use mcapdecode_core::{DecodedMessage, Value};
+
+#[test]
+fn value_serializes_as_plain_json() {
+    let value = Value::Struct(vec![
+        Value::I32(-3),
+        Value::F64(1.5),
+        Value::string("a"),
+        Value::Null,
+        Value::List(vec![Value::U8(1), Value::U8(2)]),
+        Value::Map(vec![(Value::string("k"), Value::Bool(true))]),
+        Value::Bytes(vec![7, 8].into()),
+    ]);
+
+    assert_eq!(
+        serde_json::to_string(&value).unwrap(),
+        r#"[-3,1.5,"a",null,[1,2],{"k":true},[7,8]]"#
+    );
+}
+
+#[test]
+fn decoded_message_serializes_with_timestamps() {
+    let message = DecodedMessage {
+        log_time: 10,
+        publish_time: 9,
+        value: Value::Struct(vec![Value::F32(f32::NAN)]),
+    };
+
+    assert_eq!(
+        serde_json::to_string(&message).unwrap(),
+        r#"{"log_time":10,"publish_time":9,"value":[null]}"#
+    );
+}
@@ -0,0 +1,17 @@
+use mcapdecode_core::{elapsed_ns, timestamp_to_i64};
+
+#[test]
+fn timestamp_to_i64_rejects_values_beyond_i64() {
+    assert_eq!(timestamp_to_i64(42), Some(42));
+    assert_eq!(timestamp_to_i64(i64::MAX as u64), Some(i64::MAX));
+    assert_eq!(timestamp_to_i64(i64::MAX as u64 + 1), None);
+}
+
+#[test]
+fn elapsed_ns_is_signed_and_range_checked() {
+    assert_eq!(elapsed_ns(1_500, 1_000), Some(500));
+    assert_eq!(elapsed_ns(1_000, 1_500), Some(-500));
+    assert_eq!(elapsed_ns(u64::MAX, u64::MAX - 1), Some(1));
+    assert_eq!(elapsed_ns(u64::MAX, 0), None);
+    assert_eq!(elapsed_ns(0, u64::MAX), None);
+}
@@ -0,0 +1,11 @@
+use mcapdecode_core::format_uuid;
+
+#[test]
+fn format_uuid_renders_canonical_lowercase_form() {
+    let bytes: Vec<u8> = (0..16).map(|b| b * 17).collect();
+    assert_eq!(
+        format_uuid(&bytes).as_deref(),
+        Some("00112233-4455-6677-8899-aabbccddeeff")
+    );
+    assert_eq!(format_uuid(&bytes[..15]), None);
+}
@@ -0,0 +1,71 @@
+use std::collections::HashMap;
+
+use mcapdecode_core::{DataTypeDef, FieldDef, FieldDefs, Value};
+
+#[test]
+fn vec_try_from_converts_list_and_array_items() {
+    let list = Value::List(vec![Value::F64(1.0), Value::F64(2.5)]);
+    assert_eq!(Vec::<f64>::try_from(&list).unwrap(), vec![1.0, 2.5]);
+
+    let array = Value::Array(vec![Value::string("a"), Value::string("b")]);
+    assert_eq!(Vec::<String>::try_from(&array).unwrap(), vec!["a", "b"]);
+
+    let mixed = Value::List(vec![Value::F64(1.0), Value::F32(2.0)]);
+    assert_eq!(
+        Vec::<f64>::try_from(&mixed).unwrap_err().to_string(),
+        "expected F64, got F32"
+    );
+    assert_eq!(
+        Vec::<f64>::try_from(&Value::Null).unwrap_err().to_string(),
+        "expected List or Array, got Null"
+    );
+}
+
+#[test]
+fn scalar_try_from_rejects_null() {
+    assert_eq!(i32::try_from(&Value::I32(7)).unwrap(), 7);
+    assert_eq!(
+        u64::try_from(&Value::Null).unwrap_err().to_string(),
+        "expected U64, got Null"
+    );
+}
+
+#[test]
+fn hash_map_try_from_requires_string_keys() {
+    let map = Value::Map(vec![(Value::string("x"), Value::I16(3))]);
+    let converted = HashMap::<String, Value>::try_from(&map).unwrap();
+    assert_eq!(converted["x"], Value::I16(3));
+
+    let int_keys = Value::Map(vec![(Value::U8(1), Value::Null)]);
+    assert!(HashMap::<String, Value>::try_from(&int_keys).is_err());
+}
+
+#[test]
+fn tuple_try_from_checks_item_count() {
+    let point = Value::Struct(vec![Value::F64(1.0), Value::F64(2.0), Value::string("p")]);
+    let (x, y, name): (f64, f64, String) = (&point).try_into().unwrap();
+    assert_eq!((x, y, name.as_str()), (1.0, 2.0, "p"));
+
+    let err = <(f64, f64)>::try_from(&point).unwrap_err();
+    assert_eq!(err.to_string(), "expected 2 items, got Struct(length=3)");
+}
+
+#[test]
+fn to_struct_fields_names_struct_members() {
+    let field_defs: FieldDefs = vec![
+        FieldDef::new("x", DataTypeDef::F64, false),
+        FieldDef::new("tags", DataTypeDef::String, true),
+    ]
+    .into();
+    let value = Value::Struct(vec![Value::F64(0.5), Value::Null]);
+
+    let fields = value.to_struct_fields(&field_defs).unwrap();
+    assert_eq!(fields["x"].try_f64().unwrap(), Some(0.5));
+    assert_eq!(fields["tags"], &Value::Null);
+
+    let short = Value::Struct(vec![Value::F64(0.5)]);
+    assert_eq!(
+        short.to_struct_fields(&field_defs).unwrap_err().to_string(),
+        "expected Struct(length=2), got Struct(length=1)"
+    );
+}
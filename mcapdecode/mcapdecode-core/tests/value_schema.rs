@@ -1,5 +1,16 @@
+use std::{
+    collections::HashSet,
+    hash::{DefaultHasher, Hash, Hasher},
+};
+
 use mcapdecode_core::{DataTypeDef, ElementDef, FieldDef, Value};
 
+fn hash_of(value: &Value) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
 #[test]
 fn value_string_creates_arc_str_value() {
     let value = Value::string("hello");
@@ -23,3 +34,58 @@ fn element_def_new_sets_all_fields() {
     assert!(matches!(element.data_type, DataTypeDef::I32));
     assert!(element.nullable);
 }
+
+#[test]
+fn value_equality_is_strict_about_variants() {
+    assert_eq!(Value::I32(1), Value::I32(1));
+    assert_ne!(Value::I32(1), Value::I64(1));
+    assert_ne!(Value::List(vec![]), Value::Array(vec![]));
+    assert_eq!(
+        Value::Struct(vec![Value::string("a"), Value::Null]),
+        Value::Struct(vec![Value::string("a"), Value::Null])
+    );
+}
+
+#[test]
+fn value_float_equality_treats_nans_as_equal() {
+    assert_eq!(Value::F64(f64::NAN), Value::F64(-f64::NAN));
+    assert_eq!(Value::F32(0.0), Value::F32(-0.0));
+    assert_ne!(Value::F64(1.0), Value::F64(f64::NAN));
+    assert_eq!(
+        hash_of(&Value::F64(f64::NAN)),
+        hash_of(&Value::F64(-f64::NAN))
+    );
+    assert_eq!(hash_of(&Value::F32(0.0)), hash_of(&Value::F32(-0.0)));
+}
+
+#[test]
+fn value_can_be_used_as_hash_set_key() {
+    let values: HashSet<Value> = [
+        Value::List(vec![Value::F64(1.5), Value::string("x")]),
+        Value::List(vec![Value::F64(1.5), Value::string("x")]),
+        Value::List(vec![Value::F64(2.5)]),
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(values.len(), 2);
+}
+
+#[test]
+fn value_map_comparison_can_ignore_entry_order() {
+    let a = Value::Struct(vec![Value::Map(vec![
+        (Value::string("x"), Value::I32(1)),
+        (Value::string("y"), Value::I32(2)),
+    ])]);
+    let b = Value::Struct(vec![Value::Map(vec![
+        (Value::string("y"), Value::I32(2)),
+        (Value::string("x"), Value::I32(1)),
+    ])]);
+    let c = Value::Struct(vec![Value::Map(vec![
+        (Value::string("x"), Value::I32(1)),
+        (Value::string("x"), Value::I32(1)),
+    ])]);
+
+    assert_ne!(a, b);
+    assert!(a.eq_ignoring_map_order(&b));
+    assert!(!a.eq_ignoring_map_order(&c));
+}
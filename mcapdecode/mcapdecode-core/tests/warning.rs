@@ -0,0 +1,22 @@
+use mcapdecode_core::{DecodeWarning, collect_warnings, warn};
+
+#[test]
+fn warnings_go_to_the_innermost_collector() {
+    warn(DecodeWarning::new("dropped", "no collector"));
+
+    let ((), outer) = collect_warnings(|| {
+        warn(DecodeWarning::new("a", "outer"));
+        let ((), inner) = collect_warnings(|| warn(DecodeWarning::new("b", "inner")));
+        assert_eq!(inner, vec![DecodeWarning::new("b", "inner")]);
+        warn(DecodeWarning::new("c", "outer again"));
+    });
+
+    assert_eq!(
+        outer,
+        vec![
+            DecodeWarning::new("a", "outer"),
+            DecodeWarning::new("c", "outer again"),
+        ]
+    );
+    assert_eq!(collect_warnings(|| ()).1, Vec::new());
+}
@@ -0,0 +1,147 @@
+//! Bounds-checked reader over a message payload.
+
+/// Byte order of multi-byte values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Endian {
+    Little,
+    Big,
+}
+
+/// Failure to read from a [`ByteCursor`]; the cursor is left where the
+/// failed read started.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum CursorError {
+    /// Fewer than `needed` bytes are left after `offset`.
+    #[error("unexpected end of data at byte {offset} ({needed} bytes needed)")]
+    UnexpectedEof { offset: usize, needed: usize },
+    /// The string starting at `offset` is not valid UTF-8.
+    #[error("invalid UTF-8 at byte {offset}")]
+    InvalidUtf8 { offset: usize },
+}
+
+impl CursorError {
+    /// Offset of the failed read in the payload.
+    pub fn offset(&self) -> usize {
+        match self {
+            Self::UnexpectedEof { offset, .. } | Self::InvalidUtf8 { offset } => *offset,
+        }
+    }
+}
+
+macro_rules! read_numbers {
+    ($($method:ident => $ty:ty;)*) => {
+        $(
+            #[doc = concat!("Read a `", stringify!($ty), "` in the cursor's byte order.")]
+            pub fn $method(&mut self) -> Result<$ty, CursorError> {
+                let bytes = self.take(size_of::<$ty>())?.try_into().unwrap();
+                Ok(match self.endian {
+                    Endian::Little => <$ty>::from_le_bytes(bytes),
+                    Endian::Big => <$ty>::from_be_bytes(bytes),
+                })
+            }
+        )*
+    };
+}
+
+/// Reads primitives from a payload, tracking the offset for error messages
+/// and alignment.
+///
+/// Reads never align implicitly; call [`ByteCursor::align`] first for
+/// encodings with naturally aligned values such as CDR.
+#[derive(Debug, Clone)]
+pub struct ByteCursor<'a> {
+    data: &'a [u8],
+    pos: usize,
+    align_base: usize,
+    endian: Endian,
+}
+
+impl<'a> ByteCursor<'a> {
+    pub fn new(data: &'a [u8], endian: Endian) -> Self {
+        Self {
+            data,
+            pos: 0,
+            align_base: 0,
+            endian,
+        }
+    }
+
+    pub fn endian(&self) -> Endian {
+        self.endian
+    }
+
+    /// Change the byte order, e.g. after reading an encapsulation header.
+    pub fn set_endian(&mut self, endian: Endian) {
+        self.endian = endian;
+    }
+
+    /// Make alignment relative to `offset`, e.g. the end of a CDR
+    /// encapsulation header.
+    pub fn set_align_base(&mut self, offset: usize) {
+        self.align_base = offset;
+    }
+
+    /// Offset of the next byte to read.
+    pub fn offset(&self) -> usize {
+        self.pos
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.data.len() - self.pos
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.remaining() == 0
+    }
+
+    /// Skip padding up to the next multiple of `n` bytes from the align base.
+    pub fn align(&mut self, n: usize) -> Result<(), CursorError> {
+        let pad = (n - (self.pos - self.align_base) % n) % n;
+        self.skip(pad)
+    }
+
+    pub fn skip(&mut self, n: usize) -> Result<(), CursorError> {
+        self.take(n).map(|_| ())
+    }
+
+    /// Read the next `n` bytes without copying them.
+    pub fn take(&mut self, n: usize) -> Result<&'a [u8], CursorError> {
+        if self.remaining() < n {
+            return Err(CursorError::UnexpectedEof {
+                offset: self.pos,
+                needed: n,
+            });
+        }
+        let bytes = &self.data[self.pos..self.pos + n];
+        self.pos += n;
+        Ok(bytes)
+    }
+
+    /// Read the next `n` bytes as a UTF-8 string.
+    pub fn read_str(&mut self, n: usize) -> Result<&'a str, CursorError> {
+        let offset = self.pos;
+        let bytes = self.take(n)?;
+        std::str::from_utf8(bytes).map_err(|_| {
+            self.pos = offset;
+            CursorError::InvalidUtf8 { offset }
+        })
+    }
+
+    /// Read a byte as a `bool`, any non-zero value being `true`.
+    pub fn read_bool(&mut self) -> Result<bool, CursorError> {
+        Ok(self.read_u8()? != 0)
+    }
+
+    read_numbers! {
+        read_u8 => u8;
+        read_i8 => i8;
+        read_u16 => u16;
+        read_i16 => i16;
+        read_u32 => u32;
+        read_i32 => i32;
+        read_u64 => u64;
+        read_i64 => i64;
+        read_f32 => f32;
+        read_f64 => f64;
+    }
+}
@@ -0,0 +1,52 @@
+//! [`TopicDecoder`] backed by a decode function.
+
+use std::error::Error;
+
+use mcapdecode_core::{DecoderError, FieldDefs, TopicDecoder, Value};
+
+/// [`TopicDecoder`] that decodes each payload with `decode`, reporting its
+/// errors as [`DecoderError::MessageDecode`] of `schema_name`.
+pub struct FnTopicDecoder<F> {
+    schema_name: String,
+    field_defs: FieldDefs,
+    decode: F,
+}
+
+impl<F, E> FnTopicDecoder<F>
+where
+    F: Fn(&[u8]) -> Result<Value, E> + Send + Sync,
+    E: Into<Box<dyn Error + Send + Sync>>,
+{
+    pub fn new(schema_name: impl Into<String>, field_defs: FieldDefs, decode: F) -> Self {
+        Self {
+            schema_name: schema_name.into(),
+            field_defs,
+            decode,
+        }
+    }
+}
+
+impl<F, E> TopicDecoder for FnTopicDecoder<F>
+where
+    F: Fn(&[u8]) -> Result<Value, E> + Send + Sync,
+    E: Into<Box<dyn Error + Send + Sync>>,
+{
+    fn decode(&self, message_data: &[u8]) -> Result<Value, DecoderError> {
+        (self.decode)(message_data).map_err(|e| DecoderError::MessageDecode {
+            schema_name: self.schema_name.clone(),
+            source: e.into(),
+        })
+    }
+
+    fn field_defs(&self) -> &FieldDefs {
+        &self.field_defs
+    }
+}
+
+/// [`DecoderError::SchemaInvalid`] for schema data a decoder cannot use.
+pub fn invalid_schema(schema_name: &str, detail: impl Into<String>) -> DecoderError {
+    DecoderError::SchemaInvalid {
+        schema_name: schema_name.to_string(),
+        detail: detail.into(),
+    }
+}
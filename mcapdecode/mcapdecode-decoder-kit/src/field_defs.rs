@@ -0,0 +1,113 @@
+//! Builder for [`FieldDefs`].
+
+use mcapdecode_core::{
+    DOC_METADATA_KEY, DataTypeDef, ElementDef, FieldDef, FieldDefs, UNIT_METADATA_KEY,
+};
+
+/// Builds the [`FieldDefs`] of a message, one field per call:
+///
+/// ```
+/// use mcapdecode_core::DataTypeDef;
+/// use mcapdecode_decoder_kit::FieldDefsBuilder;
+///
+/// let fields = FieldDefsBuilder::new()
+///     .field("id", DataTypeDef::U32)
+///     .field("range", DataTypeDef::F32)
+///     .unit("m")
+///     .nullable("label", DataTypeDef::String)
+///     .array("position", DataTypeDef::F64, 3)
+///     .nested("status", |s| s.field("code", DataTypeDef::U8))
+///     .build();
+/// assert_eq!(fields[1].unit(), Some("m"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct FieldDefsBuilder {
+    fields: Vec<FieldDef>,
+}
+
+impl FieldDefsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a non-nullable field.
+    pub fn field(self, name: impl Into<String>, data_type: DataTypeDef) -> Self {
+        self.push(FieldDef::new(name, data_type, false))
+    }
+
+    /// Add a field that may be [`Value::Null`](mcapdecode_core::Value::Null).
+    pub fn nullable(self, name: impl Into<String>, data_type: DataTypeDef) -> Self {
+        self.push(FieldDef::new(name, data_type, true))
+    }
+
+    /// Add a variable-length list of non-nullable `elem`s.
+    pub fn list(self, name: impl Into<String>, elem: DataTypeDef) -> Self {
+        self.field(name, list_of(elem))
+    }
+
+    /// Add a fixed-length array of `len` non-nullable `elem`s.
+    pub fn array(self, name: impl Into<String>, elem: DataTypeDef, len: usize) -> Self {
+        self.field(name, array_of(elem, len))
+    }
+
+    /// Add a non-nullable struct field whose fields are added by `build`.
+    pub fn nested(
+        self,
+        name: impl Into<String>,
+        build: impl FnOnce(FieldDefsBuilder) -> FieldDefsBuilder,
+    ) -> Self {
+        let fields = build(FieldDefsBuilder::new()).build();
+        self.field(name, DataTypeDef::Struct(fields))
+    }
+
+    /// Add a metadata entry to the last added field.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no field was added yet.
+    pub fn metadata(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        let field = self
+            .fields
+            .last_mut()
+            .expect("metadata must follow a field");
+        field.metadata.insert(key.into(), value.into());
+        self
+    }
+
+    /// Set the physical unit of the last added field.
+    pub fn unit(self, unit: impl Into<String>) -> Self {
+        self.metadata(UNIT_METADATA_KEY, unit)
+    }
+
+    /// Set the documentation of the last added field.
+    pub fn doc(self, doc: impl Into<String>) -> Self {
+        self.metadata(DOC_METADATA_KEY, doc)
+    }
+
+    pub fn build(self) -> FieldDefs {
+        self.fields.into()
+    }
+
+    fn push(mut self, field: FieldDef) -> Self {
+        self.fields.push(field);
+        self
+    }
+}
+
+/// Variable-length list of non-nullable `elem`s.
+pub fn list_of(elem: DataTypeDef) -> DataTypeDef {
+    DataTypeDef::List(Box::new(ElementDef::new(elem, false)))
+}
+
+/// Fixed-length array of `len` non-nullable `elem`s.
+pub fn array_of(elem: DataTypeDef, len: usize) -> DataTypeDef {
+    DataTypeDef::Array(Box::new(ElementDef::new(elem, false)), len)
+}
+
+/// Map with non-nullable keys and values.
+pub fn map_of(key: DataTypeDef, value: DataTypeDef) -> DataTypeDef {
+    DataTypeDef::Map {
+        key: Box::new(ElementDef::new(key, false)),
+        value: Box::new(ElementDef::new(value, false)),
+    }
+}
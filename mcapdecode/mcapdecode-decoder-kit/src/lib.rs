@@ -0,0 +1,82 @@
+//! Building blocks for [`MessageDecoder`](mcapdecode_core::MessageDecoder)s
+//! of custom binary encodings.
+//!
+//! - [`ByteCursor`]: bounds-checked little- or big-endian reads with
+//!   alignment, reporting the offset of failures.
+//! - [`FieldDefsBuilder`]: the [`FieldDefs`](mcapdecode_core::FieldDefs) of
+//!   a message, field by field.
+//! - [`IntoValue`] and [`struct_value!`]: decoded Rust values into a
+//!   [`Value`](mcapdecode_core::Value).
+//! - [`FnTopicDecoder`]: a topic decoder from a decode function.
+//!
+//! A decoder for a fixed little-endian record:
+//!
+//! ```
+//! use mcapdecode_core::{
+//!     DataTypeDef, DecoderError, EncodingKey, MessageDecoder, MessageEncoding, SchemaEncoding,
+//!     TopicDecoder,
+//! };
+//! use mcapdecode_decoder_kit::{
+//!     ByteCursor, CursorError, Endian, FieldDefsBuilder, FnTopicDecoder, struct_value,
+//! };
+//!
+//! struct RangeDecoder;
+//!
+//! impl MessageDecoder for RangeDecoder {
+//!     fn encoding_key(&self) -> EncodingKey {
+//!         EncodingKey::new(
+//!             SchemaEncoding::Unknown("acme-range".into()),
+//!             MessageEncoding::Unknown("acme".into()),
+//!         )
+//!     }
+//!
+//!     fn build_topic_decoder(
+//!         &self,
+//!         schema_name: &str,
+//!         _schema_data: &[u8],
+//!     ) -> Result<Box<dyn TopicDecoder>, DecoderError> {
+//!         let fields = FieldDefsBuilder::new()
+//!             .field("id", DataTypeDef::U16)
+//!             .list("ranges", DataTypeDef::F32)
+//!             .unit("m")
+//!             .build();
+//!         Ok(Box::new(FnTopicDecoder::new(
+//!             schema_name,
+//!             fields,
+//!             |data: &[u8]| {
+//!                 let mut cursor = ByteCursor::new(data, Endian::Little);
+//!                 let id = cursor.read_u16()?;
+//!                 let count = cursor.read_u8()?;
+//!                 let ranges = (0..count)
+//!                     .map(|_| cursor.read_f32())
+//!                     .collect::<Result<Vec<_>, CursorError>>()?;
+//!                 Ok::<_, CursorError>(struct_value![id, ranges])
+//!             },
+//!         )))
+//!     }
+//! }
+//!
+//! let decoder = RangeDecoder.build_topic_decoder("Range", &[]).unwrap();
+//! let mut payload = vec![7, 0, 1];
+//! payload.extend(1.5f32.to_le_bytes());
+//! assert_eq!(
+//!     decoder.decode(&payload).unwrap(),
+//!     struct_value![7u16, vec![1.5f32]]
+//! );
+//! assert!(decoder.decode(&[7]).is_err());
+//! ```
+
+mod cursor;
+mod decoder;
+mod field_defs;
+mod value;
+
+pub use cursor::{ByteCursor, CursorError, Endian};
+pub use decoder::{FnTopicDecoder, invalid_schema};
+pub use field_defs::{FieldDefsBuilder, array_of, list_of, map_of};
+pub use value::IntoValue;
+
+#[doc(hidden)]
+pub mod __private {
+    pub use mcapdecode_core::Value;
+}
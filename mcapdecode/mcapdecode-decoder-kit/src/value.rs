@@ -0,0 +1,83 @@
+//! Conversions of Rust values into [`Value`].
+
+use mcapdecode_core::Value;
+
+/// Conversion into the [`Value`] variant of the same type.
+///
+/// `Vec`s become lists and arrays fixed-length arrays; for byte strings use
+/// [`Value::Bytes`] directly.
+pub trait IntoValue {
+    fn into_value(self) -> Value;
+}
+
+macro_rules! impl_into_value {
+    ($($ty:ty => $variant:ident;)*) => {
+        $(
+            impl IntoValue for $ty {
+                fn into_value(self) -> Value {
+                    Value::$variant(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_into_value! {
+    bool => Bool;
+    i8 => I8;
+    i16 => I16;
+    i32 => I32;
+    i64 => I64;
+    u8 => U8;
+    u16 => U16;
+    u32 => U32;
+    u64 => U64;
+    f32 => F32;
+    f64 => F64;
+}
+
+impl IntoValue for Value {
+    fn into_value(self) -> Value {
+        self
+    }
+}
+
+impl IntoValue for &str {
+    fn into_value(self) -> Value {
+        Value::string(self)
+    }
+}
+
+impl IntoValue for String {
+    fn into_value(self) -> Value {
+        Value::String(self.into())
+    }
+}
+
+/// `None` becomes [`Value::Null`].
+impl<T: IntoValue> IntoValue for Option<T> {
+    fn into_value(self) -> Value {
+        self.map_or(Value::Null, IntoValue::into_value)
+    }
+}
+
+impl<T: IntoValue> IntoValue for Vec<T> {
+    fn into_value(self) -> Value {
+        Value::List(self.into_iter().map(IntoValue::into_value).collect())
+    }
+}
+
+impl<T: IntoValue, const N: usize> IntoValue for [T; N] {
+    fn into_value(self) -> Value {
+        Value::Array(self.into_iter().map(IntoValue::into_value).collect())
+    }
+}
+
+/// Build a [`Value::Struct`] from field values in schema order:
+/// `struct_value![id, range, Some("label"), [x, y, z]]`.
+#[macro_export]
+macro_rules! struct_value {
+    ($($value:expr),* $(,)?) => {
+        $crate::__private::Value::Struct(vec![$($crate::IntoValue::into_value($value)),*])
+    };
+}
@@ -0,0 +1,91 @@
+use mcapdecode_core::{DataTypeDef, DecoderError, ElementDef, TopicDecoder, Value};
+use mcapdecode_decoder_kit::{
+    ByteCursor, CursorError, Endian, FieldDefsBuilder, FnTopicDecoder, map_of, struct_value,
+};
+
+#[test]
+fn cursor_reads_aligned_values_in_both_byte_orders() {
+    let data = [0xAA, 0x01, 0x00, 0x00, 0x00, 0x00, 0x02, b'h', b'i'];
+    let mut cursor = ByteCursor::new(&data, Endian::Big);
+    cursor.set_align_base(1);
+    assert_eq!(cursor.read_u8(), Ok(0xAA));
+    assert_eq!(cursor.read_u16(), Ok(0x0100));
+    cursor.align(4).unwrap();
+    assert_eq!(cursor.offset(), 5);
+
+    cursor.set_endian(Endian::Little);
+    assert_eq!(cursor.read_u16(), Ok(0x0200));
+    assert_eq!(cursor.read_str(2), Ok("hi"));
+    assert!(cursor.is_empty());
+}
+
+#[test]
+fn cursor_errors_leave_the_position_unchanged() {
+    let mut cursor = ByteCursor::new(&[0x01, 0xFF, 0xFE], Endian::Little);
+    cursor.skip(1).unwrap();
+    assert_eq!(
+        cursor.read_u32(),
+        Err(CursorError::UnexpectedEof {
+            offset: 1,
+            needed: 4
+        })
+    );
+    assert_eq!(
+        cursor.read_str(2),
+        Err(CursorError::InvalidUtf8 { offset: 1 })
+    );
+    assert_eq!(cursor.remaining(), 2);
+}
+
+#[test]
+fn builder_and_values_describe_nested_messages() {
+    let fields = FieldDefsBuilder::new()
+        .field("id", DataTypeDef::U32)
+        .doc("Sensor id")
+        .nullable("label", DataTypeDef::String)
+        .nested("pose", |p| p.array("xyz", DataTypeDef::F64, 3))
+        .field("attrs", map_of(DataTypeDef::String, DataTypeDef::I64))
+        .build();
+    assert_eq!(fields[0].doc(), Some("Sensor id"));
+    assert!(fields[1].element.nullable);
+    let DataTypeDef::Struct(pose) = &fields[2].element.data_type else {
+        panic!("pose is not a struct");
+    };
+    assert_eq!(
+        pose[0].element.data_type,
+        DataTypeDef::Array(Box::new(ElementDef::new(DataTypeDef::F64, false)), 3)
+    );
+
+    assert_eq!(
+        struct_value![1u32, None::<String>, struct_value![[0.0, 1.0, 2.0]]],
+        Value::Struct(vec![
+            Value::U32(1),
+            Value::Null,
+            Value::Struct(vec![Value::Array(vec![
+                Value::F64(0.0),
+                Value::F64(1.0),
+                Value::F64(2.0),
+            ])]),
+        ])
+    );
+}
+
+#[test]
+fn fn_topic_decoder_reports_message_decode_errors() {
+    let fields = FieldDefsBuilder::new().field("x", DataTypeDef::I16).build();
+    let decoder = FnTopicDecoder::new("Point", fields, |data: &[u8]| {
+        let x = ByteCursor::new(data, Endian::Little).read_i16()?;
+        Ok::<_, CursorError>(struct_value![x])
+    });
+    assert_eq!(decoder.decode(&[0xFF, 0xFF]).unwrap(), struct_value![-1i16]);
+
+    let Err(DecoderError::MessageDecode {
+        schema_name,
+        source,
+    }) = decoder.decode(&[0x01])
+    else {
+        panic!("truncated payload should fail to decode");
+    };
+    assert_eq!(schema_name, "Point");
+    assert!(source.downcast_ref::<CursorError>().is_some());
+}
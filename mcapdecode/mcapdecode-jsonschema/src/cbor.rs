@@ -0,0 +1,135 @@
+//! CBOR (RFC 8949) parser.
+//!
+//! Tags are skipped and their content kept as is, and `undefined` and
+//! unassigned simple values read as `null`.
+
+use crate::{
+    error::PayloadError,
+    item::{ByteReader, Item, MAX_NESTING},
+};
+
+const BREAK: u8 = 0xFF;
+
+pub(crate) fn read_item(r: &mut ByteReader<'_>, depth: usize) -> Result<Item, PayloadError> {
+    if depth > MAX_NESTING {
+        return Err(r.error(format!("nesting deeper than {MAX_NESTING}")));
+    }
+    let initial = r.u8()?;
+    let major = initial >> 5;
+    let info = initial & 0x1F;
+
+    if major == 7 {
+        return match info {
+            20 => Ok(Item::Bool(false)),
+            21 => Ok(Item::Bool(true)),
+            0..=19 | 22 | 23 => Ok(Item::Null),
+            24 => r.u8().map(|_| Item::Null),
+            25 => Ok(Item::Float(f16_to_f64(r.u16()?))),
+            26 => Ok(Item::Float(f32::from_bits(r.u32()?).into())),
+            27 => Ok(Item::Float(f64::from_bits(r.u64()?))),
+            _ => Err(r.error(format!("unexpected simple value {info}"))),
+        };
+    }
+
+    if info == 31 {
+        return match major {
+            2 => indefinite_string(r, major).map(Item::Bytes),
+            3 => {
+                let bytes = indefinite_string(r, major)?;
+                String::from_utf8(bytes)
+                    .map(Item::Text)
+                    .map_err(|_| r.error("string is not valid UTF-8"))
+            }
+            4 => {
+                let mut items = Vec::new();
+                while r.peek()? != BREAK {
+                    items.push(read_item(r, depth + 1)?);
+                }
+                r.u8()?;
+                Ok(Item::Array(items))
+            }
+            5 => {
+                let mut entries = Vec::new();
+                while r.peek()? != BREAK {
+                    let key = read_item(r, depth + 1)?;
+                    entries.push((key, read_item(r, depth + 1)?));
+                }
+                r.u8()?;
+                Ok(Item::Map(entries))
+            }
+            _ => Err(r.error(format!("indefinite length for major type {major}"))),
+        };
+    }
+
+    let arg = argument(r, info)?;
+    match major {
+        0 => Ok(Item::Int(arg.into())),
+        1 => Ok(Item::Int(-1 - i128::from(arg))),
+        2 => {
+            let len = r.len(arg)?;
+            Ok(Item::Bytes(r.take(len)?.to_vec()))
+        }
+        3 => {
+            let len = r.len(arg)?;
+            r.text(len).map(Item::Text)
+        }
+        4 => {
+            let len = r.len(arg)?;
+            (0..len)
+                .map(|_| read_item(r, depth + 1))
+                .collect::<Result<_, _>>()
+                .map(Item::Array)
+        }
+        5 => {
+            let len = r.len(arg)?;
+            let mut entries = Vec::with_capacity(len);
+            for _ in 0..len {
+                let key = read_item(r, depth + 1)?;
+                entries.push((key, read_item(r, depth + 1)?));
+            }
+            Ok(Item::Map(entries))
+        }
+        // Tag: the tagged item follows.
+        _ => read_item(r, depth + 1),
+    }
+}
+
+fn argument(r: &mut ByteReader<'_>, info: u8) -> Result<u64, PayloadError> {
+    match info {
+        0..=23 => Ok(info.into()),
+        24 => r.u8().map(Into::into),
+        25 => r.u16().map(Into::into),
+        26 => r.u32().map(Into::into),
+        27 => r.u64(),
+        _ => Err(r.error(format!("reserved additional information {info}"))),
+    }
+}
+
+/// Concatenate the definite-length chunks of an indefinite-length string.
+fn indefinite_string(r: &mut ByteReader<'_>, major: u8) -> Result<Vec<u8>, PayloadError> {
+    let mut bytes = Vec::new();
+    loop {
+        let initial = r.u8()?;
+        if initial == BREAK {
+            return Ok(bytes);
+        }
+        if initial >> 5 != major || initial & 0x1F == 31 {
+            return Err(r.error("invalid chunk of an indefinite-length string"));
+        }
+        let len = argument(r, initial & 0x1F)?;
+        let len = r.len(len)?;
+        bytes.extend_from_slice(r.take(len)?);
+    }
+}
+
+fn f16_to_f64(bits: u16) -> f64 {
+    let sign = if bits & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exponent = i32::from((bits >> 10) & 0x1F);
+    let mantissa = f64::from(bits & 0x3FF);
+    sign * match exponent {
+        0 => mantissa * 2f64.powi(-24),
+        31 if mantissa == 0.0 => f64::INFINITY,
+        31 => f64::NAN,
+        _ => (1.0 + mantissa / 1024.0) * 2f64.powi(exponent - 15),
+    }
+}
@@ -0,0 +1,123 @@
+//! Matching parsed payloads against the field definitions of a schema.
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+use mcapdecode_core::{DataTypeDef, ElementDef, FieldDefs, Value};
+
+use crate::{error::PayloadError, item::Item};
+
+/// Convert the root map of a payload into a struct of `fields`.
+pub(crate) fn root_to_value(item: Item, fields: &FieldDefs) -> Result<Value, PayloadError> {
+    struct_to_value(item, fields, "")
+}
+
+fn struct_to_value(item: Item, fields: &FieldDefs, path: &str) -> Result<Value, PayloadError> {
+    let Item::Map(entries) = item else {
+        return Err(mismatch(path, "map", &item));
+    };
+    let mut values = vec![Value::Null; fields.len()];
+    for (key, item) in entries {
+        let Item::Text(key) = key else { continue };
+        let Some(index) = fields.iter().position(|field| field.name == key) else {
+            continue;
+        };
+        values[index] =
+            element_to_value(item, &fields[index].element, &key).map_err(|e| e.within(path))?;
+    }
+    for (field, value) in fields.iter().zip(&values) {
+        if !field.element.nullable && matches!(value, Value::Null) {
+            return Err(PayloadError::Missing {
+                path: join(path, &field.name),
+            });
+        }
+    }
+    Ok(Value::Struct(values))
+}
+
+fn element_to_value(item: Item, def: &ElementDef, path: &str) -> Result<Value, PayloadError> {
+    match item {
+        Item::Null if def.nullable => Ok(Value::Null),
+        Item::Null if def.data_type != DataTypeDef::Null => Err(PayloadError::Missing {
+            path: path.to_string(),
+        }),
+        item => to_value(item, &def.data_type, path),
+    }
+}
+
+fn to_value(item: Item, def: &DataTypeDef, path: &str) -> Result<Value, PayloadError> {
+    match (def, item) {
+        (DataTypeDef::Null, Item::Null) => Ok(Value::Null),
+        (DataTypeDef::Bool, Item::Bool(v)) => Ok(Value::Bool(v)),
+        (DataTypeDef::I64, Item::Int(v)) => i64::try_from(v)
+            .map(Value::I64)
+            .map_err(|_| mismatch(path, "64-bit integer", &Item::Int(v))),
+        // JSON writers may emit integral values as `1.0`.
+        (DataTypeDef::I64, Item::Float(v))
+            if v.fract() == 0.0 && (i64::MIN as f64..i64::MAX as f64).contains(&v) =>
+        {
+            Ok(Value::I64(v as i64))
+        }
+        (DataTypeDef::F64, Item::Int(v)) => Ok(Value::F64(v as f64)),
+        (DataTypeDef::F64, Item::Float(v)) => Ok(Value::F64(v)),
+        (DataTypeDef::String, Item::Text(v)) => Ok(Value::string(v)),
+        (DataTypeDef::Bytes, Item::Bytes(v)) => Ok(Value::Bytes(v.into())),
+        (DataTypeDef::Bytes, Item::Text(v)) => STANDARD
+            .decode(&v)
+            .map(|bytes| Value::Bytes(bytes.into()))
+            .map_err(|_| mismatch(path, "base64 string", &Item::Text(v))),
+        (DataTypeDef::Struct(fields), item) => struct_to_value(item, fields, path),
+        (DataTypeDef::List(elem), Item::Array(items)) => items
+            .into_iter()
+            .map(|item| element_to_value(item, elem, path))
+            .collect::<Result<_, _>>()
+            .map(Value::List),
+        (DataTypeDef::Map { value, .. }, Item::Map(entries)) => entries
+            .into_iter()
+            .map(|(key, item)| match key {
+                Item::Text(key) => {
+                    let item = element_to_value(item, value, path)?;
+                    Ok((Value::string(key), item))
+                }
+                key => Err(mismatch(path, "string key", &key)),
+            })
+            .collect::<Result<_, _>>()
+            .map(Value::Map),
+        (def, item) => Err(mismatch(path, def.type_name(), &item)),
+    }
+}
+
+fn mismatch(path: &str, expected: &'static str, found: &Item) -> PayloadError {
+    PayloadError::Mismatch {
+        path: path.to_string(),
+        expected,
+        found: found.kind(),
+    }
+}
+
+fn join(parent: &str, child: &str) -> String {
+    if parent.is_empty() {
+        child.to_string()
+    } else {
+        format!("{parent}.{child}")
+    }
+}
+
+impl PayloadError {
+    /// Prefix the field path of the error with its parent `path`.
+    fn within(self, parent: &str) -> Self {
+        match self {
+            Self::Mismatch {
+                path,
+                expected,
+                found,
+            } => Self::Mismatch {
+                path: join(parent, &path),
+                expected,
+                found,
+            },
+            Self::Missing { path } => Self::Missing {
+                path: join(parent, &path),
+            },
+            err @ Self::Malformed { .. } => err,
+        }
+    }
+}
@@ -0,0 +1,26 @@
+//! Error type for malformed payloads.
+
+use crate::item::PayloadFormat;
+
+/// Failure to decode a JSON, CBOR or MessagePack payload.
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum PayloadError {
+    /// The payload is not valid in its encoding at byte `offset`.
+    #[error("invalid {format} at byte {offset}: {detail}")]
+    Malformed {
+        format: PayloadFormat,
+        offset: usize,
+        detail: String,
+    },
+    /// A value does not match the JSON Schema of the channel at the field
+    /// `path`.
+    #[error("field '{path}': expected {expected}, found {found}")]
+    Mismatch {
+        path: String,
+        expected: &'static str,
+        found: &'static str,
+    },
+    /// A field required by the JSON Schema is missing or `null`.
+    #[error("field '{path}' is required")]
+    Missing { path: String },
+}
@@ -0,0 +1,193 @@
+//! Data model shared by the CBOR, MessagePack and JSON parsers.
+
+use std::fmt;
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+use serde_json::{Map, Number};
+
+use crate::error::PayloadError;
+
+/// Encoding of the message payloads of a channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PayloadFormat {
+    /// CBOR (RFC 8949), message encoding `cbor`.
+    Cbor,
+    /// MessagePack, message encoding `msgpack`.
+    MsgPack,
+    /// JSON text, message encoding `json`.
+    Json,
+}
+
+impl PayloadFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Cbor => "cbor",
+            Self::MsgPack => "msgpack",
+            Self::Json => "json",
+        }
+    }
+
+    /// Parse a whole payload, rejecting trailing bytes.
+    pub(crate) fn parse(self, data: &[u8]) -> Result<Item, PayloadError> {
+        let mut reader = ByteReader::new(self, data);
+        let item = match self {
+            Self::Cbor => crate::cbor::read_item(&mut reader, 0)?,
+            Self::MsgPack => crate::msgpack::read_item(&mut reader, 0)?,
+            // serde_json rejects trailing bytes itself.
+            Self::Json => return crate::json::parse(data),
+        };
+        if reader.pos != data.len() {
+            return Err(reader.error("trailing bytes after the value"));
+        }
+        Ok(item)
+    }
+}
+
+impl fmt::Display for PayloadFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Value of the JSON data model shared by CBOR, MessagePack and JSON, before
+/// it is matched against a schema.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Item {
+    Null,
+    Bool(bool),
+    /// Wide enough for both `u64` and CBOR's negative integers down to
+    /// `-2^64`.
+    Int(i128),
+    Float(f64),
+    Text(String),
+    Bytes(Vec<u8>),
+    Array(Vec<Item>),
+    Map(Vec<(Item, Item)>),
+}
+
+impl Item {
+    pub(crate) fn kind(&self) -> &'static str {
+        match self {
+            Item::Null => "null",
+            Item::Bool(_) => "boolean",
+            Item::Int(_) => "integer",
+            Item::Float(_) => "number",
+            Item::Text(_) => "string",
+            Item::Bytes(_) => "bytes",
+            Item::Array(_) => "array",
+            Item::Map(_) => "map",
+        }
+    }
+
+    /// Convert to JSON: byte strings become base64 strings, non-string map
+    /// keys their JSON text, and non-finite floats `null`.
+    pub(crate) fn to_json(&self) -> serde_json::Value {
+        use serde_json::Value as Json;
+        match self {
+            Item::Null => Json::Null,
+            Item::Bool(v) => Json::Bool(*v),
+            Item::Int(v) => match (i64::try_from(*v), u64::try_from(*v)) {
+                (Ok(v), _) => Json::from(v),
+                (_, Ok(v)) => Json::from(v),
+                _ => Number::from_f64(*v as f64).map_or(Json::Null, Json::Number),
+            },
+            Item::Float(v) => Number::from_f64(*v).map_or(Json::Null, Json::Number),
+            Item::Text(v) => Json::String(v.clone()),
+            Item::Bytes(v) => Json::String(STANDARD.encode(v)),
+            Item::Array(items) => Json::Array(items.iter().map(Item::to_json).collect()),
+            Item::Map(entries) => {
+                let mut map = Map::new();
+                for (key, value) in entries {
+                    let key = match key {
+                        Item::Text(key) => key.clone(),
+                        other => other.to_json().to_string(),
+                    };
+                    map.insert(key, value.to_json());
+                }
+                Json::Object(map)
+            }
+        }
+    }
+}
+
+/// Nesting depth at which parsing stops, so that malformed payloads cannot
+/// overflow the stack.
+pub(crate) const MAX_NESTING: usize = 256;
+
+/// Big-endian cursor over a payload.
+pub(crate) struct ByteReader<'a> {
+    format: PayloadFormat,
+    data: &'a [u8],
+    pub(crate) pos: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    fn new(format: PayloadFormat, data: &'a [u8]) -> Self {
+        Self {
+            format,
+            data,
+            pos: 0,
+        }
+    }
+
+    pub(crate) fn error(&self, detail: impl Into<String>) -> PayloadError {
+        PayloadError::Malformed {
+            format: self.format,
+            offset: self.pos,
+            detail: detail.into(),
+        }
+    }
+
+    pub(crate) fn take(&mut self, len: usize) -> Result<&'a [u8], PayloadError> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|end| *end <= self.data.len())
+            .ok_or_else(|| self.error(format!("{len} bytes past the end of the payload")))?;
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    pub(crate) fn peek(&self) -> Result<u8, PayloadError> {
+        self.data
+            .get(self.pos)
+            .copied()
+            .ok_or_else(|| self.error("unexpected end of the payload"))
+    }
+
+    pub(crate) fn u8(&mut self) -> Result<u8, PayloadError> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub(crate) fn u16(&mut self) -> Result<u16, PayloadError> {
+        Ok(u16::from_be_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    pub(crate) fn u32(&mut self) -> Result<u32, PayloadError> {
+        Ok(u32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    pub(crate) fn u64(&mut self) -> Result<u64, PayloadError> {
+        Ok(u64::from_be_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    /// Length prefix of a string or container, checked against the bytes
+    /// left so that a corrupt length cannot trigger a huge allocation.
+    pub(crate) fn len(&self, len: u64) -> Result<usize, PayloadError> {
+        usize::try_from(len)
+            .ok()
+            .filter(|len| *len <= self.data.len() - self.pos)
+            .ok_or_else(|| self.error(format!("length {len} exceeds the payload")))
+    }
+
+    pub(crate) fn text(&mut self, len: usize) -> Result<String, PayloadError> {
+        let start = self.pos;
+        let bytes = self.take(len)?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| PayloadError::Malformed {
+            format: self.format,
+            offset: start,
+            detail: "string is not valid UTF-8".to_string(),
+        })
+    }
+}
@@ -0,0 +1,49 @@
+//! JSON parser.
+//!
+//! Integers read as [`Item::Int`] and other numbers as [`Item::Float`];
+//! there are no byte strings, so `bytes` fields take base64 strings.
+
+use serde_json::Value as Json;
+
+use crate::{
+    error::PayloadError,
+    item::{Item, PayloadFormat},
+};
+
+pub(crate) fn parse(data: &[u8]) -> Result<Item, PayloadError> {
+    let json: Json = serde_json::from_slice(data).map_err(|e| PayloadError::Malformed {
+        format: PayloadFormat::Json,
+        offset: offset(data, e.line(), e.column()),
+        detail: e.to_string(),
+    })?;
+    Ok(to_item(json))
+}
+
+fn to_item(json: Json) -> Item {
+    match json {
+        Json::Null => Item::Null,
+        Json::Bool(v) => Item::Bool(v),
+        Json::Number(v) => match (v.as_i64(), v.as_u64()) {
+            (Some(v), _) => Item::Int(v.into()),
+            (_, Some(v)) => Item::Int(v.into()),
+            _ => Item::Float(v.as_f64().unwrap_or(f64::NAN)),
+        },
+        Json::String(v) => Item::Text(v),
+        Json::Array(items) => Item::Array(items.into_iter().map(to_item).collect()),
+        Json::Object(map) => Item::Map(
+            map.into_iter()
+                .map(|(key, value)| (Item::Text(key), to_item(value)))
+                .collect(),
+        ),
+    }
+}
+
+/// Byte offset of the 1-based `line` and `column` serde_json reports.
+fn offset(data: &[u8], line: usize, column: usize) -> usize {
+    let line_start = data
+        .split_inclusive(|b| *b == b'\n')
+        .take(line.saturating_sub(1))
+        .map(<[u8]>::len)
+        .sum::<usize>();
+    (line_start + column.saturating_sub(1)).min(data.len())
+}
@@ -0,0 +1,154 @@
+//! JSON, CBOR and MessagePack [`MessageDecoder`] implementations for the
+//! mcapdecode pipeline.
+//!
+//! The encodings share the JSON data model, so channels are described by a
+//! JSON Schema (`schema_encoding = jsonschema`), as Foxglove's JSON
+//! channels are:
+//!
+//! - [`JsonSchemaDecoder`] converts the schema into [`FieldDefs`] with
+//!   [`jsonschema_to_field_defs`] and decodes each payload into a
+//!   [`Value::Struct`] of its properties.
+//! - [`SchemalessDecoder`] handles channels without a schema, whose shape is
+//!   unknown before reading; each payload is decoded into a single
+//!   [`JSON_FIELD`] string holding it as JSON text. Schemaless JSON
+//!   channels are already JSON text and need no decoder of this crate.
+
+mod cbor;
+mod convert;
+mod error;
+mod item;
+mod json;
+mod msgpack;
+mod schema;
+
+pub use error::PayloadError;
+pub use item::PayloadFormat;
+use mcapdecode_core::{
+    DataTypeDef, DecoderError, EncodingKey, FieldDef, FieldDefs, MessageDecoder, MessageEncoding,
+    SchemaEncoding, TopicDecoder, Value,
+};
+pub use schema::jsonschema_to_field_defs;
+
+/// Name of the single field produced by [`SchemalessDecoder`].
+pub const JSON_FIELD: &str = "json";
+
+impl From<PayloadFormat> for MessageEncoding {
+    fn from(format: PayloadFormat) -> Self {
+        match format {
+            PayloadFormat::Cbor => MessageEncoding::Cbor,
+            PayloadFormat::MsgPack => MessageEncoding::MsgPack,
+            PayloadFormat::Json => MessageEncoding::Json,
+        }
+    }
+}
+
+/// Decoder for JSON, CBOR or MessagePack channels with a JSON Schema.
+#[derive(Debug, Clone)]
+pub struct JsonSchemaDecoder {
+    format: PayloadFormat,
+}
+
+impl JsonSchemaDecoder {
+    pub fn new(format: PayloadFormat) -> Self {
+        Self { format }
+    }
+}
+
+impl MessageDecoder for JsonSchemaDecoder {
+    fn encoding_key(&self) -> EncodingKey {
+        EncodingKey::new(SchemaEncoding::JsonSchema, self.format.into())
+    }
+
+    fn build_topic_decoder(
+        &self,
+        schema_name: &str,
+        schema_data: &[u8],
+    ) -> Result<Box<dyn TopicDecoder>, DecoderError> {
+        Ok(Box::new(JsonSchemaTopicDecoder {
+            schema_name: schema_name.to_string(),
+            format: self.format,
+            field_defs: jsonschema_to_field_defs(schema_name, schema_data)?,
+        }))
+    }
+}
+
+struct JsonSchemaTopicDecoder {
+    schema_name: String,
+    format: PayloadFormat,
+    field_defs: FieldDefs,
+}
+
+impl TopicDecoder for JsonSchemaTopicDecoder {
+    fn decode(&self, message_data: &[u8]) -> Result<Value, DecoderError> {
+        self.format
+            .parse(message_data)
+            .and_then(|item| convert::root_to_value(item, &self.field_defs))
+            .map_err(|e| DecoderError::MessageDecode {
+                schema_name: self.schema_name.clone(),
+                source: Box::new(e),
+            })
+    }
+
+    fn field_defs(&self) -> &FieldDefs {
+        &self.field_defs
+    }
+}
+
+/// Decoder for CBOR or MessagePack channels without a schema, exporting each
+/// payload as JSON text in a [`JSON_FIELD`] string.
+///
+/// Byte strings become base64 strings, and map keys that are not strings
+/// their JSON text.
+#[derive(Debug, Clone)]
+pub struct SchemalessDecoder {
+    format: PayloadFormat,
+}
+
+impl SchemalessDecoder {
+    pub fn new(format: PayloadFormat) -> Self {
+        Self { format }
+    }
+}
+
+impl MessageDecoder for SchemalessDecoder {
+    fn encoding_key(&self) -> EncodingKey {
+        EncodingKey::new(SchemaEncoding::None, self.format.into())
+    }
+
+    fn build_topic_decoder(
+        &self,
+        schema_name: &str,
+        _schema_data: &[u8],
+    ) -> Result<Box<dyn TopicDecoder>, DecoderError> {
+        Ok(Box::new(SchemalessTopicDecoder {
+            schema_name: schema_name.to_string(),
+            format: self.format,
+            field_defs: vec![FieldDef::new(JSON_FIELD, DataTypeDef::String, false)].into(),
+        }))
+    }
+}
+
+struct SchemalessTopicDecoder {
+    schema_name: String,
+    format: PayloadFormat,
+    field_defs: FieldDefs,
+}
+
+impl TopicDecoder for SchemalessTopicDecoder {
+    fn decode(&self, message_data: &[u8]) -> Result<Value, DecoderError> {
+        let item = self
+            .format
+            .parse(message_data)
+            .map_err(|e| DecoderError::MessageDecode {
+                schema_name: self.schema_name.clone(),
+                source: Box::new(e),
+            })?;
+        Ok(Value::Struct(vec![Value::string(
+            item.to_json().to_string(),
+        )]))
+    }
+
+    fn field_defs(&self) -> &FieldDefs {
+        &self.field_defs
+    }
+}
@@ -0,0 +1,118 @@
+//! MessagePack parser.
+//!
+//! Extension values read as their data bytes, without the type code.
+
+use crate::{
+    error::PayloadError,
+    item::{ByteReader, Item, MAX_NESTING},
+};
+
+pub(crate) fn read_item(r: &mut ByteReader<'_>, depth: usize) -> Result<Item, PayloadError> {
+    if depth > MAX_NESTING {
+        return Err(r.error(format!("nesting deeper than {MAX_NESTING}")));
+    }
+    let marker = r.u8()?;
+    match marker {
+        0x00..=0x7F => Ok(Item::Int(marker.into())),
+        0x80..=0x8F => map(r, (marker & 0x0F).into(), depth),
+        0x90..=0x9F => array(r, (marker & 0x0F).into(), depth),
+        0xA0..=0xBF => r.text((marker & 0x1F).into()).map(Item::Text),
+        0xC0 => Ok(Item::Null),
+        0xC2 => Ok(Item::Bool(false)),
+        0xC3 => Ok(Item::Bool(true)),
+        0xC4 => {
+            let len = r.u8()?.into();
+            bytes(r, len)
+        }
+        0xC5 => {
+            let len = r.u16()?.into();
+            bytes(r, len)
+        }
+        0xC6 => {
+            let len = r.u32()?.into();
+            bytes(r, len)
+        }
+        0xC7 => {
+            let len = r.u8()?.into();
+            ext(r, len)
+        }
+        0xC8 => {
+            let len = r.u16()?.into();
+            ext(r, len)
+        }
+        0xC9 => {
+            let len = r.u32()?.into();
+            ext(r, len)
+        }
+        0xCA => Ok(Item::Float(f32::from_bits(r.u32()?).into())),
+        0xCB => Ok(Item::Float(f64::from_bits(r.u64()?))),
+        0xCC => Ok(Item::Int(r.u8()?.into())),
+        0xCD => Ok(Item::Int(r.u16()?.into())),
+        0xCE => Ok(Item::Int(r.u32()?.into())),
+        0xCF => Ok(Item::Int(r.u64()?.into())),
+        0xD0 => Ok(Item::Int((r.u8()? as i8).into())),
+        0xD1 => Ok(Item::Int((r.u16()? as i16).into())),
+        0xD2 => Ok(Item::Int((r.u32()? as i32).into())),
+        0xD3 => Ok(Item::Int((r.u64()? as i64).into())),
+        0xD4..=0xD8 => ext(r, 1 << (marker - 0xD4)),
+        0xD9 => {
+            let len = r.u8()?.into();
+            r.text(len).map(Item::Text)
+        }
+        0xDA => {
+            let len = r.u16()?.into();
+            r.text(len).map(Item::Text)
+        }
+        0xDB => {
+            let len = r.u32()?.into();
+            let len = r.len(len)?;
+            r.text(len).map(Item::Text)
+        }
+        0xDC => {
+            let len = r.u16()?.into();
+            array(r, len, depth)
+        }
+        0xDD => {
+            let len = r.u32()?.into();
+            array(r, len, depth)
+        }
+        0xDE => {
+            let len = r.u16()?.into();
+            map(r, len, depth)
+        }
+        0xDF => {
+            let len = r.u32()?.into();
+            map(r, len, depth)
+        }
+        0xE0..=0xFF => Ok(Item::Int((marker as i8).into())),
+        0xC1 => Err(r.error("reserved marker 0xc1")),
+    }
+}
+
+fn bytes(r: &mut ByteReader<'_>, len: u64) -> Result<Item, PayloadError> {
+    let len = r.len(len)?;
+    Ok(Item::Bytes(r.take(len)?.to_vec()))
+}
+
+fn ext(r: &mut ByteReader<'_>, len: u64) -> Result<Item, PayloadError> {
+    r.u8()?;
+    bytes(r, len)
+}
+
+fn array(r: &mut ByteReader<'_>, len: u64, depth: usize) -> Result<Item, PayloadError> {
+    let len = r.len(len)?;
+    (0..len)
+        .map(|_| read_item(r, depth + 1))
+        .collect::<Result<_, _>>()
+        .map(Item::Array)
+}
+
+fn map(r: &mut ByteReader<'_>, len: u64, depth: usize) -> Result<Item, PayloadError> {
+    let len = r.len(len)?;
+    let mut entries = Vec::with_capacity(len);
+    for _ in 0..len {
+        let key = read_item(r, depth + 1)?;
+        entries.push((key, read_item(r, depth + 1)?));
+    }
+    Ok(Item::Map(entries))
+}
@@ -0,0 +1,193 @@
+//! JSON Schema → [`FieldDefs`] conversion.
+
+use mcapdecode_core::{
+    DOC_METADATA_KEY, DataTypeDef, DecoderError, ElementDef, FieldDef, FieldDefs,
+    MAX_LEN_METADATA_KEY,
+};
+use serde_json::Value as Json;
+
+/// Number of `$ref`s followed on one path before a schema is considered
+/// recursive.
+const MAX_REF_DEPTH: usize = 32;
+
+/// Convert the JSON Schema `schema_data` of a channel into field
+/// definitions.
+///
+/// The root schema must describe an object.  Properties keep their order in
+/// the schema and are nullable unless listed in `required` with a type that
+/// excludes `null`:
+///
+/// | JSON Schema | [`DataTypeDef`] |
+/// | --- | --- |
+/// | `object` with `properties` | `Struct` |
+/// | `object` with only `additionalProperties` | `Map` with string keys |
+/// | `array` with `items` | `List` |
+/// | `string` | `String` (`Bytes` with `"contentEncoding": "base64"`) |
+/// | `integer` / `number` / `boolean` / `null` | `I64` / `F64` / `Bool` / `Null` |
+///
+/// Local `$ref`s (`#/$defs/...`) are followed; `description` becomes
+/// [`DOC_METADATA_KEY`] and `maxLength` / `maxItems`
+/// [`MAX_LEN_METADATA_KEY`] metadata.  Unions of several non-null types and
+/// recursive schemas are rejected.
+pub fn jsonschema_to_field_defs(
+    schema_name: &str,
+    schema_data: &[u8],
+) -> Result<FieldDefs, DecoderError> {
+    let root: Json =
+        serde_json::from_slice(schema_data).map_err(|e| DecoderError::SchemaParse {
+            schema_name: schema_name.to_string(),
+            source: Box::new(e),
+        })?;
+    let cx = SchemaContext {
+        schema_name,
+        root: &root,
+    };
+    match cx.type_def(&root, "", 0)? {
+        (DataTypeDef::Struct(fields), _) => Ok(fields),
+        _ => Err(cx.invalid("the root schema does not describe an object")),
+    }
+}
+
+struct SchemaContext<'a> {
+    schema_name: &'a str,
+    root: &'a Json,
+}
+
+impl<'a> SchemaContext<'a> {
+    fn invalid(&self, detail: impl Into<String>) -> DecoderError {
+        DecoderError::SchemaInvalid {
+            schema_name: self.schema_name.to_string(),
+            detail: detail.into(),
+        }
+    }
+
+    /// Follow the `$ref`s of `schema`, counting them in `depth`.
+    fn resolve(&self, mut schema: &'a Json, depth: &mut usize) -> Result<&'a Json, DecoderError> {
+        while let Some(reference) = schema.get("$ref") {
+            *depth += 1;
+            if *depth > MAX_REF_DEPTH {
+                return Err(self.invalid(format!("recursive $ref {reference}")));
+            }
+            schema = reference
+                .as_str()
+                .and_then(|r| r.strip_prefix('#'))
+                .and_then(|pointer| self.root.pointer(pointer))
+                .ok_or_else(|| self.invalid(format!("unresolved $ref {reference}")))?;
+        }
+        Ok(schema)
+    }
+
+    /// Data type of `schema` and whether it admits `null`.
+    fn type_def(
+        &self,
+        schema: &'a Json,
+        path: &str,
+        mut depth: usize,
+    ) -> Result<(DataTypeDef, bool), DecoderError> {
+        let schema = self.resolve(schema, &mut depth)?;
+        let (ty, nullable) = self.type_name(schema, path)?;
+        let data_type = match ty {
+            "object" => self.object_def(schema, path, depth)?,
+            "array" => {
+                let items = schema
+                    .get("items")
+                    .ok_or_else(|| self.invalid(format!("array '{path}' has no items schema")))?;
+                let (elem, elem_nullable) = self.type_def(items, path, depth)?;
+                DataTypeDef::List(Box::new(ElementDef::new(elem, elem_nullable)))
+            }
+            "string" if schema.get("contentEncoding") == Some(&Json::from("base64")) => {
+                DataTypeDef::Bytes
+            }
+            "string" => DataTypeDef::String,
+            "integer" => DataTypeDef::I64,
+            "number" => DataTypeDef::F64,
+            "boolean" => DataTypeDef::Bool,
+            "null" => DataTypeDef::Null,
+            other => return Err(self.invalid(format!("unknown type '{other}' at '{path}'"))),
+        };
+        Ok((data_type, nullable))
+    }
+
+    /// The non-null `type` of `schema`, guessed from its keywords if absent.
+    fn type_name(&self, schema: &'a Json, path: &str) -> Result<(&'a str, bool), DecoderError> {
+        let names: Vec<&str> = match schema.get("type") {
+            Some(Json::String(name)) => vec![name],
+            Some(Json::Array(names)) => names.iter().filter_map(Json::as_str).collect(),
+            Some(other) => return Err(self.invalid(format!("invalid type {other} at '{path}'"))),
+            None if schema.get("properties").is_some() => vec!["object"],
+            None if schema.get("items").is_some() => vec!["array"],
+            None if schema
+                .get("enum")
+                .and_then(Json::as_array)
+                .is_some_and(|values| values.iter().all(Json::is_string)) =>
+            {
+                vec!["string"]
+            }
+            None => return Err(self.invalid(format!("no type at '{path}'"))),
+        };
+        let nullable = names.contains(&"null");
+        let mut types: Vec<&str> = names.into_iter().filter(|name| *name != "null").collect();
+        if types.len() == 2 && types.contains(&"integer") && types.contains(&"number") {
+            types = vec!["number"];
+        }
+        match types[..] {
+            [] => Ok(("null", true)),
+            [ty] => Ok((ty, nullable)),
+            _ => Err(self.invalid(format!("union of types {} at '{path}'", types.join(", ")))),
+        }
+    }
+
+    fn object_def(
+        &self,
+        schema: &'a Json,
+        path: &str,
+        depth: usize,
+    ) -> Result<DataTypeDef, DecoderError> {
+        if let Some(properties) = schema.get("properties").and_then(Json::as_object) {
+            let required: Vec<&str> = schema
+                .get("required")
+                .and_then(Json::as_array)
+                .map(|names| names.iter().filter_map(Json::as_str).collect())
+                .unwrap_or_default();
+            let fields = properties
+                .iter()
+                .map(|(name, property)| {
+                    let field_path = if path.is_empty() {
+                        name.clone()
+                    } else {
+                        format!("{path}.{name}")
+                    };
+                    let (data_type, nullable) = self.type_def(property, &field_path, depth)?;
+                    let nullable = nullable || !required.contains(&name.as_str());
+                    let mut field = FieldDef::new(name, data_type, nullable);
+                    let mut property_depth = depth;
+                    let property = self.resolve(property, &mut property_depth)?;
+                    if let Some(doc) = property.get("description").and_then(Json::as_str) {
+                        field = field.with_metadata(DOC_METADATA_KEY, doc);
+                    }
+                    if let Some(max_len) = property
+                        .get("maxLength")
+                        .or_else(|| property.get("maxItems"))
+                        .and_then(Json::as_u64)
+                    {
+                        field = field.with_metadata(MAX_LEN_METADATA_KEY, max_len.to_string());
+                    }
+                    Ok(field)
+                })
+                .collect::<Result<Vec<_>, DecoderError>>()?;
+            return Ok(DataTypeDef::Struct(fields.into()));
+        }
+        match schema.get("additionalProperties") {
+            Some(values @ Json::Object(_)) => {
+                let (value, nullable) = self.type_def(values, path, depth)?;
+                Ok(DataTypeDef::Map {
+                    key: Box::new(ElementDef::new(DataTypeDef::String, false)),
+                    value: Box::new(ElementDef::new(value, nullable)),
+                })
+            }
+            _ => Err(self.invalid(format!(
+                "object '{path}' has neither properties nor an additionalProperties schema"
+            ))),
+        }
+    }
+}
@@ -0,0 +1,338 @@
+use mcapdecode_core::{
+    DOC_METADATA_KEY, DataTypeDef, DecoderError, ElementDef, EncodingKey, MAX_LEN_METADATA_KEY,
+    MessageDecoder, MessageEncoding, SchemaEncoding, TopicDecoder, Value,
+};
+use mcapdecode_jsonschema::{
+    JSON_FIELD, JsonSchemaDecoder, PayloadError, PayloadFormat, SchemalessDecoder,
+    jsonschema_to_field_defs,
+};
+
+const SCHEMA: &str = r##"{
+    "type": "object",
+    "properties": {
+        "name": { "type": "string", "description": "Sensor name", "maxLength": 16 },
+        "count": { "type": "integer" },
+        "temp": { "type": ["number", "null"] },
+        "ok": { "type": "boolean" },
+        "samples": { "type": "array", "items": { "type": "integer" } },
+        "raw": { "type": "string", "contentEncoding": "base64" },
+        "pos": { "$ref": "#/$defs/Vec2" },
+        "tags": { "type": "object", "additionalProperties": { "type": "string" } }
+    },
+    "required": ["name", "count", "ok"],
+    "$defs": {
+        "Vec2": {
+            "type": "object",
+            "properties": { "x": { "type": "number" }, "y": { "type": "number" } },
+            "required": ["x", "y"]
+        }
+    }
+}"##;
+
+/// Text string header followed by its bytes (short strings only).
+fn text(prefix: u8, s: &str) -> Vec<u8> {
+    let mut out = vec![prefix + s.len() as u8];
+    out.extend_from_slice(s.as_bytes());
+    out
+}
+
+fn cbor_text(s: &str) -> Vec<u8> {
+    text(0x60, s)
+}
+
+fn msgpack_text(s: &str) -> Vec<u8> {
+    text(0xA0, s)
+}
+
+fn topic_decoder(format: PayloadFormat) -> Box<dyn TopicDecoder> {
+    JsonSchemaDecoder::new(format)
+        .build_topic_decoder("sensor", SCHEMA.as_bytes())
+        .unwrap()
+}
+
+fn payload_error(err: DecoderError) -> PayloadError {
+    match err {
+        DecoderError::MessageDecode { source, .. } => {
+            source.downcast_ref::<PayloadError>().unwrap().clone()
+        }
+        other => panic!("unexpected error: {other}"),
+    }
+}
+
+#[test]
+fn jsonschema_converts_to_field_defs() {
+    let fields = jsonschema_to_field_defs("sensor", SCHEMA.as_bytes()).unwrap();
+    let summary: Vec<_> = fields
+        .iter()
+        .map(|f| (f.name.as_str(), f.element.nullable))
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            ("name", false),
+            ("count", false),
+            ("temp", true),
+            ("ok", false),
+            ("samples", true),
+            ("raw", true),
+            ("pos", true),
+            ("tags", true),
+        ]
+    );
+    assert_eq!(fields[0].metadata[DOC_METADATA_KEY], "Sensor name");
+    assert_eq!(fields[0].metadata[MAX_LEN_METADATA_KEY], "16");
+    assert_eq!(fields[1].element.data_type, DataTypeDef::I64);
+    assert_eq!(fields[2].element.data_type, DataTypeDef::F64);
+    assert_eq!(
+        fields[4].element.data_type,
+        DataTypeDef::List(Box::new(ElementDef::new(DataTypeDef::I64, false)))
+    );
+    assert_eq!(fields[5].element.data_type, DataTypeDef::Bytes);
+    let DataTypeDef::Struct(pos) = &fields[6].element.data_type else {
+        panic!("pos is not a struct");
+    };
+    assert!(pos.iter().all(|f| !f.element.nullable));
+    assert!(matches!(
+        &fields[7].element.data_type,
+        DataTypeDef::Map { key, .. } if key.data_type == DataTypeDef::String
+    ));
+}
+
+#[test]
+fn jsonschema_rejects_unions_and_recursion() {
+    let union =
+        br#"{ "type": "object", "properties": { "v": { "type": ["string", "integer"] } } }"#;
+    let err = jsonschema_to_field_defs("union", union).unwrap_err();
+    assert!(err.to_string().contains("union of types"), "{err}");
+
+    let recursive = br##"{ "$ref": "#/$defs/Node", "$defs": { "Node": {
+        "type": "object", "properties": { "next": { "$ref": "#/$defs/Node" } } } } }"##;
+    let err = jsonschema_to_field_defs("recursive", recursive).unwrap_err();
+    assert!(err.to_string().contains("recursive $ref"), "{err}");
+
+    let err = jsonschema_to_field_defs("array", br#"{ "type": "array", "items": {} }"#);
+    assert!(matches!(err, Err(DecoderError::SchemaInvalid { .. })));
+}
+
+#[test]
+fn decode_cbor_with_jsonschema() {
+    let mut payload = vec![0xA9];
+    payload.extend(cbor_text("name"));
+    payload.extend(cbor_text("imu"));
+    payload.extend(cbor_text("count"));
+    payload.push(0x21); // -2
+    payload.extend(cbor_text("temp"));
+    payload.extend([0xF9, 0x3E, 0x00]); // 1.5 as half float
+    payload.extend(cbor_text("ok"));
+    payload.push(0xF5);
+    payload.extend(cbor_text("samples"));
+    payload.extend([0x9F, 0x01, 0x02, 0xFF]); // indefinite-length array
+    payload.extend(cbor_text("raw"));
+    payload.extend([0x42, 0x01, 0x02]);
+    payload.extend(cbor_text("pos"));
+    payload.push(0xA2);
+    payload.extend(cbor_text("x"));
+    payload.push(0x01);
+    payload.extend(cbor_text("y"));
+    payload.push(0xFB);
+    payload.extend(2.5f64.to_be_bytes());
+    payload.extend(cbor_text("tags"));
+    payload.push(0xA1);
+    payload.extend(cbor_text("k"));
+    payload.extend(cbor_text("v"));
+    payload.extend(cbor_text("extra"));
+    payload.push(0xF6);
+
+    let value = topic_decoder(PayloadFormat::Cbor).decode(&payload).unwrap();
+    assert_eq!(
+        value,
+        Value::Struct(vec![
+            Value::string("imu"),
+            Value::I64(-2),
+            Value::F64(1.5),
+            Value::Bool(true),
+            Value::List(vec![Value::I64(1), Value::I64(2)]),
+            Value::Bytes(vec![1, 2].into()),
+            Value::Struct(vec![Value::F64(1.0), Value::F64(2.5)]),
+            Value::Map(vec![(Value::string("k"), Value::string("v"))]),
+        ])
+    );
+}
+
+#[test]
+fn decode_msgpack_with_jsonschema() {
+    let mut payload = vec![0x87];
+    payload.extend(msgpack_text("name"));
+    payload.extend(msgpack_text("imu"));
+    payload.extend(msgpack_text("count"));
+    payload.push(0xFE); // -2
+    payload.extend(msgpack_text("temp"));
+    payload.push(0xC0);
+    payload.extend(msgpack_text("ok"));
+    payload.push(0xC3);
+    payload.extend(msgpack_text("samples"));
+    payload.extend([0x92, 0x01, 0xCD, 0x01, 0x00]);
+    payload.extend(msgpack_text("raw"));
+    payload.extend([0xC4, 0x02, 0x01, 0x02]);
+    payload.extend(msgpack_text("pos"));
+    payload.push(0x82);
+    payload.extend(msgpack_text("x"));
+    payload.push(0xCB);
+    payload.extend(1.0f64.to_be_bytes());
+    payload.extend(msgpack_text("y"));
+    payload.push(0xCA);
+    payload.extend(2.5f32.to_be_bytes());
+
+    let value = topic_decoder(PayloadFormat::MsgPack)
+        .decode(&payload)
+        .unwrap();
+    assert_eq!(
+        value,
+        Value::Struct(vec![
+            Value::string("imu"),
+            Value::I64(-2),
+            Value::Null,
+            Value::Bool(true),
+            Value::List(vec![Value::I64(1), Value::I64(256)]),
+            Value::Bytes(vec![1, 2].into()),
+            Value::Struct(vec![Value::F64(1.0), Value::F64(2.5)]),
+            Value::Null,
+        ])
+    );
+}
+
+#[test]
+fn decode_json_with_jsonschema() {
+    let payload = br#"{
+        "name": "imu", "count": 3.0, "temp": 21.5, "ok": false,
+        "samples": [1, 18446744073709551615], "raw": "AQI=",
+        "pos": {"x": 1, "y": 2.5}, "unknown": {"nested": true}
+    }"#;
+
+    let decoder = topic_decoder(PayloadFormat::Json);
+    // u64 values beyond i64 do not fit `integer` fields.
+    let err = payload_error(decoder.decode(payload).unwrap_err());
+    assert!(
+        matches!(&err, PayloadError::Mismatch { path, expected: "64-bit integer", .. } if path == "samples"),
+        "{err}"
+    );
+
+    let payload = br#"{
+        "name": "imu", "count": 3.0, "temp": 21.5, "ok": false,
+        "samples": [1, -2], "raw": "AQI=",
+        "pos": {"x": 1, "y": 2.5}, "unknown": {"nested": true}
+    }"#;
+    assert_eq!(
+        decoder.decode(payload).unwrap(),
+        Value::Struct(vec![
+            Value::string("imu"),
+            Value::I64(3),
+            Value::F64(21.5),
+            Value::Bool(false),
+            Value::List(vec![Value::I64(1), Value::I64(-2)]),
+            Value::Bytes(vec![1, 2].into()),
+            Value::Struct(vec![Value::F64(1.0), Value::F64(2.5)]),
+            Value::Null,
+        ])
+    );
+    assert_eq!(
+        JsonSchemaDecoder::new(PayloadFormat::Json).encoding_key(),
+        EncodingKey::new(SchemaEncoding::JsonSchema, MessageEncoding::Json)
+    );
+}
+
+#[test]
+fn malformed_json_reports_its_byte_offset() {
+    let decoder = topic_decoder(PayloadFormat::Json);
+    let err = payload_error(decoder.decode(b"{\"name\": \"imu\",\n  oops}").unwrap_err());
+    assert!(
+        matches!(
+            err,
+            PayloadError::Malformed {
+                format: PayloadFormat::Json,
+                offset: 18,
+                ..
+            }
+        ),
+        "{err}"
+    );
+
+    let err = payload_error(decoder.decode(br#"{"count": 1.5}"#).unwrap_err());
+    assert!(
+        matches!(&err, PayloadError::Mismatch { path, found: "number", .. } if path == "count"),
+        "{err}"
+    );
+}
+
+#[test]
+fn payloads_not_matching_the_schema_are_rejected() {
+    let decoder = topic_decoder(PayloadFormat::MsgPack);
+    let base = |count: &[u8]| {
+        let mut payload = vec![0x83];
+        payload.extend(msgpack_text("name"));
+        payload.extend(msgpack_text("imu"));
+        payload.extend(msgpack_text("ok"));
+        payload.push(0xC2);
+        payload.extend(msgpack_text("count"));
+        payload.extend(count);
+        payload
+    };
+
+    let err = payload_error(decoder.decode(&base(&msgpack_text("a"))).unwrap_err());
+    assert!(
+        matches!(&err, PayloadError::Mismatch { path, expected: "i64", found: "string" } if path == "count"),
+        "{err}"
+    );
+
+    let err = payload_error(decoder.decode(&base(&[0xC0])).unwrap_err());
+    assert!(
+        matches!(&err, PayloadError::Missing { path } if path == "count"),
+        "{err}"
+    );
+
+    let mut nested = base(&[0x01]);
+    nested[0] = 0x84;
+    nested.extend(msgpack_text("pos"));
+    nested.push(0x81);
+    nested.extend(msgpack_text("x"));
+    nested.push(0x01);
+    let err = payload_error(decoder.decode(&nested).unwrap_err());
+    assert!(
+        matches!(&err, PayloadError::Missing { path } if path == "pos.y"),
+        "{err}"
+    );
+
+    let truncated = &base(&[0x01])[..6];
+    let err = payload_error(decoder.decode(truncated).unwrap_err());
+    assert!(
+        matches!(
+            err,
+            PayloadError::Malformed {
+                format: PayloadFormat::MsgPack,
+                ..
+            }
+        ),
+        "{err}"
+    );
+}
+
+#[test]
+fn schemaless_payloads_decode_as_json_text() {
+    let decoder = SchemalessDecoder::new(PayloadFormat::Cbor)
+        .build_topic_decoder("", &[])
+        .unwrap();
+    assert_eq!(decoder.field_defs()[0].name, JSON_FIELD);
+
+    // {1: h'ff', "a": [true, null]}
+    let payload = [0xA2, 0x01, 0x41, 0xFF, 0x61, b'a', 0x82, 0xF5, 0xF6];
+    assert_eq!(
+        decoder.decode(&payload).unwrap(),
+        Value::Struct(vec![Value::string(r#"{"1":"/w==","a":[true,null]}"#)])
+    );
+
+    let err = payload_error(decoder.decode(&[0xA1, 0x01]).unwrap_err());
+    assert!(
+        matches!(err, PayloadError::Malformed { offset: 2, .. }),
+        "{err}"
+    );
+}
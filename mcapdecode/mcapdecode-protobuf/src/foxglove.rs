@@ -0,0 +1,47 @@
+//! Curated mappings for the `foxglove.*` schemas recorded by Foxglove Studio
+//! and the Foxglove SDKs.
+//!
+//! Their `google.protobuf.Timestamp` fields (`CompressedImage.timestamp`,
+//! `LocationFix.timestamp`, `Log.timestamp`, ...) decode to nanoseconds since
+//! the Unix epoch, marked with [`TIMESTAMP_METADATA_KEY`] so that they become
+//! Arrow `Timestamp` columns instead of `{seconds, nanos}` structs.  `bytes`
+//! fields such as `data` need no mapping: they are Arrow `Binary` already.
+
+use mcapdecode_core::{DataTypeDef, FieldDef, TIMESTAMP_METADATA_KEY, Value};
+use prost_reflect::{DynamicMessage, FieldDescriptor, Kind};
+
+const TIMESTAMP_MESSAGE: &str = "google.protobuf.Timestamp";
+
+/// Whether `fd` is a singular `google.protobuf.Timestamp` field of a
+/// `foxglove.*` message.
+pub(crate) fn is_timestamp_field(fd: &FieldDescriptor) -> bool {
+    let package = fd.parent_message().package_name();
+    (package == "foxglove" || package.starts_with("foxglove."))
+        && !fd.is_list()
+        && matches!(fd.kind(), Kind::Message(m) if m.full_name() == TIMESTAMP_MESSAGE)
+}
+
+/// Field definition of a field matched by [`is_timestamp_field`].
+///
+/// Always nullable: timestamps outside the `i64` nanosecond range (before
+/// 1677 or after 2262) decode as null.
+pub(crate) fn timestamp_field_def(fd: &FieldDescriptor) -> FieldDef {
+    FieldDef::new(fd.name(), DataTypeDef::I64, true).with_metadata(TIMESTAMP_METADATA_KEY, "true")
+}
+
+/// Decode a `google.protobuf.Timestamp` message to nanoseconds since the
+/// Unix epoch.
+pub(crate) fn timestamp_to_value(msg: &DynamicMessage) -> Value {
+    let seconds = msg
+        .get_field_by_name("seconds")
+        .and_then(|v| v.as_i64())
+        .unwrap_or_default();
+    let nanos = msg
+        .get_field_by_name("nanos")
+        .and_then(|v| v.as_i32())
+        .unwrap_or_default();
+    seconds
+        .checked_mul(1_000_000_000)
+        .and_then(|ns| ns.checked_add(i64::from(nanos)))
+        .map_or(Value::Null, Value::I64)
+}
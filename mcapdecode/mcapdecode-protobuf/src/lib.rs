@@ -5,25 +5,36 @@
 //! mcapdecode-core.  It also re-exports the lower-level helpers
 //! [`decode_protobuf_to_value`], [`decode_protobuf_to_value_with_policy`],
 //! [`parse_message_descriptor`], and [`message_fields_to_field_defs`]
-//! for direct use.
+//! for direct use, and [`descriptor_set_to_proto`] to recover readable
+//! `.proto` source from an embedded descriptor set.
+//!
+//! `google.protobuf.Timestamp` fields of the `foxglove.*` schemas decode to
+//! nanoseconds since the Unix epoch, exported as Arrow `Timestamp` columns.
 
+mod foxglove;
 mod policy;
+mod proto_text;
 mod proto_to_arrow;
 mod schema;
 
+use std::collections::HashMap;
+
 use mcapdecode_core::{
     DecoderError, EncodingKey, FieldDefs, MessageDecoder, MessageEncoding, SchemaEncoding,
     TopicDecoder, Value,
 };
-pub use policy::PresencePolicy;
+use policy::DecodeOptions;
+pub use policy::{EnumRepresentation, PresencePolicy};
 use prost_reflect::MessageDescriptor;
+pub use proto_text::descriptor_set_to_proto;
 pub use proto_to_arrow::{decode_protobuf_to_value, decode_protobuf_to_value_with_policy};
 pub use schema::{message_fields_to_field_defs, parse_message_descriptor};
 
 /// Decoder that converts protobuf-encoded MCAP messages into
 /// [`Value`] / [`FieldDefs`] via the [`MessageDecoder`] factory trait.
 pub struct ProtobufDecoder {
-    presence_policy: PresencePolicy,
+    options: DecodeOptions,
+    descriptor_overrides: HashMap<String, Vec<u8>>,
 }
 
 impl ProtobufDecoder {
@@ -32,7 +43,31 @@ impl ProtobufDecoder {
     }
 
     pub fn new_with_presence_policy(presence_policy: PresencePolicy) -> Self {
-        Self { presence_policy }
+        Self {
+            options: presence_policy.into(),
+            descriptor_overrides: HashMap::new(),
+        }
+    }
+
+    /// Decode enum fields as `enum_representation` instead of their names.
+    pub fn with_enum_representation(mut self, enum_representation: EnumRepresentation) -> Self {
+        self.options.enums = enum_representation;
+        self
+    }
+
+    /// Use `file_descriptor_set` (a serialized `google.protobuf.FileDescriptorSet`)
+    /// instead of the schema data embedded in the MCAP file for channels whose
+    /// schema name is `schema_name`.
+    ///
+    /// Useful when the embedded descriptors are missing or stale.
+    pub fn with_descriptor_override(
+        mut self,
+        schema_name: impl Into<String>,
+        file_descriptor_set: impl Into<Vec<u8>>,
+    ) -> Self {
+        self.descriptor_overrides
+            .insert(schema_name.into(), file_descriptor_set.into());
+        self
     }
 }
 
@@ -52,14 +87,17 @@ impl MessageDecoder for ProtobufDecoder {
         schema_name: &str,
         schema_data: &[u8],
     ) -> Result<Box<dyn TopicDecoder>, DecoderError> {
+        let schema_data = self
+            .descriptor_overrides
+            .get(schema_name)
+            .map_or(schema_data, Vec::as_slice);
         let desc = schema::parse_message_descriptor(schema_name, schema_data)?;
-        let field_defs =
-            schema::message_fields_to_field_defs(schema_name, &desc, self.presence_policy)?;
+        let field_defs = schema::fields_to_field_defs(schema_name, &desc, self.options)?;
         Ok(Box::new(ProtobufTopicDecoder {
             schema_name: schema_name.to_string(),
             desc,
             field_defs,
-            presence_policy: self.presence_policy,
+            options: self.options,
         }))
     }
 }
@@ -68,7 +106,7 @@ struct ProtobufTopicDecoder {
     schema_name: String,
     desc: MessageDescriptor,
     field_defs: FieldDefs,
-    presence_policy: PresencePolicy,
+    options: DecodeOptions,
 }
 
 impl TopicDecoder for ProtobufTopicDecoder {
@@ -77,7 +115,7 @@ impl TopicDecoder for ProtobufTopicDecoder {
             &self.schema_name,
             &self.desc,
             message_data,
-            self.presence_policy,
+            self.options,
         )
     }
 
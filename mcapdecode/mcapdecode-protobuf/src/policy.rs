@@ -4,8 +4,9 @@
 pub enum PresencePolicy {
     /// Legacy behavior: always read fields via protobuf default semantics.
     ///
-    /// Missing fields are materialized as default values and all fields are
-    /// treated as nullable in derived schema.
+    /// Missing fields are materialized as default values (the declared
+    /// `[default = ...]` of proto2 fields, otherwise zero/empty) and all
+    /// fields are treated as nullable in derived schema.
     AlwaysDefault,
     /// Presence-aware behavior (default):
     ///
@@ -14,3 +15,31 @@ pub enum PresencePolicy {
     #[default]
     PresenceAware,
 }
+
+/// Representation of protobuf enum fields in decoded values and derived
+/// schema.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EnumRepresentation {
+    /// The name of the enum value as a string (default); values without a
+    /// declared name are written as their number in decimal.
+    #[default]
+    Name,
+    /// The number of the enum value as an `int32`.
+    Number,
+}
+
+/// Options shared by schema derivation and decoding.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct DecodeOptions {
+    pub presence: PresencePolicy,
+    pub enums: EnumRepresentation,
+}
+
+impl From<PresencePolicy> for DecodeOptions {
+    fn from(presence: PresencePolicy) -> Self {
+        Self {
+            presence,
+            enums: EnumRepresentation::default(),
+        }
+    }
+}
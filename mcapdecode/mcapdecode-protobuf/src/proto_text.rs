@@ -0,0 +1,172 @@
+//! Render a protobuf `FileDescriptorSet` back into `.proto` source text.
+
+use std::fmt::Write;
+
+use mcapdecode_core::DecoderError;
+use prost_reflect::{
+    Cardinality, DescriptorPool, EnumDescriptor, FieldDescriptor, FileDescriptor, Kind,
+    MessageDescriptor, Syntax,
+};
+
+const INDENT: &str = "  ";
+
+/// Render `schema_data` (a serialized `google.protobuf.FileDescriptorSet`) as
+/// `.proto` source, one section per file headed by a `// <file name>` comment.
+///
+/// Only declarations are reconstructed: packages, imports, messages (with
+/// nested types, oneofs and map fields) and enums. Options, comments and
+/// services are not part of the output.
+pub fn descriptor_set_to_proto(
+    schema_name: &str,
+    schema_data: &[u8],
+) -> Result<String, DecoderError> {
+    let pool = DescriptorPool::decode(schema_data).map_err(|e| DecoderError::SchemaParse {
+        schema_name: schema_name.to_string(),
+        source: Box::new(e),
+    })?;
+    let files: Vec<String> = pool.files().map(|file| render_file(&file)).collect();
+    Ok(files.join("\n"))
+}
+
+fn render_file(file: &FileDescriptor) -> String {
+    let mut out = String::new();
+    let syntax = match file.syntax() {
+        Syntax::Proto2 => "proto2",
+        Syntax::Proto3 => "proto3",
+    };
+    let _ = writeln!(out, "// {}", file.name());
+    let _ = writeln!(out, "syntax = \"{syntax}\";");
+    if !file.package_name().is_empty() {
+        let _ = writeln!(out, "\npackage {};", file.package_name());
+    }
+    let mut dependencies = file.dependencies().peekable();
+    if dependencies.peek().is_some() {
+        out.push('\n');
+        for dependency in dependencies {
+            let _ = writeln!(out, "import \"{}\";", dependency.name());
+        }
+    }
+    for message in file.messages() {
+        out.push('\n');
+        render_message(&mut out, &message, file.syntax(), 0);
+    }
+    for enum_desc in file.enums() {
+        out.push('\n');
+        render_enum(&mut out, &enum_desc, 0);
+    }
+    out
+}
+
+fn render_message(out: &mut String, message: &MessageDescriptor, syntax: Syntax, depth: usize) {
+    let indent = INDENT.repeat(depth);
+    let inner = INDENT.repeat(depth + 1);
+    let _ = writeln!(out, "{indent}message {} {{", message.name());
+    for enum_desc in message.child_enums() {
+        render_enum(out, &enum_desc, depth + 1);
+    }
+    for child in message.child_messages().filter(|m| !m.is_map_entry()) {
+        render_message(out, &child, syntax, depth + 1);
+    }
+
+    let mut rendered_oneofs = Vec::new();
+    for field in message.fields() {
+        match field
+            .containing_oneof()
+            .filter(|oneof| !oneof.is_synthetic())
+        {
+            Some(oneof) => {
+                if rendered_oneofs.contains(&oneof.name().to_string()) {
+                    continue;
+                }
+                rendered_oneofs.push(oneof.name().to_string());
+                let _ = writeln!(out, "{inner}oneof {} {{", oneof.name());
+                for oneof_field in oneof.fields() {
+                    let _ = writeln!(
+                        out,
+                        "{inner}{INDENT}{} {} = {};",
+                        field_type(&oneof_field),
+                        oneof_field.name(),
+                        oneof_field.number()
+                    );
+                }
+                let _ = writeln!(out, "{inner}}}");
+            }
+            None => {
+                let _ = writeln!(
+                    out,
+                    "{inner}{}{} {} = {};",
+                    field_label(&field, syntax),
+                    field_type(&field),
+                    field.name(),
+                    field.number()
+                );
+            }
+        }
+    }
+    let _ = writeln!(out, "{indent}}}");
+}
+
+fn render_enum(out: &mut String, enum_desc: &EnumDescriptor, depth: usize) {
+    let indent = INDENT.repeat(depth);
+    let _ = writeln!(out, "{indent}enum {} {{", enum_desc.name());
+    for value in enum_desc.values() {
+        let _ = writeln!(
+            out,
+            "{indent}{INDENT}{} = {};",
+            value.name(),
+            value.number()
+        );
+    }
+    let _ = writeln!(out, "{indent}}}");
+}
+
+/// Label written before the field type, including its trailing space.
+fn field_label(field: &FieldDescriptor, syntax: Syntax) -> &'static str {
+    if field.is_map() {
+        return "";
+    }
+    match (field.cardinality(), syntax) {
+        (Cardinality::Repeated, _) => "repeated ",
+        (Cardinality::Required, _) => "required ",
+        (Cardinality::Optional, Syntax::Proto2) => "optional ",
+        // proto3 `optional` fields are wrapped in a synthetic oneof.
+        (Cardinality::Optional, Syntax::Proto3) if field.containing_oneof().is_some() => {
+            "optional "
+        }
+        (Cardinality::Optional, Syntax::Proto3) => "",
+    }
+}
+
+fn field_type(field: &FieldDescriptor) -> String {
+    match field.kind() {
+        Kind::Message(entry) if field.is_map() => format!(
+            "map<{}, {}>",
+            kind_name(&entry.map_entry_key_field().kind()),
+            kind_name(&entry.map_entry_value_field().kind())
+        ),
+        kind => kind_name(&kind),
+    }
+}
+
+fn kind_name(kind: &Kind) -> String {
+    match kind {
+        Kind::Double => "double".to_string(),
+        Kind::Float => "float".to_string(),
+        Kind::Int32 => "int32".to_string(),
+        Kind::Int64 => "int64".to_string(),
+        Kind::Uint32 => "uint32".to_string(),
+        Kind::Uint64 => "uint64".to_string(),
+        Kind::Sint32 => "sint32".to_string(),
+        Kind::Sint64 => "sint64".to_string(),
+        Kind::Fixed32 => "fixed32".to_string(),
+        Kind::Fixed64 => "fixed64".to_string(),
+        Kind::Sfixed32 => "sfixed32".to_string(),
+        Kind::Sfixed64 => "sfixed64".to_string(),
+        Kind::Bool => "bool".to_string(),
+        Kind::String => "string".to_string(),
+        Kind::Bytes => "bytes".to_string(),
+        // Fully qualified names resolve regardless of the enclosing scope.
+        Kind::Message(message) => format!(".{}", message.full_name()),
+        Kind::Enum(enum_desc) => format!(".{}", enum_desc.full_name()),
+    }
+}
@@ -3,12 +3,16 @@
 
 use std::sync::Arc;
 
-use mcapdecode_core::{DecoderError, Value};
+use mcapdecode_core::{DecodeWarning, DecoderError, Value, warn};
 use prost_reflect::{
     DynamicMessage, EnumDescriptor, Kind, MapKey, MessageDescriptor, Value as ProtoValue,
 };
 
-use crate::{PresencePolicy, schema::parse_message_descriptor};
+use crate::{
+    PresencePolicy, foxglove,
+    policy::{DecodeOptions, EnumRepresentation},
+    schema::parse_message_descriptor,
+};
 
 /// Decode a message payload using an already-resolved [`MessageDescriptor`].
 ///
@@ -19,7 +23,7 @@ pub(crate) fn decode_from_descriptor(
     schema_name: &str,
     message_desc: &MessageDescriptor,
     message_data: &[u8],
-    policy: PresencePolicy,
+    options: DecodeOptions,
 ) -> Result<Value, DecoderError> {
     let dynamic_message =
         DynamicMessage::decode(message_desc.clone(), message_data).map_err(|e| {
@@ -28,7 +32,7 @@ pub(crate) fn decode_from_descriptor(
                 source: Box::new(e),
             }
         })?;
-    Ok(message_to_value(&dynamic_message, message_desc, policy))
+    Ok(message_to_value(&dynamic_message, message_desc, options))
 }
 
 /// Decode a serialized protobuf message into a [`Value`].
@@ -59,31 +63,47 @@ pub fn decode_protobuf_to_value_with_policy(
     policy: PresencePolicy,
 ) -> Result<Value, DecoderError> {
     let desc = parse_message_descriptor(schema_name, schema_data)?;
-    decode_from_descriptor(schema_name, &desc, message_data, policy)
+    decode_from_descriptor(schema_name, &desc, message_data, policy.into())
 }
 
 fn message_to_value(
     msg: &DynamicMessage,
     desc: &MessageDescriptor,
-    policy: PresencePolicy,
+    options: DecodeOptions,
 ) -> Value {
     let fields = desc
         .fields()
         .map(|field_desc| {
-            if matches!(policy, PresencePolicy::PresenceAware)
+            if matches!(options.presence, PresencePolicy::PresenceAware)
                 && field_desc.supports_presence()
                 && !msg.has_field(&field_desc)
             {
                 return Value::Null;
             }
             let value = msg.get_field(&field_desc);
-            proto_value_to_value(value.as_ref(), &field_desc.kind(), policy)
+            if foxglove::is_timestamp_field(&field_desc) {
+                return value
+                    .as_message()
+                    .map_or(Value::Null, foxglove::timestamp_to_value);
+            }
+            proto_value_to_value(
+                value.as_ref(),
+                &field_desc.kind(),
+                field_desc.full_name(),
+                options,
+            )
         })
         .collect();
     Value::Struct(fields)
 }
 
-fn proto_value_to_value(value: &ProtoValue, kind: &Kind, policy: PresencePolicy) -> Value {
+/// Convert `value` of a field named `field` (fully qualified) of `kind`.
+fn proto_value_to_value(
+    value: &ProtoValue,
+    kind: &Kind,
+    field: &str,
+    options: DecodeOptions,
+) -> Value {
     match value {
         ProtoValue::Bool(v) => Value::Bool(*v),
         ProtoValue::I32(v) => Value::I32(*v),
@@ -98,18 +118,21 @@ fn proto_value_to_value(value: &ProtoValue, kind: &Kind, policy: PresencePolicy)
             let Kind::Enum(ed) = kind else {
                 panic!("EnumNumber({n}) with non-Enum kind: {kind:?}")
             };
-            enum_to_value(*n, ed)
+            match options.enums {
+                EnumRepresentation::Name => enum_to_value(*n, ed, field),
+                EnumRepresentation::Number => Value::I32(*n),
+            }
         }
         ProtoValue::Message(m) => {
             let Kind::Message(md) = kind else {
                 panic!("Message with non-Message kind: {kind:?}")
             };
-            message_to_value(m, md, policy)
+            message_to_value(m, md, options)
         }
         ProtoValue::List(items) => Value::List(
             items
                 .iter()
-                .map(|v| proto_value_to_value(v, kind, policy))
+                .map(|v| proto_value_to_value(v, kind, field, options))
                 .collect(),
         ),
         ProtoValue::Map(map) => {
@@ -124,7 +147,7 @@ fn proto_value_to_value(value: &ProtoValue, kind: &Kind, policy: PresencePolicy)
                 .map(|(k, v)| {
                     (
                         map_key_to_value(k),
-                        proto_value_to_value(v, &value_kind, policy),
+                        proto_value_to_value(v, &value_kind, field, options),
                     )
                 })
                 .collect();
@@ -133,11 +156,19 @@ fn proto_value_to_value(value: &ProtoValue, kind: &Kind, policy: PresencePolicy)
     }
 }
 
-fn enum_to_value(n: i32, ed: &EnumDescriptor) -> Value {
-    let name = ed
-        .get_value(n)
-        .map(|v| v.name().to_string())
-        .unwrap_or_else(|| n.to_string());
+/// Name of enum value `n`, or `n` in decimal with a warning if `ed` declares
+/// no such value.
+fn enum_to_value(n: i32, ed: &EnumDescriptor, field: &str) -> Value {
+    let name = match ed.get_value(n) {
+        Some(v) => v.name().to_string(),
+        None => {
+            warn(DecodeWarning::new(
+                field,
+                format!("unknown value {n} of enum {}", ed.full_name()),
+            ));
+            n.to_string()
+        }
+    };
     Value::String(Arc::from(name))
 }
 
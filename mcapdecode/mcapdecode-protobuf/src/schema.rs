@@ -3,7 +3,10 @@
 use mcapdecode_core::{DataTypeDef, DecoderError, ElementDef, FieldDef, FieldDefs};
 use prost_reflect::{DescriptorPool, FieldDescriptor, Kind, MessageDescriptor};
 
-use crate::PresencePolicy;
+use crate::{
+    PresencePolicy, foxglove,
+    policy::{DecodeOptions, EnumRepresentation},
+};
 
 /// Parse `schema_data` (a serialized `google.protobuf.FileDescriptorSet`) and
 /// look up the [`MessageDescriptor`] for `schema_name`.
@@ -29,9 +32,17 @@ pub fn message_fields_to_field_defs(
     schema_name: &str,
     desc: &MessageDescriptor,
     policy: PresencePolicy,
+) -> Result<FieldDefs, DecoderError> {
+    fields_to_field_defs(schema_name, desc, policy.into())
+}
+
+pub(crate) fn fields_to_field_defs(
+    schema_name: &str,
+    desc: &MessageDescriptor,
+    options: DecodeOptions,
 ) -> Result<FieldDefs, DecoderError> {
     desc.fields()
-        .map(|f| field_descriptor_to_field_def(schema_name, &f, policy))
+        .map(|f| field_descriptor_to_field_def(schema_name, &f, options))
         .collect::<Result<Vec<_>, _>>()
         .map(Into::into)
 }
@@ -39,9 +50,12 @@ pub fn message_fields_to_field_defs(
 fn field_descriptor_to_field_def(
     schema_name: &str,
     fd: &FieldDescriptor,
-    policy: PresencePolicy,
+    options: DecodeOptions,
 ) -> Result<FieldDef, DecoderError> {
-    let inner_dt = kind_to_data_type_def(schema_name, fd, policy)?;
+    if foxglove::is_timestamp_field(fd) {
+        return Ok(foxglove::timestamp_field_def(fd));
+    }
+    let inner_dt = kind_to_data_type_def(schema_name, fd, options)?;
 
     let dt = if fd.is_list() {
         DataTypeDef::List(Box::new(ElementDef::new(inner_dt, false)))
@@ -70,8 +84,8 @@ fn field_descriptor_to_field_def(
                     schema_name: schema_name.to_string(),
                     detail: format!("map entry `{}` missing value field", fd.name()),
                 })?;
-        let key_dt = kind_to_data_type_def(schema_name, &key_field, policy)?;
-        let val_dt = kind_to_data_type_def(schema_name, &value_field, policy)?;
+        let key_dt = kind_to_data_type_def(schema_name, &key_field, options)?;
+        let val_dt = kind_to_data_type_def(schema_name, &value_field, options)?;
         DataTypeDef::Map {
             key: Box::new(ElementDef::new(key_dt, false)),
             value: Box::new(ElementDef::new(val_dt, false)),
@@ -80,7 +94,7 @@ fn field_descriptor_to_field_def(
         inner_dt
     };
 
-    let nullable = match policy {
+    let nullable = match options.presence {
         PresencePolicy::AlwaysDefault => false,
         PresencePolicy::PresenceAware => fd.supports_presence(),
     };
@@ -90,7 +104,7 @@ fn field_descriptor_to_field_def(
 fn kind_to_data_type_def(
     schema_name: &str,
     fd: &FieldDescriptor,
-    policy: PresencePolicy,
+    options: DecodeOptions,
 ) -> Result<DataTypeDef, DecoderError> {
     let dt = match fd.kind() {
         Kind::Double => DataTypeDef::F64,
@@ -102,9 +116,12 @@ fn kind_to_data_type_def(
         Kind::Bool => DataTypeDef::Bool,
         Kind::String => DataTypeDef::String,
         Kind::Bytes => DataTypeDef::Bytes,
-        Kind::Enum(_) => DataTypeDef::String,
+        Kind::Enum(_) => match options.enums {
+            EnumRepresentation::Name => DataTypeDef::String,
+            EnumRepresentation::Number => DataTypeDef::I32,
+        },
         Kind::Message(msg_desc) => {
-            let fields = message_fields_to_field_defs(schema_name, &msg_desc, policy)?;
+            let fields = fields_to_field_defs(schema_name, &msg_desc, options)?;
             DataTypeDef::Struct(fields)
         }
     };
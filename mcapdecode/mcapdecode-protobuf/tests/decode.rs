@@ -1,13 +1,16 @@
 mod test_helpers;
 
-use mcapdecode_core::{DecoderError, Value};
+use mcapdecode_core::{
+    DataTypeDef, DecodeWarning, DecoderError, MessageDecoder, Value, collect_warnings,
+};
 use mcapdecode_protobuf::{
-    PresencePolicy, decode_protobuf_to_value, decode_protobuf_to_value_with_policy,
+    EnumRepresentation, PresencePolicy, ProtobufDecoder, decode_protobuf_to_value,
+    decode_protobuf_to_value_with_policy,
 };
 use prost::Message;
 use prost_reflect::{DescriptorPool, DynamicMessage};
 use prost_types::{
-    DescriptorProto,
+    DescriptorProto, FileDescriptorProto, FileDescriptorSet,
     field_descriptor_proto::{Label, Type},
 };
 use test_helpers::*;
@@ -224,7 +227,15 @@ fn decode_unknown_enum_value_falls_back_to_number() {
     dm.set_field_by_name("color", prost_reflect::Value::EnumNumber(999));
 
     let wire = encode_dynamic(&dm);
-    let value = decode_protobuf_to_value("WithEnum", &fds, &wire).unwrap();
+    let (value, warnings) =
+        collect_warnings(|| decode_protobuf_to_value("WithEnum", &fds, &wire).unwrap());
+    assert_eq!(
+        warnings,
+        vec![DecodeWarning::new(
+            "WithEnum.color",
+            "unknown value 999 of enum Color"
+        )]
+    );
 
     let Value::Struct(fields) = value else {
         panic!("expected Struct");
@@ -235,6 +246,33 @@ fn decode_unknown_enum_value_falls_back_to_number() {
     }
 }
 
+#[test]
+fn decoder_writes_enum_numbers_with_number_representation() {
+    let color_enum = simple_enum("Color", &[("RED", 0), ("GREEN", 1)]);
+    let msg = DescriptorProto {
+        name: Some("WithEnum".to_string()),
+        field: vec![enum_field("color", 1, ".Color")],
+        ..Default::default()
+    };
+    let fds = build_fds_with_enums("enum.proto", vec![msg], vec![color_enum]);
+    let (_pool, desc) = pool_and_desc(&fds, "WithEnum");
+
+    let mut dm = DynamicMessage::new(desc);
+    dm.set_field_by_name("color", prost_reflect::Value::EnumNumber(1));
+    let wire = encode_dynamic(&dm);
+
+    let decoder = ProtobufDecoder::new().with_enum_representation(EnumRepresentation::Number);
+    let topic_decoder = decoder.build_topic_decoder("WithEnum", &fds).unwrap();
+    assert_eq!(
+        topic_decoder.field_defs()[0].element.data_type,
+        DataTypeDef::I32
+    );
+    assert_eq!(
+        topic_decoder.decode(&wire).unwrap(),
+        Value::Struct(vec![Value::I32(1)])
+    );
+}
+
 #[test]
 fn decode_map_field() {
     let entry = map_entry_message("LabelsEntry", Type::String, Type::Int32);
@@ -357,3 +395,180 @@ fn decode_proto3_optional_missing_is_default_in_legacy_policy() {
     };
     assert!(matches!(fields[0], Value::I32(0)));
 }
+
+/// proto2 message whose fields declare custom defaults.
+fn proto2_defaults_fds() -> Vec<u8> {
+    let with_default = |mut field: prost_types::FieldDescriptorProto, default: &str| {
+        field.default_value = Some(default.to_string());
+        field
+    };
+    let msg = DescriptorProto {
+        name: Some("Legacy".to_string()),
+        field: vec![
+            with_default(scalar_field("count", 1, Type::Int32), "42"),
+            with_default(scalar_field("ratio", 2, Type::Double), "1.5"),
+            with_default(scalar_field("label", 3, Type::String), "unset"),
+            with_default(enum_field("mode", 4, "Mode"), "FAST"),
+            scalar_field("plain", 5, Type::Int32),
+        ],
+        ..Default::default()
+    };
+    let mode = simple_enum("Mode", &[("SLOW", 0), ("FAST", 1)]);
+    build_fds_with_syntax("legacy.proto", "proto2", vec![msg], vec![mode])
+}
+
+#[test]
+fn decode_proto2_custom_defaults_in_legacy_policy() {
+    let value = decode_protobuf_to_value_with_policy(
+        "Legacy",
+        &proto2_defaults_fds(),
+        &[],
+        PresencePolicy::AlwaysDefault,
+    )
+    .unwrap();
+    assert_eq!(
+        value,
+        Value::Struct(vec![
+            Value::I32(42),
+            Value::F64(1.5),
+            Value::string("unset"),
+            Value::string("FAST"),
+            Value::I32(0),
+        ])
+    );
+}
+
+#[test]
+fn decode_proto2_missing_fields_with_custom_defaults_are_null_by_default() {
+    let value = decode_protobuf_to_value("Legacy", &proto2_defaults_fds(), &[]).unwrap();
+    assert_eq!(value, Value::Struct(vec![Value::Null; 5]));
+}
+
+#[test]
+fn descriptor_override_takes_precedence_over_embedded_schema() {
+    let msg = DescriptorProto {
+        name: Some("Overridden".to_string()),
+        field: vec![scalar_field("x", 1, Type::Int32)],
+        ..Default::default()
+    };
+    let fds = build_fds("overridden.proto", vec![msg]);
+    let (_pool, desc) = pool_and_desc(&fds, "Overridden");
+    let mut dm = DynamicMessage::new(desc);
+    dm.set_field_by_name("x", prost_reflect::Value::I32(7));
+    let wire = encode_dynamic(&dm);
+
+    let decoder = ProtobufDecoder::new().with_descriptor_override("Overridden", fds);
+    let topic_decoder = decoder
+        .build_topic_decoder("Overridden", &[0xff, 0xff])
+        .unwrap();
+    assert_eq!(topic_decoder.field_defs().len(), 1);
+
+    let value = topic_decoder.decode(&wire).unwrap();
+    let Value::Struct(fields) = value else {
+        panic!("expected Struct");
+    };
+    assert!(matches!(fields[0], Value::I32(7)));
+}
+
+#[test]
+fn descriptor_override_does_not_apply_to_other_schemas() {
+    let msg = DescriptorProto {
+        name: Some("Overridden".to_string()),
+        field: vec![scalar_field("x", 1, Type::Int32)],
+        ..Default::default()
+    };
+    let fds = build_fds("overridden.proto", vec![msg]);
+
+    let decoder = ProtobufDecoder::new().with_descriptor_override("Overridden", fds);
+    let err = decoder
+        .build_topic_decoder("Other", &[0xff, 0xff])
+        .err()
+        .unwrap();
+    assert!(matches!(err, DecoderError::SchemaParse { .. }));
+}
+
+/// `foxglove.Log` (trimmed) and the `google.protobuf.Timestamp` it embeds.
+fn foxglove_log_fds() -> Vec<u8> {
+    let timestamp = FileDescriptorProto {
+        name: Some("google/protobuf/timestamp.proto".to_string()),
+        package: Some("google.protobuf".to_string()),
+        message_type: vec![DescriptorProto {
+            name: Some("Timestamp".to_string()),
+            field: vec![
+                scalar_field("seconds", 1, Type::Int64),
+                scalar_field("nanos", 2, Type::Int32),
+            ],
+            ..Default::default()
+        }],
+        syntax: Some("proto3".to_string()),
+        ..Default::default()
+    };
+    let log = FileDescriptorProto {
+        name: Some("foxglove/Log.proto".to_string()),
+        package: Some("foxglove".to_string()),
+        dependency: vec!["google/protobuf/timestamp.proto".to_string()],
+        message_type: vec![DescriptorProto {
+            name: Some("Log".to_string()),
+            field: vec![
+                message_field(
+                    "timestamp",
+                    1,
+                    ".google.protobuf.Timestamp",
+                    Label::Optional,
+                ),
+                scalar_field("message", 3, Type::String),
+                scalar_field("data", 4, Type::Bytes),
+            ],
+            ..Default::default()
+        }],
+        syntax: Some("proto3".to_string()),
+        ..Default::default()
+    };
+    FileDescriptorSet {
+        file: vec![timestamp, log],
+    }
+    .encode_to_vec()
+}
+
+#[test]
+fn decode_foxglove_timestamp_as_nanoseconds() {
+    let fds = foxglove_log_fds();
+    let (pool, log_desc) = pool_and_desc(&fds, "foxglove.Log");
+    let mut timestamp = DynamicMessage::new(
+        pool.get_message_by_name("google.protobuf.Timestamp")
+            .unwrap(),
+    );
+    timestamp.set_field_by_name("seconds", prost_reflect::Value::I64(1_700_000_000));
+    timestamp.set_field_by_name("nanos", prost_reflect::Value::I32(250));
+    let mut log = DynamicMessage::new(log_desc);
+    log.set_field_by_name("timestamp", prost_reflect::Value::Message(timestamp));
+    log.set_field_by_name("message", prost_reflect::Value::String("hi".to_string()));
+
+    let decoder = ProtobufDecoder::new()
+        .build_topic_decoder("foxglove.Log", &fds)
+        .unwrap();
+    let timestamp_def = &decoder.field_defs()[0];
+    assert_eq!(timestamp_def.element.data_type, DataTypeDef::I64);
+    assert!(timestamp_def.is_timestamp());
+    assert_eq!(
+        decoder.field_defs()[2].element.data_type,
+        DataTypeDef::Bytes
+    );
+
+    let value = decoder.decode(&encode_dynamic(&log)).unwrap();
+    assert_eq!(
+        value,
+        Value::Struct(vec![
+            Value::I64(1_700_000_000_000_000_250),
+            Value::string("hi"),
+            Value::Bytes(Vec::new().into()),
+        ])
+    );
+
+    // Unset and out-of-range timestamps are null.
+    let value = decoder.decode(&[]).unwrap();
+    let Value::Struct(fields) = value else {
+        panic!("expected Struct");
+    };
+    assert_eq!(fields[0], Value::Null);
+}
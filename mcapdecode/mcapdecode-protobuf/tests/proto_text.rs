@@ -0,0 +1,168 @@
+mod test_helpers;
+
+use mcapdecode_core::DecoderError;
+use mcapdecode_protobuf::descriptor_set_to_proto;
+use prost::Message;
+use prost_types::{
+    DescriptorProto, FileDescriptorProto, FileDescriptorSet, OneofDescriptorProto,
+    field_descriptor_proto::{Label, Type},
+};
+use test_helpers::*;
+
+#[test]
+fn renders_messages_and_enums() {
+    let point = DescriptorProto {
+        name: Some("Point".to_string()),
+        field: vec![
+            scalar_field("x", 1, Type::Double),
+            repeated_field("tags", 2, Type::String),
+            enum_field("color", 3, ".Color"),
+        ],
+        ..Default::default()
+    };
+    let fds = build_fds_with_enums(
+        "point.proto",
+        vec![point],
+        vec![simple_enum("Color", &[("RED", 0), ("GREEN", 1)])],
+    );
+
+    assert_eq!(
+        descriptor_set_to_proto("Point", &fds).unwrap(),
+        "// point.proto
+syntax = \"proto3\";
+
+message Point {
+  double x = 1;
+  repeated string tags = 2;
+  .Color color = 3;
+}
+
+enum Color {
+  RED = 0;
+  GREEN = 1;
+}
+"
+    );
+}
+
+#[test]
+fn renders_nested_types_maps_and_oneofs() {
+    let mut choice_a = scalar_field("a", 3, Type::Int32);
+    choice_a.oneof_index = Some(1);
+    let mut choice_b = scalar_field("b", 4, Type::String);
+    choice_b.oneof_index = Some(1);
+    let msg = DescriptorProto {
+        name: Some("Outer".to_string()),
+        field: vec![
+            message_field("labels", 1, ".Outer.LabelsEntry", Label::Repeated),
+            proto3_optional_scalar_field("count", 2, Type::Int32, 0),
+            choice_a,
+            choice_b,
+            message_field("inner", 5, ".Outer.Inner", Label::Optional),
+        ],
+        nested_type: vec![
+            map_entry_message("LabelsEntry", Type::String, Type::Int64),
+            DescriptorProto {
+                name: Some("Inner".to_string()),
+                field: vec![scalar_field("flag", 1, Type::Bool)],
+                ..Default::default()
+            },
+        ],
+        oneof_decl: vec![
+            synthetic_oneof("_count"),
+            OneofDescriptorProto {
+                name: Some("choice".to_string()),
+                ..Default::default()
+            },
+        ],
+        ..Default::default()
+    };
+    let fds = build_fds("outer.proto", vec![msg]);
+
+    assert_eq!(
+        descriptor_set_to_proto("Outer", &fds).unwrap(),
+        "// outer.proto
+syntax = \"proto3\";
+
+message Outer {
+  message Inner {
+    bool flag = 1;
+  }
+  map<string, int64> labels = 1;
+  optional int32 count = 2;
+  oneof choice {
+    int32 a = 3;
+    string b = 4;
+  }
+  .Outer.Inner inner = 5;
+}
+"
+    );
+}
+
+#[test]
+fn renders_package_imports_and_proto2_labels() {
+    let dep = FileDescriptorProto {
+        name: Some("common/header.proto".to_string()),
+        package: Some("common".to_string()),
+        message_type: vec![DescriptorProto {
+            name: Some("Header".to_string()),
+            field: vec![scalar_field("seq", 1, Type::Uint32)],
+            ..Default::default()
+        }],
+        syntax: Some("proto2".to_string()),
+        ..Default::default()
+    };
+    let mut id = scalar_field("id", 2, Type::Int64);
+    id.label = Some(Label::Required.into());
+    let main = FileDescriptorProto {
+        name: Some("app/msg.proto".to_string()),
+        package: Some("app".to_string()),
+        dependency: vec!["common/header.proto".to_string()],
+        message_type: vec![DescriptorProto {
+            name: Some("Msg".to_string()),
+            field: vec![
+                message_field("header", 1, ".common.Header", Label::Optional),
+                id,
+            ],
+            ..Default::default()
+        }],
+        syntax: Some("proto2".to_string()),
+        ..Default::default()
+    };
+    let fds = FileDescriptorSet {
+        file: vec![dep, main],
+    }
+    .encode_to_vec();
+
+    assert_eq!(
+        descriptor_set_to_proto("app.Msg", &fds).unwrap(),
+        "// common/header.proto
+syntax = \"proto2\";
+
+package common;
+
+message Header {
+  optional uint32 seq = 1;
+}
+
+// app/msg.proto
+syntax = \"proto2\";
+
+package app;
+
+import \"common/header.proto\";
+
+message Msg {
+  optional .common.Header header = 1;
+  required int64 id = 2;
+}
+"
+    );
+}
+
+#[test]
+fn invalid_descriptor_set_returns_schema_parse_error() {
+    let err = descriptor_set_to_proto("Broken", b"not a descriptor set").unwrap_err();
+    assert!(matches!(err, DecoderError::SchemaParse { .. }));
+}
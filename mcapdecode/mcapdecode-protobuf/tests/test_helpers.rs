@@ -18,13 +18,23 @@ pub fn build_fds_with_enums(
     file_name: &str,
     messages: Vec<DescriptorProto>,
     enums: Vec<EnumDescriptorProto>,
+) -> Vec<u8> {
+    build_fds_with_syntax(file_name, "proto3", messages, enums)
+}
+
+/// Build a `FileDescriptorSet` for a file of the given `syntax` ("proto2" or "proto3").
+pub fn build_fds_with_syntax(
+    file_name: &str,
+    syntax: &str,
+    messages: Vec<DescriptorProto>,
+    enums: Vec<EnumDescriptorProto>,
 ) -> Vec<u8> {
     let fds = FileDescriptorSet {
         file: vec![FileDescriptorProto {
             name: Some(file_name.to_string()),
             message_type: messages,
             enum_type: enums,
-            syntax: Some("proto3".to_string()),
+            syntax: Some(syntax.to_string()),
             ..Default::default()
         }],
     };
@@ -0,0 +1,206 @@
+//! ROS 1 serialization decoding to the Arrow-independent Value type.
+//!
+//! ROS 1 messages are the fields in order, little-endian and unaligned,
+//! without an encapsulation header: strings and variable-length arrays are
+//! prefixed with their `uint32` length (strings have no null terminator),
+//! fixed-length arrays are not prefixed, and `time` / `duration` are two
+//! 32-bit integers.
+
+use std::sync::Arc;
+
+use mcapdecode_core::{DecodeWarning, DecoderError, Value, warn};
+use mcapdecode_decoder_kit::{ByteCursor, Endian};
+use mcapdecode_ros2_common::{
+    ByteArrays, CdrOptions, InvalidUtf8, PrimitiveType, ResolvedField, ResolvedSchema,
+    ResolvedType, Ros2Error,
+};
+
+/// Options of [`decode_ros1_to_value_with`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Ros1Options {
+    pub invalid_utf8: InvalidUtf8,
+    pub byte_arrays: ByteArrays,
+}
+
+impl Ros1Options {
+    /// The equivalent CDR options, for deriving the `FieldDefs` of the
+    /// decoded values.
+    pub fn cdr_options(self) -> CdrOptions {
+        CdrOptions {
+            invalid_utf8: self.invalid_utf8,
+            byte_arrays: self.byte_arrays,
+            ..CdrOptions::default()
+        }
+    }
+}
+
+pub fn decode_ros1_to_value(schema: &ResolvedSchema, data: &[u8]) -> Result<Value, DecoderError> {
+    decode_ros1_to_value_with(schema, data, Ros1Options::default())
+}
+
+/// Like [`decode_ros1_to_value`], with the string and byte array handling
+/// of `options`.
+pub fn decode_ros1_to_value_with(
+    schema: &ResolvedSchema,
+    data: &[u8],
+    options: Ros1Options,
+) -> Result<Value, DecoderError> {
+    let mut d = Decoder {
+        cursor: ByteCursor::new(data, Endian::Little),
+        options,
+    };
+    d.decode_struct(schema, &schema.root, &schema.root.join("."))
+        .map_err(|detail| DecoderError::MessageDecode {
+            schema_name: schema.root.join("::"),
+            source: detail.into(),
+        })
+}
+
+struct Decoder<'a> {
+    cursor: ByteCursor<'a>,
+    options: Ros1Options,
+}
+
+impl<'a> Decoder<'a> {
+    /// [`Ros2Error::CdrError`] at `path` and the current read position.
+    fn payload_error(&self, path: &str, detail: impl Into<String>) -> Ros2Error {
+        Ros2Error::CdrError {
+            path: path.to_string(),
+            offset: self.cursor.offset(),
+            detail: detail.into(),
+        }
+    }
+
+    fn eof_error(&self, path: &str) -> Ros2Error {
+        self.payload_error(path, format!("unexpected EOF at {path}"))
+    }
+
+    fn decode_struct(
+        &mut self,
+        schema: &ResolvedSchema,
+        struct_name: &[String],
+        path: &str,
+    ) -> Result<Value, Ros2Error> {
+        let s = schema.structs.get(struct_name).ok_or_else(|| {
+            Ros2Error::UnresolvedType(format!("unknown struct: {}", struct_name.join("::")))
+        })?;
+        let mut fields = Vec::with_capacity(s.fields.len());
+        for field in &s.fields {
+            let field_path = format!("{}.{}", path, field.name);
+            fields.push(self.decode_field(schema, field, &field_path)?);
+        }
+        Ok(Value::Struct(fields))
+    }
+
+    fn decode_field(
+        &mut self,
+        schema: &ResolvedSchema,
+        field: &ResolvedField,
+        path: &str,
+    ) -> Result<Value, Ros2Error> {
+        let Some(n) = field.fixed_len else {
+            return self.decode_type(schema, &field.ty, path);
+        };
+        if self.options.byte_arrays == ByteArrays::Binary
+            && matches!(field.ty, ResolvedType::Primitive(PrimitiveType::U8))
+        {
+            return Ok(Value::Bytes(Arc::from(self.read_bytes(n, path)?)));
+        }
+        let mut arr = Vec::with_capacity(n);
+        for i in 0..n {
+            let p = format!("{path}[{i}]");
+            arr.push(self.decode_type(schema, &field.ty, &p)?);
+        }
+        Ok(Value::Array(arr))
+    }
+
+    fn decode_type(
+        &mut self,
+        schema: &ResolvedSchema,
+        ty: &ResolvedType,
+        path: &str,
+    ) -> Result<Value, Ros2Error> {
+        match ty {
+            ResolvedType::Primitive(p) => self.decode_primitive(p, path),
+            ResolvedType::Struct(name) => self.decode_struct(schema, name, path),
+            ResolvedType::Sequence { elem, .. } => {
+                let len = self.read_u32(path)? as usize;
+                // Every element takes at least one byte, except empty structs.
+                if len > self.cursor.remaining() && !is_empty_struct(schema, elem) {
+                    return Err(self.eof_error(path));
+                }
+                let mut out = Vec::with_capacity(len.min(self.cursor.remaining()));
+                for i in 0..len {
+                    let p = format!("{path}[{i}]");
+                    out.push(self.decode_type(schema, elem, &p)?);
+                }
+                Ok(Value::List(out))
+            }
+            ResolvedType::Enum(_)
+            | ResolvedType::BoundedString(_)
+            | ResolvedType::BoundedWString(_) => Err(Ros2Error::UnsupportedFeature(format!(
+                "{ty:?} is not a ROS 1 type at {path}"
+            ))),
+        }
+    }
+
+    fn decode_primitive(&mut self, p: &PrimitiveType, path: &str) -> Result<Value, Ros2Error> {
+        let c = &mut self.cursor;
+        let value = match p {
+            PrimitiveType::Bool => c.read_bool().map(Value::Bool),
+            PrimitiveType::I8 => c.read_i8().map(Value::I8),
+            PrimitiveType::I16 => c.read_i16().map(Value::I16),
+            PrimitiveType::I32 => c.read_i32().map(Value::I32),
+            PrimitiveType::I64 => c.read_i64().map(Value::I64),
+            PrimitiveType::U8 | PrimitiveType::Octet => c.read_u8().map(Value::U8),
+            PrimitiveType::U16 => c.read_u16().map(Value::U16),
+            PrimitiveType::U32 => c.read_u32().map(Value::U32),
+            PrimitiveType::U64 => c.read_u64().map(Value::U64),
+            PrimitiveType::F32 => c.read_f32().map(Value::F32),
+            PrimitiveType::F64 => c.read_f64().map(Value::F64),
+            PrimitiveType::String => {
+                let len = self.read_u32(path)? as usize;
+                let bytes = self.read_bytes(len, path)?;
+                return self.string_value(bytes, path);
+            }
+            PrimitiveType::WString => {
+                return Err(Ros2Error::UnsupportedFeature(format!(
+                    "wstring is not a ROS 1 type at {path}"
+                )));
+            }
+        };
+        value.map_err(|_| self.eof_error(path))
+    }
+
+    fn string_value(&self, bytes: &[u8], path: &str) -> Result<Value, Ros2Error> {
+        if self.options.invalid_utf8 == InvalidUtf8::Bytes {
+            return Ok(Value::Bytes(Arc::from(bytes)));
+        }
+        match std::str::from_utf8(bytes) {
+            Ok(text) => Ok(Value::string(text)),
+            Err(e) if self.options.invalid_utf8 == InvalidUtf8::Error => {
+                Err(self.payload_error(path, format!("invalid UTF-8 at {path}: {e}")))
+            }
+            Err(_) => {
+                warn(DecodeWarning::new(
+                    path,
+                    "invalid UTF-8 replaced with U+FFFD",
+                ));
+                Ok(Value::string(String::from_utf8_lossy(bytes)))
+            }
+        }
+    }
+
+    fn read_u32(&mut self, path: &str) -> Result<u32, Ros2Error> {
+        self.cursor.read_u32().map_err(|_| self.eof_error(path))
+    }
+
+    fn read_bytes(&mut self, n: usize, path: &str) -> Result<&'a [u8], Ros2Error> {
+        self.cursor.take(n).map_err(|_| self.eof_error(path))
+    }
+}
+
+fn is_empty_struct(schema: &ResolvedSchema, ty: &ResolvedType) -> bool {
+    matches!(ty, ResolvedType::Struct(name)
+        if schema.structs.get(name).is_some_and(|s| s.fields.is_empty()))
+}
@@ -0,0 +1,133 @@
+//! ROS 1 .msg → ROS 1 serialization decoder for `mcapdecode`.
+//!
+//! Implements [`MessageDecoder`] for the
+//! `(schema_encoding = ros1msg, message_encoding = ros1)` key, as written by
+//! `rosbag` → MCAP conversions of archival bags.
+//!
+//! Schemas are the `gendeps --cat` text of the message type: its `.msg`
+//! definition followed by those of its dependencies, each after a line of
+//! `=` and a `MSG: pkg/Type` header. Types are resolved with the shared ROS
+//! type resolver of `mcapdecode-ros2-common`, and decoded values and field
+//! definitions match those of ROS 2 decoders for the same definitions.
+//!
+//! # Pipeline
+//!
+//! ```text
+//! schema bytes (UTF-8 .msg with bundled dependencies)
+//!   └─ split_bundle        – split at `===` / `MSG:`
+//!       └─ parse_msg       – ROS 1 .msg → StructDef
+//!           └─ resolve_schema – type resolution → ResolvedSchema
+//!               └─ decode_ros1_to_value – ROS 1 serialized bytes → Value
+//! ```
+
+mod decode;
+mod parser;
+
+pub use decode::{Ros1Options, decode_ros1_to_value, decode_ros1_to_value_with};
+use mcapdecode_core::{
+    DecoderError, EncodingKey, FieldDefs, MessageDecoder, MessageEncoding, SchemaEncoding,
+    TopicDecoder, Value,
+};
+pub use mcapdecode_ros2_common::{ByteArrays, InvalidUtf8};
+use mcapdecode_ros2_common::{
+    ParsedSection, ResolvedSchema, Ros2Error, SuffixMatching, resolve_parsed_section_with,
+    resolved_schema_to_field_defs_with,
+};
+pub use parser::{DURATION_TYPE, MsgSection, TIME_TYPE, parse_msg, split_bundle};
+
+/// [`MessageDecoder`] for ROS 1 .msg schemas with ROS 1 serialized messages.
+#[derive(Debug, Default)]
+pub struct Ros1MsgDecoder {
+    options: Ros1Options,
+}
+
+impl Ros1MsgDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Decode strings that are not valid UTF-8 as `invalid_utf8` says
+    /// (default: [`InvalidUtf8::Error`]).
+    pub fn with_invalid_utf8(mut self, invalid_utf8: InvalidUtf8) -> Self {
+        self.options.invalid_utf8 = invalid_utf8;
+        self
+    }
+
+    /// Decode fixed-size `uint8` arrays as `byte_arrays` says (default:
+    /// [`ByteArrays::List`]).
+    pub fn with_byte_arrays(mut self, byte_arrays: ByteArrays) -> Self {
+        self.options.byte_arrays = byte_arrays;
+        self
+    }
+}
+
+impl MessageDecoder for Ros1MsgDecoder {
+    fn encoding_key(&self) -> EncodingKey {
+        EncodingKey::new(SchemaEncoding::Ros1Msg, MessageEncoding::Ros1)
+    }
+
+    fn build_topic_decoder(
+        &self,
+        schema_name: &str,
+        schema_data: &[u8],
+    ) -> Result<Box<dyn TopicDecoder>, DecoderError> {
+        let schema_text =
+            std::str::from_utf8(schema_data).map_err(|e| DecoderError::SchemaParse {
+                schema_name: schema_name.to_string(),
+                source: Box::new(e),
+            })?;
+        let resolved =
+            resolve_schema(schema_name, schema_text).map_err(|e| DecoderError::SchemaParse {
+                schema_name: schema_name.to_string(),
+                source: e.into_source(),
+            })?;
+        Ok(Box::new(Ros1TopicDecoder::new(resolved, self.options)))
+    }
+}
+
+/// Parse a ROS 1 `.msg` schema with its bundled dependencies and produce a
+/// fully resolved schema.
+pub fn resolve_schema(schema_name: &str, schema_text: &str) -> Result<ResolvedSchema, Ros2Error> {
+    let sections = split_bundle(schema_name, schema_text)?;
+    let root = sections[0].msg_path.clone();
+
+    let mut merged = ParsedSection::default();
+    for builtin in parser::builtin_structs() {
+        merged.structs.insert(builtin.full_name.clone(), builtin);
+    }
+    for section in &sections {
+        let path = section.msg_path.join("/");
+        let parsed = parse_msg(&path, &section.body).map_err(|e| e.in_section("msg", &path))?;
+        merged.structs.insert(parsed.full_name.clone(), parsed);
+    }
+    resolve_parsed_section_with(merged, root, SuffixMatching::Auto)
+}
+
+/// Topic decoder of one ROS 1 message type.
+#[derive(Debug, Clone)]
+pub struct Ros1TopicDecoder {
+    resolved: ResolvedSchema,
+    field_defs: FieldDefs,
+    options: Ros1Options,
+}
+
+impl Ros1TopicDecoder {
+    pub fn new(resolved: ResolvedSchema, options: Ros1Options) -> Self {
+        let field_defs = resolved_schema_to_field_defs_with(&resolved, options.cdr_options());
+        Self {
+            resolved,
+            field_defs,
+            options,
+        }
+    }
+}
+
+impl TopicDecoder for Ros1TopicDecoder {
+    fn decode(&self, message_data: &[u8]) -> Result<Value, DecoderError> {
+        decode_ros1_to_value_with(&self.resolved, message_data, self.options)
+    }
+
+    fn field_defs(&self) -> &FieldDefs {
+        &self.field_defs
+    }
+}
@@ -0,0 +1,303 @@
+//! ROS 1 `.msg` parsing into the shared [`StructDef`] representation.
+//!
+//! ROS 1 differs from ROS 2 `.msg` in its builtin types (`time`,
+//! `duration`, the deprecated `byte` / `char` aliases), the implicit
+//! `std_msgs/Header` package of `Header`, and the absence of bounded types
+//! and default values. Schema bundles concatenate the dependencies after
+//! `===` lines, each starting with a `MSG: pkg/Type` header.
+
+use mcapdecode_core::SchemaSyntaxError;
+use mcapdecode_ros2_common::{
+    ConstDef, ConstValue, FieldDef, FieldMeta, PrimitiveType, Ros2Error, StructDef, TypeExpr,
+};
+
+/// Qualified name of the struct standing in for the `time` builtin.
+pub const TIME_TYPE: [&str; 3] = ["ros1msg", "builtin", "time"];
+
+/// Qualified name of the struct standing in for the `duration` builtin.
+pub const DURATION_TYPE: [&str; 3] = ["ros1msg", "builtin", "duration"];
+
+/// One `.msg` section of a ROS 1 schema bundle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MsgSection {
+    /// Qualified name, e.g. `["geometry_msgs", "msg", "Point"]`.
+    pub msg_path: Vec<String>,
+    pub body: String,
+}
+
+/// Split `schema_text` into its `===`-separated sections; the first one is
+/// the root type `schema_name` unless it has a `MSG:` header itself.
+pub fn split_bundle(schema_name: &str, schema_text: &str) -> Result<Vec<MsgSection>, Ros2Error> {
+    let mut sections = Vec::new();
+    let mut lines: Vec<&str> = Vec::new();
+    let mut flush = |lines: &mut Vec<&str>, root: bool| -> Result<(), Ros2Error> {
+        let Some(first) = lines.iter().position(|line| !line.trim().is_empty()) else {
+            lines.clear();
+            return Ok(());
+        };
+        let (msg_path, body) = match lines[first].trim().strip_prefix("MSG:") {
+            Some(name) => (parse_type_name(name.trim())?, &lines[first + 1..]),
+            None if root => (parse_type_name(schema_name)?, &lines[..]),
+            None => {
+                return Err(Ros2Error::ParseError(format!(
+                    "missing `MSG:` header: {}",
+                    lines[first].trim()
+                )));
+            }
+        };
+        sections.push(MsgSection {
+            msg_path,
+            body: body.join("\n"),
+        });
+        lines.clear();
+        Ok(())
+    };
+    let mut root = true;
+    for line in schema_text.lines() {
+        let trimmed = line.trim();
+        if trimmed.len() >= 3 && trimmed.chars().all(|c| c == '=') {
+            flush(&mut lines, root)?;
+            root = false;
+        } else {
+            lines.push(line);
+        }
+    }
+    flush(&mut lines, root)?;
+    if sections.is_empty() {
+        return Err(Ros2Error::ParseError(format!(
+            "no MSG sections found for schema '{schema_name}'"
+        )));
+    }
+    Ok(sections)
+}
+
+/// Parse the ROS 1 `.msg` text of the type `schema_name` (`pkg/Type` or
+/// `pkg/msg/Type`).
+pub fn parse_msg(schema_name: &str, msg_text: &str) -> Result<StructDef, Ros2Error> {
+    let full_name = parse_type_name(schema_name)?;
+    let mut fields = Vec::new();
+    let mut consts = Vec::new();
+    for (idx, line) in msg_text.lines().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let indent = line.len() - trimmed.len();
+        let syntax_error = |offset: usize, message: String| {
+            let column = line[..indent + offset].chars().count() + 1;
+            Ros2Error::from(SchemaSyntaxError::new(
+                idx + 1,
+                column,
+                line.trim_end(),
+                message,
+            ))
+        };
+        let type_len = trimmed.find(char::is_whitespace).unwrap_or(trimmed.len());
+        let (type_name, rest) = trimmed.split_at(type_len);
+        let rest_offset = type_len + (rest.len() - rest.trim_start().len());
+        let rest = rest.trim_start();
+        let (ty, fixed_len) = parse_type(type_name).map_err(|e| syntax_error(0, e))?;
+
+        if let Some((name, value)) = rest.split_once('=') {
+            let name = name.trim();
+            if !is_identifier(name) {
+                return Err(syntax_error(
+                    rest_offset,
+                    format!("invalid constant name '{name}'"),
+                ));
+            }
+            let TypeExpr::Primitive(primitive) = &ty else {
+                return Err(syntax_error(
+                    0,
+                    format!("constant {name} must have a primitive type"),
+                ));
+            };
+            if fixed_len.is_some() {
+                return Err(syntax_error(
+                    0,
+                    format!("constant {name} cannot be an array"),
+                ));
+            }
+            // String constants run to the end of the line, `#` included.
+            let value = match primitive {
+                PrimitiveType::String => value.trim(),
+                _ => split_comment(value).0.trim(),
+            };
+            let typed_value = const_value(primitive, value).ok_or_else(|| {
+                syntax_error(
+                    rest_offset,
+                    format!("invalid {type_name} value '{value}' of constant {name}"),
+                )
+            })?;
+            consts.push(ConstDef {
+                ty: ty.clone(),
+                name: name.to_string(),
+                value: value.to_string(),
+                typed_value: Some(typed_value),
+            });
+            continue;
+        }
+
+        let (decl, comment) = split_comment(rest);
+        let mut tokens = decl.split_whitespace();
+        let Some(name) = tokens.next() else {
+            return Err(syntax_error(
+                rest_offset,
+                format!("missing field name after type {type_name}"),
+            ));
+        };
+        if !is_identifier(name) {
+            return Err(syntax_error(
+                rest_offset,
+                format!("invalid field name '{name}'"),
+            ));
+        }
+        if let Some(extra) = tokens.next() {
+            return Err(syntax_error(
+                rest_offset + decl.find(extra).unwrap_or_default(),
+                format!("unexpected '{extra}' after field {name}"),
+            ));
+        }
+        fields.push(FieldDef {
+            name: name.to_string(),
+            ty,
+            fixed_len,
+            meta: FieldMeta {
+                unit: comment.and_then(FieldMeta::unit_from_comment),
+                ..FieldMeta::default()
+            },
+        });
+    }
+    Ok(StructDef {
+        full_name,
+        fields,
+        consts,
+    })
+}
+
+/// Struct definitions of the `time` and `duration` builtins, which ROS 1
+/// serializes as two 32-bit integers.
+pub(crate) fn builtin_structs() -> [StructDef; 2] {
+    let field = |name: &str, ty: PrimitiveType| FieldDef {
+        name: name.to_string(),
+        ty: TypeExpr::Primitive(ty),
+        fixed_len: None,
+        meta: FieldMeta::default(),
+    };
+    [
+        StructDef {
+            full_name: TIME_TYPE.map(str::to_string).to_vec(),
+            fields: vec![
+                field("secs", PrimitiveType::U32),
+                field("nsecs", PrimitiveType::U32),
+            ],
+            consts: Vec::new(),
+        },
+        StructDef {
+            full_name: DURATION_TYPE.map(str::to_string).to_vec(),
+            fields: vec![
+                field("secs", PrimitiveType::I32),
+                field("nsecs", PrimitiveType::I32),
+            ],
+            consts: Vec::new(),
+        },
+    ]
+}
+
+/// `pkg/Type` or `pkg/msg/Type` → `["pkg", "msg", "Type"]`.
+fn parse_type_name(name: &str) -> Result<Vec<String>, Ros2Error> {
+    match name.split('/').collect::<Vec<_>>().as_slice() {
+        [package, ty] | [package, "msg", ty] if !package.is_empty() && !ty.is_empty() => {
+            Ok(vec![package.to_string(), "msg".to_string(), ty.to_string()])
+        }
+        _ => Err(Ros2Error::ParseError(format!(
+            "invalid schema name format: {name}"
+        ))),
+    }
+}
+
+/// Type of a field declaration and its fixed array length, if any.
+fn parse_type(type_name: &str) -> Result<(TypeExpr, Option<usize>), String> {
+    let Some((base, suffix)) = type_name.split_once('[') else {
+        return Ok((parse_base_type(type_name)?, None));
+    };
+    let Some(len) = suffix.strip_suffix(']') else {
+        return Err(format!("unclosed '[' in type {type_name}"));
+    };
+    let elem = parse_base_type(base)?;
+    if len.is_empty() {
+        return Ok((
+            TypeExpr::Sequence {
+                elem: Box::new(elem),
+                max_len: None,
+            },
+            None,
+        ));
+    }
+    let len = len
+        .parse()
+        .map_err(|_| format!("invalid array length in type {type_name}"))?;
+    Ok((elem, Some(len)))
+}
+
+fn parse_base_type(name: &str) -> Result<TypeExpr, String> {
+    let primitive = match name {
+        "bool" => PrimitiveType::Bool,
+        // `byte` and `char` are deprecated aliases of `int8` and `uint8`.
+        "int8" | "byte" => PrimitiveType::I8,
+        "uint8" | "char" => PrimitiveType::U8,
+        "int16" => PrimitiveType::I16,
+        "uint16" => PrimitiveType::U16,
+        "int32" => PrimitiveType::I32,
+        "uint32" => PrimitiveType::U32,
+        "int64" => PrimitiveType::I64,
+        "uint64" => PrimitiveType::U64,
+        "float32" => PrimitiveType::F32,
+        "float64" => PrimitiveType::F64,
+        "string" => PrimitiveType::String,
+        "time" => return Ok(scoped(&TIME_TYPE)),
+        "duration" => return Ok(scoped(&DURATION_TYPE)),
+        "Header" => return Ok(scoped(&["std_msgs", "msg", "Header"])),
+        _ => {
+            return match name.split_once('/') {
+                Some((package, ty)) if is_identifier(package) && is_identifier(ty) => {
+                    Ok(scoped(&[package, "msg", ty]))
+                }
+                None if is_identifier(name) => Ok(scoped(&[name])),
+                _ => Err(format!("invalid type '{name}'")),
+            };
+        }
+    };
+    Ok(TypeExpr::Primitive(primitive))
+}
+
+fn scoped(name: &[&str]) -> TypeExpr {
+    TypeExpr::Scoped(name.iter().map(|s| s.to_string()).collect())
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Split `text` at its first `#`.
+fn split_comment(text: &str) -> (&str, Option<&str>) {
+    match text.split_once('#') {
+        Some((decl, comment)) => (decl, Some(comment)),
+        None => (text, None),
+    }
+}
+
+fn const_value(ty: &PrimitiveType, value: &str) -> Option<ConstValue> {
+    match ty {
+        PrimitiveType::String => Some(ConstValue::String(value.to_string())),
+        PrimitiveType::Bool => match value {
+            "true" | "True" | "1" => Some(ConstValue::Bool(true)),
+            "false" | "False" | "0" => Some(ConstValue::Bool(false)),
+            _ => None,
+        },
+        PrimitiveType::F32 | PrimitiveType::F64 => value.parse().ok().map(ConstValue::Float),
+        _ => value.parse().ok().map(ConstValue::Int),
+    }
+}
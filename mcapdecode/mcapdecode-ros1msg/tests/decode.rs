@@ -0,0 +1,180 @@
+use mcapdecode_core::{DataTypeDef, DecoderError, MessageDecoder, SchemaSyntaxError, Value};
+use mcapdecode_ros1msg::{
+    ByteArrays, Ros1MsgDecoder, TIME_TYPE, parse_msg, resolve_schema, split_bundle,
+};
+use mcapdecode_ros2_common::{ConstValue, PrimitiveType, Ros2Error, TypeExpr};
+
+/// `sensor_msgs/Range`-like bundle with its `Header` dependency, as
+/// written by rosbag → MCAP conversions.
+const RANGE_BUNDLE: &str = "\
+uint8 ULTRASOUND=0
+uint8 INFRARED=1
+Header header
+uint8 radiation_type
+float32 range # m
+uint16[] samples
+char[4] tag
+================================================================================
+MSG: std_msgs/Header
+uint32 seq
+time stamp
+string frame_id
+";
+
+fn push_string(buf: &mut Vec<u8>, s: &str) {
+    buf.extend_from_slice(&(s.len() as u32).to_le_bytes());
+    buf.extend_from_slice(s.as_bytes());
+}
+
+fn range_payload() -> Vec<u8> {
+    let mut buf = Vec::new();
+    buf.extend_from_slice(&7u32.to_le_bytes());
+    buf.extend_from_slice(&100u32.to_le_bytes());
+    buf.extend_from_slice(&5u32.to_le_bytes());
+    push_string(&mut buf, "sonar");
+    buf.push(1);
+    buf.extend_from_slice(&1.5f32.to_le_bytes());
+    buf.extend_from_slice(&2u32.to_le_bytes());
+    buf.extend_from_slice(&10u16.to_le_bytes());
+    buf.extend_from_slice(&20u16.to_le_bytes());
+    buf.extend_from_slice(b"abcd");
+    buf
+}
+
+#[test]
+fn splits_bundle_into_root_and_msg_sections() {
+    let sections = split_bundle("sensor_msgs/Range", RANGE_BUNDLE).unwrap();
+    let paths: Vec<_> = sections.iter().map(|s| s.msg_path.join("/")).collect();
+    assert_eq!(paths, vec!["sensor_msgs/msg/Range", "std_msgs/msg/Header"]);
+    assert!(sections[1].body.starts_with("uint32 seq"));
+}
+
+#[test]
+fn parses_ros1_builtins_and_aliases() {
+    let msg = "time t\nduration d\nbyte b\nchar c\nHeader h\ngeometry_msgs/Point p\nPoint q\n";
+    let parsed = parse_msg("pkg/Foo", msg).unwrap();
+    let types: Vec<_> = parsed.fields.iter().map(|f| &f.ty).collect();
+    let scoped = |path: &[&str]| TypeExpr::Scoped(path.iter().map(|s| s.to_string()).collect());
+    assert_eq!(
+        types,
+        vec![
+            &scoped(&TIME_TYPE),
+            &scoped(&["ros1msg", "builtin", "duration"]),
+            &TypeExpr::Primitive(PrimitiveType::I8),
+            &TypeExpr::Primitive(PrimitiveType::U8),
+            &scoped(&["std_msgs", "msg", "Header"]),
+            &scoped(&["geometry_msgs", "msg", "Point"]),
+            &scoped(&["Point"]),
+        ]
+    );
+}
+
+#[test]
+fn parses_constants_and_units() {
+    let msg = "int32 X=-3 # comment\nstring S=a # b\nfloat64 y # [m/s]\n";
+    let parsed = parse_msg("pkg/Foo", msg).unwrap();
+    let consts: Vec<_> = parsed
+        .consts
+        .iter()
+        .map(|c| (c.name.as_str(), c.typed_value.clone()))
+        .collect();
+    assert_eq!(
+        consts,
+        vec![
+            ("X", Some(ConstValue::Int(-3))),
+            ("S", Some(ConstValue::String("a # b".to_string()))),
+        ]
+    );
+    assert_eq!(parsed.fields[0].meta.unit.as_deref(), Some("m/s"));
+}
+
+#[test]
+fn reports_syntax_error_location_in_section() {
+    let bundle = "pkg/Bar bar\n===\nMSG: pkg/Bar\nint32 x\n  int32[ y\n";
+    let Err(DecoderError::SchemaParse { source, .. }) =
+        Ros1MsgDecoder::new().build_topic_decoder("pkg/Foo", bundle.as_bytes())
+    else {
+        panic!("unclosed array bracket should fail to parse");
+    };
+    let syntax = source
+        .downcast_ref::<SchemaSyntaxError>()
+        .expect("source should be a syntax error");
+    assert_eq!(syntax.section.as_deref(), Some("pkg/msg/Bar"));
+    assert_eq!((syntax.line, syntax.column), (2, 3));
+    assert_eq!(syntax.snippet, "  int32[ y");
+}
+
+#[test]
+fn decodes_ros1_serialized_message() {
+    let decoder = Ros1MsgDecoder::new()
+        .build_topic_decoder("sensor_msgs/Range", RANGE_BUNDLE.as_bytes())
+        .unwrap();
+    let value = decoder.decode(&range_payload()).unwrap();
+    assert_eq!(
+        value,
+        Value::Struct(vec![
+            Value::Struct(vec![
+                Value::U32(7),
+                Value::Struct(vec![Value::U32(100), Value::U32(5)]),
+                Value::string("sonar"),
+            ]),
+            Value::U8(1),
+            Value::F32(1.5),
+            Value::List(vec![Value::U16(10), Value::U16(20)]),
+            Value::Array(b"abcd".iter().map(|b| Value::U8(*b)).collect()),
+        ])
+    );
+
+    let names: Vec<_> = decoder
+        .field_defs()
+        .iter()
+        .map(|f| f.name.as_str())
+        .collect();
+    assert_eq!(
+        names,
+        vec!["header", "radiation_type", "range", "samples", "tag"]
+    );
+    let DataTypeDef::Struct(header) = &decoder.field_defs()[0].element.data_type else {
+        panic!("header should be a struct");
+    };
+    assert!(header[1].is_timestamp());
+    let DataTypeDef::Struct(stamp) = &header[1].element.data_type else {
+        panic!("stamp should be a struct");
+    };
+    let stamp: Vec<_> = stamp
+        .iter()
+        .map(|f| (f.name.as_str(), f.element.data_type.clone()))
+        .collect();
+    assert_eq!(
+        stamp,
+        vec![("secs", DataTypeDef::U32), ("nsecs", DataTypeDef::U32)]
+    );
+}
+
+#[test]
+fn decodes_fixed_byte_arrays_as_binary() {
+    let decoder = Ros1MsgDecoder::new()
+        .with_byte_arrays(ByteArrays::Binary)
+        .build_topic_decoder("sensor_msgs/Range", RANGE_BUNDLE.as_bytes())
+        .unwrap();
+    let Value::Struct(fields) = decoder.decode(&range_payload()).unwrap() else {
+        panic!("message should decode to a struct");
+    };
+    assert_eq!(fields[4], Value::Bytes(b"abcd".as_slice().into()));
+}
+
+#[test]
+fn truncated_payload_reports_field_and_offset() {
+    let resolved = resolve_schema("sensor_msgs/Range", RANGE_BUNDLE).unwrap();
+    let payload = range_payload();
+    let Err(DecoderError::MessageDecode { source, .. }) =
+        mcapdecode_ros1msg::decode_ros1_to_value(&resolved, &payload[..15])
+    else {
+        panic!("truncated payload should fail to decode");
+    };
+    let Some(Ros2Error::CdrError { path, offset, .. }) = source.downcast_ref::<Ros2Error>() else {
+        panic!("expected a payload error, got {source}");
+    };
+    assert_eq!(path, "sensor_msgs.msg.Range.header.frame_id");
+    assert_eq!(*offset, 12);
+}
@@ -3,7 +3,10 @@
 //! Both parsers produce these types, which are then consumed by
 //! [`crate::type_resolver`] to produce a fully resolved schema.
 
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    hash::{Hash, Hasher},
+};
 
 /// Scalar primitive types supported by ROS 2 IDL and .msg formats.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -52,6 +55,73 @@ pub struct FieldDef {
     pub ty: TypeExpr,
     /// `Some(n)` means the field is a fixed-length array of `n` elements.
     pub fixed_len: Option<usize>,
+    pub meta: FieldMeta,
+}
+
+/// Descriptive information attached to a field by comments or annotations.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FieldMeta {
+    /// Physical unit of the value, e.g. `m/s`.
+    pub unit: Option<String>,
+    /// Documentation of the field, e.g. from an IDL `@verbatim` comment.
+    pub doc: Option<String>,
+    /// Whether the field is a DDS instance key (`@key`).
+    pub key: bool,
+}
+
+/// Units recognised when a field comment consists of a single word.
+const KNOWN_UNITS: &[&str] = &[
+    "%", "A", "C", "K", "N", "Nm", "Pa", "T", "V", "W", "deg", "degC", "dB", "g", "h", "hPa", "Hz",
+    "kg", "kHz", "km", "kPa", "lux", "m", "mA", "mm", "cm", "ms", "mV", "ns", "rad", "rpm", "s",
+    "us",
+];
+
+impl FieldMeta {
+    /// Extract a unit from a field comment.
+    ///
+    /// Recognised forms are a bracketed unit anywhere in the comment
+    /// (`velocity [m/s]`), a `unit:` / `units:` prefix, and a comment that is
+    /// a single unit-like word (`m/s`, `rad`).
+    pub fn unit_from_comment(comment: &str) -> Option<String> {
+        let comment = comment.trim();
+        let comment_lower = comment.to_ascii_lowercase();
+        for prefix in ["units:", "unit:"] {
+            if comment_lower.starts_with(prefix) {
+                return comment[prefix.len()..]
+                    .split_whitespace()
+                    .next()
+                    .map(str::to_string);
+            }
+        }
+
+        let mut rest = comment;
+        while let Some(start) = rest.find('[') {
+            let Some(len) = rest[start + 1..].find(']') else {
+                break;
+            };
+            let inner = rest[start + 1..start + 1 + len].trim();
+            if is_unit_like(inner) {
+                return Some(inner.to_string());
+            }
+            rest = &rest[start + 1 + len..];
+        }
+
+        let mut words = comment.split_whitespace();
+        match (words.next(), words.next()) {
+            (Some(word), None) if word.contains('/') && is_unit_like(word) => {
+                Some(word.to_string())
+            }
+            (Some(word), None) if KNOWN_UNITS.contains(&word) => Some(word.to_string()),
+            _ => None,
+        }
+    }
+}
+
+fn is_unit_like(s: &str) -> bool {
+    !s.is_empty()
+        && s.chars().any(|c| c.is_alphabetic() || c == '%')
+        && s.chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '/' | '^' | '*' | '.' | '%' | '-'))
 }
 
 /// A constant defined inside a struct (`const T NAME = VALUE;`).
@@ -61,6 +131,56 @@ pub struct ConstDef {
     pub name: String,
     /// Raw string representation of the constant value as it appeared in the source.
     pub value: String,
+    /// `value` evaluated and converted to `ty`; `None` if it could not be
+    /// evaluated (e.g. it references a constant of another scope).
+    pub typed_value: Option<ConstValue>,
+}
+
+/// Evaluated value of a constant.
+#[derive(Debug, Clone)]
+pub enum ConstValue {
+    Bool(bool),
+    /// Any integer type; wide enough for both `int64` and `uint64`.
+    Int(i128),
+    Float(f64),
+    String(String),
+}
+
+impl ConstValue {
+    /// The value as a length or size, if it is a non-negative integer.
+    pub fn as_usize(&self) -> Option<usize> {
+        match self {
+            Self::Int(v) => usize::try_from(*v).ok(),
+            _ => None,
+        }
+    }
+}
+
+// Floats compare (and hash) by bit pattern so that `ConstDef` can stay `Eq`.
+impl PartialEq for ConstValue {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Bool(a), Self::Bool(b)) => a == b,
+            (Self::Int(a), Self::Int(b)) => a == b,
+            (Self::Float(a), Self::Float(b)) => a.to_bits() == b.to_bits(),
+            (Self::String(a), Self::String(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for ConstValue {}
+
+impl Hash for ConstValue {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Self::Bool(v) => v.hash(state),
+            Self::Int(v) => v.hash(state),
+            Self::Float(v) => v.to_bits().hash(state),
+            Self::String(v) => v.hash(state),
+        }
+    }
 }
 
 /// A fully-parsed struct definition with its qualified name.
@@ -86,4 +206,6 @@ pub struct EnumDef {
 pub struct ParsedSection {
     pub structs: HashMap<Vec<String>, StructDef>,
     pub enums: HashMap<Vec<String>, EnumDef>,
+    /// Constants declared outside structs, keyed by module path plus name.
+    pub consts: HashMap<Vec<String>, ConstDef>,
 }
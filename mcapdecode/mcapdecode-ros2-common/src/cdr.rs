@@ -2,8 +2,8 @@
 
 use std::sync::Arc;
 
-use bytes::{Buf, Bytes};
-use mcapdecode_core::{DecoderError, Value};
+use mcapdecode_core::{DecodeWarning, DecoderError, Value, format_uuid, warn};
+use mcapdecode_decoder_kit::{ByteCursor, Endian};
 
 use crate::{
     ast::PrimitiveType,
@@ -11,9 +11,143 @@ use crate::{
     type_resolver::{ResolvedField, ResolvedSchema, ResolvedType},
 };
 
+/// How strings that are not valid UTF-8 are decoded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InvalidUtf8 {
+    /// Fail to decode the message (default).
+    #[default]
+    Error,
+    /// Replace invalid sequences with U+FFFD and report a
+    /// [`DecodeWarning`] for each replaced string.
+    Replace,
+    /// Decode every string as bytes (without the null terminator), so that
+    /// string fields become binary columns.
+    Bytes,
+}
+
+/// CDR layout conventions in which writers of the same type differ.
+///
+/// ROS 2 messages are laid out by rosidl typesupport whichever RMW wrote
+/// them, so bags recorded with Fast DDS and Cyclone DDS decode identically
+/// with the default; the other modes are for payloads of plain DDS types.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CdrQuirks {
+    /// rosidl typesupport (every ROS 2 RMW): classic CDR alignment, and
+    /// empty structs carry a one-byte `structure_needs_at_least_one_member`
+    /// placeholder (default).
+    #[default]
+    Rosidl,
+    /// Plain DDS types in classic CDR (XCDR1): empty structs occupy no bytes.
+    Dds,
+    /// Final DDS types in XCDR2: 8-byte primitives are aligned to 4 bytes and
+    /// empty structs occupy no bytes, also under a classic `CDR_LE`
+    /// encapsulation header.
+    Xcdr2,
+}
+
+/// Extensibility of the structs of an XCDR2 payload, as told by its
+/// encapsulation identifier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Extensibility {
+    /// `CDR2`: members follow each other, as in classic CDR.
+    Final,
+    /// `D_CDR2`: every struct is preceded by a `DHEADER` with its size, so
+    /// that members appended by newer type versions can be skipped.
+    Appendable,
+    /// `PL_CDR2`: a `DHEADER`, then each member preceded by an `EMHEADER`
+    /// with its member ID and size.
+    Mutable,
+}
+
+/// How fixed-size `uint8` / `octet` arrays are decoded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ByteArrays {
+    /// Arrays of `U8` values, i.e. fixed-size lists of `UInt8` (default).
+    #[default]
+    List,
+    /// Bytes, i.e. `FixedSizeBinary(N)` columns for `uint8[N]` fields.
+    Binary,
+}
+
+/// How `unique_identifier_msgs/msg/UUID` values are decoded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UuidRepresentation {
+    /// A struct with the `uuid` byte array (default).
+    #[default]
+    Struct,
+    /// The 16 bytes, i.e. `FixedSizeBinary(16)` columns for UUID fields.
+    Binary,
+    /// Canonical lowercase strings (`xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx`).
+    String,
+}
+
+/// Options of [`decode_cdr_to_value_with`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CdrOptions {
+    pub invalid_utf8: InvalidUtf8,
+    pub quirks: CdrQuirks,
+    pub byte_arrays: ByteArrays,
+    pub uuids: UuidRepresentation,
+}
+
+/// Byte length of `unique_identifier_msgs/msg/UUID`.
+pub(crate) const UUID_LEN: usize = 16;
+
+/// Whether `name` is `unique_identifier_msgs/msg/UUID` (also in its DDS
+/// spelling `unique_identifier_msgs::msg::dds_::UUID_`) with its usual
+/// single `uint8[16] uuid` member.
+pub(crate) fn is_uuid(schema: &ResolvedSchema, name: &[String]) -> bool {
+    let named = match name {
+        [package, msg, ty] => package == "unique_identifier_msgs" && msg == "msg" && ty == "UUID",
+        [package, msg, dds, ty] => {
+            package == "unique_identifier_msgs" && msg == "msg" && dds == "dds_" && ty == "UUID_"
+        }
+        _ => false,
+    };
+    named
+        && schema.structs.get(name).is_some_and(|s| {
+            matches!(
+                s.fields.as_slice(),
+                [field] if field.fixed_len == Some(UUID_LEN)
+                    && matches!(
+                        field.ty,
+                        ResolvedType::Primitive(PrimitiveType::U8 | PrimitiveType::Octet)
+                    )
+            )
+        })
+}
+
+/// The bytes of a decoded UUID struct, its `uuid` member read as bytes or
+/// as an array of `U8`.
+fn uuid_struct_bytes(value: &Value) -> Option<Vec<u8>> {
+    match value {
+        Value::Struct(fields) => match fields.as_slice() {
+            [Value::Bytes(bytes)] => Some(bytes.to_vec()),
+            [Value::Array(items)] => items
+                .iter()
+                .map(|item| match item {
+                    Value::U8(b) => Some(*b),
+                    _ => None,
+                })
+                .collect(),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
 pub fn decode_cdr_to_value(schema: &ResolvedSchema, data: &[u8]) -> Result<Value, DecoderError> {
-    let bytes = Bytes::copy_from_slice(data);
-    let mut d = Decoder::new(bytes);
+    decode_cdr_to_value_with(schema, data, CdrOptions::default())
+}
+
+/// Like [`decode_cdr_to_value`], with the string and layout handling of
+/// `options`.
+pub fn decode_cdr_to_value_with(
+    schema: &ResolvedSchema,
+    data: &[u8],
+    options: CdrOptions,
+) -> Result<Value, DecoderError> {
+    let mut d = Decoder::new(data, options);
     d.read_encapsulation()
         .map_err(|detail| DecoderError::MessageDecode {
             schema_name: schema.root.join("::"),
@@ -35,37 +169,119 @@ fn primitive_align_size(p: &PrimitiveType) -> usize {
     }
 }
 
-struct Decoder {
-    buf: Bytes,
-    initial_len: usize,
-    align_base: usize,
+/// Whether the elements of a collection of `ty` are primitive in XCDR2
+/// terms, i.e. the collection has no `DHEADER`.
+fn is_xcdr2_primitive(ty: &ResolvedType) -> bool {
+    match ty {
+        ResolvedType::Primitive(p) => !matches!(p, PrimitiveType::String | PrimitiveType::WString),
+        ResolvedType::Enum(_) => true,
+        _ => false,
+    }
+}
+
+struct Decoder<'a> {
+    cursor: ByteCursor<'a>,
+    invalid_utf8: InvalidUtf8,
+    quirks: CdrQuirks,
+    byte_arrays: ByteArrays,
+    uuids: UuidRepresentation,
+    /// Extensibility of the structs if the payload is XCDR2.
+    xcdr2: Option<Extensibility>,
 }
 
-impl Decoder {
-    fn new(buf: Bytes) -> Self {
-        let initial_len = buf.len();
+impl<'a> Decoder<'a> {
+    fn new(data: &'a [u8], options: CdrOptions) -> Self {
         Self {
-            buf,
-            initial_len,
-            align_base: 0,
+            cursor: ByteCursor::new(data, Endian::Little),
+            invalid_utf8: options.invalid_utf8,
+            quirks: options.quirks,
+            byte_arrays: options.byte_arrays,
+            uuids: options.uuids,
+            xcdr2: (options.quirks == CdrQuirks::Xcdr2).then_some(Extensibility::Final),
         }
     }
 
     fn read_encapsulation(&mut self) -> Result<(), Ros2Error> {
-        if self.buf.remaining() < 4 {
-            return Err("incomplete encapsulation header".into());
+        let header = self
+            .cursor
+            .take(4)
+            .map_err(|_| self.cdr_error("", "incomplete encapsulation header"))?;
+        // The RTPS identifiers of CDR and of the XCDR2 encodings, and the
+        // XTypes spellings of the latter; the low bit is set for little
+        // endian.
+        self.xcdr2 = match header[1] & !0x01 {
+            0x00 => self.xcdr2,
+            0x06 | 0x10 => Some(Extensibility::Final),
+            0x08 | 0x14 => Some(Extensibility::Appendable),
+            0x0a | 0x12 => Some(Extensibility::Mutable),
+            0x02 => {
+                return Err(Ros2Error::UnsupportedFeature(
+                    "unsupported CDR encapsulation: PL_CDR".to_string(),
+                ));
+            }
+            _ => {
+                return Err(Ros2Error::UnsupportedFeature(format!(
+                    "unsupported CDR encapsulation: 0x{:02x}",
+                    header[1]
+                )));
+            }
+        };
+        self.cursor.set_endian(if header[1] & 0x01 != 0 {
+            Endian::Little
+        } else {
+            Endian::Big
+        });
+        self.cursor.set_align_base(4);
+        Ok(())
+    }
+
+    /// Largest alignment of a primitive.
+    fn max_align(&self) -> usize {
+        if self.xcdr2.is_some() { 4 } else { 8 }
+    }
+
+    /// Read an XCDR2 `DHEADER` and return the offset its object ends at.
+    fn read_dheader(&mut self, path: &str) -> Result<usize, Ros2Error> {
+        self.align(4, path)?;
+        let size = self.read_u32(path)? as usize;
+        let end = self.cursor.offset() + size;
+        if size > self.cursor.remaining() {
+            return Err(self.cdr_error(
+                path,
+                format!("DHEADER size {size} exceeds the payload at {path}"),
+            ));
         }
-        let header = self.buf.get_u32_le();
-        let endianness = (header >> 8) & 0xFF;
-        if endianness != 0x01 {
-            return Err(format!("unsupported CDR endianness: 0x{:02x}", endianness as u8).into());
+        Ok(end)
+    }
+
+    /// Skip to `end`, the end of an object of known size.
+    fn skip_to(&mut self, end: usize, path: &str) -> Result<(), Ros2Error> {
+        let offset = self.cursor.offset();
+        if offset > end {
+            return Err(self.cdr_error(
+                path,
+                format!(
+                    "{path} overruns its declared size by {} bytes",
+                    offset - end
+                ),
+            ));
+        }
+        self.cursor
+            .skip(end - offset)
+            .map_err(|_| self.eof_error(path))
+    }
+
+    /// [`Ros2Error::CdrError`] at `path` and the current read position.
+    fn cdr_error(&self, path: &str, detail: impl Into<String>) -> Ros2Error {
+        Ros2Error::CdrError {
+            path: path.to_string(),
+            offset: self.cursor.offset(),
+            detail: detail.into(),
         }
-        self.align_base = 4;
-        Ok(())
     }
 
-    fn current_offset(&self) -> usize {
-        self.initial_len - self.buf.remaining()
+    fn eof_error(&self, path: &str) -> Ros2Error {
+        self.cdr_error(path, format!("unexpected EOF at {path}"))
     }
 
     fn decode_struct(
@@ -74,10 +290,24 @@ impl Decoder {
         struct_name: &[String],
         path: &str,
     ) -> Result<Value, Ros2Error> {
-        let s = schema
-            .structs
-            .get(struct_name)
-            .ok_or_else(|| format!("unknown struct: {}", struct_name.join("::")))?;
+        let s = schema.structs.get(struct_name).ok_or_else(|| {
+            Ros2Error::UnresolvedType(format!("unknown struct: {}", struct_name.join("::")))
+        })?;
+        match self.xcdr2 {
+            Some(Extensibility::Appendable) => {
+                return self.decode_appendable_struct(schema, &s.fields, path);
+            }
+            Some(Extensibility::Mutable) => {
+                return self.decode_mutable_struct(schema, &s.fields, path);
+            }
+            Some(Extensibility::Final) | None => {}
+        }
+        if s.fields.is_empty() {
+            if self.quirks == CdrQuirks::Rosidl {
+                self.cursor.read_u8().map_err(|_| self.eof_error(path))?;
+            }
+            return Ok(Value::Struct(Vec::new()));
+        }
         let mut fields = Vec::with_capacity(s.fields.len());
         for field in &s.fields {
             let field_path = format!("{}.{}", path, field.name);
@@ -87,6 +317,156 @@ impl Decoder {
         Ok(Value::Struct(fields))
     }
 
+    /// Members of a `D_CDR2` struct: members missing from an older type
+    /// version take their default value, and members of a newer one are
+    /// skipped.
+    fn decode_appendable_struct(
+        &mut self,
+        schema: &ResolvedSchema,
+        members: &[ResolvedField],
+        path: &str,
+    ) -> Result<Value, Ros2Error> {
+        let end = self.read_dheader(path)?;
+        let mut fields = Vec::with_capacity(members.len());
+        for field in members {
+            let field_path = format!("{}.{}", path, field.name);
+            if self.cursor.offset() >= end {
+                fields.push(self.default_value(schema, &field.ty, field.fixed_len, &field_path)?);
+                continue;
+            }
+            fields.push(self.decode_field(schema, field, &field_path)?);
+        }
+        self.skip_to(end, path)?;
+        Ok(Value::Struct(fields))
+    }
+
+    /// Members of a `PL_CDR2` struct, in any order, identified by their
+    /// member ID (the member index, as IDL assigns them by default). Absent
+    /// members take their default value, and unknown ones are skipped unless
+    /// they must be understood.
+    fn decode_mutable_struct(
+        &mut self,
+        schema: &ResolvedSchema,
+        members: &[ResolvedField],
+        path: &str,
+    ) -> Result<Value, Ros2Error> {
+        let end = self.read_dheader(path)?;
+        let mut fields = vec![None; members.len()];
+        while self.cursor.offset() < end {
+            self.align(4, path)?;
+            let emheader = self.read_u32(path)?;
+            let must_understand = emheader & 0x8000_0000 != 0;
+            let id = (emheader & 0x0fff_ffff) as usize;
+            let size = match (emheader >> 28) & 0x7 {
+                lc @ 0..=3 => 1 << lc,
+                4 => self.read_u32(path)? as usize,
+                // The length is the member's own leading count (e.g. of a
+                // sequence), which stays part of its value.
+                lc => {
+                    let mut peek = self.cursor.clone();
+                    let count = peek.read_u32().map_err(|_| self.eof_error(path))? as usize;
+                    4 + count * [1, 4, 8][lc as usize - 5]
+                }
+            };
+            let member_end = self.cursor.offset() + size;
+            match members.get(id) {
+                Some(field) => {
+                    let field_path = format!("{}.{}", path, field.name);
+                    fields[id] = Some(self.decode_field(schema, field, &field_path)?);
+                    self.skip_to(member_end, &field_path)?;
+                }
+                None if must_understand => {
+                    return Err(self.cdr_error(
+                        path,
+                        format!("unknown member ID {id} of {path} must be understood"),
+                    ));
+                }
+                None => self.skip_to(member_end, path)?,
+            }
+        }
+        self.skip_to(end, path)?;
+        let fields = fields
+            .into_iter()
+            .zip(members)
+            .map(|(value, field)| match value {
+                Some(value) => Ok(value),
+                None => {
+                    let field_path = format!("{}.{}", path, field.name);
+                    self.default_value(schema, &field.ty, field.fixed_len, &field_path)
+                }
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Value::Struct(fields))
+    }
+
+    /// Value of a member absent from an XCDR2 struct: zero, empty or the
+    /// first enumerator, as XTypes defaults it.
+    fn default_value(
+        &self,
+        schema: &ResolvedSchema,
+        ty: &ResolvedType,
+        fixed_len: Option<usize>,
+        path: &str,
+    ) -> Result<Value, Ros2Error> {
+        if let Some(n) = fixed_len {
+            if self.byte_arrays == ByteArrays::Binary
+                && matches!(
+                    ty,
+                    ResolvedType::Primitive(PrimitiveType::U8 | PrimitiveType::Octet)
+                )
+            {
+                return Ok(Value::Bytes(Arc::from(vec![0; n])));
+            }
+            let arr = (0..n)
+                .map(|i| self.default_value(schema, ty, None, &format!("{path}[{i}]")))
+                .collect::<Result<_, _>>()?;
+            return Ok(Value::Array(arr));
+        }
+        Ok(match ty {
+            ResolvedType::Primitive(p) => match p {
+                PrimitiveType::Bool => Value::Bool(false),
+                PrimitiveType::I8 => Value::I8(0),
+                PrimitiveType::I16 => Value::I16(0),
+                PrimitiveType::I32 => Value::I32(0),
+                PrimitiveType::I64 => Value::I64(0),
+                PrimitiveType::U8 | PrimitiveType::Octet => Value::U8(0),
+                PrimitiveType::U16 => Value::U16(0),
+                PrimitiveType::U32 => Value::U32(0),
+                PrimitiveType::U64 => Value::U64(0),
+                PrimitiveType::F32 => Value::F32(0.0),
+                PrimitiveType::F64 => Value::F64(0.0),
+                PrimitiveType::String | PrimitiveType::WString => self.string_value(b"", path)?,
+            },
+            ResolvedType::BoundedString(_) | ResolvedType::BoundedWString(_) => {
+                self.string_value(b"", path)?
+            }
+            ResolvedType::Struct(name)
+                if self.uuids != UuidRepresentation::Struct && is_uuid(schema, name) =>
+            {
+                self.uuid_value(&[0; UUID_LEN])
+            }
+            ResolvedType::Struct(name) => {
+                let s = schema.structs.get(name).ok_or_else(|| {
+                    Ros2Error::UnresolvedType(format!("unknown struct: {}", name.join("::")))
+                })?;
+                let fields = s
+                    .fields
+                    .iter()
+                    .map(|field| {
+                        let field_path = format!("{}.{}", path, field.name);
+                        self.default_value(schema, &field.ty, field.fixed_len, &field_path)
+                    })
+                    .collect::<Result<_, _>>()?;
+                Value::Struct(fields)
+            }
+            ResolvedType::Enum(name) => {
+                let first = schema.enums.get(name).and_then(|vars| vars.first());
+                Value::string(first.map_or("0", String::as_str))
+            }
+            ResolvedType::Sequence { .. } => Value::List(Vec::new()),
+        })
+    }
+
     fn decode_field(
         &mut self,
         schema: &ResolvedSchema,
@@ -94,11 +474,26 @@ impl Decoder {
         path: &str,
     ) -> Result<Value, Ros2Error> {
         if let Some(n) = field.fixed_len {
+            if self.byte_arrays == ByteArrays::Binary
+                && matches!(
+                    field.ty,
+                    ResolvedType::Primitive(PrimitiveType::U8 | PrimitiveType::Octet)
+                )
+            {
+                return Ok(Value::Bytes(Arc::from(self.read_bytes(n, path)?)));
+            }
+            let end = match self.xcdr2 {
+                Some(_) if !is_xcdr2_primitive(&field.ty) => Some(self.read_dheader(path)?),
+                _ => None,
+            };
             let mut arr = Vec::with_capacity(n);
             for i in 0..n {
                 let p = format!("{path}[{i}]");
                 arr.push(self.decode_type(schema, &field.ty, &p)?);
             }
+            if let Some(end) = end {
+                self.skip_to(end, path)?;
+            }
             return Ok(Value::Array(arr));
         }
         self.decode_type(schema, &field.ty, path)
@@ -113,108 +508,158 @@ impl Decoder {
         match ty {
             ResolvedType::Primitive(p) => self.decode_primitive(p, path),
             ResolvedType::BoundedString(max) => {
-                let s = self.decode_string(path)?;
-                if s.len() > *max {
-                    return Err(
-                        format!("bounded string overflow at {path}: {} > {max}", s.len()).into(),
-                    );
+                let bytes = self.decode_string_bytes(path)?;
+                if bytes.len() > *max {
+                    return Err(self.cdr_error(
+                        path,
+                        format!("bounded string overflow at {path}: {} > {max}", bytes.len()),
+                    ));
                 }
-                Ok(Value::String(Arc::from(s)))
+                self.string_value(bytes, path)
             }
-            ResolvedType::BoundedWString(_max) => {
-                Err(format!("wstring not supported at {path}").into())
+            ResolvedType::BoundedWString(_max) => Err(Ros2Error::UnsupportedFeature(format!(
+                "wstring not supported at {path}"
+            ))),
+            ResolvedType::Struct(name)
+                if self.uuids != UuidRepresentation::Struct && is_uuid(schema, name) =>
+            {
+                // Only final structs are the 16 bytes alone; appendable and
+                // mutable ones start with a DHEADER.
+                if matches!(self.xcdr2, None | Some(Extensibility::Final)) {
+                    let bytes = self.read_bytes(UUID_LEN, path)?;
+                    return Ok(self.uuid_value(bytes));
+                }
+                let value = self.decode_struct(schema, name, path)?;
+                let bytes = uuid_struct_bytes(&value)
+                    .ok_or_else(|| self.cdr_error(path, format!("malformed UUID at {path}")))?;
+                Ok(self.uuid_value(&bytes))
             }
             ResolvedType::Struct(name) => self.decode_struct(schema, name, path),
             ResolvedType::Enum(name) => {
-                self.align(4)?;
-                let raw = self
-                    .buf
-                    .try_get_u32_le()
-                    .map_err(|_| Ros2Error(format!("unexpected EOF at {path}")))?;
+                self.align(4, path)?;
+                let raw = self.read_u32(path)?;
                 let s = match schema.enums.get(name) {
                     Some(vars) if (raw as usize) < vars.len() => vars[raw as usize].clone(),
-                    _ => raw.to_string(),
+                    _ => {
+                        warn(DecodeWarning::new(
+                            path,
+                            format!("unknown value {raw} of enum {}", name.join("::")),
+                        ));
+                        raw.to_string()
+                    }
                 };
                 Ok(Value::String(Arc::from(s)))
             }
             ResolvedType::Sequence { elem, max_len } => {
-                self.align(4)?;
-                let len = self
-                    .buf
-                    .try_get_u32_le()
-                    .map_err(|_| Ros2Error(format!("unexpected EOF at {path}")))?
-                    as usize;
+                let end = match self.xcdr2 {
+                    Some(_) if !is_xcdr2_primitive(elem) => Some(self.read_dheader(path)?),
+                    _ => None,
+                };
+                self.align(4, path)?;
+                let len = self.read_u32(path)? as usize;
                 if let Some(max) = max_len
                     && len > *max
                 {
-                    return Err(format!("sequence bound overflow at {path}: {len} > {max}").into());
+                    return Err(self.cdr_error(
+                        path,
+                        format!("sequence bound overflow at {path}: {len} > {max}"),
+                    ));
                 }
-                let mut out = Vec::with_capacity(len);
+                let mut out = Vec::with_capacity(len.min(self.cursor.remaining()));
                 for i in 0..len {
                     let p = format!("{path}[{i}]");
                     out.push(self.decode_type(schema, elem, &p)?);
                 }
+                if let Some(end) = end {
+                    self.skip_to(end, path)?;
+                }
                 Ok(Value::List(out))
             }
         }
     }
 
     fn decode_primitive(&mut self, p: &PrimitiveType, path: &str) -> Result<Value, Ros2Error> {
-        self.align(primitive_align_size(p))?;
-        let eof_err = || Ros2Error(format!("unexpected EOF at {path}"));
-
-        Ok(match p {
-            PrimitiveType::Bool => Value::Bool(self.buf.try_get_u8().map_err(|_| eof_err())? != 0),
-            PrimitiveType::I8 => Value::I8(self.buf.try_get_i8().map_err(|_| eof_err())?),
-            PrimitiveType::I16 => Value::I16(self.buf.try_get_i16_le().map_err(|_| eof_err())?),
-            PrimitiveType::I32 => Value::I32(self.buf.try_get_i32_le().map_err(|_| eof_err())?),
-            PrimitiveType::I64 => Value::I64(self.buf.try_get_i64_le().map_err(|_| eof_err())?),
-            PrimitiveType::U8 | PrimitiveType::Octet => {
-                Value::U8(self.buf.try_get_u8().map_err(|_| eof_err())?)
-            }
-            PrimitiveType::U16 => Value::U16(self.buf.try_get_u16_le().map_err(|_| eof_err())?),
-            PrimitiveType::U32 => Value::U32(self.buf.try_get_u32_le().map_err(|_| eof_err())?),
-            PrimitiveType::U64 => Value::U64(self.buf.try_get_u64_le().map_err(|_| eof_err())?),
-            PrimitiveType::F32 => Value::F32(self.buf.try_get_f32_le().map_err(|_| eof_err())?),
-            PrimitiveType::F64 => Value::F64(self.buf.try_get_f64_le().map_err(|_| eof_err())?),
-            PrimitiveType::String => Value::String(Arc::from(self.decode_string(path)?)),
+        self.align(primitive_align_size(p).min(self.max_align()), path)?;
+        let c = &mut self.cursor;
+        let value = match p {
+            PrimitiveType::Bool => c.read_bool().map(Value::Bool),
+            PrimitiveType::I8 => c.read_i8().map(Value::I8),
+            PrimitiveType::I16 => c.read_i16().map(Value::I16),
+            PrimitiveType::I32 => c.read_i32().map(Value::I32),
+            PrimitiveType::I64 => c.read_i64().map(Value::I64),
+            PrimitiveType::U8 | PrimitiveType::Octet => c.read_u8().map(Value::U8),
+            PrimitiveType::U16 => c.read_u16().map(Value::U16),
+            PrimitiveType::U32 => c.read_u32().map(Value::U32),
+            PrimitiveType::U64 => c.read_u64().map(Value::U64),
+            PrimitiveType::F32 => c.read_f32().map(Value::F32),
+            PrimitiveType::F64 => c.read_f64().map(Value::F64),
+            PrimitiveType::String => {
+                let bytes = self.decode_string_bytes(path)?;
+                return self.string_value(bytes, path);
+            }
             PrimitiveType::WString => {
-                return Err(format!("wstring not supported at {path}").into());
+                return Err(Ros2Error::UnsupportedFeature(format!(
+                    "wstring not supported at {path}"
+                )));
             }
-        })
+        };
+        value.map_err(|_| self.eof_error(path))
     }
 
-    fn decode_string(&mut self, path: &str) -> Result<String, Ros2Error> {
-        self.align(4)?;
-        let len =
-            self.buf
-                .try_get_u32_le()
-                .map_err(|_| Ros2Error(format!("unexpected EOF at {path}")))? as usize;
+    /// Bytes of a string, without the null terminator.
+    fn decode_string_bytes(&mut self, path: &str) -> Result<&'a [u8], Ros2Error> {
+        self.align(4, path)?;
+        let len = self.read_u32(path)? as usize;
         if len == 0 {
-            return Ok(String::new());
+            return Ok(&[]);
         }
         let bytes = self.read_bytes(len, path)?;
         if bytes.last() != Some(&0) {
-            return Err(format!("string missing null terminator at {path}").into());
+            return Err(self.cdr_error(path, format!("string missing null terminator at {path}")));
         }
-        String::from_utf8(bytes[..len - 1].to_vec())
-            .map_err(|e| Ros2Error(format!("invalid UTF-8 at {path}: {e}")))
+        Ok(&bytes[..len - 1])
     }
 
-    fn align(&mut self, n: usize) -> Result<(), Ros2Error> {
-        let relative_offset = self.current_offset() - self.align_base;
-        let pad = (n - (relative_offset % n)) % n;
-        if self.buf.remaining() < pad {
-            return Err("buffer underflow while aligning".into());
+    /// A UUID in the configured non-struct representation.
+    fn uuid_value(&self, bytes: &[u8]) -> Value {
+        match self.uuids {
+            UuidRepresentation::String => {
+                format_uuid(bytes).map_or_else(|| Value::Bytes(Arc::from(bytes)), Value::string)
+            }
+            _ => Value::Bytes(Arc::from(bytes)),
         }
-        self.buf.advance(pad);
-        Ok(())
     }
 
-    fn read_bytes(&mut self, n: usize, path: &str) -> Result<Bytes, Ros2Error> {
-        if self.buf.remaining() < n {
-            return Err(format!("unexpected EOF at {path}").into());
+    fn string_value(&self, bytes: &[u8], path: &str) -> Result<Value, Ros2Error> {
+        if self.invalid_utf8 == InvalidUtf8::Bytes {
+            return Ok(Value::Bytes(Arc::from(bytes)));
+        }
+        match std::str::from_utf8(bytes) {
+            Ok(text) => Ok(Value::string(text)),
+            Err(e) if self.invalid_utf8 == InvalidUtf8::Error => {
+                Err(self.cdr_error(path, format!("invalid UTF-8 at {path}: {e}")))
+            }
+            Err(_) => {
+                warn(DecodeWarning::new(
+                    path,
+                    "invalid UTF-8 replaced with U+FFFD",
+                ));
+                Ok(Value::string(String::from_utf8_lossy(bytes)))
+            }
         }
-        Ok(self.buf.copy_to_bytes(n))
+    }
+
+    fn align(&mut self, n: usize, path: &str) -> Result<(), Ros2Error> {
+        self.cursor
+            .align(n)
+            .map_err(|_| self.cdr_error(path, "buffer underflow while aligning"))
+    }
+
+    fn read_u32(&mut self, path: &str) -> Result<u32, Ros2Error> {
+        self.cursor.read_u32().map_err(|_| self.eof_error(path))
+    }
+
+    fn read_bytes(&mut self, n: usize, path: &str) -> Result<&'a [u8], Ros2Error> {
+        self.cursor.take(n).map_err(|_| self.eof_error(path))
     }
 }
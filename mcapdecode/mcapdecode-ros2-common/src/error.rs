@@ -1,18 +1,61 @@
 //! Shared error type for ROS 2 parsing/resolution/decoding helpers.
 
-/// Lightweight error wrapper used in internal ROS 2 modules instead of raw strings.
+use mcapdecode_core::SchemaSyntaxError;
+
+/// Failure in ROS 2 schema parsing, type resolution or CDR decoding,
+/// classified so that callers can branch on its cause.
 #[derive(Debug, Clone, thiserror::Error)]
-#[error("{0}")]
-pub struct Ros2Error(pub String);
+pub enum Ros2Error {
+    /// The schema text is malformed.
+    #[error("{0}")]
+    ParseError(String),
+    /// The schema text is malformed at a known line and column.
+    #[error(transparent)]
+    Syntax(Box<SchemaSyntaxError>),
+    /// A type name (or the root type) is not defined by the schema.
+    #[error("{0}")]
+    UnresolvedType(String),
+    /// The schema or payload uses a feature that is not supported
+    /// (unions, typedefs, wide strings, ...).
+    #[error("{0}")]
+    UnsupportedFeature(String),
+    /// The CDR payload is malformed at the field `path`, `offset` bytes into
+    /// the message.
+    #[error("{detail}")]
+    CdrError {
+        path: String,
+        offset: usize,
+        detail: String,
+    },
+}
+
+impl Ros2Error {
+    /// Attribute the error to the schema bundle section at `path`; `kind`
+    /// names the section type (`IDL`, `msg`) for errors without a location.
+    pub fn in_section(self, kind: &str, path: &str) -> Self {
+        let context = |message: String| format!("while parsing {kind} section '{path}': {message}");
+        match self {
+            Self::Syntax(err) => Self::Syntax(Box::new(err.with_section(path))),
+            Self::ParseError(message) => Self::ParseError(context(message)),
+            Self::UnresolvedType(message) => Self::UnresolvedType(context(message)),
+            Self::UnsupportedFeature(message) => Self::UnsupportedFeature(context(message)),
+            err @ Self::CdrError { .. } => err,
+        }
+    }
 
-impl From<String> for Ros2Error {
-    fn from(value: String) -> Self {
-        Self(value)
+    /// Box the error as the `source` of a
+    /// [`DecoderError`](mcapdecode_core::DecoderError), exposing
+    /// [`SchemaSyntaxError`] directly for downcasting.
+    pub fn into_source(self) -> Box<dyn std::error::Error + Send + Sync> {
+        match self {
+            Self::Syntax(err) => err,
+            other => Box::new(other),
+        }
     }
 }
 
-impl From<&str> for Ros2Error {
-    fn from(value: &str) -> Self {
-        Self(value.to_string())
+impl From<SchemaSyntaxError> for Ros2Error {
+    fn from(value: SchemaSyntaxError) -> Self {
+        Self::Syntax(Box::new(value))
     }
 }
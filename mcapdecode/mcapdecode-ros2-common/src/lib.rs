@@ -5,21 +5,37 @@
 //! - [`ast`] — AST types produced by IDL/msg parsers
 //! - [`cdr`] — CDR byte stream → [`mcapdecode_core::Value`] decoder
 //! - [`schema`] — [`type_resolver::ResolvedSchema`] → [`mcapdecode_core::FieldDefs`] conversion
+//! - [`SchemaOverrides`] — locally supplied schema text keyed by schema name
+//! - [`resolved_struct_to_msg`] — resolved structs rendered back into `.msg` text
+//! - [`resolve_type_object`] — DDS XTypes `TypeObject`s → [`type_resolver::ResolvedSchema`]
 //! - [`type_resolver`] — type-name resolution and injection of ROS 2 builtin types
 
 pub mod ast;
 mod cdr;
 mod error;
+mod msg_text;
 mod schema;
+mod schema_override;
 mod topic_decoder;
+mod type_object;
 mod type_resolver;
 
-pub use ast::{ConstDef, EnumDef, FieldDef, ParsedSection, PrimitiveType, StructDef, TypeExpr};
-pub use cdr::decode_cdr_to_value;
+pub use ast::{
+    ConstDef, ConstValue, EnumDef, FieldDef, FieldMeta, ParsedSection, PrimitiveType, StructDef,
+    TypeExpr,
+};
+pub use cdr::{
+    ByteArrays, CdrOptions, CdrQuirks, InvalidUtf8, UuidRepresentation, decode_cdr_to_value,
+    decode_cdr_to_value_with,
+};
 pub use error::Ros2Error;
-pub use schema::resolved_schema_to_field_defs;
-pub use topic_decoder::Ros2CdrTopicDecoder;
+pub use msg_text::{resolved_schema_to_msg_bundle, resolved_struct_to_msg};
+pub use schema::{resolved_schema_to_field_defs, resolved_schema_to_field_defs_with};
+pub use schema_override::SchemaOverrides;
+pub use topic_decoder::{CDR_MESSAGE_ENCODINGS, Ros2CdrTopicDecoder, cdr_encoding_keys};
+pub use type_object::resolve_type_object;
 pub use type_resolver::{
-    ResolvedField, ResolvedSchema, ResolvedStruct, ResolvedType, ensure_builtin_structs,
-    resolve_parsed_section, resolve_single_struct, resolve_struct,
+    ResolvedField, ResolvedSchema, ResolvedStruct, ResolvedType, SuffixMatching,
+    ensure_builtin_structs, resolve_parsed_section, resolve_parsed_section_with,
+    resolve_single_struct, resolve_struct, resolve_struct_with,
 };
@@ -0,0 +1,150 @@
+//! Render resolved structs back into ROS 2 `.msg` definitions.
+//!
+//! This is the inverse of parsing: schemas recorded as IDL (or `.msg`) are
+//! written out in the `.msg` format regardless of their source.  IDL enums,
+//! which `.msg` cannot express, are written as `uint32` (their CDR encoding)
+//! with the enum name in a trailing comment.
+
+use std::fmt::Write;
+
+use crate::{
+    ast::{ConstDef, PrimitiveType, TypeExpr},
+    type_resolver::{ResolvedSchema, ResolvedStruct, ResolvedType},
+};
+
+/// Separator line between the sections of a `.msg` schema bundle.
+const SECTION_SEPARATOR: &str =
+    "================================================================================";
+
+/// Render `resolved` as the body of a `.msg` file: constants first, then one
+/// line per field.
+pub fn resolved_struct_to_msg(resolved: &ResolvedStruct) -> String {
+    let mut out = String::new();
+    for konst in &resolved.consts {
+        let _ = writeln!(out, "{}", const_line(konst));
+    }
+    for field in &resolved.fields {
+        if let Some(doc) = &field.meta.doc {
+            for line in doc.lines() {
+                let _ = writeln!(out, "# {line}");
+            }
+        }
+        let mut ty = resolved_type_name(&field.ty);
+        if let Some(n) = field.fixed_len {
+            ty = format!("{ty}[{n}]");
+        }
+        let mut comments = Vec::new();
+        if let Some(unit) = &field.meta.unit {
+            comments.push(format!("[{unit}]"));
+        }
+        if let Some(ResolvedType::Enum(key)) = field.ty.named() {
+            comments.push(format!("enum {}", key.join("::")));
+        }
+        if comments.is_empty() {
+            let _ = writeln!(out, "{ty} {}", field.name);
+        } else {
+            let _ = writeln!(out, "{ty} {}  # {}", field.name, comments.join(", "));
+        }
+    }
+    out
+}
+
+/// Render `schema` as a `.msg` schema bundle, as recorded in MCAP files for
+/// the `ros2msg` encoding: the root definition followed by a `MSG:` section
+/// for every struct it references, in first-use order.
+pub fn resolved_schema_to_msg_bundle(schema: &ResolvedSchema) -> String {
+    let mut order = vec![schema.root.clone()];
+    let mut next = 0;
+    while let Some(key) = order.get(next) {
+        if let Some(resolved) = schema.structs.get(key) {
+            for field in &resolved.fields {
+                if let Some(ResolvedType::Struct(child)) = field.ty.named()
+                    && !order.contains(child)
+                {
+                    order.push(child.clone());
+                }
+            }
+        }
+        next += 1;
+    }
+
+    let mut out = String::new();
+    for (idx, key) in order.iter().enumerate() {
+        let Some(resolved) = schema.structs.get(key) else {
+            continue;
+        };
+        if idx > 0 {
+            let _ = writeln!(out, "{SECTION_SEPARATOR}");
+            let _ = writeln!(out, "MSG: {}", key.join("/"));
+        }
+        out.push_str(&resolved_struct_to_msg(resolved));
+    }
+    out
+}
+
+fn const_line(konst: &ConstDef) -> String {
+    format!(
+        "{} {}={}",
+        type_expr_name(&konst.ty),
+        konst.name,
+        konst.value
+    )
+}
+
+fn resolved_type_name(ty: &ResolvedType) -> String {
+    match ty {
+        ResolvedType::Primitive(p) => primitive_name(p).to_string(),
+        ResolvedType::Struct(key) => msg_type_name(key),
+        ResolvedType::Enum(_) => "uint32".to_string(),
+        ResolvedType::Sequence { elem, max_len } => {
+            sequence_name(&resolved_type_name(elem), *max_len)
+        }
+        ResolvedType::BoundedString(n) => format!("string<={n}"),
+        ResolvedType::BoundedWString(n) => format!("wstring<={n}"),
+    }
+}
+
+fn type_expr_name(ty: &TypeExpr) -> String {
+    match ty {
+        TypeExpr::Primitive(p) => primitive_name(p).to_string(),
+        TypeExpr::Scoped(key) => msg_type_name(key),
+        TypeExpr::Sequence { elem, max_len } => sequence_name(&type_expr_name(elem), *max_len),
+        TypeExpr::BoundedString(n) => format!("string<={n}"),
+        TypeExpr::BoundedWString(n) => format!("wstring<={n}"),
+    }
+}
+
+fn sequence_name(elem: &str, max_len: Option<usize>) -> String {
+    match max_len {
+        Some(n) => format!("{elem}[<={n}]"),
+        None => format!("{elem}[]"),
+    }
+}
+
+/// `.msg` field types omit the `msg` namespace: `["pkg", "msg", "T"]` is
+/// written `pkg/T`.
+fn msg_type_name(key: &[String]) -> String {
+    match key {
+        [package, namespace, name] if namespace == "msg" => format!("{package}/{name}"),
+        _ => key.join("/"),
+    }
+}
+
+fn primitive_name(p: &PrimitiveType) -> &'static str {
+    match p {
+        PrimitiveType::Bool => "bool",
+        PrimitiveType::I8 => "int8",
+        PrimitiveType::I16 => "int16",
+        PrimitiveType::I32 => "int32",
+        PrimitiveType::I64 => "int64",
+        PrimitiveType::U8 => "uint8",
+        PrimitiveType::U16 => "uint16",
+        PrimitiveType::U32 => "uint32",
+        PrimitiveType::U64 => "uint64",
+        PrimitiveType::F32 => "float32",
+        PrimitiveType::F64 => "float64",
+        PrimitiveType::String => "string",
+        PrimitiveType::WString => "wstring",
+        PrimitiveType::Octet => "byte",
+    }
+}
@@ -9,64 +9,192 @@
 //! | Struct                 | `Struct(FieldDefs)`          |
 //! | Enum                   | `String` (variant name)      |
 //! | Sequence               | `List(element type)`         |
-//! | BoundedString/WString  | `String`                     |
+//! | BoundedString/WString  | `String` (`Bytes` with [`InvalidUtf8::Bytes`]) |
 //! | Fixed-length field     | `Array(element type, n)`     |
+//!
+//! Field units and docs are attached as [`UNIT_METADATA_KEY`] and
+//! [`DOC_METADATA_KEY`] metadata, and the bounds of bounded strings and
+//! sequences as [`MAX_LEN_METADATA_KEY`].  Fields annotated `@key` carry
+//! [`KEY_METADATA_KEY`].
+//!
+//! With [`ByteArrays::Binary`], `uint8[n]` / `octet[n]` fields are `Bytes`
+//! marked with [`BYTE_WIDTH_METADATA_KEY`]; [`UuidRepresentation`] replaces
+//! `unique_identifier_msgs/msg/UUID` structs with 16-byte `Bytes` or
+//! `String`s marked with [`UUID_METADATA_KEY`]. Fields of a time struct
+//! (`builtin_interfaces/msg/Time`, or the ROS 1 `time` builtin) are marked
+//! with [`TIMESTAMP_METADATA_KEY`], which tells them apart from durations of
+//! the same shape.
 
-use mcapdecode_core::{DataTypeDef, ElementDef, FieldDef, FieldDefs};
+use mcapdecode_core::{
+    BYTE_WIDTH_METADATA_KEY, DOC_METADATA_KEY, DataTypeDef, ElementDef, FieldDef, FieldDefs,
+    KEY_METADATA_KEY, MAX_LEN_METADATA_KEY, TIMESTAMP_METADATA_KEY, UNIT_METADATA_KEY,
+    UUID_METADATA_KEY,
+};
 
 use crate::{
     ast::PrimitiveType,
+    cdr::{ByteArrays, CdrOptions, InvalidUtf8, UUID_LEN, UuidRepresentation, is_uuid},
     type_resolver::{ResolvedField, ResolvedSchema, ResolvedStruct, ResolvedType},
 };
 
 /// Convert the root struct of `schema` to [`FieldDefs`] for Arrow schema derivation.
 pub fn resolved_schema_to_field_defs(schema: &ResolvedSchema) -> FieldDefs {
+    resolved_schema_to_field_defs_with(schema, CdrOptions::default())
+}
+
+/// Like [`resolved_schema_to_field_defs`], for values decoded with
+/// `options` (narrow strings are `Bytes` with [`InvalidUtf8::Bytes`]).
+pub fn resolved_schema_to_field_defs_with(
+    schema: &ResolvedSchema,
+    options: CdrOptions,
+) -> FieldDefs {
     let root_struct = schema
         .structs
         .get(&schema.root)
         .unwrap_or_else(|| panic!("Root struct {:?} not found", schema.root));
-    resolved_struct_to_field_defs(schema, root_struct)
+    resolved_struct_to_field_defs(schema, root_struct, &options)
 }
 
-fn resolved_struct_to_field_defs(schema: &ResolvedSchema, st: &ResolvedStruct) -> FieldDefs {
+fn resolved_struct_to_field_defs(
+    schema: &ResolvedSchema,
+    st: &ResolvedStruct,
+    options: &CdrOptions,
+) -> FieldDefs {
     FieldDefs::new(
         st.fields
             .iter()
-            .map(|f| resolved_field_to_field_def(schema, f))
+            .map(|f| resolved_field_to_field_def(schema, f, options))
             .collect(),
     )
 }
 
-fn resolved_field_to_field_def(schema: &ResolvedSchema, field: &ResolvedField) -> FieldDef {
-    let inner_dt = resolved_type_to_data_type_def(schema, &field.ty);
+fn resolved_field_to_field_def(
+    schema: &ResolvedSchema,
+    field: &ResolvedField,
+    options: &CdrOptions,
+) -> FieldDef {
+    let byte_array = options.byte_arrays == ByteArrays::Binary
+        && matches!(
+            field.ty,
+            ResolvedType::Primitive(PrimitiveType::U8 | PrimitiveType::Octet)
+        );
+    let byte_width = match (&field.ty, field.fixed_len) {
+        (_, Some(n)) if byte_array => Some(n),
+        (ResolvedType::Struct(name), None)
+            if options.uuids == UuidRepresentation::Binary && is_uuid(schema, name) =>
+        {
+            Some(UUID_LEN)
+        }
+        _ => None,
+    };
 
     // Wrap in Array when the field has a fixed static length.
     let dt = match field.fixed_len {
-        Some(n) => DataTypeDef::Array(Box::new(ElementDef::new(inner_dt, false)), n),
-        None => inner_dt,
+        Some(_) if byte_array => DataTypeDef::Bytes,
+        Some(n) => DataTypeDef::Array(
+            Box::new(ElementDef::new(
+                resolved_type_to_data_type_def(schema, &field.ty, options),
+                false,
+            )),
+            n,
+        ),
+        None => resolved_type_to_data_type_def(schema, &field.ty, options),
     };
 
-    FieldDef::new(&field.name, dt, false)
+    let mut def = FieldDef::new(&field.name, dt, false);
+    if let Some(width) = byte_width {
+        def = def.with_metadata(BYTE_WIDTH_METADATA_KEY, width.to_string());
+    }
+    if let (ResolvedType::Struct(name), None) = (&field.ty, field.fixed_len)
+        && options.uuids != UuidRepresentation::Struct
+        && is_uuid(schema, name)
+    {
+        def = def.with_metadata(UUID_METADATA_KEY, "true");
+    }
+    if let (ResolvedType::Struct(name), None) = (&field.ty, field.fixed_len)
+        && is_time(name)
+    {
+        def = def.with_metadata(TIMESTAMP_METADATA_KEY, "true");
+    }
+    if let Some(unit) = &field.meta.unit {
+        def = def.with_metadata(UNIT_METADATA_KEY, unit);
+    }
+    if let Some(doc) = &field.meta.doc {
+        def = def.with_metadata(DOC_METADATA_KEY, doc);
+    }
+    if let Some(max_len) = type_bound(&field.ty) {
+        def = def.with_metadata(MAX_LEN_METADATA_KEY, max_len.to_string());
+    }
+    if field.meta.key {
+        def = def.with_metadata(KEY_METADATA_KEY, "true");
+    }
+    def
 }
 
-fn resolved_type_to_data_type_def(schema: &ResolvedSchema, ty: &ResolvedType) -> DataTypeDef {
+/// Whether `name` is `builtin_interfaces/msg/Time` (also in its DDS spelling
+/// `builtin_interfaces::msg::dds_::Time_`) or the struct standing in for the
+/// ROS 1 `time` builtin.
+fn is_time(name: &[String]) -> bool {
+    match name {
+        [package, msg, ty] => {
+            (package == "builtin_interfaces" && msg == "msg" && ty == "Time")
+                || (package == "ros1msg" && msg == "builtin" && ty == "time")
+        }
+        [package, msg, dds, ty] => {
+            package == "builtin_interfaces" && msg == "msg" && dds == "dds_" && ty == "Time_"
+        }
+        _ => false,
+    }
+}
+
+/// Upper bound declared by a bounded string or sequence type.
+fn type_bound(ty: &ResolvedType) -> Option<usize> {
     match ty {
+        ResolvedType::Sequence { max_len, .. } => *max_len,
+        ResolvedType::BoundedString(n) | ResolvedType::BoundedWString(n) => Some(*n),
+        _ => None,
+    }
+}
+
+/// Data type of narrow strings.
+fn string_data_type_def(options: &CdrOptions) -> DataTypeDef {
+    match options.invalid_utf8 {
+        InvalidUtf8::Bytes => DataTypeDef::Bytes,
+        InvalidUtf8::Error | InvalidUtf8::Replace => DataTypeDef::String,
+    }
+}
+
+fn resolved_type_to_data_type_def(
+    schema: &ResolvedSchema,
+    ty: &ResolvedType,
+    options: &CdrOptions,
+) -> DataTypeDef {
+    match ty {
+        ResolvedType::Primitive(PrimitiveType::String) => string_data_type_def(options),
         ResolvedType::Primitive(p) => primitive_to_data_type_def(p),
+        ResolvedType::Struct(name)
+            if options.uuids != UuidRepresentation::Struct && is_uuid(schema, name) =>
+        {
+            match options.uuids {
+                UuidRepresentation::String => DataTypeDef::String,
+                _ => DataTypeDef::Bytes,
+            }
+        }
         ResolvedType::Struct(name) => {
             let st = schema
                 .structs
                 .get(name)
                 .unwrap_or_else(|| panic!("Struct {:?} not found", name));
-            let fields = resolved_struct_to_field_defs(schema, st);
+            let fields = resolved_struct_to_field_defs(schema, st, options);
             DataTypeDef::Struct(fields)
         }
         // Enums are represented as their variant name string.
         ResolvedType::Enum(_) => DataTypeDef::String,
         ResolvedType::Sequence { elem, .. } => {
-            let elem_dt = resolved_type_to_data_type_def(schema, elem);
+            let elem_dt = resolved_type_to_data_type_def(schema, elem, options);
             DataTypeDef::List(Box::new(ElementDef::new(elem_dt, false)))
         }
-        ResolvedType::BoundedString(_) => DataTypeDef::String,
+        ResolvedType::BoundedString(_) => string_data_type_def(options),
         ResolvedType::BoundedWString(_) => DataTypeDef::String,
     }
 }
@@ -0,0 +1,74 @@
+//! Locally supplied schema text that replaces the schema data embedded in MCAP.
+
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Schema text overrides keyed by schema name (e.g., `"geometry_msgs/msg/Point"`).
+///
+/// Override text uses the same format as the corresponding MCAP schema data,
+/// so dependent definitions may be bundled into one override.
+#[derive(Debug, Clone, Default)]
+pub struct SchemaOverrides {
+    entries: HashMap<String, String>,
+}
+
+impl SchemaOverrides {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register `schema_text` for `schema_name`, replacing any previous entry.
+    pub fn insert(&mut self, schema_name: impl Into<String>, schema_text: impl Into<String>) {
+        self.entries.insert(schema_name.into(), schema_text.into());
+    }
+
+    /// Look up the override for `schema_name`.
+    pub fn get(&self, schema_name: &str) -> Option<&str> {
+        self.entries.get(schema_name).map(String::as_str)
+    }
+
+    /// Iterate over the schema names and override texts.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.entries
+            .iter()
+            .map(|(name, text)| (name.as_str(), text.as_str()))
+    }
+
+    /// Recursively load every `*.{extension}` file below `dir`.
+    ///
+    /// The schema name is the file path relative to `dir` without the
+    /// extension, so `dir/geometry_msgs/msg/Point.msg` registers
+    /// `geometry_msgs/msg/Point`.
+    pub fn load_dir(&mut self, dir: &Path, extension: &str) -> io::Result<()> {
+        let mut pending: Vec<PathBuf> = vec![dir.to_path_buf()];
+        while let Some(current) = pending.pop() {
+            for entry in fs::read_dir(&current)? {
+                let path = entry?.path();
+                if path.is_dir() {
+                    pending.push(path);
+                    continue;
+                }
+                if path.extension().and_then(|e| e.to_str()) != Some(extension) {
+                    continue;
+                }
+                let Some(schema_name) = schema_name_from_path(dir, &path) else {
+                    continue;
+                };
+                self.insert(schema_name, fs::read_to_string(&path)?);
+            }
+        }
+        Ok(())
+    }
+}
+
+fn schema_name_from_path(root: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(root).ok()?.with_extension("");
+    let parts = relative
+        .components()
+        .map(|c| c.as_os_str().to_str())
+        .collect::<Option<Vec<_>>>()?;
+    Some(parts.join("/"))
+}
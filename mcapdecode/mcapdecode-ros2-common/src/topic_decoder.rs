@@ -1,26 +1,72 @@
-use mcapdecode_core::{DecoderError, FieldDefs, TopicDecoder, Value};
+use mcapdecode_core::{
+    DecoderError, EncodingKey, FieldDefs, MessageEncoding, SchemaEncoding, TopicDecoder, Value,
+};
 
-use crate::{ResolvedSchema, decode_cdr_to_value, resolved_schema_to_field_defs};
+use crate::{
+    CdrOptions, CdrQuirks, InvalidUtf8, ResolvedSchema, decode_cdr_to_value_with,
+    resolved_schema_to_field_defs_with,
+};
+
+/// Spellings of the CDR message encoding written by ROS 2 and DDS recorders;
+/// all of them denote CDR payloads, whose encapsulation header tells their
+/// endianness and CDR version.
+pub const CDR_MESSAGE_ENCODINGS: &[&str] = &["cdr", "cdr_le", "xcdr"];
+
+/// Encoding keys of `schema_encoding` with each of [`CDR_MESSAGE_ENCODINGS`].
+pub fn cdr_encoding_keys(schema_encoding: SchemaEncoding) -> Vec<EncodingKey> {
+    CDR_MESSAGE_ENCODINGS
+        .iter()
+        .map(|encoding| EncodingKey::new(schema_encoding.clone(), MessageEncoding::from(*encoding)))
+        .collect()
+}
 
 /// Shared ROS 2 CDR topic decoder used by both `ros2msg` and `ros2idl`.
+#[derive(Debug, Clone)]
 pub struct Ros2CdrTopicDecoder {
     resolved: ResolvedSchema,
     field_defs: FieldDefs,
+    options: CdrOptions,
 }
 
 impl Ros2CdrTopicDecoder {
     pub fn new(resolved: ResolvedSchema) -> Self {
-        let field_defs = resolved_schema_to_field_defs(&resolved);
+        let options = CdrOptions::default();
+        let field_defs = resolved_schema_to_field_defs_with(&resolved, options);
         Self {
             resolved,
             field_defs,
+            options,
         }
     }
+
+    /// Decode strings that are not valid UTF-8 as `invalid_utf8` says
+    /// (default: [`InvalidUtf8::Error`]).
+    pub fn with_invalid_utf8(self, invalid_utf8: InvalidUtf8) -> Self {
+        let options = CdrOptions {
+            invalid_utf8,
+            ..self.options
+        };
+        self.with_cdr_options(options)
+    }
+
+    /// Decode payloads laid out as `quirks` says (default:
+    /// [`CdrQuirks::Rosidl`]).
+    pub fn with_cdr_quirks(mut self, quirks: CdrQuirks) -> Self {
+        self.options.quirks = quirks;
+        self
+    }
+
+    /// Decode payloads with every option of `options`.
+    pub fn with_cdr_options(mut self, options: CdrOptions) -> Self {
+        self.field_defs = resolved_schema_to_field_defs_with(&self.resolved, options);
+        self.options = options;
+        self
+    }
 }
 
 impl TopicDecoder for Ros2CdrTopicDecoder {
     fn decode(&self, message_data: &[u8]) -> Result<Value, DecoderError> {
-        decode_cdr_to_value(&self.resolved, message_data)
+        decode_cdr_to_value_with(&self.resolved, message_data, self.options)
     }
 
     fn field_defs(&self) -> &FieldDefs {
@@ -0,0 +1,771 @@
+//! Schemas recorded as serialized OMG DDS XTypes `TypeObject`s.
+//!
+//! Some DDS recorders store the type of a topic as the `TypeObject`s used by
+//! XTypes type lookup rather than as IDL text.  The accepted schema data is
+//! an XCDR2 little-endian `TypeIdentifierTypeObjectPairSeq`, i.e. the
+//! complete `TypeObject` of every type involved keyed by its
+//! `TypeIdentifier`:
+//!
+//! ```text
+//! encapsulation header (CDR2_LE or D_CDR2_LE)
+//!   └─ sequence<TypeIdentifierTypeObjectPair>
+//!       ├─ TypeIdentifier  – EK_COMPLETE equivalence hash
+//!       └─ TypeObject      – CompleteTypeObject (struct, enum, alias, ...)
+//! ```
+//!
+//! The types are converted into a [`ResolvedSchema`], so payloads are decoded
+//! by the same CDR decoder as IDL and `.msg` schemas.  Final and appendable
+//! structs (including inheritance), enums, aliases, strings, sequences and
+//! arrays are supported.  Mutable structs, optional members, unions, maps,
+//! bitmasks and recursive types are reported as
+//! [`Ros2Error::UnsupportedFeature`]; minimal `TypeObject`s carry hashes
+//! instead of member names and are rejected as well.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    ast::{FieldMeta, PrimitiveType},
+    error::Ros2Error,
+    type_resolver::{ResolvedField, ResolvedSchema, ResolvedStruct, ResolvedType},
+};
+
+// Type kinds and TypeIdentifier discriminators (XTypes 1.3, 7.3.4.2).
+const TK_NONE: u8 = 0x00;
+const TK_BOOLEAN: u8 = 0x01;
+const TK_BYTE: u8 = 0x02;
+const TK_INT16: u8 = 0x03;
+const TK_INT32: u8 = 0x04;
+const TK_INT64: u8 = 0x05;
+const TK_UINT16: u8 = 0x06;
+const TK_UINT32: u8 = 0x07;
+const TK_UINT64: u8 = 0x08;
+const TK_FLOAT32: u8 = 0x09;
+const TK_FLOAT64: u8 = 0x0A;
+const TK_FLOAT128: u8 = 0x0B;
+const TK_INT8: u8 = 0x0C;
+const TK_UINT8: u8 = 0x0D;
+const TK_CHAR8: u8 = 0x10;
+const TK_CHAR16: u8 = 0x11;
+const TK_STRING8: u8 = 0x20;
+const TK_STRING16: u8 = 0x21;
+const TK_ALIAS: u8 = 0x30;
+const TK_ENUM: u8 = 0x40;
+const TK_BITMASK: u8 = 0x41;
+const TK_ANNOTATION: u8 = 0x50;
+const TK_STRUCTURE: u8 = 0x51;
+const TK_UNION: u8 = 0x52;
+const TK_BITSET: u8 = 0x53;
+const TK_SEQUENCE: u8 = 0x60;
+const TK_ARRAY: u8 = 0x61;
+const TK_MAP: u8 = 0x62;
+const TI_STRING8_SMALL: u8 = 0x70;
+const TI_STRING8_LARGE: u8 = 0x71;
+const TI_STRING16_SMALL: u8 = 0x72;
+const TI_STRING16_LARGE: u8 = 0x73;
+const TI_PLAIN_SEQUENCE_SMALL: u8 = 0x80;
+const TI_PLAIN_SEQUENCE_LARGE: u8 = 0x81;
+const TI_PLAIN_ARRAY_SMALL: u8 = 0x90;
+const TI_PLAIN_ARRAY_LARGE: u8 = 0x91;
+const TI_PLAIN_MAP_SMALL: u8 = 0xA0;
+const TI_PLAIN_MAP_LARGE: u8 = 0xA1;
+const TI_STRONGLY_CONNECTED_COMPONENT: u8 = 0xB0;
+const EK_MINIMAL: u8 = 0xF1;
+const EK_COMPLETE: u8 = 0xF2;
+
+/// `StructTypeFlag` / `StructMemberFlag` bits.
+const IS_MUTABLE: u16 = 1 << 2;
+const IS_OPTIONAL: u16 = 1 << 3;
+const IS_KEY: u16 = 1 << 5;
+
+/// Encapsulation identifiers of XCDR2 little-endian data.
+const CDR2_LE: u16 = 0x0007;
+const D_CDR2_LE: u16 = 0x0009;
+
+/// Largest enum literal value mapped to a name; the CDR decoder looks
+/// variants up by index.
+const MAX_ENUM_VALUE: i32 = 4096;
+
+/// Parse `data` (see the [module documentation](self)) and resolve it into a
+/// [`ResolvedSchema`] rooted at the struct named `schema_name` (`::` or `/`
+/// separated), or at the first struct if none has that name.
+pub fn resolve_type_object(schema_name: &str, data: &[u8]) -> Result<ResolvedSchema, Ros2Error> {
+    let mut reader = Reader::new(data)?;
+    let mut types = HashMap::new();
+    let mut first_struct = None;
+    let mut named_root = None;
+    let wanted: Vec<String> = schema_name
+        .split("::")
+        .flat_map(|part| part.split('/'))
+        .map(str::to_string)
+        .collect();
+
+    let end = reader.dheader()?;
+    let count = reader.u32()?;
+    for _ in 0..count {
+        let id = reader.type_identifier()?;
+        let TypeId::Hash(hash) = id else {
+            return Err(reader.error("TypeObject keyed by a non-hash TypeIdentifier"));
+        };
+        let def = reader.type_object()?;
+        if let TypeDef::Struct(st) = &def {
+            first_struct.get_or_insert(hash);
+            if st.name == wanted {
+                named_root = Some(hash);
+            }
+        }
+        types.insert(hash, def);
+    }
+    reader.seek(end)?;
+
+    let root = named_root.or(first_struct).ok_or_else(|| {
+        Ros2Error::UnresolvedType(format!("no struct TypeObject found for '{schema_name}'"))
+    })?;
+    let mut resolver = Resolver {
+        types: &types,
+        structs: HashMap::new(),
+        enums: HashMap::new(),
+        in_progress: HashSet::new(),
+    };
+    let root = resolver.resolve_struct(&root)?;
+    Ok(ResolvedSchema {
+        root,
+        structs: resolver.structs,
+        enums: resolver.enums,
+    })
+}
+
+type Hash = [u8; 14];
+
+/// A `TypeIdentifier`, reduced to what the conversion needs.
+#[derive(Debug, Clone)]
+enum TypeId {
+    None,
+    Primitive(PrimitiveType),
+    /// `bound` 0 means unbounded.
+    String {
+        bound: usize,
+    },
+    WString {
+        bound: usize,
+    },
+    Sequence {
+        elem: Box<TypeId>,
+        bound: usize,
+    },
+    Array {
+        elem: Box<TypeId>,
+        dims: Vec<usize>,
+    },
+    /// Equivalence hash of a type defined by a `TypeObject`.
+    Hash(Hash),
+    /// A type that cannot be decoded from plain CDR, named for the error.
+    Unsupported(String),
+}
+
+/// A complete `TypeObject`, reduced to what the conversion needs.
+#[derive(Debug)]
+enum TypeDef {
+    Struct(StructType),
+    Enum {
+        name: String,
+        bit_bound: u16,
+        literals: Vec<(i32, String)>,
+    },
+    Alias(TypeId),
+    /// Anonymous sequence or array type with a `TypeObject` of its own.
+    Collection(TypeId),
+    Unsupported(String),
+}
+
+#[derive(Debug)]
+struct StructType {
+    name: Vec<String>,
+    flags: u16,
+    base: TypeId,
+    members: Vec<Member>,
+}
+
+#[derive(Debug)]
+struct Member {
+    name: String,
+    flags: u16,
+    ty: TypeId,
+    unit: Option<String>,
+}
+
+/// XCDR2 little-endian reader; alignment is relative to the end of the
+/// encapsulation header and capped at 4 bytes.
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    const ORIGIN: usize = 4;
+
+    fn new(data: &'a [u8]) -> Result<Self, Ros2Error> {
+        let Some(header) = data.get(..Self::ORIGIN) else {
+            return Err(Ros2Error::ParseError(
+                "invalid TypeObject data: incomplete encapsulation header".to_string(),
+            ));
+        };
+        let kind = u16::from_be_bytes([header[0], header[1]]);
+        if kind != CDR2_LE && kind != D_CDR2_LE {
+            return Err(Ros2Error::UnsupportedFeature(format!(
+                "TypeObject encapsulation 0x{kind:04x} (expected XCDR2 little-endian)"
+            )));
+        }
+        Ok(Self {
+            data,
+            pos: Self::ORIGIN,
+        })
+    }
+
+    fn error(&self, detail: &str) -> Ros2Error {
+        Ros2Error::ParseError(format!(
+            "invalid TypeObject data at byte {}: {detail}",
+            self.pos
+        ))
+    }
+
+    fn align(&mut self, n: usize) -> Result<(), Ros2Error> {
+        let n = n.min(4);
+        let pad = (n - (self.pos - Self::ORIGIN) % n) % n;
+        self.take(pad).map(|_| ())
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], Ros2Error> {
+        let end = self
+            .pos
+            .checked_add(n)
+            .filter(|end| *end <= self.data.len())
+            .ok_or_else(|| self.error("unexpected end of data"))?;
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    /// Skip a value of `size` bytes, aligned to its size.
+    fn skip(&mut self, size: usize) -> Result<(), Ros2Error> {
+        self.align(size)?;
+        self.take(size).map(|_| ())
+    }
+
+    fn u8(&mut self) -> Result<u8, Ros2Error> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn bool(&mut self) -> Result<bool, Ros2Error> {
+        Ok(self.u8()? != 0)
+    }
+
+    fn u16(&mut self) -> Result<u16, Ros2Error> {
+        self.align(2)?;
+        let bytes = self.take(2)?;
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32, Ros2Error> {
+        self.align(4)?;
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn i32(&mut self) -> Result<i32, Ros2Error> {
+        self.u32().map(|v| v as i32)
+    }
+
+    fn string(&mut self) -> Result<String, Ros2Error> {
+        let len = self.u32()? as usize;
+        let bytes = self.take(len)?;
+        let text = bytes.strip_suffix(&[0]).unwrap_or(bytes);
+        String::from_utf8(text.to_vec()).map_err(|_| self.error("string is not UTF-8"))
+    }
+
+    /// Read a DHEADER and return the end offset of the value it precedes.
+    fn dheader(&mut self) -> Result<usize, Ros2Error> {
+        let size = self.u32()? as usize;
+        self.pos
+            .checked_add(size)
+            .filter(|end| *end <= self.data.len())
+            .ok_or_else(|| self.error("DHEADER size exceeds the data"))
+    }
+
+    /// Continue after a value ending at `end`, skipping members appended by
+    /// later versions of its type.
+    fn seek(&mut self, end: usize) -> Result<(), Ros2Error> {
+        if end < self.pos {
+            return Err(self.error("value exceeds its DHEADER size"));
+        }
+        self.pos = end;
+        Ok(())
+    }
+
+    /// Read an `@optional` member of a final or appendable struct.
+    fn optional<T>(
+        &mut self,
+        read: impl FnOnce(&mut Self) -> Result<T, Ros2Error>,
+    ) -> Result<Option<T>, Ros2Error> {
+        if self.bool()? {
+            read(self).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Skip a sequence of non-primitive elements (e.g. `AppliedAnnotationSeq`).
+    fn skip_sequence(&mut self) -> Result<(), Ros2Error> {
+        let end = self.dheader()?;
+        self.seek(end)
+    }
+
+    fn hash(&mut self) -> Result<Hash, Ros2Error> {
+        let mut hash = [0; 14];
+        hash.copy_from_slice(self.take(14)?);
+        Ok(hash)
+    }
+
+    fn type_identifier(&mut self) -> Result<TypeId, Ros2Error> {
+        let kind = self.u8()?;
+        let primitive = |p| Ok(TypeId::Primitive(p));
+        match kind {
+            TK_NONE => Ok(TypeId::None),
+            TK_BOOLEAN => primitive(PrimitiveType::Bool),
+            TK_BYTE => primitive(PrimitiveType::Octet),
+            TK_INT8 => primitive(PrimitiveType::I8),
+            TK_UINT8 | TK_CHAR8 => primitive(PrimitiveType::U8),
+            TK_INT16 => primitive(PrimitiveType::I16),
+            TK_UINT16 => primitive(PrimitiveType::U16),
+            TK_INT32 => primitive(PrimitiveType::I32),
+            TK_UINT32 => primitive(PrimitiveType::U32),
+            TK_INT64 => primitive(PrimitiveType::I64),
+            TK_UINT64 => primitive(PrimitiveType::U64),
+            TK_FLOAT32 => primitive(PrimitiveType::F32),
+            TK_FLOAT64 => primitive(PrimitiveType::F64),
+            TK_FLOAT128 => Ok(TypeId::Unsupported("long double".to_string())),
+            TK_CHAR16 => Ok(TypeId::Unsupported("wchar".to_string())),
+            TI_STRING8_SMALL => Ok(TypeId::String {
+                bound: self.u8()?.into(),
+            }),
+            TI_STRING8_LARGE => Ok(TypeId::String {
+                bound: self.u32()? as usize,
+            }),
+            TI_STRING16_SMALL => Ok(TypeId::WString {
+                bound: self.u8()?.into(),
+            }),
+            TI_STRING16_LARGE => Ok(TypeId::WString {
+                bound: self.u32()? as usize,
+            }),
+            TI_PLAIN_SEQUENCE_SMALL | TI_PLAIN_SEQUENCE_LARGE => {
+                self.collection_header()?;
+                let bound = match kind {
+                    TI_PLAIN_SEQUENCE_SMALL => self.u8()?.into(),
+                    _ => self.u32()? as usize,
+                };
+                let elem = Box::new(self.type_identifier()?);
+                Ok(TypeId::Sequence { elem, bound })
+            }
+            TI_PLAIN_ARRAY_SMALL | TI_PLAIN_ARRAY_LARGE => {
+                self.collection_header()?;
+                let count = self.u32()?;
+                let dims = (0..count)
+                    .map(|_| match kind {
+                        TI_PLAIN_ARRAY_SMALL => self.u8().map(usize::from),
+                        _ => self.u32().map(|n| n as usize),
+                    })
+                    .collect::<Result<_, _>>()?;
+                let elem = Box::new(self.type_identifier()?);
+                Ok(TypeId::Array { elem, dims })
+            }
+            TI_PLAIN_MAP_SMALL | TI_PLAIN_MAP_LARGE => {
+                self.collection_header()?;
+                match kind {
+                    TI_PLAIN_MAP_SMALL => self.skip(1)?,
+                    _ => self.skip(4)?,
+                }
+                self.type_identifier()?;
+                self.u16()?;
+                self.type_identifier()?;
+                Ok(TypeId::Unsupported("map".to_string()))
+            }
+            TI_STRONGLY_CONNECTED_COMPONENT => {
+                self.u8()?;
+                self.hash()?;
+                self.i32()?;
+                self.i32()?;
+                Ok(TypeId::Unsupported("recursive type".to_string()))
+            }
+            EK_COMPLETE | EK_MINIMAL => self.hash().map(TypeId::Hash),
+            _ => {
+                // ExtendedTypeDefn: an empty appendable struct.
+                let end = self.dheader()?;
+                self.seek(end)?;
+                Ok(TypeId::Unsupported(format!("type identifier 0x{kind:02x}")))
+            }
+        }
+    }
+
+    /// `PlainCollectionHeader`: equivalence kind and element flags.
+    fn collection_header(&mut self) -> Result<(), Ros2Error> {
+        self.u8()?;
+        self.u16()?;
+        Ok(())
+    }
+
+    fn type_object(&mut self) -> Result<TypeDef, Ros2Error> {
+        let end = self.dheader()?;
+        let def = match self.u8()? {
+            EK_COMPLETE => self.complete_type_object()?,
+            EK_MINIMAL => TypeDef::Unsupported(
+                "minimal TypeObject (member names are not recorded)".to_string(),
+            ),
+            _ => return Err(self.error("unknown TypeObject equivalence kind")),
+        };
+        self.seek(end)?;
+        Ok(def)
+    }
+
+    fn complete_type_object(&mut self) -> Result<TypeDef, Ros2Error> {
+        match self.u8()? {
+            TK_STRUCTURE => {
+                let flags = self.u16()?;
+                let header_end = self.dheader()?;
+                let base = self.type_identifier()?;
+                let name = self.type_detail()?;
+                self.seek(header_end)?;
+                let members = self.sequence(|r| {
+                    let end = r.dheader()?;
+                    r.u32()?;
+                    let flags = r.u16()?;
+                    let ty = r.type_identifier()?;
+                    let (name, unit) = r.member_detail()?;
+                    r.seek(end)?;
+                    Ok(Member {
+                        name,
+                        flags,
+                        ty,
+                        unit,
+                    })
+                })?;
+                Ok(TypeDef::Struct(StructType {
+                    name: name.split("::").map(str::to_string).collect(),
+                    flags,
+                    base,
+                    members,
+                }))
+            }
+            TK_ENUM => {
+                self.u16()?;
+                let header_end = self.dheader()?;
+                let bit_bound = self.u16()?;
+                let name = self.type_detail()?;
+                self.seek(header_end)?;
+                let literals = self.sequence(|r| {
+                    let end = r.dheader()?;
+                    let value = r.i32()?;
+                    r.u16()?;
+                    let (name, _) = r.member_detail()?;
+                    r.seek(end)?;
+                    Ok((value, name))
+                })?;
+                Ok(TypeDef::Enum {
+                    name,
+                    bit_bound,
+                    literals,
+                })
+            }
+            TK_ALIAS => {
+                self.u16()?;
+                self.type_detail()?;
+                self.u16()?;
+                let related = self.type_identifier()?;
+                self.optional(Self::builtin_member_annotations)?;
+                self.optional(Self::skip_sequence)?;
+                Ok(TypeDef::Alias(related))
+            }
+            kind @ (TK_SEQUENCE | TK_ARRAY) => {
+                self.u16()?;
+                let (bound, dims) = if kind == TK_SEQUENCE {
+                    let bound = self.u32()? as usize;
+                    self.optional(Self::type_detail)?;
+                    (bound, Vec::new())
+                } else {
+                    let count = self.u32()?;
+                    let dims = (0..count)
+                        .map(|_| self.u32().map(|n| n as usize))
+                        .collect::<Result<_, _>>()?;
+                    self.type_detail()?;
+                    (0, dims)
+                };
+                self.u16()?;
+                let elem = Box::new(self.type_identifier()?);
+                self.optional(Self::builtin_member_annotations)?;
+                self.optional(Self::skip_sequence)?;
+                Ok(TypeDef::Collection(if kind == TK_SEQUENCE {
+                    TypeId::Sequence { elem, bound }
+                } else {
+                    TypeId::Array { elem, dims }
+                }))
+            }
+            kind => {
+                let what = match kind {
+                    TK_UNION => "union",
+                    TK_MAP => "map",
+                    TK_BITMASK => "bitmask",
+                    TK_BITSET => "bitset",
+                    TK_ANNOTATION => "annotation",
+                    _ => "extended type",
+                };
+                Ok(TypeDef::Unsupported(what.to_string()))
+            }
+        }
+    }
+
+    /// Read a sequence of non-primitive elements.
+    fn sequence<T>(
+        &mut self,
+        mut read: impl FnMut(&mut Self) -> Result<T, Ros2Error>,
+    ) -> Result<Vec<T>, Ros2Error> {
+        let end = self.dheader()?;
+        let count = self.u32()?;
+        let items = (0..count)
+            .map(|_| read(self))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.seek(end)?;
+        Ok(items)
+    }
+
+    /// `CompleteTypeDetail`, returning the qualified type name.
+    fn type_detail(&mut self) -> Result<String, Ros2Error> {
+        // AppliedBuiltinTypeAnnotations { @optional AppliedVerbatimAnnotation }
+        self.optional(|r| {
+            r.optional(|r| {
+                r.string()?;
+                r.string()?;
+                r.string()
+            })
+        })?;
+        self.optional(Self::skip_sequence)?;
+        self.string()
+    }
+
+    /// `CompleteMemberDetail`, returning the member name and unit.
+    fn member_detail(&mut self) -> Result<(String, Option<String>), Ros2Error> {
+        let name = self.string()?;
+        let unit = self.optional(Self::builtin_member_annotations)?.flatten();
+        self.optional(Self::skip_sequence)?;
+        Ok((name, unit))
+    }
+
+    /// `AppliedBuiltinMemberAnnotations`, returning the `@unit`.
+    fn builtin_member_annotations(&mut self) -> Result<Option<String>, Ros2Error> {
+        let unit = self.optional(Self::string)?;
+        self.optional(Self::annotation_parameter_value)?;
+        self.optional(Self::annotation_parameter_value)?;
+        self.optional(Self::string)?;
+        Ok(unit)
+    }
+
+    /// Skip an `AnnotationParameterValue` (`@min` / `@max`).
+    fn annotation_parameter_value(&mut self) -> Result<(), Ros2Error> {
+        match self.u8()? {
+            TK_BOOLEAN | TK_BYTE | TK_INT8 | TK_UINT8 | TK_CHAR8 => self.skip(1),
+            TK_INT16 | TK_UINT16 | TK_CHAR16 => self.skip(2),
+            TK_INT32 | TK_UINT32 | TK_FLOAT32 | TK_ENUM => self.skip(4),
+            TK_INT64 | TK_UINT64 | TK_FLOAT64 => self.skip(8),
+            TK_FLOAT128 => self.skip(16),
+            TK_STRING8 => self.string().map(|_| ()),
+            TK_STRING16 => {
+                let len = self.u32()? as usize;
+                self.take(len).map(|_| ())
+            }
+            _ => self.skip_sequence(),
+        }
+    }
+}
+
+/// Converts [`TypeDef`]s reachable from the root into resolved structs and
+/// enums.
+struct Resolver<'a> {
+    types: &'a HashMap<Hash, TypeDef>,
+    structs: HashMap<Vec<String>, ResolvedStruct>,
+    enums: HashMap<Vec<String>, Vec<String>>,
+    /// Structs being resolved, to reject recursive references.
+    in_progress: HashSet<Hash>,
+}
+
+impl<'a> Resolver<'a> {
+    fn lookup(&self, hash: &Hash, path: &str) -> Result<&'a TypeDef, Ros2Error> {
+        self.types.get(hash).ok_or_else(|| {
+            Ros2Error::UnresolvedType(format!("no TypeObject for the type of '{path}'"))
+        })
+    }
+
+    /// Resolve the struct `hash` and return its key.
+    fn resolve_struct(&mut self, hash: &Hash) -> Result<Vec<String>, Ros2Error> {
+        let TypeDef::Struct(st) = self.lookup(hash, "root")? else {
+            return Err(Ros2Error::UnresolvedType(
+                "root TypeObject is not a struct".to_string(),
+            ));
+        };
+        if !self.structs.contains_key(&st.name) {
+            let fields = self.struct_fields(hash)?;
+            self.structs.insert(
+                st.name.clone(),
+                ResolvedStruct {
+                    fields,
+                    consts: Vec::new(),
+                },
+            );
+        }
+        Ok(st.name.clone())
+    }
+
+    /// Fields of the struct `hash`, those of its base type first.
+    fn struct_fields(&mut self, hash: &Hash) -> Result<Vec<ResolvedField>, Ros2Error> {
+        let Some(TypeDef::Struct(st)) = self.types.get(hash) else {
+            return Err(Ros2Error::UnresolvedType(
+                "base type TypeObject is not a struct".to_string(),
+            ));
+        };
+        let name = st.name.join("::");
+        if st.flags & IS_MUTABLE != 0 {
+            return Err(Ros2Error::UnsupportedFeature(format!(
+                "mutable struct '{name}'"
+            )));
+        }
+        if !self.in_progress.insert(*hash) {
+            return Err(Ros2Error::UnsupportedFeature(format!(
+                "recursive type '{name}'"
+            )));
+        }
+
+        let mut fields = match &st.base {
+            TypeId::None => Vec::new(),
+            TypeId::Hash(base) => self.struct_fields(base)?,
+            _ => {
+                return Err(Ros2Error::UnresolvedType(format!(
+                    "base type of '{name}' is not a struct"
+                )));
+            }
+        };
+        for member in &st.members {
+            let path = format!("{name}.{}", member.name);
+            if member.flags & IS_OPTIONAL != 0 {
+                return Err(Ros2Error::UnsupportedFeature(format!(
+                    "optional member '{path}'"
+                )));
+            }
+            let (ty, fixed_len) = self.member_type(&member.ty, &path)?;
+            fields.push(ResolvedField {
+                name: member.name.clone(),
+                ty,
+                fixed_len,
+                meta: FieldMeta {
+                    unit: member.unit.clone(),
+                    doc: None,
+                    key: member.flags & IS_KEY != 0,
+                },
+            });
+        }
+        self.in_progress.remove(hash);
+        Ok(fields)
+    }
+
+    /// Type of a member, with the length of fixed-size arrays (multi-
+    /// dimensional ones flattened in row-major order).
+    fn member_type(
+        &mut self,
+        id: &TypeId,
+        path: &str,
+    ) -> Result<(ResolvedType, Option<usize>), Ros2Error> {
+        match id {
+            TypeId::Array { elem, dims } => {
+                let ty = self.element_type(elem, path)?;
+                Ok((ty, Some(dims.iter().product())))
+            }
+            TypeId::Hash(hash) => match self.lookup(hash, path)? {
+                TypeDef::Alias(related) | TypeDef::Collection(related) => {
+                    self.member_type(related, path)
+                }
+                _ => Ok((self.element_type(id, path)?, None)),
+            },
+            _ => Ok((self.element_type(id, path)?, None)),
+        }
+    }
+
+    fn element_type(&mut self, id: &TypeId, path: &str) -> Result<ResolvedType, Ros2Error> {
+        match id {
+            TypeId::Primitive(p) => Ok(ResolvedType::Primitive(p.clone())),
+            TypeId::String { bound: 0 } => Ok(ResolvedType::Primitive(PrimitiveType::String)),
+            TypeId::String { bound } => Ok(ResolvedType::BoundedString(*bound)),
+            TypeId::WString { bound: 0 } => Ok(ResolvedType::Primitive(PrimitiveType::WString)),
+            TypeId::WString { bound } => Ok(ResolvedType::BoundedWString(*bound)),
+            TypeId::Sequence { elem, bound } => Ok(ResolvedType::Sequence {
+                elem: Box::new(self.element_type(elem, path)?),
+                max_len: (*bound > 0).then_some(*bound),
+            }),
+            TypeId::Array { .. } => Err(Ros2Error::UnsupportedFeature(format!(
+                "array nested in a sequence or array at '{path}'"
+            ))),
+            TypeId::Hash(hash) => match self.lookup(hash, path)? {
+                TypeDef::Struct(_) => self.resolve_struct(hash).map(ResolvedType::Struct),
+                TypeDef::Enum {
+                    name,
+                    bit_bound,
+                    literals,
+                } => {
+                    let key: Vec<String> = name.split("::").map(str::to_string).collect();
+                    let variants = enum_variants(name, *bit_bound, literals)?;
+                    self.enums.insert(key.clone(), variants);
+                    Ok(ResolvedType::Enum(key))
+                }
+                TypeDef::Alias(related) | TypeDef::Collection(related) => {
+                    self.element_type(related, path)
+                }
+                TypeDef::Unsupported(what) => {
+                    Err(Ros2Error::UnsupportedFeature(format!("{what} at '{path}'")))
+                }
+            },
+            TypeId::None => Err(Ros2Error::UnresolvedType(format!(
+                "member '{path}' has no type"
+            ))),
+            TypeId::Unsupported(what) => {
+                Err(Ros2Error::UnsupportedFeature(format!("{what} at '{path}'")))
+            }
+        }
+    }
+}
+
+/// Variant names indexed by value, as the CDR decoder expects; values
+/// without a literal map to their number.
+fn enum_variants(
+    name: &str,
+    bit_bound: u16,
+    literals: &[(i32, String)],
+) -> Result<Vec<String>, Ros2Error> {
+    // Enums with a bit bound of 16 or less are not encoded as 32-bit values.
+    if bit_bound <= 16 {
+        return Err(Ros2Error::UnsupportedFeature(format!(
+            "enum '{name}' with @bit_bound({bit_bound})"
+        )));
+    }
+    let len = literals
+        .iter()
+        .map(|(value, _)| *value)
+        .max()
+        .map_or(0, |max| max + 1);
+    if literals
+        .iter()
+        .any(|(value, _)| !(0..MAX_ENUM_VALUE).contains(value))
+    {
+        return Err(Ros2Error::UnsupportedFeature(format!(
+            "enum '{name}' with literal values outside 0..{MAX_ENUM_VALUE}"
+        )));
+    }
+    let mut variants: Vec<String> = (0..len).map(|value| value.to_string()).collect();
+    for (value, literal) in literals {
+        variants[*value as usize] = literal.clone();
+    }
+    Ok(variants)
+}
@@ -12,16 +12,34 @@
 //! 3. **Suffix match** — if the exact key is absent, find a unique entry whose
 //!    key *ends with* the candidate segments (e.g. `["Point"]` resolves to
 //!    `["geometry_msgs", "msg", "Point"]`).  Returns `None` if the suffix is
-//!    ambiguous (more than one match).
-//! 4. **Error** — if none of the above succeeds.
+//!    ambiguous (more than one match).  [`SuffixMatching`] can prefer the
+//!    package of the referencing struct or disable this step.
+//! 4. **Error** — if none of the above succeeds; the message lists the
+//!    suffix candidates that were ambiguous or not considered.
 
 use std::collections::HashMap;
 
 use crate::{
-    ast::{EnumDef, FieldDef, ParsedSection, PrimitiveType, StructDef, TypeExpr},
+    ast::{
+        ConstDef, EnumDef, FieldDef, FieldMeta, ParsedSection, PrimitiveType, StructDef, TypeExpr,
+    },
     error::Ros2Error,
 };
 
+/// How scoped names without an exact definition are matched by suffix.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SuffixMatching {
+    /// Use the unique definition whose qualified name ends with the name.
+    #[default]
+    Auto,
+    /// Prefer definitions in the package of the referencing struct, then
+    /// fall back to [`SuffixMatching::Auto`] if the package has none.
+    SamePackageFirst,
+    /// Only accept exact names (single-segment names are still qualified
+    /// with the enclosing module first).
+    Strict,
+}
+
 /// A fully-resolved type — all named references have been replaced with
 /// their qualified keys into [`ResolvedSchema::structs`] / [`ResolvedSchema::enums`].
 #[derive(Debug, Clone)]
@@ -42,6 +60,18 @@ pub enum ResolvedType {
     BoundedWString(usize),
 }
 
+impl ResolvedType {
+    /// The struct or enum reference inside this type, looking through
+    /// sequences.
+    pub(crate) fn named(&self) -> Option<&ResolvedType> {
+        match self {
+            Self::Struct(_) | Self::Enum(_) => Some(self),
+            Self::Sequence { elem, .. } => elem.named(),
+            _ => None,
+        }
+    }
+}
+
 /// A field with its type fully resolved.
 #[derive(Debug, Clone)]
 pub struct ResolvedField {
@@ -49,12 +79,15 @@ pub struct ResolvedField {
     pub ty: ResolvedType,
     /// `Some(n)` means this field is a fixed-length array of `n` elements.
     pub fixed_len: Option<usize>,
+    pub meta: FieldMeta,
 }
 
 /// A struct with all its fields fully resolved.
 #[derive(Debug, Clone)]
 pub struct ResolvedStruct {
     pub fields: Vec<ResolvedField>,
+    /// Constants declared in the struct or in its `<Name>_Constants` module.
+    pub consts: Vec<ConstDef>,
 }
 
 /// The complete, self-contained schema needed for CDR decoding.
@@ -91,11 +124,13 @@ pub fn ensure_builtin_structs(all_structs: &mut HashMap<Vec<String>, StructDef>)
                     name: "sec".to_string(),
                     ty: TypeExpr::Primitive(PrimitiveType::I32),
                     fixed_len: None,
+                    meta: FieldMeta::default(),
                 },
                 FieldDef {
                     name: "nanosec".to_string(),
                     ty: TypeExpr::Primitive(PrimitiveType::U32),
                     fixed_len: None,
+                    meta: FieldMeta::default(),
                 },
             ],
             consts: vec![],
@@ -115,11 +150,13 @@ pub fn ensure_builtin_structs(all_structs: &mut HashMap<Vec<String>, StructDef>)
                     name: "sec".to_string(),
                     ty: TypeExpr::Primitive(PrimitiveType::I32),
                     fixed_len: None,
+                    meta: FieldMeta::default(),
                 },
                 FieldDef {
                     name: "nanosec".to_string(),
                     ty: TypeExpr::Primitive(PrimitiveType::U32),
                     fixed_len: None,
+                    meta: FieldMeta::default(),
                 },
             ],
             consts: vec![],
@@ -132,46 +169,91 @@ pub fn resolve_struct(
     all_structs: &HashMap<Vec<String>, StructDef>,
     all_enums: &HashMap<Vec<String>, EnumDef>,
 ) -> Result<ResolvedStruct, Ros2Error> {
+    resolve_struct_with(def, all_structs, all_enums, SuffixMatching::default())
+}
+
+/// Like [`resolve_struct`], matching scoped names according to `suffix_matching`.
+pub fn resolve_struct_with(
+    def: &StructDef,
+    all_structs: &HashMap<Vec<String>, StructDef>,
+    all_enums: &HashMap<Vec<String>, EnumDef>,
+    suffix_matching: SuffixMatching,
+) -> Result<ResolvedStruct, Ros2Error> {
+    let lookup = TypeLookup {
+        all_structs,
+        all_enums,
+        suffix_matching,
+    };
     let mut fields = Vec::with_capacity(def.fields.len());
     for f in &def.fields {
-        let ty = resolve_type_expr(&f.ty, &def.full_name, all_structs, all_enums)?;
+        let ty = lookup.resolve(&f.ty, &def.full_name)?;
         fields.push(ResolvedField {
             name: f.name.clone(),
             ty,
             fixed_len: f.fixed_len,
+            meta: f.meta.clone(),
         });
     }
 
-    Ok(ResolvedStruct { fields })
+    Ok(ResolvedStruct {
+        fields,
+        consts: def.consts.clone(),
+    })
 }
 
 /// Build a [`ResolvedSchema`] from parsed structs/enums and a selected root type.
 ///
+/// Only the structs and enums reachable from `root` are resolved and kept;
+/// unresolvable types in unrelated definitions are ignored.
+///
 /// This is the shared resolution backend used by both `ros2idl` and `ros2msg`.
 /// Builtin `builtin_interfaces` structs are injected when missing.
 pub fn resolve_parsed_section(
+    parsed: ParsedSection,
+    root: Vec<String>,
+) -> Result<ResolvedSchema, Ros2Error> {
+    resolve_parsed_section_with(parsed, root, SuffixMatching::default())
+}
+
+/// Like [`resolve_parsed_section`], matching scoped names according to
+/// `suffix_matching`.
+pub fn resolve_parsed_section_with(
     mut parsed: ParsedSection,
     root: Vec<String>,
+    suffix_matching: SuffixMatching,
 ) -> Result<ResolvedSchema, Ros2Error> {
     ensure_builtin_structs(&mut parsed.structs);
 
-    let mut out = HashMap::new();
-    for (name, def) in &parsed.structs {
-        let resolved = resolve_struct(def, &parsed.structs, &parsed.enums)?;
-        out.insert(name.clone(), resolved);
+    if !parsed.structs.contains_key(&root) {
+        return Err(Ros2Error::UnresolvedType(format!(
+            "root type '{}' not found in parsed structs",
+            root.join("::")
+        )));
     }
 
+    // Only types reachable from the root are resolved, so broken definitions
+    // elsewhere in the bundle do not affect the topic.
+    let mut out = HashMap::new();
     let mut enum_out = HashMap::new();
-    for (name, def) in &parsed.enums {
-        enum_out.insert(name.clone(), def.variants.clone());
-    }
-
-    if !out.contains_key(&root) {
-        return Err(format!(
-            "root type '{}' not found in parsed structs",
-            root.join("::")
-        )
-        .into());
+    let mut pending = vec![root.clone()];
+    while let Some(name) = pending.pop() {
+        if out.contains_key(&name) {
+            continue;
+        }
+        let def = &parsed.structs[&name];
+        let resolved = resolve_struct_with(def, &parsed.structs, &parsed.enums, suffix_matching)?;
+        for field in &resolved.fields {
+            match field.ty.named() {
+                Some(ResolvedType::Struct(key)) => pending.push(key.clone()),
+                Some(ResolvedType::Enum(key)) => {
+                    enum_out
+                        .entry(key.clone())
+                        .or_insert_with(|| parsed.enums[key].variants.clone());
+                }
+                _ => {}
+            }
+        }
+        out.insert(name, resolved);
     }
 
     Ok(ResolvedSchema {
@@ -181,82 +263,129 @@ pub fn resolve_parsed_section(
     })
 }
 
-/// Recursively resolve a [`TypeExpr`] within the context of `current_struct`.
-///
-/// Single-segment scoped names are first qualified with the enclosing module
-/// before attempting exact and suffix lookups.
-fn resolve_type_expr(
-    expr: &TypeExpr,
-    current_struct: &[String],
-    all_structs: &HashMap<Vec<String>, StructDef>,
-    all_enums: &HashMap<Vec<String>, EnumDef>,
-) -> Result<ResolvedType, Ros2Error> {
-    match expr {
-        TypeExpr::Primitive(p) => Ok(ResolvedType::Primitive(p.clone())),
-        TypeExpr::BoundedString(n) => Ok(ResolvedType::BoundedString(*n)),
-        TypeExpr::BoundedWString(n) => Ok(ResolvedType::BoundedWString(*n)),
-        TypeExpr::Sequence { elem, max_len } => Ok(ResolvedType::Sequence {
-            elem: Box::new(resolve_type_expr(
-                elem,
-                current_struct,
-                all_structs,
-                all_enums,
-            )?),
-            max_len: *max_len,
-        }),
-        TypeExpr::Scoped(name) => {
-            // For a single-segment name, prepend the enclosing module so that
-            // intra-module references (e.g. `State` within `ex::msg`) resolve
-            // to `ex::msg::State` before falling back to a global suffix search.
-            let candidate = if name.len() == 1 {
-                let mut scope = current_struct[..current_struct.len().saturating_sub(1)].to_vec();
-                scope.push(name[0].clone());
-                scope
-            } else {
-                name.clone()
-            };
+/// Struct and enum definitions that scoped names are resolved against.
+struct TypeLookup<'a> {
+    all_structs: &'a HashMap<Vec<String>, StructDef>,
+    all_enums: &'a HashMap<Vec<String>, EnumDef>,
+    suffix_matching: SuffixMatching,
+}
 
-            if all_structs.contains_key(&candidate) {
-                Ok(ResolvedType::Struct(candidate))
-            } else if all_enums.contains_key(&candidate) {
-                Ok(ResolvedType::Enum(candidate))
-            } else if let Some(found) = find_by_suffix(all_structs, &candidate) {
-                Ok(ResolvedType::Struct(found))
-            } else if let Some(found) = find_by_suffix(all_enums, &candidate) {
-                Ok(ResolvedType::Enum(found))
-            } else {
-                Err(format!(
-                    "unresolved type '{}' in '{}'",
-                    name.join("::"),
-                    current_struct.join("::")
-                )
-                .into())
-            }
+impl TypeLookup<'_> {
+    /// Recursively resolve a [`TypeExpr`] within the context of `current_struct`.
+    ///
+    /// Single-segment scoped names are first qualified with the enclosing module
+    /// before attempting exact and suffix lookups.
+    fn resolve(
+        &self,
+        expr: &TypeExpr,
+        current_struct: &[String],
+    ) -> Result<ResolvedType, Ros2Error> {
+        match expr {
+            TypeExpr::Primitive(p) => Ok(ResolvedType::Primitive(p.clone())),
+            TypeExpr::BoundedString(n) => Ok(ResolvedType::BoundedString(*n)),
+            TypeExpr::BoundedWString(n) => Ok(ResolvedType::BoundedWString(*n)),
+            TypeExpr::Sequence { elem, max_len } => Ok(ResolvedType::Sequence {
+                elem: Box::new(self.resolve(elem, current_struct)?),
+                max_len: *max_len,
+            }),
+            TypeExpr::Scoped(name) => self.resolve_scoped(name, current_struct),
         }
     }
-}
 
-/// Find the unique key in `map` whose suffix matches `wanted`.
-///
-/// Returns `None` if no key matches or if more than one key matches
-/// (ambiguous reference).
-fn find_by_suffix(
-    all_structs: &HashMap<Vec<String>, impl Sized>,
-    wanted: &[String],
-) -> Option<Vec<String>> {
-    let mut found: Option<Vec<String>> = None;
-    for key in all_structs.keys() {
-        if key.len() < wanted.len() {
-            continue;
+    fn resolve_scoped(
+        &self,
+        name: &[String],
+        current_struct: &[String],
+    ) -> Result<ResolvedType, Ros2Error> {
+        // For a single-segment name, prepend the enclosing module so that
+        // intra-module references (e.g. `State` within `ex::msg`) resolve
+        // to `ex::msg::State` before falling back to a suffix search.
+        let candidate = if name.len() == 1 {
+            let mut scope = current_struct[..current_struct.len().saturating_sub(1)].to_vec();
+            scope.push(name[0].clone());
+            scope
+        } else {
+            name.to_vec()
+        };
+
+        if self.all_structs.contains_key(&candidate) {
+            return Ok(ResolvedType::Struct(candidate));
+        }
+        if self.all_enums.contains_key(&candidate) {
+            return Ok(ResolvedType::Enum(candidate));
+        }
+
+        // The suffix search uses the name as written, so `Header` may match
+        // `std_msgs::msg::Header` from any module.
+        let package = current_struct.first().map(String::as_str);
+        let struct_candidates = suffix_candidates(self.all_structs, name);
+        if let Some(found) = self.pick(&struct_candidates, package) {
+            return Ok(ResolvedType::Struct(found));
         }
-        if key[key.len() - wanted.len()..] == *wanted {
-            if found.is_some() {
-                // Ambiguous — more than one key ends with `wanted`.
-                return None;
+        let enum_candidates = suffix_candidates(self.all_enums, name);
+        if let Some(found) = self.pick(&enum_candidates, package) {
+            return Ok(ResolvedType::Enum(found));
+        }
+
+        let mut message = format!(
+            "unresolved type '{}' in '{}'",
+            name.join("::"),
+            current_struct.join("::")
+        );
+        let candidates: Vec<String> = struct_candidates
+            .iter()
+            .chain(&enum_candidates)
+            .map(|key| key.join("::"))
+            .collect();
+        if !candidates.is_empty() {
+            let reason = match self.suffix_matching {
+                SuffixMatching::Strict => "suffix matching is disabled",
+                _ => "ambiguous suffix match",
+            };
+            message.push_str(&format!(
+                " ({reason}; candidates: {})",
+                candidates.join(", ")
+            ));
+        }
+        Err(Ros2Error::UnresolvedType(message))
+    }
+
+    /// Choose the suffix candidate to resolve to, if the matching mode
+    /// allows an unambiguous choice.
+    fn pick(&self, candidates: &[Vec<String>], package: Option<&str>) -> Option<Vec<String>> {
+        let unique = |keys: Vec<&Vec<String>>| match keys.as_slice() {
+            [key] => Some((*key).clone()),
+            _ => None,
+        };
+        match self.suffix_matching {
+            SuffixMatching::Strict => None,
+            SuffixMatching::Auto => unique(candidates.iter().collect()),
+            SuffixMatching::SamePackageFirst => {
+                let same_package: Vec<_> = candidates
+                    .iter()
+                    .filter(|key| key.first().map(String::as_str) == package)
+                    .collect();
+                if same_package.is_empty() {
+                    unique(candidates.iter().collect())
+                } else {
+                    unique(same_package)
+                }
             }
-            found = Some(key.clone());
         }
     }
+}
+
+/// Keys in `map` whose suffix matches `wanted`, sorted.
+fn suffix_candidates(
+    map: &HashMap<Vec<String>, impl Sized>,
+    wanted: &[String],
+) -> Vec<Vec<String>> {
+    let mut found: Vec<Vec<String>> = map
+        .keys()
+        .filter(|key| key.len() >= wanted.len() && key[key.len() - wanted.len()..] == *wanted)
+        .cloned()
+        .collect();
+    found.sort();
     found
 }
 
@@ -1,8 +1,10 @@
 use std::collections::HashMap;
 
-use mcapdecode_core::Value;
+use mcapdecode_core::{DataTypeDef, DecodeWarning, DecoderError, Value, collect_warnings};
 use mcapdecode_ros2_common::{
-    PrimitiveType, ResolvedField, ResolvedSchema, ResolvedStruct, ResolvedType, decode_cdr_to_value,
+    ByteArrays, CdrOptions, CdrQuirks, FieldMeta, InvalidUtf8, PrimitiveType, ResolvedField,
+    ResolvedSchema, ResolvedStruct, ResolvedType, Ros2Error, UuidRepresentation,
+    decode_cdr_to_value, decode_cdr_to_value_with, resolved_schema_to_field_defs_with,
 };
 
 // ── helpers ──────────────────────────────────────────────────────────────────
@@ -22,7 +24,13 @@ fn make_schema(
 ) -> ResolvedSchema {
     let root = vec!["ex".to_string(), "msg".to_string(), "A".to_string()];
     let mut structs = HashMap::new();
-    structs.insert(root.clone(), ResolvedStruct { fields });
+    structs.insert(
+        root.clone(),
+        ResolvedStruct {
+            fields,
+            consts: vec![],
+        },
+    );
     ResolvedSchema {
         root,
         structs,
@@ -47,11 +55,13 @@ fn decodes_f64_with_encapsulation_relative_alignment() {
                 name: "flag".to_string(),
                 ty: ResolvedType::Primitive(PrimitiveType::U8),
                 fixed_len: None,
+                meta: FieldMeta::default(),
             },
             ResolvedField {
                 name: "value".to_string(),
                 ty: ResolvedType::Primitive(PrimitiveType::F64),
                 fixed_len: None,
+                meta: FieldMeta::default(),
             },
         ],
         HashMap::new(),
@@ -81,6 +91,7 @@ fn fails_on_sequence_bound_overflow() {
                 max_len: Some(1),
             },
             fixed_len: None,
+            meta: FieldMeta::default(),
         }],
         HashMap::new(),
     );
@@ -102,6 +113,7 @@ fn decodes_enum_index_to_variant_name() {
             name: "state".to_string(),
             ty: ResolvedType::Enum(enum_name.clone()),
             fixed_len: None,
+            meta: FieldMeta::default(),
         }],
         HashMap::from([(enum_name, vec!["OK".to_string(), "WARN".to_string()])]),
     );
@@ -125,6 +137,7 @@ fn fails_on_string_without_null_terminator() {
             name: "name".to_string(),
             ty: ResolvedType::Primitive(PrimitiveType::String),
             fixed_len: None,
+            meta: FieldMeta::default(),
         }],
         HashMap::new(),
     );
@@ -149,11 +162,13 @@ fn decodes_bool_fields() {
                 name: "a".to_string(),
                 ty: ResolvedType::Primitive(PrimitiveType::Bool),
                 fixed_len: None,
+                meta: FieldMeta::default(),
             },
             ResolvedField {
                 name: "b".to_string(),
                 ty: ResolvedType::Primitive(PrimitiveType::Bool),
                 fixed_len: None,
+                meta: FieldMeta::default(),
             },
         ],
         HashMap::new(),
@@ -176,6 +191,7 @@ fn decodes_fixed_length_array() {
             name: "coords".to_string(),
             ty: ResolvedType::Primitive(PrimitiveType::I32),
             fixed_len: Some(3),
+            meta: FieldMeta::default(),
         }],
         HashMap::new(),
     );
@@ -214,20 +230,25 @@ fn decodes_nested_struct() {
                 name: "x".to_string(),
                 ty: ResolvedType::Primitive(PrimitiveType::U32),
                 fixed_len: None,
+                meta: FieldMeta::default(),
             },
             ResolvedField {
                 name: "y".to_string(),
                 ty: ResolvedType::Primitive(PrimitiveType::U32),
                 fixed_len: None,
+                meta: FieldMeta::default(),
             },
         ],
+        consts: vec![],
     };
     let root_struct = ResolvedStruct {
         fields: vec![ResolvedField {
             name: "inner".to_string(),
             ty: ResolvedType::Struct(inner_name.clone()),
             fixed_len: None,
+            meta: FieldMeta::default(),
         }],
+        consts: vec![],
     };
 
     let mut structs = HashMap::new();
@@ -264,6 +285,7 @@ fn decodes_out_of_range_enum_index_as_raw_number() {
             name: "e".to_string(),
             ty: ResolvedType::Enum(enum_name.clone()),
             fixed_len: None,
+            meta: FieldMeta::default(),
         }],
         HashMap::from([(enum_name, vec!["A".to_string()])]),
     );
@@ -289,14 +311,84 @@ fn fails_on_truncated_encapsulation_header() {
     assert!(format!("{err:#}").contains("incomplete encapsulation header"));
 }
 
-/// An unsupported endianness byte in the encapsulation header returns an error.
+/// A `CDR_BE` payload decodes with big-endian reads and the same alignment.
 #[test]
-fn fails_on_big_endian_encapsulation() {
+fn decodes_big_endian_encapsulation() {
+    let schema = make_schema(
+        vec![
+            ResolvedField {
+                name: "flag".to_string(),
+                ty: ResolvedType::Primitive(PrimitiveType::U8),
+                fixed_len: None,
+                meta: FieldMeta::default(),
+            },
+            ResolvedField {
+                name: "x".to_string(),
+                ty: ResolvedType::Primitive(PrimitiveType::F64),
+                fixed_len: None,
+                meta: FieldMeta::default(),
+            },
+            ResolvedField {
+                name: "name".to_string(),
+                ty: ResolvedType::Primitive(PrimitiveType::String),
+                fixed_len: None,
+                meta: FieldMeta::default(),
+            },
+        ],
+        HashMap::new(),
+    );
+    let mut payload = vec![7];
+    align(&mut payload, 8);
+    payload.extend_from_slice(&2.5f64.to_be_bytes());
+    payload.extend_from_slice(&3u32.to_be_bytes());
+    payload.extend_from_slice(b"ab\0");
+    let mut cdr = vec![0x00, 0x00, 0x00, 0x00]; // CDR_BE encapsulation
+    cdr.extend(payload);
+
+    let value = decode_cdr_to_value(&schema, &cdr).expect("decode should succeed");
+    assert_eq!(
+        value,
+        Value::Struct(vec![Value::U8(7), Value::F64(2.5), Value::string("ab")])
+    );
+}
+
+/// An unknown encapsulation identifier returns an error.
+#[test]
+fn fails_on_unknown_encapsulation() {
     let schema = make_schema(vec![], HashMap::new());
-    // Byte 1 = 0x00 → big-endian (only 0x01 little-endian is supported).
-    let cdr = vec![0x00, 0x00, 0x00, 0x00];
+    let cdr = vec![0x00, 0x20, 0x00, 0x00];
     let err = decode_cdr_to_value(&schema, &cdr).expect_err("should fail");
-    assert!(format!("{err:#}").contains("unsupported CDR endianness"));
+    assert!(format!("{err:#}").contains("unsupported CDR encapsulation: 0x20"));
+}
+
+/// A payload ending inside a field reports the field path and byte offset.
+#[test]
+fn truncated_payload_reports_cdr_error_location() {
+    let schema = make_schema(
+        vec![ResolvedField {
+            name: "x".to_string(),
+            ty: ResolvedType::Primitive(PrimitiveType::F64),
+            fixed_len: None,
+            meta: FieldMeta::default(),
+        }],
+        HashMap::new(),
+    );
+    let cdr = cdr_with_payload(vec![0x00, 0x01]);
+
+    let Err(DecoderError::MessageDecode { source, .. }) = decode_cdr_to_value(&schema, &cdr) else {
+        panic!("truncated payload should fail to decode");
+    };
+    let Some(Ros2Error::CdrError {
+        path,
+        offset,
+        detail,
+    }) = source.downcast_ref::<Ros2Error>()
+    else {
+        panic!("expected a CDR error, got {source}");
+    };
+    assert_eq!(path, "ex.msg.A.x");
+    assert_eq!(*offset, 4);
+    assert_eq!(detail, "unexpected EOF at ex.msg.A.x");
 }
 
 /// An unbounded sequence decodes its length-prefixed elements into `Value::List`.
@@ -310,6 +402,7 @@ fn decodes_unbounded_sequence() {
                 max_len: None,
             },
             fixed_len: None,
+            meta: FieldMeta::default(),
         }],
         HashMap::new(),
     );
@@ -340,6 +433,7 @@ fn decodes_string_with_null_terminator() {
             name: "label".to_string(),
             ty: ResolvedType::Primitive(PrimitiveType::String),
             fixed_len: None,
+            meta: FieldMeta::default(),
         }],
         HashMap::new(),
     );
@@ -356,3 +450,504 @@ fn decodes_string_with_null_terminator() {
     };
     assert!(matches!(&fields[0], Value::String(s) if s.as_ref() == "hello"));
 }
+
+/// Strings with invalid UTF-8 fail, are replaced with a warning, or decode
+/// as bytes, depending on the policy.
+#[test]
+fn decodes_invalid_utf8_strings_by_policy() {
+    let schema = make_schema(
+        vec![ResolvedField {
+            name: "frame_id".to_string(),
+            ty: ResolvedType::Primitive(PrimitiveType::String),
+            fixed_len: None,
+            meta: FieldMeta::default(),
+        }],
+        HashMap::new(),
+    );
+    let s = b"map\xff\0";
+    let mut payload = Vec::new();
+    payload.extend_from_slice(&(s.len() as u32).to_le_bytes());
+    payload.extend_from_slice(s);
+    let cdr = cdr_with_payload(payload);
+
+    let err = decode_cdr_to_value_with(
+        &schema,
+        &cdr,
+        CdrOptions {
+            invalid_utf8: InvalidUtf8::Error,
+            ..CdrOptions::default()
+        },
+    )
+    .unwrap_err();
+    assert!(format!("{err:#}").contains("invalid UTF-8 at ex.msg.A.frame_id"));
+
+    let (value, warnings) = collect_warnings(|| {
+        decode_cdr_to_value_with(
+            &schema,
+            &cdr,
+            CdrOptions {
+                invalid_utf8: InvalidUtf8::Replace,
+                ..CdrOptions::default()
+            },
+        )
+    });
+    assert_eq!(
+        value.unwrap(),
+        Value::Struct(vec![Value::string("map\u{fffd}")])
+    );
+    assert_eq!(
+        warnings,
+        vec![DecodeWarning::new(
+            "ex.msg.A.frame_id",
+            "invalid UTF-8 replaced with U+FFFD"
+        )]
+    );
+
+    let value = decode_cdr_to_value_with(
+        &schema,
+        &cdr,
+        CdrOptions {
+            invalid_utf8: InvalidUtf8::Bytes,
+            ..CdrOptions::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        value,
+        Value::Struct(vec![Value::Bytes(b"map\xff".as_slice().into())])
+    );
+    assert_eq!(
+        resolved_schema_to_field_defs_with(
+            &schema,
+            CdrOptions {
+                invalid_utf8: InvalidUtf8::Bytes,
+                ..CdrOptions::default()
+            }
+        )[0]
+        .element
+        .data_type,
+        DataTypeDef::Bytes
+    );
+}
+
+/// A `Header`-like message with a nested empty struct followed by fields of
+/// every alignment.
+fn empty_struct_schema() -> ResolvedSchema {
+    let field = |name: &str, ty: ResolvedType| ResolvedField {
+        name: name.to_string(),
+        ty,
+        fixed_len: None,
+        meta: FieldMeta::default(),
+    };
+    let empty = vec!["ex".to_string(), "msg".to_string(), "Empty".to_string()];
+    let mut schema = make_schema(
+        vec![
+            field("marker", ResolvedType::Struct(empty.clone())),
+            field("flag", ResolvedType::Primitive(PrimitiveType::U8)),
+            field("seq", ResolvedType::Primitive(PrimitiveType::U32)),
+            field("stamp", ResolvedType::Primitive(PrimitiveType::F64)),
+        ],
+        HashMap::new(),
+    );
+    schema.structs.insert(
+        empty,
+        ResolvedStruct {
+            fields: vec![],
+            consts: vec![],
+        },
+    );
+    schema
+}
+
+/// Payloads of the same message as written by Fast DDS and by Cyclone DDS
+/// (which records the trailing padding in the encapsulation options) decode
+/// identically, and each quirks mode reads its own layout.
+#[test]
+fn decodes_rmw_payloads_identically_across_quirks_modes() {
+    let schema = empty_struct_schema();
+    let expected = Value::Struct(vec![
+        Value::Struct(vec![]),
+        Value::U8(7),
+        Value::U32(42),
+        Value::F64(1.5),
+    ]);
+
+    // rosidl: placeholder byte, flag, pad to 4, seq, pad to 8, stamp.
+    let mut rosidl = vec![0x00, 0x07];
+    align(&mut rosidl, 4);
+    rosidl.extend_from_slice(&42u32.to_le_bytes());
+    align(&mut rosidl, 8);
+    rosidl.extend_from_slice(&1.5f64.to_le_bytes());
+    // plain DDS: no placeholder byte.
+    let mut dds = vec![0x07];
+    align(&mut dds, 4);
+    dds.extend_from_slice(&42u32.to_le_bytes());
+    align(&mut dds, 8);
+    dds.extend_from_slice(&1.5f64.to_le_bytes());
+    // XCDR2: no placeholder byte, f64 aligned to 4 only.
+    let mut xcdr2 = vec![0x07];
+    align(&mut xcdr2, 4);
+    xcdr2.extend_from_slice(&42u32.to_le_bytes());
+    xcdr2.extend_from_slice(&1.5f64.to_le_bytes());
+
+    let fastdds = cdr_with_payload(rosidl.clone());
+    let mut cyclonedds = vec![0x00, 0x01, 0x00, 0x03];
+    cyclonedds.extend(&rosidl);
+    cyclonedds.extend([0, 0, 0]);
+    let mut cyclonedds_xcdr2 = vec![0x00, 0x07, 0x00, 0x00];
+    cyclonedds_xcdr2.extend(&xcdr2);
+
+    let cases = [
+        ("fastdds", CdrQuirks::Rosidl, fastdds),
+        ("cyclonedds", CdrQuirks::Rosidl, cyclonedds),
+        ("dds", CdrQuirks::Dds, cdr_with_payload(dds)),
+        ("xcdr2", CdrQuirks::Xcdr2, cdr_with_payload(xcdr2)),
+        ("cyclonedds xcdr2", CdrQuirks::Xcdr2, cyclonedds_xcdr2),
+    ];
+    for (name, quirks, cdr) in cases {
+        let options = CdrOptions {
+            quirks,
+            ..CdrOptions::default()
+        };
+        let value = decode_cdr_to_value_with(&schema, &cdr, options)
+            .unwrap_or_else(|e| panic!("{name}: {e:#}"));
+        assert_eq!(value, expected, "{name}");
+    }
+}
+
+/// A top-level empty message (e.g. `std_msgs/Empty`) needs its placeholder
+/// byte only with rosidl quirks.
+#[test]
+fn decodes_empty_message_by_quirks_mode() {
+    let schema = make_schema(vec![], HashMap::new());
+    let rosidl = CdrOptions::default();
+    let dds = CdrOptions {
+        quirks: CdrQuirks::Dds,
+        ..CdrOptions::default()
+    };
+
+    let with_placeholder = cdr_with_payload(vec![0x00]);
+    assert_eq!(
+        decode_cdr_to_value_with(&schema, &with_placeholder, rosidl).unwrap(),
+        Value::Struct(vec![])
+    );
+    let err = decode_cdr_to_value_with(&schema, &cdr_with_payload(vec![]), rosidl).unwrap_err();
+    assert!(format!("{err:#}").contains("unexpected EOF at ex.msg.A"));
+    assert_eq!(
+        decode_cdr_to_value_with(&schema, &cdr_with_payload(vec![]), dds).unwrap(),
+        Value::Struct(vec![])
+    );
+}
+
+/// A message with a `uint8[4]` field and a `unique_identifier_msgs/msg/UUID`
+/// field.
+fn byte_array_schema() -> ResolvedSchema {
+    let uuid = vec![
+        "unique_identifier_msgs".to_string(),
+        "msg".to_string(),
+        "UUID".to_string(),
+    ];
+    let mut schema = make_schema(
+        vec![
+            ResolvedField {
+                name: "tag".to_string(),
+                ty: ResolvedType::Primitive(PrimitiveType::U8),
+                fixed_len: Some(4),
+                meta: FieldMeta::default(),
+            },
+            ResolvedField {
+                name: "goal_id".to_string(),
+                ty: ResolvedType::Struct(uuid.clone()),
+                fixed_len: None,
+                meta: FieldMeta::default(),
+            },
+        ],
+        HashMap::new(),
+    );
+    schema.structs.insert(
+        uuid,
+        ResolvedStruct {
+            fields: vec![ResolvedField {
+                name: "uuid".to_string(),
+                ty: ResolvedType::Primitive(PrimitiveType::U8),
+                fixed_len: Some(16),
+                meta: FieldMeta::default(),
+            }],
+            consts: vec![],
+        },
+    );
+    schema
+}
+
+#[test]
+fn decodes_byte_arrays_and_uuids_as_binary() {
+    let schema = byte_array_schema();
+    let uuid: Vec<u8> = (0..16).collect();
+    let mut payload = vec![1, 2, 3, 4];
+    payload.extend(&uuid);
+    let cdr = cdr_with_payload(payload);
+    let options = CdrOptions {
+        byte_arrays: ByteArrays::Binary,
+        uuids: UuidRepresentation::Binary,
+        ..CdrOptions::default()
+    };
+
+    assert_eq!(
+        decode_cdr_to_value_with(&schema, &cdr, options).unwrap(),
+        Value::Struct(vec![
+            Value::Bytes(vec![1, 2, 3, 4].into()),
+            Value::Bytes(uuid.into()),
+        ])
+    );
+    let field_defs = resolved_schema_to_field_defs_with(&schema, options);
+    assert_eq!(field_defs[0].element.data_type, DataTypeDef::Bytes);
+    assert_eq!(field_defs[0].byte_width(), Some(4));
+    assert_eq!(field_defs[1].element.data_type, DataTypeDef::Bytes);
+    assert_eq!(field_defs[1].byte_width(), Some(16));
+    assert!(field_defs[1].is_uuid());
+}
+
+#[test]
+fn decodes_uuids_as_canonical_strings() {
+    let schema = byte_array_schema();
+    let mut payload = vec![1, 2, 3, 4];
+    payload.extend((0..16).map(|b: u8| b * 17));
+    let cdr = cdr_with_payload(payload);
+    let options = CdrOptions {
+        uuids: UuidRepresentation::String,
+        ..CdrOptions::default()
+    };
+
+    let value = decode_cdr_to_value_with(&schema, &cdr, options).unwrap();
+    assert_eq!(
+        value,
+        Value::Struct(vec![
+            Value::Array([1, 2, 3, 4].into_iter().map(Value::U8).collect()),
+            Value::string("00112233-4455-6677-8899-aabbccddeeff"),
+        ])
+    );
+    let field_defs = resolved_schema_to_field_defs_with(&schema, options);
+    assert!(matches!(
+        field_defs[0].element.data_type,
+        DataTypeDef::Array(_, 4)
+    ));
+    assert_eq!(field_defs[1].element.data_type, DataTypeDef::String);
+    assert_eq!(field_defs[1].byte_width(), None);
+    assert!(field_defs[1].is_uuid());
+    assert!(!field_defs[0].is_uuid());
+}
+
+// ── XCDR2 ────────────────────────────────────────────────────────────────────
+
+fn primitive_field(name: &str, ty: ResolvedType) -> ResolvedField {
+    ResolvedField {
+        name: name.to_string(),
+        ty,
+        fixed_len: None,
+        meta: FieldMeta::default(),
+    }
+}
+
+/// Build an XCDR2 buffer with the given encapsulation identifier.
+fn xcdr2_with_payload(id: u8, payload: Vec<u8>) -> Vec<u8> {
+    let mut buf = vec![0x00, id, 0x00, 0x00];
+    buf.extend(payload);
+    buf
+}
+
+/// A `CDR2_LE` payload is detected without XCDR2 quirks: 8-byte primitives
+/// are aligned to 4, and sequences of strings have a `DHEADER`.
+#[test]
+fn detects_cdr2_encapsulation() {
+    let schema = make_schema(
+        vec![
+            primitive_field("seq", ResolvedType::Primitive(PrimitiveType::U32)),
+            primitive_field("stamp", ResolvedType::Primitive(PrimitiveType::F64)),
+            primitive_field(
+                "names",
+                ResolvedType::Sequence {
+                    elem: Box::new(ResolvedType::Primitive(PrimitiveType::String)),
+                    max_len: None,
+                },
+            ),
+        ],
+        HashMap::new(),
+    );
+    let mut payload = Vec::new();
+    payload.extend_from_slice(&42u32.to_le_bytes());
+    payload.extend_from_slice(&1.5f64.to_le_bytes());
+    payload.extend_from_slice(&11u32.to_le_bytes()); // DHEADER
+    payload.extend_from_slice(&1u32.to_le_bytes());
+    payload.extend_from_slice(&3u32.to_le_bytes());
+    payload.extend_from_slice(b"ab\0");
+
+    let value = decode_cdr_to_value(&schema, &xcdr2_with_payload(0x07, payload)).unwrap();
+    assert_eq!(
+        value,
+        Value::Struct(vec![
+            Value::U32(42),
+            Value::F64(1.5),
+            Value::List(vec![Value::string("ab")]),
+        ])
+    );
+}
+
+/// `D_CDR2_LE` structs written by an older type version give the missing
+/// members their default value, and members appended by a newer one are
+/// skipped.
+#[test]
+fn decodes_appendable_structs_across_type_versions() {
+    let schema = make_schema(
+        vec![
+            primitive_field("a", ResolvedType::Primitive(PrimitiveType::U32)),
+            primitive_field("b", ResolvedType::Primitive(PrimitiveType::U16)),
+            primitive_field("c", ResolvedType::Primitive(PrimitiveType::U32)),
+        ],
+        HashMap::new(),
+    );
+
+    let mut older = Vec::new();
+    older.extend_from_slice(&4u32.to_le_bytes()); // DHEADER
+    older.extend_from_slice(&1u32.to_le_bytes());
+    let value = decode_cdr_to_value(&schema, &xcdr2_with_payload(0x09, older)).unwrap();
+    assert_eq!(
+        value,
+        Value::Struct(vec![Value::U32(1), Value::U16(0), Value::U32(0)])
+    );
+
+    let mut newer = Vec::new();
+    newer.extend_from_slice(&16u32.to_le_bytes()); // DHEADER
+    newer.extend_from_slice(&1u32.to_le_bytes());
+    newer.extend_from_slice(&2u16.to_le_bytes());
+    align(&mut newer, 4);
+    newer.extend_from_slice(&3u32.to_le_bytes());
+    newer.extend_from_slice(&4u32.to_le_bytes()); // appended member
+    let value = decode_cdr_to_value(&schema, &xcdr2_with_payload(0x09, newer)).unwrap();
+    assert_eq!(
+        value,
+        Value::Struct(vec![Value::U32(1), Value::U16(2), Value::U32(3)])
+    );
+}
+
+/// `PL_CDR2_LE` members are matched by member ID in any order; absent
+/// members take their default value, and unknown ones are skipped unless
+/// they must be understood.
+#[test]
+fn decodes_mutable_struct_members_by_id() {
+    let schema = make_schema(
+        vec![
+            primitive_field("a", ResolvedType::Primitive(PrimitiveType::U32)),
+            primitive_field("b", ResolvedType::Primitive(PrimitiveType::U16)),
+            primitive_field(
+                "values",
+                ResolvedType::Sequence {
+                    elem: Box::new(ResolvedType::Primitive(PrimitiveType::U32)),
+                    max_len: None,
+                },
+            ),
+        ],
+        HashMap::new(),
+    );
+    let emheader = |must_understand: bool, lc: u32, id: u32| {
+        (u32::from(must_understand) << 31 | lc << 28 | id).to_le_bytes()
+    };
+    let members = |unknown_must_understand: bool| {
+        let mut members = Vec::new();
+        // `values`, its length code telling the size from its own count.
+        members.extend_from_slice(&emheader(false, 6, 2));
+        members.extend_from_slice(&2u32.to_le_bytes());
+        members.extend_from_slice(&10u32.to_le_bytes());
+        members.extend_from_slice(&20u32.to_le_bytes());
+        members.extend_from_slice(&emheader(false, 2, 0));
+        members.extend_from_slice(&7u32.to_le_bytes());
+        members.extend_from_slice(&emheader(unknown_must_understand, 2, 9));
+        members.extend_from_slice(&0u32.to_le_bytes());
+        let mut payload = (members.len() as u32).to_le_bytes().to_vec(); // DHEADER
+        payload.extend(members);
+        xcdr2_with_payload(0x0b, payload)
+    };
+
+    let value = decode_cdr_to_value(&schema, &members(false)).unwrap();
+    assert_eq!(
+        value,
+        Value::Struct(vec![
+            Value::U32(7),
+            Value::U16(0),
+            Value::List(vec![Value::U32(10), Value::U32(20)]),
+        ])
+    );
+
+    let err = decode_cdr_to_value(&schema, &members(true)).expect_err("should fail");
+    assert!(format!("{err:#}").contains("unknown member ID 9"));
+}
+
+/// A `D_CDR2_LE` UUID struct is read with its `DHEADER` before it is turned
+/// into bytes or a string, so the members after it stay aligned.
+#[test]
+fn decodes_uuids_in_appendable_structs() {
+    let mut schema = byte_array_schema();
+    let root = schema.root.clone();
+    schema
+        .structs
+        .get_mut(&root)
+        .unwrap()
+        .fields
+        .push(primitive_field(
+            "count",
+            ResolvedType::Primitive(PrimitiveType::U32),
+        ));
+    let uuid: Vec<u8> = (0..16).map(|b: u8| b * 17).collect();
+    let mut payload = Vec::new();
+    payload.extend_from_slice(&28u32.to_le_bytes()); // DHEADER
+    payload.extend_from_slice(&[1, 2, 3, 4]);
+    payload.extend_from_slice(&16u32.to_le_bytes()); // DHEADER of the UUID
+    payload.extend_from_slice(&uuid);
+    payload.extend_from_slice(&7u32.to_le_bytes());
+    let cdr = xcdr2_with_payload(0x09, payload);
+
+    let options = CdrOptions {
+        byte_arrays: ByteArrays::Binary,
+        uuids: UuidRepresentation::Binary,
+        ..CdrOptions::default()
+    };
+    assert_eq!(
+        decode_cdr_to_value_with(&schema, &cdr, options).unwrap(),
+        Value::Struct(vec![
+            Value::Bytes(vec![1, 2, 3, 4].into()),
+            Value::Bytes(uuid.into()),
+            Value::U32(7),
+        ])
+    );
+
+    let options = CdrOptions {
+        uuids: UuidRepresentation::String,
+        ..CdrOptions::default()
+    };
+    assert_eq!(
+        decode_cdr_to_value_with(&schema, &cdr, options).unwrap(),
+        Value::Struct(vec![
+            Value::Array([1, 2, 3, 4].into_iter().map(Value::U8).collect()),
+            Value::string("00112233-4455-6677-8899-aabbccddeeff"),
+            Value::U32(7),
+        ])
+    );
+}
+
+/// Big-endian XCDR2 payloads read their `DHEADER`s big-endian too.
+#[test]
+fn decodes_big_endian_appendable_struct() {
+    let schema = make_schema(
+        vec![
+            primitive_field("a", ResolvedType::Primitive(PrimitiveType::U32)),
+            primitive_field("b", ResolvedType::Primitive(PrimitiveType::U16)),
+        ],
+        HashMap::new(),
+    );
+    let mut payload = Vec::new();
+    payload.extend_from_slice(&6u32.to_be_bytes()); // DHEADER
+    payload.extend_from_slice(&1u32.to_be_bytes());
+    payload.extend_from_slice(&2u16.to_be_bytes());
+
+    let value = decode_cdr_to_value(&schema, &xcdr2_with_payload(0x08, payload)).unwrap();
+    assert_eq!(value, Value::Struct(vec![Value::U32(1), Value::U16(2)]));
+}
@@ -0,0 +1,143 @@
+use std::collections::HashMap;
+
+use mcapdecode_ros2_common::{
+    ConstDef, FieldMeta, PrimitiveType, ResolvedField, ResolvedSchema, ResolvedStruct,
+    ResolvedType, TypeExpr, resolved_schema_to_msg_bundle, resolved_struct_to_msg,
+};
+
+fn key(path: &str) -> Vec<String> {
+    path.split("::").map(str::to_string).collect()
+}
+
+fn field(name: &str, ty: ResolvedType) -> ResolvedField {
+    ResolvedField {
+        name: name.to_string(),
+        ty,
+        fixed_len: None,
+        meta: FieldMeta::default(),
+    }
+}
+
+fn point_struct() -> ResolvedStruct {
+    ResolvedStruct {
+        fields: vec![
+            field("x", ResolvedType::Primitive(PrimitiveType::F64)),
+            field("y", ResolvedType::Primitive(PrimitiveType::F64)),
+        ],
+        consts: vec![],
+    }
+}
+
+#[test]
+fn renders_fields_constants_and_bounds() {
+    let mut covariance = field("covariance", ResolvedType::Primitive(PrimitiveType::F64));
+    covariance.fixed_len = Some(9);
+    let mut speed = field("speed", ResolvedType::Primitive(PrimitiveType::F32));
+    speed.meta.unit = Some("m/s".to_string());
+    speed.meta.doc = Some("Forward speed.".to_string());
+    let resolved = ResolvedStruct {
+        fields: vec![
+            field("frame_id", ResolvedType::BoundedString(16)),
+            covariance,
+            field(
+                "points",
+                ResolvedType::Sequence {
+                    elem: Box::new(ResolvedType::Struct(key("geometry_msgs::msg::Point"))),
+                    max_len: Some(4),
+                },
+            ),
+            field(
+                "data",
+                ResolvedType::Sequence {
+                    elem: Box::new(ResolvedType::Primitive(PrimitiveType::Octet)),
+                    max_len: None,
+                },
+            ),
+            field("mode", ResolvedType::Enum(key("pkg::msg::Mode"))),
+            speed,
+        ],
+        consts: vec![ConstDef {
+            ty: TypeExpr::Primitive(PrimitiveType::U8),
+            name: "MAX".to_string(),
+            value: "10".to_string(),
+            typed_value: None,
+        }],
+    };
+
+    assert_eq!(
+        resolved_struct_to_msg(&resolved),
+        "uint8 MAX=10
+string<=16 frame_id
+float64[9] covariance
+geometry_msgs/Point[<=4] points
+byte[] data
+uint32 mode  # enum pkg::msg::Mode
+# Forward speed.
+float32 speed  # [m/s]
+"
+    );
+}
+
+#[test]
+fn renders_bundle_with_referenced_structs_in_first_use_order() {
+    let root = key("pkg::msg::Path");
+    let mut structs = HashMap::new();
+    structs.insert(
+        root.clone(),
+        ResolvedStruct {
+            fields: vec![
+                field(
+                    "stamp",
+                    ResolvedType::Struct(key("builtin_interfaces::msg::Time")),
+                ),
+                field(
+                    "points",
+                    ResolvedType::Sequence {
+                        elem: Box::new(ResolvedType::Struct(key("geometry_msgs::msg::Point"))),
+                        max_len: None,
+                    },
+                ),
+                field(
+                    "origin",
+                    ResolvedType::Struct(key("geometry_msgs::msg::Point")),
+                ),
+            ],
+            consts: vec![],
+        },
+    );
+    structs.insert(key("geometry_msgs::msg::Point"), point_struct());
+    structs.insert(
+        key("builtin_interfaces::msg::Time"),
+        ResolvedStruct {
+            fields: vec![
+                field("sec", ResolvedType::Primitive(PrimitiveType::I32)),
+                field("nanosec", ResolvedType::Primitive(PrimitiveType::U32)),
+            ],
+            consts: vec![],
+        },
+    );
+    let schema = ResolvedSchema {
+        root,
+        structs,
+        enums: HashMap::new(),
+    };
+
+    let separator = "=".repeat(80);
+    assert_eq!(
+        resolved_schema_to_msg_bundle(&schema),
+        format!(
+            "builtin_interfaces/Time stamp
+geometry_msgs/Point[] points
+geometry_msgs/Point origin
+{separator}
+MSG: builtin_interfaces/msg/Time
+int32 sec
+uint32 nanosec
+{separator}
+MSG: geometry_msgs/msg/Point
+float64 x
+float64 y
+"
+        )
+    );
+}
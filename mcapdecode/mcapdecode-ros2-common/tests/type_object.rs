@@ -0,0 +1,315 @@
+use mcapdecode_core::Value;
+use mcapdecode_ros2_common::{Ros2Error, decode_cdr_to_value, resolve_type_object};
+
+// ── XCDR2 writer ─────────────────────────────────────────────────────────────
+
+const EK_COMPLETE: u8 = 0xF2;
+const EK_MINIMAL: u8 = 0xF1;
+const TK_UINT32: u8 = 0x07;
+const TK_INT16: u8 = 0x03;
+const TK_FLOAT32: u8 = 0x09;
+const TK_FLOAT64: u8 = 0x0A;
+const TK_ENUM: u8 = 0x40;
+const TK_STRUCTURE: u8 = 0x51;
+const IS_OPTIONAL: u16 = 1 << 3;
+const IS_KEY: u16 = 1 << 5;
+
+/// Little-endian XCDR2 writer, starting with a `CDR2_LE` encapsulation header.
+struct Xcdr(Vec<u8>);
+
+impl Xcdr {
+    fn new() -> Self {
+        Self(vec![0x00, 0x07, 0x00, 0x00])
+    }
+
+    fn align(&mut self, n: usize) {
+        while !(self.0.len() - 4).is_multiple_of(n.min(4)) {
+            self.0.push(0);
+        }
+    }
+
+    fn u8(&mut self, v: u8) {
+        self.0.push(v);
+    }
+
+    fn u16(&mut self, v: u16) {
+        self.align(2);
+        self.0.extend_from_slice(&v.to_le_bytes());
+    }
+
+    fn u32(&mut self, v: u32) {
+        self.align(4);
+        self.0.extend_from_slice(&v.to_le_bytes());
+    }
+
+    fn string(&mut self, s: &str) {
+        self.u32(s.len() as u32 + 1);
+        self.0.extend_from_slice(s.as_bytes());
+        self.0.push(0);
+    }
+
+    /// Write `body` preceded by its DHEADER.
+    fn dheader(&mut self, body: impl FnOnce(&mut Self)) {
+        self.align(4);
+        let at = self.0.len();
+        self.0.extend_from_slice(&[0; 4]);
+        body(self);
+        let size = (self.0.len() - at - 4) as u32;
+        self.0[at..at + 4].copy_from_slice(&size.to_le_bytes());
+    }
+
+    /// Write a sequence of non-primitive elements.
+    fn sequence<T>(&mut self, items: &[T], mut write: impl FnMut(&mut Self, &T)) {
+        self.dheader(|w| {
+            w.u32(items.len() as u32);
+            for item in items {
+                write(w, item);
+            }
+        });
+    }
+
+    fn hash(&mut self, id: u8) {
+        self.u8(EK_COMPLETE);
+        self.0.extend_from_slice(&[id; 14]);
+    }
+
+    /// `CompleteTypeDetail` without annotations.
+    fn type_detail(&mut self, name: &str) {
+        self.u8(0);
+        self.u8(0);
+        self.string(name);
+    }
+
+    /// `CompleteMemberDetail`, with a `@unit` if given.
+    fn member_detail(&mut self, name: &str, unit: Option<&str>) {
+        self.string(name);
+        match unit {
+            Some(unit) => {
+                self.u8(1);
+                self.u8(1);
+                self.string(unit);
+                self.u8(0);
+                self.u8(0);
+                self.u8(0);
+            }
+            None => self.u8(0),
+        }
+        self.u8(0);
+    }
+}
+
+type TypeIdWriter = fn(&mut Xcdr);
+type TypeObjectWriter<'a> = &'a dyn Fn(&mut Xcdr);
+
+struct Member {
+    name: &'static str,
+    flags: u16,
+    ty: TypeIdWriter,
+    unit: Option<&'static str>,
+}
+
+fn member(name: &'static str, ty: TypeIdWriter) -> Member {
+    Member {
+        name,
+        flags: 0,
+        ty,
+        unit: None,
+    }
+}
+
+fn struct_object(w: &mut Xcdr, name: &str, base: Option<u8>, members: &[Member]) {
+    w.dheader(|w| {
+        w.u8(EK_COMPLETE);
+        w.u8(TK_STRUCTURE);
+        w.u16(1 << 1);
+        w.dheader(|w| {
+            match base {
+                Some(id) => w.hash(id),
+                None => w.u8(0),
+            }
+            w.type_detail(name);
+        });
+        w.sequence(members, |w, m| {
+            w.dheader(|w| {
+                w.u32(0);
+                w.u16(m.flags);
+                (m.ty)(w);
+                w.member_detail(m.name, m.unit);
+            });
+        });
+    });
+}
+
+fn enum_object(w: &mut Xcdr, name: &str, literals: &[(u32, &str)]) {
+    w.dheader(|w| {
+        w.u8(EK_COMPLETE);
+        w.u8(TK_ENUM);
+        w.u16(0);
+        w.dheader(|w| {
+            w.u16(32);
+            w.type_detail(name);
+        });
+        w.sequence(literals, |w, (value, name)| {
+            w.dheader(|w| {
+                w.u32(*value);
+                w.u16(0);
+                w.member_detail(name, None);
+            });
+        });
+    });
+}
+
+/// `TypeIdentifierTypeObjectPairSeq` of `(hash id, TypeObject writer)`.
+fn pairs(objects: &[(u8, TypeObjectWriter)]) -> Vec<u8> {
+    let mut w = Xcdr::new();
+    w.sequence(objects, |w, (id, object)| {
+        w.hash(*id);
+        object(w);
+    });
+    w.0
+}
+
+const STATUS: u8 = 1;
+const POINT: u8 = 2;
+const BASE: u8 = 3;
+const READING: u8 = 4;
+
+/// `demo::Reading : demo::Base` with nested structs, an enum, a bounded
+/// string, sequences and an array.
+fn reading_type_objects() -> Vec<u8> {
+    pairs(&[
+        (STATUS, &|w| {
+            enum_object(w, "demo::Status", &[(0, "OK"), (1, "WARN"), (4, "ERROR")])
+        }),
+        (POINT, &|w| {
+            struct_object(
+                w,
+                "demo::Point",
+                None,
+                &[
+                    member("x", |w| w.u8(TK_FLOAT32)),
+                    member("y", |w| w.u8(TK_FLOAT32)),
+                ],
+            )
+        }),
+        (BASE, &|w| {
+            let id = Member {
+                flags: IS_KEY,
+                ..member("id", |w| w.u8(TK_UINT32))
+            };
+            struct_object(w, "demo::Base", None, &[id])
+        }),
+        (READING, &|w| {
+            let value = Member {
+                unit: Some("m"),
+                ..member("value", |w| w.u8(TK_FLOAT64))
+            };
+            struct_object(
+                w,
+                "demo::Reading",
+                Some(BASE),
+                &[
+                    value,
+                    // string<8>
+                    member("label", |w| {
+                        w.u8(0x70);
+                        w.u8(8);
+                    }),
+                    // sequence<int16, 4>
+                    member("samples", |w| {
+                        w.u8(0x80);
+                        w.u8(0xF3);
+                        w.u16(0);
+                        w.u8(4);
+                        w.u8(TK_INT16);
+                    }),
+                    // demo::Point corners[2]
+                    member("corners", |w| {
+                        w.u8(0x90);
+                        w.u8(EK_COMPLETE);
+                        w.u16(0);
+                        w.u32(1);
+                        w.u8(2);
+                        w.hash(POINT);
+                    }),
+                    member("status", |w| w.hash(STATUS)),
+                ],
+            )
+        }),
+    ])
+}
+
+// ── tests ────────────────────────────────────────────────────────────────────
+
+#[test]
+fn type_objects_resolve_for_cdr_decoding() {
+    let schema = resolve_type_object("demo/Reading", &reading_type_objects()).unwrap();
+    assert_eq!(schema.root, ["demo", "Reading"]);
+
+    let root = &schema.structs[&schema.root];
+    let names: Vec<_> = root.fields.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(
+        names,
+        ["id", "value", "label", "samples", "corners", "status"]
+    );
+    assert!(root.fields[0].meta.key);
+    assert_eq!(root.fields[1].meta.unit.as_deref(), Some("m"));
+    assert_eq!(root.fields[4].fixed_len, Some(2));
+
+    let mut payload = vec![0x00, 0x01, 0x00, 0x00];
+    payload.extend_from_slice(&7u32.to_le_bytes());
+    payload.extend_from_slice(&[0; 4]);
+    payload.extend_from_slice(&1.5f64.to_le_bytes());
+    payload.extend_from_slice(&3u32.to_le_bytes());
+    payload.extend_from_slice(b"ab\0\0");
+    payload.extend_from_slice(&2u32.to_le_bytes());
+    payload.extend_from_slice(&(-1i16).to_le_bytes());
+    payload.extend_from_slice(&2i16.to_le_bytes());
+    for v in [1.0f32, 2.0, 3.0, 4.0] {
+        payload.extend_from_slice(&v.to_le_bytes());
+    }
+    payload.extend_from_slice(&4u32.to_le_bytes());
+
+    let point = |x, y| Value::Struct(vec![Value::F32(x), Value::F32(y)]);
+    assert_eq!(
+        decode_cdr_to_value(&schema, &payload).unwrap(),
+        Value::Struct(vec![
+            Value::U32(7),
+            Value::F64(1.5),
+            Value::string("ab"),
+            Value::List(vec![Value::I16(-1), Value::I16(2)]),
+            Value::Array(vec![point(1.0, 2.0), point(3.0, 4.0)]),
+            Value::string("ERROR"),
+        ])
+    );
+}
+
+#[test]
+fn type_objects_needing_xcdr2_payloads_are_unsupported() {
+    let optional = pairs(&[(1, &|w| {
+        let note = Member {
+            flags: IS_OPTIONAL,
+            ..member("note", |w| w.u8(TK_UINT32))
+        };
+        struct_object(w, "demo::Note", None, &[note])
+    })]);
+    let err = resolve_type_object("demo::Note", &optional).unwrap_err();
+    assert!(
+        matches!(&err, Ros2Error::UnsupportedFeature(msg) if msg.contains("demo::Note.note")),
+        "{err}"
+    );
+
+    let minimal = pairs(&[(1, &|w| {
+        w.dheader(|w| {
+            w.u8(EK_MINIMAL);
+            w.u8(TK_STRUCTURE);
+        })
+    })]);
+    let err = resolve_type_object("demo::Note", &minimal).unwrap_err();
+    assert!(matches!(err, Ros2Error::UnresolvedType(_)), "{err}");
+
+    let mut xcdr1 = reading_type_objects();
+    xcdr1[1] = 0x01;
+    let err = resolve_type_object("demo::Reading", &xcdr1).unwrap_err();
+    assert!(matches!(err, Ros2Error::UnsupportedFeature(_)), "{err}");
+}
@@ -0,0 +1,121 @@
+//! Parsing of IDL annotations such as `@unit (value="m/s")`.
+
+/// A parsed annotation: its name and `(key, value)` parameters.
+///
+/// Positional parameters have no key. String parameters are unescaped and
+/// adjacent string literals are concatenated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Annotation {
+    pub(crate) name: String,
+    pub(crate) params: Vec<(Option<String>, String)>,
+}
+
+impl Annotation {
+    /// Value of the parameter named `key`. A single positional parameter is
+    /// treated as `value`, the default member of IDL annotations.
+    pub(crate) fn param(&self, key: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(k, _)| k.as_deref() == Some(key))
+            .or_else(|| match self.params.as_slice() {
+                [(None, _)] if key == "value" => self.params.first(),
+                _ => None,
+            })
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// Parse the text of one annotation (starting with `@`).
+///
+/// Returns `None` if the text is not a well-formed annotation.
+pub(crate) fn parse_annotation(text: &str) -> Option<Annotation> {
+    let rest = text.trim().strip_prefix('@')?;
+    let name_len = rest
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == ':'))
+        .unwrap_or(rest.len());
+    let (name, rest) = rest.split_at(name_len);
+    if name.is_empty() {
+        return None;
+    }
+
+    let rest = rest.trim_start();
+    let mut params = Vec::new();
+    if let Some(body) = rest.strip_prefix('(') {
+        let mut chars = body.chars().peekable();
+        loop {
+            skip_ws(&mut chars);
+            match chars.peek() {
+                Some(')') => break,
+                Some(_) => params.push(parse_param(&mut chars)?),
+                None => return None,
+            }
+            skip_ws(&mut chars);
+            match chars.next() {
+                Some(',') => continue,
+                Some(')') => break,
+                _ => return None,
+            }
+        }
+    }
+
+    Some(Annotation {
+        name: name.to_string(),
+        params,
+    })
+}
+
+type Chars<'a> = std::iter::Peekable<std::str::Chars<'a>>;
+
+fn skip_ws(chars: &mut Chars<'_>) {
+    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+}
+
+/// Parse `[key =] value` where value is one or more string literals or a bare token.
+fn parse_param(chars: &mut Chars<'_>) -> Option<(Option<String>, String)> {
+    let first = parse_value(chars)?;
+    skip_ws(chars);
+    if chars.next_if_eq(&'=').is_none() {
+        return Some((None, first.text));
+    }
+    if first.quoted {
+        return None;
+    }
+    skip_ws(chars);
+    let value = parse_value(chars)?;
+    Some((Some(first.text), value.text))
+}
+
+struct ParsedValue {
+    text: String,
+    quoted: bool,
+}
+
+fn parse_value(chars: &mut Chars<'_>) -> Option<ParsedValue> {
+    if chars.peek() != Some(&'"') {
+        let mut text = String::new();
+        while let Some(c) = chars.next_if(|c| !matches!(c, ',' | ')' | '=') && !c.is_whitespace()) {
+            text.push(c);
+        }
+        return (!text.is_empty()).then_some(ParsedValue {
+            text,
+            quoted: false,
+        });
+    }
+
+    let mut text = String::new();
+    while chars.next_if_eq(&'"').is_some() {
+        loop {
+            match chars.next()? {
+                '"' => break,
+                '\\' => match chars.next()? {
+                    'n' => text.push('\n'),
+                    't' => text.push('\t'),
+                    other => text.push(other),
+                },
+                c => text.push(c),
+            }
+        }
+        skip_ws(chars);
+    }
+    Some(ParsedValue { text, quoted: true })
+}
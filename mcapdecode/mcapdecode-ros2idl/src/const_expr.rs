@@ -0,0 +1,348 @@
+//! Evaluation of IDL constant expressions (`const T NAME = <expr>;`).
+//!
+//! Supported: decimal, hexadecimal (`0x1F`) and octal (`017`) integers,
+//! floating-point, boolean (`TRUE`/`FALSE`) and string literals, references
+//! to previously evaluated constants, parentheses, the unary operators
+//! `- + ~` and the binary operators `* / % + - << >> & ^ |` with C precedence.
+
+use std::{borrow::Cow, collections::HashMap};
+
+use mcapdecode_ros2_common::{ConstValue, PrimitiveType, TypeExpr};
+
+/// Constants visible to an expression, keyed by name.
+pub(crate) type ConstScope = HashMap<String, ConstValue>;
+
+/// Make a constant declared at `full_name` visible by its name and by every
+/// qualified form of it (`Type_Constants::NAME`, `pkg::msg::Type_Constants::NAME`).
+pub(crate) fn insert_const(scope: &mut ConstScope, full_name: &[String], value: &ConstValue) {
+    for start in 0..full_name.len() {
+        scope.insert(full_name[start..].join("::"), value.clone());
+    }
+}
+
+/// Evaluate `expr` and convert the result to `ty`.
+///
+/// Returns `None` if the expression is malformed, references an unknown
+/// constant, divides by zero, or does not fit `ty`.
+pub(crate) fn evaluate_const(expr: &str, ty: &TypeExpr, scope: &ConstScope) -> Option<ConstValue> {
+    let tokens = tokenize(expr)?;
+    let mut parser = ExprParser {
+        tokens: &tokens,
+        pos: 0,
+        scope,
+    };
+    let value = parser.expr()?;
+    if parser.pos != tokens.len() {
+        return None;
+    }
+    convert_to_type(value, ty)
+}
+
+/// Replace names of integer constants inside `<...>` and `[...]` of a field
+/// declaration with their values, so `sequence<uint8, MAX_LEN>`,
+/// `string<MAX_LEN>` and `name[MAX_LEN]` get numeric bounds.
+pub(crate) fn substitute_const_bounds<'a>(decl: &'a str, scope: &ConstScope) -> Cow<'a, str> {
+    if scope.is_empty() {
+        return Cow::Borrowed(decl);
+    }
+    let mut out = String::with_capacity(decl.len());
+    let mut depth = 0usize;
+    let mut rest = decl;
+    while let Some(c) = rest.chars().next() {
+        if depth > 0 && (c.is_ascii_alphabetic() || c == '_') {
+            let len = identifier_len(rest);
+            let name = &rest[..len];
+            match scope.get(name).and_then(ConstValue::as_usize) {
+                Some(value) => out.push_str(&value.to_string()),
+                None => out.push_str(name),
+            }
+            rest = &rest[len..];
+            continue;
+        }
+        match c {
+            '<' | '[' => depth += 1,
+            '>' | ']' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        out.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+    Cow::Owned(out)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Value(ConstValue),
+    Ident(String),
+    Op(&'static str),
+    Open,
+    Close,
+}
+
+const OPERATORS: [&str; 11] = ["<<", ">>", "*", "/", "%", "+", "-", "&", "^", "|", "~"];
+
+fn tokenize(expr: &str) -> Option<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut rest = expr.trim();
+    while let Some(c) = rest.chars().next() {
+        if c.is_whitespace() {
+            rest = rest.trim_start();
+            continue;
+        }
+        if c == '(' || c == ')' {
+            tokens.push(if c == '(' { Token::Open } else { Token::Close });
+            rest = &rest[1..];
+            continue;
+        }
+        if let Some(op) = OPERATORS.iter().find(|op| rest.starts_with(**op)) {
+            tokens.push(Token::Op(op));
+            rest = &rest[op.len()..];
+            continue;
+        }
+        if c == '"' || (c == 'L' && rest[1..].starts_with('"')) {
+            let (value, remaining) = string_literal(rest.trim_start_matches('L'))?;
+            // Adjacent string literals are concatenated.
+            match tokens.last_mut() {
+                Some(Token::Value(ConstValue::String(prev))) => prev.push_str(&value),
+                _ => tokens.push(Token::Value(ConstValue::String(value))),
+            }
+            rest = remaining;
+            continue;
+        }
+        if c.is_ascii_digit() || c == '.' {
+            let len = number_len(rest);
+            tokens.push(Token::Value(number_literal(&rest[..len])?));
+            rest = &rest[len..];
+            continue;
+        }
+        if c.is_ascii_alphabetic() || c == '_' {
+            let len = identifier_len(rest);
+            let name = &rest[..len];
+            tokens.push(match name {
+                "TRUE" | "true" => Token::Value(ConstValue::Bool(true)),
+                "FALSE" | "false" => Token::Value(ConstValue::Bool(false)),
+                _ => Token::Ident(name.to_string()),
+            });
+            rest = &rest[len..];
+            continue;
+        }
+        return None;
+    }
+    Some(tokens)
+}
+
+/// Length of a possibly scoped identifier (`A::B::C`) at the start of `s`.
+fn identifier_len(s: &str) -> usize {
+    let mut len = 0;
+    loop {
+        len += s[len..]
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(s.len() - len);
+        let next = &s[len..];
+        if next.starts_with("::")
+            && next[2..].starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        {
+            len += 2;
+        } else {
+            return len;
+        }
+    }
+}
+
+/// Length of the numeric literal at the start of `s`, including an
+/// exponent sign (`1e-3`).
+fn number_len(s: &str) -> usize {
+    let is_hex = s.starts_with("0x") || s.starts_with("0X");
+    let mut prev = '\0';
+    s.find(|c: char| {
+        let exponent_sign = !is_hex && matches!(c, '+' | '-') && matches!(prev, 'e' | 'E');
+        prev = c;
+        !(c.is_ascii_alphanumeric() || c == '.' || exponent_sign)
+    })
+    .unwrap_or(s.len())
+}
+
+fn number_literal(text: &str) -> Option<ConstValue> {
+    if let Some(hex) = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        return i128::from_str_radix(hex, 16).ok().map(ConstValue::Int);
+    }
+    if text.contains(['.', 'e', 'E']) {
+        return text.parse().ok().map(ConstValue::Float);
+    }
+    if text.len() > 1 && text.starts_with('0') {
+        return i128::from_str_radix(&text[1..], 8)
+            .ok()
+            .map(ConstValue::Int);
+    }
+    text.parse().ok().map(ConstValue::Int)
+}
+
+/// Parse a `"..."` literal at the start of `s`; returns the unescaped text
+/// and the remaining input.
+fn string_literal(s: &str) -> Option<(String, &str)> {
+    let mut out = String::new();
+    let mut chars = s.char_indices().skip(1);
+    while let Some((idx, c)) = chars.next() {
+        match c {
+            '"' => return Some((out, &s[idx + 1..])),
+            '\\' => out.push(match chars.next()?.1 {
+                'n' => '\n',
+                't' => '\t',
+                'r' => '\r',
+                '0' => '\0',
+                other => other,
+            }),
+            c => out.push(c),
+        }
+    }
+    None
+}
+
+struct ExprParser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+    scope: &'a ConstScope,
+}
+
+/// Binary operators from the loosest to the tightest binding.
+const PRECEDENCE: [&[&str]; 6] = [
+    &["|"],
+    &["^"],
+    &["&"],
+    &["<<", ">>"],
+    &["+", "-"],
+    &["*", "/", "%"],
+];
+
+impl ExprParser<'_> {
+    fn expr(&mut self) -> Option<ConstValue> {
+        self.binary(0)
+    }
+
+    fn binary(&mut self, level: usize) -> Option<ConstValue> {
+        if level == PRECEDENCE.len() {
+            return self.unary();
+        }
+        let mut lhs = self.binary(level + 1)?;
+        while let Some(Token::Op(op)) = self.tokens.get(self.pos) {
+            if !PRECEDENCE[level].contains(op) {
+                break;
+            }
+            self.pos += 1;
+            let rhs = self.binary(level + 1)?;
+            lhs = apply_binary(op, lhs, rhs)?;
+        }
+        Some(lhs)
+    }
+
+    fn unary(&mut self) -> Option<ConstValue> {
+        match self.tokens.get(self.pos)? {
+            Token::Op(op @ ("-" | "+" | "~")) => {
+                self.pos += 1;
+                let value = self.unary()?;
+                match (*op, value) {
+                    ("-", ConstValue::Int(v)) => v.checked_neg().map(ConstValue::Int),
+                    ("-", ConstValue::Float(v)) => Some(ConstValue::Float(-v)),
+                    ("+", value @ (ConstValue::Int(_) | ConstValue::Float(_))) => Some(value),
+                    ("~", ConstValue::Int(v)) => Some(ConstValue::Int(!v)),
+                    _ => None,
+                }
+            }
+            Token::Open => {
+                self.pos += 1;
+                let value = self.expr()?;
+                (self.tokens.get(self.pos) == Some(&Token::Close)).then(|| {
+                    self.pos += 1;
+                    value
+                })
+            }
+            Token::Value(value) => {
+                self.pos += 1;
+                Some(value.clone())
+            }
+            Token::Ident(name) => {
+                self.pos += 1;
+                self.scope.get(name).cloned()
+            }
+            Token::Op(_) | Token::Close => None,
+        }
+    }
+}
+
+fn apply_binary(op: &str, lhs: ConstValue, rhs: ConstValue) -> Option<ConstValue> {
+    use ConstValue::{Float, Int};
+    match (lhs, rhs) {
+        (Int(a), Int(b)) => match op {
+            "|" => Some(a | b),
+            "^" => Some(a ^ b),
+            "&" => Some(a & b),
+            "<<" => u32::try_from(b).ok().and_then(|b| a.checked_shl(b)),
+            ">>" => u32::try_from(b).ok().and_then(|b| a.checked_shr(b)),
+            "+" => a.checked_add(b),
+            "-" => a.checked_sub(b),
+            "*" => a.checked_mul(b),
+            "/" => a.checked_div(b),
+            "%" => a.checked_rem(b),
+            _ => None,
+        }
+        .map(Int),
+        (a @ (Int(_) | Float(_)), b @ (Int(_) | Float(_))) => {
+            let (a, b) = (as_f64(&a), as_f64(&b));
+            match op {
+                "+" => Some(a + b),
+                "-" => Some(a - b),
+                "*" => Some(a * b),
+                "/" => Some(a / b),
+                _ => None,
+            }
+            .map(Float)
+        }
+        _ => None,
+    }
+}
+
+fn as_f64(value: &ConstValue) -> f64 {
+    match value {
+        ConstValue::Int(v) => *v as f64,
+        ConstValue::Float(v) => *v,
+        _ => f64::NAN,
+    }
+}
+
+fn convert_to_type(value: ConstValue, ty: &TypeExpr) -> Option<ConstValue> {
+    let range = match ty {
+        TypeExpr::Primitive(primitive) => match primitive {
+            PrimitiveType::I8 => Some((i8::MIN.into(), i8::MAX.into())),
+            PrimitiveType::I16 => Some((i16::MIN.into(), i16::MAX.into())),
+            PrimitiveType::I32 => Some((i32::MIN.into(), i32::MAX.into())),
+            PrimitiveType::I64 => Some((i64::MIN.into(), i64::MAX.into())),
+            PrimitiveType::U8 | PrimitiveType::Octet => Some((0, u8::MAX.into())),
+            PrimitiveType::U16 => Some((0, u16::MAX.into())),
+            PrimitiveType::U32 => Some((0, u32::MAX.into())),
+            PrimitiveType::U64 => Some((0, u64::MAX.into())),
+            PrimitiveType::Bool => return matches!(value, ConstValue::Bool(_)).then_some(value),
+            PrimitiveType::F32 | PrimitiveType::F64 => {
+                return match value {
+                    ConstValue::Int(v) => Some(ConstValue::Float(v as f64)),
+                    value @ ConstValue::Float(_) => Some(value),
+                    _ => None,
+                };
+            }
+            PrimitiveType::String | PrimitiveType::WString => {
+                return matches!(value, ConstValue::String(_)).then_some(value);
+            }
+        },
+        TypeExpr::BoundedString(_) | TypeExpr::BoundedWString(_) => {
+            return matches!(value, ConstValue::String(_)).then_some(value);
+        }
+        // Named types (enums, typedefs) are kept as evaluated.
+        TypeExpr::Scoped(_) | TypeExpr::Sequence { .. } => None,
+    };
+    match (value, range) {
+        (ConstValue::Int(v), Some((min, max))) if (min..=max).contains(&v) => {
+            Some(ConstValue::Int(v))
+        }
+        (value, None) => Some(value),
+        _ => None,
+    }
+}
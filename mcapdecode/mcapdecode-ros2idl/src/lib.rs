@@ -1,7 +1,8 @@
 //! ROS 2 IDL → CDR decoder for `mcapdecode`.
 //!
 //! Implements [`MessageDecoder`] for the
-//! `(schema_encoding = ros2idl, message_encoding = cdr)` key.
+//! `(schema_encoding = ros2idl, message_encoding = cdr)` key, and for the
+//! `cdr_le` and `xcdr` spellings of the message encoding.
 //!
 //! # Pipeline
 //!
@@ -12,26 +13,132 @@
 //!           └─ resolve_schema    – type-name resolution → ResolvedSchema
 //!               └─ decode_cdr_to_value  – CDR bytes → Value
 //! ```
+//!
+//! [`XTypesDecoder`] decodes the same CDR payloads for schemas recorded as
+//! DDS XTypes `TypeObject`s instead of IDL text.
 
+mod annotation;
+mod const_expr;
 mod lex;
 mod parser;
 mod resolver;
 mod schema_bundle;
+mod section_cache;
+mod xtypes;
+
+use std::{borrow::Cow, path::Path};
 
 use mcapdecode_core::{
     DecoderError, EncodingKey, MessageDecoder, MessageEncoding, SchemaEncoding, TopicDecoder,
 };
-use mcapdecode_ros2_common::{ResolvedSchema, Ros2CdrTopicDecoder};
+pub use mcapdecode_ros2_common::{
+    ByteArrays, CdrQuirks, InvalidUtf8, Ros2Error, UuidRepresentation,
+};
+use mcapdecode_ros2_common::{
+    CdrOptions, ResolvedSchema, Ros2CdrTopicDecoder, SchemaOverrides, SuffixMatching,
+    cdr_encoding_keys,
+};
 pub use parser::parse_idl_section;
-pub use resolver::resolve_schema;
+use resolver::{BundleOptions, resolve_bundle};
+pub use resolver::{resolve_schema, resolve_schema_with_root};
 pub use schema_bundle::{IdlSection, SchemaBundle};
+use section_cache::SectionCache;
+pub use xtypes::{XTYPES_SCHEMA_ENCODING, XTypesDecoder};
 
 /// [`MessageDecoder`] for ROS 2 IDL schemas with CDR-encoded messages.
-pub struct Ros2IdlDecoder;
+pub struct Ros2IdlDecoder {
+    schema_overrides: SchemaOverrides,
+    root_type: Option<String>,
+    suffix_matching: SuffixMatching,
+    lenient_sections: bool,
+    cdr_options: CdrOptions,
+    /// Sections parsed for earlier topics, shared across schemas.
+    section_cache: SectionCache,
+}
 
 impl Ros2IdlDecoder {
     pub fn new() -> Self {
-        Self
+        Self {
+            schema_overrides: SchemaOverrides::new(),
+            root_type: None,
+            suffix_matching: SuffixMatching::default(),
+            lenient_sections: false,
+            cdr_options: CdrOptions::default(),
+            section_cache: SectionCache::default(),
+        }
+    }
+
+    /// Use `schema_text` instead of the schema data embedded in the MCAP file
+    /// for channels whose schema name is `schema_name`.
+    ///
+    /// A plain `.idl` file without `IDL:` section headers is accepted and
+    /// treated as the section for `schema_name`.
+    pub fn with_schema_override(
+        mut self,
+        schema_name: impl Into<String>,
+        schema_text: impl Into<String>,
+    ) -> Self {
+        self.schema_overrides.insert(schema_name, schema_text);
+        self
+    }
+
+    /// Load schema overrides from every `*.idl` file below `dir`
+    /// (see [`SchemaOverrides::load_dir`]).
+    pub fn with_schema_override_dir(mut self, dir: impl AsRef<Path>) -> std::io::Result<Self> {
+        self.schema_overrides.load_dir(dir.as_ref(), "idl")?;
+        Ok(self)
+    }
+
+    /// Decode messages as `root_type` (e.g. `pkg::msg::Type`) for schemas
+    /// whose bundle defines it, instead of the section named after the schema
+    /// (see [`resolve_schema_with_root`]). Schemas that do not define it
+    /// report a warning.
+    pub fn with_root_type(mut self, root_type: impl Into<String>) -> Self {
+        self.root_type = Some(root_type.into());
+        self
+    }
+
+    /// Match type names that are not defined exactly according to
+    /// `suffix_matching` (default: [`SuffixMatching::Auto`]).
+    pub fn with_suffix_matching(mut self, suffix_matching: SuffixMatching) -> Self {
+        self.suffix_matching = suffix_matching;
+        self
+    }
+
+    /// Skip bundle sections that fail to parse, as long as the types
+    /// reachable from the root are defined by the others. Skipped sections
+    /// are reported as warnings of the topic.
+    pub fn with_lenient_sections(mut self, lenient: bool) -> Self {
+        self.lenient_sections = lenient;
+        self
+    }
+
+    /// Decode strings that are not valid UTF-8 as `invalid_utf8` says
+    /// (default: [`InvalidUtf8::Error`]).
+    pub fn with_invalid_utf8(mut self, invalid_utf8: InvalidUtf8) -> Self {
+        self.cdr_options.invalid_utf8 = invalid_utf8;
+        self
+    }
+
+    /// Decode payloads laid out as `quirks` says (default:
+    /// [`CdrQuirks::Rosidl`]).
+    pub fn with_cdr_quirks(mut self, quirks: CdrQuirks) -> Self {
+        self.cdr_options.quirks = quirks;
+        self
+    }
+
+    /// Decode fixed-size `uint8` / `octet` arrays as `byte_arrays` says
+    /// (default: [`ByteArrays::List`]).
+    pub fn with_byte_arrays(mut self, byte_arrays: ByteArrays) -> Self {
+        self.cdr_options.byte_arrays = byte_arrays;
+        self
+    }
+
+    /// Decode `unique_identifier_msgs/msg/UUID` values as `uuids` says
+    /// (default: [`UuidRepresentation::Struct`]).
+    pub fn with_uuids(mut self, uuids: UuidRepresentation) -> Self {
+        self.cdr_options.uuids = uuids;
+        self
     }
 }
 
@@ -46,13 +153,34 @@ impl MessageDecoder for Ros2IdlDecoder {
         EncodingKey::new(SchemaEncoding::Ros2Idl, MessageEncoding::Cdr)
     }
 
+    fn encoding_keys(&self) -> Vec<EncodingKey> {
+        cdr_encoding_keys(SchemaEncoding::Ros2Idl)
+    }
+
     fn build_topic_decoder(
         &self,
         schema_name: &str,
         schema_data: &[u8],
     ) -> Result<Box<dyn TopicDecoder>, DecoderError> {
-        let resolved = resolve_for_cdr(schema_name, schema_data)?;
-        Ok(Box::new(Ros2CdrTopicDecoder::new(resolved)))
+        let root_type = self.root_type.as_deref();
+        let schema_text = self
+            .schema_overrides
+            .get(schema_name)
+            .map(|text| with_idl_header(schema_name, text));
+        let schema_data = schema_text.as_deref().map_or(schema_data, str::as_bytes);
+        let resolved = resolve_with_options(
+            schema_name,
+            schema_data,
+            &BundleOptions {
+                root_type,
+                suffix_matching: self.suffix_matching,
+                lenient_sections: self.lenient_sections,
+                cache: Some(&self.section_cache),
+            },
+        )?;
+        Ok(Box::new(
+            Ros2CdrTopicDecoder::new(resolved).with_cdr_options(self.cdr_options),
+        ))
     }
 }
 
@@ -60,13 +188,32 @@ impl MessageDecoder for Ros2IdlDecoder {
 pub fn resolve_for_cdr(
     schema_name: &str,
     schema_data: &[u8],
+) -> Result<ResolvedSchema, DecoderError> {
+    resolve_with_options(schema_name, schema_data, &BundleOptions::default())
+}
+
+fn resolve_with_options(
+    schema_name: &str,
+    schema_data: &[u8],
+    options: &BundleOptions<'_>,
 ) -> Result<ResolvedSchema, DecoderError> {
     let schema_str = std::str::from_utf8(schema_data).map_err(|e| DecoderError::SchemaParse {
         schema_name: schema_name.to_string(),
         source: Box::new(e),
     })?;
-    resolve_schema(schema_name, schema_str).map_err(|e| DecoderError::SchemaParse {
+    resolve_bundle(schema_name, schema_str, options).map_err(|e| DecoderError::SchemaParse {
         schema_name: schema_name.to_string(),
-        source: e.into(),
+        source: e.into_source(),
     })
 }
+
+fn with_idl_header<'a>(schema_name: &str, schema_text: &'a str) -> Cow<'a, str> {
+    if schema_text
+        .lines()
+        .any(|line| line.trim_start().starts_with("IDL:"))
+    {
+        Cow::Borrowed(schema_text)
+    } else {
+        Cow::Owned(format!("IDL: {schema_name}\n{schema_text}"))
+    }
+}
@@ -12,10 +12,13 @@
 //! - Sequence types (bounded and unbounded)
 //! - Bounded strings and wide strings
 //! - Fixed-size arrays
-//! - Const declarations
-//! - Module scoping
+//! - Const declarations with typed values (hex/octal literals, arithmetic and
+//!   references to earlier constants); integer constants can be used as bounds
+//! - Module scoping, including module-level constants; those of a rosidl
+//!   `<Name>_Constants` module are attached to the struct `<Name>`
 //! - Scoped type names (using :: or / separators)
-//! - Annotations (ignored)
+//! - Annotations (`@unit` and `@verbatim` comments are kept as field metadata,
+//!   others are ignored)
 //! - Include directives (ignored)
 //!
 //! # Unsupported Features
@@ -27,8 +30,10 @@
 
 use std::collections::HashMap;
 
+use mcapdecode_core::SchemaSyntaxError;
 use mcapdecode_ros2_common::{
-    ConstDef, EnumDef, FieldDef, ParsedSection, PrimitiveType, Ros2Error, StructDef, TypeExpr,
+    ConstDef, EnumDef, FieldDef, FieldMeta, ParsedSection, PrimitiveType, Ros2Error, StructDef,
+    TypeExpr,
 };
 use nom::{
     IResult, Parser,
@@ -41,7 +46,11 @@ use nom::{
     sequence::{pair, preceded, terminated, tuple},
 };
 
-use crate::lex::strip_comments;
+use crate::{
+    annotation::{Annotation, parse_annotation},
+    const_expr::{ConstScope, evaluate_const, insert_const, substitute_const_bounds},
+    lex::strip_comments,
+};
 
 enum PendingDecl {
     Module(String),
@@ -63,22 +72,50 @@ enum LineStatement {
 }
 
 pub fn parse_idl_section(idl_body: &str) -> Result<ParsedSection, Ros2Error> {
+    parse_idl_section_with_consts(idl_body, &mut ConstScope::new())
+}
+
+/// Like [`parse_idl_section`], with the constants in `scope` (e.g. from
+/// other sections of the bundle) available to constant expressions and
+/// bounds. Module-level constants are added to `scope` as they are parsed,
+/// also when a later declaration fails.
+pub(crate) fn parse_idl_section_with_consts(
+    idl_body: &str,
+    scope: &mut ConstScope,
+) -> Result<ParsedSection, Ros2Error> {
     let mut structs: HashMap<Vec<String>, StructDef> = HashMap::new();
     let mut enums: HashMap<Vec<String>, EnumDef> = HashMap::new();
+    let mut module_consts: Vec<(Vec<String>, ConstDef)> = Vec::new();
     let mut modules: Vec<String> = Vec::new();
     let mut current_struct: Option<(String, Vec<FieldDef>, Vec<ConstDef>)> = None;
     let mut current_enum: Option<(String, Vec<String>)> = None;
     let mut pending_decl: Option<PendingDecl> = None;
+    // Field errors are reported once the section has been read, so that
+    // constants declared after the field still reach `scope`.
+    let mut field_error: Option<Ros2Error> = None;
+    // Constants visible in the current struct.
+    let mut struct_scope = ConstScope::new();
 
     let mut annotation_depth = 0i32;
     let mut ann_in_str = false;
     let mut ann_escaped = false;
+    let mut annotation_text = String::new();
+    let mut pending_annotations: Vec<Annotation> = Vec::new();
     let mut in_block_comment = false;
 
     for (idx, raw) in idl_body.lines().enumerate() {
         let line_no = idx + 1;
         let line = strip_comments(raw, &mut in_block_comment);
-        let line = line.trim();
+        let mut line = line.trim();
+
+        // Annotations that close on their line may precede a declaration on
+        // the same line, as in `@key long id;`.
+        while annotation_depth == 0
+            && let Some(len) = single_line_annotation_len(line)
+        {
+            pending_annotations.extend(parse_annotation(&line[..len]));
+            line = line[len..].trim_start();
+        }
         if line.is_empty() {
             continue;
         }
@@ -88,33 +125,46 @@ pub fn parse_idl_section(idl_body: &str) -> Result<ParsedSection, Ros2Error> {
                 paren_counts_outside_strings(line, &mut ann_in_str, &mut ann_escaped);
             annotation_depth += open as i32;
             annotation_depth -= close as i32;
+            annotation_text.push_str(line);
+            annotation_text.push('\n');
+            if annotation_depth <= 0 {
+                pending_annotations.extend(parse_annotation(&annotation_text));
+                annotation_text.clear();
+            }
             continue;
         }
 
+        // Annotations apply to the declaration that follows them.
+        let annotations = std::mem::take(&mut pending_annotations);
+
         if let Some(pending) = pending_decl.take() {
             if line != "{" {
-                return Err(
-                    format!("expected '{{' after declaration at line {line_no}: {line}").into(),
-                );
+                return Err(syntax_error(
+                    raw,
+                    line_no,
+                    None,
+                    "expected '{' after declaration",
+                ));
             }
             match pending {
                 PendingDecl::Module(name) => modules.push(name),
                 PendingDecl::Struct(name) => {
-                    if current_struct.is_some() || current_enum.is_some() {
-                        return Err(format!(
-                            "nested declaration unsupported at line {line_no}: {line}"
-                        )
-                        .into());
-                    }
+                    ensure_no_nested_declaration(
+                        current_struct.is_some(),
+                        current_enum.is_some(),
+                        line_no,
+                        raw,
+                    )?;
                     current_struct = Some((name, Vec::new(), Vec::new()));
+                    struct_scope = scope.clone();
                 }
                 PendingDecl::Enum(name) => {
-                    if current_struct.is_some() || current_enum.is_some() {
-                        return Err(format!(
-                            "nested declaration unsupported at line {line_no}: {line}"
-                        )
-                        .into());
-                    }
+                    ensure_no_nested_declaration(
+                        current_struct.is_some(),
+                        current_enum.is_some(),
+                        line_no,
+                        raw,
+                    )?;
                     current_enum = Some((name, Vec::new()));
                 }
             }
@@ -125,9 +175,7 @@ pub fn parse_idl_section(idl_body: &str) -> Result<ParsedSection, Ros2Error> {
             match statement {
                 LineStatement::Include => continue,
                 LineStatement::Unsupported => {
-                    return Err(
-                        format!("unsupported IDL declaration at line {line_no}: {line}").into(),
-                    );
+                    return Err(unsupported(raw, line_no, "unsupported IDL declaration"));
                 }
                 LineStatement::ModuleOpens(names) => {
                     modules.extend(names);
@@ -142,9 +190,10 @@ pub fn parse_idl_section(idl_body: &str) -> Result<ParsedSection, Ros2Error> {
                         current_struct.is_some(),
                         current_enum.is_some(),
                         line_no,
-                        line,
+                        raw,
                     )?;
                     current_struct = Some((name, Vec::new(), Vec::new()));
+                    struct_scope = scope.clone();
                     continue;
                 }
                 LineStatement::StructHead(name) => {
@@ -152,7 +201,7 @@ pub fn parse_idl_section(idl_body: &str) -> Result<ParsedSection, Ros2Error> {
                         current_struct.is_some(),
                         current_enum.is_some(),
                         line_no,
-                        line,
+                        raw,
                     )?;
                     pending_decl = Some(PendingDecl::Struct(name));
                     continue;
@@ -162,7 +211,7 @@ pub fn parse_idl_section(idl_body: &str) -> Result<ParsedSection, Ros2Error> {
                         current_struct.is_some(),
                         current_enum.is_some(),
                         line_no,
-                        line,
+                        raw,
                     )?;
                     current_enum = Some((name, Vec::new()));
                     continue;
@@ -172,7 +221,7 @@ pub fn parse_idl_section(idl_body: &str) -> Result<ParsedSection, Ros2Error> {
                         current_struct.is_some(),
                         current_enum.is_some(),
                         line_no,
-                        line,
+                        raw,
                     )?;
                     pending_decl = Some(PendingDecl::Enum(name));
                     continue;
@@ -201,7 +250,12 @@ pub fn parse_idl_section(idl_body: &str) -> Result<ParsedSection, Ros2Error> {
                                 },
                             );
                         } else if modules.pop().is_none() {
-                            return Err(format!("unmatched closing brace at line {line_no}").into());
+                            return Err(syntax_error(
+                                raw,
+                                line_no,
+                                Some("}"),
+                                "unmatched closing brace",
+                            ));
                         }
                     }
                     continue;
@@ -211,22 +265,27 @@ pub fn parse_idl_section(idl_body: &str) -> Result<ParsedSection, Ros2Error> {
 
         if let Some((_, fields, consts)) = current_struct.as_mut() {
             if line.starts_with("const ") {
-                consts.push(
-                    parse_const(line)
-                        .map_err(|e| Ros2Error(format!("parse error at line {line_no}: {e}")))?,
-                );
+                let def = parse_const(line, &struct_scope).map_err(|e| e.locate(raw, line_no))?;
+                if let Some(value) = &def.typed_value {
+                    struct_scope.insert(def.name.clone(), value.clone());
+                }
+                consts.push(def);
             } else {
-                fields.push(
-                    parse_field(line)
-                        .map_err(|e| Ros2Error(format!("parse error at line {line_no}: {e}")))?,
-                );
+                match parse_field(&substitute_const_bounds(line, &struct_scope)) {
+                    Ok(mut field) => {
+                        field.meta = field_meta(&annotations);
+                        fields.push(field);
+                    }
+                    Err(e) => {
+                        field_error.get_or_insert_with(|| e.locate(raw, line_no));
+                    }
+                }
             }
             continue;
         }
 
         if let Some((_, variants)) = current_enum.as_mut() {
-            let name = parse_enum_variant(line)
-                .map_err(|e| Ros2Error(format!("parse error at line {line_no}: {e}")))?;
+            let name = parse_enum_variant(line).map_err(|e| e.locate(raw, line_no))?;
             if !name.is_empty() {
                 variants.push(name);
             }
@@ -234,38 +293,180 @@ pub fn parse_idl_section(idl_body: &str) -> Result<ParsedSection, Ros2Error> {
         }
 
         if line.starts_with("const ") {
-            parse_const(line)
-                .map_err(|e| Ros2Error(format!("parse error at line {line_no}: {e}")))?;
+            let def = parse_const(line, scope).map_err(|e| e.locate(raw, line_no))?;
+            let mut full = modules.clone();
+            full.push(def.name.clone());
+            if let Some(value) = &def.typed_value {
+                insert_const(scope, &full, value);
+            }
+            module_consts.push((full, def));
             continue;
         }
 
-        return Err(format!("unexpected top-level statement at line {line_no}: {line}").into());
+        return Err(syntax_error(
+            raw,
+            line_no,
+            None,
+            "unexpected top-level statement",
+        ));
     }
 
+    if let Some(e) = field_error {
+        return Err(e);
+    }
     if current_struct.is_some() {
-        return Err("unclosed struct declaration".into());
+        return Err(Ros2Error::ParseError(
+            "unclosed struct declaration".to_string(),
+        ));
     }
     if current_enum.is_some() {
-        return Err("unclosed enum declaration".into());
+        return Err(Ros2Error::ParseError(
+            "unclosed enum declaration".to_string(),
+        ));
     }
     if pending_decl.is_some() {
-        return Err("declaration missing opening brace".into());
+        return Err(Ros2Error::ParseError(
+            "declaration missing opening brace".to_string(),
+        ));
+    }
+    attach_constants_modules(&mut structs, &module_consts);
+    Ok(ParsedSection {
+        structs,
+        enums,
+        consts: module_consts.into_iter().collect(),
+    })
+}
+
+/// Add the constants of each `module <Name>_Constants` block, as emitted by
+/// rosidl, to the sibling struct `<Name>`.
+fn attach_constants_modules(
+    structs: &mut HashMap<Vec<String>, StructDef>,
+    module_consts: &[(Vec<String>, ConstDef)],
+) {
+    for (full_name, def) in module_consts {
+        let [parent @ .., module, _] = full_name.as_slice() else {
+            continue;
+        };
+        let Some(name) = module.strip_suffix("_Constants") else {
+            continue;
+        };
+        let mut struct_name = parent.to_vec();
+        struct_name.push(name.to_string());
+        if let Some(st) = structs.get_mut(&struct_name) {
+            st.consts.push(def.clone());
+        }
+    }
+}
+
+/// Field metadata carried by the annotations preceding a field.
+fn field_meta(annotations: &[Annotation]) -> FieldMeta {
+    FieldMeta {
+        unit: annotations
+            .iter()
+            .find(|a| a.name == "unit")
+            .and_then(|a| a.param("value"))
+            .map(str::to_string),
+        doc: verbatim_doc(annotations),
+        key: annotations
+            .iter()
+            .find(|a| a.name == "key")
+            .is_some_and(|a| {
+                !a.param("value")
+                    .is_some_and(|v| v.eq_ignore_ascii_case("false"))
+            }),
     }
-    Ok(ParsedSection { structs, enums })
+}
+
+/// Documentation text of the `@verbatim(language="comment", text=...)`
+/// annotations preceding a field, joined by newlines.
+fn verbatim_doc(annotations: &[Annotation]) -> Option<String> {
+    let texts: Vec<&str> = annotations
+        .iter()
+        .filter(|a| a.name == "verbatim" && a.param("language") == Some("comment"))
+        .filter_map(|a| a.param("text"))
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+        .collect();
+    (!texts.is_empty()).then(|| texts.join("\n"))
 }
 
 fn ensure_no_nested_declaration(
     has_current_struct: bool,
     has_current_enum: bool,
     line_no: usize,
-    line: &str,
+    raw: &str,
 ) -> Result<(), Ros2Error> {
     if has_current_struct || has_current_enum {
-        return Err(format!("nested declaration unsupported at line {line_no}: {line}").into());
+        return Err(unsupported(raw, line_no, "nested declaration unsupported"));
     }
     Ok(())
 }
 
+/// Error in a single declaration.
+enum DeclError {
+    /// Malformed declaration, detected at the text `at` when known.
+    Syntax { message: String, at: Option<String> },
+    /// Well-formed declaration using an unsupported IDL feature.
+    Unsupported(String),
+}
+
+impl DeclError {
+    fn at(message: impl Into<String>, at: &str) -> Self {
+        Self::Syntax {
+            message: message.into(),
+            at: Some(at.to_string()),
+        }
+    }
+
+    /// Locate the error on line `line_no` of the section, whose text is `raw`.
+    fn locate(self, raw: &str, line_no: usize) -> Ros2Error {
+        match self {
+            Self::Syntax { message, at } => syntax_error(raw, line_no, at.as_deref(), message),
+            Self::Unsupported(message) => unsupported(raw, line_no, &message),
+        }
+    }
+}
+
+impl From<&str> for DeclError {
+    fn from(message: &str) -> Self {
+        Self::Syntax {
+            message: message.to_string(),
+            at: None,
+        }
+    }
+}
+
+/// [`Ros2Error::UnsupportedFeature`] for line `line_no` whose text is `raw`.
+fn unsupported(raw: &str, line_no: usize, message: &str) -> Ros2Error {
+    Ros2Error::UnsupportedFeature(format!("{message} at line {line_no}: {}", raw.trim()))
+}
+
+/// Text a nom parser failed at, if it reported one.
+fn nom_error_input<'a>(err: &nom::Err<Error<&'a str>>) -> Option<&'a str> {
+    match err {
+        nom::Err::Error(e) | nom::Err::Failure(e) => Some(e.input),
+        nom::Err::Incomplete(_) => None,
+    }
+}
+
+/// Syntax error on line `line_no` whose text is `raw`. The column points at
+/// the first occurrence of `at` in the line, or at its first non-blank
+/// character.
+fn syntax_error(
+    raw: &str,
+    line_no: usize,
+    at: Option<&str>,
+    message: impl Into<String>,
+) -> Ros2Error {
+    let byte = at
+        .map(str::trim)
+        .filter(|at| !at.is_empty())
+        .and_then(|at| raw.find(at))
+        .unwrap_or_else(|| raw.len() - raw.trim_start().len());
+    let column = raw[..byte].chars().count() + 1;
+    SchemaSyntaxError::new(line_no, column, raw.trim_end(), message).into()
+}
+
 fn parse_line_statement(line: &str) -> Option<LineStatement> {
     parse_complete(line_statement, line)
 }
@@ -377,41 +578,57 @@ fn enum_decl_head(input: &str) -> IResult<&str, &str> {
     )(input)
 }
 
-fn parse_const(line: &str) -> Result<ConstDef, Ros2Error> {
+/// Parse a const declaration, evaluating its value against `scope`.
+fn parse_const(line: &str, scope: &ConstScope) -> Result<ConstDef, DeclError> {
     let body = line
         .strip_prefix("const ")
-        .ok_or_else(|| Ros2Error("const declaration must start with `const`".to_string()))?;
+        .ok_or("const declaration must start with `const`")?;
     let body = body
         .strip_suffix(';')
-        .ok_or_else(|| Ros2Error("const declaration must end with ';'".to_string()))?;
+        .ok_or("const declaration must end with ';'")?;
     if has_long_double_tokens(body) {
-        return Err("unsupported IDL type `long double`".into());
+        return Err(DeclError::Unsupported(
+            "unsupported IDL type `long double`".to_string(),
+        ));
     }
 
     match const_decl(body.trim()) {
-        Ok((remaining, def)) if remaining.trim().is_empty() => Ok(def),
-        Ok((remaining, _)) => {
-            Err(format!("Unexpected trailing characters in const: {remaining}").into())
-        }
-        Err(e) => Err(format!("Failed to parse const declaration: {e}").into()),
+        Ok((remaining, def)) if remaining.trim().is_empty() => Ok(ConstDef {
+            typed_value: evaluate_const(&def.value, &def.ty, scope),
+            ..def
+        }),
+        Ok((remaining, _)) => Err(DeclError::at(
+            format!("Unexpected trailing characters in const: {remaining}"),
+            remaining,
+        )),
+        Err(e) => Err(DeclError::Syntax {
+            message: format!("Failed to parse const declaration: {e}"),
+            at: nom_error_input(&e).map(str::to_string),
+        }),
     }
 }
 
-fn parse_field(line: &str) -> Result<FieldDef, Ros2Error> {
+fn parse_field(line: &str) -> Result<FieldDef, DeclError> {
     let body = line
         .strip_suffix(';')
-        .ok_or_else(|| "field declaration must end with ';'".to_string())?
+        .ok_or("field declaration must end with ';'")?
         .trim();
     if has_long_double_tokens(body) {
-        return Err("unsupported IDL type `long double`".into());
+        return Err(DeclError::Unsupported(
+            "unsupported IDL type `long double`".to_string(),
+        ));
     }
 
     match field_decl(body) {
         Ok((remaining, def)) if remaining.trim().is_empty() => Ok(def),
-        Ok((remaining, _)) => {
-            Err(format!("Unexpected trailing characters in field: {remaining}").into())
-        }
-        Err(e) => Err(format!("Failed to parse field declaration: {e}").into()),
+        Ok((remaining, _)) => Err(DeclError::at(
+            format!("Unexpected trailing characters in field: {remaining}"),
+            remaining,
+        )),
+        Err(e) => Err(DeclError::Syntax {
+            message: format!("Failed to parse field declaration: {e}"),
+            at: nom_error_input(&e).map(str::to_string),
+        }),
     }
 }
 
@@ -514,9 +731,8 @@ fn number(input: &str) -> IResult<&str, usize> {
 fn sequence_bound(input: &str) -> IResult<&str, Option<usize>> {
     alt((
         map(number, Some),
-        // Some schemas use named constants like `kObjectsCapacity`.
-        // Reader-side schema derivation accepts those declarations but
-        // leaves enforcement to the writer / producer side for now.
+        // Named constants known to the bundle were substituted before
+        // parsing; unknown names leave the sequence unbounded.
         value(None, scoped_name),
     ))(input)
 }
@@ -587,6 +803,7 @@ fn field_decl(input: &str) -> IResult<&str, FieldDef> {
             name: name.to_string(),
             ty,
             fixed_len,
+            meta: FieldMeta::default(),
         },
     )(input)
 }
@@ -612,6 +829,7 @@ fn const_decl(input: &str) -> IResult<&str, ConstDef> {
             ty,
             name: name.to_string(),
             value: value.to_string(),
+            typed_value: None,
         },
     )(input)
 }
@@ -632,12 +850,48 @@ fn enum_variant(input: &str) -> IResult<&str, Option<&str>> {
     ))(trimmed)
 }
 
-fn parse_enum_variant(line: &str) -> std::result::Result<String, Ros2Error> {
+fn parse_enum_variant(line: &str) -> std::result::Result<String, DeclError> {
     match enum_variant(line) {
         Ok((_, Some(name))) => Ok(name.to_string()),
         Ok((_, None)) => Ok(String::new()),
-        Err(e) => Err(format!("Failed to parse enum variant '{line}': {e}").into()),
+        Err(e) => Err(DeclError::Syntax {
+            message: format!("Failed to parse enum variant '{line}': {e}"),
+            at: nom_error_input(&e).map(str::to_string),
+        }),
+    }
+}
+
+/// Length of the annotation at the start of `line` (`@name` with an optional
+/// parenthesized parameter list), if it is complete within the line.
+fn single_line_annotation_len(line: &str) -> Option<usize> {
+    let rest = line.strip_prefix('@')?;
+    let name_len = rest
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == ':'))
+        .unwrap_or(rest.len());
+    // `@annotation` declarations are not applied annotations.
+    if name_len == 0 || &rest[..name_len] == "annotation" {
+        return None;
+    }
+    let after_name = 1 + name_len;
+    let params = line[after_name..].trim_start();
+    if !params.starts_with('(') {
+        return Some(after_name);
+    }
+    let params_start = line.len() - params.len();
+    let (mut in_str, mut escaped) = (false, false);
+    let mut depth = 0usize;
+    for (idx, ch) in params.char_indices() {
+        let (open, close) =
+            paren_counts_outside_strings(ch.encode_utf8(&mut [0; 4]), &mut in_str, &mut escaped);
+        depth += open;
+        if close > 0 {
+            depth -= close;
+            if depth == 0 {
+                return Some(params_start + idx + 1);
+            }
+        }
     }
+    None
 }
 
 fn paren_counts_outside_strings(s: &str, in_str: &mut bool, escaped: &mut bool) -> (usize, usize) {
@@ -1,39 +1,174 @@
 //! Ties together schema-bundle parsing, IDL parsing, and type resolution.
 
-use mcapdecode_ros2_common::{ParsedSection, ResolvedSchema, Ros2Error, resolve_parsed_section};
+use mcapdecode_core::{DecodeWarning, warn};
+use mcapdecode_ros2_common::{
+    ParsedSection, ResolvedSchema, Ros2Error, SuffixMatching, resolve_parsed_section_with,
+};
 
-use crate::{parser::parse_idl_section, schema_bundle::SchemaBundle};
+use crate::{
+    const_expr::ConstScope, lex::split_qual, parser::parse_idl_section_with_consts,
+    schema_bundle::SchemaBundle, section_cache::SectionCache,
+};
 
 /// Parse a multi-section IDL schema text and produce a fully resolved [`ResolvedSchema`].
 ///
 /// Steps:
 /// 1. Split `schema_text` into [`SchemaBundle`] sections at `====` separator lines.
-/// 2. Parse each section with [`parse_idl_section`] and merge results.
+/// 2. Parse each section with [`parse_idl_section`](crate::parse_idl_section),
+///    resolving constants across sections, and merge results.
 /// 3. Identify the root type from `schema_name`.
 /// 4. Resolve all type references.
 pub fn resolve_schema(schema_name: &str, schema_text: &str) -> Result<ResolvedSchema, Ros2Error> {
+    resolve_schema_with_root(schema_name, schema_text, None)
+}
+
+/// Like [`resolve_schema`], but use `root_type` (e.g. `pkg::msg::Type` or
+/// `pkg/msg/Type`) as the root struct if the bundle defines it.
+///
+/// Without a usable `root_type`, the root is the section named `schema_name`.
+/// If a multi-section bundle has no such section, the first section is used.
+/// Both a `root_type` the bundle does not define and this fallback are
+/// reported as a [`DecodeWarning`] (see [`mcapdecode_core::collect_warnings`]).
+pub fn resolve_schema_with_root(
+    schema_name: &str,
+    schema_text: &str,
+    root_type: Option<&str>,
+) -> Result<ResolvedSchema, Ros2Error> {
+    resolve_bundle(
+        schema_name,
+        schema_text,
+        &BundleOptions {
+            root_type,
+            ..BundleOptions::default()
+        },
+    )
+}
+
+/// Settings for [`resolve_bundle`] beyond the schema itself.
+#[derive(Default, Clone, Copy)]
+pub(crate) struct BundleOptions<'a> {
+    /// Preferred root struct, see [`resolve_schema_with_root`].
+    pub(crate) root_type: Option<&'a str>,
+    pub(crate) suffix_matching: SuffixMatching,
+    /// Skip sections that fail to parse instead of failing the bundle.
+    pub(crate) lenient_sections: bool,
+    /// Sections already parsed for other schemas.
+    pub(crate) cache: Option<&'a SectionCache>,
+}
+
+/// Like [`resolve_schema_with_root`], configured by `options`.
+///
+/// In lenient mode, sections that fail to parse are left out; the schema
+/// still resolves if the types reachable from the root are all defined by
+/// the remaining sections, and each skipped section is reported as a
+/// [`DecodeWarning`]. Otherwise the parse error of the root's own section
+/// or the resolution error is returned.
+pub(crate) fn resolve_bundle(
+    schema_name: &str,
+    schema_text: &str,
+    options: &BundleOptions<'_>,
+) -> Result<ResolvedSchema, Ros2Error> {
     let bundle = SchemaBundle::parse(schema_name, schema_text)?;
 
     let mut merged = ParsedSection::default();
-    for section in &bundle.sections {
-        let parsed = parse_idl_section(&section.body).map_err(|e| {
-            Ros2Error(format!(
-                "while parsing IDL section '{}': {e}",
-                section.idl_path.join("/")
-            ))
-        })?;
-        for (k, v) in parsed.structs {
-            merged.structs.insert(k, v);
+    let mut skipped = Vec::new();
+    for (section, parsed) in bundle
+        .sections
+        .iter()
+        .zip(parse_sections(&bundle, options.cache))
+    {
+        let parsed = match parsed {
+            Ok(parsed) => parsed,
+            Err(e) if options.lenient_sections => {
+                skipped.push((&section.idl_path, e));
+                continue;
+            }
+            Err(e) => return Err(e.in_section("IDL", &section.idl_path.join("/"))),
+        };
+        merged.structs.extend(parsed.structs);
+        merged.enums.extend(parsed.enums);
+        merged.consts.extend(parsed.consts);
+    }
+
+    let root_override = options.root_type.and_then(|root_type| {
+        let root = split_root_type(root_type);
+        if merged.structs.contains_key(&root) {
+            return Some(root);
+        }
+        warn(DecodeWarning::new(
+            schema_name,
+            format!("root type '{root_type}' is not defined by the schema; ignoring it"),
+        ));
+        None
+    });
+    let root = match root_override.or_else(|| bundle.find_type(schema_name)) {
+        Some(root) => root,
+        None => {
+            let root = bundle.main_type(schema_name).ok_or_else(|| {
+                Ros2Error::UnresolvedType(format!(
+                    "unable to determine root type for schema '{schema_name}'"
+                ))
+            })?;
+            if bundle.sections.len() > 1 {
+                warn(DecodeWarning::new(
+                    schema_name,
+                    format!(
+                        "no IDL section matches the schema name; using '{}' as the root type",
+                        root.join("::")
+                    ),
+                ));
+            }
+            root
         }
-        for (k, v) in parsed.enums {
-            merged.enums.insert(k, v);
+    };
+
+    match resolve_parsed_section_with(merged, root.clone(), options.suffix_matching) {
+        Ok(resolved) => {
+            for (path, e) in skipped {
+                warn(DecodeWarning::new(
+                    path.join("/"),
+                    format!("skipped unparsable IDL section: {e}"),
+                ));
+            }
+            Ok(resolved)
         }
+        Err(e) => Err(skipped
+            .into_iter()
+            .find(|(path, _)| **path == root)
+            .map_or(e, |(path, section_err)| {
+                section_err.in_section("IDL", &path.join("/"))
+            })),
     }
-    let root = bundle.main_type(schema_name).ok_or_else(|| {
-        Ros2Error(format!(
-            "unable to determine root type for schema '{schema_name}'"
-        ))
-    })?;
+}
 
-    resolve_parsed_section(merged, root)
+/// Parse every section with the module-level constants of the whole bundle
+/// in scope, so bounds may name constants declared later or in another
+/// section. Sections are re-parsed until no new constants are found.
+fn parse_sections(
+    bundle: &SchemaBundle,
+    cache: Option<&SectionCache>,
+) -> Vec<Result<ParsedSection, Ros2Error>> {
+    let mut scope = ConstScope::new();
+    loop {
+        let known = scope.len();
+        let results: Vec<_> = bundle
+            .sections
+            .iter()
+            .map(|section| match cache {
+                Some(cache) => cache.parse(&section.body, &mut scope),
+                None => parse_idl_section_with_consts(&section.body, &mut scope),
+            })
+            .collect();
+        if scope.len() == known {
+            return results;
+        }
+    }
+}
+
+fn split_root_type(root_type: &str) -> Vec<String> {
+    if root_type.contains("::") {
+        split_qual(root_type, "::")
+    } else {
+        split_qual(root_type, "/")
+    }
 }
@@ -26,7 +26,8 @@ use crate::lex::{is_separator_line, split_qual};
 pub struct IdlSection {
     /// Path components from the `IDL: pkg/msg/Type` header line.
     pub idl_path: Vec<String>,
-    /// The raw IDL body text (everything after the header until the next separator).
+    /// The raw IDL body text (everything after the header until the next
+    /// separator). Line numbers in parse errors count from its first line.
     pub body: String,
 }
 
@@ -61,7 +62,9 @@ impl SchemaBundle {
         }
 
         if sections.is_empty() {
-            return Err(format!("no IDL sections found for schema '{schema_name}'").into());
+            return Err(Ros2Error::ParseError(format!(
+                "no IDL sections found for schema '{schema_name}'"
+            )));
         }
 
         Ok(Self { sections })
@@ -73,15 +76,21 @@ impl SchemaBundle {
     /// `schema_name`.  Falls back to the first section if no exact match is found,
     /// which handles the common case of a single-section bundle.
     pub fn main_type(&self, schema_name: &str) -> Option<Vec<String>> {
+        self.find_type(schema_name)
+            .or_else(|| self.sections.first().map(|s| s.idl_path.clone()))
+    }
+
+    /// Return the qualified name of the section that matches `schema_name`
+    /// exactly, without falling back to the first section.
+    pub fn find_type(&self, schema_name: &str) -> Option<Vec<String>> {
         let schema_key = split_qual(schema_name, "/");
-        if !schema_key.is_empty() {
-            for s in &self.sections {
-                if s.idl_path == schema_key {
-                    return Some(s.idl_path.clone());
-                }
-            }
+        if schema_key.is_empty() {
+            return None;
         }
-        self.sections.first().map(|s| s.idl_path.clone())
+        self.sections
+            .iter()
+            .find(|s| s.idl_path == schema_key)
+            .map(|s| s.idl_path.clone())
     }
 }
 
@@ -93,21 +102,23 @@ fn has_meaningful_lines(lines: &[String]) -> bool {
 ///
 /// The first non-empty line must be an `IDL: <path>` header.
 fn parse_section(lines: &[String]) -> Result<IdlSection, Ros2Error> {
-    let mut it = lines.iter().map(|s| s.trim()).filter(|s| !s.is_empty());
-    let header = it
-        .next()
-        .ok_or_else(|| Ros2Error("empty IDL section".to_string()))?;
+    let header_idx = lines
+        .iter()
+        .position(|line| !line.trim().is_empty())
+        .ok_or_else(|| Ros2Error::ParseError("empty IDL section".to_string()))?;
+    let header = lines[header_idx].trim();
     let path = header
         .strip_prefix("IDL:")
-        .ok_or_else(|| Ros2Error(format!("missing `IDL:` header: {header}")))?
+        .ok_or_else(|| Ros2Error::ParseError(format!("missing `IDL:` header: {header}")))?
         .trim();
     if path.is_empty() {
-        return Err("empty IDL path in section header".into());
+        return Err(Ros2Error::ParseError(
+            "empty IDL path in section header".to_string(),
+        ));
     }
 
-    let body = it.collect::<Vec<_>>().join("\n");
     Ok(IdlSection {
         idl_path: split_qual(path, "/"),
-        body,
+        body: lines[header_idx + 1..].join("\n"),
     })
 }
@@ -0,0 +1,76 @@
+//! Cache of parsed IDL sections shared by the topics of one decoder.
+//!
+//! ROS 2 schema bundles repeat the sections of common dependencies
+//! (`std_msgs/Header`, `builtin_interfaces/Time`, geometry types, ...) for
+//! every topic. The cache lets each distinct section be parsed once.
+
+use std::{
+    collections::HashMap,
+    hash::{DefaultHasher, Hash, Hasher},
+    sync::{Mutex, MutexGuard, PoisonError},
+};
+
+use mcapdecode_ros2_common::{ConstValue, ParsedSection, Ros2Error};
+
+use crate::{const_expr::ConstScope, parser::parse_idl_section_with_consts};
+
+/// Outcome of parsing one section, with the constants it added to the scope.
+#[derive(Clone)]
+struct CachedSection {
+    result: Result<ParsedSection, Ros2Error>,
+    scope_updates: Vec<(String, ConstValue)>,
+}
+
+/// Parsed sections keyed by a hash of the section body and the constants
+/// in scope while parsing it, since bounds may name constants of other
+/// sections.
+#[derive(Default)]
+pub(crate) struct SectionCache {
+    entries: Mutex<HashMap<u64, CachedSection>>,
+}
+
+impl SectionCache {
+    /// Like [`parse_idl_section_with_consts`], reusing the result of an
+    /// earlier parse of the same body in the same scope.
+    pub(crate) fn parse(
+        &self,
+        body: &str,
+        scope: &mut ConstScope,
+    ) -> Result<ParsedSection, Ros2Error> {
+        let key = section_key(body, scope);
+        if let Some(cached) = self.entries().get(&key).cloned() {
+            scope.extend(cached.scope_updates);
+            return cached.result;
+        }
+
+        let before = scope.clone();
+        let result = parse_idl_section_with_consts(body, scope);
+        let scope_updates = scope
+            .iter()
+            .filter(|(name, value)| before.get(*name) != Some(value))
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect();
+        self.entries().insert(
+            key,
+            CachedSection {
+                result: result.clone(),
+                scope_updates,
+            },
+        );
+        result
+    }
+
+    fn entries(&self) -> MutexGuard<'_, HashMap<u64, CachedSection>> {
+        // Entries are inserted whole, so a poisoned map is still consistent.
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+fn section_key(body: &str, scope: &ConstScope) -> u64 {
+    let mut consts: Vec<_> = scope.iter().collect();
+    consts.sort_unstable_by(|a, b| a.0.cmp(b.0));
+    let mut hasher = DefaultHasher::new();
+    body.hash(&mut hasher);
+    consts.hash(&mut hasher);
+    hasher.finish()
+}
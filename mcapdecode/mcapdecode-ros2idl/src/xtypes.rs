@@ -0,0 +1,103 @@
+//! [`MessageDecoder`] for schemas recorded as DDS XTypes `TypeObject`s.
+
+use mcapdecode_core::{
+    DecoderError, EncodingKey, MessageDecoder, MessageEncoding, SchemaEncoding, TopicDecoder,
+};
+use mcapdecode_ros2_common::{
+    ByteArrays, CdrOptions, CdrQuirks, InvalidUtf8, Ros2CdrTopicDecoder, UuidRepresentation,
+    cdr_encoding_keys, resolve_type_object,
+};
+
+/// Schema encoding [`XTypesDecoder`] is registered for by default.
+pub const XTYPES_SCHEMA_ENCODING: &str = "xtypes";
+
+/// [`MessageDecoder`] for CDR-encoded messages whose schema is a serialized
+/// XTypes `TypeIdentifierTypeObjectPairSeq`
+/// (see [`resolve_type_object`]).
+///
+/// The MCAP registry defines no schema encoding for `TypeObject`s, so the
+/// decoder matches [`XTYPES_SCHEMA_ENCODING`] unless another name is set
+/// with [`XTypesDecoder::with_schema_encoding`].
+pub struct XTypesDecoder {
+    schema_encoding: String,
+    cdr_options: CdrOptions,
+}
+
+impl XTypesDecoder {
+    pub fn new() -> Self {
+        Self {
+            schema_encoding: XTYPES_SCHEMA_ENCODING.to_string(),
+            cdr_options: CdrOptions::default(),
+        }
+    }
+
+    /// Decode channels whose schema encoding is `schema_encoding`, as
+    /// written by the recorder.
+    pub fn with_schema_encoding(mut self, schema_encoding: impl Into<String>) -> Self {
+        self.schema_encoding = schema_encoding.into();
+        self
+    }
+
+    /// Decode strings that are not valid UTF-8 as `invalid_utf8` says
+    /// (default: [`InvalidUtf8::Error`]).
+    pub fn with_invalid_utf8(mut self, invalid_utf8: InvalidUtf8) -> Self {
+        self.cdr_options.invalid_utf8 = invalid_utf8;
+        self
+    }
+
+    /// Decode payloads laid out as `quirks` says (default:
+    /// [`CdrQuirks::Rosidl`]).
+    pub fn with_cdr_quirks(mut self, quirks: CdrQuirks) -> Self {
+        self.cdr_options.quirks = quirks;
+        self
+    }
+
+    /// Decode fixed-size `uint8` / `octet` arrays as `byte_arrays` says
+    /// (default: [`ByteArrays::List`]).
+    pub fn with_byte_arrays(mut self, byte_arrays: ByteArrays) -> Self {
+        self.cdr_options.byte_arrays = byte_arrays;
+        self
+    }
+
+    /// Decode `unique_identifier_msgs/msg/UUID` values as `uuids` says
+    /// (default: [`UuidRepresentation::Struct`]).
+    pub fn with_uuids(mut self, uuids: UuidRepresentation) -> Self {
+        self.cdr_options.uuids = uuids;
+        self
+    }
+}
+
+impl Default for XTypesDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MessageDecoder for XTypesDecoder {
+    fn encoding_key(&self) -> EncodingKey {
+        EncodingKey::new(
+            SchemaEncoding::from(self.schema_encoding.as_str()),
+            MessageEncoding::Cdr,
+        )
+    }
+
+    fn encoding_keys(&self) -> Vec<EncodingKey> {
+        cdr_encoding_keys(SchemaEncoding::from(self.schema_encoding.as_str()))
+    }
+
+    fn build_topic_decoder(
+        &self,
+        schema_name: &str,
+        schema_data: &[u8],
+    ) -> Result<Box<dyn TopicDecoder>, DecoderError> {
+        let resolved = resolve_type_object(schema_name, schema_data).map_err(|e| {
+            DecoderError::SchemaParse {
+                schema_name: schema_name.to_string(),
+                source: e.into_source(),
+            }
+        })?;
+        Ok(Box::new(
+            Ros2CdrTopicDecoder::new(resolved).with_cdr_options(self.cdr_options),
+        ))
+    }
+}
@@ -1,5 +1,12 @@
-use mcapdecode_ros2_common::{PrimitiveType, ResolvedType};
-use mcapdecode_ros2idl::{SchemaBundle, parse_idl_section, resolve_schema};
+use mcapdecode_core::{
+    DataTypeDef, DecodeWarning, DecoderError, MessageDecoder, SchemaSyntaxError, collect_warnings,
+};
+use mcapdecode_ros2_common::{
+    ConstValue, PrimitiveType, ResolvedType, Ros2Error, SuffixMatching, TypeExpr,
+};
+use mcapdecode_ros2idl::{
+    Ros2IdlDecoder, SchemaBundle, parse_idl_section, resolve_schema, resolve_schema_with_root,
+};
 
 // ── existing tests ─────────────────────────────────────────────────────────────
 
@@ -214,12 +221,77 @@ module ex {
 "#;
 
     // "ex/msg/NoMatch" does not match either section — should fall back to First.
-    let resolved =
-        resolve_schema("ex/msg/NoMatch", schema).expect("resolve should succeed with fallback");
+    let (resolved, warnings) = collect_warnings(|| resolve_schema("ex/msg/NoMatch", schema));
+    let resolved = resolved.expect("resolve should succeed with fallback");
     assert_eq!(
         resolved.root,
         vec!["ex".to_string(), "msg".to_string(), "First".to_string()]
     );
+    assert_eq!(
+        warnings,
+        vec![DecodeWarning::new(
+            "ex/msg/NoMatch",
+            "no IDL section matches the schema name; using 'ex::msg::First' as the root type"
+        )]
+    );
+}
+
+/// An explicit root type overrides the section named after the schema.
+#[test]
+fn resolve_schema_with_root_selects_requested_struct() {
+    let schema = r#"
+================================================================================
+IDL: ex/msg/First
+module ex {
+  module msg {
+    struct First {
+      uint8 x;
+    };
+  };
+};
+================================================================================
+IDL: ex/msg/Second
+module ex {
+  module msg {
+    struct Second {
+      uint8 y;
+    };
+  };
+};
+"#;
+
+    let bundle = SchemaBundle::parse("ex/msg/NoMatch", schema).unwrap();
+    assert_eq!(bundle.find_type("ex/msg/NoMatch"), None);
+
+    let second = vec!["ex".to_string(), "msg".to_string(), "Second".to_string()];
+    for root_type in ["ex::msg::Second", "ex/msg/Second"] {
+        let resolved = resolve_schema_with_root("ex/msg/First", schema, Some(root_type))
+            .expect("resolve should succeed");
+        assert_eq!(resolved.root, second);
+    }
+
+    // A root type the bundle does not define leaves the usual selection in
+    // place, with a warning.
+    let (resolved, warnings) = collect_warnings(|| {
+        resolve_schema_with_root("ex/msg/First", schema, Some("other::msg::Type"))
+    });
+    assert_eq!(
+        resolved.expect("resolve should succeed").root,
+        vec!["ex".to_string(), "msg".to_string(), "First".to_string()]
+    );
+    assert_eq!(
+        warnings,
+        vec![DecodeWarning::new(
+            "ex/msg/First",
+            "root type 'other::msg::Type' is not defined by the schema; ignoring it"
+        )]
+    );
+
+    let topic_decoder = Ros2IdlDecoder::new()
+        .with_root_type("ex::msg::Second")
+        .build_topic_decoder("ex/msg/NoMatch", schema.as_bytes())
+        .unwrap();
+    assert_eq!(topic_decoder.field_defs()[0].name, "y");
 }
 
 /// A sequence field in IDL resolves correctly to `ResolvedType::Sequence`.
@@ -648,6 +720,686 @@ module ex {
     assert_eq!(root.fields[0].name, "items");
     assert!(matches!(
         root.fields[0].ty,
-        ResolvedType::Sequence { max_len: None, .. }
+        ResolvedType::Sequence {
+            max_len: Some(500),
+            ..
+        }
     ));
 }
+
+#[test]
+fn schema_override_replaces_embedded_idl() {
+    let idl = r#"
+module ex {
+  module msg {
+    struct Point {
+      double x;
+      double y;
+    };
+  };
+};
+"#;
+    let decoder = Ros2IdlDecoder::new().with_schema_override("ex/msg/Point", idl);
+
+    let topic_decoder = decoder
+        .build_topic_decoder("ex/msg/Point", b"module ex { module msg { struct Po")
+        .unwrap();
+    assert_eq!(topic_decoder.field_defs().len(), 2);
+}
+
+#[test]
+fn resolve_schema_keeps_unit_annotations() {
+    let schema = r#"
+================================================================================
+IDL: ex/msg/Motion
+module ex {
+  module msg {
+    @verbatim (language="comment", text=
+      "Vehicle speed" "\n"
+      "over ground")
+    @unit (value="m/s")
+    struct Motion {
+      @unit (value="m/s")
+      double speed;
+      @unit("rad")
+      double heading;
+      @default (value=0)
+      double gain;
+    };
+  };
+};
+"#;
+    let resolved = resolve_schema("ex/msg/Motion", schema).expect("resolve should succeed");
+    let motion = resolved
+        .structs
+        .get(&vec!["ex".into(), "msg".into(), "Motion".into()])
+        .expect("Motion should exist");
+    let units: Vec<_> = motion
+        .fields
+        .iter()
+        .map(|f| (f.name.as_str(), f.meta.unit.as_deref()))
+        .collect();
+    assert_eq!(
+        units,
+        vec![
+            ("speed", Some("m/s")),
+            ("heading", Some("rad")),
+            ("gain", None)
+        ]
+    );
+
+    let topic_decoder = Ros2IdlDecoder::new()
+        .with_schema_override("ex/msg/Motion", schema)
+        .build_topic_decoder("ex/msg/Motion", b"")
+        .unwrap();
+    assert_eq!(topic_decoder.field_defs()[0].unit(), Some("m/s"));
+}
+
+#[test]
+fn resolve_schema_keeps_verbatim_comments_as_field_docs() {
+    let schema = r#"
+================================================================================
+IDL: ex/msg/Motion
+module ex {
+  module msg {
+    @verbatim (language="comment", text=
+      "Motion state.")
+    struct Motion {
+      @verbatim (language="comment", text=
+        "Vehicle speed" "\n"
+        "over ground")
+      @unit (value="m/s")
+      double speed;
+      @verbatim (language="rst", text="ignored")
+      double heading;
+    };
+  };
+};
+"#;
+    let topic_decoder = Ros2IdlDecoder::new()
+        .with_schema_override("ex/msg/Motion", schema)
+        .build_topic_decoder("ex/msg/Motion", b"")
+        .unwrap();
+    let fields = topic_decoder.field_defs();
+    assert_eq!(fields[0].doc(), Some("Vehicle speed\nover ground"));
+    assert_eq!(fields[0].unit(), Some("m/s"));
+    assert_eq!(fields[1].doc(), None);
+}
+
+#[test]
+fn resolve_schema_marks_key_fields() {
+    let schema = r#"
+================================================================================
+IDL: ex/msg/Track
+module ex {
+  module msg {
+    struct Track {
+      @key uint32 id;
+      @key
+      string sensor;
+      @key (FALSE) uint8 flags;
+      @unit (value="m") @key(TRUE) double range;
+      double value;
+    };
+  };
+};
+"#;
+    let topic_decoder = Ros2IdlDecoder::new()
+        .with_schema_override("ex/msg/Track", schema)
+        .build_topic_decoder("ex/msg/Track", b"")
+        .unwrap();
+    let keys: Vec<_> = topic_decoder
+        .field_defs()
+        .iter()
+        .map(|f| (f.name.as_str(), f.is_key()))
+        .collect();
+    assert_eq!(
+        keys,
+        vec![
+            ("id", true),
+            ("sensor", true),
+            ("flags", false),
+            ("range", true),
+            ("value", false)
+        ]
+    );
+    assert_eq!(topic_decoder.field_defs()[3].unit(), Some("m"));
+}
+
+#[test]
+fn parse_idl_section_evaluates_constant_expressions() {
+    let parsed = parse_idl_section(
+        r#"
+module ex {
+  module msg {
+    const uint16 BASE = 0x10;
+    struct Sample {
+      const uint8 MASK = 017;
+      const int32 NEG = -(BASE * 2 + 1);
+      const uint32 SHIFTED = 1 << 4 | MASK;
+      const double HALF = 1.5e-1 / 3;
+      const boolean ON = TRUE;
+      const string NAME = "a" "b";
+      const uint8 TOO_BIG = BASE * BASE;
+      const uint8 OTHER = pkg::msg::Other_Constants::X;
+      uint8 kind;
+    };
+  };
+};
+"#,
+    )
+    .expect("IDL should parse");
+
+    let sample = parsed
+        .structs
+        .get(&vec!["ex".into(), "msg".into(), "Sample".into()])
+        .expect("Sample struct should exist");
+    let values: Vec<_> = sample
+        .consts
+        .iter()
+        .map(|c| (c.name.as_str(), c.typed_value.clone()))
+        .collect();
+    assert_eq!(
+        values,
+        vec![
+            ("MASK", Some(ConstValue::Int(15))),
+            ("NEG", Some(ConstValue::Int(-33))),
+            ("SHIFTED", Some(ConstValue::Int(31))),
+            ("HALF", Some(ConstValue::Float(0.15 / 3.0))),
+            ("ON", Some(ConstValue::Bool(true))),
+            ("NAME", Some(ConstValue::String("ab".into()))),
+            ("TOO_BIG", None),
+            ("OTHER", None),
+        ]
+    );
+    assert_eq!(sample.consts[1].value, "-(BASE * 2 + 1)");
+}
+
+#[test]
+fn parse_idl_section_uses_constants_as_bounds() {
+    let parsed = parse_idl_section(
+        r#"
+module ex {
+  module msg {
+    const uint32 MAX_LEN = 4 * 2;
+    struct Sample {
+      const uint8 SIZE = 3;
+      sequence<uint8, MAX_LEN> data;
+      string<MAX_LEN> name;
+      double values[SIZE];
+      sequence<uint8, UNKNOWN> other;
+    };
+  };
+};
+"#,
+    )
+    .expect("IDL should parse");
+
+    let sample = parsed
+        .structs
+        .get(&vec!["ex".into(), "msg".into(), "Sample".into()])
+        .expect("Sample struct should exist");
+    assert!(matches!(
+        sample.fields[0].ty,
+        TypeExpr::Sequence {
+            max_len: Some(8),
+            ..
+        }
+    ));
+    assert_eq!(sample.fields[1].ty, TypeExpr::BoundedString(8));
+    assert_eq!(sample.fields[2].fixed_len, Some(3));
+    assert!(matches!(
+        sample.fields[3].ty,
+        TypeExpr::Sequence { max_len: None, .. }
+    ));
+}
+
+#[test]
+fn resolve_schema_resolves_bounds_named_by_constants_of_other_sections() {
+    let schema = r#"
+================================================================================
+IDL: ex/msg/Root
+#include "ex/msg/Limits.idl"
+module ex {
+  module msg {
+    struct Root {
+      sequence<uint8, MAX_SIZE> data;
+      string<ex::msg::MAX_NAME> name;
+      double values[DIM];
+    };
+    const uint8 DIM = 3;
+  };
+};
+================================================================================
+IDL: ex/msg/Limits
+module ex {
+  module msg {
+    const uint32 MAX_SIZE = MAX_NAME * 4;
+    const uint32 MAX_NAME = 16;
+  };
+};
+"#;
+
+    let resolved = resolve_schema("ex/msg/Root", schema).expect("resolve should succeed");
+    let root = resolved
+        .structs
+        .get(&vec!["ex".into(), "msg".into(), "Root".into()])
+        .expect("Root should exist");
+    assert!(matches!(
+        root.fields[0].ty,
+        ResolvedType::Sequence {
+            max_len: Some(64),
+            ..
+        }
+    ));
+    assert!(matches!(root.fields[1].ty, ResolvedType::BoundedString(16)));
+    assert_eq!(root.fields[2].fixed_len, Some(3));
+}
+
+#[test]
+fn resolve_schema_rejects_unknown_constant_as_string_bound() {
+    let schema = r#"
+================================================================================
+IDL: ex/msg/Root
+module ex {
+  module msg {
+    struct Root {
+      string<MISSING> name;
+    };
+  };
+};
+"#;
+    let err = resolve_schema("ex/msg/Root", schema).expect_err("unknown bound should fail");
+    assert!(format!("{err}").contains("parse error at line"));
+}
+
+#[test]
+fn parse_idl_section_collects_module_constants() {
+    let parsed = parse_idl_section(
+        r#"
+module ex {
+  module msg {
+    const uint8 KIND_A = 1;
+    struct Sample {
+      uint8 kind;
+    };
+  };
+};
+"#,
+    )
+    .expect("IDL should parse");
+    let kind_a = parsed
+        .consts
+        .get(&vec!["ex".into(), "msg".into(), "KIND_A".into()])
+        .expect("KIND_A should be collected");
+    assert_eq!(kind_a.typed_value, Some(ConstValue::Int(1)));
+}
+
+#[test]
+fn resolve_schema_attaches_rosidl_constants_modules_to_structs() {
+    let schema = r#"
+================================================================================
+IDL: ex/msg/Status
+module ex {
+  module msg {
+    module Status_Constants {
+      const uint8 OK = 0;
+      const uint8 ERROR = 1;
+      const uint32 MAX_DETAILS = 0x4;
+    };
+    struct Status {
+      uint8 level;
+      sequence<string, Status_Constants::MAX_DETAILS> details;
+    };
+  };
+};
+"#;
+
+    let resolved = resolve_schema("ex/msg/Status", schema).expect("resolve should succeed");
+    let status = resolved
+        .structs
+        .get(&vec!["ex".into(), "msg".into(), "Status".into()])
+        .expect("Status should exist");
+    let consts: Vec<_> = status
+        .consts
+        .iter()
+        .map(|c| (c.name.as_str(), c.typed_value.clone()))
+        .collect();
+    assert_eq!(
+        consts,
+        vec![
+            ("OK", Some(ConstValue::Int(0))),
+            ("ERROR", Some(ConstValue::Int(1))),
+            ("MAX_DETAILS", Some(ConstValue::Int(4))),
+        ]
+    );
+    assert!(matches!(
+        status.fields[1].ty,
+        ResolvedType::Sequence {
+            max_len: Some(4),
+            ..
+        }
+    ));
+}
+
+#[test]
+fn decoder_reports_syntax_error_location() {
+    let schema = r#"
+================================================================================
+IDL: ex/msg/Root
+module ex {
+  module msg {
+    struct Root {
+      uint32 x y;
+    };
+  };
+};
+"#;
+    let err = match Ros2IdlDecoder::new().build_topic_decoder("ex/msg/Root", schema.as_bytes()) {
+        Ok(_) => panic!("trailing field tokens should fail"),
+        Err(err) => err,
+    };
+    let DecoderError::SchemaParse { source, .. } = err else {
+        panic!("expected a schema parse error, got {err}");
+    };
+    let syntax = source
+        .downcast_ref::<SchemaSyntaxError>()
+        .expect("source should be a syntax error");
+    assert_eq!(syntax.section.as_deref(), Some("ex/msg/Root"));
+    assert_eq!((syntax.line, syntax.column), (4, 16));
+    assert_eq!(syntax.snippet, "      uint32 x y;");
+}
+
+#[test]
+fn resolve_schema_classifies_errors() {
+    let union = r#"
+IDL: bad/msg/U
+module bad {
+  module msg {
+    union U switch(uint8) {
+      case 0: uint8 a;
+    };
+  };
+};
+"#;
+    let unresolved = r#"
+IDL: ex/msg/A
+module ex {
+  module msg {
+    struct A {
+      ex::msg::Missing m;
+    };
+  };
+};
+"#;
+    let unclosed = r#"
+IDL: ex/msg/A
+module ex {
+  module msg {
+    struct A {
+      uint32 x;
+"#;
+
+    assert!(matches!(
+        resolve_schema("bad/msg/U", union),
+        Err(Ros2Error::UnsupportedFeature(_))
+    ));
+    assert!(matches!(
+        resolve_schema("ex/msg/A", unresolved),
+        Err(Ros2Error::UnresolvedType(_))
+    ));
+    assert!(matches!(
+        resolve_schema("ex/msg/A", unclosed),
+        Err(Ros2Error::ParseError(_))
+    ));
+}
+
+const VENDOR_HEADER_SCHEMA: &str = r#"
+================================================================================
+IDL: ex/srv/Req
+module ex {
+  module srv {
+    struct Req {
+      Header header;
+    };
+  };
+};
+================================================================================
+IDL: ex/msg/Header
+module ex {
+  module msg {
+    struct Header {
+      uint32 stamp;
+    };
+  };
+};
+================================================================================
+IDL: vendor/msg/Header
+module vendor {
+  module msg {
+    struct Header {
+      uint32 vendor_id;
+    };
+  };
+};
+"#;
+
+#[test]
+fn suffix_matching_modes_resolve_ambiguous_names() {
+    let build = |matching| {
+        Ros2IdlDecoder::new()
+            .with_suffix_matching(matching)
+            .build_topic_decoder("ex/srv/Req", VENDOR_HEADER_SCHEMA.as_bytes())
+    };
+
+    let decoder = build(SuffixMatching::SamePackageFirst).expect("same package should win");
+    let DataTypeDef::Struct(header) = &decoder.field_defs()[0].element.data_type else {
+        panic!("header should be a struct");
+    };
+    assert_eq!(header[0].name, "stamp");
+
+    for matching in [SuffixMatching::Auto, SuffixMatching::Strict] {
+        let err = match build(matching) {
+            Ok(_) => panic!("{matching:?} should not pick a Header"),
+            Err(err) => err,
+        };
+        assert!(
+            format!("{err:#}").contains("candidates: ex::msg::Header, vendor::msg::Header"),
+            "unexpected error: {err:#}"
+        );
+    }
+}
+
+#[test]
+fn strict_suffix_matching_rejects_unique_suffix() {
+    let schema = r#"
+================================================================================
+IDL: ex/msg/Root
+module ex {
+  module msg {
+    struct Root {
+      Point p;
+    };
+  };
+};
+================================================================================
+IDL: geometry_msgs/msg/Point
+module geometry_msgs {
+  module msg {
+    struct Point {
+      double x;
+    };
+  };
+};
+"#;
+    assert!(
+        Ros2IdlDecoder::new()
+            .build_topic_decoder("ex/msg/Root", schema.as_bytes())
+            .is_ok()
+    );
+    let err = match Ros2IdlDecoder::new()
+        .with_suffix_matching(SuffixMatching::Strict)
+        .build_topic_decoder("ex/msg/Root", schema.as_bytes())
+    {
+        Ok(_) => panic!("strict mode should not match by suffix"),
+        Err(err) => err,
+    };
+    assert!(
+        format!("{err:#}").contains(
+            "unresolved type 'Point' in 'ex::msg::Root' \
+             (suffix matching is disabled; candidates: geometry_msgs::msg::Point)"
+        ),
+        "unexpected error: {err:#}"
+    );
+}
+
+#[test]
+fn decoder_reuses_sections_without_mixing_constant_scopes() {
+    let schema = |dim: u32| {
+        format!(
+            r#"
+================================================================================
+IDL: ex/msg/Root
+module ex {{
+  module msg {{
+    struct Root {{
+      double values[DIM];
+    }};
+  }};
+}};
+================================================================================
+IDL: ex/msg/Limits
+module ex {{
+  module msg {{
+    const uint32 DIM = {dim};
+  }};
+}};
+"#
+        )
+    };
+
+    let decoder = Ros2IdlDecoder::new();
+    for dim in [3, 5, 3] {
+        let topic_decoder = decoder
+            .build_topic_decoder("ex/msg/Root", schema(dim).as_bytes())
+            .expect("build should succeed");
+        let DataTypeDef::Array(_, len) = &topic_decoder.field_defs()[0].element.data_type else {
+            panic!("values should be a fixed-size array");
+        };
+        assert_eq!(*len, dim as usize);
+    }
+}
+
+#[test]
+fn resolve_schema_prunes_types_unreachable_from_root() {
+    let schema = r#"
+================================================================================
+IDL: ex/msg/Root
+module ex {
+  module msg {
+    enum Mode {
+      AUTO,
+      MANUAL
+    };
+    struct Root {
+      sequence<Point> points;
+      Mode mode;
+    };
+    struct Point {
+      double x;
+    };
+  };
+};
+================================================================================
+IDL: ex/msg/Broken
+module ex {
+  module msg {
+    enum Unused {
+      A
+    };
+    struct Broken {
+      Missing m;
+    };
+  };
+};
+"#;
+
+    let resolved = resolve_schema("ex/msg/Root", schema).expect("resolve should succeed");
+    let mut structs: Vec<String> = resolved.structs.keys().map(|key| key.join("::")).collect();
+    structs.sort();
+    assert_eq!(structs, vec!["ex::msg::Point", "ex::msg::Root"]);
+    let enums: Vec<String> = resolved.enums.keys().map(|key| key.join("::")).collect();
+    assert_eq!(enums, vec!["ex::msg::Mode"]);
+
+    let err = resolve_schema_with_root("ex/msg/Broken", schema, Some("ex::msg::Broken"))
+        .expect_err("Broken references an undefined type");
+    assert!(matches!(err, Ros2Error::UnresolvedType(_)), "{err:?}");
+}
+
+#[test]
+fn lenient_sections_skip_unparsable_unreachable_sections() {
+    let schema = |root_field: &str| {
+        format!(
+            r#"
+================================================================================
+IDL: ex/msg/Root
+module ex {{
+  module msg {{
+    struct Root {{
+      {root_field};
+    }};
+  }};
+}};
+================================================================================
+IDL: ex/msg/Broken
+module ex {{
+  module msg {{
+    struct Broken {{
+      uint32 x y;
+    }};
+  }};
+}};
+"#
+        )
+    };
+    let build = |lenient, schema: String| {
+        Ros2IdlDecoder::new()
+            .with_lenient_sections(lenient)
+            .build_topic_decoder("ex/msg/Root", schema.as_bytes())
+    };
+
+    assert!(build(false, schema("uint32 x")).is_err());
+    let (decoder, warnings) = collect_warnings(|| build(true, schema("uint32 x")));
+    let decoder = decoder.expect("broken section should be skipped");
+    assert_eq!(decoder.field_defs()[0].name, "x");
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].path, "ex/msg/Broken");
+    assert!(
+        warnings[0]
+            .detail
+            .starts_with("skipped unparsable IDL section: parse error at line 4"),
+        "{}",
+        warnings[0].detail
+    );
+
+    let err = match build(true, schema("Broken b")) {
+        Ok(_) => panic!("Root depends on the skipped section"),
+        Err(err) => err,
+    };
+    assert!(
+        format!("{err:#}").contains("unresolved type 'Broken'"),
+        "unexpected error: {err:#}"
+    );
+
+    let err = match build(true, schema("uint32 a b")) {
+        Ok(_) => panic!("Root's own section is unparsable"),
+        Err(err) => err,
+    };
+    let DecoderError::SchemaParse { source, .. } = &err else {
+        panic!("unexpected error: {err:?}");
+    };
+    let syntax = source
+        .downcast_ref::<SchemaSyntaxError>()
+        .expect("root section error should be reported");
+    assert_eq!(syntax.section.as_deref(), Some("ex/msg/Root"));
+}
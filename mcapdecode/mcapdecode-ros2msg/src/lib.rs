@@ -1,7 +1,8 @@
 //! ROS 2 .msg → CDR decoder for `mcapdecode`.
 //!
 //! Implements [`MessageDecoder`] for the
-//! `(schema_encoding = ros2msg, message_encoding = cdr)` key.
+//! `(schema_encoding = ros2msg, message_encoding = cdr)` key, and for the
+//! `cdr_le` and `xcdr` spellings of the message encoding.
 //!
 //! ROS 2 MCAP writers often embed dependent `.msg` definitions in the schema
 //! blob, separated by `====` lines and `MSG:` headers. `ros2msg` therefore
@@ -23,20 +24,99 @@ mod parser;
 mod resolver;
 mod schema_bundle;
 
+use std::{collections::HashSet, fmt::Write as _, path::Path};
+
 use mcapdecode_core::{
     DecoderError, EncodingKey, MessageDecoder, MessageEncoding, SchemaEncoding, TopicDecoder,
 };
-use mcapdecode_ros2_common::{ResolvedSchema, Ros2CdrTopicDecoder};
+pub use mcapdecode_ros2_common::{ByteArrays, CdrQuirks, InvalidUtf8, UuidRepresentation};
+use mcapdecode_ros2_common::{
+    CdrOptions, ResolvedSchema, Ros2CdrTopicDecoder, SchemaOverrides, SuffixMatching, TypeExpr,
+    cdr_encoding_keys,
+};
 pub use parser::parse_msg;
 pub use resolver::resolve_schema;
+use resolver::resolve_schema_with;
 pub use schema_bundle::{MsgSection, SchemaBundle};
 
 /// [`MessageDecoder`] for ROS 2 .msg schemas with CDR-encoded messages.
-pub struct Ros2MsgDecoder;
+pub struct Ros2MsgDecoder {
+    schema_overrides: SchemaOverrides,
+    suffix_matching: SuffixMatching,
+    cdr_options: CdrOptions,
+}
 
 impl Ros2MsgDecoder {
     pub fn new() -> Self {
-        Self
+        Self {
+            schema_overrides: SchemaOverrides::new(),
+            suffix_matching: SuffixMatching::default(),
+            cdr_options: CdrOptions::default(),
+        }
+    }
+
+    /// Use `schema_text` instead of the schema data embedded in the MCAP file
+    /// for channels whose schema name is `schema_name`.
+    pub fn with_schema_override(
+        mut self,
+        schema_name: impl Into<String>,
+        schema_text: impl Into<String>,
+    ) -> Self {
+        self.schema_overrides.insert(schema_name, schema_text);
+        self
+    }
+
+    /// Load schema overrides from every `*.msg` file below `dir`
+    /// (see [`SchemaOverrides::load_dir`]).
+    ///
+    /// Each file is bundled with the files of the types it depends on, as
+    /// `MSG:` sections, so a type can be overridden without inlining its
+    /// nested message types.
+    pub fn with_schema_override_dir(mut self, dir: impl AsRef<Path>) -> std::io::Result<Self> {
+        let mut dir_overrides = SchemaOverrides::new();
+        dir_overrides.load_dir(dir.as_ref(), "msg")?;
+        for (schema_name, schema_text) in dir_overrides.iter() {
+            self.schema_overrides.insert(
+                schema_name,
+                with_dependencies(schema_name, schema_text, &dir_overrides),
+            );
+        }
+        Ok(self)
+    }
+
+    /// Match type names that are not defined exactly according to
+    /// `suffix_matching` (default: [`SuffixMatching::Auto`]).
+    pub fn with_suffix_matching(mut self, suffix_matching: SuffixMatching) -> Self {
+        self.suffix_matching = suffix_matching;
+        self
+    }
+
+    /// Decode strings that are not valid UTF-8 as `invalid_utf8` says
+    /// (default: [`InvalidUtf8::Error`]).
+    pub fn with_invalid_utf8(mut self, invalid_utf8: InvalidUtf8) -> Self {
+        self.cdr_options.invalid_utf8 = invalid_utf8;
+        self
+    }
+
+    /// Decode payloads laid out as `quirks` says (default:
+    /// [`CdrQuirks::Rosidl`]).
+    pub fn with_cdr_quirks(mut self, quirks: CdrQuirks) -> Self {
+        self.cdr_options.quirks = quirks;
+        self
+    }
+
+    /// Decode fixed-size `uint8` / `octet` arrays as `byte_arrays` says
+    /// (default: [`ByteArrays::List`]).
+    pub fn with_byte_arrays(mut self, byte_arrays: ByteArrays) -> Self {
+        self.cdr_options.byte_arrays = byte_arrays;
+        self
+    }
+
+    /// Decode `unique_identifier_msgs/msg/UUID` values as `uuids` says
+    /// (default: [`UuidRepresentation::Struct`]).
+    pub fn with_uuids(mut self, uuids: UuidRepresentation) -> Self {
+        self.cdr_options.uuids = uuids;
+        self
     }
 }
 
@@ -51,13 +131,23 @@ impl MessageDecoder for Ros2MsgDecoder {
         EncodingKey::new(SchemaEncoding::Ros2Msg, MessageEncoding::Cdr)
     }
 
+    fn encoding_keys(&self) -> Vec<EncodingKey> {
+        cdr_encoding_keys(SchemaEncoding::Ros2Msg)
+    }
+
     fn build_topic_decoder(
         &self,
         schema_name: &str,
         schema_data: &[u8],
     ) -> Result<Box<dyn TopicDecoder>, DecoderError> {
-        let resolved = resolve_for_cdr(schema_name, schema_data)?;
-        Ok(Box::new(Ros2CdrTopicDecoder::new(resolved)))
+        let schema_data = self
+            .schema_overrides
+            .get(schema_name)
+            .map_or(schema_data, str::as_bytes);
+        let resolved = resolve_bytes(schema_name, schema_data, self.suffix_matching)?;
+        Ok(Box::new(
+            Ros2CdrTopicDecoder::new(resolved).with_cdr_options(self.cdr_options),
+        ))
     }
 }
 
@@ -65,13 +155,96 @@ impl MessageDecoder for Ros2MsgDecoder {
 pub fn resolve_for_cdr(
     schema_name: &str,
     schema_data: &[u8],
+) -> Result<ResolvedSchema, DecoderError> {
+    resolve_bytes(schema_name, schema_data, SuffixMatching::default())
+}
+
+const SECTION_SEPARATOR: &str =
+    "================================================================================";
+
+/// `schema_text` followed by a `MSG:` section for every type it depends on,
+/// directly or not, that `overrides` defines and `schema_text` does not.
+///
+/// Text that does not parse is returned unchanged; the decoder reports the
+/// error when the topic is decoded.
+fn with_dependencies(schema_name: &str, schema_text: &str, overrides: &SchemaOverrides) -> String {
+    let mut text = schema_text.to_string();
+    let Ok(bundle) = SchemaBundle::parse(schema_name, schema_text) else {
+        return text;
+    };
+    let mut seen: HashSet<Vec<String>> = bundle
+        .sections
+        .iter()
+        .map(|section| section.msg_path.clone())
+        .collect();
+    let mut pending = bundle.sections;
+    while let Some(section) = pending.pop() {
+        let Ok(parsed) = parse_msg(&section.schema_name(), &section.body) else {
+            continue;
+        };
+        for field in &parsed.fields {
+            let Some(path) = type_path(&field.ty, &section.msg_path) else {
+                continue;
+            };
+            if !seen.insert(path.clone()) {
+                continue;
+            }
+            let dependency_name = path.join("/");
+            let Some(dependency) = overrides
+                .get(&dependency_name)
+                .and_then(|text| SchemaBundle::parse(&dependency_name, text).ok())
+            else {
+                continue;
+            };
+            for dependency_section in dependency.sections {
+                if dependency_section.msg_path != path
+                    && !seen.insert(dependency_section.msg_path.clone())
+                {
+                    continue;
+                }
+                let _ = write!(
+                    text,
+                    "\n{SECTION_SEPARATOR}\nMSG: {}\n{}",
+                    dependency_section.path(),
+                    dependency_section.body
+                );
+                pending.push(dependency_section);
+            }
+        }
+    }
+    text
+}
+
+/// Qualified name of the message type `ty` refers to (or holds a sequence
+/// of) in the section of type `section_path`.
+fn type_path(ty: &TypeExpr, section_path: &[String]) -> Option<Vec<String>> {
+    match ty {
+        TypeExpr::Scoped(name) => match name.as_slice() {
+            [name] => Some(vec![
+                section_path.first()?.clone(),
+                "msg".to_string(),
+                name.clone(),
+            ]),
+            _ => Some(name.clone()),
+        },
+        TypeExpr::Sequence { elem, .. } => type_path(elem, section_path),
+        _ => None,
+    }
+}
+
+fn resolve_bytes(
+    schema_name: &str,
+    schema_data: &[u8],
+    suffix_matching: SuffixMatching,
 ) -> Result<ResolvedSchema, DecoderError> {
     let schema_str = std::str::from_utf8(schema_data).map_err(|e| DecoderError::SchemaParse {
         schema_name: schema_name.to_string(),
         source: Box::new(e),
     })?;
-    resolve_schema(schema_name, schema_str).map_err(|e| DecoderError::SchemaParse {
-        schema_name: schema_name.to_string(),
-        source: e.into(),
+    resolve_schema_with(schema_name, schema_str, suffix_matching).map_err(|e| {
+        DecoderError::SchemaParse {
+            schema_name: schema_name.to_string(),
+            source: e.into_source(),
+        }
     })
 }
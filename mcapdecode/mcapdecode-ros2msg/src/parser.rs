@@ -4,24 +4,81 @@
 //! `re_ros_msg` crate and then maps the resulting `MessageSpecification` into
 //! the types understood by `mcapdecode-ros2-common`.
 
-use mcapdecode_ros2_common::{ConstDef, FieldDef, PrimitiveType, Ros2Error, StructDef, TypeExpr};
+use std::collections::HashMap;
+
+use mcapdecode_core::SchemaSyntaxError;
+use mcapdecode_ros2_common::{
+    ConstDef, ConstValue, FieldDef, FieldMeta, PrimitiveType, Ros2Error, StructDef, TypeExpr,
+};
 use re_ros_msg::{
     MessageSchema,
     message_spec::{
-        ArraySize, BuiltInType, ComplexType, Constant, Field, MessageSpecification, Type,
+        ArraySize, BuiltInType, ComplexType, Constant, Field, Literal, MessageSpecification, Type,
     },
 };
 /// Parse .msg format and generate StructDef
 pub fn parse_msg(schema_name: &str, msg_text: &str) -> Result<StructDef, Ros2Error> {
     // 1. Parse with re_ros_msg
-    let schema = MessageSchema::parse(schema_name, msg_text)
-        .map_err(|e| Ros2Error(format!("failed to parse msg schema '{schema_name}': {e}")))?;
+    let schema = MessageSchema::parse(schema_name, msg_text).map_err(|e| {
+        locate_syntax_error(schema_name, msg_text).map_or_else(
+            || Ros2Error::ParseError(format!("failed to parse msg schema '{schema_name}': {e}")),
+            Ros2Error::from,
+        )
+    })?;
 
     // 2. Parse schema_name to get full_name
     let full_name = parse_schema_name(schema_name)?;
 
     // 3. Convert MessageSpecification → StructDef
-    convert_to_struct_def(full_name, schema.spec)
+    convert_to_struct_def(full_name, schema.spec, &field_comments(msg_text))
+}
+
+/// Find the first line of `msg_text` that fails to parse on its own.
+/// `re_ros_msg` reports errors without a location.
+fn locate_syntax_error(schema_name: &str, msg_text: &str) -> Option<SchemaSyntaxError> {
+    msg_text.lines().enumerate().find_map(|(idx, line)| {
+        let err = MessageSchema::parse(schema_name, line).err()?;
+        let column = line[..line.len() - line.trim_start().len()].chars().count() + 1;
+        Some(SchemaSyntaxError::new(
+            idx + 1,
+            column,
+            line.trim_end(),
+            err.root_cause().to_string(),
+        ))
+    })
+}
+
+/// Trailing comment of each field declaration, keyed by field name.
+/// `re_ros_msg` drops comments, so they are recovered from the raw text.
+fn field_comments(msg_text: &str) -> HashMap<String, String> {
+    msg_text
+        .lines()
+        .filter_map(|line| {
+            let (decl, comment) = split_comment(line)?;
+            let mut tokens = decl.split_whitespace();
+            let _ty = tokens.next()?;
+            let name = tokens.next()?;
+            // Constants: `int32 FOO=1` / `int32 FOO = 1`.
+            if name.contains('=') || tokens.next().is_some_and(|t| t.starts_with('=')) {
+                return None;
+            }
+            Some((name.to_string(), comment.trim().to_string()))
+        })
+        .collect()
+}
+
+/// Split `line` at the first `#` outside a quoted default value.
+fn split_comment(line: &str) -> Option<(&str, &str)> {
+    let mut quote = None;
+    for (idx, ch) in line.char_indices() {
+        match (quote, ch) {
+            (None, '"' | '\'') => quote = Some(ch),
+            (Some(q), _) if ch == q => quote = None,
+            (None, '#') => return Some((&line[..idx], &line[idx + 1..])),
+            _ => {}
+        }
+    }
+    None
 }
 
 fn parse_schema_name(name: &str) -> Result<Vec<String>, Ros2Error> {
@@ -36,18 +93,24 @@ fn parse_schema_name(name: &str) -> Result<Vec<String>, Ros2Error> {
             "msg".to_string(),
             parts[1].to_string(),
         ]),
-        _ => Err(format!("invalid schema name format: {name}").into()),
+        _ => Err(Ros2Error::ParseError(format!(
+            "invalid schema name format: {name}"
+        ))),
     }
 }
 
 fn convert_to_struct_def(
     full_name: Vec<String>,
     spec: MessageSpecification,
+    comments: &HashMap<String, String>,
 ) -> Result<StructDef, Ros2Error> {
     let fields = spec
         .fields
         .into_iter()
-        .map(convert_field)
+        .map(|field| {
+            let comment = comments.get(&field.name).map(String::as_str);
+            convert_field(field, comment)
+        })
         .collect::<Result<Vec<_>, _>>()?;
 
     let consts = spec
@@ -63,13 +126,17 @@ fn convert_to_struct_def(
     })
 }
 
-fn convert_field(field: Field) -> Result<FieldDef, Ros2Error> {
+fn convert_field(field: Field, comment: Option<&str>) -> Result<FieldDef, Ros2Error> {
     let (ty, fixed_len) = convert_type(&field.ty)?;
 
     Ok(FieldDef {
         name: field.name,
         ty,
         fixed_len,
+        meta: FieldMeta {
+            unit: comment.and_then(FieldMeta::unit_from_comment),
+            ..FieldMeta::default()
+        },
     })
 }
 
@@ -85,7 +152,9 @@ fn convert_type(ty: &Type) -> Result<(TypeExpr, Option<usize>), Ros2Error> {
 
             // If the element itself has fixed_len, that becomes the outer array
             if elem_fixed.is_some() {
-                return Err("nested fixed arrays are not supported in ROS2".into());
+                return Err(Ros2Error::UnsupportedFeature(
+                    "nested fixed arrays are not supported in ROS2".to_string(),
+                ));
             }
 
             match size {
@@ -163,16 +232,29 @@ fn convert_constant(constant: Constant) -> Result<ConstDef, Ros2Error> {
     let ty = match &constant.ty {
         Type::BuiltIn(builtin) => convert_builtin_type(builtin),
         Type::Array { .. } => {
-            return Err("constants cannot be arrays".into());
+            return Err(Ros2Error::UnsupportedFeature(
+                "constants cannot be arrays".to_string(),
+            ));
         }
         Type::Complex(_) => {
-            return Err("constants must be primitive types".into());
+            return Err(Ros2Error::UnsupportedFeature(
+                "constants must be primitive types".to_string(),
+            ));
         }
     };
 
+    let typed_value = match &constant.value {
+        Literal::Bool(v) => Some(ConstValue::Bool(*v)),
+        Literal::Int(v) => Some(ConstValue::Int((*v).into())),
+        Literal::UInt(v) => Some(ConstValue::Int((*v).into())),
+        Literal::Float(v) => Some(ConstValue::Float(*v)),
+        Literal::String(v) => Some(ConstValue::String(v.clone())),
+        Literal::Array(_) => None,
+    };
     Ok(ConstDef {
         ty,
         name: constant.name,
         value: format!("{:?}", constant.value),
+        typed_value,
     })
 }
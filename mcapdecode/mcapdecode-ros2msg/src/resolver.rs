@@ -1,27 +1,34 @@
-use mcapdecode_ros2_common::{ParsedSection, ResolvedSchema, Ros2Error, resolve_parsed_section};
+use mcapdecode_ros2_common::{
+    ParsedSection, ResolvedSchema, Ros2Error, SuffixMatching, resolve_parsed_section_with,
+};
 
 use crate::{parse_msg, schema_bundle::SchemaBundle};
 
 /// Parse a `.msg` schema text or schema bundle and produce a fully resolved schema.
 pub fn resolve_schema(schema_name: &str, schema_text: &str) -> Result<ResolvedSchema, Ros2Error> {
+    resolve_schema_with(schema_name, schema_text, SuffixMatching::default())
+}
+
+/// Like [`resolve_schema`], matching type names according to `suffix_matching`.
+pub(crate) fn resolve_schema_with(
+    schema_name: &str,
+    schema_text: &str,
+    suffix_matching: SuffixMatching,
+) -> Result<ResolvedSchema, Ros2Error> {
     let bundle = SchemaBundle::parse(schema_name, schema_text)?;
 
     let mut merged = ParsedSection::default();
     for section in &bundle.sections {
-        let parsed = parse_msg(&section.schema_name(), &section.body).map_err(|e| {
-            Ros2Error(format!(
-                "while parsing msg section '{}': {e}",
-                section.path()
-            ))
-        })?;
+        let parsed = parse_msg(&section.schema_name(), &section.body)
+            .map_err(|e| e.in_section("msg", &section.path()))?;
         merged.structs.insert(parsed.full_name.clone(), parsed);
     }
 
     let root = bundle.main_type(schema_name).ok_or_else(|| {
-        Ros2Error(format!(
+        Ros2Error::UnresolvedType(format!(
             "unable to determine root type for schema '{schema_name}'"
         ))
     })?;
 
-    resolve_parsed_section(merged, root)
+    resolve_parsed_section_with(merged, root, suffix_matching)
 }
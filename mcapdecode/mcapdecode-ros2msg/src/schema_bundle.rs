@@ -54,7 +54,9 @@ impl SchemaBundle {
         }
 
         if sections.is_empty() {
-            return Err(format!("no MSG sections found for schema '{schema_name}'").into());
+            return Err(Ros2Error::ParseError(format!(
+                "no MSG sections found for schema '{schema_name}'"
+            )));
         }
 
         Ok(Self { sections })
@@ -82,15 +84,17 @@ fn parse_section(
     let first_idx = lines
         .iter()
         .position(|line| !line.trim().is_empty())
-        .ok_or_else(|| Ros2Error("empty MSG section".to_string()))?;
+        .ok_or_else(|| Ros2Error::ParseError("empty MSG section".to_string()))?;
     let header = lines[first_idx].trim();
 
     let (msg_path, body_start) = if let Some(path) = header.strip_prefix("MSG:") {
         (parse_schema_name(path.trim())?, first_idx + 1)
     } else if let Some(path) = fallback_path {
-        (path, first_idx)
+        (path, 0)
     } else {
-        return Err(format!("missing `MSG:` header: {header}").into());
+        return Err(Ros2Error::ParseError(format!(
+            "missing `MSG:` header: {header}"
+        )));
     };
 
     Ok(MsgSection {
@@ -118,6 +122,8 @@ fn parse_schema_name(name: &str) -> Result<Vec<String>, Ros2Error> {
             "msg".to_string(),
             parts[1].to_string(),
         ]),
-        _ => Err(format!("invalid schema name format: {name}").into()),
+        _ => Err(Ros2Error::ParseError(format!(
+            "invalid schema name format: {name}"
+        ))),
     }
 }
@@ -1,5 +1,6 @@
-use mcapdecode_ros2_common::TypeExpr;
-use mcapdecode_ros2msg::{SchemaBundle, parse_msg, resolve_for_cdr};
+use mcapdecode_core::{DecoderError, MessageDecoder, SchemaSyntaxError};
+use mcapdecode_ros2_common::{ConstValue, TypeExpr};
+use mcapdecode_ros2msg::{Ros2MsgDecoder, SchemaBundle, parse_msg, resolve_for_cdr};
 
 // ── existing tests ─────────────────────────────────────────────────────────────
 
@@ -71,6 +72,33 @@ string MODE="auto"
     assert_eq!(result.consts.len(), 2);
 }
 
+#[test]
+fn parse_constants_keeps_typed_values() {
+    let msg = r#"
+int32 STATUS_OK=-1
+uint64 BIG=18446744073709551615
+float64 RATIO=0.5
+bool ENABLED=true
+string MODE="auto"
+"#;
+    let result = parse_msg("test_msgs/msg/Const", msg).unwrap();
+    let values: Vec<_> = result
+        .consts
+        .iter()
+        .map(|c| c.typed_value.clone())
+        .collect();
+    assert_eq!(
+        values,
+        vec![
+            Some(ConstValue::Int(-1)),
+            Some(ConstValue::Int(u64::MAX.into())),
+            Some(ConstValue::Float(0.5)),
+            Some(ConstValue::Bool(true)),
+            Some(ConstValue::String("auto".into())),
+        ]
+    );
+}
+
 #[test]
 fn parse_bounded_string() {
     let msg = "string<=20 name";
@@ -254,3 +282,173 @@ string frame_id
             .contains_key(&vec!["std_msgs".into(), "msg".into(), "Header".into()])
     );
 }
+
+#[test]
+fn schema_override_replaces_truncated_schema_data() {
+    let decoder =
+        Ros2MsgDecoder::new().with_schema_override("test_msgs/msg/Point", "float64 x\nfloat64 y");
+
+    let topic_decoder = decoder
+        .build_topic_decoder("test_msgs/msg/Point", b"float64 x\nfloa")
+        .unwrap();
+    let names: Vec<_> = topic_decoder
+        .field_defs()
+        .iter()
+        .map(|f| f.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["x", "y"]);
+}
+
+#[test]
+fn schema_override_dir_maps_relative_paths_to_schema_names() {
+    let dir = std::env::temp_dir().join(format!(
+        "mcapdecode-ros2msg-overrides-{}",
+        std::process::id()
+    ));
+    std::fs::create_dir_all(dir.join("test_msgs/msg")).unwrap();
+    std::fs::write(
+        dir.join("test_msgs/msg/Point.msg"),
+        "float64 x\nfloat64 y\nfloat64 z",
+    )
+    .unwrap();
+
+    let decoder = Ros2MsgDecoder::new()
+        .with_schema_override_dir(&dir)
+        .unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let topic_decoder = decoder
+        .build_topic_decoder("test_msgs/msg/Point", b"")
+        .unwrap();
+    assert_eq!(topic_decoder.field_defs().len(), 3);
+}
+
+#[test]
+fn schema_override_dir_bundles_nested_message_types() {
+    let dir = std::env::temp_dir().join(format!(
+        "mcapdecode-ros2msg-nested-overrides-{}",
+        std::process::id()
+    ));
+    std::fs::create_dir_all(dir.join("std_msgs/msg")).unwrap();
+    std::fs::create_dir_all(dir.join("test_msgs/msg")).unwrap();
+    std::fs::write(
+        dir.join("std_msgs/msg/Header.msg"),
+        "builtin_interfaces/Time stamp\nstring frame_id",
+    )
+    .unwrap();
+    std::fs::write(dir.join("test_msgs/msg/Point.msg"), "float64 x\nfloat64 y").unwrap();
+    std::fs::write(
+        dir.join("test_msgs/msg/Stamped.msg"),
+        "std_msgs/Header header\nPoint[] points",
+    )
+    .unwrap();
+
+    let decoder = Ros2MsgDecoder::new()
+        .with_schema_override_dir(&dir)
+        .unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let topic_decoder = decoder
+        .build_topic_decoder("test_msgs/msg/Stamped", b"")
+        .unwrap();
+    let names: Vec<_> = topic_decoder
+        .field_defs()
+        .iter()
+        .map(|f| f.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["header", "points"]);
+}
+
+#[test]
+fn parse_field_units_from_trailing_comments() {
+    let msg = r#"
+# leading comment lines are not field comments
+float64 speed # m/s
+float64 yaw_rate  # yaw rate [rad/s]
+float64 temperature # unit: degC
+float64 heading # rad
+string label "a#b" # free text, not a unit
+int32 LIMIT=10 # [m]
+uint8 level # 0 to 255
+"#;
+    let result = parse_msg("test_msgs/msg/Units", msg).unwrap();
+    let units: Vec<_> = result
+        .fields
+        .iter()
+        .map(|f| (f.name.as_str(), f.meta.unit.as_deref()))
+        .collect();
+    assert_eq!(
+        units,
+        vec![
+            ("speed", Some("m/s")),
+            ("yaw_rate", Some("rad/s")),
+            ("temperature", Some("degC")),
+            ("heading", Some("rad")),
+            ("label", None),
+            ("level", None),
+        ]
+    );
+}
+
+#[test]
+fn field_units_are_exposed_as_field_def_metadata() {
+    let topic_decoder = Ros2MsgDecoder::new()
+        .build_topic_decoder("test_msgs/msg/Speed", b"float64 speed # m/s\nfloat64 gain")
+        .unwrap();
+    let field_defs = topic_decoder.field_defs();
+    assert_eq!(field_defs[0].unit(), Some("m/s"));
+    assert_eq!(field_defs[1].unit(), None);
+}
+
+#[test]
+fn bounded_types_are_exposed_as_max_len_metadata() {
+    let topic_decoder = Ros2MsgDecoder::new()
+        .build_topic_decoder(
+            "test_msgs/msg/Bounded",
+            b"string<=8 name\nint32[<=3] values\nint32[] items\nstring<=4[2] codes",
+        )
+        .unwrap();
+    let field_defs = topic_decoder.field_defs();
+    let bounds: Vec<_> = field_defs.iter().map(|f| f.max_len()).collect();
+    assert_eq!(bounds, vec![Some(8), Some(3), None, Some(4)]);
+}
+
+#[test]
+fn time_fields_are_marked_as_timestamps() {
+    let bundle = "\
+builtin_interfaces/Time stamp
+builtin_interfaces/Duration timeout
+================================================================================
+MSG: builtin_interfaces/Time
+int32 sec
+uint32 nanosec
+================================================================================
+MSG: builtin_interfaces/Duration
+int32 sec
+uint32 nanosec
+";
+    let topic_decoder = Ros2MsgDecoder::new()
+        .build_topic_decoder("test_msgs/msg/Deadline", bundle.as_bytes())
+        .unwrap();
+    let field_defs = topic_decoder.field_defs();
+    assert!(field_defs[0].is_timestamp());
+    assert!(!field_defs[1].is_timestamp());
+}
+
+/// `re_ros_msg` errors carry no location; the failing line is located by
+/// re-parsing line by line.
+#[test]
+fn resolve_for_cdr_reports_syntax_error_line() {
+    let msg = "int32 x\n# comment\n  int32[ y\n";
+    let Err(DecoderError::SchemaParse { source, .. }) =
+        resolve_for_cdr("pkg/msg/Foo", msg.as_bytes())
+    else {
+        panic!("unclosed array bracket should fail to parse");
+    };
+    let syntax = source
+        .downcast_ref::<SchemaSyntaxError>()
+        .expect("source should be a syntax error");
+    assert_eq!(syntax.section.as_deref(), Some("pkg/msg/Foo"));
+    assert_eq!((syntax.line, syntax.column), (3, 3));
+    assert_eq!(syntax.snippet, "  int32[ y");
+}
@@ -1,16 +1,36 @@
-use std::{path::Path, sync::Arc};
+use std::{collections::HashMap, path::Path, sync::Arc};
 
 use arrow::{datatypes::SchemaRef, record_batch::RecordBatch};
-use mcapdecode_arrow::{arrow_value_rows_to_record_batch, field_defs_to_arrow_schema};
-use mcapdecode_core::DecodedMessage;
+use mcapdecode_arrow::{
+    ConvertOptions, field_defs_to_arrow_schema_cached,
+    try_arrow_value_rows_to_record_batch_with_report, with_sorted_map_keys,
+};
+use mcapdecode_core::{DecodedMessage, FieldDefs};
+use memmap2::Mmap;
 
-use crate::{McapReader, McapReaderError, reader::TopicDecodeContext};
+use crate::{
+    FollowOptions, McapReader, McapReaderError,
+    dedup::PayloadDedup,
+    follow::FollowEvent,
+    reader::{TopicDecodeContext, ensure_supported_compressions},
+    record_batches::RecordBatches,
+    skip::MessageSkip,
+};
 
 struct TopicBatchContext {
     decode: TopicDecodeContext,
     arrow_schema: SchemaRef,
 }
 
+/// State of one topic of [`McapReaderArrowExt::for_each_record_batch_multi`].
+struct TopicBatchReader {
+    topic: String,
+    context: TopicBatchContext,
+    skip: MessageSkip,
+    dedup: PayloadDedup,
+    rows: Vec<DecodedMessage>,
+}
+
 pub trait McapReaderArrowExt {
     /// Read all messages for a topic and emit Arrow RecordBatches to callback.
     ///
@@ -22,6 +42,45 @@ pub trait McapReaderArrowExt {
         topic: &str,
         callback: impl FnMut(RecordBatch) -> Result<(), Box<dyn std::error::Error + Send + Sync>>,
     ) -> Result<(), McapReaderError>;
+
+    /// Like [`for_each_record_batch`](Self::for_each_record_batch), but
+    /// return the batches as an iterator, e.g. to compose with iterator
+    /// adapters or feed a query engine, instead of passing them to a
+    /// callback.
+    ///
+    /// Reading runs on a background thread, so errors (including an unknown
+    /// topic) are yielded by the iterator rather than returned here.
+    fn record_batches(&self, path: &Path, topic: &str) -> RecordBatches;
+
+    /// Read all messages of several topics in a single pass over the file and
+    /// emit each topic's Arrow RecordBatches to callback with its topic.
+    ///
+    /// Every topic decoder is built before the first message is read, then
+    /// messages are demultiplexed by channel as the file is scanned once, so
+    /// files with many requested topics are not re-read per topic. Batches
+    /// of different topics are emitted interleaved, as they fill up, and
+    /// the last partial batch of each topic is emitted at the end, in the
+    /// order of `topics`. Messages are decoded on the calling thread.
+    fn for_each_record_batch_multi(
+        &self,
+        path: &Path,
+        topics: &[impl AsRef<str>],
+        callback: impl FnMut(&str, RecordBatch) -> Result<(), Box<dyn std::error::Error + Send + Sync>>,
+    ) -> Result<(), McapReaderError>;
+
+    /// Follow a topic of an MCAP file that is still being written and emit
+    /// Arrow RecordBatches to callback as messages are appended.
+    ///
+    /// A partial batch is emitted whenever all data written so far has been
+    /// read. See [`McapReader::follow_decoded_messages`] for when following
+    /// stops.
+    fn follow_record_batches(
+        &self,
+        path: &Path,
+        topic: &str,
+        options: &FollowOptions,
+        callback: impl FnMut(RecordBatch) -> Result<(), Box<dyn std::error::Error + Send + Sync>>,
+    ) -> Result<(), McapReaderError>;
 }
 
 impl McapReaderArrowExt for McapReader {
@@ -34,24 +93,231 @@ impl McapReaderArrowExt for McapReader {
         let mmap = self.mmap_file(path)?;
         let summary = self.read_summary(path, &mmap)?;
         let context = resolve_topic_batch_context(self, &summary, topic)?;
-        let mut rows = Vec::with_capacity(self.batch_size());
-        self.for_each_decoded_message_impl(
+        self.for_each_record_batch_impl(
             &mmap,
             &summary,
             &context.decode,
+            &context.arrow_schema,
             topic,
-            &mut |decoded| {
-                push_decoded_message(
-                    self.batch_size(),
-                    &context.arrow_schema,
-                    &mut rows,
-                    decoded,
-                    &mut callback,
-                )
+            &mut callback,
+        )
+    }
+
+    fn record_batches(&self, path: &Path, topic: &str) -> RecordBatches {
+        RecordBatches::spawn(self.clone(), path.to_path_buf(), topic.to_string())
+    }
+
+    fn for_each_record_batch_multi(
+        &self,
+        path: &Path,
+        topics: &[impl AsRef<str>],
+        mut callback: impl FnMut(
+            &str,
+            RecordBatch,
+        ) -> Result<(), Box<dyn std::error::Error + Send + Sync>>,
+    ) -> Result<(), McapReaderError> {
+        let mmap = self.mmap_file(path)?;
+        let summary = self.read_summary(path, &mmap)?;
+        ensure_supported_compressions(&summary)?;
+
+        let mut readers: Vec<TopicBatchReader> = Vec::with_capacity(topics.len());
+        let mut by_channel = HashMap::new();
+        for topic in topics {
+            let topic = topic.as_ref();
+            if readers.iter().any(|reader| reader.topic == topic) {
+                continue;
+            }
+            let context = resolve_topic_batch_context(self, &summary, topic)?;
+            let channel_id = context.decode.channel_id;
+            by_channel.insert(channel_id, readers.len());
+            readers.push(TopicBatchReader {
+                topic: topic.to_string(),
+                skip: self.channel_skip(&mmap, &summary, channel_id)?,
+                dedup: PayloadDedup::default(),
+                rows: Vec::with_capacity(self.batch_size()),
+                context,
+            });
+        }
+
+        for message in mcap::MessageStream::new(&mmap)? {
+            if self.is_cancelled() || readers.iter().all(|reader| reader.skip.is_done()) {
+                break;
+            }
+            let message = message?;
+            let Some(reader) = by_channel
+                .get(&message.channel.id)
+                .map(|idx| &mut readers[*idx])
+            else {
+                continue;
+            };
+            if reader.skip.skips(message.log_time) {
+                continue;
+            }
+            if self.dedup() && reader.dedup.is_repeat(&message.data) {
+                continue;
+            }
+            let topic = reader.topic.as_str();
+            let decoded = self.decode_message(
+                &reader.context.decode,
+                topic,
+                message.log_time,
+                message.publish_time,
+                &message.data,
+            )?;
+            self.push_decoded_message(
+                topic,
+                &reader.context.arrow_schema,
+                &mut reader.rows,
+                decoded,
+                &mut |batch| callback(topic, batch),
+            )?;
+        }
+        if self.is_cancelled() {
+            return Ok(());
+        }
+
+        for reader in &mut readers {
+            let topic = reader.topic.as_str();
+            self.flush_batch(
+                topic,
+                &reader.context.arrow_schema,
+                &mut reader.rows,
+                &mut |batch| callback(topic, batch),
+            )?;
+        }
+        Ok(())
+    }
+
+    fn follow_record_batches(
+        &self,
+        path: &Path,
+        topic: &str,
+        options: &FollowOptions,
+        mut callback: impl FnMut(RecordBatch) -> Result<(), Box<dyn std::error::Error + Send + Sync>>,
+    ) -> Result<(), McapReaderError> {
+        let mut arrow_schema: Option<SchemaRef> = None;
+        let mut rows = Vec::with_capacity(self.batch_size());
+        self.follow_impl(path, topic, options, &mut |event| match event {
+            FollowEvent::Subscribed {
+                context,
+                schema_name,
+            } => {
+                if context.field_defs.is_empty() {
+                    return Err(McapReaderError::EmptyDerivedSchema {
+                        topic: topic.to_string(),
+                        schema_name: schema_name.to_string(),
+                    });
+                }
+                arrow_schema = Some(self.arrow_schema(&context.field_defs));
+                Ok(())
+            }
+            FollowEvent::Message(decoded) => match &arrow_schema {
+                Some(schema) => {
+                    self.push_decoded_message(topic, schema, &mut rows, decoded, &mut callback)
+                }
+                None => Ok(()),
             },
-        )?;
+            FollowEvent::CaughtUp => match &arrow_schema {
+                Some(schema) => self.flush_batch(topic, schema, &mut rows, &mut callback),
+                None => Ok(()),
+            },
+        })?;
+
+        match &arrow_schema {
+            Some(_) if self.is_cancelled() => Ok(()),
+            Some(schema) => self.flush_batch(topic, schema, &mut rows, &mut callback),
+            None => Ok(()),
+        }
+    }
+}
+
+impl McapReader {
+    pub(crate) fn for_each_record_batch_impl<F>(
+        &self,
+        mmap: &Mmap,
+        summary: &mcap::read::Summary,
+        context: &TopicDecodeContext,
+        arrow_schema: &SchemaRef,
+        topic: &str,
+        callback: &mut F,
+    ) -> Result<(), McapReaderError>
+    where
+        F: FnMut(RecordBatch) -> Result<(), Box<dyn std::error::Error + Send + Sync>>,
+    {
+        let mut rows = Vec::with_capacity(self.batch_size());
+        self.for_each_decoded_message_impl(mmap, summary, context, topic, &mut |decoded| {
+            self.push_decoded_message(topic, arrow_schema, &mut rows, decoded, callback)
+        })?;
+        if self.is_cancelled() {
+            return Ok(());
+        }
+
+        self.flush_batch(topic, arrow_schema, &mut rows, callback)
+    }
+
+    pub(crate) fn arrow_schema(&self, field_defs: &FieldDefs) -> SchemaRef {
+        let schema = field_defs_to_arrow_schema_cached(field_defs);
+        if self.sort_map_keys() {
+            Arc::new(with_sorted_map_keys(&schema))
+        } else {
+            schema
+        }
+    }
 
-        flush_batch(&context.arrow_schema, &mut rows, &mut callback)
+    fn flush_batch<F>(
+        &self,
+        topic: &str,
+        schema: &SchemaRef,
+        rows: &mut Vec<DecodedMessage>,
+        callback: &mut F,
+    ) -> Result<(), McapReaderError>
+    where
+        F: FnMut(RecordBatch) -> Result<(), Box<dyn std::error::Error + Send + Sync>>,
+    {
+        if rows.is_empty() {
+            return Ok(());
+        }
+
+        let options = ConvertOptions {
+            duplicate_map_keys: self.duplicate_map_keys(),
+            widening: self.widening(),
+            struct_mismatch: self.struct_mismatch(),
+        };
+        let (batch, report) = try_arrow_value_rows_to_record_batch_with_report(
+            schema, rows, &options,
+        )
+        .map_err(|source| McapReaderError::ArrowConvertFailed {
+            topic: topic.to_string(),
+            source,
+        })?;
+        rows.clear();
+        if report.aligned_structs > 0
+            && let Some(stats) = self.decode_stats()
+        {
+            stats.record_aligned_structs(topic, report.aligned_structs);
+        }
+        if let Some(stats) = self.column_stats() {
+            stats.record(topic, &batch);
+        }
+        callback(batch).map_err(McapReaderError::Callback)
+    }
+
+    fn push_decoded_message<F>(
+        &self,
+        topic: &str,
+        schema: &SchemaRef,
+        rows: &mut Vec<DecodedMessage>,
+        decoded: DecodedMessage,
+        callback: &mut F,
+    ) -> Result<(), McapReaderError>
+    where
+        F: FnMut(RecordBatch) -> Result<(), Box<dyn std::error::Error + Send + Sync>>,
+    {
+        rows.push(decoded);
+        if rows.len() >= self.batch_size() {
+            self.flush_batch(topic, schema, rows, callback)?;
+        }
+        Ok(())
     }
 }
 
@@ -69,7 +335,7 @@ fn resolve_topic_batch_context(
         });
     }
 
-    let arrow_schema = Arc::new(field_defs_to_arrow_schema(&decode.field_defs));
+    let arrow_schema = reader.arrow_schema(&decode.field_defs);
 
     Ok(TopicBatchContext {
         decode,
@@ -90,46 +356,9 @@ fn get_schema_name(summary: &mcap::read::Summary, topic: &str) -> Result<String,
         });
     }
 
-    let schema = channel
+    Ok(channel
         .schema
         .as_ref()
-        .ok_or_else(|| McapReaderError::SchemaNotAvailable {
-            topic: channel.topic.clone(),
-            channel_id: channel.id,
-        })?;
-    Ok(schema.name.clone())
-}
-
-fn flush_batch<F>(
-    schema: &SchemaRef,
-    rows: &mut Vec<DecodedMessage>,
-    callback: &mut F,
-) -> Result<(), McapReaderError>
-where
-    F: FnMut(RecordBatch) -> Result<(), Box<dyn std::error::Error + Send + Sync>>,
-{
-    if rows.is_empty() {
-        return Ok(());
-    }
-
-    let batch = arrow_value_rows_to_record_batch(schema, rows.as_slice());
-    rows.clear();
-    callback(batch).map_err(McapReaderError::Callback)
-}
-
-fn push_decoded_message<F>(
-    batch_size: usize,
-    schema: &SchemaRef,
-    rows: &mut Vec<DecodedMessage>,
-    decoded: DecodedMessage,
-    callback: &mut F,
-) -> Result<(), McapReaderError>
-where
-    F: FnMut(RecordBatch) -> Result<(), Box<dyn std::error::Error + Send + Sync>>,
-{
-    rows.push(decoded);
-    if rows.len() >= batch_size {
-        flush_batch(schema, rows, callback)?;
-    }
-    Ok(())
+        .map(|schema| schema.name.clone())
+        .unwrap_or_default())
 }
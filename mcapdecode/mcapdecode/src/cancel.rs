@@ -0,0 +1,35 @@
+//! Cooperative cancellation of reads (`with_cancellation_token`).
+
+use std::sync::{
+    Arc,
+    atomic::{AtomicBool, Ordering},
+};
+
+/// Flag that stops the reads of the readers built with it, e.g. from another
+/// thread or a signal handler.
+///
+/// Once [`cancel`](Self::cancel)led, reads return `Ok(())` before emitting
+/// their next message or batch; messages read but not yet emitted are
+/// dropped. Unlike an error returned from the callback, this tells a clean
+/// early stop apart from a failure: check [`is_cancelled`](Self::is_cancelled)
+/// after the read returns. Clones share the flag.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stop the reads using this token. Reads started afterwards return
+    /// immediately.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
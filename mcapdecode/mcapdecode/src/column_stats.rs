@@ -0,0 +1,53 @@
+//! Column statistics collected per topic while reading (`with_column_stats`).
+
+use std::{collections::BTreeMap, fmt, sync::Mutex};
+
+use arrow::record_batch::RecordBatch;
+use mcapdecode_arrow::{ColumnStats, ColumnStatsAccumulator};
+
+/// Collects [`ColumnStats`] of the Arrow record batches read, per topic,
+/// e.g. for data-quality reports without re-reading the converted output.
+///
+/// Pass one instance to `McapReaderBuilder::with_column_stats` and read it
+/// after reading; readers built with the same instance add to the same
+/// statistics. Batches are counted as they are built, before they are
+/// passed to the caller, including the `@log_time` / `@publish_time`
+/// columns.
+#[derive(Debug, Default)]
+pub struct TopicColumnStats {
+    topics: Mutex<BTreeMap<String, ColumnStatsAccumulator>>,
+}
+
+impl TopicColumnStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the values of `batch`, read from `topic`.
+    pub fn record(&self, topic: &str, batch: &RecordBatch) {
+        let mut topics = self.topics.lock().unwrap_or_else(|e| e.into_inner());
+        topics.entry(topic.to_string()).or_default().update(batch);
+    }
+
+    /// Statistics of the columns of each topic read so far, ordered by
+    /// topic.
+    pub fn topics(&self) -> Vec<(String, Vec<ColumnStats>)> {
+        let topics = self.topics.lock().unwrap_or_else(|e| e.into_inner());
+        topics
+            .iter()
+            .map(|(topic, columns)| (topic.clone(), columns.stats()))
+            .collect()
+    }
+}
+
+/// One line per topic and column: `'<topic>' <column stats>`.
+impl fmt::Display for TopicColumnStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (topic, columns) in self.topics() {
+            for column in columns {
+                writeln!(f, "'{topic}' {column}")?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,88 @@
+//! One-call conversion of a topic to an Arrow record batch writer.
+
+use std::{path::Path, sync::Arc};
+
+use arrow::{
+    datatypes::SchemaRef,
+    record_batch::{RecordBatch, RecordBatchWriter},
+};
+use mcapdecode_arrow::{
+    ArrowConvertError, FlattenPolicy, field_defs_to_record_batch_schema, flatten_record_batch,
+};
+
+use crate::{McapReader, McapReaderArrowExt, McapReaderError};
+
+/// Options of [`convert_topic`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConvertTopicOptions {
+    /// Flattening applied to every batch before it is written; `None` writes
+    /// the nested columns as decoded.
+    pub flatten: Option<FlattenPolicy>,
+    /// Separator of flattened column paths (default: `.`).
+    pub separator: Option<char>,
+    /// Messages per batch (default: the reader's default batch size).
+    pub batch_size: Option<usize>,
+}
+
+impl ConvertTopicOptions {
+    fn reader(&self) -> McapReader {
+        let builder = McapReader::builder().with_default_decoders();
+        match self.batch_size {
+            Some(size) => builder.with_batch_size(size),
+            None => builder,
+        }
+        .build()
+    }
+
+    fn flatten(&self, topic: &str, batch: RecordBatch) -> Result<RecordBatch, McapReaderError> {
+        let Some(policy) = &self.flatten else {
+            return Ok(batch);
+        };
+        flatten_record_batch(&batch, self.separator, policy)
+            .map(|(batch, _dropped)| batch)
+            .map_err(|source| McapReaderError::ArrowConvertFailed {
+                topic: topic.to_string(),
+                source: ArrowConvertError::Arrow(source),
+            })
+    }
+}
+
+/// Decode every message of `topic` in the MCAP file at `path` with the
+/// built-in decoders, convert them to record batches flattened as `options`
+/// say, write them to `sink` and close it.
+///
+/// Returns the number of rows written. Errors of `sink` are returned as
+/// [`McapReaderError::Callback`]. Writers that need the schema up front
+/// (e.g. Parquet's `ArrowWriter`) can be created with
+/// [`convert_topic_schema`].
+pub fn convert_topic(
+    path: impl AsRef<Path>,
+    topic: &str,
+    mut sink: impl RecordBatchWriter,
+    options: &ConvertTopicOptions,
+) -> Result<u64, McapReaderError> {
+    let reader = options.reader();
+    let mut rows = 0;
+    reader.for_each_record_batch(path.as_ref(), topic, |batch| {
+        let batch = options.flatten(topic, batch)?;
+        rows += batch.num_rows() as u64;
+        sink.write(&batch)?;
+        Ok(())
+    })?;
+    sink.close()
+        .map_err(|e| McapReaderError::Callback(Box::new(e)))?;
+    Ok(rows)
+}
+
+/// Schema of the batches [`convert_topic`] writes for `topic` with
+/// `options`, derived without decoding any message.
+pub fn convert_topic_schema(
+    path: impl AsRef<Path>,
+    topic: &str,
+    options: &ConvertTopicOptions,
+) -> Result<SchemaRef, McapReaderError> {
+    let field_defs = options.reader().topic_field_defs(path.as_ref(), topic)?;
+    let schema = Arc::new(field_defs_to_record_batch_schema(&field_defs));
+    let batch = options.flatten(topic, RecordBatch::new_empty(schema))?;
+    Ok(batch.schema())
+}
@@ -0,0 +1,27 @@
+//! Filter for messages that repeat the payload of the previous message.
+
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+/// Tracks the payload hash of the last emitted message of a topic.
+#[derive(Debug, Default)]
+pub(crate) struct PayloadDedup {
+    last: Option<u64>,
+}
+
+impl PayloadDedup {
+    /// Record `data` and return whether it hashes like the previous payload.
+    pub(crate) fn is_repeat(&mut self, data: &[u8]) -> bool {
+        self.is_repeat_hash(payload_hash(data))
+    }
+
+    /// Like [`Self::is_repeat`] for an already computed [`payload_hash`].
+    pub(crate) fn is_repeat_hash(&mut self, hash: u64) -> bool {
+        self.last.replace(hash) == Some(hash)
+    }
+}
+
+pub(crate) fn payload_hash(data: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    data.hash(&mut hasher);
+    hasher.finish()
+}
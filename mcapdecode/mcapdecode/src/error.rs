@@ -1,6 +1,8 @@
 //! Error types for the MCAP reader.
 
-use mcapdecode_core::DecoderError;
+use std::io;
+
+use mcapdecode_core::{DecoderError, ErrorCategory};
 
 /// Errors produced by [`McapReader`](crate::McapReader).
 #[derive(Debug, thiserror::Error)]
@@ -17,6 +19,13 @@ pub enum McapReaderError {
     #[error("MCAP summary not available in {path}")]
     SummaryNotAvailable { path: String },
 
+    /// Chunks are compressed with a codec the `mcap` crate cannot decompress.
+    #[error(
+        "unsupported chunk compression '{compression}' (supported: {})",
+        crate::SUPPORTED_COMPRESSIONS.join(", ")
+    )]
+    UnsupportedCompression { compression: String },
+
     /// The MCAP summary section has no statistics record.
     #[error("MCAP summary stats not available in {path}")]
     StatsNotAvailable { path: String },
@@ -64,7 +73,134 @@ pub enum McapReaderError {
         source: DecoderError,
     },
 
+    /// Decoded messages could not be converted to an Arrow RecordBatch.
+    #[cfg(feature = "arrow")]
+    #[error("Arrow conversion failed for topic '{topic}': {source}")]
+    ArrowConvertFailed {
+        topic: String,
+        #[source]
+        source: mcapdecode_arrow::ArrowConvertError,
+    },
+
+    /// A reader option cannot be applied to the file being read.
+    #[error("invalid option {option}: {detail}")]
+    InvalidOption {
+        option: &'static str,
+        detail: String,
+    },
+
+    /// [`McapReader::prepare`](crate::McapReader::prepare) found one or more
+    /// problems; each is reported as its own error.
+    #[error("{} problem(s) preparing {path}: {}", errors.len(), join_errors(errors))]
+    PrepareFailed {
+        path: String,
+        errors: Vec<McapReaderError>,
+    },
+
     /// An error returned by the user-supplied callback in reader iteration APIs.
     #[error(transparent)]
     Callback(Box<dyn std::error::Error + Send + Sync>),
 }
+
+impl McapReaderError {
+    /// Returns a stable, machine-readable code for this error variant.
+    pub fn code(&self) -> &'static str {
+        match self {
+            McapReaderError::Io(_) => "io",
+            McapReaderError::Mcap(_) => "mcap",
+            McapReaderError::SummaryNotAvailable { .. } => "summary_not_available",
+            McapReaderError::UnsupportedCompression { .. } => "unsupported_compression",
+            McapReaderError::StatsNotAvailable { .. } => "stats_not_available",
+            McapReaderError::SchemaNotAvailable { .. } => "schema_not_available",
+            McapReaderError::TopicNotFound { .. } => "topic_not_found",
+            McapReaderError::NoDecoder { .. } => "no_decoder",
+            McapReaderError::EmptyDerivedSchema { .. } => "empty_derived_schema",
+            McapReaderError::MultipleChannels { .. } => "multiple_channels",
+            McapReaderError::SchemaDerivationFailed { .. } => "schema_derivation_failed",
+            McapReaderError::MessageDecodeFailed { .. } => "message_decode_failed",
+            #[cfg(feature = "arrow")]
+            McapReaderError::ArrowConvertFailed { .. } => "arrow_convert_failed",
+            McapReaderError::InvalidOption { .. } => "invalid_option",
+            McapReaderError::PrepareFailed { .. } => "prepare_failed",
+            McapReaderError::Callback(_) => "callback",
+        }
+    }
+
+    /// Returns the coarse category of this error.
+    ///
+    /// Decoder failures report the category of the wrapped [`DecoderError`].
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            McapReaderError::Io(_) => ErrorCategory::IoIssue,
+            McapReaderError::Mcap(mcap::McapError::Io(_)) => ErrorCategory::IoIssue,
+            McapReaderError::Mcap(_)
+            | McapReaderError::SummaryNotAvailable { .. }
+            | McapReaderError::UnsupportedCompression { .. }
+            | McapReaderError::StatsNotAvailable { .. } => ErrorCategory::PayloadIssue,
+            #[cfg(feature = "arrow")]
+            McapReaderError::ArrowConvertFailed { .. } => ErrorCategory::PayloadIssue,
+            McapReaderError::SchemaNotAvailable { .. }
+            | McapReaderError::EmptyDerivedSchema { .. }
+            | McapReaderError::MultipleChannels { .. } => ErrorCategory::SchemaIssue,
+            McapReaderError::TopicNotFound { .. }
+            | McapReaderError::NoDecoder { .. }
+            | McapReaderError::InvalidOption { .. } => ErrorCategory::ConfigIssue,
+            McapReaderError::PrepareFailed { errors, .. } => errors
+                .first()
+                .map_or(ErrorCategory::ConfigIssue, McapReaderError::category),
+            McapReaderError::SchemaDerivationFailed { source, .. }
+            | McapReaderError::MessageDecodeFailed { source, .. } => source.category(),
+            McapReaderError::Callback(err) => match err.downcast_ref::<McapReaderError>() {
+                Some(inner) => inner.category(),
+                None if err.is::<io::Error>() => ErrorCategory::IoIssue,
+                None => ErrorCategory::ConfigIssue,
+            },
+        }
+    }
+
+    /// Returns `true` if retrying the same operation could succeed.
+    ///
+    /// Only transient I/O conditions (interrupted, timed out, would block,
+    /// busy) and failed schema lookups are considered retryable; everything
+    /// else is deterministic for a given input and configuration.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            McapReaderError::Io(err) | McapReaderError::Mcap(mcap::McapError::Io(err)) => {
+                is_transient_io(err)
+            }
+            McapReaderError::SchemaDerivationFailed { source, .. }
+            | McapReaderError::MessageDecodeFailed { source, .. } => source.is_retryable(),
+            McapReaderError::PrepareFailed { errors, .. } => {
+                errors.iter().all(McapReaderError::is_retryable)
+            }
+            McapReaderError::Callback(err) => {
+                if let Some(inner) = err.downcast_ref::<McapReaderError>() {
+                    inner.is_retryable()
+                } else if let Some(io_err) = err.downcast_ref::<io::Error>() {
+                    is_transient_io(io_err)
+                } else {
+                    false
+                }
+            }
+            _ => false,
+        }
+    }
+}
+
+fn join_errors(errors: &[McapReaderError]) -> String {
+    errors
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+fn is_transient_io(err: &io::Error) -> bool {
+    matches!(
+        err.kind(),
+        io::ErrorKind::Interrupted
+            | io::ErrorKind::TimedOut
+            | io::ErrorKind::WouldBlock
+            | io::ErrorKind::ResourceBusy
+    )
+}
@@ -0,0 +1,225 @@
+//! Tail mode for MCAP files that are still being written.
+
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    fs,
+    io::Read,
+    path::Path,
+    thread,
+    time::{Duration, Instant},
+};
+
+use mcap::{
+    records::Record,
+    sans_io::{LinearReadEvent, LinearReader},
+};
+use mcapdecode_core::DecodedMessage;
+
+use crate::{McapReader, McapReaderError, dedup::PayloadDedup, reader::TopicDecodeContext};
+
+/// Options for following a live-growing MCAP file.
+#[derive(Debug, Clone)]
+pub struct FollowOptions {
+    poll_interval: Duration,
+    idle_timeout: Option<Duration>,
+}
+
+impl FollowOptions {
+    pub fn new() -> Self {
+        Self {
+            poll_interval: Duration::from_millis(100),
+            idle_timeout: None,
+        }
+    }
+
+    /// Set how often the file is checked for appended data (default: 100ms).
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Stop following when no data was appended for `timeout`
+    /// (default: follow until the recorder finishes the file).
+    pub fn with_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.idle_timeout = Some(timeout);
+        self
+    }
+}
+
+impl Default for FollowOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Progress reported while following a file.
+pub(crate) enum FollowEvent<'a> {
+    /// The channel of the followed topic was found.
+    #[cfg_attr(not(feature = "arrow"), allow(dead_code))]
+    Subscribed {
+        context: &'a TopicDecodeContext,
+        schema_name: &'a str,
+    },
+    Message(DecodedMessage),
+    /// All data written so far has been read.
+    CaughtUp,
+}
+
+impl McapReader {
+    /// Follow a topic of an MCAP file that is still being written and emit
+    /// decoded messages to callback as they are appended.
+    ///
+    /// The file is read linearly, so no summary section is required. Once the
+    /// end of the written data is reached, the file is polled for new records.
+    /// Following stops when the recorder writes the end of the data section,
+    /// when the idle timeout of `options` expires, or when the reader's
+    /// [`CancellationToken`](crate::CancellationToken) is cancelled.
+    ///
+    /// Chunks are only read once they are complete, so recorders must write
+    /// each chunk record in one go (e.g. `mcap::WriteOptions::disable_seeking`).
+    pub fn follow_decoded_messages(
+        &self,
+        path: &Path,
+        topic: &str,
+        options: &FollowOptions,
+        mut callback: impl FnMut(DecodedMessage) -> Result<(), Box<dyn std::error::Error + Send + Sync>>,
+    ) -> Result<(), McapReaderError> {
+        self.follow_impl(path, topic, options, &mut |event| match event {
+            FollowEvent::Message(decoded) => callback(decoded).map_err(McapReaderError::Callback),
+            FollowEvent::Subscribed { .. } | FollowEvent::CaughtUp => Ok(()),
+        })
+    }
+
+    pub(crate) fn follow_impl<F>(
+        &self,
+        path: &Path,
+        topic: &str,
+        options: &FollowOptions,
+        callback: &mut F,
+    ) -> Result<(), McapReaderError>
+    where
+        F: FnMut(FollowEvent<'_>) -> Result<(), McapReaderError>,
+    {
+        let mut file = fs::File::open(path)?;
+        let mut linear = LinearReader::new();
+        let mut schemas = HashMap::<u16, mcap::Schema<'static>>::new();
+        let mut context: Option<TopicDecodeContext> = None;
+        let mut last_data = Instant::now();
+        let mut caught_up = false;
+        let mut dedup = PayloadDedup::default();
+        let mut skip = self.message_skip();
+
+        while let Some(event) = linear.next_event() {
+            if self.is_cancelled() {
+                return Ok(());
+            }
+            let event = event.map_err(|e| match e {
+                mcap::McapError::UnsupportedCompression(compression) => {
+                    McapReaderError::UnsupportedCompression { compression }
+                }
+                e => e.into(),
+            })?;
+            match event {
+                LinearReadEvent::ReadRequest(need) => {
+                    let read = file.read(linear.insert(need))?;
+                    if read > 0 {
+                        // Never report EOF to the linear reader: the file may still grow.
+                        linear.notify_read(read);
+                        last_data = Instant::now();
+                        caught_up = false;
+                        continue;
+                    }
+                    if !caught_up {
+                        caught_up = true;
+                        callback(FollowEvent::CaughtUp)?;
+                    }
+                    if options
+                        .idle_timeout
+                        .is_some_and(|timeout| last_data.elapsed() >= timeout)
+                    {
+                        break;
+                    }
+                    thread::sleep(options.poll_interval);
+                }
+                LinearReadEvent::Record { opcode, data } => {
+                    let record = mcap::parse_record(opcode, data)?;
+                    match record {
+                        Record::Schema { header, data } => {
+                            schemas.insert(
+                                header.id,
+                                mcap::Schema {
+                                    id: header.id,
+                                    name: header.name,
+                                    encoding: header.encoding,
+                                    data: Cow::Owned(data.into_owned()),
+                                },
+                            );
+                        }
+                        Record::Channel(channel) if channel.topic == topic => {
+                            if let Some(existing) = &context {
+                                if existing.channel_id == channel.id {
+                                    continue;
+                                }
+                                return Err(McapReaderError::MultipleChannels {
+                                    topic: topic.to_string(),
+                                });
+                            }
+                            // Schema ID 0 marks a channel without a schema.
+                            let schema = match channel.schema_id {
+                                0 => None,
+                                id => Some(schemas.get(&id).ok_or_else(|| {
+                                    McapReaderError::SchemaNotAvailable {
+                                        topic: topic.to_string(),
+                                        channel_id: channel.id,
+                                    }
+                                })?),
+                            };
+                            let subscribed = self.build_topic_decode_context(
+                                topic,
+                                channel.id,
+                                &channel.message_encoding,
+                                schema,
+                            )?;
+                            callback(FollowEvent::Subscribed {
+                                context: &subscribed,
+                                schema_name: schema.map_or("", |schema| &schema.name),
+                            })?;
+                            context = Some(subscribed);
+                        }
+                        Record::Message { header, data } => {
+                            let Some(context) = &context else {
+                                continue;
+                            };
+                            if header.channel_id != context.channel_id
+                                || skip.skips(header.log_time)
+                            {
+                                continue;
+                            }
+                            if self.dedup() && dedup.is_repeat(&data) {
+                                continue;
+                            }
+                            let decoded = self.decode_message(
+                                context,
+                                topic,
+                                header.log_time,
+                                header.publish_time,
+                                &data,
+                            )?;
+                            callback(FollowEvent::Message(decoded))?;
+                        }
+                        Record::DataEnd(_) => break,
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        if context.is_none() {
+            return Err(McapReaderError::TopicNotFound {
+                topic: topic.to_string(),
+            });
+        }
+        Ok(())
+    }
+}
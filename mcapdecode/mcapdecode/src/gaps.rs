@@ -0,0 +1,129 @@
+//! Inter-message gap and sequence-integrity analysis of a topic.
+
+use std::time::Duration;
+
+/// Gap threshold used when none is given: this many median intervals.
+const DEFAULT_THRESHOLD_FACTOR: u64 = 3;
+
+/// Timing and sequence integrity of the messages of a topic.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GapReport {
+    pub message_count: u64,
+    /// Smallest log-time delta between consecutive messages, in nanoseconds.
+    pub min_interval_ns: Option<u64>,
+    /// Median log-time delta between consecutive messages, in nanoseconds.
+    pub median_interval_ns: Option<u64>,
+    /// Largest log-time delta between consecutive messages, in nanoseconds.
+    pub max_interval_ns: Option<u64>,
+    /// Deltas above this many nanoseconds are reported as [`Self::gaps`].
+    pub threshold_ns: Option<u64>,
+    pub gaps: Vec<TimeGap>,
+    /// Breaks in the publisher sequence numbers of the message records, or
+    /// `None` when the messages carry none (all zero).
+    pub sequence_jumps: Option<Vec<SequenceJump>>,
+}
+
+impl GapReport {
+    /// Whether any gap or sequence jump was found.
+    pub fn has_issues(&self) -> bool {
+        !self.gaps.is_empty() || self.sequence_jumps.as_ref().is_some_and(|j| !j.is_empty())
+    }
+}
+
+/// Log-time delta between consecutive messages above the threshold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeGap {
+    /// Index of the first message after the gap.
+    pub index: u64,
+    /// Log time of the message before the gap.
+    pub start_ns: u64,
+    /// Log time of the message after the gap.
+    pub end_ns: u64,
+}
+
+impl TimeGap {
+    pub fn duration_ns(&self) -> u64 {
+        self.end_ns - self.start_ns
+    }
+}
+
+/// Message whose sequence number does not follow its predecessor's.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SequenceJump {
+    /// Index of the message within the topic.
+    pub index: u64,
+    pub log_time: u64,
+    pub expected: u32,
+    pub actual: u32,
+}
+
+/// Accumulates message log times and sequence numbers into a [`GapReport`].
+#[derive(Debug, Default)]
+pub(crate) struct GapAnalyzer {
+    log_times: Vec<u64>,
+    sequence_jumps: Vec<SequenceJump>,
+    has_sequence: bool,
+    last_sequence: Option<u32>,
+}
+
+impl GapAnalyzer {
+    pub(crate) fn push(&mut self, log_time: u64, sequence: u32) {
+        let index = self.log_times.len() as u64;
+        self.has_sequence |= sequence != 0;
+        if let Some(last) = self.last_sequence.replace(sequence) {
+            let expected = last.wrapping_add(1);
+            if sequence != expected {
+                self.sequence_jumps.push(SequenceJump {
+                    index,
+                    log_time,
+                    expected,
+                    actual: sequence,
+                });
+            }
+        }
+        self.log_times.push(log_time);
+    }
+
+    /// Build the report, flagging deltas above `threshold` (or above
+    /// [`DEFAULT_THRESHOLD_FACTOR`] median intervals if `None`).
+    pub(crate) fn finish(self, threshold: Option<Duration>) -> GapReport {
+        let deltas: Vec<u64> = self
+            .log_times
+            .windows(2)
+            .map(|pair| pair[1].saturating_sub(pair[0]))
+            .collect();
+        let mut sorted = deltas.clone();
+        sorted.sort_unstable();
+        let median_interval_ns = sorted.get(sorted.len() / 2).copied();
+        let threshold_ns = match threshold {
+            Some(threshold) => Some(u64::try_from(threshold.as_nanos()).unwrap_or(u64::MAX)),
+            None => {
+                median_interval_ns.map(|median| median.saturating_mul(DEFAULT_THRESHOLD_FACTOR))
+            }
+        };
+
+        let gaps = match threshold_ns {
+            Some(threshold_ns) => deltas
+                .iter()
+                .enumerate()
+                .filter(|(_, delta)| **delta > threshold_ns)
+                .map(|(idx, _)| TimeGap {
+                    index: idx as u64 + 1,
+                    start_ns: self.log_times[idx],
+                    end_ns: self.log_times[idx + 1],
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+
+        GapReport {
+            message_count: self.log_times.len() as u64,
+            min_interval_ns: sorted.first().copied(),
+            median_interval_ns,
+            max_interval_ns: sorted.last().copied(),
+            threshold_ns,
+            gaps,
+            sequence_jumps: self.has_sequence.then_some(self.sequence_jumps),
+        }
+    }
+}
@@ -1,12 +1,57 @@
 #[cfg(feature = "arrow")]
 mod arrow_ext;
+mod cancel;
+#[cfg(feature = "arrow")]
+mod column_stats;
+#[cfg(feature = "arrow")]
+mod convert;
+mod dedup;
 mod error;
+mod follow;
+mod gaps;
+mod prepare;
 mod reader;
+#[cfg(feature = "arrow")]
+mod record_batches;
+mod repack;
+mod sample;
+mod skip;
+mod stats;
+mod subscription;
+mod text;
 
 #[cfg(feature = "arrow")]
 pub use arrow_ext::McapReaderArrowExt;
+pub use cancel::CancellationToken;
+#[cfg(feature = "arrow")]
+pub use column_stats::TopicColumnStats;
+#[cfg(feature = "arrow")]
+pub use convert::{ConvertTopicOptions, convert_topic, convert_topic_schema};
 pub use error::McapReaderError;
+pub use follow::FollowOptions;
+pub use gaps::{GapReport, SequenceJump, TimeGap};
 #[cfg(feature = "arrow")]
 pub use mcapdecode_arrow as arrow;
 pub use mcapdecode_core as core;
-pub use reader::{McapReader, RawMessage, TopicInfo};
+#[cfg(feature = "jsonschema")]
+pub use mcapdecode_jsonschema as jsonschema;
+#[cfg(feature = "protobuf")]
+pub use mcapdecode_protobuf as protobuf;
+#[cfg(feature = "ros1msg")]
+pub use mcapdecode_ros1msg as ros1msg;
+#[cfg(feature = "ros2idl")]
+pub use mcapdecode_ros2idl as ros2idl;
+#[cfg(feature = "ros2msg")]
+pub use mcapdecode_ros2msg as ros2msg;
+pub use prepare::{Prepared, PreparedTopic};
+pub use reader::{
+    McapReader, RawChannel, RawChannelMessage, RawMessage, RawSchema, SUPPORTED_COMPRESSIONS,
+    TopicInfo,
+};
+#[cfg(feature = "arrow")]
+pub use record_batches::RecordBatches;
+pub use repack::{RepackCompression, RepackOptions, RepackSummary};
+pub use sample::Sample;
+pub use stats::{DecodeStats, WarningCount};
+pub use subscription::Subscription;
+pub use text::{TEXT_FIELD, TextDecoder};
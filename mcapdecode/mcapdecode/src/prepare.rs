@@ -0,0 +1,193 @@
+//! Up-front validation of the topics of a batch job (`McapReader::prepare`).
+
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "arrow")]
+use arrow::{datatypes::SchemaRef, record_batch::RecordBatch};
+use mcapdecode_core::{DecodedMessage, FieldDefs};
+use memmap2::Mmap;
+
+use crate::{
+    McapReader, McapReaderError,
+    reader::{TopicDecodeContext, ensure_supported_compressions},
+};
+
+/// Topics of an MCAP file with their decoders built and schemas derived, as
+/// returned by [`McapReader::prepare`].
+///
+/// Reads of a prepared topic reuse its decoder and the file mapping.
+pub struct Prepared {
+    reader: McapReader,
+    path: PathBuf,
+    mmap: Mmap,
+    summary: mcap::read::Summary,
+    topics: Vec<PreparedTopic>,
+}
+
+/// One topic of [`Prepared`].
+pub struct PreparedTopic {
+    pub topic: String,
+    /// `None` for schemaless channels.
+    pub schema_name: Option<String>,
+    pub field_defs: FieldDefs,
+    context: TopicDecodeContext,
+    #[cfg(feature = "arrow")]
+    arrow_schema: SchemaRef,
+}
+
+impl PreparedTopic {
+    /// Arrow schema of the batches of this topic.
+    #[cfg(feature = "arrow")]
+    pub fn arrow_schema(&self) -> &SchemaRef {
+        &self.arrow_schema
+    }
+}
+
+impl McapReader {
+    /// Build the decoders and derive the schemas of `topics` in the MCAP file
+    /// at `path`, and check the reader options against the file, without
+    /// decoding any message.
+    ///
+    /// Every topic is checked even after one fails, and all problems are
+    /// returned at once as [`McapReaderError::PrepareFailed`], so that batch
+    /// jobs fail fast on misconfiguration. Problems with the file itself
+    /// (I/O, missing summary, unsupported compression) are returned as is.
+    /// With the `arrow` feature, topics whose derived schema has no fields
+    /// fail as in [`McapReaderArrowExt::for_each_record_batch`](crate::McapReaderArrowExt::for_each_record_batch).
+    pub fn prepare(
+        &self,
+        path: &Path,
+        topics: &[impl AsRef<str>],
+    ) -> Result<Prepared, McapReaderError> {
+        let mmap = self.mmap_file(path)?;
+        let summary = self.read_summary(path, &mmap)?;
+        ensure_supported_compressions(&summary)?;
+
+        let mut errors = Vec::new();
+        if let Some(sample) = self.sample()
+            && summary
+                .chunk_indexes
+                .iter()
+                .any(|chunk_index| chunk_index.message_index_offsets.is_empty())
+        {
+            errors.push(McapReaderError::InvalidOption {
+                option: "sample",
+                detail: format!(
+                    "{sample} needs the message indexes, which {} lacks",
+                    path.display()
+                ),
+            });
+        }
+
+        let mut prepared = Vec::with_capacity(topics.len());
+        for topic in topics {
+            match self.prepare_topic(&summary, topic.as_ref()) {
+                Ok(topic) => prepared.push(topic),
+                Err(error) => errors.push(error),
+            }
+        }
+        if !errors.is_empty() {
+            return Err(McapReaderError::PrepareFailed {
+                path: path.display().to_string(),
+                errors,
+            });
+        }
+
+        Ok(Prepared {
+            reader: self.clone(),
+            path: path.to_path_buf(),
+            mmap,
+            summary,
+            topics: prepared,
+        })
+    }
+
+    fn prepare_topic(
+        &self,
+        summary: &mcap::read::Summary,
+        topic: &str,
+    ) -> Result<PreparedTopic, McapReaderError> {
+        let context = self.resolve_topic_decode_context(summary, topic)?;
+        let schema_name = summary
+            .channels
+            .get(&context.channel_id)
+            .and_then(|channel| channel.schema.as_ref())
+            .map(|schema| schema.name.clone());
+        #[cfg(feature = "arrow")]
+        if context.field_defs.is_empty() {
+            return Err(McapReaderError::EmptyDerivedSchema {
+                topic: topic.to_string(),
+                schema_name: schema_name.clone().unwrap_or_default(),
+            });
+        }
+        Ok(PreparedTopic {
+            topic: topic.to_string(),
+            schema_name,
+            field_defs: context.field_defs.clone(),
+            #[cfg(feature = "arrow")]
+            arrow_schema: self.arrow_schema(&context.field_defs),
+            context,
+        })
+    }
+}
+
+impl Prepared {
+    /// The prepared topics, in the order they were requested.
+    pub fn topics(&self) -> &[PreparedTopic] {
+        &self.topics
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Like [`McapReader::for_each_decoded_message`], for a prepared topic.
+    ///
+    /// Fails with [`McapReaderError::TopicNotFound`] if `topic` was not
+    /// prepared.
+    pub fn for_each_decoded_message(
+        &self,
+        topic: &str,
+        mut callback: impl FnMut(DecodedMessage) -> Result<(), Box<dyn std::error::Error + Send + Sync>>,
+    ) -> Result<(), McapReaderError> {
+        let prepared = self.topic(topic)?;
+        self.reader.for_each_decoded_message_impl(
+            &self.mmap,
+            &self.summary,
+            &prepared.context,
+            topic,
+            &mut |decoded| callback(decoded).map_err(McapReaderError::Callback),
+        )
+    }
+
+    /// Like [`McapReaderArrowExt::for_each_record_batch`](crate::McapReaderArrowExt::for_each_record_batch),
+    /// for a prepared topic.
+    ///
+    /// Fails with [`McapReaderError::TopicNotFound`] if `topic` was not
+    /// prepared.
+    #[cfg(feature = "arrow")]
+    pub fn for_each_record_batch(
+        &self,
+        topic: &str,
+        mut callback: impl FnMut(RecordBatch) -> Result<(), Box<dyn std::error::Error + Send + Sync>>,
+    ) -> Result<(), McapReaderError> {
+        let prepared = self.topic(topic)?;
+        self.reader.for_each_record_batch_impl(
+            &self.mmap,
+            &self.summary,
+            &prepared.context,
+            &prepared.arrow_schema,
+            topic,
+            &mut callback,
+        )
+    }
+
+    fn topic(&self, topic: &str) -> Result<&PreparedTopic, McapReaderError> {
+        self.topics
+            .iter()
+            .find(|prepared| prepared.topic == topic)
+            .ok_or_else(|| McapReaderError::TopicNotFound {
+                topic: topic.to_string(),
+            })
+    }
+}
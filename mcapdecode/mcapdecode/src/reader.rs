@@ -1,7 +1,7 @@
 //! MCAP file reader with pluggable decoder support.
 
 use std::{
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, HashMap, hash_map::Entry},
     fs, io,
     path::Path,
     sync::{
@@ -9,27 +9,69 @@ use std::{
         atomic::{AtomicBool, Ordering},
         mpsc,
     },
+    time::Duration,
 };
 
+#[cfg(feature = "arrow")]
+use mcapdecode_arrow::{ConversionOptions, DuplicateMapKeys, StructMismatch, WideningPolicy};
 use mcapdecode_core::{
-    DecodedMessage, EncodingKey, FieldDefs, MessageDecoder, MessageEncoding, SchemaEncoding,
-    TopicDecoder,
+    DecodedMessage, DepthLimitedDecoder, EncodingKey, FieldDefs, MessageDecoder, MessageEncoding,
+    SchemaEncoding, SchemaResolver, TopicDecoder, collect_warnings,
 };
+#[cfg(feature = "jsonschema")]
+use mcapdecode_jsonschema::{JsonSchemaDecoder, PayloadFormat, SchemalessDecoder};
 #[cfg(feature = "protobuf")]
 use mcapdecode_protobuf::ProtobufDecoder;
+#[cfg(feature = "ros1msg")]
+use mcapdecode_ros1msg::Ros1MsgDecoder;
 #[cfg(feature = "ros2idl")]
-use mcapdecode_ros2idl::Ros2IdlDecoder;
+use mcapdecode_ros2idl::{Ros2IdlDecoder, XTypesDecoder};
 #[cfg(feature = "ros2msg")]
 use mcapdecode_ros2msg::Ros2MsgDecoder;
 use memmap2::Mmap;
 
-use crate::error::McapReaderError;
+#[cfg(feature = "arrow")]
+use crate::column_stats::TopicColumnStats;
+use crate::{
+    cancel::CancellationToken,
+    dedup::{PayloadDedup, payload_hash},
+    error::McapReaderError,
+    gaps::{GapAnalyzer, GapReport},
+    sample::{Sample, SampledMessages},
+    skip::{MessageSkip, PlannedChunk},
+    stats::DecodeStats,
+    text::TextDecoder,
+};
+
+/// Chunk compression codecs that can be decompressed, besides uncompressed chunks.
+pub const SUPPORTED_COMPRESSIONS: &[&str] = &["zstd", "lz4"];
 
 /// Reads an MCAP file and decodes messages using registered [`MessageDecoder`]s.
+///
+/// Cloning is cheap: registered decoders are shared between clones.
+#[derive(Clone)]
 pub struct McapReader {
     decoders: HashMap<EncodingKey, Arc<dyn MessageDecoder>>,
     batch_size: usize,
     parallel: bool,
+    subscription_capacity: usize,
+    max_depth: Option<usize>,
+    dedup: bool,
+    skip_messages: u64,
+    start_time: Option<u64>,
+    sample: Option<Sample>,
+    cancellation: Option<CancellationToken>,
+    schema_resolver: Option<Arc<dyn SchemaResolver>>,
+    decode_stats: Option<Arc<DecodeStats>>,
+    #[cfg(feature = "arrow")]
+    column_stats: Option<Arc<TopicColumnStats>>,
+    sort_map_keys: bool,
+    #[cfg(feature = "arrow")]
+    duplicate_map_keys: DuplicateMapKeys,
+    #[cfg(feature = "arrow")]
+    widening: WideningPolicy,
+    #[cfg(feature = "arrow")]
+    struct_mismatch: StructMismatch,
 }
 
 /// Builder for configuring [`McapReader`].
@@ -37,6 +79,24 @@ pub struct McapReaderBuilder {
     decoders: Vec<Arc<dyn MessageDecoder>>,
     batch_size: usize,
     parallel: bool,
+    subscription_capacity: usize,
+    max_depth: Option<usize>,
+    dedup: bool,
+    skip_messages: u64,
+    start_time: Option<u64>,
+    sample: Option<Sample>,
+    cancellation: Option<CancellationToken>,
+    schema_resolver: Option<Arc<dyn SchemaResolver>>,
+    decode_stats: Option<Arc<DecodeStats>>,
+    #[cfg(feature = "arrow")]
+    column_stats: Option<Arc<TopicColumnStats>>,
+    sort_map_keys: bool,
+    #[cfg(feature = "arrow")]
+    duplicate_map_keys: DuplicateMapKeys,
+    #[cfg(feature = "arrow")]
+    widening: WideningPolicy,
+    #[cfg(feature = "arrow")]
+    struct_mismatch: StructMismatch,
 }
 
 /// Metadata about a topic discovered from the MCAP summary section.
@@ -55,6 +115,40 @@ pub struct TopicInfo {
 pub struct RawMessage {
     pub log_time: u64,
     pub publish_time: u64,
+    /// Publisher sequence number of the message record (0 if unset).
+    pub sequence: u32,
+    pub data: Arc<[u8]>,
+}
+
+/// Schema record of a topic's channel, exactly as stored in the MCAP file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawSchema {
+    pub name: String,
+    pub encoding: String,
+    pub data: Arc<[u8]>,
+}
+
+/// Channel record of a message, exactly as stored in the MCAP file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawChannel {
+    pub id: u16,
+    pub topic: String,
+    pub message_encoding: String,
+    pub metadata: BTreeMap<String, String>,
+    /// `None` for schemaless channels.
+    pub schema: Option<RawSchema>,
+}
+
+/// Undecoded message of any channel, as emitted by
+/// [`McapReader::for_each_channel_message`].
+#[derive(Debug, Clone)]
+pub struct RawChannelMessage {
+    /// Shared by every message of the channel.
+    pub channel: Arc<RawChannel>,
+    pub log_time: u64,
+    pub publish_time: u64,
+    /// Publisher sequence number of the message record (0 if unset).
+    pub sequence: u32,
     pub data: Arc<[u8]>,
 }
 
@@ -65,6 +159,24 @@ impl McapReader {
             decoders: Vec::new(),
             batch_size: 1024,
             parallel: true,
+            subscription_capacity: 1024,
+            max_depth: None,
+            dedup: false,
+            skip_messages: 0,
+            start_time: None,
+            sample: None,
+            cancellation: None,
+            schema_resolver: None,
+            decode_stats: None,
+            #[cfg(feature = "arrow")]
+            column_stats: None,
+            sort_map_keys: false,
+            #[cfg(feature = "arrow")]
+            duplicate_map_keys: DuplicateMapKeys::default(),
+            #[cfg(feature = "arrow")]
+            widening: WideningPolicy::default(),
+            #[cfg(feature = "arrow")]
+            struct_mismatch: StructMismatch::default(),
         }
     }
 
@@ -73,17 +185,39 @@ impl McapReader {
             decoders: HashMap::new(),
             batch_size: 1024,
             parallel: true,
+            subscription_capacity: 1024,
+            max_depth: None,
+            dedup: false,
+            skip_messages: 0,
+            start_time: None,
+            sample: None,
+            cancellation: None,
+            schema_resolver: None,
+            decode_stats: None,
+            #[cfg(feature = "arrow")]
+            column_stats: None,
+            sort_map_keys: false,
+            #[cfg(feature = "arrow")]
+            duplicate_map_keys: DuplicateMapKeys::default(),
+            #[cfg(feature = "arrow")]
+            widening: WideningPolicy::default(),
+            #[cfg(feature = "arrow")]
+            struct_mismatch: StructMismatch::default(),
         }
     }
 
-    /// Register a decoder for a specific encoding pair.
+    /// Register a decoder for each encoding pair it handles
+    /// ([`MessageDecoder::encoding_keys`]).
     pub fn register_decoder(&mut self, decoder: Box<dyn MessageDecoder>) {
         self.register_shared_decoder(Arc::from(decoder));
     }
 
-    /// Register a shared decoder for a specific encoding pair.
+    /// Register a shared decoder for each encoding pair it handles
+    /// ([`MessageDecoder::encoding_keys`]).
     pub fn register_shared_decoder(&mut self, decoder: Arc<dyn MessageDecoder>) {
-        self.decoders.insert(decoder.encoding_key(), decoder);
+        for key in decoder.encoding_keys() {
+            self.decoders.insert(key, Arc::clone(&decoder));
+        }
     }
 
     #[cfg(feature = "arrow")]
@@ -91,6 +225,69 @@ impl McapReader {
         self.batch_size
     }
 
+    #[cfg(feature = "arrow")]
+    pub(crate) fn sort_map_keys(&self) -> bool {
+        self.sort_map_keys
+    }
+
+    #[cfg(feature = "arrow")]
+    pub(crate) fn column_stats(&self) -> Option<&Arc<TopicColumnStats>> {
+        self.column_stats.as_ref()
+    }
+
+    #[cfg(feature = "arrow")]
+    pub(crate) fn duplicate_map_keys(&self) -> DuplicateMapKeys {
+        self.duplicate_map_keys
+    }
+
+    #[cfg(feature = "arrow")]
+    pub(crate) fn widening(&self) -> WideningPolicy {
+        self.widening
+    }
+
+    #[cfg(feature = "arrow")]
+    pub(crate) fn struct_mismatch(&self) -> StructMismatch {
+        self.struct_mismatch
+    }
+
+    pub(crate) fn subscription_capacity(&self) -> usize {
+        self.subscription_capacity
+    }
+
+    pub(crate) fn dedup(&self) -> bool {
+        self.dedup
+    }
+
+    pub(crate) fn sample(&self) -> Option<Sample> {
+        self.sample
+    }
+
+    /// Whether the cancellation token, if any, was cancelled.
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.cancellation
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
+    }
+
+    pub(crate) fn message_skip(&self) -> MessageSkip {
+        MessageSkip::new(self.skip_messages, self.start_time)
+    }
+
+    /// [`Self::message_skip`] of `channel_id`, with its [`Sample`], if any.
+    pub(crate) fn channel_skip(
+        &self,
+        mmap: &[u8],
+        summary: &mcap::read::Summary,
+        channel_id: u16,
+    ) -> Result<MessageSkip, McapReaderError> {
+        let skip = self.message_skip();
+        let Some(sample) = self.sample else {
+            return Ok(skip);
+        };
+        let total = skip.count_passing(mmap, summary, channel_id)?;
+        Ok(skip.with_sample(SampledMessages::new(sample.select(total))))
+    }
+
     pub(crate) fn mmap_file(&self, path: &Path) -> Result<Mmap, McapReaderError> {
         let file = fs::File::open(path)?;
         Ok(unsafe { Mmap::map(&file) }?)
@@ -106,6 +303,17 @@ impl McapReader {
         })
     }
 
+    /// Return the number of chunks per compression codec, from the MCAP
+    /// summary section. Uncompressed chunks are counted under `""`.
+    pub fn chunk_compressions(
+        &self,
+        path: &Path,
+    ) -> Result<BTreeMap<String, usize>, McapReaderError> {
+        let mmap = self.mmap_file(path)?;
+        let summary = self.read_summary(path, &mmap)?;
+        Ok(count_chunk_compressions(&summary))
+    }
+
     fn find_decoder(
         &self,
         topic: &str,
@@ -128,20 +336,75 @@ impl McapReader {
         topic: &str,
     ) -> Result<TopicDecodeContext, McapReaderError> {
         let channel = get_channel_from_summary(summary, topic)?;
-        let schema = Arc::clone(get_schema_from_channel(channel)?);
-        let schema_enc = SchemaEncoding::from(schema.encoding.as_str());
-        let message_enc = MessageEncoding::from(channel.message_encoding.as_str());
-        let decoder = Arc::clone(self.find_decoder(&channel.topic, &schema_enc, &message_enc)?);
-        let topic_decoder = decoder
-            .build_topic_decoder(&schema.name, &schema.data)
-            .map_err(|e| McapReaderError::SchemaDerivationFailed {
-                topic: topic.to_string(),
-                source: e,
-            })?;
+        self.build_topic_decode_context(
+            topic,
+            channel.id,
+            &channel.message_encoding,
+            channel.schema.as_deref(),
+        )
+    }
+
+    /// Build the decode context of `topic` from its channel and schema records.
+    ///
+    /// Channels without a schema (`schema` is `None`) are decoded by a decoder
+    /// registered for [`SchemaEncoding::None`], such as [`TextDecoder`];
+    /// without one they fail with
+    /// [`McapReaderError::SchemaNotAvailable`].
+    pub(crate) fn build_topic_decode_context(
+        &self,
+        topic: &str,
+        channel_id: u16,
+        message_encoding: &str,
+        schema: Option<&mcap::Schema>,
+    ) -> Result<TopicDecodeContext, McapReaderError> {
+        let message_enc = MessageEncoding::from(message_encoding);
+        let (schema_enc, decoder) = match schema {
+            Some(schema) => {
+                let schema_enc = SchemaEncoding::from(schema.encoding.as_str());
+                let decoder = self.find_decoder(topic, &schema_enc, &message_enc)?;
+                (schema_enc, decoder)
+            }
+            None => {
+                let key = EncodingKey::new(SchemaEncoding::None, message_enc);
+                let decoder =
+                    self.decoders
+                        .get(&key)
+                        .ok_or_else(|| McapReaderError::SchemaNotAvailable {
+                            topic: topic.to_string(),
+                            channel_id,
+                        })?;
+                (SchemaEncoding::None, decoder)
+            }
+        };
+        let decoder = Arc::clone(decoder);
+        let (schema_name, schema_data) = schema
+            .map(|schema| (schema.name.as_str(), &schema.data[..]))
+            .unwrap_or_default();
+        let schema_derivation_failed = |e| McapReaderError::SchemaDerivationFailed {
+            topic: topic.to_string(),
+            source: e,
+        };
+        // Schema warnings, such as a guessed root type, count as warnings
+        // of the topic.
+        let mut topic_decoder = self.collecting_warnings(topic, || {
+            let resolved = match &self.schema_resolver {
+                Some(resolver) => resolver
+                    .resolve_schema(&schema_enc, schema_name, schema_data)
+                    .map_err(schema_derivation_failed)?,
+                None => None,
+            };
+            let schema_data = resolved.as_deref().unwrap_or(schema_data);
+            decoder
+                .build_topic_decoder(schema_name, schema_data)
+                .map_err(schema_derivation_failed)
+        })?;
+        if let Some(max_depth) = self.max_depth {
+            topic_decoder = Box::new(DepthLimitedDecoder::new(topic_decoder, max_depth));
+        }
         let field_defs = topic_decoder.field_defs().clone();
 
         Ok(TopicDecodeContext {
-            channel_id: channel.id,
+            channel_id,
             decoder: topic_decoder,
             field_defs,
         })
@@ -217,16 +480,91 @@ impl McapReader {
         let mmap = self.mmap_file(path)?;
         let summary = self.read_summary(path, &mmap)?;
         let channel = get_channel_from_summary(&summary, topic)?;
+        ensure_supported_compressions(&summary)?;
+        let mut dedup = PayloadDedup::default();
+        let mut skip = self.channel_skip(&mmap, &summary, channel.id)?;
 
         for message in mcap::MessageStream::new(&mmap)? {
+            if skip.is_done() || self.is_cancelled() {
+                break;
+            }
             let message = message?;
-            if message.channel.id != channel.id {
+            if message.channel.id != channel.id || skip.skips(message.log_time) {
+                continue;
+            }
+            if self.dedup && dedup.is_repeat(&message.data) {
                 continue;
             }
 
             callback(RawMessage {
                 log_time: message.log_time,
                 publish_time: message.publish_time,
+                sequence: message.sequence,
+                data: Arc::from(message.data),
+            })
+            .map_err(McapReaderError::Callback)?;
+        }
+
+        Ok(())
+    }
+
+    /// Read the undecoded messages of every channel in file order and emit
+    /// them one-by-one to callback, with the channel and schema records they
+    /// were written with.
+    ///
+    /// No decoder is looked up, so channels of any encoding are emitted. The
+    /// time range, stride and deduplication options apply per channel.
+    pub fn for_each_channel_message(
+        &self,
+        path: &Path,
+        mut callback: impl FnMut(
+            RawChannelMessage,
+        ) -> Result<(), Box<dyn std::error::Error + Send + Sync>>,
+    ) -> Result<(), McapReaderError> {
+        let mmap = self.mmap_file(path)?;
+        let summary = self.read_summary(path, &mmap)?;
+        ensure_supported_compressions(&summary)?;
+        let mut channels: HashMap<u16, (Arc<RawChannel>, MessageSkip, PayloadDedup)> =
+            HashMap::new();
+
+        for message in mcap::MessageStream::new(&mmap)? {
+            if self.is_cancelled() {
+                break;
+            }
+            let message = message?;
+            let (channel, skip, dedup) = match channels.entry(message.channel.id) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    let channel = RawChannel {
+                        id: message.channel.id,
+                        topic: message.channel.topic.clone(),
+                        message_encoding: message.channel.message_encoding.clone(),
+                        metadata: message.channel.metadata.clone(),
+                        schema: message.channel.schema.as_ref().map(|schema| RawSchema {
+                            name: schema.name.clone(),
+                            encoding: schema.encoding.clone(),
+                            data: Arc::from(&schema.data[..]),
+                        }),
+                    };
+                    entry.insert((
+                        Arc::new(channel),
+                        self.channel_skip(&mmap, &summary, message.channel.id)?,
+                        PayloadDedup::default(),
+                    ))
+                }
+            };
+            if skip.skips(message.log_time) {
+                continue;
+            }
+            if self.dedup && dedup.is_repeat(&message.data) {
+                continue;
+            }
+
+            callback(RawChannelMessage {
+                channel: channel.clone(),
+                log_time: message.log_time,
+                publish_time: message.publish_time,
+                sequence: message.sequence,
                 data: Arc::from(message.data),
             })
             .map_err(McapReaderError::Callback)?;
@@ -246,10 +584,11 @@ impl McapReader {
     where
         F: FnMut(DecodedMessage) -> Result<(), McapReaderError>,
     {
+        ensure_supported_compressions(summary)?;
         if self.parallel {
             self.for_each_decoded_message_parallel(mmap, summary, context, topic, callback)
         } else {
-            self.for_each_decoded_message_sequential(mmap, context, topic, callback)
+            self.for_each_decoded_message_sequential(mmap, summary, context, topic, callback)
         }
     }
 
@@ -266,11 +605,9 @@ impl McapReader {
     {
         use rayon::prelude::*;
 
-        let chunk_indexes: Vec<_> = summary
-            .chunk_indexes
-            .iter()
-            .filter(|ci| ci.message_index_offsets.contains_key(&context.channel_id))
-            .collect();
+        let chunk_indexes = self
+            .channel_skip(mmap, summary, context.channel_id)?
+            .plan_chunks(mmap, summary, context.channel_id)?;
         let chunk_count = chunk_indexes.len();
         let cancelled = Arc::new(AtomicBool::new(false));
         let (sender, receiver) = mpsc::channel();
@@ -280,14 +617,9 @@ impl McapReader {
             let worker = scope.spawn(|| {
                 chunk_indexes.into_par_iter().enumerate().for_each_with(
                     worker_sender,
-                    |sender, (position, chunk_index)| {
+                    |sender, (position, chunk)| {
                         let result = self.decode_chunk_messages(
-                            mmap,
-                            summary,
-                            context,
-                            topic,
-                            chunk_index,
-                            &cancelled,
+                            mmap, summary, context, topic, chunk, &cancelled,
                         );
                         let _ = sender.send((position, result));
                     },
@@ -297,6 +629,7 @@ impl McapReader {
 
             let mut next_position = 0usize;
             let mut pending = BTreeMap::new();
+            let mut dedup = PayloadDedup::default();
             while next_position < chunk_count {
                 let (position, result) = receiver.recv().map_err(|_| {
                     McapReaderError::Io(io::Error::other(
@@ -306,14 +639,27 @@ impl McapReader {
                 pending.insert(position, result);
 
                 while let Some(result) = pending.remove(&next_position) {
-                    let chunk_messages = match result {
-                        Ok(messages) => messages,
+                    let chunk = match result {
+                        Ok(chunk) => chunk,
                         Err(error) => {
                             cancelled.store(true, Ordering::Relaxed);
                             return Err(error);
                         }
                     };
+                    let mut chunk_messages = chunk.messages.into_iter();
+                    // Repeats inside a chunk are already skipped; only the
+                    // first message may repeat the end of the previous chunk.
+                    if let (Some(first), Some(last)) = (chunk.first_hash, chunk.last_hash) {
+                        if dedup.is_repeat_hash(first) {
+                            chunk_messages.next();
+                        }
+                        dedup.is_repeat_hash(last);
+                    }
                     for decoded in chunk_messages {
+                        if self.is_cancelled() {
+                            cancelled.store(true, Ordering::Relaxed);
+                            return Ok(());
+                        }
                         if let Err(error) = callback(decoded) {
                             cancelled.store(true, Ordering::Relaxed);
                             return Err(error);
@@ -335,38 +681,59 @@ impl McapReader {
         summary: &mcap::read::Summary,
         context: &TopicDecodeContext,
         topic: &str,
-        chunk_index: &mcap::records::ChunkIndex,
+        planned: PlannedChunk<'_>,
         cancelled: &AtomicBool,
-    ) -> Result<Vec<DecodedMessage>, McapReaderError> {
-        if cancelled.load(Ordering::Relaxed) {
-            return Ok(Vec::new());
+    ) -> Result<DecodedChunk, McapReaderError> {
+        let mut chunk = DecodedChunk::default();
+        if cancelled.load(Ordering::Relaxed) || self.is_cancelled() {
+            return Ok(chunk);
         }
 
-        let mut decoded_messages = Vec::new();
-        for msg_result in summary.stream_chunk(mmap, chunk_index)? {
+        let mut skip = planned.skip;
+        let mut messages = Vec::new();
+        for msg_result in summary.stream_chunk(mmap, planned.index)? {
             if cancelled.load(Ordering::Relaxed) {
-                return Ok(decoded_messages);
+                return Ok(chunk);
             }
 
             let msg = msg_result?;
-            if msg.channel.id != context.channel_id {
+            if msg.channel.id != context.channel_id || skip.skips(msg.log_time) {
                 continue;
             }
-            decoded_messages.push(self.decode_message(
-                context,
-                topic,
-                msg.log_time,
-                msg.publish_time,
-                &msg.data,
-            )?);
+            if self.dedup {
+                let hash = payload_hash(&msg.data);
+                if chunk.last_hash == Some(hash) {
+                    continue;
+                }
+                chunk.first_hash.get_or_insert(hash);
+                chunk.last_hash = Some(hash);
+            }
+            messages.push(msg);
         }
 
-        Ok(decoded_messages)
+        let payloads: Vec<&[u8]> = messages.iter().map(|msg| &msg.data[..]).collect();
+        let values = self
+            .collecting_warnings(topic, || context.decoder.decode_batch(&payloads))
+            .map_err(|e| McapReaderError::MessageDecodeFailed {
+                topic: topic.to_string(),
+                source: e,
+            })?;
+        chunk.messages = messages
+            .iter()
+            .zip(values)
+            .map(|(msg, value)| DecodedMessage {
+                log_time: msg.log_time,
+                publish_time: msg.publish_time,
+                value,
+            })
+            .collect();
+        Ok(chunk)
     }
 
     fn for_each_decoded_message_sequential<F>(
         &self,
         mmap: &Mmap,
+        summary: &mcap::read::Summary,
         context: &TopicDecodeContext,
         topic: &str,
         callback: &mut F,
@@ -374,9 +741,17 @@ impl McapReader {
     where
         F: FnMut(DecodedMessage) -> Result<(), McapReaderError>,
     {
+        let mut dedup = PayloadDedup::default();
+        let mut skip = self.channel_skip(mmap, summary, context.channel_id)?;
         for message in mcap::MessageStream::new(mmap)? {
+            if skip.is_done() || self.is_cancelled() {
+                break;
+            }
             let message = message?;
-            if message.channel.id != context.channel_id {
+            if message.channel.id != context.channel_id || skip.skips(message.log_time) {
+                continue;
+            }
+            if self.dedup && dedup.is_repeat(&message.data) {
                 continue;
             }
 
@@ -393,7 +768,7 @@ impl McapReader {
         Ok(())
     }
 
-    fn decode_message(
+    pub(crate) fn decode_message(
         &self,
         context: &TopicDecodeContext,
         topic: &str,
@@ -401,14 +776,12 @@ impl McapReader {
         publish_time: u64,
         data: &[u8],
     ) -> Result<DecodedMessage, McapReaderError> {
-        let value =
-            context
-                .decoder
-                .decode(data)
-                .map_err(|e| McapReaderError::MessageDecodeFailed {
-                    topic: topic.to_string(),
-                    source: e,
-                })?;
+        let value = self
+            .collecting_warnings(topic, || context.decoder.decode(data))
+            .map_err(|e| McapReaderError::MessageDecodeFailed {
+                topic: topic.to_string(),
+                source: e,
+            })?;
 
         Ok(DecodedMessage {
             log_time,
@@ -417,6 +790,22 @@ impl McapReader {
         })
     }
 
+    /// Run `decode`, recording the warnings it reports in the decode stats
+    /// of `topic`, if any.
+    fn collecting_warnings<R>(&self, topic: &str, decode: impl FnOnce() -> R) -> R {
+        let Some(stats) = &self.decode_stats else {
+            return decode();
+        };
+        let (result, warnings) = collect_warnings(decode);
+        stats.record(topic, warnings);
+        result
+    }
+
+    /// Stats passed to [`McapReaderBuilder::with_decode_stats`].
+    pub fn decode_stats(&self) -> Option<&Arc<DecodeStats>> {
+        self.decode_stats.as_ref()
+    }
+
     /// Return the total message count from the MCAP summary section.
     ///
     /// MCAP summary and summary stats are required.
@@ -439,6 +828,56 @@ impl McapReader {
             .unwrap_or_default())
     }
 
+    /// Return the log time of the earliest message in the file, from the MCAP
+    /// summary section.
+    ///
+    /// MCAP summary and summary stats are required.
+    pub fn message_start_time(&self, path: &Path) -> Result<u64, McapReaderError> {
+        let mmap = self.mmap_file(path)?;
+        let summary = self.read_summary(path, &mmap)?;
+        let stats = summary
+            .stats
+            .as_ref()
+            .ok_or_else(|| McapReaderError::StatsNotAvailable {
+                path: path.display().to_string(),
+            })?;
+        Ok(stats.message_start_time)
+    }
+
+    /// Return the profile of the MCAP header (e.g. `ros2`; empty if none is
+    /// set).
+    ///
+    /// Only the header is read, so files that are still being recorded are
+    /// supported.
+    pub fn profile(&self, path: &Path) -> Result<String, McapReaderError> {
+        let mmap = self.mmap_file(path)?;
+        let mut records = mcap::read::LinearReader::new_with_options(
+            &mmap,
+            mcap::read::Options::IgnoreEndMagic.into(),
+        )?;
+        match records.next().transpose()? {
+            Some(mcap::records::Record::Header(header)) => Ok(header.profile),
+            _ => Ok(String::new()),
+        }
+    }
+
+    /// Scan the message records of a topic for log-time gaps above
+    /// `threshold` (three median intervals if `None`) and breaks in their
+    /// sequence numbers.
+    pub fn gap_report(
+        &self,
+        path: &Path,
+        topic: &str,
+        threshold: Option<Duration>,
+    ) -> Result<GapReport, McapReaderError> {
+        let mut analyzer = GapAnalyzer::default();
+        self.for_each_raw_message(path, topic, |message| {
+            analyzer.push(message.log_time, message.sequence);
+            Ok(())
+        })?;
+        Ok(analyzer.finish(threshold))
+    }
+
     /// Derive and return schema IR (`FieldDef`) for a topic without reading message payloads.
     pub fn topic_field_defs(&self, path: &Path, topic: &str) -> Result<FieldDefs, McapReaderError> {
         let mmap = self.mmap_file(path)?;
@@ -446,6 +885,28 @@ impl McapReader {
         let context = self.resolve_topic_decode_context(&summary, topic)?;
         Ok(context.field_defs)
     }
+
+    /// Return the schema record of a topic's channel without decoding it.
+    ///
+    /// Fails with [`McapReaderError::SchemaNotAvailable`] for schemaless channels.
+    pub fn topic_schema(&self, path: &Path, topic: &str) -> Result<RawSchema, McapReaderError> {
+        let mmap = self.mmap_file(path)?;
+        let summary = self.read_summary(path, &mmap)?;
+        let channel = get_channel_from_summary(&summary, topic)?;
+        let schema =
+            channel
+                .schema
+                .as_ref()
+                .ok_or_else(|| McapReaderError::SchemaNotAvailable {
+                    topic: topic.to_string(),
+                    channel_id: channel.id,
+                })?;
+        Ok(RawSchema {
+            name: schema.name.clone(),
+            encoding: schema.encoding.clone(),
+            data: Arc::from(&schema.data[..]),
+        })
+    }
 }
 
 impl Default for McapReader {
@@ -473,13 +934,186 @@ impl McapReaderBuilder {
         self
     }
 
-    /// Register all built-in decoders (Protobuf).
+    /// Set how many decoded messages a subscription buffers before the
+    /// decoding thread waits for the consumer (default: 1024).
+    pub fn with_subscription_capacity(mut self, capacity: usize) -> Self {
+        self.subscription_capacity = capacity;
+        self
+    }
+
+    /// Collapse structs nested deeper than `max_depth` field levels into
+    /// JSON string fields (default: no limit).
+    ///
+    /// Top-level fields are level 1. See
+    /// [`limit_field_defs_depth`](mcapdecode_core::limit_field_defs_depth).
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    /// Skip messages whose payload is identical to the previous message of
+    /// the same topic (default: false).
+    ///
+    /// Payloads are compared by a 64-bit hash, which suits static topics such
+    /// as `/tf_static` or `/camera_info` that repeat the same message.
+    pub fn with_dedup(mut self, dedup: bool) -> Self {
+        self.dedup = dedup;
+        self
+    }
+
+    /// Skip the first `count` messages of each topic read (default: 0).
+    ///
+    /// Skipped messages are not decoded, and with parallel reading, chunks
+    /// holding only skipped messages are not decompressed either (using the
+    /// message indexes). Applied before [`Self::with_dedup`].
+    pub fn with_skip_messages(mut self, count: u64) -> Self {
+        self.skip_messages = count;
+        self
+    }
+
+    /// Skip messages logged before `start_time` (nanoseconds), checked before
+    /// [`Self::with_skip_messages`] (default: none).
+    ///
+    /// With parallel reading, chunks that end before `start_time` are not
+    /// decompressed.
+    pub fn with_start_time(mut self, start_time: u64) -> Self {
+        self.start_time = Some(start_time);
+        self
+    }
+
+    /// Keep only the messages of each topic read that `sample` selects
+    /// (default: all), e.g. for preview datasets.
+    ///
+    /// The messages are counted and located with the message indexes, so
+    /// with parallel reading only chunks holding sampled messages are
+    /// decompressed, and reading stops after the last sampled message.
+    /// Applied after [`Self::with_start_time`] and
+    /// [`Self::with_skip_messages`], before [`Self::with_dedup`]; not applied
+    /// when following a file.
+    pub fn with_sample(mut self, sample: Sample) -> Self {
+        self.sample = Some(sample);
+        self
+    }
+
+    /// Stop reads cleanly once `token` is cancelled (default: none); see
+    /// [`CancellationToken`].
+    ///
+    /// Reads check the token between messages, and parallel reading stops
+    /// decompressing chunks, so a read returns `Ok(())` soon after
+    /// cancellation. Following a file stops as well, even while waiting for
+    /// new data.
+    pub fn with_cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Set a hook that may replace schema data (e.g. a protobuf stub
+    /// descriptor) before decoders parse it.
+    ///
+    /// The resolver runs once per topic read on the calling thread, before
+    /// any message is decoded.
+    pub fn with_schema_resolver(mut self, resolver: Box<dyn SchemaResolver>) -> Self {
+        self.schema_resolver = Some(Arc::from(resolver));
+        self
+    }
+
+    /// Count the warnings decoders report while decoding messages
+    /// ([`mcapdecode_core::warn`]) in `stats`.
+    ///
+    /// Warnings are not collected without stats.
+    pub fn with_decode_stats(mut self, stats: Arc<DecodeStats>) -> Self {
+        self.decode_stats = Some(stats);
+        self
+    }
+
+    /// Collect the [`ColumnStats`](mcapdecode_arrow::ColumnStats) of every
+    /// record batch read in `stats`, per topic.
+    ///
+    /// Statistics are not collected without it.
+    #[cfg(feature = "arrow")]
+    pub fn with_column_stats(mut self, stats: Arc<TopicColumnStats>) -> Self {
+        self.column_stats = Some(stats);
+        self
+    }
+
+    /// Sort the entries of map columns by key and mark them `keys_sorted`
+    /// in the Arrow schema (default: false, keeping the encoded order).
+    ///
+    /// Maps holding the same entries then compare and hash equal.
+    pub fn with_sorted_map_keys(mut self, sort: bool) -> Self {
+        self.sort_map_keys = sort;
+        self
+    }
+
+    /// Set how map entries repeating a key of the same map are converted to
+    /// Arrow (default: [`DuplicateMapKeys::KeepLast`]).
+    #[cfg(feature = "arrow")]
+    pub fn with_duplicate_map_keys(mut self, policy: DuplicateMapKeys) -> Self {
+        self.duplicate_map_keys = policy;
+        self
+    }
+
+    /// Set whether numeric values of a different type than their column are
+    /// converted when this is lossless (default: [`WideningPolicy::Exact`]).
+    #[cfg(feature = "arrow")]
+    pub fn with_widening(mut self, policy: WideningPolicy) -> Self {
+        self.widening = policy;
+        self
+    }
+
+    /// Set how struct values with more or fewer fields than the schema are
+    /// converted to Arrow (default: [`StructMismatch::Error`]).
+    ///
+    /// With [`StructMismatch::Align`], the number of aligned values is
+    /// counted per topic in the stats passed to
+    /// [`with_decode_stats`](Self::with_decode_stats).
+    #[cfg(feature = "arrow")]
+    pub fn with_struct_mismatch(mut self, policy: StructMismatch) -> Self {
+        self.struct_mismatch = policy;
+        self
+    }
+
+    /// Apply the value conversion policies and depth limit of `options`
+    /// (see [`Self::with_duplicate_map_keys`], [`Self::with_widening`],
+    /// [`Self::with_struct_mismatch`] and [`Self::with_max_depth`]).
+    ///
+    /// Flattening, the separator and the time columns apply to the record
+    /// batches read, e.g. with
+    /// [`flatten_record_batch`](mcapdecode_arrow::flatten_record_batch).
+    #[cfg(feature = "arrow")]
+    pub fn with_conversion_options(mut self, options: &ConversionOptions) -> Self {
+        let convert = options.convert_options();
+        self.duplicate_map_keys = convert.duplicate_map_keys;
+        self.widening = convert.widening;
+        self.struct_mismatch = convert.struct_mismatch;
+        self.max_depth = options.max_depth();
+        self
+    }
+
+    /// Register all built-in decoders: protobuf, ROS 1 .msg, ROS 2 (IDL and
+    /// .msg), DDS XTypes `TypeObject`s, JSON with a JSON Schema, CBOR and
+    /// MessagePack (with a JSON Schema or schemaless) and [`TextDecoder`] for
+    /// schemaless `log` and `json` channels.
     pub fn with_default_decoders(self) -> Self {
-        let s = self;
+        let s = self
+            .with_decoder(Box::new(TextDecoder::new("log")))
+            .with_decoder(Box::new(TextDecoder::new(MessageEncoding::Json)));
+        #[cfg(feature = "jsonschema")]
+        let s = [PayloadFormat::Cbor, PayloadFormat::MsgPack]
+            .into_iter()
+            .fold(s, |s, format| {
+                s.with_decoder(Box::new(JsonSchemaDecoder::new(format)))
+                    .with_decoder(Box::new(SchemalessDecoder::new(format)))
+            })
+            .with_decoder(Box::new(JsonSchemaDecoder::new(PayloadFormat::Json)));
         #[cfg(feature = "protobuf")]
         let s = s.with_decoder(Box::new(ProtobufDecoder::new()));
+        #[cfg(feature = "ros1msg")]
+        let s = s.with_decoder(Box::new(Ros1MsgDecoder::new()));
         #[cfg(feature = "ros2idl")]
-        let s = s.with_decoder(Box::new(Ros2IdlDecoder::new()));
+        let s = s
+            .with_decoder(Box::new(Ros2IdlDecoder::new()))
+            .with_decoder(Box::new(XTypesDecoder::new()));
         #[cfg(feature = "ros2msg")]
         let s = s.with_decoder(Box::new(Ros2MsgDecoder::new()));
         s
@@ -490,6 +1124,23 @@ impl McapReaderBuilder {
         let mut reader = McapReader::new();
         reader.batch_size = self.batch_size;
         reader.parallel = self.parallel;
+        reader.subscription_capacity = self.subscription_capacity;
+        reader.max_depth = self.max_depth;
+        reader.dedup = self.dedup;
+        reader.skip_messages = self.skip_messages;
+        reader.start_time = self.start_time;
+        reader.sample = self.sample;
+        reader.cancellation = self.cancellation;
+        reader.schema_resolver = self.schema_resolver;
+        reader.decode_stats = self.decode_stats;
+        reader.sort_map_keys = self.sort_map_keys;
+        #[cfg(feature = "arrow")]
+        {
+            reader.column_stats = self.column_stats;
+            reader.duplicate_map_keys = self.duplicate_map_keys;
+            reader.widening = self.widening;
+            reader.struct_mismatch = self.struct_mismatch;
+        }
         for decoder in self.decoders {
             reader.register_shared_decoder(decoder);
         }
@@ -515,16 +1166,41 @@ fn get_channel_from_summary<'a>(
     Ok(first)
 }
 
-fn get_schema_from_channel<'a>(
-    channel: &'a Arc<mcap::Channel>,
-) -> Result<&'a Arc<mcap::Schema<'a>>, McapReaderError> {
-    channel
-        .schema
-        .as_ref()
-        .ok_or_else(|| McapReaderError::SchemaNotAvailable {
-            topic: channel.topic.clone(),
-            channel_id: channel.id,
-        })
+fn count_chunk_compressions(summary: &mcap::read::Summary) -> BTreeMap<String, usize> {
+    let mut counts = BTreeMap::new();
+    for chunk_index in &summary.chunk_indexes {
+        *counts.entry(chunk_index.compression.clone()).or_default() += 1;
+    }
+    counts
+}
+
+/// Fail before reading any message if a chunk uses a codec that cannot be decompressed.
+pub(crate) fn ensure_supported_compressions(
+    summary: &mcap::read::Summary,
+) -> Result<(), McapReaderError> {
+    match summary
+        .chunk_indexes
+        .iter()
+        .find(|chunk_index| !is_supported_compression(&chunk_index.compression))
+    {
+        Some(chunk_index) => Err(McapReaderError::UnsupportedCompression {
+            compression: chunk_index.compression.clone(),
+        }),
+        None => Ok(()),
+    }
+}
+
+fn is_supported_compression(compression: &str) -> bool {
+    compression.is_empty() || SUPPORTED_COMPRESSIONS.contains(&compression)
+}
+
+/// Messages of one chunk decoded by a parallel worker.
+#[derive(Default)]
+struct DecodedChunk {
+    messages: Vec<DecodedMessage>,
+    /// Payload hashes of the first and last message, set when dedup is enabled.
+    first_hash: Option<u64>,
+    last_hash: Option<u64>,
 }
 
 pub(crate) struct TopicDecodeContext {
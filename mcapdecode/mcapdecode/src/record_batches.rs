@@ -0,0 +1,79 @@
+//! Pull-based Arrow record batches backed by a decoding thread
+//! (`McapReaderArrowExt::record_batches`).
+
+use std::{
+    error::Error,
+    fmt, io,
+    path::PathBuf,
+    sync::mpsc::{self, Receiver},
+    thread::{self, JoinHandle},
+};
+
+use arrow::record_batch::RecordBatch;
+
+use crate::{McapReader, McapReaderArrowExt, McapReaderError};
+
+/// Callback error that stops decoding once the iterator is dropped.
+#[derive(Debug)]
+struct IteratorDropped;
+
+impl fmt::Display for IteratorDropped {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("record batch iterator dropped")
+    }
+}
+
+impl Error for IteratorDropped {}
+
+/// Batches decoded ahead of the consumer.
+const BATCHES_AHEAD: usize = 1;
+
+/// Arrow RecordBatches of one topic, produced by a background thread, as
+/// returned by [`McapReaderArrowExt::record_batches`].
+///
+/// The thread decodes at most one batch ahead of the consumer. An error
+/// ends the iteration after it is yielded; dropping the iterator stops
+/// decoding before the next batch.
+pub struct RecordBatches {
+    receiver: Receiver<Result<RecordBatch, McapReaderError>>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl RecordBatches {
+    pub(crate) fn spawn(reader: McapReader, path: PathBuf, topic: String) -> Self {
+        let (sender, receiver) = mpsc::sync_channel(BATCHES_AHEAD);
+        let worker = thread::spawn(move || {
+            let result = reader.for_each_record_batch(&path, &topic, |batch| {
+                sender.send(Ok(batch)).map_err(|_| IteratorDropped.into())
+            });
+            match result {
+                Err(McapReaderError::Callback(e)) if e.is::<IteratorDropped>() => {}
+                Err(error) => {
+                    let _ = sender.send(Err(error));
+                }
+                Ok(()) => {}
+            }
+        });
+        Self {
+            receiver,
+            worker: Some(worker),
+        }
+    }
+}
+
+impl Iterator for RecordBatches {
+    type Item = Result<RecordBatch, McapReaderError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Ok(item) = self.receiver.recv() {
+            return Some(item);
+        }
+        // The thread has exited; report it once if it panicked.
+        let worker = self.worker.take()?;
+        worker.join().err().map(|_| {
+            Err(McapReaderError::Io(io::Error::other(
+                "record batch decode thread panicked",
+            )))
+        })
+    }
+}
@@ -0,0 +1,131 @@
+//! Rewrite an MCAP file with different chunking and compression.
+
+use std::{
+    fs,
+    io::{self, BufWriter},
+    path::Path,
+    str::FromStr,
+};
+
+use crate::{McapReader, error::McapReaderError};
+
+/// Chunk compression of a repacked file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RepackCompression {
+    /// Uncompressed chunks.
+    None,
+    #[default]
+    Zstd,
+    Lz4,
+}
+
+impl FromStr for RepackCompression {
+    type Err = String;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        match raw.to_ascii_lowercase().as_str() {
+            "none" => Ok(Self::None),
+            "zstd" => Ok(Self::Zstd),
+            "lz4" => Ok(Self::Lz4),
+            _ => Err(format!(
+                "invalid compression '{raw}' (expected: none, zstd, lz4)"
+            )),
+        }
+    }
+}
+
+impl RepackCompression {
+    fn to_mcap(self) -> Option<mcap::Compression> {
+        match self {
+            Self::None => None,
+            Self::Zstd => Some(mcap::Compression::Zstd),
+            Self::Lz4 => Some(mcap::Compression::Lz4),
+        }
+    }
+}
+
+/// Chunking and compression of a file written by [`McapReader::repack`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RepackOptions {
+    pub compression: RepackCompression,
+    /// Target uncompressed size of each chunk, in bytes.
+    pub chunk_size: u64,
+}
+
+impl Default for RepackOptions {
+    fn default() -> Self {
+        Self {
+            compression: RepackCompression::default(),
+            chunk_size: 4 * 1024 * 1024,
+        }
+    }
+}
+
+/// Records copied by [`McapReader::repack`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RepackSummary {
+    pub messages: u64,
+    pub attachments: u64,
+    pub metadata: u64,
+}
+
+impl McapReader {
+    /// Copy the messages, attachments and metadata of `input` to a new file
+    /// at `output`, chunked and compressed according to `options`, with a
+    /// freshly written summary section and indexes.
+    ///
+    /// The input does not need a summary section, so files left behind by an
+    /// interrupted recording can be repacked. Schema and channel IDs, the
+    /// header profile and message order are preserved; attachments and
+    /// metadata are written after the messages.
+    pub fn repack(
+        &self,
+        input: &Path,
+        output: &Path,
+        options: &RepackOptions,
+    ) -> Result<RepackSummary, McapReaderError> {
+        if output.exists() && fs::canonicalize(input)? == fs::canonicalize(output)? {
+            return Err(McapReaderError::Io(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "repack output must differ from the input file",
+            )));
+        }
+        let mmap = self.mmap_file(input)?;
+        let profile = self.profile(input)?;
+        let mut writer = mcap::Writer::with_options(
+            BufWriter::new(fs::File::create(output)?),
+            mcap::WriteOptions::new()
+                .profile(profile)
+                .compression(options.compression.to_mcap())
+                .chunk_size(Some(options.chunk_size)),
+        )?;
+
+        let mut summary = RepackSummary::default();
+        let read_options = mcap::read::Options::IgnoreEndMagic.into();
+        for message in mcap::MessageStream::new_with_options(&mmap, read_options)? {
+            writer.write(&message?)?;
+            summary.messages += 1;
+        }
+        for record in mcap::read::LinearReader::new_with_options(&mmap, read_options)? {
+            match record? {
+                mcap::records::Record::Attachment { header, data, .. } => {
+                    writer.attach(&mcap::Attachment {
+                        log_time: header.log_time,
+                        create_time: header.create_time,
+                        name: header.name,
+                        media_type: header.media_type,
+                        data,
+                    })?;
+                    summary.attachments += 1;
+                }
+                mcap::records::Record::Metadata(metadata) => {
+                    writer.write_metadata(&metadata)?;
+                    summary.metadata += 1;
+                }
+                _ => {}
+            }
+        }
+        writer.finish()?;
+        Ok(summary)
+    }
+}
@@ -0,0 +1,135 @@
+//! Subsets of a topic's messages for previews (`with_sample`).
+
+use std::{fmt, str::FromStr, sync::Arc};
+
+/// Which messages of each topic read are kept by the `with_sample` option of
+/// [`McapReader::builder`](crate::McapReader::builder).
+///
+/// Messages are counted after `with_start_time` and `with_skip_messages`,
+/// from the message indexes of the file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sample {
+    /// The first `n` messages.
+    Head(u64),
+    /// The last `n` messages.
+    Tail(u64),
+    /// `n` messages picked at random, in file order. The pick depends only
+    /// on the number of messages, so rereading a file yields the same sample.
+    Random(u64),
+}
+
+impl Sample {
+    /// Ordinals (in `0..total`, ascending) of the messages kept out of
+    /// `total`.
+    pub(crate) fn select(self, total: u64) -> Vec<u64> {
+        match self {
+            Self::Head(n) => (0..n.min(total)).collect(),
+            Self::Tail(n) => (total.saturating_sub(n)..total).collect(),
+            Self::Random(n) if n >= total => (0..total).collect(),
+            Self::Random(n) => {
+                // Floyd's algorithm: n distinct ordinals in n draws.
+                let mut rng = SplitMix64(RANDOM_SEED ^ total);
+                let mut picked = std::collections::HashSet::with_capacity(n as usize);
+                for upper in total - n..total {
+                    let candidate = rng.next() % (upper + 1);
+                    if !picked.insert(candidate) {
+                        picked.insert(upper);
+                    }
+                }
+                let mut ordinals: Vec<u64> = picked.into_iter().collect();
+                ordinals.sort_unstable();
+                ordinals
+            }
+        }
+    }
+}
+
+impl FromStr for Sample {
+    type Err = String;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid sample '{raw}' (expected: head:N, tail:N, random:N)");
+        let (mode, count) = raw.split_once(':').ok_or_else(invalid)?;
+        let count: u64 = count.trim().parse().map_err(|_| invalid())?;
+        match mode.trim().to_ascii_lowercase().as_str() {
+            "head" => Ok(Self::Head(count)),
+            "tail" => Ok(Self::Tail(count)),
+            "random" => Ok(Self::Random(count)),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl fmt::Display for Sample {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Head(n) => write!(f, "head:{n}"),
+            Self::Tail(n) => write!(f, "tail:{n}"),
+            Self::Random(n) => write!(f, "random:{n}"),
+        }
+    }
+}
+
+/// Seed of [`Sample::Random`], mixed with the message count.
+const RANDOM_SEED: u64 = 0x6d63_6170_6465_636f;
+
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
+/// Position of a topic's reading within the ordinals of its [`Sample`].
+#[derive(Debug, Clone)]
+pub(crate) struct SampledMessages {
+    ordinals: Arc<[u64]>,
+    /// Ordinal of the next message.
+    position: u64,
+    /// Index of the first ordinal not yet reached.
+    next: usize,
+}
+
+impl SampledMessages {
+    pub(crate) fn new(ordinals: Vec<u64>) -> Self {
+        Self {
+            ordinals: ordinals.into(),
+            position: 0,
+            next: 0,
+        }
+    }
+
+    /// Whether the next message is kept.
+    pub(crate) fn keeps_next(&mut self) -> bool {
+        let kept = self.ordinals.get(self.next) == Some(&self.position);
+        if kept {
+            self.next += 1;
+        }
+        self.position += 1;
+        kept
+    }
+
+    /// Whether any of the next `count` messages is kept.
+    pub(crate) fn keeps_any_of_next(&self, count: u64) -> bool {
+        self.ordinals
+            .get(self.next)
+            .is_some_and(|ordinal| *ordinal < self.position + count)
+    }
+
+    /// Pass over the next `count` messages, e.g. those of a chunk read with
+    /// a copy of this sample.
+    pub(crate) fn advance(&mut self, count: u64) {
+        self.position += count;
+        self.next += self.ordinals[self.next..].partition_point(|ordinal| *ordinal < self.position);
+    }
+
+    /// Whether every kept message has been reached.
+    pub(crate) fn is_done(&self) -> bool {
+        self.next == self.ordinals.len()
+    }
+}
@@ -0,0 +1,149 @@
+//! Filter for the leading messages of a topic (`with_skip_messages`,
+//! `with_start_time`) and its sample (`with_sample`).
+
+use crate::{error::McapReaderError, sample::SampledMessages};
+
+/// A chunk to stream, with the skip to apply while streaming it.
+pub(crate) struct PlannedChunk<'a> {
+    pub(crate) index: &'a mcap::records::ChunkIndex,
+    pub(crate) skip: MessageSkip,
+}
+
+/// Skips messages logged before `start_time`, then the next `remaining`
+/// messages, then the messages left out of `sample`.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct MessageSkip {
+    remaining: u64,
+    start_time: Option<u64>,
+    sample: Option<SampledMessages>,
+}
+
+impl MessageSkip {
+    pub(crate) fn new(count: u64, start_time: Option<u64>) -> Self {
+        Self {
+            remaining: count,
+            start_time,
+            sample: None,
+        }
+    }
+
+    /// Also skip the messages left out of `sample`, counting the messages
+    /// that pass the rest of this skip.
+    pub(crate) fn with_sample(mut self, sample: SampledMessages) -> Self {
+        self.sample = Some(sample);
+        self
+    }
+
+    /// Whether every remaining message of the topic is skipped.
+    pub(crate) fn is_done(&self) -> bool {
+        self.sample.as_ref().is_some_and(SampledMessages::is_done)
+    }
+
+    /// Number of messages of `channel_id` that pass the start time and
+    /// count, from the message indexes.
+    pub(crate) fn count_passing(
+        &self,
+        mmap: &[u8],
+        summary: &mcap::read::Summary,
+        channel_id: u16,
+    ) -> Result<u64, McapReaderError> {
+        let mut count = 0u64;
+        for chunk_index in &summary.chunk_indexes {
+            if !chunk_index.message_index_offsets.contains_key(&channel_id)
+                || self
+                    .start_time
+                    .is_some_and(|start| chunk_index.message_end_time < start)
+            {
+                continue;
+            }
+            count += channel_log_times(mmap, summary, chunk_index, channel_id)?
+                .filter(|time| self.start_time.is_none_or(|start| *time >= start))
+                .count() as u64;
+        }
+        Ok(count.saturating_sub(self.remaining))
+    }
+
+    /// Whether the next message of the topic, logged at `log_time`, is skipped.
+    pub(crate) fn skips(&mut self, log_time: u64) -> bool {
+        if self.start_time.is_some_and(|start| log_time < start) {
+            return true;
+        }
+        if self.remaining > 0 {
+            self.remaining -= 1;
+            return true;
+        }
+        if let Some(sample) = &mut self.sample {
+            return !sample.keeps_next();
+        }
+        false
+    }
+
+    /// Split this skip over the chunks containing `channel_id`, in file order.
+    ///
+    /// Chunks whose messages are all skipped are left out, using the chunk
+    /// time range and message indexes so they are never decompressed; with
+    /// a sample, this includes the chunks holding none of its messages.
+    pub(crate) fn plan_chunks<'a>(
+        mut self,
+        mmap: &[u8],
+        summary: &'a mcap::read::Summary,
+        channel_id: u16,
+    ) -> Result<Vec<PlannedChunk<'a>>, McapReaderError> {
+        let mut plan = Vec::new();
+        for chunk_index in &summary.chunk_indexes {
+            if !chunk_index.message_index_offsets.contains_key(&channel_id) {
+                continue;
+            }
+            if self
+                .start_time
+                .is_some_and(|start| chunk_index.message_end_time < start)
+            {
+                continue;
+            }
+            let mut passing = None;
+            if self.remaining > 0 || self.sample.is_some() {
+                let count = channel_log_times(mmap, summary, chunk_index, channel_id)?
+                    .filter(|time| self.start_time.is_none_or(|start| *time >= start))
+                    .count() as u64;
+                if count <= self.remaining {
+                    self.remaining -= count;
+                    continue;
+                }
+                let count = count - self.remaining;
+                if let Some(sample) = &mut self.sample
+                    && !sample.keeps_any_of_next(count)
+                {
+                    sample.advance(count);
+                    self.remaining = 0;
+                    continue;
+                }
+                passing = Some(count);
+            }
+            plan.push(PlannedChunk {
+                index: chunk_index,
+                skip: self.clone(),
+            });
+            self.remaining = 0;
+            if let (Some(sample), Some(count)) = (&mut self.sample, passing) {
+                sample.advance(count);
+            }
+        }
+        Ok(plan)
+    }
+}
+
+/// Log times of the messages of `channel_id` in a chunk, from its message index.
+fn channel_log_times(
+    mmap: &[u8],
+    summary: &mcap::read::Summary,
+    chunk_index: &mcap::records::ChunkIndex,
+    channel_id: u16,
+) -> Result<impl Iterator<Item = u64>, McapReaderError> {
+    let entries = summary
+        .read_message_indexes(mmap, chunk_index)?
+        .into_iter()
+        .find(|(channel, _)| channel.id == channel_id)
+        .map(|(_, entries)| entries)
+        .unwrap_or_default();
+    Ok(entries.into_iter().map(|entry| entry.log_time))
+}
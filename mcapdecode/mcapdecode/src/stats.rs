@@ -0,0 +1,119 @@
+//! Decode warnings collected per topic while reading.
+
+use std::{collections::BTreeMap, fmt, sync::Mutex};
+
+use mcapdecode_core::DecodeWarning;
+
+/// Warnings of one field path of a topic.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WarningCount {
+    pub topic: String,
+    pub path: String,
+    pub count: u64,
+    /// Detail of the first warning of the path.
+    pub first_detail: String,
+}
+
+/// Counts the [`DecodeWarning`]s reported while decoding messages and
+/// building their decoders, grouped by topic and field path (or type name).
+///
+/// Pass one instance to `McapReaderBuilder::with_decode_stats` and read it
+/// after decoding; readers built with the same instance add to the same
+/// counts.
+#[derive(Debug, Default)]
+pub struct DecodeStats {
+    warnings: Mutex<BTreeMap<(String, String), (u64, String)>>,
+    /// Struct values aligned to the schema per topic.
+    aligned_structs: Mutex<BTreeMap<String, u64>>,
+}
+
+impl DecodeStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count `warnings` reported while decoding messages of `topic` or
+    /// building their decoder.
+    pub fn record(&self, topic: &str, warnings: Vec<DecodeWarning>) {
+        if warnings.is_empty() {
+            return;
+        }
+        let mut counts = self.warnings.lock().unwrap_or_else(|e| e.into_inner());
+        for warning in warnings {
+            counts
+                .entry((topic.to_string(), warning.path))
+                .or_insert((0, warning.detail))
+                .0 += 1;
+        }
+    }
+
+    /// Count `count` struct values of `topic` with more or fewer fields than
+    /// the schema, aligned to it during Arrow conversion
+    /// (`StructMismatch::Align`).
+    pub fn record_aligned_structs(&self, topic: &str, count: u64) {
+        let mut counts = self
+            .aligned_structs
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        *counts.entry(topic.to_string()).or_default() += count;
+    }
+
+    /// Struct values aligned to the schema so far, per topic, ordered by
+    /// topic.
+    pub fn aligned_structs(&self) -> Vec<(String, u64)> {
+        let counts = self
+            .aligned_structs
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        counts
+            .iter()
+            .map(|(topic, count)| (topic.clone(), *count))
+            .collect()
+    }
+
+    /// Total number of warnings recorded.
+    pub fn warning_count(&self) -> u64 {
+        let counts = self.warnings.lock().unwrap_or_else(|e| e.into_inner());
+        counts.values().map(|(count, _)| count).sum()
+    }
+
+    /// Warnings recorded so far, ordered by topic and path.
+    pub fn warnings(&self) -> Vec<WarningCount> {
+        let counts = self.warnings.lock().unwrap_or_else(|e| e.into_inner());
+        counts
+            .iter()
+            .map(|((topic, path), (count, first_detail))| WarningCount {
+                topic: topic.clone(),
+                path: path.clone(),
+                count: *count,
+                first_detail: first_detail.clone(),
+            })
+            .collect()
+    }
+}
+
+/// One line per topic and path: `'<topic>' <path>: <count> warnings (first: <detail>)`,
+/// then one per topic with aligned struct values.
+impl fmt::Display for DecodeStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for warning in self.warnings() {
+            writeln!(
+                f,
+                "'{}' {}: {} warning{} (first: {})",
+                warning.topic,
+                warning.path,
+                warning.count,
+                if warning.count == 1 { "" } else { "s" },
+                warning.first_detail
+            )?;
+        }
+        for (topic, count) in self.aligned_structs() {
+            writeln!(
+                f,
+                "'{topic}' aligned {count} struct value{} with more or fewer fields than the schema",
+                if count == 1 { "" } else { "s" }
+            )?;
+        }
+        Ok(())
+    }
+}
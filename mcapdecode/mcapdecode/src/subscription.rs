@@ -0,0 +1,118 @@
+//! Decoded message subscriptions backed by a decoding thread.
+
+use std::{
+    error::Error,
+    fmt,
+    path::PathBuf,
+    sync::mpsc::{self, Receiver, RecvTimeoutError, TryRecvError},
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use mcapdecode_core::{DecodedMessage, FieldDefs};
+
+use crate::{McapReader, McapReaderError};
+
+/// Callback error that stops decoding once the subscription is dropped.
+#[derive(Debug)]
+struct SubscriptionClosed;
+
+impl fmt::Display for SubscriptionClosed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("subscription closed")
+    }
+}
+
+impl Error for SubscriptionClosed {}
+
+/// Decoded messages of one topic, produced by a background thread.
+///
+/// The thread blocks once the configured number of messages is buffered
+/// (see `McapReaderBuilder::with_subscription_capacity`), so it never
+/// runs ahead of the consumer. Dropping the subscription stops decoding.
+pub struct Subscription {
+    field_defs: FieldDefs,
+    receiver: Receiver<DecodedMessage>,
+    worker: JoinHandle<Result<(), McapReaderError>>,
+}
+
+impl McapReader {
+    /// Decode messages of `topic` on a background thread and receive them
+    /// through a bounded channel.
+    ///
+    /// The topic is resolved before the thread is started, so an unknown
+    /// topic or a missing decoder is reported here. Errors raised while
+    /// decoding end the subscription and are returned by [`Subscription::join`].
+    pub fn subscribe(
+        &self,
+        path: impl Into<PathBuf>,
+        topic: impl Into<String>,
+    ) -> Result<Subscription, McapReaderError> {
+        let path = path.into();
+        let topic = topic.into();
+        let field_defs = self.topic_field_defs(&path, &topic)?;
+
+        let reader = self.clone();
+        let (sender, receiver) = mpsc::sync_channel(self.subscription_capacity());
+        let worker = thread::spawn(move || {
+            let result = reader.for_each_decoded_message(&path, &topic, |message| {
+                sender.send(message).map_err(|_| SubscriptionClosed.into())
+            });
+            match result {
+                Err(McapReaderError::Callback(e)) if e.is::<SubscriptionClosed>() => Ok(()),
+                result => result,
+            }
+        });
+
+        Ok(Subscription {
+            field_defs,
+            receiver,
+            worker,
+        })
+    }
+}
+
+impl Subscription {
+    /// Schema IR of the subscribed topic.
+    pub fn field_defs(&self) -> &FieldDefs {
+        &self.field_defs
+    }
+
+    /// Block until the next message is decoded.
+    ///
+    /// Returns `None` once all messages were received or decoding failed.
+    pub fn recv(&self) -> Option<DecodedMessage> {
+        self.receiver.recv().ok()
+    }
+
+    /// Like [`Self::recv`], but give up after `timeout`.
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<DecodedMessage, RecvTimeoutError> {
+        self.receiver.recv_timeout(timeout)
+    }
+
+    /// Return the next message if one is already decoded.
+    pub fn try_recv(&self) -> Result<DecodedMessage, TryRecvError> {
+        self.receiver.try_recv()
+    }
+
+    /// Stop decoding and wait for the thread to exit.
+    ///
+    /// Messages that were not received yet are discarded. Returns the error
+    /// that ended decoding early, if any.
+    pub fn join(self) -> Result<(), McapReaderError> {
+        drop(self.receiver);
+        self.worker.join().unwrap_or_else(|_| {
+            Err(McapReaderError::Io(std::io::Error::other(
+                "subscription decode thread panicked",
+            )))
+        })
+    }
+}
+
+impl Iterator for Subscription {
+    type Item = DecodedMessage;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.recv()
+    }
+}
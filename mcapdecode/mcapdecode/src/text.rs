@@ -0,0 +1,69 @@
+//! Decoder for channels without a schema whose payloads are plain text.
+
+use std::borrow::Cow;
+
+use mcapdecode_core::{
+    DataTypeDef, DecodeWarning, DecoderError, EncodingKey, FieldDef, FieldDefs, MessageDecoder,
+    MessageEncoding, SchemaEncoding, TopicDecoder, Value, warn,
+};
+
+/// Name of the single field produced by [`TextDecoder`].
+pub const TEXT_FIELD: &str = "text";
+
+/// [`MessageDecoder`] for schemaless channels (e.g. `message_encoding = "log"`,
+/// or self-describing `json`) that decodes each payload into a single
+/// [`TEXT_FIELD`] string.
+///
+/// Payloads are decoded as UTF-8, replacing invalid sequences with U+FFFD
+/// (reported as a warning), so that the channel can be exported without
+/// knowing its structure.
+#[derive(Debug, Clone)]
+pub struct TextDecoder {
+    message_encoding: MessageEncoding,
+}
+
+impl TextDecoder {
+    /// Decode schemaless channels with the given message encoding.
+    pub fn new(message_encoding: impl Into<MessageEncoding>) -> Self {
+        Self {
+            message_encoding: message_encoding.into(),
+        }
+    }
+}
+
+impl MessageDecoder for TextDecoder {
+    fn encoding_key(&self) -> EncodingKey {
+        EncodingKey::new(SchemaEncoding::None, self.message_encoding.clone())
+    }
+
+    fn build_topic_decoder(
+        &self,
+        _schema_name: &str,
+        _schema_data: &[u8],
+    ) -> Result<Box<dyn TopicDecoder>, DecoderError> {
+        Ok(Box::new(TextTopicDecoder {
+            field_defs: vec![FieldDef::new(TEXT_FIELD, DataTypeDef::String, false)].into(),
+        }))
+    }
+}
+
+struct TextTopicDecoder {
+    field_defs: FieldDefs,
+}
+
+impl TopicDecoder for TextTopicDecoder {
+    fn decode(&self, message_data: &[u8]) -> Result<Value, DecoderError> {
+        let text = String::from_utf8_lossy(message_data);
+        if let Cow::Owned(_) = text {
+            warn(DecodeWarning::new(
+                TEXT_FIELD,
+                "invalid UTF-8 replaced with U+FFFD",
+            ));
+        }
+        Ok(Value::Struct(vec![Value::string(text)]))
+    }
+
+    fn field_defs(&self) -> &FieldDefs {
+        &self.field_defs
+    }
+}
@@ -1,21 +1,37 @@
-#[cfg(feature = "arrow")]
-use std::sync::Arc;
 use std::{
     collections::BTreeMap,
-    fs::{self, File},
+    fs::{self, File, OpenOptions},
+    io::Write,
     path::{Path, PathBuf},
-    sync::atomic::{AtomicUsize, Ordering},
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    thread,
+    time::Duration,
 };
 
 #[cfg(feature = "arrow")]
 use arrow::array::Int64Array;
 use mcap::{WriteOptions, Writer, records::MessageHeader};
+use mcapdecode::{
+    CancellationToken, DecodeStats, FollowOptions, McapReader, McapReaderError, RawSchema,
+    RepackCompression, RepackOptions, RepackSummary, Sample, SequenceJump, TEXT_FIELD, TimeGap,
+    TopicInfo, WarningCount,
+};
 #[cfg(feature = "arrow")]
-use mcapdecode::McapReaderArrowExt;
-use mcapdecode::{McapReader, McapReaderError, TopicInfo};
+use mcapdecode::{
+    ConvertTopicOptions, McapReaderArrowExt, TextDecoder, TopicColumnStats,
+    arrow::{
+        ArrayPolicy, ConversionOptions, FlattenPolicy, ListPolicy, MapPolicy, StatValue,
+        StructMismatch, StructPolicy,
+    },
+    convert_topic, convert_topic_schema,
+};
 use mcapdecode_core::{
-    DataTypeDef, DecoderError, EncodingKey, FieldDef, FieldDefs, MessageDecoder, MessageEncoding,
-    SchemaEncoding, TopicDecoder, Value,
+    DataTypeDef, DecodeWarning, DecodedMessage, DecoderError, EncodingKey, ErrorCategory, FieldDef,
+    FieldDefs, MessageDecoder, MessageEncoding, SchemaEncoding, SchemaResolver, TopicDecoder,
+    Value, warn,
 };
 #[cfg(feature = "arrow")]
 use memmap2::Mmap;
@@ -89,6 +105,107 @@ fn write_chunked_fixture(name: &str, payloads: &[&[u8]]) -> TempFixture {
     TempFixture { path }
 }
 
+/// Write a schemaless `/log` channel whose messages have the given
+/// `(log_time, sequence)` headers.
+fn write_timed_log_fixture(name: &str, headers: &[(u64, u32)]) -> TempFixture {
+    let path = temp_fixture_path(name);
+    let mut writer = Writer::with_options(
+        File::create(&path).unwrap(),
+        WriteOptions::new().library("mcapdecode-test"),
+    )
+    .unwrap();
+    let channel_id = writer
+        .add_channel(0, "/log", "log", &BTreeMap::new())
+        .unwrap();
+    for &(log_time, sequence) in headers {
+        writer
+            .write_to_known_channel(
+                &MessageHeader {
+                    channel_id,
+                    sequence,
+                    log_time,
+                    publish_time: log_time,
+                },
+                b"tick",
+            )
+            .unwrap();
+    }
+    writer.finish().unwrap();
+    TempFixture { path }
+}
+
+/// Write a schemaless `/log` channel with `message_encoding = "log"`.
+fn write_log_fixture(name: &str, payloads: &[&[u8]]) -> TempFixture {
+    let path = temp_fixture_path(name);
+    let mut writer = Writer::with_options(
+        File::create(&path).unwrap(),
+        WriteOptions::new().library("mcapdecode-test"),
+    )
+    .unwrap();
+    let channel_id = writer
+        .add_channel(0, "/log", "log", &BTreeMap::new())
+        .unwrap();
+    for (idx, payload) in payloads.iter().enumerate() {
+        writer
+            .write_to_known_channel(
+                &MessageHeader {
+                    channel_id,
+                    sequence: idx as u32,
+                    log_time: idx as u64,
+                    publish_time: idx as u64,
+                },
+                payload,
+            )
+            .unwrap();
+    }
+    writer.finish().unwrap();
+    TempFixture { path }
+}
+
+/// Write zstd-compressed chunks, then rename the codec in every chunk and chunk
+/// index record to the 4-character `compression`.
+fn write_renamed_compression_fixture(name: &str, compression: &[u8; 4]) -> TempFixture {
+    let path = temp_fixture_path(name);
+    let mut writer = Writer::with_options(
+        File::create(&path).unwrap(),
+        WriteOptions::new()
+            .compression(Some(mcap::Compression::Zstd))
+            .chunk_size(Some(1))
+            .library("mcapdecode-test"),
+    )
+    .unwrap();
+    let schema_id = writer
+        .add_schema("test.Msg", "jsonschema", br#"{"type":"object"}"#)
+        .unwrap();
+    let channel_id = writer
+        .add_channel(schema_id, "/decoded", "json", &BTreeMap::new())
+        .unwrap();
+    for idx in 0..2u32 {
+        writer
+            .write_to_known_channel(
+                &MessageHeader {
+                    channel_id,
+                    sequence: idx,
+                    log_time: u64::from(idx),
+                    publish_time: u64::from(idx),
+                },
+                br#"{"value":1}"#,
+            )
+            .unwrap();
+    }
+    writer.finish().unwrap();
+
+    let mut bytes = fs::read(&path).unwrap();
+    let mut pos = 0;
+    while let Some(offset) = bytes[pos..].windows(4).position(|w| w == b"zstd") {
+        let start = pos + offset;
+        bytes[start..start + 4].copy_from_slice(compression);
+        pos = start + 4;
+    }
+    fs::write(&path, bytes).unwrap();
+    TempFixture { path }
+}
+
 fn write_duplicate_topic_fixture(name: &str) -> TempFixture {
     let path = temp_fixture_path(name);
     let file = File::create(&path).unwrap();
@@ -130,6 +247,39 @@ fn write_duplicate_topic_fixture(name: &str) -> TempFixture {
     TempFixture { path }
 }
 
+/// Write the first `len` bytes of `source`, like a file that is still being recorded.
+fn write_partial_copy(name: &str, source: &Path, len: usize) -> TempFixture {
+    let path = temp_fixture_path(name);
+    let data = fs::read(source).unwrap();
+    fs::write(&path, &data[..len]).unwrap();
+    TempFixture { path }
+}
+
+fn value_payloads(count: usize) -> Vec<Vec<u8>> {
+    (1..=count)
+        .map(|value| format!(r#"{{"value":{value}}}"#).into_bytes())
+        .collect()
+}
+
+fn collect_followed_i64_values(
+    reader: &McapReader,
+    path: &Path,
+    options: &FollowOptions,
+) -> Result<Vec<i64>, McapReaderError> {
+    let mut values = Vec::new();
+    reader.follow_decoded_messages(path, "/decoded", options, |message| {
+        match message.value {
+            Value::Struct(fields) => match fields[0] {
+                Value::I64(value) => values.push(value),
+                ref other => panic!("expected I64 field, got {other:?}"),
+            },
+            other => panic!("expected struct payload, got {other:?}"),
+        }
+        Ok(())
+    })?;
+    Ok(values)
+}
+
 #[cfg(feature = "arrow")]
 fn chunk_index_count(path: &Path) -> usize {
     let file = File::open(path).unwrap();
@@ -430,6 +580,88 @@ fn for_each_record_batch_propagates_callback_error() {
     assert!(err.to_string().contains("callback failed"));
 }
 
+/// [`arrow::record_batch::RecordBatchWriter`] keeping the written batches.
+#[cfg(feature = "arrow")]
+struct CollectingSink<'a> {
+    batches: &'a mut Vec<arrow::record_batch::RecordBatch>,
+    closed: &'a mut bool,
+}
+
+#[cfg(feature = "arrow")]
+impl arrow::record_batch::RecordBatchWriter for CollectingSink<'_> {
+    fn write(
+        &mut self,
+        batch: &arrow::record_batch::RecordBatch,
+    ) -> Result<(), arrow::error::ArrowError> {
+        self.batches.push(batch.clone());
+        Ok(())
+    }
+
+    fn close(self) -> Result<(), arrow::error::ArrowError> {
+        *self.closed = true;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "arrow")]
+#[test]
+fn convert_topic_writes_flattened_batches_and_closes_sink() {
+    let fixture = write_log_fixture("convert-topic", &[b"one", b"two", b"three"]);
+    let options = ConvertTopicOptions {
+        flatten: Some(FlattenPolicy {
+            list: ListPolicy::Drop,
+            list_flatten_fixed_size: 1,
+            array: ArrayPolicy::Drop,
+            map: MapPolicy::Drop,
+            struct_: StructPolicy::Flatten,
+        }),
+        batch_size: Some(2),
+        ..ConvertTopicOptions::default()
+    };
+    let mut batches = Vec::new();
+    let mut closed = false;
+    let sink = CollectingSink {
+        batches: &mut batches,
+        closed: &mut closed,
+    };
+
+    let rows = convert_topic(fixture.path(), "/log", sink, &options).unwrap();
+
+    assert_eq!(rows, 3);
+    assert!(closed);
+    assert_eq!(
+        batches.iter().map(|b| b.num_rows()).collect::<Vec<_>>(),
+        vec![2, 1]
+    );
+    let schema = convert_topic_schema(fixture.path(), "/log", &options).unwrap();
+    assert_eq!(batches[0].schema(), schema);
+    let names: Vec<_> = schema.fields().iter().map(|f| f.name().as_str()).collect();
+    assert_eq!(names, ["@log_time", "@publish_time", TEXT_FIELD]);
+}
+
+#[cfg(feature = "arrow")]
+#[test]
+fn convert_topic_reports_unknown_topic() {
+    let fixture = write_log_fixture("convert-topic-unknown", &[b"one"]);
+    let mut batches = Vec::new();
+    let mut closed = false;
+    let sink = CollectingSink {
+        batches: &mut batches,
+        closed: &mut closed,
+    };
+
+    let err = convert_topic(
+        fixture.path(),
+        "/missing",
+        sink,
+        &ConvertTopicOptions::default(),
+    )
+    .unwrap_err();
+
+    assert!(matches!(err, McapReaderError::TopicNotFound { .. }));
+    assert!(!closed);
+}
+
 #[cfg(feature = "arrow")]
 #[test]
 fn for_each_record_batch_emits_batches_by_batch_size() {
@@ -531,6 +763,172 @@ fn for_each_decoded_message_errors_when_schema_is_missing() {
     ));
 }
 
+#[test]
+fn for_each_decoded_message_decodes_log_topic_as_text() {
+    let fixture = write_log_fixture("log-text", &[b"started", b"bad \xff byte"]);
+    let reader = McapReader::builder().with_default_decoders().build();
+
+    let mut values = Vec::new();
+    reader
+        .for_each_decoded_message(fixture.path(), "/log", |message| {
+            values.push(message.value);
+            Ok(())
+        })
+        .unwrap();
+
+    assert_eq!(
+        values,
+        vec![
+            Value::Struct(vec![Value::string("started")]),
+            Value::Struct(vec![Value::string("bad \u{fffd} byte")]),
+        ]
+    );
+}
+
+#[cfg(feature = "ros2msg")]
+#[test]
+fn for_each_decoded_message_decodes_cdr_le_spelling_with_ros2msg_decoder() {
+    let path = temp_fixture_path("cdr-le");
+    let mut writer = Writer::with_options(
+        File::create(&path).unwrap(),
+        WriteOptions::new().library("mcapdecode-test"),
+    )
+    .unwrap();
+    let schema_id = writer
+        .add_schema("std_msgs/msg/Int32", "ros2msg", b"int32 data")
+        .unwrap();
+    let channel_id = writer
+        .add_channel(schema_id, "/count", "cdr_le", &BTreeMap::new())
+        .unwrap();
+    writer
+        .write_to_known_channel(
+            &MessageHeader {
+                channel_id,
+                sequence: 0,
+                log_time: 1,
+                publish_time: 1,
+            },
+            &[0x00, 0x01, 0x00, 0x00, 0x07, 0x00, 0x00, 0x00],
+        )
+        .unwrap();
+    writer.finish().unwrap();
+    let fixture = TempFixture { path };
+
+    let reader = McapReader::builder().with_default_decoders().build();
+    let mut values = Vec::new();
+    reader
+        .for_each_decoded_message(fixture.path(), "/count", |message| {
+            values.push(message.value);
+            Ok(())
+        })
+        .unwrap();
+
+    assert_eq!(values, vec![Value::Struct(vec![Value::I32(7)])]);
+}
+
+#[test]
+fn decode_stats_count_decoder_warnings_per_topic() {
+    let fixture = write_log_fixture("log-warnings", &[b"\xff", b"ok", b"bad \xfe"]);
+    let stats = Arc::new(DecodeStats::new());
+    for parallel in [false, true] {
+        let reader = McapReader::builder()
+            .with_default_decoders()
+            .with_parallel(parallel)
+            .with_decode_stats(Arc::clone(&stats))
+            .build();
+        reader
+            .for_each_decoded_message(fixture.path(), "/log", |_| Ok(()))
+            .unwrap();
+    }
+
+    assert_eq!(
+        stats.warnings(),
+        vec![WarningCount {
+            topic: "/log".to_string(),
+            path: TEXT_FIELD.to_string(),
+            count: 4,
+            first_detail: "invalid UTF-8 replaced with U+FFFD".to_string(),
+        }]
+    );
+    assert_eq!(
+        stats.to_string(),
+        "'/log' text: 4 warnings (first: invalid UTF-8 replaced with U+FFFD)\n"
+    );
+}
+
+/// Reports a warning about the schema while building its topic decoder.
+struct SchemaWarningDecoder;
+
+impl MessageDecoder for SchemaWarningDecoder {
+    fn encoding_key(&self) -> EncodingKey {
+        TestJsonDecoder.encoding_key()
+    }
+
+    fn build_topic_decoder(
+        &self,
+        schema_name: &str,
+        schema_data: &[u8],
+    ) -> Result<Box<dyn TopicDecoder>, DecoderError> {
+        warn(DecodeWarning::new(schema_name, "guessed the root type"));
+        TestJsonDecoder.build_topic_decoder(schema_name, schema_data)
+    }
+}
+
+#[test]
+fn decode_stats_count_schema_warnings_of_topic() {
+    let payloads = value_payloads(2);
+    let payloads: Vec<&[u8]> = payloads.iter().map(Vec::as_slice).collect();
+    let fixture = write_chunked_fixture("schema-warnings", &payloads);
+    let stats = Arc::new(DecodeStats::new());
+    let reader = McapReader::builder()
+        .with_decoder(Box::new(SchemaWarningDecoder))
+        .with_decode_stats(Arc::clone(&stats))
+        .build();
+    reader
+        .for_each_decoded_message(fixture.path(), "/decoded", |_| Ok(()))
+        .unwrap();
+
+    assert_eq!(
+        stats.to_string(),
+        "'/decoded' test.Msg: 1 warning (first: guessed the root type)\n"
+    );
+}
+
+#[test]
+fn for_each_decoded_message_without_text_decoder_errors_for_log_topic() {
+    let fixture = write_log_fixture("log-missing", &[b"started"]);
+    let reader = McapReader::new();
+
+    let err = reader
+        .for_each_decoded_message(fixture.path(), "/log", |_message| Ok(()))
+        .unwrap_err();
+
+    assert!(matches!(
+        err,
+        McapReaderError::SchemaNotAvailable { ref topic, .. } if topic == "/log"
+    ));
+}
+
+#[cfg(feature = "arrow")]
+#[test]
+fn for_each_record_batch_emits_text_column_for_log_topic() {
+    let fixture = write_log_fixture("log-batch", &[b"a", b"b"]);
+    let reader = McapReader::builder()
+        .with_decoder(Box::new(TextDecoder::new("log")))
+        .build();
+
+    let mut rows = 0;
+    reader
+        .for_each_record_batch(fixture.path(), "/log", |batch| {
+            assert!(batch.schema().field_with_name(TEXT_FIELD).is_ok());
+            rows += batch.num_rows();
+            Ok(())
+        })
+        .unwrap();
+
+    assert_eq!(rows, 2);
+}
+
 #[test]
 fn for_each_decoded_message_without_decoder_returns_error() {
     let reader = McapReader::new();
@@ -544,6 +942,131 @@ fn for_each_decoded_message_without_decoder_returns_error() {
     assert!(matches!(err, McapReaderError::NoDecoder { .. }));
 }
 
+#[test]
+fn topic_schema_returns_stored_schema_record() {
+    let fixture = write_chunked_fixture("topic-schema", &[br#"{"value":1}"#]);
+    let reader = McapReader::new();
+
+    assert_eq!(
+        reader.topic_schema(fixture.path(), "/decoded").unwrap(),
+        RawSchema {
+            name: "test.Msg".to_string(),
+            encoding: "jsonschema".to_string(),
+            data: Arc::from(&br#"{"type":"object"}"#[..]),
+        }
+    );
+}
+
+#[test]
+fn topic_schema_errors_for_schema_less_topic() {
+    let fixture = write_log_fixture("topic-schema-log", &[b"started"]);
+    let reader = McapReader::new();
+
+    let err = reader.topic_schema(fixture.path(), "/log").unwrap_err();
+
+    assert!(matches!(
+        err,
+        McapReaderError::SchemaNotAvailable { ref topic, .. } if topic == "/log"
+    ));
+}
+
+#[test]
+fn repack_rewrites_messages_with_new_compression() {
+    let input = write_timed_log_fixture("repack-in", &[(0, 1), (10, 2), (20, 3)]);
+    let output = TempFixture {
+        path: temp_fixture_path("repack-out"),
+    };
+    let reader = McapReader::new();
+
+    let summary = reader
+        .repack(
+            input.path(),
+            output.path(),
+            &RepackOptions {
+                compression: RepackCompression::Zstd,
+                chunk_size: 16,
+            },
+        )
+        .unwrap();
+
+    assert_eq!(
+        summary,
+        RepackSummary {
+            messages: 3,
+            ..RepackSummary::default()
+        }
+    );
+    let compressions = reader.chunk_compressions(output.path()).unwrap();
+    assert_eq!(compressions.keys().collect::<Vec<_>>(), ["zstd"]);
+    assert!(compressions["zstd"] > 1);
+    assert_eq!(reader.message_count(output.path(), "/log").unwrap(), 3);
+    let report = reader.gap_report(output.path(), "/log", None).unwrap();
+    assert_eq!(report.sequence_jumps, Some(Vec::new()));
+}
+
+#[test]
+fn repack_refuses_to_overwrite_input() {
+    let input = write_timed_log_fixture("repack-same", &[(0, 1)]);
+    let reader = McapReader::new();
+
+    let err = reader
+        .repack(input.path(), input.path(), &RepackOptions::default())
+        .unwrap_err();
+
+    assert!(matches!(err, McapReaderError::Io(_)));
+    assert_eq!(reader.message_count(input.path(), "/log").unwrap(), 1);
+}
+
+#[test]
+fn gap_report_flags_time_gaps_and_sequence_jumps() {
+    let fixture = write_timed_log_fixture(
+        "gap-report",
+        &[(0, 1), (10, 2), (20, 3), (100, 5), (110, 6)],
+    );
+    let reader = McapReader::new();
+
+    let report = reader.gap_report(fixture.path(), "/log", None).unwrap();
+
+    assert_eq!(report.message_count, 5);
+    assert_eq!(report.min_interval_ns, Some(10));
+    assert_eq!(report.max_interval_ns, Some(80));
+    assert_eq!(report.threshold_ns, Some(30));
+    assert_eq!(
+        report.gaps,
+        vec![TimeGap {
+            index: 3,
+            start_ns: 20,
+            end_ns: 100,
+        }]
+    );
+    assert_eq!(
+        report.sequence_jumps,
+        Some(vec![SequenceJump {
+            index: 3,
+            log_time: 100,
+            expected: 4,
+            actual: 5,
+        }])
+    );
+    assert!(report.has_issues());
+
+    let report = reader
+        .gap_report(fixture.path(), "/log", Some(Duration::from_nanos(100)))
+        .unwrap();
+    assert!(report.gaps.is_empty());
+}
+
+#[test]
+fn gap_report_skips_sequence_check_without_sequence_numbers() {
+    let fixture = write_timed_log_fixture("gap-report-no-seq", &[(0, 0), (10, 0), (20, 0)]);
+    let reader = McapReader::new();
+
+    let report = reader.gap_report(fixture.path(), "/log", None).unwrap();
+
+    assert_eq!(report.sequence_jumps, None);
+    assert!(!report.has_issues());
+}
+
 #[test]
 fn for_each_raw_message_reads_schema_less_topic_payloads() {
     let reader = McapReader::new();
@@ -585,14 +1108,61 @@ fn for_each_raw_message_propagates_callback_error() {
 }
 
 #[test]
-fn for_each_decoded_message_parallel_matches_sequential_for_multi_chunk_fixture() {
-    let fixture = write_chunked_fixture(
-        "parallel-multi-chunk-decoded",
-        &[
-            br#"{"value":1}"#,
-            br#"{"value":2}"#,
-            br#"{"value":3}"#,
-            br#"{"value":4}"#,
+fn for_each_channel_message_emits_messages_of_every_channel() {
+    let fixture = write_duplicate_topic_fixture("channel-messages");
+    let mut messages = Vec::new();
+
+    McapReader::new()
+        .for_each_channel_message(fixture.path(), |message| {
+            messages.push(message);
+            Ok(())
+        })
+        .unwrap();
+
+    assert_eq!(messages.len(), 2);
+    for (message, (source, value)) in messages.iter().zip([("left", 1), ("right", 2)]) {
+        let channel = &message.channel;
+        assert_eq!(channel.topic, "/duplicate");
+        assert_eq!(channel.message_encoding, "json");
+        assert_eq!(
+            channel.metadata.get("source").map(String::as_str),
+            Some(source)
+        );
+        assert_eq!(
+            channel.schema,
+            Some(RawSchema {
+                name: "test.Msg".to_string(),
+                encoding: "jsonschema".to_string(),
+                data: Arc::from(&br#"{"type":"object"}"#[..]),
+            })
+        );
+        assert_eq!(message.log_time, value);
+        assert_eq!(&*message.data, format!(r#"{{"value":{value}}}"#).as_bytes());
+    }
+    assert_ne!(messages[0].channel.id, messages[1].channel.id);
+}
+
+#[test]
+fn for_each_channel_message_propagates_callback_error() {
+    let fixture = write_duplicate_topic_fixture("channel-messages-callback-error");
+
+    let err = McapReader::new()
+        .for_each_channel_message(fixture.path(), |_message| Err("callback failed".into()))
+        .unwrap_err();
+
+    assert!(matches!(err, McapReaderError::Callback(_)));
+    assert!(err.to_string().contains("callback failed"));
+}
+
+#[test]
+fn for_each_decoded_message_parallel_matches_sequential_for_multi_chunk_fixture() {
+    let fixture = write_chunked_fixture(
+        "parallel-multi-chunk-decoded",
+        &[
+            br#"{"value":1}"#,
+            br#"{"value":2}"#,
+            br#"{"value":3}"#,
+            br#"{"value":4}"#,
             br#"{"value":5}"#,
         ],
     );
@@ -612,6 +1182,513 @@ fn for_each_decoded_message_parallel_matches_sequential_for_multi_chunk_fixture(
     );
 }
 
+/// Wraps [`TestJsonDecoder`], counting the payloads decoded in batches.
+struct BatchCountingDecoder {
+    batched: Arc<AtomicUsize>,
+}
+
+struct BatchCountingTopicDecoder {
+    inner: Box<dyn TopicDecoder>,
+    batched: Arc<AtomicUsize>,
+}
+
+impl MessageDecoder for BatchCountingDecoder {
+    fn encoding_key(&self) -> EncodingKey {
+        TestJsonDecoder.encoding_key()
+    }
+
+    fn build_topic_decoder(
+        &self,
+        schema_name: &str,
+        schema_data: &[u8],
+    ) -> Result<Box<dyn TopicDecoder>, DecoderError> {
+        Ok(Box::new(BatchCountingTopicDecoder {
+            inner: TestJsonDecoder.build_topic_decoder(schema_name, schema_data)?,
+            batched: Arc::clone(&self.batched),
+        }))
+    }
+}
+
+impl TopicDecoder for BatchCountingTopicDecoder {
+    fn decode(&self, message_data: &[u8]) -> Result<Value, DecoderError> {
+        self.inner.decode(message_data)
+    }
+
+    fn decode_batch(&self, payloads: &[&[u8]]) -> Result<Vec<Value>, DecoderError> {
+        self.batched.fetch_add(payloads.len(), Ordering::Relaxed);
+        self.inner.decode_batch(payloads)
+    }
+
+    fn field_defs(&self) -> &FieldDefs {
+        self.inner.field_defs()
+    }
+}
+
+#[test]
+fn parallel_reader_decodes_chunks_in_batches() {
+    let payloads = value_payloads(5);
+    let payloads: Vec<&[u8]> = payloads.iter().map(Vec::as_slice).collect();
+    let fixture = write_chunked_fixture("batch-decode", &payloads);
+
+    for (parallel, expected_batched) in [(true, 5), (false, 0)] {
+        let batched = Arc::new(AtomicUsize::new(0));
+        let reader = McapReader::builder()
+            .with_decoder(Box::new(BatchCountingDecoder {
+                batched: Arc::clone(&batched),
+            }))
+            .with_parallel(parallel)
+            .build();
+        assert_eq!(
+            collect_decoded_i64_values(&reader, fixture.path(), "/decoded"),
+            vec![1, 2, 3, 4, 5],
+            "parallel={parallel}"
+        );
+        assert_eq!(batched.load(Ordering::Relaxed), expected_batched);
+    }
+}
+
+#[test]
+fn dedup_skips_consecutive_identical_payloads() {
+    let fixture = write_chunked_fixture(
+        "dedup-consecutive",
+        &[
+            br#"{"value":1}"#,
+            br#"{"value":1}"#,
+            br#"{"value":2}"#,
+            br#"{"value":2}"#,
+            br#"{"value":2}"#,
+            br#"{"value":1}"#,
+        ],
+    );
+
+    for parallel in [true, false] {
+        let reader = McapReader::builder()
+            .with_decoder(Box::new(TestJsonDecoder))
+            .with_parallel(parallel)
+            .with_dedup(true)
+            .build();
+        assert_eq!(
+            collect_decoded_i64_values(&reader, fixture.path(), "/decoded"),
+            vec![1, 2, 1],
+            "parallel={parallel}"
+        );
+    }
+
+    let reader = McapReader::builder()
+        .with_decoder(Box::new(TestJsonDecoder))
+        .build();
+    assert_eq!(
+        collect_decoded_i64_values(&reader, fixture.path(), "/decoded"),
+        vec![1, 1, 2, 2, 2, 1]
+    );
+}
+
+#[test]
+fn dedup_applies_to_raw_messages() {
+    let fixture = write_chunked_fixture(
+        "dedup-raw",
+        &[br#"{"value":1}"#, br#"{"value":1}"#, br#"{"value":3}"#],
+    );
+    let reader = McapReader::builder().with_dedup(true).build();
+
+    assert_eq!(
+        collect_raw_payloads(&reader, fixture.path(), "/decoded"),
+        vec![br#"{"value":1}"#.to_vec(), br#"{"value":3}"#.to_vec()]
+    );
+}
+
+#[test]
+fn skip_messages_and_start_time_skip_leading_messages() {
+    let payloads: Vec<Vec<u8>> = (1..=6)
+        .map(|value| format!(r#"{{"value":{value}}}"#).into_bytes())
+        .collect();
+    let payloads: Vec<&[u8]> = payloads.iter().map(Vec::as_slice).collect();
+    let fixture = write_chunked_fixture("skip-leading", &payloads);
+
+    for parallel in [true, false] {
+        let builder = || {
+            McapReader::builder()
+                .with_decoder(Box::new(TestJsonDecoder))
+                .with_parallel(parallel)
+        };
+
+        let reader = builder().with_skip_messages(4).build();
+        assert_eq!(
+            collect_decoded_i64_values(&reader, fixture.path(), "/decoded"),
+            vec![5, 6],
+            "parallel={parallel}"
+        );
+
+        // Log times are 1..=6; the count applies after the time filter.
+        let reader = builder().with_start_time(3).with_skip_messages(1).build();
+        assert_eq!(
+            collect_decoded_i64_values(&reader, fixture.path(), "/decoded"),
+            vec![4, 5, 6],
+            "parallel={parallel}"
+        );
+
+        let reader = builder().with_skip_messages(10).build();
+        assert!(collect_decoded_i64_values(&reader, fixture.path(), "/decoded").is_empty());
+    }
+
+    let reader = McapReader::builder().with_skip_messages(5).build();
+    assert_eq!(
+        collect_raw_payloads(&reader, fixture.path(), "/decoded"),
+        vec![br#"{"value":6}"#.to_vec()]
+    );
+}
+
+#[test]
+fn sample_keeps_head_tail_and_random_messages() {
+    let payloads = value_payloads(10);
+    let payloads: Vec<&[u8]> = payloads.iter().map(Vec::as_slice).collect();
+    let fixture = write_chunked_fixture("sample", &payloads);
+
+    let mut random_picks = Vec::new();
+    for parallel in [true, false] {
+        let builder = || {
+            McapReader::builder()
+                .with_decoder(Box::new(TestJsonDecoder))
+                .with_parallel(parallel)
+        };
+
+        let reader = builder().with_sample(Sample::Head(3)).build();
+        assert_eq!(
+            collect_decoded_i64_values(&reader, fixture.path(), "/decoded"),
+            vec![1, 2, 3],
+            "parallel={parallel}"
+        );
+
+        let reader = builder().with_sample(Sample::Tail(2)).build();
+        assert_eq!(
+            collect_decoded_i64_values(&reader, fixture.path(), "/decoded"),
+            vec![9, 10],
+            "parallel={parallel}"
+        );
+
+        // The sample is taken from the messages left after skipping.
+        let reader = builder()
+            .with_skip_messages(6)
+            .with_sample(Sample::Head(2))
+            .build();
+        assert_eq!(
+            collect_decoded_i64_values(&reader, fixture.path(), "/decoded"),
+            vec![7, 8],
+            "parallel={parallel}"
+        );
+
+        let reader = builder().with_sample(Sample::Random(4)).build();
+        let picked = collect_decoded_i64_values(&reader, fixture.path(), "/decoded");
+        assert_eq!(picked.len(), 4, "parallel={parallel}");
+        assert!(picked.windows(2).all(|w| w[0] < w[1]), "{picked:?}");
+        random_picks.push(picked);
+
+        let reader = builder().with_sample(Sample::Random(20)).build();
+        assert_eq!(
+            collect_decoded_i64_values(&reader, fixture.path(), "/decoded"),
+            (1..=10).collect::<Vec<_>>(),
+            "parallel={parallel}"
+        );
+    }
+    assert_eq!(random_picks[0], random_picks[1]);
+
+    let reader = McapReader::builder().with_sample(Sample::Tail(1)).build();
+    assert_eq!(
+        collect_raw_payloads(&reader, fixture.path(), "/decoded"),
+        vec![br#"{"value":10}"#.to_vec()]
+    );
+}
+
+#[test]
+fn cancellation_token_stops_reads_cleanly() {
+    let payloads = value_payloads(10);
+    let payloads: Vec<&[u8]> = payloads.iter().map(Vec::as_slice).collect();
+    let fixture = write_chunked_fixture("cancel", &payloads);
+
+    for parallel in [true, false] {
+        let token = CancellationToken::new();
+        let reader = McapReader::builder()
+            .with_decoder(Box::new(TestJsonDecoder))
+            .with_parallel(parallel)
+            .with_cancellation_token(token.clone())
+            .build();
+        let mut values = Vec::new();
+        reader
+            .for_each_decoded_message(fixture.path(), "/decoded", |message| {
+                let Value::Struct(fields) = message.value else {
+                    panic!("expected struct payload");
+                };
+                values.push(fields[0].clone());
+                if values.len() == 3 {
+                    token.cancel();
+                }
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(
+            values,
+            vec![Value::I64(1), Value::I64(2), Value::I64(3)],
+            "parallel={parallel}"
+        );
+        assert!(token.is_cancelled());
+
+        // Reads started after cancellation emit nothing.
+        assert!(collect_raw_payloads(&reader, fixture.path(), "/decoded").is_empty());
+    }
+}
+
+#[test]
+fn prepare_builds_decoders_once_for_later_reads() {
+    let payloads = value_payloads(3);
+    let payloads: Vec<&[u8]> = payloads.iter().map(Vec::as_slice).collect();
+    let fixture = write_chunked_fixture("prepare", &payloads);
+    let reader = McapReader::builder()
+        .with_decoder(Box::new(TestJsonDecoder))
+        .build();
+
+    let prepared = reader.prepare(fixture.path(), &["/decoded"]).unwrap();
+    let [topic] = prepared.topics() else {
+        panic!("expected one prepared topic");
+    };
+    assert_eq!(topic.topic, "/decoded");
+    assert_eq!(topic.schema_name.as_deref(), Some("test.Msg"));
+    assert_eq!(topic.field_defs[0].name, "value");
+
+    let mut values = Vec::new();
+    prepared
+        .for_each_decoded_message("/decoded", |message| {
+            values.push(message.value);
+            Ok(())
+        })
+        .unwrap();
+    assert_eq!(values.len(), 3);
+
+    let err = prepared
+        .for_each_decoded_message("/other", |_| Ok(()))
+        .unwrap_err();
+    assert_eq!(err.code(), "topic_not_found");
+}
+
+#[test]
+fn prepare_reports_every_problem_at_once() {
+    let path = temp_fixture_path("prepare-unindexed");
+    let fixture = TempFixture { path: path.clone() };
+    let mut writer = Writer::with_options(
+        File::create(&path).unwrap(),
+        WriteOptions::new()
+            .emit_message_indexes(false)
+            .library("mcapdecode-test"),
+    )
+    .unwrap();
+    let schema_id = writer
+        .add_schema("test.Msg", "jsonschema", br#"{"type":"object"}"#)
+        .unwrap();
+    let channel_id = writer
+        .add_channel(schema_id, "/decoded", "json", &BTreeMap::new())
+        .unwrap();
+    writer
+        .write_to_known_channel(
+            &MessageHeader {
+                channel_id,
+                sequence: 0,
+                log_time: 1,
+                publish_time: 1,
+            },
+            br#"{"value":1}"#,
+        )
+        .unwrap();
+    writer.finish().unwrap();
+
+    // No decoder is registered, the second topic does not exist and the
+    // sample cannot be located without message indexes.
+    let reader = McapReader::builder().with_sample(Sample::Head(1)).build();
+    let err = match reader.prepare(fixture.path(), &["/decoded", "/missing"]) {
+        Ok(_) => panic!("prepare should fail"),
+        Err(err) => err,
+    };
+    assert_eq!(err.code(), "prepare_failed");
+    let McapReaderError::PrepareFailed { errors, .. } = err else {
+        unreachable!();
+    };
+    let codes: Vec<_> = errors.iter().map(McapReaderError::code).collect();
+    assert_eq!(
+        codes,
+        vec!["invalid_option", "no_decoder", "topic_not_found"]
+    );
+}
+
+#[test]
+fn sample_parses_mode_and_count() {
+    assert_eq!("head:100".parse(), Ok(Sample::Head(100)));
+    assert_eq!("TAIL:5".parse(), Ok(Sample::Tail(5)));
+    assert_eq!("random:1000".parse(), Ok(Sample::Random(1000)));
+    assert_eq!(Sample::Random(1000).to_string(), "random:1000");
+    assert_eq!(
+        "first:3".parse::<Sample>(),
+        Err("invalid sample 'first:3' (expected: head:N, tail:N, random:N)".to_string())
+    );
+    assert!("head".parse::<Sample>().is_err());
+}
+
+/// Decoder whose single field is named after the schema data it was built from.
+struct SchemaDataDecoder;
+
+impl MessageDecoder for SchemaDataDecoder {
+    fn encoding_key(&self) -> EncodingKey {
+        EncodingKey::new(SchemaEncoding::JsonSchema, MessageEncoding::Json)
+    }
+
+    fn build_topic_decoder(
+        &self,
+        _schema_name: &str,
+        schema_data: &[u8],
+    ) -> Result<Box<dyn TopicDecoder>, DecoderError> {
+        let name = String::from_utf8_lossy(schema_data);
+        Ok(Box::new(TestJsonTopicDecoder {
+            field_defs: vec![FieldDef::new(name, DataTypeDef::I64, true)].into(),
+        }))
+    }
+}
+
+struct StubSchemaResolver {
+    fail: bool,
+}
+
+impl SchemaResolver for StubSchemaResolver {
+    fn resolve_schema(
+        &self,
+        schema_encoding: &SchemaEncoding,
+        schema_name: &str,
+        _schema_data: &[u8],
+    ) -> Result<Option<Vec<u8>>, DecoderError> {
+        assert_eq!(*schema_encoding, SchemaEncoding::JsonSchema);
+        if self.fail {
+            return Err(DecoderError::SchemaResolve {
+                schema_name: schema_name.to_string(),
+                source: "registry unavailable".into(),
+            });
+        }
+        Ok(Some(format!("resolved:{schema_name}").into_bytes()))
+    }
+}
+
+#[test]
+fn schema_resolver_replaces_schema_data() {
+    let fixture = write_chunked_fixture("schema-resolver", &[br#"{"value":5}"#]);
+    let reader = McapReader::builder()
+        .with_decoder(Box::new(SchemaDataDecoder))
+        .with_schema_resolver(Box::new(StubSchemaResolver { fail: false }))
+        .build();
+
+    let field_defs = reader.topic_field_defs(fixture.path(), "/decoded").unwrap();
+    assert_eq!(field_defs[0].name, "resolved:test.Msg");
+    assert_eq!(
+        collect_decoded_i64_values(&reader, fixture.path(), "/decoded"),
+        vec![5]
+    );
+
+    let reader = McapReader::builder()
+        .with_decoder(Box::new(SchemaDataDecoder))
+        .build();
+    let field_defs = reader.topic_field_defs(fixture.path(), "/decoded").unwrap();
+    assert_eq!(field_defs[0].name, r#"{"type":"object"}"#);
+}
+
+#[test]
+fn schema_resolver_error_is_retryable_schema_issue() {
+    let fixture = write_chunked_fixture("schema-resolver-error", &[br#"{"value":5}"#]);
+    let reader = McapReader::builder()
+        .with_decoder(Box::new(SchemaDataDecoder))
+        .with_schema_resolver(Box::new(StubSchemaResolver { fail: true }))
+        .build();
+
+    let err = reader
+        .topic_field_defs(fixture.path(), "/decoded")
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        McapReaderError::SchemaDerivationFailed {
+            source: DecoderError::SchemaResolve { .. },
+            ..
+        }
+    ));
+    assert_eq!(err.category(), ErrorCategory::SchemaIssue);
+    assert!(err.is_retryable());
+}
+
+#[test]
+fn chunk_compressions_counts_chunks_per_codec() {
+    let fixture = write_renamed_compression_fixture("compression-count", b"zstd");
+    let reader = McapReader::new();
+
+    let compressions = reader.chunk_compressions(fixture.path()).unwrap();
+    assert_eq!(compressions.keys().collect::<Vec<_>>(), vec!["zstd"]);
+    assert!(compressions["zstd"] >= 2);
+}
+
+#[test]
+fn profile_is_read_from_header_of_unfinished_files() {
+    let path = temp_fixture_path("profile");
+    let writer = Writer::with_options(
+        File::create(&path).unwrap(),
+        WriteOptions::new()
+            .profile("ros2")
+            .library("mcapdecode-test"),
+    )
+    .unwrap();
+    drop(writer);
+    let fixture = TempFixture { path };
+    let reader = McapReader::new();
+    assert_eq!(reader.profile(fixture.path()).unwrap(), "ros2");
+
+    // Cut off the footer, as for a file that is still being recorded.
+    let bytes = fs::read(fixture.path()).unwrap();
+    fs::write(fixture.path(), &bytes[..bytes.len() - 8]).unwrap();
+    assert_eq!(reader.profile(fixture.path()).unwrap(), "ros2");
+
+    let fixture = write_chunked_fixture("profile-unset", &[br#"{"value":1}"#]);
+    assert_eq!(reader.profile(fixture.path()).unwrap(), "");
+}
+
+#[test]
+fn unsupported_compression_fails_before_decoding() {
+    let fixture = write_renamed_compression_fixture("compression-unsupported", b"brot");
+
+    for parallel in [true, false] {
+        let reader = McapReader::builder()
+            .with_decoder(Box::new(TestJsonDecoder))
+            .with_parallel(parallel)
+            .build();
+        let mut called = false;
+        let err = reader
+            .for_each_decoded_message(fixture.path(), "/decoded", |_| {
+                called = true;
+                Ok(())
+            })
+            .unwrap_err();
+        assert!(!called);
+        let McapReaderError::UnsupportedCompression { compression } = &err else {
+            panic!("unexpected error: {err:?}");
+        };
+        assert_eq!(compression, "brot");
+        assert_eq!(err.code(), "unsupported_compression");
+        assert_eq!(err.category(), ErrorCategory::PayloadIssue);
+        assert!(err.to_string().contains("'brot'"));
+    }
+
+    let err = McapReader::new()
+        .for_each_raw_message(fixture.path(), "/decoded", |_| Ok(()))
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        McapReaderError::UnsupportedCompression { .. }
+    ));
+    let compressions = McapReader::new()
+        .chunk_compressions(fixture.path())
+        .unwrap();
+    assert_eq!(compressions.keys().collect::<Vec<_>>(), vec!["brot"]);
+}
+
 #[test]
 fn for_each_decoded_message_parallel_propagates_decode_error() {
     let fixture = write_chunked_fixture(
@@ -629,6 +1706,37 @@ fn for_each_decoded_message_parallel_propagates_decode_error() {
         .unwrap_err();
 
     assert!(matches!(err, McapReaderError::MessageDecodeFailed { .. }));
+    assert_eq!(err.code(), "message_decode_failed");
+    assert_eq!(err.category(), ErrorCategory::PayloadIssue);
+    assert!(!err.is_retryable());
+}
+
+#[test]
+fn missing_file_reports_io_category() {
+    let reader = McapReader::new();
+    let err = reader
+        .list_topics(&temp_fixture_path("does-not-exist"))
+        .unwrap_err();
+
+    assert!(matches!(err, McapReaderError::Io(_)));
+    assert_eq!(err.code(), "io");
+    assert_eq!(err.category(), ErrorCategory::IoIssue);
+    assert!(!err.is_retryable());
+}
+
+#[test]
+fn error_category_classifies_config_and_callback_errors() {
+    let err = McapReaderError::TopicNotFound {
+        topic: "/missing".to_string(),
+    };
+    assert_eq!(err.code(), "topic_not_found");
+    assert_eq!(err.category(), ErrorCategory::ConfigIssue);
+
+    let transient = McapReaderError::Callback(Box::new(std::io::Error::from(
+        std::io::ErrorKind::Interrupted,
+    )));
+    assert_eq!(transient.category(), ErrorCategory::IoIssue);
+    assert!(transient.is_retryable());
 }
 
 #[test]
@@ -683,6 +1791,254 @@ fn for_each_decoded_message_parallel_stops_after_callback_error() {
     assert_eq!(visited, vec![1, 2]);
 }
 
+#[cfg(feature = "arrow")]
+#[test]
+fn record_batches_iterates_batches_in_order() {
+    let payloads = value_payloads(5);
+    let payloads: Vec<&[u8]> = payloads.iter().map(Vec::as_slice).collect();
+    let fixture = write_chunked_fixture("record-batches", &payloads);
+    let reader = McapReader::builder()
+        .with_decoder(Box::new(TestJsonDecoder))
+        .with_batch_size(2)
+        .build();
+
+    let values: Vec<Vec<i64>> = reader
+        .record_batches(fixture.path(), "/decoded")
+        .map(|batch| {
+            let batch = batch?;
+            let values = batch
+                .column_by_name("value")
+                .unwrap()
+                .as_any()
+                .downcast_ref::<Int64Array>()
+                .unwrap();
+            Ok(values.values().to_vec())
+        })
+        .collect::<Result<_, McapReaderError>>()
+        .unwrap();
+    assert_eq!(values, vec![vec![1, 2], vec![3, 4], vec![5]]);
+
+    // Dropping the iterator early stops the read.
+    let first = reader.record_batches(fixture.path(), "/decoded").next();
+    assert_eq!(first.unwrap().unwrap().num_rows(), 2);
+}
+
+#[cfg(feature = "arrow")]
+#[test]
+fn record_batches_yields_errors_then_ends() {
+    let fixture = write_chunked_fixture(
+        "record-batches-error",
+        &[br#"{"value":1}"#, b"invalid", br#"{"value":3}"#],
+    );
+    let reader = McapReader::builder()
+        .with_decoder(Box::new(TestJsonDecoder))
+        .with_batch_size(1)
+        .with_parallel(false)
+        .build();
+
+    let mut batches = reader.record_batches(fixture.path(), "/decoded");
+    assert_eq!(batches.next().unwrap().unwrap().num_rows(), 1);
+    assert!(matches!(
+        batches.next(),
+        Some(Err(McapReaderError::MessageDecodeFailed { .. }))
+    ));
+    assert!(batches.next().is_none());
+
+    let mut missing = reader.record_batches(fixture.path(), "/missing");
+    assert!(matches!(
+        missing.next(),
+        Some(Err(McapReaderError::TopicNotFound { .. }))
+    ));
+    assert!(missing.next().is_none());
+}
+
+#[cfg(feature = "arrow")]
+#[test]
+fn with_column_stats_collects_stats_of_batches_read() {
+    let payloads = value_payloads(5);
+    let payloads: Vec<&[u8]> = payloads.iter().map(Vec::as_slice).collect();
+    let fixture = write_chunked_fixture("column-stats", &payloads);
+    let stats = Arc::new(TopicColumnStats::new());
+    let reader = McapReader::builder()
+        .with_decoder(Box::new(TestJsonDecoder))
+        .with_batch_size(2)
+        .with_column_stats(Arc::clone(&stats))
+        .build();
+
+    reader
+        .for_each_record_batch(fixture.path(), "/decoded", |_| Ok(()))
+        .unwrap();
+
+    let topics = stats.topics();
+    assert_eq!(topics.len(), 1);
+    let (topic, columns) = &topics[0];
+    assert_eq!(topic, "/decoded");
+    let value = columns.iter().find(|c| c.column == "value").unwrap();
+    assert_eq!(
+        (value.rows, value.null_count, &value.min, &value.max),
+        (5, 0, &Some(StatValue::Int(1)), &Some(StatValue::Int(5)))
+    );
+    assert_eq!(value.distinct_estimate, Some(5));
+}
+
+/// Decodes messages to one more struct field than its schema declares.
+#[cfg(feature = "arrow")]
+struct ExtraFieldDecoder;
+
+#[cfg(feature = "arrow")]
+struct ExtraFieldTopicDecoder(Box<dyn TopicDecoder>);
+
+#[cfg(feature = "arrow")]
+impl MessageDecoder for ExtraFieldDecoder {
+    fn encoding_key(&self) -> EncodingKey {
+        TestJsonDecoder.encoding_key()
+    }
+
+    fn build_topic_decoder(
+        &self,
+        schema_name: &str,
+        schema_data: &[u8],
+    ) -> Result<Box<dyn TopicDecoder>, DecoderError> {
+        let inner = TestJsonDecoder.build_topic_decoder(schema_name, schema_data)?;
+        Ok(Box::new(ExtraFieldTopicDecoder(inner)))
+    }
+}
+
+#[cfg(feature = "arrow")]
+impl TopicDecoder for ExtraFieldTopicDecoder {
+    fn decode(&self, message_data: &[u8]) -> Result<Value, DecoderError> {
+        let Value::Struct(mut fields) = self.0.decode(message_data)? else {
+            unreachable!("the test decoder returns structs");
+        };
+        fields.push(Value::Bool(true));
+        Ok(Value::Struct(fields))
+    }
+
+    fn field_defs(&self) -> &FieldDefs {
+        self.0.field_defs()
+    }
+}
+
+#[cfg(feature = "arrow")]
+#[test]
+fn decode_stats_count_aligned_structs_per_topic() {
+    let payloads = value_payloads(3);
+    let payloads: Vec<&[u8]> = payloads.iter().map(Vec::as_slice).collect();
+    let fixture = write_chunked_fixture("aligned-structs", &payloads);
+    let stats = Arc::new(DecodeStats::new());
+    let reader = McapReader::builder()
+        .with_decoder(Box::new(ExtraFieldDecoder))
+        .with_batch_size(2)
+        .with_struct_mismatch(StructMismatch::Align)
+        .with_decode_stats(Arc::clone(&stats))
+        .build();
+
+    assert_eq!(
+        collect_i64_values(&reader, fixture.path(), "/decoded"),
+        vec![1, 2, 3]
+    );
+    assert_eq!(stats.aligned_structs(), vec![("/decoded".to_string(), 3)]);
+    assert_eq!(
+        stats.to_string(),
+        "'/decoded' aligned 3 struct values with more or fewer fields than the schema\n"
+    );
+}
+
+#[cfg(feature = "arrow")]
+#[test]
+fn reader_applies_the_value_policies_of_conversion_options() {
+    let payloads = value_payloads(3);
+    let payloads: Vec<&[u8]> = payloads.iter().map(Vec::as_slice).collect();
+    let fixture = write_chunked_fixture("conversion-options", &payloads);
+    let options = ConversionOptions::parquet()
+        .with_struct_mismatch(StructMismatch::Align)
+        .build()
+        .unwrap();
+    let reader = McapReader::builder()
+        .with_decoder(Box::new(ExtraFieldDecoder))
+        .with_conversion_options(&options)
+        .build();
+
+    assert_eq!(
+        collect_i64_values(&reader, fixture.path(), "/decoded"),
+        vec![1, 2, 3]
+    );
+}
+
+#[cfg(feature = "arrow")]
+#[test]
+fn for_each_record_batch_multi_demultiplexes_topics_in_one_pass() {
+    let path = temp_fixture_path("multi-topic");
+    let fixture = TempFixture { path: path.clone() };
+    let mut writer = Writer::with_options(
+        File::create(&path).unwrap(),
+        WriteOptions::new().library("mcapdecode-test"),
+    )
+    .unwrap();
+    let schema_id = writer
+        .add_schema("test.Msg", "jsonschema", br#"{"type":"object"}"#)
+        .unwrap();
+    let channels: Vec<u16> = ["/a", "/b", "/c"]
+        .iter()
+        .map(|topic| {
+            writer
+                .add_channel(schema_id, topic, "json", &BTreeMap::new())
+                .unwrap()
+        })
+        .collect();
+    for value in 1..=9u64 {
+        let channel_id = channels[value as usize % 3];
+        writer
+            .write_to_known_channel(
+                &MessageHeader {
+                    channel_id,
+                    sequence: value as u32,
+                    log_time: value,
+                    publish_time: value,
+                },
+                format!(r#"{{"value":{value}}}"#).as_bytes(),
+            )
+            .unwrap();
+    }
+    writer.finish().unwrap();
+
+    let reader = McapReader::builder()
+        .with_decoder(Box::new(TestJsonDecoder))
+        .with_batch_size(2)
+        .build();
+    let mut batches = Vec::new();
+    reader
+        .for_each_record_batch_multi(fixture.path(), &["/b", "/a"], |topic, batch| {
+            let values = batch
+                .column_by_name("value")
+                .unwrap()
+                .as_any()
+                .downcast_ref::<Int64Array>()
+                .unwrap()
+                .values()
+                .to_vec();
+            batches.push((topic.to_string(), values));
+            Ok(())
+        })
+        .unwrap();
+    // `/a` gets 3, 6, 9 and `/b` 1, 4, 7; full batches are emitted as they
+    // fill up, then the partial ones in the requested order.
+    assert_eq!(
+        batches,
+        vec![
+            ("/b".to_string(), vec![1, 4]),
+            ("/a".to_string(), vec![3, 6]),
+            ("/b".to_string(), vec![7]),
+            ("/a".to_string(), vec![9]),
+        ]
+    );
+
+    let err = reader
+        .for_each_record_batch_multi(fixture.path(), &["/a", "/missing"], |_, _| Ok(()))
+        .unwrap_err();
+    assert_eq!(err.code(), "topic_not_found");
+}
+
 #[cfg(feature = "arrow")]
 #[test]
 fn for_each_record_batch_parallel_matches_sequential_for_multi_chunk_fixture() {
@@ -739,3 +2095,204 @@ fn for_each_record_batch_parallel_propagates_decode_error_for_multi_chunk_fixtur
 
     assert!(matches!(err, McapReaderError::MessageDecodeFailed { .. }));
 }
+
+#[test]
+fn follow_decoded_messages_stops_at_end_of_finished_file() {
+    let payloads = value_payloads(5);
+    let payloads: Vec<&[u8]> = payloads.iter().map(Vec::as_slice).collect();
+    let fixture = write_chunked_fixture("follow-finished", &payloads);
+    let reader = McapReader::builder()
+        .with_decoder(Box::new(TestJsonDecoder))
+        .build();
+
+    let values =
+        collect_followed_i64_values(&reader, fixture.path(), &FollowOptions::new()).unwrap();
+
+    assert_eq!(values, vec![1, 2, 3, 4, 5]);
+}
+
+#[test]
+fn follow_decoded_messages_stops_after_idle_timeout() {
+    let payloads = value_payloads(20);
+    let payloads: Vec<&[u8]> = payloads.iter().map(Vec::as_slice).collect();
+    let fixture = write_chunked_fixture("follow-idle-source", &payloads);
+    let len = fs::metadata(fixture.path()).unwrap().len() as usize;
+    let partial = write_partial_copy("follow-idle", fixture.path(), len / 2);
+    let reader = McapReader::builder()
+        .with_decoder(Box::new(TestJsonDecoder))
+        .build();
+    let options = FollowOptions::new()
+        .with_poll_interval(Duration::from_millis(10))
+        .with_idle_timeout(Duration::from_millis(50));
+
+    let values = collect_followed_i64_values(&reader, partial.path(), &options).unwrap();
+
+    assert!(!values.is_empty() && values.len() < 20, "{values:?}");
+    assert!(values.iter().copied().eq(1..=values.len() as i64));
+}
+
+#[test]
+fn follow_decoded_messages_reads_appended_chunks() {
+    let payloads = value_payloads(20);
+    let payloads: Vec<&[u8]> = payloads.iter().map(Vec::as_slice).collect();
+    let fixture = write_chunked_fixture("follow-append-source", &payloads);
+    let data = fs::read(fixture.path()).unwrap();
+    let partial = write_partial_copy("follow-append", fixture.path(), data.len() / 2);
+    let reader = McapReader::builder()
+        .with_decoder(Box::new(TestJsonDecoder))
+        .build();
+    // The timeout only guards against hanging; the recorder finishes the file first.
+    let options = FollowOptions::new()
+        .with_poll_interval(Duration::from_millis(10))
+        .with_idle_timeout(Duration::from_secs(10));
+
+    let values = thread::scope(|scope| {
+        let follower =
+            scope.spawn(|| collect_followed_i64_values(&reader, partial.path(), &options));
+        thread::sleep(Duration::from_millis(100));
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(partial.path())
+            .unwrap();
+        file.write_all(&data[data.len() / 2..]).unwrap();
+        follower.join().unwrap()
+    })
+    .unwrap();
+
+    assert_eq!(values, (1..=20).collect::<Vec<_>>());
+}
+
+#[test]
+fn follow_decoded_messages_unknown_topic_returns_error() {
+    let fixture = write_chunked_fixture("follow-unknown-topic", &[br#"{"value":1}"#]);
+    let reader = McapReader::builder()
+        .with_decoder(Box::new(TestJsonDecoder))
+        .build();
+
+    let err = reader
+        .follow_decoded_messages(
+            fixture.path(),
+            "/unknown",
+            &FollowOptions::new(),
+            |_| Ok(()),
+        )
+        .unwrap_err();
+
+    assert!(matches!(
+        err,
+        McapReaderError::TopicNotFound { ref topic } if topic == "/unknown"
+    ));
+}
+
+#[cfg(feature = "arrow")]
+#[test]
+fn follow_record_batches_flushes_partial_batch_when_caught_up() {
+    let payloads = value_payloads(20);
+    let payloads: Vec<&[u8]> = payloads.iter().map(Vec::as_slice).collect();
+    let fixture = write_chunked_fixture("follow-batches-source", &payloads);
+    let len = fs::metadata(fixture.path()).unwrap().len() as usize;
+    let partial = write_partial_copy("follow-batches", fixture.path(), len / 2);
+    let reader = McapReader::builder()
+        .with_decoder(Box::new(TestJsonDecoder))
+        .with_batch_size(1024)
+        .build();
+    let options = FollowOptions::new()
+        .with_poll_interval(Duration::from_millis(10))
+        .with_idle_timeout(Duration::from_millis(50));
+
+    let mut batch_rows = Vec::new();
+    reader
+        .follow_record_batches(partial.path(), "/decoded", &options, |batch| {
+            batch_rows.push(batch.num_rows());
+            Ok(())
+        })
+        .unwrap();
+
+    assert_eq!(batch_rows.len(), 1);
+    assert!(batch_rows[0] > 0 && batch_rows[0] < 20);
+}
+
+fn message_i64_value(message: &DecodedMessage) -> i64 {
+    match &message.value {
+        Value::Struct(fields) => match fields[0] {
+            Value::I64(value) => value,
+            ref other => panic!("expected I64 field, got {other:?}"),
+        },
+        other => panic!("expected struct payload, got {other:?}"),
+    }
+}
+
+#[test]
+fn subscribe_delivers_messages_in_order_with_small_capacity() {
+    let payloads = value_payloads(10);
+    let payloads: Vec<&[u8]> = payloads.iter().map(Vec::as_slice).collect();
+    let fixture = write_chunked_fixture("subscribe-order", &payloads);
+    let reader = McapReader::builder()
+        .with_decoder(Box::new(TestJsonDecoder))
+        .with_subscription_capacity(1)
+        .build();
+
+    let mut subscription = reader.subscribe(fixture.path(), "/decoded").unwrap();
+    assert_eq!(subscription.field_defs().len(), 1);
+    let values: Vec<i64> = subscription
+        .by_ref()
+        .map(|m| message_i64_value(&m))
+        .collect();
+
+    assert_eq!(values, (1..=10).collect::<Vec<_>>());
+    subscription.join().unwrap();
+}
+
+#[test]
+fn subscribe_join_before_end_stops_decoding() {
+    let payloads = value_payloads(10);
+    let payloads: Vec<&[u8]> = payloads.iter().map(Vec::as_slice).collect();
+    let fixture = write_chunked_fixture("subscribe-early-join", &payloads);
+    let reader = McapReader::builder()
+        .with_decoder(Box::new(TestJsonDecoder))
+        .with_subscription_capacity(1)
+        .build();
+
+    let subscription = reader.subscribe(fixture.path(), "/decoded").unwrap();
+    let first = subscription.recv().unwrap();
+
+    assert_eq!(message_i64_value(&first), 1);
+    subscription.join().unwrap();
+}
+
+#[test]
+fn subscribe_join_returns_decode_error() {
+    let fixture = write_chunked_fixture(
+        "subscribe-decode-error",
+        &[br#"{"value":1}"#, b"invalid", br#"{"value":3}"#],
+    );
+    let reader = McapReader::builder()
+        .with_decoder(Box::new(TestJsonDecoder))
+        .with_parallel(false)
+        .build();
+
+    let mut subscription = reader.subscribe(fixture.path(), "/decoded").unwrap();
+    let values: Vec<i64> = subscription
+        .by_ref()
+        .map(|m| message_i64_value(&m))
+        .collect();
+    let err = subscription.join().unwrap_err();
+
+    assert_eq!(values, vec![1]);
+    assert!(matches!(err, McapReaderError::MessageDecodeFailed { .. }));
+}
+
+#[test]
+fn subscribe_unknown_topic_returns_error() {
+    let fixture = write_chunked_fixture("subscribe-unknown-topic", &[br#"{"value":1}"#]);
+    let reader = McapReader::builder()
+        .with_decoder(Box::new(TestJsonDecoder))
+        .build();
+
+    let err = reader.subscribe(fixture.path(), "/unknown").err().unwrap();
+
+    assert!(matches!(
+        err,
+        McapReaderError::TopicNotFound { ref topic } if topic == "/unknown"
+    ));
+}
@@ -1,18 +1,14 @@
 use std::{
     io,
     path::{Path, PathBuf},
-    sync::{
-        Arc,
-        atomic::{AtomicBool, Ordering},
-        mpsc::{self, Receiver, TryRecvError},
-    },
+    sync::mpsc::{self, Receiver, TryRecvError},
     thread,
     time::{Duration, Instant},
 };
 
 use anyhow::Result;
 use mcapdecode::{
-    McapReader, McapReaderError, RawMessage, TopicInfo,
+    CancellationToken, McapReader, McapReaderError, RawMessage, TopicInfo,
     core::{DataTypeDef, DecodedMessage, FieldDef, FieldDefs, Value},
 };
 
@@ -21,7 +17,6 @@ use crate::{
     format,
 };
 
-const CANCELLED_ERROR: &str = "__mcaptui_loader_cancelled__";
 const MAX_LOADER_EVENTS_PER_FRAME: usize = 32;
 const MESSAGE_BATCH_SIZE: usize = 64;
 const MESSAGE_BATCH_MAX_LATENCY: Duration = Duration::from_millis(40);
@@ -34,7 +29,7 @@ pub(crate) struct LoaderDrainResult {
 
 pub(crate) struct TopicLoader {
     receiver: Receiver<LoaderEvent>,
-    cancel: Arc<AtomicBool>,
+    cancel: CancellationToken,
     join_handle: thread::JoinHandle<()>,
 }
 
@@ -48,37 +43,33 @@ enum LoaderEvent {
 impl TopicLoader {
     pub(crate) fn spawn(input: PathBuf, topic: TopicInfo, parallel: bool) -> Self {
         let (sender, receiver) = mpsc::channel();
-        let cancel = Arc::new(AtomicBool::new(false));
-        let cancel_for_thread = Arc::clone(&cancel);
+        let cancel = CancellationToken::new();
+        let cancel_for_thread = cancel.clone();
         let topic_name = topic.topic.clone();
 
         let join_handle = thread::spawn(move || {
             let reader = McapReader::builder()
                 .with_default_decoders()
                 .with_parallel(parallel)
+                .with_cancellation_token(cancel_for_thread.clone())
                 .build();
 
             let load_result = match reader.topic_field_defs(&input, &topic_name) {
-                Ok(field_defs) => load_decoded_topic(
-                    &reader,
-                    &input,
-                    &topic_name,
-                    field_defs,
-                    &sender,
-                    &cancel_for_thread,
-                ),
+                Ok(field_defs) => {
+                    load_decoded_topic(&reader, &input, &topic_name, field_defs, &sender)
+                }
                 Err(error) if supports_raw_fallback(&error) => {
-                    load_raw_topic(&reader, &input, &topic_name, &sender, &cancel_for_thread)
+                    load_raw_topic(&reader, &input, &topic_name, &sender)
                 }
                 Err(error) => Err(anyhow::Error::new(error)
                     .context(format!("failed to load schema for topic '{}'", topic_name))),
             };
 
             match load_result {
+                _ if cancel_for_thread.is_cancelled() => {}
                 Ok(()) => {
                     let _ = sender.send(LoaderEvent::Finished { topic: topic_name });
                 }
-                Err(error) if error.to_string() == CANCELLED_ERROR => {}
                 Err(error) => {
                     let _ = sender.send(LoaderEvent::Failed {
                         topic: topic_name,
@@ -155,7 +146,7 @@ pub(crate) fn drain_loader_events(
 
 pub(crate) fn cancel_loader(loader: &mut Option<TopicLoader>) {
     if let Some(active_loader) = loader.take() {
-        active_loader.cancel.store(true, Ordering::Relaxed);
+        active_loader.cancel.cancel();
         thread::spawn(move || {
             let _ = active_loader.join_handle.join();
         });
@@ -181,7 +172,6 @@ fn load_decoded_topic(
     topic_name: &str,
     field_defs: FieldDefs,
     sender: &mpsc::Sender<LoaderEvent>,
-    cancel: &AtomicBool,
 ) -> Result<()> {
     if sender.send(LoaderEvent::FieldDefs(field_defs)).is_err() {
         return Ok(());
@@ -191,10 +181,6 @@ fn load_decoded_topic(
     let mut last_flush = Instant::now();
     let mut next_index = 0usize;
     let load_result = reader.for_each_decoded_message(input, topic_name, |message| {
-        if cancel.load(Ordering::Relaxed) {
-            return Err(CANCELLED_ERROR.into());
-        }
-
         pending.push(to_loaded_message(next_index, message));
         next_index += 1;
 
@@ -215,7 +201,6 @@ fn load_raw_topic(
     input: &Path,
     topic_name: &str,
     sender: &mpsc::Sender<LoaderEvent>,
-    cancel: &AtomicBool,
 ) -> Result<()> {
     if sender
         .send(LoaderEvent::FieldDefs(raw_payload_field_defs()))
@@ -228,10 +213,6 @@ fn load_raw_topic(
     let mut last_flush = Instant::now();
     let mut next_index = 0usize;
     let load_result = reader.for_each_raw_message(input, topic_name, |message| {
-        if cancel.load(Ordering::Relaxed) {
-            return Err(CANCELLED_ERROR.into());
-        }
-
         pending.push(to_loaded_raw_message(next_index, message));
         next_index += 1;
 
@@ -0,0 +1,89 @@
+//! Progress of a `--split-topics` conversion, saved whenever a file is
+//! complete so that `--resume` can continue after an interruption.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// File in the output directory holding the [`Checkpoint`]; removed once the
+/// manifest is written.
+pub const CHECKPOINT_FILE: &str = "checkpoint.json";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// Input file being converted.
+    pub input: String,
+    /// Manifest entries of the completed files.
+    pub outputs: Vec<serde_json::Value>,
+    pub topics: BTreeMap<String, TopicProgress>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct TopicProgress {
+    /// Messages of the topic written to completed files.
+    pub messages: u64,
+    /// `true` once every message of the topic is written.
+    pub complete: bool,
+}
+
+impl Checkpoint {
+    pub fn new(input: &Path) -> Self {
+        Self {
+            input: input.display().to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Load the checkpoint of a conversion of `input` into `dir`.
+    pub fn load(dir: &Path, input: &Path) -> Result<Self> {
+        let path = dir.join(CHECKPOINT_FILE);
+        let file = fs::File::open(&path)
+            .with_context(|| format!("no checkpoint to resume from: {}", path.display()))?;
+        let checkpoint: Self = serde_json::from_reader(file)
+            .with_context(|| format!("invalid checkpoint {}", path.display()))?;
+        let input = input.display().to_string();
+        if checkpoint.input != input {
+            anyhow::bail!(
+                "{} was written for {}, not {input}",
+                path.display(),
+                checkpoint.input
+            );
+        }
+        Ok(checkpoint)
+    }
+
+    /// Write the checkpoint to `dir`, replacing the previous one atomically.
+    pub fn save(&self, dir: &Path) -> Result<()> {
+        let tmp = dir.join(format!("{CHECKPOINT_FILE}.tmp"));
+        let mut file = BufWriter::new(fs::File::create(&tmp)?);
+        serde_json::to_writer_pretty(&mut file, self)?;
+        writeln!(file)?;
+        file.into_inner()?.sync_all()?;
+        fs::rename(&tmp, dir.join(CHECKPOINT_FILE))?;
+        Ok(())
+    }
+
+    /// Delete the checkpoint of `dir`, if any.
+    pub fn remove(dir: &Path) -> Result<()> {
+        match fs::remove_file(dir.join(CHECKPOINT_FILE)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Messages of `topic` to skip when resuming, or `None` if the topic is
+    /// complete.
+    pub fn resume_offset(&self, topic: &str) -> Option<u64> {
+        match self.topics.get(topic) {
+            Some(progress) if progress.complete => None,
+            Some(progress) => Some(progress.messages),
+            None => Some(0),
+        }
+    }
+}
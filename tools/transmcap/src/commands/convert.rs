@@ -1,22 +1,43 @@
-use std::{path::PathBuf, str::FromStr};
+use std::{collections::HashMap, path::PathBuf, str::FromStr, sync::Arc, time::Duration};
 
-use anyhow::{Result, bail};
-use clap::Args;
+use anyhow::Result;
+use arrow::{
+    datatypes::{Field, FieldRef, Schema},
+    error::ArrowError,
+    record_batch::RecordBatch,
+};
+use clap::{Args, CommandFactory, error::ErrorKind};
 use indicatif::{ProgressBar, ProgressStyle};
 use mcapdecode::{
-    McapReader, McapReaderArrowExt,
+    DecodeStats, FollowOptions, McapReader, McapReaderArrowExt, Sample, TopicColumnStats,
     arrow::{
-        ArrayPolicy, FlattenPolicy, ListPolicy, MapPolicy, StructPolicy, flatten_record_batch,
-        project_record_batch,
+        ArrayPolicy, DuplicateMapKeys, FlattenPolicy, INSTANCE_KEY_COLUMN, LaserScanPoints,
+        LatestSnapshot, ListPolicy, ListPolicySpec, MapPolicy, SchemaEvolution, StructMismatch,
+        StructPolicy, TimeColumns, WideningPolicy, field_defs_to_record_batch_schema,
+        flatten_record_batch, instance_key_column, project_record_batch,
     },
 };
 
 use crate::{
-    format::OutputFormat,
-    writer::{CsvWriter, JsonlWriter, ParquetWriter, RecordBatchWriter},
+    checkpoint::Checkpoint,
+    config::{Config, ConversionConfig},
+    decoders::DecoderArgs,
+    format::{JsonInt64, OutputCompression, OutputFormat},
+    layout::{Layout, LongLayout, TIMESTAMP_COLUMNS, WideLayout},
+    limit::{LimitReached, OutputLimit, parse_size, stop_at_limit},
+    provenance::{Provenance, ProvenanceWriter},
+    queue::BatchQueue,
+    stats::{sample_topic_stats, write_column_stats},
+    table_names::{TableNameMapping, TableNames},
+    template::OutputTemplate,
+    transforms::TransformArgs,
+    writer::{
+        CsvWriter, InfluxLineWriter, JsonlWriter, ParquetWriter, RecordBatchWriter, SnapshotWriter,
+        SplitParquetWriter, TimeColumnsWriter,
+    },
 };
 
-#[derive(Args)]
+#[derive(Args, Clone)]
 pub struct ConvertArgs {
     /// Path to the mcap file
     input: PathBuf,
@@ -26,21 +47,127 @@ pub struct ConvertArgs {
     format: OutputFormat,
 
     /// Filter by topic name
-    #[arg(short, long)]
-    topic: String,
+    #[arg(
+        short,
+        long,
+        required_unless_present_any = ["topics", "config"],
+        conflicts_with = "topics"
+    )]
+    topic: Option<String>,
+
+    /// Comma-separated list of topics written into a single table (see --layout)
+    #[arg(long, value_delimiter = ',')]
+    topics: Option<Vec<String>>,
+
+    /// Table layout for the selected topics [default: wide]
+    /// (a single topic with the wide layout is written as-is)
+    #[arg(long, value_enum)]
+    layout: Option<Layout>,
+
+    /// Comma-separated columns written as tags with --format influx
+    #[arg(long, value_delimiter = ',')]
+    influx_tags: Option<Vec<String>>,
+
+    /// ADBC driver library name or path used with --format adbc
+    /// (e.g. adbc_driver_postgresql)
+    #[cfg(feature = "adbc")]
+    #[arg(long)]
+    adbc_driver: Option<String>,
+
+    /// Database URI passed to the ADBC driver with --format adbc
+    #[cfg(feature = "adbc")]
+    #[arg(long)]
+    dsn: Option<String>,
+
+    /// Target table for --format adbc (default: one table per topic, named
+    /// after the topic, see --table-name)
+    #[cfg(feature = "adbc")]
+    #[arg(long)]
+    table: Option<String>,
+
+    /// Comma-separated TOPIC=NAME table names of topics written to their own
+    /// table or --split-topics file (default: the topic without its leading
+    /// `/`, other characters than letters, digits and `_` replaced by `_`)
+    #[arg(long, value_name = "TOPIC=NAME", value_delimiter = ',')]
+    table_name: Option<Vec<TableNameMapping>>,
 
     /// Output file path (stdout if not specified)
     #[arg(short, long)]
     output: Option<PathBuf>,
 
-    /// Policy for List columns: drop | keep | flatten-fixed
+    /// Compress jsonl/csv/influx output (e.g. with -o out.jsonl.zst)
+    #[arg(long, value_enum, value_name = "CODEC")]
+    compress: Option<OutputCompression>,
+
+    /// Write 64-bit integers in jsonl output as numbers or as strings
+    /// (for consumers that parse JSON numbers as doubles)
+    #[arg(long, value_enum, value_name = "MODE", default_value_t = JsonInt64::Number)]
+    json_int64: JsonInt64,
+
+    /// Existing Parquet file of the dataset being extended; fail before
+    /// writing if the output schema cannot be appended to it
+    #[arg(long, value_name = "PARQUET")]
+    check_append: Option<PathBuf>,
+
+    /// With --check-append, fields the existing dataset does not have:
+    /// strict (fail) | add-columns (write them as new nullable columns, and
+    /// nulls for nullable fields the output no longer has)
+    #[arg(
+        long,
+        value_name = "MODE",
+        value_parser = parse_schema_evolution,
+        default_value = "strict",
+        requires = "check_append"
+    )]
+    schema_evolution: SchemaEvolution,
+
+    /// Write each topic to its own Parquet file in the -o directory,
+    /// indexed by a manifest.json
+    #[arg(long, conflicts_with_all = ["layout", "check_append", "follow"])]
+    split_topics: bool,
+
+    /// With --split-topics, write a Hive-partitioned dataset with the topic
+    /// as `@topic` partition column (`@topic=%2Fimu/part-0.parquet`)
+    #[arg(long, requires = "split_topics")]
+    partition_by_topic: bool,
+
+    /// With --split-topics, start a new file per DURATION of `@log_time`
+    /// (e.g. 10min, 1h), named by the window start (`imu_2024-05-01T12-00.parquet`)
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration, requires = "split_topics")]
+    split_by: Option<Duration>,
+
+    /// With --split-topics, continue an interrupted conversion into the -o
+    /// directory from its checkpoint, keeping the files already completed
+    #[arg(
+        long,
+        requires = "split_topics",
+        conflicts_with_all = ["dedup", "snapshot", "limit_rows", "limit_bytes"]
+    )]
+    resume: bool,
+
+    /// With --split-topics, file names of the topics, relative to the -o
+    /// directory (placeholders: {topic}, {topic_snake}, {window}, {ext})
+    #[arg(
+        long,
+        value_name = "TEMPLATE",
+        requires = "split_topics",
+        conflicts_with = "partition_by_topic"
+    )]
+    output_template: Option<OutputTemplate>,
+
+    /// Policy for List columns: drop | keep | flatten-fixed[:N]
     #[arg(long, value_parser = parse_list_policy)]
-    list_policy: Option<ListPolicy>,
+    list_policy: Option<ListPolicySpec>,
 
     /// Number of columns generated when --list-policy flatten-fixed is used
+    /// (inferred from sampled messages if not specified)
     #[arg(long)]
     list_flatten_size: Option<usize>,
 
+    /// Number of messages sampled to infer --list-flatten-size
+    #[arg(long, default_value_t = 1000)]
+    list_flatten_sample: u64,
+
     /// Policy for FixedSizeList columns: drop | keep | flatten
     #[arg(long, value_parser = parse_array_policy)]
     array_policy: Option<ArrayPolicy>,
@@ -49,83 +176,707 @@ pub struct ConvertArgs {
     #[arg(long, value_parser = parse_map_policy)]
     map_policy: Option<MapPolicy>,
 
+    /// Sort map entries by key, so that maps with the same entries are equal
+    #[arg(long)]
+    sort_map_keys: bool,
+
+    /// Map entries repeating a key of the same map: error | keep-first | keep-last
+    #[arg(long, value_parser = parse_duplicate_map_keys, default_value = "keep-last")]
+    duplicate_map_keys: DuplicateMapKeys,
+
+    /// Numbers of another type than their column: exact (fail) | lossless
+    /// (convert when no value can lose precision, e.g. uint8 into Int32)
+    #[arg(long, value_parser = parse_widening, default_value = "exact")]
+    widening: WideningPolicy,
+
+    /// Structs with more or fewer fields than the schema: error | align
+    /// (match fields by position, drop surplus ones and null missing ones)
+    #[arg(long, value_parser = parse_struct_mismatch, default_value = "error")]
+    struct_mismatch: StructMismatch,
+
     /// Comma-separated list of fields to include in the output (all fields if not specified).
     /// Field paths are applied before flattening (e.g. "x,y,z" or "position.x,position.y").
     #[arg(long, value_delimiter = ',')]
     fields: Option<Vec<String>>,
 
+    /// Stop each topic after writing N rows
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    limit_rows: Option<u64>,
+
+    /// Stop each topic after writing about SIZE bytes of Arrow data (e.g. 64K, 10M, 1G)
+    #[arg(long, value_name = "SIZE", value_parser = parse_size)]
+    limit_bytes: Option<u64>,
+
+    /// Skip the first N messages of each topic (e.g. to resume a partial export)
+    #[arg(long, value_name = "N")]
+    skip_rows: Option<u64>,
+
+    /// Skip messages logged within this long after the recording start
+    /// (e.g. 90s, 5m, 1h; plain numbers are seconds)
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration, conflicts_with = "follow")]
+    skip_duration: Option<Duration>,
+
+    /// Convert only a sample of each topic, located with the message
+    /// indexes: head:N (first N messages) | tail:N (last N) | random:N
+    /// (N at random, the same on every run)
+    #[arg(
+        long,
+        value_name = "MODE:N",
+        value_parser = parse_sample,
+        conflicts_with_all = ["follow", "resume"]
+    )]
+    sample: Option<Sample>,
+
     /// Enable parallel chunk decompression and decoding.
     #[arg(short, long)]
     parallel: bool,
+
+    /// Batches decoded ahead of the writer on a separate thread
+    /// (0 decodes and writes on one thread)
+    #[arg(long, value_name = "N", default_value_t = 4)]
+    queue_capacity: usize,
+
+    /// Time columns to write: absolute | elapsed | both
+    /// (`@elapsed_ns` is `@log_time` minus the recording start)
+    #[arg(long, value_parser = parse_time_columns, default_value = "absolute")]
+    time_columns: TimeColumns,
+
+    /// Append an `@instance_key` column hashing the fields marked `@key`
+    /// (topics without key fields are written unchanged)
+    #[arg(long, conflicts_with = "topics")]
+    instance_key: bool,
+
+    /// Write only the latest row of every key: the final state, or one
+    /// snapshot per --snapshot-interval of `@log_time`
+    #[arg(long, conflicts_with = "topics")]
+    snapshot: bool,
+
+    /// Comma-separated columns identifying a key for --snapshot
+    /// [default: @instance_key, else the fields marked `@key`]
+    #[arg(long, value_delimiter = ',', requires = "snapshot")]
+    snapshot_key: Option<Vec<String>>,
+
+    /// Emit a --snapshot per DURATION of `@log_time` instead of the final state
+    /// (e.g. 1s, 5m)
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration, requires = "snapshot")]
+    snapshot_interval: Option<Duration>,
+
+    /// Add an `@latency_ns` column holding `@log_time - @publish_time`
+    #[arg(long)]
+    latency: bool,
+
+    /// Skip messages whose payload is identical to the previous message of the topic
+    #[arg(long)]
+    dedup: bool,
+
+    /// Write structs nested deeper than N field levels as JSON string columns
+    #[arg(long, value_name = "N")]
+    max_depth: Option<usize>,
+
+    /// Print per-column statistics of the decoded topics (rows, nulls, min,
+    /// max, approximate distinct count) after converting
+    #[arg(long)]
+    stats: bool,
+
+    /// Write the --stats report as JSON to PATH instead of printing it
+    #[arg(long, value_name = "PATH")]
+    stats_output: Option<PathBuf>,
+
+    #[command(flatten)]
+    decoder: DecoderArgs,
+
+    #[command(flatten)]
+    transforms: TransformArgs,
+
+    /// Keep converting messages appended to a file that is still being recorded
+    /// (e.g. `.mcap.active`) until the recorder finishes it
+    #[arg(long, conflicts_with_all = ["topics", "layout"])]
+    follow: bool,
+
+    /// Write byte-identical output when converting the same input again:
+    /// implies --sort-map-keys and pins all Parquet writer properties
+    #[arg(long)]
+    deterministic: bool,
+
+    /// Stamp written batches (and Parquet files) with metadata tracing them
+    /// back to the input: path, CRC-32, MCAP profile, topic, converter
+    /// version and arguments
+    #[arg(long, conflicts_with = "follow")]
+    provenance: bool,
+
+    /// Stop --follow after this many seconds without new data
+    #[arg(long, requires = "follow")]
+    idle_timeout: Option<u64>,
+
+    /// TOML file with per-topic and [conversion] options overriding the flags above
+    /// [default: ./transmcap.toml if it exists]; without --topic/--topics,
+    /// each of its [[groups]] is converted into its own output
+    #[arg(long, value_name = "FILE")]
+    config: Option<PathBuf>,
+
+    /// Separator of flattened column paths, from the `[conversion]` table of
+    /// the config file.
+    #[arg(skip)]
+    separator: Option<char>,
 }
 
 impl ConvertArgs {
-    pub fn run(self) -> Result<()> {
-        let reader = McapReader::builder()
-            .with_default_decoders()
-            .with_parallel(self.parallel)
-            .build();
-        let flatten_policy = self.flatten_policy()?;
-
-        let count = reader.message_count(&self.input, &self.topic)?;
-        let pb = ProgressBar::new(count);
-        pb.set_style(
-            ProgressStyle::with_template(
-                "{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} ({per_sec}, ETA: {eta})",
-            )?
-            .progress_chars("=>-"),
-        );
+    pub fn run(mut self) -> Result<()> {
+        let file_config = Config::load(self.config.as_deref())?.unwrap_or_default();
+        if let Some(conversion) = &file_config.conversion {
+            self.apply_conversion_config(conversion);
+        }
+        if self.topic.is_none() && self.topics.is_none() {
+            return self.run_groups(&file_config);
+        }
+        self.convert(&file_config)
+    }
+
+    /// Replace the flags matching the keys `conversion` sets.
+    fn apply_conversion_config(&mut self, conversion: &ConversionConfig) {
+        let options = &conversion.options;
+        if conversion.sets("flatten") {
+            // The struct policy stays the one of the format and layout.
+            let flatten = options.flatten();
+            self.list_policy = Some(ListPolicySpec {
+                policy: flatten.list,
+                flatten_fixed_size: (flatten.list == ListPolicy::FlattenFixed)
+                    .then_some(flatten.list_flatten_fixed_size),
+            });
+            self.list_flatten_size = None;
+            self.array_policy = Some(flatten.array);
+            self.map_policy = Some(flatten.map);
+        }
+        if conversion.sets("separator") {
+            self.separator = Some(options.separator());
+        }
+        if conversion.sets("time-columns") {
+            self.time_columns = options.time_columns();
+        }
+        let convert = options.convert_options();
+        if conversion.sets("duplicate-map-keys") {
+            self.duplicate_map_keys = convert.duplicate_map_keys;
+        }
+        if conversion.sets("widening") {
+            self.widening = convert.widening;
+        }
+        if conversion.sets("struct-mismatch") {
+            self.struct_mismatch = convert.struct_mismatch;
+        }
+        if conversion.sets("max-depth") {
+            self.max_depth = options.max_depth();
+        }
+    }
+
+    /// Convert every `[[groups]]` entry of `file_config` with its topics and
+    /// output settings.
+    fn run_groups(&self, file_config: &Config) -> Result<()> {
+        if file_config.groups.is_empty() {
+            anyhow::bail!("--config without --topic or --topics requires [[groups]]");
+        }
+        let topics: Vec<String> = McapReader::new()
+            .list_topics(&self.input)?
+            .into_iter()
+            .map(|info| info.topic)
+            .collect();
+        for (idx, group) in file_config.groups.iter().enumerate() {
+            let selected = group.select(&topics);
+            if selected.is_empty() {
+                eprintln!("Warning: group {} matches no topic", idx + 1);
+                continue;
+            }
+            eprintln!("Info: group {}: {}", idx + 1, selected.join(", "));
+            let mut args = self.clone();
+            args.topics = Some(selected.clone());
+            args.output = group.output.clone().or(args.output);
+            args.format = group.format.unwrap_or(args.format);
+            args.layout = group.layout.or(args.layout);
+            args.split_topics = group.split_topics.unwrap_or(args.split_topics);
+            args.convert(&file_config.for_group(group, &selected))?;
+        }
+        Ok(())
+    }
+
+    fn convert(&self, file_config: &Config) -> Result<()> {
+        let config = self.validate().unwrap_or_else(|e| e.exit());
+        self.validate_file_config(file_config)?;
+        let checkpoint = match &self.output {
+            Some(dir) if self.resume => Some(Checkpoint::load(dir, &self.input)?),
+            _ => None,
+        };
+        let decode_stats = Arc::new(DecodeStats::new());
+        let column_stats =
+            (self.stats || self.stats_output.is_some()).then(|| Arc::new(TopicColumnStats::new()));
+        let reader = self.reader(0, &decode_stats, column_stats.as_ref())?;
+        let topics = self.topics();
+        if !self.follow {
+            // Report every misconfigured topic before decoding any of them.
+            reader.prepare(&self.input, &topics)?;
+        }
+        let mut flatten_policy = config.flatten_policy;
+        if config.infer_list_flatten_size {
+            // Sampled messages are decoded again below; keep their warnings
+            // out of the summary.
+            let sample_reader = self.reader(0, &Arc::default(), None)?;
+            flatten_policy.list_flatten_fixed_size =
+                self.infer_list_flatten_size(&sample_reader, &topics, file_config)?;
+        }
+        let topic_options = topics
+            .iter()
+            .map(|topic| self.topic_options(file_config, topic, &flatten_policy))
+            .collect::<Result<Vec<_>>>()?;
+        let table_names = self.table_names(file_config, &topics, config.layout)?;
+
+        // A file that is still being recorded has no summary to count messages from.
+        let pb = if self.follow {
+            let pb = ProgressBar::new_spinner();
+            pb.set_style(ProgressStyle::with_template(
+                "{spinner:.green} [{elapsed_precise}] {pos} messages ({per_sec}) {msg}",
+            )?);
+            pb
+        } else {
+            let mut count = 0;
+            for topic in &topics {
+                let resume_offset = checkpoint
+                    .as_ref()
+                    .map_or(Some(0), |c| c.resume_offset(topic));
+                let Some(resumed) = resume_offset else {
+                    continue;
+                };
+                let mut topic_count = reader
+                    .message_count(&self.input, topic)?
+                    .saturating_sub(self.skip_rows.unwrap_or_default() + resumed);
+                if let Some(Sample::Head(n) | Sample::Tail(n) | Sample::Random(n)) = self.sample {
+                    topic_count = topic_count.min(n);
+                }
+                count += self
+                    .limit_rows
+                    .map_or(topic_count, |rows| topic_count.min(rows));
+            }
+            let pb = ProgressBar::new(count);
+            pb.set_style(
+                ProgressStyle::with_template(
+                    "{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} ({per_sec}, ETA: {eta}) {msg}",
+                )?
+                .progress_chars("=>-"),
+            );
+            pb
+        };
 
         let mut writer: Box<dyn RecordBatchWriter> = match self.format {
-            OutputFormat::Jsonl => Box::new(JsonlWriter::new(self.output.as_deref())?),
-            OutputFormat::Csv => Box::new(CsvWriter::new(self.output.as_deref())?),
+            OutputFormat::Jsonl => Box::new(
+                JsonlWriter::new(self.output.as_deref(), self.compress)?
+                    .with_int64(self.json_int64),
+            ),
+            OutputFormat::Csv => Box::new(CsvWriter::new(self.output.as_deref(), self.compress)?),
+            #[cfg(feature = "adbc")]
+            OutputFormat::Adbc => Box::new(crate::writer::AdbcWriter::new(
+                self.adbc_driver.as_deref().unwrap_or_default(),
+                self.dsn.as_deref().unwrap_or_default(),
+                self.table.clone(),
+                table_names.clone(),
+            )?),
+            OutputFormat::Influx => Box::new(InfluxLineWriter::new(
+                self.output.as_deref(),
+                self.compress,
+                self.influx_tags.clone().unwrap_or_default(),
+            )?),
+            OutputFormat::Parquet if self.split_topics => {
+                let dir = self
+                    .output
+                    .as_deref()
+                    .ok_or_else(|| anyhow::anyhow!("--split-topics requires -o <dir>"))?;
+                let mut writer = SplitParquetWriter::new(dir, &self.input)?;
+                if self.deterministic {
+                    writer = writer.with_deterministic();
+                }
+                if let Some(window) = self.split_by {
+                    writer = writer.with_time_windows(window);
+                }
+                if let Some(checkpoint) = &checkpoint {
+                    writer = writer.with_resume(checkpoint.clone());
+                }
+                writer = writer.with_table_names(table_names.clone());
+                if self.partition_by_topic {
+                    Box::new(writer.with_topic_partitions())
+                } else {
+                    Box::new(writer.with_output_template(self.split_template()))
+                }
+            }
             OutputFormat::Parquet => {
                 let path = self
                     .output
                     .as_deref()
                     .ok_or_else(|| anyhow::anyhow!("Parquet output requires -o <file>"))?;
-                Box::new(ParquetWriter::new(path)?)
+                let mut writer = ParquetWriter::new(path)?;
+                if self.deterministic {
+                    writer = writer.with_deterministic();
+                }
+                match &self.check_append {
+                    Some(existing) => {
+                        Box::new(writer.with_append_check(existing, self.schema_evolution)?)
+                    }
+                    None => Box::new(writer),
+                }
             }
         };
+        let topic_time_columns: HashMap<String, TimeColumns> = file_config
+            .topics
+            .iter()
+            .filter_map(|(topic, options)| Some((topic.clone(), options.time_columns?)))
+            .collect();
+        if self.time_columns != TimeColumns::Absolute
+            || self.latency
+            || !topic_time_columns.is_empty()
+        {
+            let start_ns = reader.message_start_time(&self.input).ok();
+            writer = Box::new(
+                TimeColumnsWriter::new(writer, self.time_columns, start_ns)
+                    .with_latency(self.latency)
+                    .with_topic_time_columns(topic_time_columns),
+            );
+        }
+        if self.snapshot {
+            let mut snapshot = LatestSnapshot::new();
+            if let Some(columns) = &self.snapshot_key {
+                snapshot = snapshot.with_key_columns(columns.clone());
+            }
+            if let Some(interval) = self.snapshot_interval {
+                snapshot = snapshot.with_interval(interval);
+            }
+            writer = Box::new(SnapshotWriter::new(writer, snapshot));
+        }
+        if self.provenance {
+            let provenance = Provenance::new(&reader, &self.input, &topics)?;
+            writer = Box::new(ProvenanceWriter::new(writer, provenance));
+        }
         let mut dropped_warned = false;
+        let limit = OutputLimit::new(self.limit_rows, self.limit_bytes);
+        let mut queue = BatchQueue::new(self.queue_capacity);
 
-        reader.for_each_record_batch(&self.input, &self.topic, |batch| {
-            let projected = if let Some(fields) = &self.fields {
-                project_record_batch(&batch, fields)?
-            } else {
-                batch
-            };
-            let (flat_batch, dropped_columns) =
-                flatten_record_batch(&projected, None, &flatten_policy)?;
-            if !dropped_warned && !dropped_columns.is_empty() {
-                dropped_warned = true;
-                eprintln!(
-                    "Warning: output policy skipped columns: {}",
-                    dropped_columns.join(", ")
-                );
+        match config.layout {
+            None => {
+                for (topic, (fields, policy)) in topics.iter().zip(&topic_options) {
+                    let resume_offset = checkpoint
+                        .as_ref()
+                        .map_or(Some(0), |c| c.resume_offset(topic));
+                    let Some(resumed) = resume_offset else {
+                        eprintln!("Info: {topic} was already converted");
+                        continue;
+                    };
+                    let reader = match resumed {
+                        0 => reader.clone(),
+                        resumed => self.reader(resumed, &decode_stats, column_stats.as_ref())?,
+                    };
+                    writer.begin_topic(topic)?;
+                    let mut limit = limit.reset();
+                    let write_batch = |batch: RecordBatch, queued: usize| {
+                        pb.set_message(format!("queue {queued}/{}", self.queue_capacity));
+                        let mut flat_batch = post_process(
+                            &batch,
+                            *fields,
+                            &self.transforms,
+                            policy,
+                            self.separator,
+                            &mut dropped_warned,
+                        )?;
+                        if matches!(self.format, OutputFormat::Influx) {
+                            flat_batch = with_timestamps(&batch, &flat_batch)?;
+                        }
+                        if self.instance_key {
+                            flat_batch = with_instance_key(&batch, &flat_batch)?;
+                        }
+                        let (flat_batch, reached) = limit.take(flat_batch);
+                        let n = flat_batch.num_rows() as u64;
+                        writer.write_batch(flat_batch)?;
+                        writer.messages_written(&batch)?;
+                        pb.inc(n);
+                        if reached {
+                            return Err(LimitReached.into());
+                        }
+                        Ok(())
+                    };
+                    let decode = |send: &mut dyn FnMut(RecordBatch) -> _| {
+                        if self.follow {
+                            reader.follow_record_batches(
+                                &self.input,
+                                topic,
+                                &self.follow_options(),
+                                send,
+                            )
+                        } else {
+                            reader.for_each_record_batch(&self.input, topic, send)
+                        }
+                    };
+                    stop_at_limit(queue.run(decode, write_batch))?;
+                }
+            }
+            Some(Layout::Wide) => {
+                let layout = self.wide_layout(&reader, &topics, &topic_options)?;
+                for (idx, topic) in topics.iter().enumerate() {
+                    let (fields, policy) = &topic_options[idx];
+                    let mut limit = limit.reset();
+                    let decode = |send: &mut dyn FnMut(RecordBatch) -> _| {
+                        reader.for_each_record_batch(&self.input, topic, send)
+                    };
+                    stop_at_limit(queue.run(decode, |batch, queued| {
+                        pb.set_message(format!("queue {queued}/{}", self.queue_capacity));
+                        let flat_batch = post_process(
+                            &batch,
+                            *fields,
+                            &self.transforms,
+                            policy,
+                            self.separator,
+                            &mut dropped_warned,
+                        )?;
+                        let (wide_batch, reached) =
+                            limit.take(layout.widen(idx, &batch, &flat_batch)?);
+                        let n = wide_batch.num_rows() as u64;
+                        writer.write_batch(wide_batch)?;
+                        pb.inc(n);
+                        if reached {
+                            return Err(LimitReached.into());
+                        }
+                        Ok(())
+                    }))?;
+                }
+            }
+            Some(Layout::Long) => {
+                let layout = LongLayout::new(self.log_time_field(&reader, &topics)?);
+                for (topic, (fields, policy)) in topics.iter().zip(&topic_options) {
+                    let mut limit = limit.reset();
+                    let decode = |send: &mut dyn FnMut(RecordBatch) -> _| {
+                        reader.for_each_record_batch(&self.input, topic, send)
+                    };
+                    stop_at_limit(queue.run(decode, |batch, queued| {
+                        pb.set_message(format!("queue {queued}/{}", self.queue_capacity));
+                        let flat_batch = post_process(
+                            &batch,
+                            *fields,
+                            &self.transforms,
+                            policy,
+                            self.separator,
+                            &mut dropped_warned,
+                        )?;
+                        let n = flat_batch.num_rows() as u64;
+                        let (long_batch, reached) =
+                            limit.take(layout.lengthen(topic, &batch, &flat_batch)?);
+                        writer.write_batch(long_batch)?;
+                        pb.inc(n);
+                        if reached {
+                            return Err(LimitReached.into());
+                        }
+                        Ok(())
+                    }))?;
+                }
             }
-            let n = flat_batch.num_rows() as u64;
-            writer.write_batch(flat_batch)?;
-            pb.inc(n);
-            Ok(())
-        })?;
+        }
 
         writer.finish()?;
         pb.finish_with_message("done");
+        if self.queue_capacity > 0 {
+            eprintln!("Info: {queue}");
+        }
+        for line in decode_stats.to_string().lines() {
+            eprintln!("Warning: {line}");
+        }
+        if let Some(column_stats) = &column_stats {
+            match &self.stats_output {
+                Some(path) => {
+                    write_column_stats(path, &self.input, column_stats)?;
+                    eprintln!("Column statistics written to {}", path.display());
+                }
+                None => {
+                    for line in column_stats.to_string().lines() {
+                        eprintln!("Stats: {line}");
+                    }
+                }
+            }
+        }
         Ok(())
     }
 
-    fn flatten_policy(&self) -> Result<FlattenPolicy> {
+    /// Reader configured by the flags, skipping `resumed` more messages of
+    /// each topic, counting decode warnings in `decode_stats` and collecting
+    /// column statistics in `column_stats`.
+    fn reader(
+        &self,
+        resumed: u64,
+        decode_stats: &Arc<DecodeStats>,
+        column_stats: Option<&Arc<TopicColumnStats>>,
+    ) -> Result<McapReader> {
+        let mut builder = McapReader::builder()
+            .with_default_decoders()
+            .with_decode_stats(Arc::clone(decode_stats))
+            .with_parallel(self.parallel)
+            .with_dedup(self.dedup)
+            .with_sorted_map_keys(self.sort_map_keys || self.deterministic)
+            .with_duplicate_map_keys(self.duplicate_map_keys)
+            .with_widening(self.widening)
+            .with_struct_mismatch(self.struct_mismatch);
+        for decoder in self.decoder.decoders() {
+            builder = builder.with_decoder(decoder);
+        }
+        if let Some(max_depth) = self.max_depth {
+            builder = builder.with_max_depth(max_depth);
+        }
+        if let Some(column_stats) = column_stats {
+            builder = builder.with_column_stats(Arc::clone(column_stats));
+        }
+        let skip_messages = self.skip_rows.unwrap_or_default() + resumed;
+        if skip_messages > 0 {
+            builder = builder.with_skip_messages(skip_messages);
+        }
+        if let Some(skip_duration) = self.skip_duration {
+            let start_time = McapReader::new().message_start_time(&self.input)?;
+            let skip_ns = u64::try_from(skip_duration.as_nanos()).unwrap_or(u64::MAX);
+            builder = builder.with_start_time(start_time.saturating_add(skip_ns));
+        }
+        if let Some(sample) = self.sample {
+            builder = builder.with_sample(sample);
+        }
+        Ok(builder.build())
+    }
+
+    /// Topics selected by `--topic` or `--topics`.
+    fn topics(&self) -> Vec<String> {
+        match (&self.topic, &self.topics) {
+            (Some(topic), _) => vec![topic.clone()],
+            (None, Some(topics)) => topics.clone(),
+            (None, None) => Vec::new(),
+        }
+    }
+
+    fn follow_options(&self) -> FollowOptions {
+        let options = FollowOptions::new();
+        match self.idle_timeout {
+            Some(secs) => options.with_idle_timeout(Duration::from_secs(secs)),
+            None => options,
+        }
+    }
+
+    /// The `@log_time` field of the reader batches for the first topic.
+    fn log_time_field(&self, reader: &McapReader, topics: &[String]) -> Result<FieldRef> {
+        let Some(topic) = topics.first() else {
+            anyhow::bail!("no topic selected");
+        };
+        let field_defs = reader.topic_field_defs(&self.input, topic)?;
+        let schema = field_defs_to_record_batch_schema(&field_defs);
+        Ok(Arc::new(
+            schema.field_with_name(TIMESTAMP_COLUMNS[0])?.clone(),
+        ))
+    }
+
+    /// `--fields` and flatten policy of `topic`, with its `--config` section applied.
+    fn topic_options<'a>(
+        &'a self,
+        file_config: &'a Config,
+        topic: &str,
+        policy: &FlattenPolicy,
+    ) -> Result<(Option<&'a [String]>, FlattenPolicy)> {
+        match file_config.topic(topic) {
+            Some(options) => Ok((
+                options.fields.as_deref().or(self.fields.as_deref()),
+                options.flatten_policy(topic, policy)?,
+            )),
+            None => Ok((self.fields.as_deref(), *policy)),
+        }
+    }
+
+    /// Derive the merged output schema from each topic's post-processed schema.
+    fn wide_layout(
+        &self,
+        reader: &McapReader,
+        topics: &[String],
+        topic_options: &[(Option<&[String]>, FlattenPolicy)],
+    ) -> Result<WideLayout> {
+        let mut timestamp_fields = Vec::new();
+        let mut topic_schemas = Vec::with_capacity(topics.len());
+        for (topic, (fields, policy)) in topics.iter().zip(topic_options) {
+            let field_defs = reader.topic_field_defs(&self.input, topic)?;
+            let schema = Arc::new(field_defs_to_record_batch_schema(&field_defs));
+            if timestamp_fields.is_empty() {
+                timestamp_fields = schema
+                    .fields()
+                    .iter()
+                    .filter(|f| TIMESTAMP_COLUMNS.contains(&f.name().as_str()))
+                    .cloned()
+                    .collect();
+            }
+            let empty = RecordBatch::new_empty(schema);
+            let flat = post_process(
+                &empty,
+                *fields,
+                &self.transforms,
+                policy,
+                self.separator,
+                &mut true,
+            )?;
+            topic_schemas.push((topic.as_str(), flat.schema()));
+        }
+        Ok(WideLayout::new(&timestamp_fields, &topic_schemas))
+    }
+
+    /// Sample messages and return the longest list observed in the output fields.
+    fn infer_list_flatten_size(
+        &self,
+        reader: &McapReader,
+        topics: &[String],
+        file_config: &Config,
+    ) -> Result<usize> {
+        let mut size = 1;
+        let mut sampled = 0;
+        for topic in topics {
+            let fields = file_config
+                .topic(topic)
+                .and_then(|options| options.fields.as_ref())
+                .or(self.fields.as_ref());
+            let report = sample_topic_stats(reader, &self.input, topic, self.list_flatten_sample)?;
+            let max_len = report.max_list_len(|path| match fields {
+                Some(fields) => fields.iter().any(|f| is_within_field(path, f)),
+                None => true,
+            });
+            size = size.max(max_len.unwrap_or(0));
+            sampled += report.sampled_messages;
+        }
+        eprintln!("Info: inferred --list-flatten-size {size} from {sampled} sampled messages");
+        Ok(size)
+    }
+
+    /// Check flag combinations and resolve the effective [`FlattenPolicy`].
+    fn validate(&self) -> Result<ConvertConfig, clap::Error> {
         let mut policy = self.format.default_policy();
+        let mut list_size = self.list_flatten_size;
 
-        if let Some(v) = self.list_policy {
-            policy.list = v;
+        if let Some(spec) = self.list_policy {
+            policy.list = spec.policy;
+            match (spec.flatten_fixed_size, list_size) {
+                (Some(a), Some(b)) if a != b => {
+                    return Err(usage_error(
+                        ErrorKind::ArgumentConflict,
+                        format!(
+                            "--list-policy flatten-fixed:{a} conflicts with --list-flatten-size {b}"
+                        ),
+                    ));
+                }
+                (Some(a), _) => list_size = Some(a),
+                (None, _) => {}
+            }
         }
-        if let Some(v) = self.list_flatten_size {
+        if let Some(size) = list_size {
             if policy.list != ListPolicy::FlattenFixed {
-                bail!("--list-flatten-size requires --list-policy flatten-fixed");
+                return Err(usage_error(
+                    ErrorKind::ArgumentConflict,
+                    "--list-flatten-size requires --list-policy flatten-fixed",
+                ));
+            }
+            if size == 0 {
+                return Err(usage_error(
+                    ErrorKind::InvalidValue,
+                    "--list-flatten-size must be at least 1",
+                ));
             }
-            policy.list_flatten_fixed_size = v;
+            policy.list_flatten_fixed_size = size;
         }
         if let Some(v) = self.array_policy {
             policy.array = v;
@@ -133,17 +884,307 @@ impl ConvertArgs {
         if let Some(v) = self.map_policy {
             policy.map = v;
         }
-        policy.struct_ = match self.format {
-            OutputFormat::Jsonl => StructPolicy::Keep,
-            OutputFormat::Csv | OutputFormat::Parquet => StructPolicy::Flatten,
+        let is_influx = matches!(self.format, OutputFormat::Influx);
+        if is_influx && self.layout.is_some() {
+            return Err(usage_error(
+                ErrorKind::ArgumentConflict,
+                "--layout cannot be used with --format influx (each topic is written as its own measurement)",
+            ));
+        }
+        if self.transforms.laser_scan_points == Some(LaserScanPoints::Explode)
+            && (self.layout.is_some() || self.instance_key)
+        {
+            return Err(usage_error(
+                ErrorKind::ArgumentConflict,
+                "--laser-scan-points explode cannot be used with --layout or --instance-key (rows no longer match the decoded messages)",
+            ));
+        }
+        if self.check_append.is_some() && !matches!(self.format, OutputFormat::Parquet) {
+            return Err(usage_error(
+                ErrorKind::ArgumentConflict,
+                "--check-append requires --format parquet",
+            ));
+        }
+        if self.compress.is_some()
+            && !matches!(
+                self.format,
+                OutputFormat::Jsonl | OutputFormat::Csv | OutputFormat::Influx
+            )
+        {
+            return Err(usage_error(
+                ErrorKind::ArgumentConflict,
+                "--compress requires --format jsonl, csv or influx (Parquet is compressed internally)",
+            ));
+        }
+        if self.json_int64 != JsonInt64::Number && !matches!(self.format, OutputFormat::Jsonl) {
+            return Err(usage_error(
+                ErrorKind::ArgumentConflict,
+                "--json-int64 requires --format jsonl",
+            ));
+        }
+        if self.split_topics && !matches!(self.format, OutputFormat::Parquet) {
+            return Err(usage_error(
+                ErrorKind::ArgumentConflict,
+                "--split-topics requires --format parquet",
+            ));
+        }
+        if let Some(window) = self.split_by {
+            if window < Duration::from_secs(1) {
+                return Err(usage_error(
+                    ErrorKind::InvalidValue,
+                    "--split-by must be at least 1s",
+                ));
+            }
+            if self
+                .output_template
+                .as_ref()
+                .is_some_and(|template| !template.has_window())
+            {
+                return Err(usage_error(
+                    ErrorKind::ArgumentConflict,
+                    "--output-template must contain {window} with --split-by",
+                ));
+            }
+        }
+        if is_influx && self.time_columns == TimeColumns::Elapsed {
+            return Err(usage_error(
+                ErrorKind::ArgumentConflict,
+                "--time-columns elapsed cannot be used with --format influx (points are timestamped by @log_time)",
+            ));
+        }
+        if !is_influx && self.influx_tags.is_some() {
+            return Err(usage_error(
+                ErrorKind::ArgumentConflict,
+                "--influx-tags requires --format influx",
+            ));
+        }
+        #[cfg(feature = "adbc")]
+        self.validate_adbc()?;
+
+        // Merging layouts apply to multiple topics, or to any topic when long.
+        let layout = match self.layout {
+            _ if is_influx || self.split_topics => None,
+            // Without an explicit layout, database output gets one table per topic.
+            #[cfg(feature = "adbc")]
+            None if matches!(self.format, OutputFormat::Adbc) => None,
+            None | Some(Layout::Wide) if self.topics().len() <= 1 => None,
+            layout => Some(layout.unwrap_or(Layout::Wide)),
         };
 
-        Ok(policy)
+        policy.struct_ = match (self.format, layout) {
+            (_, Some(Layout::Long)) => StructPolicy::Flatten,
+            (OutputFormat::Jsonl, _) => StructPolicy::Keep,
+            _ => StructPolicy::Flatten,
+        };
+
+        let infer_list_flatten_size =
+            policy.list == ListPolicy::FlattenFixed && list_size.is_none();
+        if self.follow && infer_list_flatten_size {
+            return Err(usage_error(
+                ErrorKind::MissingRequiredArgument,
+                "--follow with --list-policy flatten-fixed requires --list-flatten-size",
+            ));
+        }
+
+        Ok(ConvertConfig {
+            flatten_policy: policy,
+            layout,
+            infer_list_flatten_size,
+        })
+    }
+
+    /// File names of `--split-topics` outputs.
+    fn split_template(&self) -> OutputTemplate {
+        match (&self.output_template, self.split_by) {
+            (Some(template), _) => template.clone(),
+            (None, Some(_)) => OutputTemplate::windowed(),
+            (None, None) => OutputTemplate::default(),
+        }
+    }
+
+    /// Table names of `topics` from `--table-name` and the `table-name` keys
+    /// of `file_config`, checked up front for topics that would share a
+    /// table or file of the output.
+    #[cfg_attr(not(feature = "adbc"), allow(unused_variables))]
+    fn table_names(
+        &self,
+        file_config: &Config,
+        topics: &[String],
+        layout: Option<Layout>,
+    ) -> Result<TableNames> {
+        let mut table_names: TableNames = self.table_name.iter().flatten().cloned().collect();
+        for (topic, options) in &file_config.topics {
+            if let Some(name) = &options.table_name {
+                table_names.insert(topic, name)?;
+            }
+        }
+        if self.split_topics && self.partition_by_topic {
+            table_names.check_unique(topics, |topic| {
+                table_names.explicit(topic).unwrap_or(topic).to_string()
+            })?;
+        } else if self.split_topics {
+            let template = self.split_template();
+            table_names.check_unique(topics, |topic| {
+                template.render(topic, &table_names.name(topic), None, "parquet")
+            })?;
+        }
+        #[cfg(feature = "adbc")]
+        if matches!(self.format, OutputFormat::Adbc) && self.table.is_none() && layout.is_none() {
+            table_names.check_unique(topics, |topic| table_names.name(topic))?;
+        }
+        Ok(table_names)
+    }
+
+    /// Check the `--config` sections against the flags they combine with.
+    fn validate_file_config(&self, file_config: &Config) -> Result<()> {
+        for (topic, options) in &file_config.topics {
+            if matches!(self.format, OutputFormat::Influx)
+                && options.time_columns == Some(TimeColumns::Elapsed)
+            {
+                anyhow::bail!(
+                    "config for topic '{topic}': time-columns elapsed cannot be used with --format influx"
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "adbc")]
+impl ConvertArgs {
+    fn validate_adbc(&self) -> Result<(), clap::Error> {
+        let is_adbc = matches!(self.format, OutputFormat::Adbc);
+        if !is_adbc {
+            if self.adbc_driver.is_some() || self.dsn.is_some() || self.table.is_some() {
+                return Err(usage_error(
+                    ErrorKind::ArgumentConflict,
+                    "--adbc-driver, --dsn and --table require --format adbc",
+                ));
+            }
+            return Ok(());
+        }
+        if self.adbc_driver.is_none() || self.dsn.is_none() {
+            return Err(usage_error(
+                ErrorKind::MissingRequiredArgument,
+                "--format adbc requires --adbc-driver and --dsn",
+            ));
+        }
+        let merged = match self.layout {
+            Some(Layout::Long) => true,
+            Some(Layout::Wide) => self.topics().len() > 1,
+            None => false,
+        };
+        if merged && self.table.is_none() {
+            return Err(usage_error(
+                ErrorKind::MissingRequiredArgument,
+                "--layout with --format adbc requires --table",
+            ));
+        }
+        Ok(())
     }
 }
 
-fn parse_list_policy(raw: &str) -> Result<ListPolicy, String> {
-    ListPolicy::from_str(raw)
+/// Flag combination of [`ConvertArgs`] after validation.
+struct ConvertConfig {
+    flatten_policy: FlattenPolicy,
+    /// Multi-topic layout, or `None` when each topic is written as-is.
+    layout: Option<Layout>,
+    /// `true` when flatten-fixed was requested without an explicit size.
+    infer_list_flatten_size: bool,
+}
+
+/// Build a clap error that is reported with the `convert` usage line.
+fn usage_error(kind: ErrorKind, message: impl std::fmt::Display) -> clap::Error {
+    let mut cmd = crate::Cli::command();
+    cmd.build();
+    match cmd.find_subcommand_mut("convert") {
+        Some(convert) => convert.error(kind, message),
+        None => cmd.error(kind, message),
+    }
+}
+
+/// Apply `--fields` projection, the semantic transforms and flattening (with
+/// `separator`, `.` if `None`) to a reader batch.
+fn post_process(
+    batch: &RecordBatch,
+    fields: Option<&[String]>,
+    transforms: &TransformArgs,
+    policy: &FlattenPolicy,
+    separator: Option<char>,
+    dropped_warned: &mut bool,
+) -> Result<RecordBatch, ArrowError> {
+    let batch = match fields {
+        Some(fields) => project_record_batch(batch, fields)?,
+        None => batch.clone(),
+    };
+    let (flat_batch, dropped_columns) =
+        flatten_record_batch(&transforms.apply(batch)?, separator, policy)?;
+    if !*dropped_warned && !dropped_columns.is_empty() {
+        *dropped_warned = true;
+        eprintln!(
+            "Warning: output policy skipped columns: {}",
+            dropped_columns.join(", ")
+        );
+    }
+    Ok(flat_batch)
+}
+
+/// Prepend the reader batch's timestamp columns if projection removed them.
+fn with_timestamps(
+    batch: &RecordBatch,
+    flat_batch: &RecordBatch,
+) -> Result<RecordBatch, ArrowError> {
+    let schema = flat_batch.schema();
+    let mut fields = Vec::new();
+    let mut columns = Vec::new();
+    for name in TIMESTAMP_COLUMNS {
+        if schema.column_with_name(name).is_none()
+            && let Some((idx, field)) = batch.schema().column_with_name(name)
+        {
+            fields.push(Arc::new(field.clone()));
+            columns.push(batch.column(idx).clone());
+        }
+    }
+    if fields.is_empty() {
+        return Ok(flat_batch.clone());
+    }
+    fields.extend(schema.fields().iter().cloned());
+    columns.extend(flat_batch.columns().iter().cloned());
+    RecordBatch::try_new(Arc::new(Schema::new(fields)), columns)
+}
+
+/// Append the `@instance_key` column computed from the reader batch's key
+/// fields, which projection and flattening may have removed or split.
+fn with_instance_key(
+    batch: &RecordBatch,
+    flat_batch: &RecordBatch,
+) -> Result<RecordBatch, ArrowError> {
+    let Some(keys) = instance_key_column(batch)? else {
+        return Ok(flat_batch.clone());
+    };
+    let schema = flat_batch.schema();
+    let mut fields: Vec<FieldRef> = schema.fields().iter().cloned().collect();
+    fields.push(Arc::new(Field::new(
+        INSTANCE_KEY_COLUMN,
+        keys.data_type().clone(),
+        false,
+    )));
+    let mut columns = flat_batch.columns().to_vec();
+    columns.push(keys);
+    RecordBatch::try_new(
+        Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone())),
+        columns,
+    )
+}
+
+/// Whether stats `path` lies at or below the projected field path `field`.
+fn is_within_field(path: &str, field: &str) -> bool {
+    path.strip_prefix(field)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('.') || rest.starts_with('['))
+}
+
+fn parse_list_policy(raw: &str) -> Result<ListPolicySpec, String> {
+    ListPolicySpec::from_str(raw)
 }
 
 fn parse_array_policy(raw: &str) -> Result<ArrayPolicy, String> {
@@ -153,3 +1194,46 @@ fn parse_array_policy(raw: &str) -> Result<ArrayPolicy, String> {
 fn parse_map_policy(raw: &str) -> Result<MapPolicy, String> {
     MapPolicy::from_str(raw)
 }
+
+fn parse_duplicate_map_keys(raw: &str) -> Result<DuplicateMapKeys, String> {
+    DuplicateMapKeys::from_str(raw)
+}
+
+fn parse_widening(raw: &str) -> Result<WideningPolicy, String> {
+    WideningPolicy::from_str(raw)
+}
+
+fn parse_struct_mismatch(raw: &str) -> Result<StructMismatch, String> {
+    StructMismatch::from_str(raw)
+}
+
+fn parse_schema_evolution(raw: &str) -> Result<SchemaEvolution, String> {
+    SchemaEvolution::from_str(raw)
+}
+
+fn parse_sample(raw: &str) -> Result<Sample, String> {
+    Sample::from_str(raw)
+}
+
+pub(crate) fn parse_duration(raw: &str) -> Result<Duration, String> {
+    let invalid = || format!("invalid duration '{raw}' (expected e.g. 500ms, 90s, 5m, 10min, 1h)");
+    let value = raw.trim();
+    let unit_start = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let amount: u64 = value[..unit_start].parse().map_err(|_| invalid())?;
+    let duration = match &value[unit_start..] {
+        "ns" => Duration::from_nanos(amount),
+        "us" => Duration::from_micros(amount),
+        "ms" => Duration::from_millis(amount),
+        "" | "s" => Duration::from_secs(amount),
+        "m" | "min" => Duration::from_secs(amount.saturating_mul(60)),
+        "h" => Duration::from_secs(amount.saturating_mul(3600)),
+        _ => return Err(invalid()),
+    };
+    Ok(duration)
+}
+
+fn parse_time_columns(raw: &str) -> Result<TimeColumns, String> {
+    TimeColumns::from_str(raw)
+}
@@ -0,0 +1,269 @@
+use std::{fmt, path::PathBuf};
+
+use anyhow::Result;
+use clap::Args;
+use mcapdecode::{
+    McapReader, McapReaderError,
+    core::{DecoderError, SchemaSyntaxError},
+    ros2idl::Ros2Error,
+};
+
+use crate::{decoders::DecoderArgs, stats::SampleLimitReached};
+
+#[derive(Args)]
+pub struct DoctorArgs {
+    /// Path to the mcap file
+    input: PathBuf,
+
+    /// Topic to diagnose
+    #[arg(short, long)]
+    topic: String,
+
+    #[command(flatten)]
+    decoder: DecoderArgs,
+
+    /// Number of messages decoded by the message check (0 skips it)
+    #[arg(long, default_value_t = 1000)]
+    sample: u64,
+}
+
+/// Conversion stages checked by `doctor`, in the order they run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Stage {
+    /// The topic exists in the summary section.
+    Topic,
+    /// A decoder is registered for the channel's encoding pair.
+    Decoder,
+    /// The schema data is fetched and parsed.
+    Schema,
+    /// Type names in the parsed schema resolve to a root message type.
+    Types,
+    /// Message payloads decode with the derived schema.
+    Messages,
+}
+
+impl Stage {
+    const ALL: [Stage; 5] = [
+        Stage::Topic,
+        Stage::Decoder,
+        Stage::Schema,
+        Stage::Types,
+        Stage::Messages,
+    ];
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Stage::Topic => "topic",
+            Stage::Decoder => "decoder",
+            Stage::Schema => "schema",
+            Stage::Types => "types",
+            Stage::Messages => "messages",
+        }
+    }
+}
+
+/// First failing stage and the lines explaining the failure.
+struct Failure {
+    stage: Stage,
+    details: Vec<String>,
+}
+
+impl Failure {
+    fn new(stage: Stage, detail: impl Into<String>) -> Self {
+        Self {
+            stage,
+            details: vec![detail.into()],
+        }
+    }
+}
+
+/// Result of every stage up to (and including) the first failing one.
+struct Report {
+    passed: Vec<(Stage, String)>,
+    failure: Option<Failure>,
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (stage, summary) in &self.passed {
+            writeln!(f, "ok    {:<8}  {summary}", stage.as_str())?;
+        }
+        let Some(failure) = &self.failure else {
+            return Ok(());
+        };
+        for stage in Stage::ALL {
+            if stage == failure.stage {
+                let (first, rest) = failure.details.split_first().expect("failure has details");
+                writeln!(f, "FAIL  {:<8}  {first}", stage.as_str())?;
+                for line in rest.iter().flat_map(|detail| detail.lines()) {
+                    writeln!(f, "                {line}")?;
+                }
+            } else if !self.passed.iter().any(|(passed, _)| *passed == stage) {
+                writeln!(f, "skip  {:<8}  not checked", stage.as_str())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl DoctorArgs {
+    pub fn run(self) -> Result<()> {
+        // Decode sequentially so that the failing message's index is exact.
+        let mut builder = McapReader::builder()
+            .with_default_decoders()
+            .with_parallel(false);
+        for decoder in self.decoder.decoders() {
+            builder = builder.with_decoder(decoder);
+        }
+        let reader = builder.build();
+
+        let report = self.diagnose(&reader);
+        print!("{report}");
+        match report.failure {
+            Some(failure) => anyhow::bail!(
+                "topic '{}' cannot be converted: {} check failed",
+                self.topic,
+                failure.stage.as_str()
+            ),
+            None => Ok(()),
+        }
+    }
+
+    fn diagnose(&self, reader: &McapReader) -> Report {
+        let mut report = Report {
+            passed: Vec::new(),
+            failure: None,
+        };
+        if let Err(failure) = self.run_stages(reader, &mut report.passed) {
+            report.failure = Some(failure);
+        }
+        report
+    }
+
+    fn run_stages(
+        &self,
+        reader: &McapReader,
+        passed: &mut Vec<(Stage, String)>,
+    ) -> Result<(), Failure> {
+        let topics = reader
+            .list_topics(&self.input)
+            .map_err(|e| Failure::new(Stage::Topic, e.to_string()))?;
+        let info = topics
+            .into_iter()
+            .find(|info| info.topic == self.topic)
+            .ok_or_else(|| {
+                Failure::new(Stage::Topic, format!("topic '{}' not found", self.topic))
+            })?;
+        let encoding = format!("{}/{}", info.schema_encoding, info.message_encoding);
+        passed.push((
+            Stage::Topic,
+            format!(
+                "schema '{}', {} message(s), {} channel(s)",
+                info.schema_name.as_deref().unwrap_or("-"),
+                info.message_count
+                    .map_or_else(|| "?".to_string(), |count| count.to_string()),
+                info.channel_count,
+            ),
+        ));
+
+        let field_defs = match reader.topic_field_defs(&self.input, &self.topic) {
+            Ok(field_defs) => field_defs,
+            Err(err) => {
+                let failure = schema_failure(err);
+                // Duplicate channels only surface once the topic is resolved.
+                passed.retain(|(stage, _)| *stage != failure.stage);
+                for stage in Stage::ALL[1..].iter().take_while(|s| **s != failure.stage) {
+                    passed.push((*stage, stage_summary(*stage, &encoding)));
+                }
+                return Err(failure);
+            }
+        };
+        for stage in [Stage::Decoder, Stage::Schema] {
+            passed.push((stage, stage_summary(stage, &encoding)));
+        }
+        passed.push((
+            Stage::Types,
+            format!("resolved {} top-level field(s)", field_defs.len()),
+        ));
+
+        if self.sample == 0 {
+            return Ok(());
+        }
+        let mut decoded = 0u64;
+        let result = reader.for_each_decoded_message(&self.input, &self.topic, |_message| {
+            decoded += 1;
+            if decoded >= self.sample {
+                return Err(SampleLimitReached.into());
+            }
+            Ok(())
+        });
+        match result {
+            Ok(()) => {}
+            Err(McapReaderError::Callback(e)) if e.is::<SampleLimitReached>() => {}
+            Err(McapReaderError::MessageDecodeFailed { source, .. }) => {
+                return Err(message_failure(decoded, &source));
+            }
+            Err(err) => return Err(Failure::new(Stage::Messages, err.to_string())),
+        }
+        passed.push((Stage::Messages, format!("decoded {decoded} message(s)")));
+        Ok(())
+    }
+}
+
+fn stage_summary(stage: Stage, encoding: &str) -> String {
+    match stage {
+        Stage::Decoder => format!("decoder registered for {encoding}"),
+        Stage::Schema => "schema parsed".to_string(),
+        _ => String::new(),
+    }
+}
+
+/// Attribute an error from deriving the topic's fields to the stage it
+/// comes from.
+fn schema_failure(err: McapReaderError) -> Failure {
+    match err {
+        McapReaderError::TopicNotFound { .. } | McapReaderError::MultipleChannels { .. } => {
+            Failure::new(Stage::Topic, err.to_string())
+        }
+        McapReaderError::NoDecoder { .. } | McapReaderError::SchemaNotAvailable { .. } => {
+            Failure::new(Stage::Decoder, err.to_string())
+        }
+        McapReaderError::EmptyDerivedSchema { .. } => Failure::new(Stage::Types, err.to_string()),
+        McapReaderError::SchemaDerivationFailed { source, .. } => {
+            let stage = match &source {
+                DecoderError::SchemaInvalid { .. } => Stage::Types,
+                DecoderError::SchemaParse { source: cause, .. }
+                    if matches!(
+                        cause.downcast_ref::<Ros2Error>(),
+                        Some(Ros2Error::UnresolvedType(_))
+                    ) =>
+                {
+                    Stage::Types
+                }
+                _ => Stage::Schema,
+            };
+            let mut failure = Failure::new(stage, source.to_string());
+            if let DecoderError::SchemaParse { source: cause, .. } = &source
+                && let Some(syntax) = cause.downcast_ref::<SchemaSyntaxError>()
+            {
+                failure.details.push(syntax.render_diagnostic());
+            }
+            failure
+        }
+        other => Failure::new(Stage::Schema, other.to_string()),
+    }
+}
+
+/// Describe the first message that failed to decode, `index` messages into
+/// the topic.
+fn message_failure(index: u64, source: &DecoderError) -> Failure {
+    let mut failure = Failure::new(Stage::Messages, format!("message #{index}: {source}"));
+    if let DecoderError::MessageDecode { source: cause, .. } = source
+        && let Some(Ros2Error::CdrError { path, offset, .. }) = cause.downcast_ref::<Ros2Error>()
+    {
+        failure
+            .details
+            .push(format!("at field '{path}', byte offset {offset}"));
+    }
+    failure
+}
@@ -0,0 +1,87 @@
+use std::{
+    fs,
+    io::{BufWriter, Write},
+    path::PathBuf,
+};
+
+use anyhow::Result;
+use clap::Args;
+use mcapdecode::{McapReader, protobuf::descriptor_set_to_proto};
+
+use crate::{table_names::sanitize_table_name, template::OutputTemplate, writer::MANIFEST_FILE};
+
+#[derive(Args)]
+pub struct ExportSchemasArgs {
+    /// Path to the mcap file
+    input: PathBuf,
+
+    /// Directory the schema files and manifest.json are written to
+    #[arg(short, long)]
+    output: PathBuf,
+
+    /// Comma-separated list of topics to export [default: all protobuf topics]
+    #[arg(long, value_delimiter = ',')]
+    topics: Option<Vec<String>>,
+}
+
+impl ExportSchemasArgs {
+    pub fn run(self) -> Result<()> {
+        let reader = McapReader::new();
+        let topics: Vec<String> = match &self.topics {
+            Some(topics) => topics.clone(),
+            None => reader
+                .list_topics(&self.input)?
+                .into_iter()
+                .filter(|info| info.schema_encoding == "protobuf")
+                .map(|info| info.topic)
+                .collect(),
+        };
+        if topics.is_empty() {
+            anyhow::bail!("no protobuf topics found in {}", self.input.display());
+        }
+
+        fs::create_dir_all(&self.output)?;
+        let template = OutputTemplate::default();
+        let mut entries = Vec::with_capacity(topics.len());
+        for topic in &topics {
+            let schema = reader.topic_schema(&self.input, topic)?;
+            if schema.encoding != "protobuf" {
+                anyhow::bail!(
+                    "topic '{topic}' has a {} schema, not protobuf",
+                    schema.encoding
+                );
+            }
+            let table = sanitize_table_name(topic);
+            let descriptor_file = template.render(topic, &table, None, "bin");
+            let proto_file = template.render(topic, &table, None, "proto");
+            fs::write(self.output.join(&descriptor_file), &schema.data)?;
+            fs::write(
+                self.output.join(&proto_file),
+                descriptor_set_to_proto(&schema.name, &schema.data)?,
+            )?;
+            entries.push(serde_json::json!({
+                "topic": topic,
+                "schema_name": schema.name,
+                "descriptor_set": descriptor_file,
+                "proto": proto_file,
+                "descriptor_crc32": format!("{:08x}", crc32fast::hash(&schema.data)),
+            }));
+        }
+
+        let manifest = serde_json::json!({
+            "input": self.input.display().to_string(),
+            "schemas": entries,
+        });
+        let path = self.output.join(MANIFEST_FILE);
+        let mut file = BufWriter::new(fs::File::create(&path)?);
+        serde_json::to_writer_pretty(&mut file, &manifest)?;
+        writeln!(file)?;
+        file.flush()?;
+        eprintln!(
+            "Exported {} schema(s) to {}",
+            topics.len(),
+            self.output.display()
+        );
+        Ok(())
+    }
+}
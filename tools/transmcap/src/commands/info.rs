@@ -0,0 +1,74 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::Args;
+use mcapdecode::{McapReader, SUPPORTED_COMPRESSIONS};
+
+#[derive(Args)]
+pub struct InfoArgs {
+    /// Path to the mcap file
+    input: PathBuf,
+}
+
+impl InfoArgs {
+    pub fn run(self) -> Result<()> {
+        let reader = McapReader::new();
+        let topics = reader.list_topics(&self.input)?;
+        let compressions = reader.chunk_compressions(&self.input)?;
+
+        let topic_rows: Vec<Vec<String>> = topics
+            .into_iter()
+            .map(|info| {
+                vec![
+                    info.topic,
+                    info.message_count
+                        .map_or_else(|| "-".to_string(), |count| count.to_string()),
+                    info.schema_name.unwrap_or_else(|| "-".to_string()),
+                    format!("{}/{}", info.schema_encoding, info.message_encoding),
+                ]
+            })
+            .collect();
+        print_table(&["topic", "messages", "schema", "encoding"], &topic_rows);
+
+        println!();
+        let compression_rows: Vec<Vec<String>> = compressions
+            .into_iter()
+            .map(|(compression, chunks)| {
+                let supported = compression.is_empty()
+                    || SUPPORTED_COMPRESSIONS.contains(&compression.as_str());
+                let name = if compression.is_empty() {
+                    "none".to_string()
+                } else {
+                    compression
+                };
+                vec![
+                    name,
+                    chunks.to_string(),
+                    if supported { "yes" } else { "no" }.to_string(),
+                ]
+            })
+            .collect();
+        print_table(&["compression", "chunks", "supported"], &compression_rows);
+        Ok(())
+    }
+}
+
+fn print_table(headers: &[&str], rows: &[Vec<String>]) {
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.len()).collect();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    let headers: Vec<String> = headers.iter().map(|h| h.to_string()).collect();
+    for row in std::iter::once(&headers).chain(rows) {
+        let line = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect::<Vec<_>>()
+            .join("  ");
+        println!("{}", line.trim_end());
+    }
+}
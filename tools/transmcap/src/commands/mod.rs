@@ -1,2 +1,7 @@
 pub mod convert;
+pub mod doctor;
+pub mod export_schemas;
+pub mod info;
+pub mod repack;
 pub mod schema;
+pub mod verify;
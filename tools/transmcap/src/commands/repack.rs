@@ -0,0 +1,47 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::Args;
+use mcapdecode::{McapReader, RepackCompression, RepackOptions};
+
+use crate::limit::parse_size;
+
+#[derive(Args)]
+pub struct RepackArgs {
+    /// Path to the mcap file
+    input: PathBuf,
+
+    /// Path of the repacked mcap file
+    #[arg(short, long)]
+    output: PathBuf,
+
+    /// Target uncompressed chunk size (e.g. 512K, 4MB)
+    #[arg(long, value_name = "SIZE", value_parser = parse_size, default_value = "4M")]
+    chunk_size: u64,
+
+    /// Chunk compression: none | zstd | lz4
+    #[arg(long, value_parser = parse_compression, default_value = "zstd")]
+    compression: RepackCompression,
+}
+
+impl RepackArgs {
+    pub fn run(self) -> Result<()> {
+        let options = RepackOptions {
+            compression: self.compression,
+            chunk_size: self.chunk_size,
+        };
+        let summary = McapReader::new().repack(&self.input, &self.output, &options)?;
+        eprintln!(
+            "Repacked {} message(s), {} attachment(s), {} metadata record(s) to {}",
+            summary.messages,
+            summary.attachments,
+            summary.metadata,
+            self.output.display()
+        );
+        Ok(())
+    }
+}
+
+fn parse_compression(raw: &str) -> Result<RepackCompression, String> {
+    raw.parse()
+}
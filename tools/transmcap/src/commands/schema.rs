@@ -1,8 +1,29 @@
-use std::{fs, path::PathBuf};
+use std::{
+    borrow::Cow,
+    fs,
+    io::{self, Write},
+    path::PathBuf,
+    sync::Arc,
+};
 
 use anyhow::Result;
-use clap::Args;
-use mcapdecode::{McapReader, core::format_field_defs};
+use clap::{Args, ValueEnum};
+use mcapdecode::{
+    DecodeStats, McapReader,
+    core::{format_field_defs, format_field_defs_tree, format_field_defs_with_docs},
+    protobuf::descriptor_set_to_proto,
+};
+
+use crate::{decoders::DecoderArgs, stats::sample_topic_stats};
+
+#[derive(Clone, Copy, Debug, Default, ValueEnum)]
+pub enum SchemaFormat {
+    /// Indented fields with compound types expanded below them
+    #[default]
+    Flat,
+    /// Tree with full element types, nullability (`?`) and bounds
+    Tree,
+}
 
 #[derive(Args)]
 pub struct SchemaArgs {
@@ -16,18 +37,113 @@ pub struct SchemaArgs {
     /// Output file path (stdout if not specified)
     #[arg(short, long)]
     output: Option<PathBuf>,
+
+    /// Schema layout
+    #[arg(long, value_enum, default_value_t)]
+    format: SchemaFormat,
+
+    /// Show structs nested deeper than N field levels as JSON strings
+    #[arg(long, value_name = "N")]
+    max_depth: Option<usize>,
+
+    #[command(flatten)]
+    decoder: DecoderArgs,
+
+    /// Show field documentation (e.g. IDL `@verbatim` comments) above each field
+    #[arg(long)]
+    docs: bool,
+
+    /// Sample messages and report per-field list lengths, string lengths and null rates
+    #[arg(long)]
+    stats: bool,
+
+    /// Number of messages sampled by --stats
+    #[arg(long, default_value_t = 1000, requires = "stats")]
+    sample: u64,
+
+    /// Print the schema exactly as stored in the mcap file instead of the
+    /// derived fields
+    #[arg(long, conflicts_with_all = [
+        "format",
+        "max_depth",
+        "root_type",
+        "proto_presence",
+        "enum_as",
+        "invalid_utf8",
+        "cdr_quirks",
+        "byte_arrays",
+        "uuid_as",
+        "docs",
+        "stats"
+    ])]
+    raw: bool,
 }
 
 impl SchemaArgs {
     pub fn run(self) -> Result<()> {
-        let reader = McapReader::builder().with_default_decoders().build();
+        if self.raw {
+            return self.dump_raw();
+        }
+        if self.docs && matches!(self.format, SchemaFormat::Tree) {
+            anyhow::bail!("--docs is only supported with --format flat");
+        }
+        let decode_stats = Arc::new(DecodeStats::new());
+        let mut builder = McapReader::builder()
+            .with_default_decoders()
+            .with_decode_stats(Arc::clone(&decode_stats));
+        for decoder in self.decoder.decoders() {
+            builder = builder.with_decoder(decoder);
+        }
+        if let Some(max_depth) = self.max_depth {
+            builder = builder.with_max_depth(max_depth);
+        }
+        let reader = builder.build();
         let field_defs = reader.topic_field_defs(&self.input, &self.topic)?;
-        let text = format_field_defs(&field_defs)?;
+        let mut text = match self.format {
+            SchemaFormat::Tree => format_field_defs_tree(&field_defs)?,
+            SchemaFormat::Flat if self.docs => format_field_defs_with_docs(&field_defs)?,
+            SchemaFormat::Flat => format_field_defs(&field_defs)?,
+        };
+        if self.stats {
+            let report = sample_topic_stats(&reader, &self.input, &self.topic, self.sample)?;
+            text = format!("{}\n\n{}", text.trim_end(), report.to_string().trim_end());
+        }
 
         match self.output {
             Some(path) => fs::write(path, format!("{text}\n"))?,
             None => println!("{text}"),
         }
+        for line in decode_stats.to_string().lines() {
+            eprintln!("Warning: {line}");
+        }
+        Ok(())
+    }
+
+    /// Write the stored schema bytes to `--output`, or print them if they are
+    /// text (IDL bundles, `.msg` definitions, JSON Schema). Protobuf
+    /// descriptor sets are printed as reconstructed `.proto` source.
+    fn dump_raw(&self) -> Result<()> {
+        let schema = McapReader::new().topic_schema(&self.input, &self.topic)?;
+        if let Some(path) = &self.output {
+            fs::write(path, &schema.data)?;
+            return Ok(());
+        }
+        let text = if schema.encoding == "protobuf" {
+            Cow::Owned(descriptor_set_to_proto(&schema.name, &schema.data)?)
+        } else if let Ok(text) = std::str::from_utf8(&schema.data) {
+            Cow::Borrowed(text)
+        } else {
+            anyhow::bail!(
+                "schema '{}' ({}) is binary; use -o to write the raw bytes",
+                schema.name,
+                schema.encoding
+            );
+        };
+        let mut stdout = io::stdout().lock();
+        stdout.write_all(text.as_bytes())?;
+        if !text.ends_with('\n') {
+            writeln!(stdout)?;
+        }
         Ok(())
     }
 }
@@ -0,0 +1,97 @@
+use std::{path::PathBuf, time::Duration};
+
+use anyhow::Result;
+use clap::Args;
+use mcapdecode::{GapReport, McapReader};
+
+use super::convert::parse_duration;
+
+#[derive(Args)]
+pub struct VerifyArgs {
+    /// Path to the mcap file
+    input: PathBuf,
+
+    /// Topic to verify [default: all topics]
+    #[arg(short, long)]
+    topic: Option<String>,
+
+    /// Report log-time gaps between consecutive messages and jumps in their
+    /// sequence numbers
+    #[arg(long, required = true)]
+    gaps: bool,
+
+    /// Report gaps longer than DURATION (e.g. 500ms, 2s)
+    /// [default: three times the median interval of each topic]
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+    gap_threshold: Option<Duration>,
+}
+
+impl VerifyArgs {
+    pub fn run(self) -> Result<()> {
+        let reader = McapReader::new();
+        let topics = match &self.topic {
+            Some(topic) => vec![topic.clone()],
+            None => reader
+                .list_topics(&self.input)?
+                .into_iter()
+                .map(|info| info.topic)
+                .collect(),
+        };
+
+        let mut failed = 0usize;
+        for topic in &topics {
+            let report = reader.gap_report(&self.input, topic, self.gap_threshold)?;
+            print_report(topic, &report);
+            if report.has_issues() {
+                failed += 1;
+            }
+        }
+        if failed > 0 {
+            anyhow::bail!("{failed} topic(s) have gaps or sequence jumps");
+        }
+        Ok(())
+    }
+}
+
+fn print_report(topic: &str, report: &GapReport) {
+    let status = if report.has_issues() { "FAIL" } else { "ok" };
+    println!(
+        "{status:<4}  {topic}: {} message(s), interval min {} / median {} / max {}, threshold {}",
+        report.message_count,
+        format_ns(report.min_interval_ns),
+        format_ns(report.median_interval_ns),
+        format_ns(report.max_interval_ns),
+        format_ns(report.threshold_ns),
+    );
+    for gap in &report.gaps {
+        println!(
+            "      gap of {} before message #{} (log time {} -> {})",
+            format_ns(Some(gap.duration_ns())),
+            gap.index,
+            gap.start_ns,
+            gap.end_ns
+        );
+    }
+    match &report.sequence_jumps {
+        Some(jumps) => {
+            for jump in jumps {
+                println!(
+                    "      sequence jump at message #{} (log time {}): expected {}, got {}",
+                    jump.index, jump.log_time, jump.expected, jump.actual
+                );
+            }
+        }
+        None => println!("      no sequence numbers recorded"),
+    }
+}
+
+/// Human-readable duration for a nanosecond count.
+fn format_ns(ns: Option<u64>) -> String {
+    match ns {
+        None => "-".to_string(),
+        Some(ns) if ns >= 1_000_000_000 => format!("{:.3}s", ns as f64 / 1e9),
+        Some(ns) if ns >= 1_000_000 => format!("{:.3}ms", ns as f64 / 1e6),
+        Some(ns) if ns >= 1_000 => format!("{:.3}us", ns as f64 / 1e3),
+        Some(ns) => format!("{ns}ns"),
+    }
+}
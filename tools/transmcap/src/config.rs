@@ -0,0 +1,325 @@
+//! Per-topic `convert` options read from a TOML file (`--config`, or
+//! `transmcap.toml` in the working directory).
+//!
+//! ```toml
+//! [topics."/imu"]
+//! fields = ["linear_acceleration", "angular_velocity"]
+//! time-columns = "both"
+//!
+//! [topics."/scan"]
+//! list-policy = "flatten-fixed:360"
+//! table-name = "lidar"
+//! ```
+//!
+//! Keys mirror the `convert` flags of the same name and override them for
+//! the topic; topics without a section use the flags.
+//!
+//! A `[conversion]` table holds [`ConversionOptions`] for every topic; the
+//! keys it sets override the matching flags:
+//!
+//! ```toml
+//! [conversion]
+//! separator = "/"
+//! widening = "lossless"
+//! max-depth = 4
+//! ```
+//!
+//! Without `--topic`/`--topics`, every `[[groups]]` entry is converted into
+//! its own output, selecting the topics of the file by glob patterns:
+//!
+//! ```toml
+//! [[groups]]
+//! include = ["/sensors/**"]
+//! exclude = ["**/camera/**"]
+//! output = "dataset/sensors"
+//! format = "parquet"
+//! split-topics = true
+//! ```
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use mcapdecode::arrow::{
+    ArrayPolicy, ConversionOptions, FlattenPolicy, ListPolicy, ListPolicySpec, MapPolicy,
+    TimeColumns,
+};
+use serde::{Deserialize, Deserializer};
+
+use crate::{format::OutputFormat, layout::Layout};
+
+/// Config file loaded when `--config` is not given, if it exists.
+pub const DEFAULT_CONFIG_FILE: &str = "transmcap.toml";
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    #[serde(default)]
+    pub topics: BTreeMap<String, TopicConfig>,
+    #[serde(default)]
+    pub groups: Vec<GroupConfig>,
+    pub conversion: Option<ConversionConfig>,
+}
+
+/// The `[conversion]` table.
+#[derive(Debug, Clone)]
+pub struct ConversionConfig {
+    pub options: ConversionOptions,
+    /// Keys set in the table; the options of the others are the defaults
+    /// and leave the flags unchanged.
+    keys: BTreeSet<String>,
+}
+
+impl ConversionConfig {
+    /// Whether the table sets `key` (e.g. `"max-depth"`).
+    pub fn sets(&self, key: &str) -> bool {
+        self.keys.contains(key)
+    }
+}
+
+impl<'de> Deserialize<'de> for ConversionConfig {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let table = toml::Table::deserialize(deserializer)?;
+        let keys = table.keys().cloned().collect();
+        let options = toml::Value::Table(table)
+            .try_into()
+            .map_err(serde::de::Error::custom)?;
+        Ok(Self { options, keys })
+    }
+}
+
+/// Options of one `[topics."<name>"]` section.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct TopicConfig {
+    pub fields: Option<Vec<String>>,
+    #[serde(default, deserialize_with = "parse_opt")]
+    pub list_policy: Option<ListPolicySpec>,
+    pub list_flatten_size: Option<usize>,
+    #[serde(default, deserialize_with = "parse_opt")]
+    pub array_policy: Option<ArrayPolicy>,
+    #[serde(default, deserialize_with = "parse_opt")]
+    pub map_policy: Option<MapPolicy>,
+    #[serde(default, deserialize_with = "parse_opt")]
+    pub time_columns: Option<TimeColumns>,
+    pub table_name: Option<String>,
+}
+
+impl Config {
+    /// Load `path`, or [`DEFAULT_CONFIG_FILE`] if it exists when `path` is
+    /// `None`.
+    pub fn load(path: Option<&Path>) -> Result<Option<Self>> {
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None => {
+                let path = PathBuf::from(DEFAULT_CONFIG_FILE);
+                if !path.is_file() {
+                    return Ok(None);
+                }
+                path
+            }
+        };
+        let text = std::fs::read_to_string(&path)
+            .with_context(|| format!("failed to read config '{}'", path.display()))?;
+        let config = toml::from_str(&text)
+            .with_context(|| format!("invalid config '{}'", path.display()))?;
+        Ok(Some(config))
+    }
+
+    pub fn topic(&self, topic: &str) -> Option<&TopicConfig> {
+        self.topics.get(topic)
+    }
+
+    /// Config for converting the `topics` selected by `group`: their own
+    /// sections, else the group's options.
+    pub fn for_group(&self, group: &GroupConfig, topics: &[String]) -> Self {
+        let topics = topics
+            .iter()
+            .map(|topic| {
+                let options = self
+                    .topic(topic)
+                    .cloned()
+                    .unwrap_or_else(|| group.topic_config());
+                (topic.clone(), options)
+            })
+            .collect();
+        Self {
+            topics,
+            groups: Vec::new(),
+            conversion: self.conversion.clone(),
+        }
+    }
+}
+
+/// One `[[groups]]` entry: a topic selection converted into its own output.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct GroupConfig {
+    /// Topic patterns to convert (all topics if empty).
+    #[serde(default)]
+    pub include: Vec<String>,
+    /// Topic patterns skipped even if included.
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    pub output: Option<PathBuf>,
+    #[serde(default, deserialize_with = "parse_value_enum")]
+    pub format: Option<OutputFormat>,
+    #[serde(default, deserialize_with = "parse_value_enum")]
+    pub layout: Option<Layout>,
+    pub split_topics: Option<bool>,
+    pub fields: Option<Vec<String>>,
+    #[serde(default, deserialize_with = "parse_opt")]
+    pub list_policy: Option<ListPolicySpec>,
+    pub list_flatten_size: Option<usize>,
+    #[serde(default, deserialize_with = "parse_opt")]
+    pub array_policy: Option<ArrayPolicy>,
+    #[serde(default, deserialize_with = "parse_opt")]
+    pub map_policy: Option<MapPolicy>,
+    #[serde(default, deserialize_with = "parse_opt")]
+    pub time_columns: Option<TimeColumns>,
+}
+
+impl GroupConfig {
+    /// Topics of `topics` matched by an `include` and no `exclude` pattern,
+    /// in their original order.
+    pub fn select(&self, topics: &[String]) -> Vec<String> {
+        topics
+            .iter()
+            .filter(|topic| {
+                (self.include.is_empty() || self.include.iter().any(|p| glob_match(p, topic)))
+                    && !self.exclude.iter().any(|p| glob_match(p, topic))
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Topic options applied to the selected topics without a section of
+    /// their own.
+    pub fn topic_config(&self) -> TopicConfig {
+        TopicConfig {
+            fields: self.fields.clone(),
+            list_policy: self.list_policy,
+            list_flatten_size: self.list_flatten_size,
+            array_policy: self.array_policy,
+            map_policy: self.map_policy,
+            time_columns: self.time_columns,
+            table_name: None,
+        }
+    }
+}
+
+impl TopicConfig {
+    /// Apply the section's flatten options on top of `base`.
+    pub fn flatten_policy(&self, topic: &str, base: &FlattenPolicy) -> Result<FlattenPolicy> {
+        let mut policy = *base;
+        let mut size = self.list_flatten_size;
+        if let Some(spec) = self.list_policy {
+            policy.list = spec.policy;
+            size = spec.flatten_fixed_size.or(size);
+        }
+        if let Some(size) = size {
+            anyhow::ensure!(
+                policy.list == ListPolicy::FlattenFixed,
+                "config for topic '{topic}': list-flatten-size requires list-policy flatten-fixed"
+            );
+            anyhow::ensure!(
+                size > 0,
+                "config for topic '{topic}': list-flatten-size must be at least 1"
+            );
+            policy.list_flatten_fixed_size = size;
+        } else if policy.list == ListPolicy::FlattenFixed && base.list != ListPolicy::FlattenFixed {
+            anyhow::bail!(
+                "config for topic '{topic}': list-policy flatten-fixed requires a size (flatten-fixed:N or list-flatten-size)"
+            );
+        }
+        if let Some(array) = self.array_policy {
+            policy.array = array;
+        }
+        if let Some(map) = self.map_policy {
+            policy.map = map;
+        }
+        Ok(policy)
+    }
+}
+
+/// Match `topic` against a glob `pattern`: `**` matches any characters,
+/// `*` any characters except `/`, and `?` one character except `/`.
+pub fn glob_match(pattern: &str, topic: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let topic: Vec<char> = topic.chars().collect();
+    glob_match_chars(&pattern, &topic)
+}
+
+fn glob_match_chars(pattern: &[char], topic: &[char]) -> bool {
+    match pattern {
+        [] => topic.is_empty(),
+        ['*', '*', rest @ ..] => (0..=topic.len()).any(|i| glob_match_chars(rest, &topic[i..])),
+        ['*', rest @ ..] => {
+            let segment = topic.iter().position(|c| *c == '/').unwrap_or(topic.len());
+            (0..=segment).any(|i| glob_match_chars(rest, &topic[i..]))
+        }
+        ['?', rest @ ..] => {
+            topic.first().is_some_and(|c| *c != '/') && glob_match_chars(rest, &topic[1..])
+        }
+        [c, rest @ ..] => topic.first() == Some(c) && glob_match_chars(rest, &topic[1..]),
+    }
+}
+
+/// Parse a string value like the [`ValueEnum`] flag of the same name.
+fn parse_value_enum<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: ValueEnum,
+{
+    let raw = String::deserialize(deserializer)?;
+    T::from_str(&raw, true)
+        .map(Some)
+        .map_err(serde::de::Error::custom)
+}
+
+/// Parse a string value with the `FromStr` impl used by the matching flag.
+fn parse_opt<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: Display,
+{
+    let raw = String::deserialize(deserializer)?;
+    raw.parse().map(Some).map_err(serde::de::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use mcapdecode::arrow::WideningPolicy;
+
+    use super::*;
+
+    #[test]
+    fn conversion_table_records_the_keys_it_sets() {
+        let config: Config = toml::from_str(
+            r#"
+            [conversion]
+            flatten = { list = "flatten-fixed", list-flatten-fixed-size = 3, array = "flatten", map = "drop" }
+            separator = "/"
+            widening = "lossless"
+            "#,
+        )
+        .unwrap();
+        let conversion = config.conversion.unwrap();
+        assert!(conversion.sets("separator") && !conversion.sets("max-depth"));
+        assert_eq!(conversion.options.separator(), '/');
+        assert_eq!(conversion.options.flatten().list_flatten_fixed_size, 3);
+        assert_eq!(
+            conversion.options.convert_options().widening,
+            WideningPolicy::Lossless
+        );
+
+        let invalid = toml::from_str::<Config>("[conversion]\nseparator = \"_\"\n");
+        assert!(invalid.is_err());
+    }
+}
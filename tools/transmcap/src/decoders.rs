@@ -0,0 +1,189 @@
+//! Decoder options shared by the commands that decode messages.
+
+use clap::{Args, ValueEnum};
+use mcapdecode::{
+    core::MessageDecoder,
+    protobuf::{EnumRepresentation, PresencePolicy, ProtobufDecoder},
+    ros1msg::Ros1MsgDecoder,
+    ros2idl::{
+        ByteArrays, CdrQuirks, InvalidUtf8, Ros2IdlDecoder, UuidRepresentation, XTypesDecoder,
+    },
+    ros2msg::Ros2MsgDecoder,
+};
+
+/// Values of unset protobuf fields (`--proto-presence`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum ProtoPresence {
+    /// Null for unset fields that track presence (optional, message and
+    /// oneof fields), the default value for the others
+    #[default]
+    PresenceAware,
+    /// The default value for every unset field
+    AlwaysDefault,
+}
+
+/// Representation of protobuf enum fields (`--enum-as`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum EnumAs {
+    /// Value names as strings
+    #[default]
+    Name,
+    /// Value numbers as int32
+    Number,
+}
+
+/// Decoding of CDR strings that are not valid UTF-8 (`--invalid-utf8`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum InvalidUtf8Mode {
+    /// Fail the conversion
+    #[default]
+    Error,
+    /// Replace invalid sequences with U+FFFD and count the strings in the
+    /// warning summary
+    Replace,
+    /// Write every CDR string as binary
+    Bytes,
+}
+
+/// Layout of CDR payloads (`--cdr-quirks`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum CdrQuirksMode {
+    /// ROS 2 messages from any RMW: empty structs carry a placeholder byte
+    #[default]
+    Rosidl,
+    /// Plain DDS types: empty structs occupy no bytes
+    Dds,
+    /// Final DDS types in XCDR2: 8-byte values aligned to 4 bytes
+    Xcdr2,
+}
+
+/// Representation of CDR `uint8` / `octet` fixed-size arrays
+/// (`--byte-arrays`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum ByteArraysMode {
+    /// Fixed-size lists of uint8
+    #[default]
+    List,
+    /// Fixed-size binary values
+    Binary,
+}
+
+/// Representation of `unique_identifier_msgs/msg/UUID` fields (`--uuid-as`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum UuidAs {
+    /// Structs with a `uuid` byte array
+    #[default]
+    Struct,
+    /// 16-byte fixed-size binary values
+    Binary,
+    /// Canonical hyphenated strings
+    String,
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct DecoderArgs {
+    /// Root struct of ROS 2 IDL schemas (e.g. pkg::msg::Type), overriding
+    /// the section named after the schema
+    #[arg(long)]
+    pub root_type: Option<String>,
+
+    /// Values of unset protobuf fields
+    #[arg(long, value_enum, value_name = "POLICY", default_value_t)]
+    pub proto_presence: ProtoPresence,
+
+    /// Representation of protobuf enum fields
+    #[arg(long, value_enum, value_name = "MODE", default_value_t)]
+    pub enum_as: EnumAs,
+
+    /// Decoding of ROS 1, ROS 2 and DDS CDR strings that are not valid UTF-8
+    #[arg(long, value_enum, value_name = "MODE", default_value_t)]
+    pub invalid_utf8: InvalidUtf8Mode,
+
+    /// Layout of ROS 2 / DDS CDR payloads
+    #[arg(long, value_enum, value_name = "MODE", default_value_t)]
+    pub cdr_quirks: CdrQuirksMode,
+
+    /// Representation of ROS 1, ROS 2 and DDS fixed-size uint8 / octet arrays
+    #[arg(long, value_enum, value_name = "MODE", default_value_t)]
+    pub byte_arrays: ByteArraysMode,
+
+    /// Representation of ROS 2 UUID fields
+    #[arg(long, value_enum, value_name = "MODE", default_value_t)]
+    pub uuid_as: UuidAs,
+}
+
+impl DecoderArgs {
+    /// Decoders configured by the flags, replacing the default decoders of
+    /// the same encodings; empty when every flag has its default.
+    pub fn decoders(&self) -> Vec<Box<dyn MessageDecoder>> {
+        let mut decoders: Vec<Box<dyn MessageDecoder>> = Vec::new();
+        if self.root_type.is_some()
+            || self.invalid_utf8 != InvalidUtf8Mode::default()
+            || self.cdr_quirks != CdrQuirksMode::default()
+            || self.byte_arrays != ByteArraysMode::default()
+            || self.uuid_as != UuidAs::default()
+        {
+            let invalid_utf8 = match self.invalid_utf8 {
+                InvalidUtf8Mode::Error => InvalidUtf8::Error,
+                InvalidUtf8Mode::Replace => InvalidUtf8::Replace,
+                InvalidUtf8Mode::Bytes => InvalidUtf8::Bytes,
+            };
+            let quirks = match self.cdr_quirks {
+                CdrQuirksMode::Rosidl => CdrQuirks::Rosidl,
+                CdrQuirksMode::Dds => CdrQuirks::Dds,
+                CdrQuirksMode::Xcdr2 => CdrQuirks::Xcdr2,
+            };
+            let byte_arrays = match self.byte_arrays {
+                ByteArraysMode::List => ByteArrays::List,
+                ByteArraysMode::Binary => ByteArrays::Binary,
+            };
+            let uuids = match self.uuid_as {
+                UuidAs::Struct => UuidRepresentation::Struct,
+                UuidAs::Binary => UuidRepresentation::Binary,
+                UuidAs::String => UuidRepresentation::String,
+            };
+            let mut idl = Ros2IdlDecoder::new()
+                .with_invalid_utf8(invalid_utf8)
+                .with_cdr_quirks(quirks)
+                .with_byte_arrays(byte_arrays)
+                .with_uuids(uuids);
+            if let Some(root_type) = &self.root_type {
+                idl = idl.with_root_type(root_type);
+            }
+            decoders.push(Box::new(idl));
+            decoders.push(Box::new(
+                Ros2MsgDecoder::new()
+                    .with_invalid_utf8(invalid_utf8)
+                    .with_cdr_quirks(quirks)
+                    .with_byte_arrays(byte_arrays)
+                    .with_uuids(uuids),
+            ));
+            decoders.push(Box::new(
+                Ros1MsgDecoder::new()
+                    .with_invalid_utf8(invalid_utf8)
+                    .with_byte_arrays(byte_arrays),
+            ));
+            decoders.push(Box::new(
+                XTypesDecoder::new()
+                    .with_invalid_utf8(invalid_utf8)
+                    .with_cdr_quirks(quirks)
+                    .with_byte_arrays(byte_arrays)
+                    .with_uuids(uuids),
+            ));
+        }
+        if self.proto_presence != ProtoPresence::default() || self.enum_as != EnumAs::default() {
+            let presence = match self.proto_presence {
+                ProtoPresence::PresenceAware => PresencePolicy::PresenceAware,
+                ProtoPresence::AlwaysDefault => PresencePolicy::AlwaysDefault,
+            };
+            let enums = match self.enum_as {
+                EnumAs::Name => EnumRepresentation::Name,
+                EnumAs::Number => EnumRepresentation::Number,
+            };
+            decoders.push(Box::new(
+                ProtobufDecoder::new_with_presence_policy(presence).with_enum_representation(enums),
+            ));
+        }
+        decoders
+    }
+}
@@ -7,30 +7,49 @@ pub enum OutputFormat {
     Jsonl,
     Csv,
     Parquet,
+    /// InfluxDB line protocol
+    Influx,
+    /// Database table via an ADBC driver
+    #[cfg(feature = "adbc")]
+    Adbc,
+}
+
+/// Compression applied to text output with `--compress`.
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum OutputCompression {
+    Gzip,
+    Zstd,
+}
+
+/// Representation of 64-bit integers in JSON Lines output (`--json-int64`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum JsonInt64 {
+    /// JSON numbers (values above 2^53 lose precision in JavaScript)
+    #[default]
+    Number,
+    /// JSON strings holding the decimal value
+    String,
 }
 
 impl OutputFormat {
     pub fn default_policy(&self) -> FlattenPolicy {
         match self {
-            OutputFormat::Jsonl => FlattenPolicy {
+            OutputFormat::Jsonl => FlattenPolicy::jsonl(),
+            OutputFormat::Csv => FlattenPolicy::csv(),
+            OutputFormat::Parquet => FlattenPolicy::parquet(),
+            #[cfg(feature = "adbc")]
+            OutputFormat::Adbc => FlattenPolicy {
                 list: ListPolicy::Keep,
                 list_flatten_fixed_size: 1,
                 array: ArrayPolicy::Keep,
-                map: MapPolicy::Keep,
-                struct_: StructPolicy::Keep,
-            },
-            OutputFormat::Csv => FlattenPolicy {
-                list: ListPolicy::Drop,
-                list_flatten_fixed_size: 1,
-                array: ArrayPolicy::Drop,
                 map: MapPolicy::Drop,
                 struct_: StructPolicy::Flatten,
             },
-            OutputFormat::Parquet => FlattenPolicy {
-                list: ListPolicy::Keep,
+            OutputFormat::Influx => FlattenPolicy {
+                list: ListPolicy::Drop,
                 list_flatten_fixed_size: 1,
-                array: ArrayPolicy::Keep,
-                map: MapPolicy::Keep,
+                array: ArrayPolicy::Flatten,
+                map: MapPolicy::Drop,
                 struct_: StructPolicy::Flatten,
             },
         }
@@ -0,0 +1,242 @@
+//! Layouts for writing several topics into a single output table.
+
+use std::{ops::Range, sync::Arc};
+
+use anyhow::{Result, bail};
+use arrow::{
+    array::{
+        Array, ArrayRef, AsArray, Float64Array, Float64Builder, StringArray, StringBuilder,
+        UInt32Builder, new_null_array,
+    },
+    compute::{cast, take},
+    datatypes::{DataType, Field, FieldRef, Float64Type, Schema, SchemaRef},
+    record_batch::RecordBatch,
+    util::display::{ArrayFormatter, FormatOptions},
+};
+use clap::ValueEnum;
+
+/// Timestamp columns shared by all topics in a multi-topic table.
+pub const TIMESTAMP_COLUMNS: [&str; 2] = ["@log_time", "@publish_time"];
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+#[value(rename_all = "kebab-case")]
+pub enum Layout {
+    /// One row per message; columns are prefixed by topic and null for other topics
+    Wide,
+    /// One row per non-null field value: (@log_time, topic, field_path, value_double, value_string)
+    Long,
+}
+
+/// Merges per-topic batches into one table with topic-prefixed columns.
+pub struct WideLayout {
+    schema: SchemaRef,
+    /// Columns of `schema` owned by each topic, in topic order.
+    topic_columns: Vec<Range<usize>>,
+}
+
+impl WideLayout {
+    /// Build the merged schema from the timestamp fields and each topic's
+    /// output schema. Timestamp columns in the topic schemas are skipped.
+    pub fn new(timestamp_fields: &[FieldRef], topics: &[(&str, SchemaRef)]) -> Self {
+        let mut fields: Vec<FieldRef> = timestamp_fields.to_vec();
+        let mut topic_columns = Vec::with_capacity(topics.len());
+
+        for (topic, schema) in topics {
+            let start = fields.len();
+            let prefix = topic.trim_start_matches('/');
+            fields.extend(body_fields(schema).map(|f| {
+                Arc::new(Field::new(
+                    format!("{prefix}.{}", f.name()),
+                    f.data_type().clone(),
+                    true,
+                ))
+            }));
+            topic_columns.push(start..fields.len());
+        }
+
+        Self {
+            schema: Arc::new(Schema::new(fields)),
+            topic_columns,
+        }
+    }
+
+    /// Convert one batch of topic `topic_index` to the merged schema.
+    ///
+    /// `timestamps` must contain the [`TIMESTAMP_COLUMNS`] for the rows of
+    /// `batch`, which holds the post-processed topic columns.
+    pub fn widen(
+        &self,
+        topic_index: usize,
+        timestamps: &RecordBatch,
+        batch: &RecordBatch,
+    ) -> Result<RecordBatch> {
+        let num_rows = batch.num_rows();
+        let mut columns: Vec<ArrayRef> = Vec::with_capacity(self.schema.fields().len());
+
+        for name in TIMESTAMP_COLUMNS {
+            let Some(col) = timestamps.column_by_name(name) else {
+                bail!("batch is missing timestamp column '{name}'");
+            };
+            columns.push(col.clone());
+        }
+
+        let own: Vec<ArrayRef> = batch
+            .schema()
+            .fields()
+            .iter()
+            .zip(batch.columns())
+            .filter(|(f, _)| !TIMESTAMP_COLUMNS.contains(&f.name().as_str()))
+            .map(|(_, col)| col.clone())
+            .collect();
+        let own_range = &self.topic_columns[topic_index];
+        if own.len() != own_range.len() {
+            bail!(
+                "batch has {} columns but the wide layout expects {}",
+                own.len(),
+                own_range.len()
+            );
+        }
+
+        for (idx, range) in self.topic_columns.iter().enumerate() {
+            if idx == topic_index {
+                columns.extend(own.iter().cloned());
+            } else {
+                columns.extend(
+                    self.schema.fields()[range.clone()]
+                        .iter()
+                        .map(|f| new_null_array(f.data_type(), num_rows)),
+                );
+            }
+        }
+
+        Ok(RecordBatch::try_new(self.schema.clone(), columns)?)
+    }
+}
+
+/// Converts topic batches to `(@log_time, topic, field_path, value_double,
+/// value_string)` rows.
+///
+/// Numeric and boolean values go to `value_double`, everything else is
+/// rendered into `value_string`. Null values produce no row.
+pub struct LongLayout {
+    schema: SchemaRef,
+}
+
+enum LongColumn {
+    Double(Float64Array),
+    String(StringArray),
+}
+
+impl LongColumn {
+    fn from_array(array: &ArrayRef) -> Result<Self> {
+        let dt = array.data_type();
+        if dt.is_numeric() || *dt == DataType::Boolean {
+            let values = cast(array, &DataType::Float64)?;
+            return Ok(Self::Double(values.as_primitive::<Float64Type>().clone()));
+        }
+        if matches!(
+            dt,
+            DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View
+        ) {
+            let values = cast(array, &DataType::Utf8)?;
+            return Ok(Self::String(values.as_string::<i32>().clone()));
+        }
+        let formatter = ArrayFormatter::try_new(array.as_ref(), &FormatOptions::default())?;
+        Ok(Self::String(
+            (0..array.len())
+                .map(|i| array.is_valid(i).then(|| formatter.value(i).to_string()))
+                .collect(),
+        ))
+    }
+
+    fn is_null(&self, idx: usize) -> bool {
+        match self {
+            Self::Double(values) => values.is_null(idx),
+            Self::String(values) => values.is_null(idx),
+        }
+    }
+}
+
+impl LongLayout {
+    /// `log_time_field` is the `@log_time` field of the reader batches.
+    pub fn new(log_time_field: FieldRef) -> Self {
+        Self {
+            schema: Arc::new(Schema::new(vec![
+                log_time_field,
+                Arc::new(Field::new("topic", DataType::Utf8, false)),
+                Arc::new(Field::new("field_path", DataType::Utf8, false)),
+                Arc::new(Field::new("value_double", DataType::Float64, true)),
+                Arc::new(Field::new("value_string", DataType::Utf8, true)),
+            ])),
+        }
+    }
+
+    /// Convert one post-processed `batch` of `topic` to long rows.
+    ///
+    /// `timestamps` must contain the `@log_time` column for the rows of `batch`.
+    pub fn lengthen(
+        &self,
+        topic: &str,
+        timestamps: &RecordBatch,
+        batch: &RecordBatch,
+    ) -> Result<RecordBatch> {
+        let Some(log_time) = timestamps.column_by_name(TIMESTAMP_COLUMNS[0]) else {
+            bail!(
+                "batch is missing timestamp column '{}'",
+                TIMESTAMP_COLUMNS[0]
+            );
+        };
+        let schema = batch.schema();
+        let columns = schema
+            .fields()
+            .iter()
+            .zip(batch.columns())
+            .filter(|(f, _)| !TIMESTAMP_COLUMNS.contains(&f.name().as_str()))
+            .map(|(f, col)| Ok((f.name().as_str(), LongColumn::from_array(col)?)))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut rows = UInt32Builder::new();
+        let mut field_path = StringBuilder::new();
+        let mut value_double = Float64Builder::new();
+        let mut value_string = StringBuilder::new();
+        for row in 0..batch.num_rows() {
+            for (name, column) in &columns {
+                if column.is_null(row) {
+                    continue;
+                }
+                rows.append_value(row as u32);
+                field_path.append_value(name);
+                match column {
+                    LongColumn::Double(values) => {
+                        value_double.append_value(values.value(row));
+                        value_string.append_null();
+                    }
+                    LongColumn::String(values) => {
+                        value_double.append_null();
+                        value_string.append_value(values.value(row));
+                    }
+                }
+            }
+        }
+
+        let rows = rows.finish();
+        let topic_column: StringArray = std::iter::repeat_n(Some(topic), rows.len()).collect();
+        Ok(RecordBatch::try_new(
+            self.schema.clone(),
+            vec![
+                take(log_time.as_ref(), &rows, None)?,
+                Arc::new(topic_column),
+                Arc::new(field_path.finish()),
+                Arc::new(value_double.finish()),
+                Arc::new(value_string.finish()),
+            ],
+        )?)
+    }
+}
+
+fn body_fields(schema: &Schema) -> impl Iterator<Item = &FieldRef> {
+    schema
+        .fields()
+        .iter()
+        .filter(|f| !TIMESTAMP_COLUMNS.contains(&f.name().as_str()))
+}
@@ -0,0 +1,91 @@
+//! `--limit-rows` / `--limit-bytes` handling for `convert`.
+
+use std::fmt;
+
+use arrow::record_batch::RecordBatch;
+use mcapdecode::McapReaderError;
+
+/// Output budget of one topic.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OutputLimit {
+    rows: Option<u64>,
+    bytes: Option<u64>,
+    written_rows: u64,
+    written_bytes: u64,
+}
+
+impl OutputLimit {
+    pub fn new(rows: Option<u64>, bytes: Option<u64>) -> Self {
+        Self {
+            rows,
+            bytes,
+            ..Self::default()
+        }
+    }
+
+    /// The same limit with nothing written yet.
+    pub fn reset(&self) -> Self {
+        Self::new(self.rows, self.bytes)
+    }
+
+    /// Truncate `batch` to the remaining row budget and count it as written.
+    ///
+    /// Returns the batch to write and whether the limit is now reached.
+    /// The byte limit is checked after each batch, so the last batch may
+    /// go past it.
+    pub fn take(&mut self, batch: RecordBatch) -> (RecordBatch, bool) {
+        let batch = match self.rows {
+            Some(rows) => {
+                let remaining = rows.saturating_sub(self.written_rows);
+                if (batch.num_rows() as u64) > remaining {
+                    batch.slice(0, remaining as usize)
+                } else {
+                    batch
+                }
+            }
+            None => batch,
+        };
+        self.written_rows += batch.num_rows() as u64;
+        self.written_bytes += batch.get_array_memory_size() as u64;
+        let reached = self.rows.is_some_and(|rows| self.written_rows >= rows)
+            || self.bytes.is_some_and(|bytes| self.written_bytes >= bytes);
+        (batch, reached)
+    }
+}
+
+/// Callback error that stops reading once an [`OutputLimit`] is reached.
+#[derive(Debug)]
+pub struct LimitReached;
+
+impl fmt::Display for LimitReached {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("output limit reached")
+    }
+}
+
+impl std::error::Error for LimitReached {}
+
+/// Treat a read stopped by [`LimitReached`] as finished.
+pub fn stop_at_limit(result: Result<(), McapReaderError>) -> Result<(), McapReaderError> {
+    match result {
+        Err(McapReaderError::Callback(err)) if err.is::<LimitReached>() => Ok(()),
+        other => other,
+    }
+}
+
+/// Parse a byte size such as `500000`, `64K`, `10MiB` or `1g` (binary multiples).
+pub fn parse_size(raw: &str) -> Result<u64, String> {
+    let invalid = || format!("invalid size '{raw}' (expected e.g. 500000, 64K, 10M, 1G)");
+    let lower = raw.trim().to_ascii_lowercase();
+    let unit = lower.trim_start_matches(|c: char| c.is_ascii_digit());
+    let digits = &lower[..lower.len() - unit.len()];
+    let shift = match unit.trim_end_matches("ib").trim_end_matches('b') {
+        "" => 0,
+        "k" => 10,
+        "m" => 20,
+        "g" => 30,
+        _ => return Err(invalid()),
+    };
+    let value: u64 = digits.parse().map_err(|_| invalid())?;
+    value.checked_mul(1 << shift).ok_or_else(invalid)
+}
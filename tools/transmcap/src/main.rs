@@ -1,10 +1,25 @@
+mod checkpoint;
 mod commands;
+mod config;
+mod decoders;
 mod format;
+mod layout;
+mod limit;
+mod provenance;
+mod queue;
+mod stats;
+mod table_names;
+mod template;
+mod transforms;
 mod writer;
 
 use anyhow::Result;
 use clap::{Parser, Subcommand};
-use commands::{convert::ConvertArgs, schema::SchemaArgs};
+use commands::{
+    convert::ConvertArgs, doctor::DoctorArgs, export_schemas::ExportSchemasArgs, info::InfoArgs,
+    repack::RepackArgs, schema::SchemaArgs, verify::VerifyArgs,
+};
+use mcapdecode::core::SchemaSyntaxError;
 
 #[derive(Parser)]
 #[command(name = "transmcap", about = "Convert mcap files to various formats")]
@@ -15,17 +30,47 @@ struct Cli {
 
 #[derive(Subcommand)]
 enum Commands {
-    /// Convert mcap to jsonl/csv/parquet
-    Convert(ConvertArgs),
+    /// Convert mcap to jsonl/csv/parquet/influx line protocol
+    Convert(Box<ConvertArgs>),
     /// Print Arrow schema for a topic
     Schema(SchemaArgs),
+    /// List topics and chunk compression codecs of an mcap file
+    Info(InfoArgs),
+    /// Report which conversion stage fails for a topic
+    Doctor(DoctorArgs),
+    /// Check topics for message gaps and sequence-number jumps
+    Verify(VerifyArgs),
+    /// Rewrite an mcap file with different chunking and compression
+    Repack(RepackArgs),
+    /// Export the FileDescriptorSets of protobuf topics as .bin and .proto files
+    ExportSchemas(ExportSchemasArgs),
 }
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
-    match cli.command {
+    let result = match cli.command {
         Commands::Convert(args) => args.run(),
         Commands::Schema(args) => args.run(),
+        Commands::Info(args) => args.run(),
+        Commands::Doctor(args) => args.run(),
+        Commands::Verify(args) => args.run(),
+        Commands::Repack(args) => args.run(),
+        Commands::ExportSchemas(args) => args.run(),
+    };
+    if let Err(err) = &result {
+        print_schema_diagnostic(err);
+    }
+    result
+}
+
+/// Point at the offending schema text when `err` was caused by a schema
+/// syntax error.
+fn print_schema_diagnostic(err: &anyhow::Error) {
+    if let Some(syntax) = err
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<SchemaSyntaxError>())
+    {
+        eprintln!("{}\n", syntax.render_diagnostic());
     }
 }
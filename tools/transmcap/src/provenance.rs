@@ -0,0 +1,122 @@
+//! `--provenance` metadata for `convert`, tracing written rows back to their
+//! MCAP file.
+
+use std::{
+    collections::HashMap,
+    fs,
+    io::{BufReader, Read},
+    path::Path,
+    sync::Arc,
+};
+
+use anyhow::Result;
+use arrow::{datatypes::Schema, record_batch::RecordBatch};
+use mcapdecode::McapReader;
+
+use crate::writer::RecordBatchWriter;
+
+/// Schema metadata key of the MCAP file path (canonicalized if possible).
+pub const SOURCE_PATH_KEY: &str = "transmcap.source_path";
+/// Schema metadata key of the CRC-32 of the whole MCAP file, as 8 hex digits.
+pub const SOURCE_CRC32_KEY: &str = "transmcap.source_crc32";
+/// Schema metadata key of the profile of the MCAP header (e.g. `ros2`).
+pub const MCAP_PROFILE_KEY: &str = "transmcap.mcap_profile";
+/// Schema metadata key of the topic the batch was read from; unset for
+/// batches merging several topics.
+pub const TOPIC_KEY: &str = "transmcap.topic";
+/// Schema metadata key of the JSON array of all converted topics.
+pub const TOPICS_KEY: &str = "transmcap.topics";
+/// Schema metadata key of the converter name and version.
+pub const CONVERTER_KEY: &str = "transmcap.converter";
+/// Schema metadata key of the JSON array of the command-line arguments.
+pub const ARGUMENTS_KEY: &str = "transmcap.arguments";
+
+/// Provenance of one conversion, shared by all its batches.
+#[derive(Debug, Clone)]
+pub struct Provenance {
+    metadata: HashMap<String, String>,
+}
+
+impl Provenance {
+    /// Describe converting `topics` of `input`; reads the whole file once to
+    /// checksum it.
+    pub fn new(reader: &McapReader, input: &Path, topics: &[String]) -> Result<Self> {
+        let path = fs::canonicalize(input).unwrap_or_else(|_| input.to_path_buf());
+        let arguments: Vec<String> = std::env::args().skip(1).collect();
+        let metadata = HashMap::from([
+            (SOURCE_PATH_KEY.to_string(), path.display().to_string()),
+            (
+                SOURCE_CRC32_KEY.to_string(),
+                format!("{:08x}", file_crc32(input)?),
+            ),
+            (MCAP_PROFILE_KEY.to_string(), reader.profile(input)?),
+            (TOPICS_KEY.to_string(), serde_json::to_string(topics)?),
+            (
+                CONVERTER_KEY.to_string(),
+                concat!("transmcap ", env!("CARGO_PKG_VERSION")).to_string(),
+            ),
+            (
+                ARGUMENTS_KEY.to_string(),
+                serde_json::to_string(&arguments)?,
+            ),
+        ]);
+        Ok(Self { metadata })
+    }
+}
+
+fn file_crc32(path: &Path) -> Result<u32> {
+    let mut file = BufReader::new(fs::File::open(path)?);
+    let mut hasher = crc32fast::Hasher::new();
+    let mut buf = vec![0; 1 << 20];
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            return Ok(hasher.finalize());
+        }
+        hasher.update(&buf[..n]);
+    }
+}
+
+/// Adds the [`Provenance`] to the schema metadata of every batch before
+/// passing it on.
+pub struct ProvenanceWriter {
+    inner: Box<dyn RecordBatchWriter>,
+    provenance: Provenance,
+    topic: Option<String>,
+}
+
+impl ProvenanceWriter {
+    pub fn new(inner: Box<dyn RecordBatchWriter>, provenance: Provenance) -> Self {
+        Self {
+            inner,
+            provenance,
+            topic: None,
+        }
+    }
+}
+
+impl RecordBatchWriter for ProvenanceWriter {
+    fn begin_topic(&mut self, topic: &str) -> Result<()> {
+        self.topic = Some(topic.to_string());
+        self.inner.begin_topic(topic)
+    }
+
+    fn write_batch(&mut self, batch: RecordBatch) -> Result<()> {
+        let schema = batch.schema();
+        let mut metadata = schema.metadata().clone();
+        metadata.extend(self.provenance.metadata.clone());
+        if let Some(topic) = &self.topic {
+            metadata.insert(TOPIC_KEY.to_string(), topic.clone());
+        }
+        let schema = Schema::new_with_metadata(schema.fields().clone(), metadata);
+        self.inner.write_batch(batch.with_schema(Arc::new(schema))?)
+    }
+
+    fn messages_written(&mut self, messages: &RecordBatch) -> Result<()> {
+        self.inner.messages_written(messages)
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.inner.finish()
+    }
+}
@@ -0,0 +1,153 @@
+//! Bounded queue between decoding and writing for `convert`
+//! (`--queue-capacity`).
+
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{self, TryRecvError, TrySendError},
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use arrow::record_batch::RecordBatch;
+use mcapdecode::McapReaderError;
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Counters of a [`BatchQueue`] telling whether decoding or writing is the
+/// bottleneck.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct QueueStats {
+    pub batches: u64,
+    /// Most batches decoded but not yet written at once.
+    pub max_depth: usize,
+    /// Time the decoder waited for the writer to free a slot.
+    pub decode_blocked: Duration,
+    /// Time the writer waited for the decoder to produce a batch.
+    pub write_idle: Duration,
+}
+
+/// Decodes on a separate thread while the calling thread writes, holding at
+/// most `capacity` batches in between, so that a slow sink stalls decoding
+/// instead of buffering without bound.
+pub struct BatchQueue {
+    capacity: usize,
+    stats: QueueStats,
+}
+
+impl BatchQueue {
+    /// With `capacity` 0, batches are written on the decoding thread.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            stats: QueueStats::default(),
+        }
+    }
+
+    /// Run `decode`, passing every batch it emits to `write` together with
+    /// the number of batches still queued behind it.
+    ///
+    /// An error of `write` stops decoding and is returned as
+    /// [`McapReaderError::Callback`], as if `write` were the reader callback.
+    pub fn run<D, W>(&mut self, decode: D, mut write: W) -> Result<(), McapReaderError>
+    where
+        D: FnOnce(
+                &mut dyn FnMut(RecordBatch) -> Result<(), BoxError>,
+            ) -> Result<(), McapReaderError>
+            + Send,
+        W: FnMut(RecordBatch, usize) -> Result<(), BoxError>,
+    {
+        if self.capacity == 0 {
+            return decode(&mut |batch| {
+                self.stats.batches += 1;
+                write(batch, 0)
+            });
+        }
+
+        let (sender, receiver) = mpsc::sync_channel::<RecordBatch>(self.capacity);
+        let depth = AtomicUsize::new(0);
+        thread::scope(|scope| {
+            let producer = scope.spawn(|| {
+                let mut max_depth = 0;
+                let mut blocked = Duration::ZERO;
+                let result = decode(&mut |batch| {
+                    // Counted before sending so that the writer never sees it
+                    // dequeued before it was queued.
+                    max_depth = max_depth.max(depth.fetch_add(1, Ordering::Relaxed) + 1);
+                    match sender.try_send(batch) {
+                        Ok(()) => Ok(()),
+                        Err(TrySendError::Full(batch)) => {
+                            let start = Instant::now();
+                            let sent = sender.send(batch);
+                            blocked += start.elapsed();
+                            sent.map_err(|_| QueueClosed.into())
+                        }
+                        Err(TrySendError::Disconnected(_)) => Err(QueueClosed.into()),
+                    }
+                });
+                drop(sender);
+                (result, max_depth, blocked)
+            });
+
+            let mut write_result = Ok(());
+            loop {
+                let batch = match receiver.try_recv() {
+                    Ok(batch) => batch,
+                    Err(TryRecvError::Empty) => {
+                        let start = Instant::now();
+                        let received = receiver.recv();
+                        self.stats.write_idle += start.elapsed();
+                        match received {
+                            Ok(batch) => batch,
+                            Err(_) => break,
+                        }
+                    }
+                    Err(TryRecvError::Disconnected) => break,
+                };
+                let queued = depth.fetch_sub(1, Ordering::Relaxed) - 1;
+                self.stats.batches += 1;
+                if let Err(err) = write(batch, queued) {
+                    write_result = Err(err);
+                    break;
+                }
+            }
+            // Unblock the decoder if writing stopped early.
+            drop(receiver);
+
+            let (decode_result, max_depth, blocked) = producer
+                .join()
+                .unwrap_or_else(|panic| std::panic::resume_unwind(panic));
+            self.stats.max_depth = self.stats.max_depth.max(max_depth);
+            self.stats.decode_blocked += blocked;
+            match write_result {
+                Err(err) => Err(McapReaderError::Callback(err)),
+                Ok(()) => decode_result,
+            }
+        })
+    }
+}
+
+impl fmt::Display for BatchQueue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let stats = &self.stats;
+        write!(
+            f,
+            "output queue: {} batches, max depth {}/{}; decoding waited {:.1?} for writing, writing waited {:.1?} for decoding",
+            stats.batches, stats.max_depth, self.capacity, stats.decode_blocked, stats.write_idle
+        )
+    }
+}
+
+/// Callback error of the decoder after the writer stopped.
+#[derive(Debug)]
+struct QueueClosed;
+
+impl fmt::Display for QueueClosed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("output queue closed")
+    }
+}
+
+impl std::error::Error for QueueClosed {}
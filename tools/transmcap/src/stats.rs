@@ -0,0 +1,332 @@
+//! Per-field statistics collected from a sample of decoded messages, and
+//! the column statistics report of `convert --stats`.
+
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt, fs,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use anyhow::Result;
+use mcapdecode::{
+    McapReader, McapReaderError, TopicColumnStats,
+    arrow::StatValue,
+    core::{DataTypeDef, FieldDefs, Value},
+};
+
+/// Callback error that stops reading once enough messages are sampled.
+#[derive(Debug)]
+pub(crate) struct SampleLimitReached;
+
+impl fmt::Display for SampleLimitReached {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("sample limit reached")
+    }
+}
+
+impl Error for SampleLimitReached {}
+
+/// Suffix appended to a field path to address the elements of a list or array.
+const ELEMENT_SUFFIX: &str = "[]";
+
+/// Observed min / max / 95th percentile of a length distribution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LengthSummary {
+    pub min: usize,
+    pub max: usize,
+    pub p95: usize,
+}
+
+impl LengthSummary {
+    fn from_samples(samples: &mut [usize]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        samples.sort_unstable();
+        // Nearest-rank percentile.
+        let rank = (samples.len() * 95).div_ceil(100);
+        Some(Self {
+            min: samples[0],
+            max: samples[samples.len() - 1],
+            p95: samples[rank.saturating_sub(1)],
+        })
+    }
+}
+
+impl fmt::Display for LengthSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}/{}", self.min, self.max, self.p95)
+    }
+}
+
+/// Statistics for one field path.
+#[derive(Debug, Clone)]
+pub struct FieldStats {
+    /// Dot-separated field path; list elements are addressed with `[]`.
+    pub path: String,
+    /// Number of values observed (including nulls).
+    pub observed: u64,
+    /// Number of null values observed.
+    pub nulls: u64,
+    /// Lengths of list values.
+    pub list_len: Option<LengthSummary>,
+    /// Character counts of string values.
+    pub str_len: Option<LengthSummary>,
+}
+
+impl FieldStats {
+    pub fn null_rate(&self) -> f64 {
+        if self.observed == 0 {
+            0.0
+        } else {
+            self.nulls as f64 / self.observed as f64
+        }
+    }
+}
+
+/// Statistics for all fields of a topic.
+#[derive(Debug, Clone)]
+pub struct StatsReport {
+    pub sampled_messages: u64,
+    pub fields: Vec<FieldStats>,
+}
+
+impl StatsReport {
+    /// Maximum observed list length over the fields selected by `include`.
+    pub fn max_list_len(&self, include: impl Fn(&str) -> bool) -> Option<usize> {
+        self.fields
+            .iter()
+            .filter(|f| include(&f.path))
+            .filter_map(|f| f.list_len.map(|l| l.max))
+            .max()
+    }
+}
+
+impl fmt::Display for StatsReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const HEADERS: [&str; 4] = [
+            "field",
+            "null_rate",
+            "list_len (min/max/p95)",
+            "str_len (min/max/p95)",
+        ];
+
+        let rows: Vec<[String; 4]> = self
+            .fields
+            .iter()
+            .map(|s| {
+                [
+                    s.path.clone(),
+                    format!("{:.2}%", s.null_rate() * 100.0),
+                    s.list_len
+                        .map_or_else(|| "-".to_string(), |l| l.to_string()),
+                    s.str_len.map_or_else(|| "-".to_string(), |l| l.to_string()),
+                ]
+            })
+            .collect();
+
+        let mut widths = HEADERS.map(str::len);
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.len());
+            }
+        }
+
+        writeln!(f, "sampled messages: {}", self.sampled_messages)?;
+        let headers = HEADERS.map(str::to_string);
+        for row in std::iter::once(&headers).chain(&rows) {
+            let line = row
+                .iter()
+                .zip(widths)
+                .map(|(cell, width)| format!("{cell:<width$}"))
+                .collect::<Vec<_>>()
+                .join("  ");
+            writeln!(f, "{}", line.trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+/// Sample up to `limit` messages of `topic` and collect per-field statistics.
+pub fn sample_topic_stats(
+    reader: &McapReader,
+    input: &Path,
+    topic: &str,
+    limit: u64,
+) -> Result<StatsReport> {
+    let field_defs = reader.topic_field_defs(input, topic)?;
+    let mut collector = StatsCollector::new(&field_defs);
+
+    if limit > 0 {
+        let result = reader.for_each_decoded_message(input, topic, |message| {
+            collector.observe_message(&field_defs, &message.value);
+            if collector.sampled_messages >= limit {
+                return Err(SampleLimitReached.into());
+            }
+            Ok(())
+        });
+        match result {
+            Ok(()) => {}
+            Err(McapReaderError::Callback(e)) if e.is::<SampleLimitReached>() => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    Ok(collector.finish())
+}
+
+struct Accumulator {
+    path: String,
+    observed: u64,
+    nulls: u64,
+    list_lens: Vec<usize>,
+    str_lens: Vec<usize>,
+}
+
+struct StatsCollector {
+    sampled_messages: u64,
+    fields: Vec<Accumulator>,
+    index: HashMap<String, usize>,
+}
+
+impl StatsCollector {
+    fn new(field_defs: &FieldDefs) -> Self {
+        let mut collector = Self {
+            sampled_messages: 0,
+            fields: Vec::new(),
+            index: HashMap::new(),
+        };
+        for field in field_defs.iter() {
+            collector.register(field.name.clone(), &field.element.data_type);
+        }
+        collector
+    }
+
+    fn register(&mut self, path: String, data_type: &DataTypeDef) {
+        self.index.insert(path.clone(), self.fields.len());
+        self.fields.push(Accumulator {
+            path: path.clone(),
+            observed: 0,
+            nulls: 0,
+            list_lens: Vec::new(),
+            str_lens: Vec::new(),
+        });
+
+        match data_type {
+            DataTypeDef::Struct(children) => {
+                for child in children.iter() {
+                    self.register(format!("{path}.{}", child.name), &child.element.data_type);
+                }
+            }
+            DataTypeDef::List(elem) | DataTypeDef::Array(elem, _) => {
+                self.register(format!("{path}{ELEMENT_SUFFIX}"), &elem.data_type);
+            }
+            _ => {}
+        }
+    }
+
+    fn observe_message(&mut self, field_defs: &FieldDefs, value: &Value) {
+        self.sampled_messages += 1;
+        let Value::Struct(values) = value else {
+            return;
+        };
+        for (field, value) in field_defs.iter().zip(values) {
+            self.observe(&field.name, &field.element.data_type, value);
+        }
+    }
+
+    fn observe(&mut self, path: &str, data_type: &DataTypeDef, value: &Value) {
+        let Some(&idx) = self.index.get(path) else {
+            return;
+        };
+        let acc = &mut self.fields[idx];
+        acc.observed += 1;
+
+        match (data_type, value) {
+            (_, Value::Null) => acc.nulls += 1,
+            (_, Value::String(s)) => acc.str_lens.push(s.chars().count()),
+            (DataTypeDef::Struct(children), Value::Struct(values)) => {
+                for (child, value) in children.iter().zip(values) {
+                    self.observe(
+                        &format!("{path}.{}", child.name),
+                        &child.element.data_type,
+                        value,
+                    );
+                }
+            }
+            (DataTypeDef::List(elem), Value::List(items))
+            | (DataTypeDef::Array(elem, _), Value::Array(items)) => {
+                if matches!(data_type, DataTypeDef::List(_)) {
+                    acc.list_lens.push(items.len());
+                }
+                let elem_path = format!("{path}{ELEMENT_SUFFIX}");
+                for item in items {
+                    self.observe(&elem_path, &elem.data_type, item);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn finish(self) -> StatsReport {
+        StatsReport {
+            sampled_messages: self.sampled_messages,
+            fields: self
+                .fields
+                .into_iter()
+                .map(|mut acc| FieldStats {
+                    list_len: LengthSummary::from_samples(&mut acc.list_lens),
+                    str_len: LengthSummary::from_samples(&mut acc.str_lens),
+                    path: acc.path,
+                    observed: acc.observed,
+                    nulls: acc.nulls,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Write the column statistics of the topics converted from `input` to
+/// `path` as JSON.
+pub fn write_column_stats(path: &Path, input: &Path, stats: &TopicColumnStats) -> Result<()> {
+    let stat_value = |value: &Option<StatValue>| match value {
+        None => serde_json::Value::Null,
+        Some(StatValue::Bool(v)) => serde_json::json!(v),
+        Some(StatValue::Int(v)) => serde_json::json!(v),
+        Some(StatValue::UInt(v)) => serde_json::json!(v),
+        Some(StatValue::Float(v)) => serde_json::json!(v),
+        Some(StatValue::String(v)) => serde_json::json!(v),
+    };
+    let topics: Vec<_> = stats
+        .topics()
+        .into_iter()
+        .map(|(topic, columns)| {
+            let columns: Vec<_> = columns
+                .iter()
+                .map(|column| {
+                    serde_json::json!({
+                        "column": column.column,
+                        "data_type": column.data_type,
+                        "rows": column.rows,
+                        "null_count": column.null_count,
+                        "min": stat_value(&column.min),
+                        "max": stat_value(&column.max),
+                        "distinct_estimate": column.distinct_estimate,
+                    })
+                })
+                .collect();
+            serde_json::json!({ "topic": topic, "columns": columns })
+        })
+        .collect();
+    let report = serde_json::json!({
+        "input": input.display().to_string(),
+        "topics": topics,
+    });
+    let mut file = BufWriter::new(fs::File::create(path)?);
+    serde_json::to_writer_pretty(&mut file, &report)?;
+    writeln!(file)?;
+    file.flush()?;
+    Ok(())
+}
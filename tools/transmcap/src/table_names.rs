@@ -0,0 +1,118 @@
+//! Output table names of topics (`--table-name`), shared by the writers
+//! that give each topic its own table or file.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    str::FromStr,
+};
+
+use anyhow::{Result, bail};
+
+/// One `TOPIC=NAME` entry of `--table-name`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableNameMapping {
+    pub topic: String,
+    pub name: String,
+}
+
+impl FromStr for TableNameMapping {
+    type Err = String;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        let Some((topic, name)) = raw.rsplit_once('=') else {
+            return Err(format!(
+                "invalid table name mapping '{raw}' (expected: TOPIC=NAME)"
+            ));
+        };
+        if topic.is_empty() {
+            return Err(format!("table name mapping '{raw}' has no topic"));
+        }
+        validate_name(name).map_err(|e| format!("table name mapping '{raw}': {e}"))?;
+        Ok(Self {
+            topic: topic.to_string(),
+            name: name.to_string(),
+        })
+    }
+}
+
+/// Table names of topics: explicitly mapped names, else the topic run
+/// through [`sanitize_table_name`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TableNames {
+    explicit: BTreeMap<String, String>,
+}
+
+impl TableNames {
+    /// Map `topic` to `name`, replacing an earlier mapping of the topic.
+    pub fn insert(&mut self, topic: &str, name: &str) -> Result<()> {
+        if let Err(e) = validate_name(name) {
+            bail!("table name of {topic}: {e}");
+        }
+        self.explicit.insert(topic.to_string(), name.to_string());
+        Ok(())
+    }
+
+    /// The explicitly mapped name of `topic`, if any.
+    pub fn explicit(&self, topic: &str) -> Option<&str> {
+        self.explicit.get(topic).map(String::as_str)
+    }
+
+    /// Table name of `topic`.
+    pub fn name(&self, topic: &str) -> String {
+        self.explicit(topic)
+            .map_or_else(|| sanitize_table_name(topic), str::to_string)
+    }
+
+    /// Fail if two of `topics` get the same `name` (a table name, or the
+    /// file name derived from it), before anything is written.
+    pub fn check_unique<'a>(
+        &self,
+        topics: impl IntoIterator<Item = &'a String>,
+        name: impl Fn(&str) -> String,
+    ) -> Result<()> {
+        let mut seen: HashMap<String, &str> = HashMap::new();
+        for topic in topics {
+            let name = name(topic);
+            if let Some(other) = seen.insert(name.clone(), topic)
+                && other != topic
+            {
+                bail!(
+                    "Topics {other} and {topic} would both be written to {name}; map one of them to another name with --table-name"
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromIterator<TableNameMapping> for TableNames {
+    fn from_iter<I: IntoIterator<Item = TableNameMapping>>(iter: I) -> Self {
+        Self {
+            explicit: iter
+                .into_iter()
+                .map(|mapping| (mapping.topic, mapping.name))
+                .collect(),
+        }
+    }
+}
+
+/// Default table name of `topic`: the topic without its leading `/`, with
+/// every character other than ASCII letters, digits and `_` replaced by `_`
+/// (`/imu/data` → `imu_data`, `/front-camera` → `front_camera`).
+pub fn sanitize_table_name(topic: &str) -> String {
+    topic
+        .trim_start_matches('/')
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+fn validate_name(name: &str) -> Result<(), String> {
+    if name.is_empty() {
+        return Err("table name is empty".to_string());
+    }
+    if name.contains('/') || matches!(name, "." | "..") {
+        return Err(format!("table name '{name}' is not a valid file name"));
+    }
+    Ok(())
+}
@@ -0,0 +1,107 @@
+//! `--output-template` handling for `convert --split-topics`.
+
+use std::str::FromStr;
+
+#[cfg(doc)]
+use crate::table_names::TableNames;
+
+/// Placeholders accepted by [`OutputTemplate`].
+const PLACEHOLDERS: [&str; 4] = ["topic", "topic_snake", "window", "ext"];
+
+/// File name pattern of per-topic outputs, relative to the output directory.
+///
+/// - `{topic}`: the table name of the topic (see [`TableNames`]), by default
+///   the topic without its leading `/` and with other characters than
+///   letters, digits and `_` replaced by `_` (`/imu/data` → `imu_data`)
+/// - `{topic_snake}`: the topic in snake case (`/camera/imageRaw` →
+///   `camera_image_raw`)
+/// - `{window}`: the start of the `--split-by` time window in UTC
+///   (`2024-05-01T12-00`)
+/// - `{ext}`: the extension of the output format (`parquet`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputTemplate(String);
+
+impl Default for OutputTemplate {
+    fn default() -> Self {
+        Self("{topic}.{ext}".to_string())
+    }
+}
+
+impl FromStr for OutputTemplate {
+    type Err = String;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        let mut has_topic = false;
+        let mut rest = raw;
+        while let Some(start) = rest.find('{') {
+            let Some(len) = rest[start..].find('}') else {
+                return Err(format!("unclosed '{{' in output template '{raw}'"));
+            };
+            let name = &rest[start + 1..start + len];
+            if !PLACEHOLDERS.contains(&name) {
+                return Err(format!(
+                    "unknown placeholder '{{{name}}}' in output template '{raw}' (expected: {})",
+                    PLACEHOLDERS.map(|p| format!("{{{p}}}")).join(", ")
+                ));
+            }
+            has_topic |= name.starts_with("topic");
+            rest = &rest[start + len + 1..];
+        }
+        if !has_topic {
+            return Err(format!(
+                "output template '{raw}' must contain {{topic}} or {{topic_snake}}"
+            ));
+        }
+        if raw.starts_with('/') || raw.split('/').any(|part| part == "..") {
+            return Err(format!(
+                "output template '{raw}' must stay inside the output directory"
+            ));
+        }
+        Ok(Self(raw.to_string()))
+    }
+}
+
+impl OutputTemplate {
+    /// Default file names of time-windowed outputs: `{topic}_{window}.{ext}`.
+    pub fn windowed() -> Self {
+        Self("{topic}_{window}.{ext}".to_string())
+    }
+
+    /// Whether the file names include the time window.
+    pub fn has_window(&self) -> bool {
+        self.0.contains("{window}")
+    }
+
+    /// File name of the output of `topic`, whose table name is `table` (for
+    /// the time window labelled `window`), with extension `ext`.
+    pub fn render(&self, topic: &str, table: &str, window: Option<&str>, ext: &str) -> String {
+        self.0
+            .replace("{topic}", table)
+            .replace("{topic_snake}", &snake_case(topic))
+            .replace("{window}", window.unwrap_or_default())
+            .replace("{ext}", ext)
+    }
+}
+
+/// Lowercase `topic` with word boundaries (separators, camel case humps)
+/// turned into single `_`.
+fn snake_case(topic: &str) -> String {
+    let mut snake = String::with_capacity(topic.len() + 4);
+    let mut prev_lower = false;
+    for c in topic.chars() {
+        if c.is_alphanumeric() {
+            if c.is_uppercase() && prev_lower {
+                snake.push('_');
+            }
+            prev_lower = c.is_lowercase() || c.is_numeric();
+            snake.extend(c.to_lowercase());
+        } else {
+            if !snake.is_empty() && !snake.ends_with('_') {
+                snake.push('_');
+            }
+            prev_lower = false;
+        }
+    }
+    snake.truncate(snake.trim_end_matches('_').len());
+    snake
+}
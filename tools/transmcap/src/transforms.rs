@@ -0,0 +1,64 @@
+//! Semantic transforms applied to decoded batches before flattening.
+
+use std::str::FromStr;
+
+use arrow::{error::ArrowError, record_batch::RecordBatch};
+use clap::Args;
+use mcapdecode::arrow::{
+    CovarianceColumns, LaserScanPoints, QuaternionColumns, add_extension_types,
+    add_quaternion_columns, laser_scan_to_points, name_covariance_columns,
+};
+
+#[derive(Args, Clone, Debug, Default)]
+pub struct TransformArgs {
+    /// Columns derived from every quaternion field (struct of x, y, z, w):
+    /// euler | euler-degrees | rotation-matrix
+    #[arg(long, value_name = "COLUMNS", value_parser = parse_quaternion_columns)]
+    pub quaternion_columns: Option<QuaternionColumns>,
+
+    /// Write covariance matrices (`covariance`, `*_covariance` fields) as
+    /// columns named after their axes: named | diagonal
+    #[arg(long, value_name = "COLUMNS", value_parser = parse_covariance_columns)]
+    pub covariance_columns: Option<CovarianceColumns>,
+
+    /// Convert `sensor_msgs/msg/LaserScan` ranges to x/y points: list (a
+    /// `points` column per scan) | explode (one row per point)
+    #[arg(long, value_name = "MODE", value_parser = parse_laser_scan_points)]
+    pub laser_scan_points: Option<LaserScanPoints>,
+
+    /// Register Arrow extension types (`arrow.uuid`, `ros2.quaternion`,
+    /// `ros2.geo_point`, `ros2.time`) in the metadata of semantic fields
+    #[arg(long)]
+    pub extension_types: bool,
+}
+
+impl TransformArgs {
+    /// Apply the requested transforms to a projected reader batch.
+    pub fn apply(&self, mut batch: RecordBatch) -> Result<RecordBatch, ArrowError> {
+        if let Some(points) = self.laser_scan_points {
+            batch = laser_scan_to_points(&batch, points)?;
+        }
+        if let Some(columns) = self.quaternion_columns {
+            batch = add_quaternion_columns(&batch, columns)?;
+        }
+        if let Some(columns) = self.covariance_columns {
+            batch = name_covariance_columns(&batch, columns)?;
+        }
+        if self.extension_types {
+            batch = add_extension_types(&batch)?;
+        }
+        Ok(batch)
+    }
+}
+
+fn parse_quaternion_columns(raw: &str) -> Result<QuaternionColumns, String> {
+    QuaternionColumns::from_str(raw)
+}
+
+fn parse_covariance_columns(raw: &str) -> Result<CovarianceColumns, String> {
+    CovarianceColumns::from_str(raw)
+}
+
+fn parse_laser_scan_points(raw: &str) -> Result<LaserScanPoints, String> {
+    LaserScanPoints::from_str(raw)
+}
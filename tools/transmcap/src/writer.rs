@@ -1,40 +1,290 @@
 use std::{
+    collections::HashMap,
+    fmt::Write as _,
     fs,
     io::{self, BufWriter, Write},
     path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
 };
 
-use anyhow::Result;
-use arrow::record_batch::RecordBatch;
+use anyhow::{Result, bail};
+use arrow::{
+    array::{Array, ArrayRef, AsArray, BooleanArray, Float64Array, Int64Array, UInt64Array},
+    compute::cast,
+    datatypes::{
+        DataType, FieldRef, Float64Type, Int64Type, Schema, SchemaRef, TimeUnit,
+        TimestampNanosecondType, UInt64Type,
+    },
+    record_batch::RecordBatch,
+    temporal_conversions::timestamp_ns_to_datetime,
+    util::display::{ArrayFormatter, FormatOptions},
+};
+use mcapdecode::arrow::{
+    LatestSnapshot, SchemaEvolution, SchemaIncompatibility, TimeColumns, apply_time_columns,
+    check_append_compatibility, conform_to_schema, merge_append_schema, schema_fingerprint,
+    with_latency_column,
+};
+use parquet::{
+    basic::Compression,
+    file::{
+        metadata::KeyValue,
+        properties::{EnabledStatistics, WriterProperties, WriterPropertiesBuilder, WriterVersion},
+    },
+};
+
+use crate::{
+    checkpoint::Checkpoint,
+    format::{JsonInt64, OutputCompression},
+    table_names::TableNames,
+    template::OutputTemplate,
+};
 
 pub trait RecordBatchWriter {
+    /// Called before the batches of each topic are written.
+    fn begin_topic(&mut self, _topic: &str) -> Result<()> {
+        Ok(())
+    }
     fn write_batch(&mut self, batch: RecordBatch) -> Result<()>;
+    /// Called once the rows decoded from the reader batch `messages` are
+    /// written, so that progress can be counted in input messages (the
+    /// transforms may write any number of rows per message).
+    fn messages_written(&mut self, _messages: &RecordBatch) -> Result<()> {
+        Ok(())
+    }
     fn finish(&mut self) -> Result<()>;
 }
 
+// --- Time columns ---
+
+/// Applies `--time-columns` and `--latency` to every batch before passing it on.
+pub struct TimeColumnsWriter {
+    inner: Box<dyn RecordBatchWriter>,
+    time_columns: TimeColumns,
+    /// Per-topic overrides of `time_columns` (from `--config`).
+    topic_time_columns: HashMap<String, TimeColumns>,
+    /// Time columns of the topic being written.
+    current: TimeColumns,
+    latency: bool,
+    /// Recording start; taken from the first `@log_time` written if unknown.
+    start_ns: Option<u64>,
+}
+
+impl TimeColumnsWriter {
+    pub fn new(
+        inner: Box<dyn RecordBatchWriter>,
+        time_columns: TimeColumns,
+        start_ns: Option<u64>,
+    ) -> Self {
+        Self {
+            inner,
+            time_columns,
+            topic_time_columns: HashMap::new(),
+            current: time_columns,
+            latency: false,
+            start_ns,
+        }
+    }
+
+    /// Write `topic_time_columns[topic]` instead for the topics it contains.
+    pub fn with_topic_time_columns(
+        mut self,
+        topic_time_columns: HashMap<String, TimeColumns>,
+    ) -> Self {
+        self.topic_time_columns = topic_time_columns;
+        self
+    }
+
+    /// Insert `@latency_ns` before the time columns are applied.
+    pub fn with_latency(mut self, latency: bool) -> Self {
+        self.latency = latency;
+        self
+    }
+}
+
+impl RecordBatchWriter for TimeColumnsWriter {
+    fn begin_topic(&mut self, topic: &str) -> Result<()> {
+        self.current = self
+            .topic_time_columns
+            .get(topic)
+            .copied()
+            .unwrap_or(self.time_columns);
+        self.inner.begin_topic(topic)
+    }
+
+    fn write_batch(&mut self, mut batch: RecordBatch) -> Result<()> {
+        if self.latency {
+            batch = with_latency_column(&batch)?;
+        }
+        if self.current == TimeColumns::Absolute {
+            return self.inner.write_batch(batch);
+        }
+        let start_ns = match self.start_ns {
+            Some(start_ns) => start_ns,
+            None => {
+                let Some(first) = first_log_time(&batch) else {
+                    return self.inner.write_batch(batch);
+                };
+                *self.start_ns.insert(first)
+            }
+        };
+        self.inner
+            .write_batch(apply_time_columns(&batch, self.current, start_ns)?)
+    }
+
+    fn messages_written(&mut self, messages: &RecordBatch) -> Result<()> {
+        self.inner.messages_written(messages)
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.inner.finish()
+    }
+}
+
+fn first_log_time(batch: &RecordBatch) -> Option<u64> {
+    let log_time = batch
+        .column_by_name("@log_time")?
+        .as_primitive_opt::<TimestampNanosecondType>()?;
+    log_time.iter().flatten().next()?.try_into().ok()
+}
+
+// --- Snapshots ---
+
+/// Reduces each topic to the latest row per key (`--snapshot`) before
+/// passing it on. Snapshot rows do not map to input messages, so
+/// [`RecordBatchWriter::messages_written`] is not passed on.
+pub struct SnapshotWriter {
+    inner: Box<dyn RecordBatchWriter>,
+    snapshot: LatestSnapshot,
+}
+
+impl SnapshotWriter {
+    pub fn new(inner: Box<dyn RecordBatchWriter>, snapshot: LatestSnapshot) -> Self {
+        Self { inner, snapshot }
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        match self.snapshot.finish() {
+            Some(state) => self.inner.write_batch(state),
+            None => Ok(()),
+        }
+    }
+}
+
+impl RecordBatchWriter for SnapshotWriter {
+    fn begin_topic(&mut self, topic: &str) -> Result<()> {
+        self.flush()?;
+        self.inner.begin_topic(topic)
+    }
+
+    fn write_batch(&mut self, batch: RecordBatch) -> Result<()> {
+        for snapshot in self.snapshot.push(&batch)? {
+            self.inner.write_batch(snapshot)?;
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.flush()?;
+        self.inner.finish()
+    }
+}
+
+// --- Output streams ---
+
+/// Text output destination, optionally compressed with `--compress`.
+pub enum OutputStream {
+    Plain(Box<dyn Write>),
+    Gzip(flate2::write::GzEncoder<Box<dyn Write>>),
+    Zstd(zstd::Encoder<'static, Box<dyn Write>>),
+}
+
+impl OutputStream {
+    /// Open `output` (stdout if `None`) and wrap it in the selected encoder.
+    pub fn open(output: Option<&Path>, compression: Option<OutputCompression>) -> Result<Self> {
+        let dest: Box<dyn Write> = match output {
+            Some(path) => Box::new(BufWriter::new(fs::File::create(path)?)),
+            None => Box::new(BufWriter::new(io::stdout().lock())),
+        };
+        Ok(match compression {
+            None => Self::Plain(dest),
+            Some(OutputCompression::Gzip) => Self::Gzip(flate2::write::GzEncoder::new(
+                dest,
+                flate2::Compression::default(),
+            )),
+            Some(OutputCompression::Zstd) => {
+                Self::Zstd(zstd::Encoder::new(dest, zstd::DEFAULT_COMPRESSION_LEVEL)?)
+            }
+        })
+    }
+
+    /// Write the compressed stream trailer, if any, and flush.
+    pub fn finish(&mut self) -> io::Result<()> {
+        // Encoders must not be written to (or flushed) after finishing.
+        match self {
+            Self::Plain(dest) => dest.flush(),
+            Self::Gzip(encoder) => {
+                encoder.try_finish()?;
+                encoder.get_mut().flush()
+            }
+            Self::Zstd(encoder) => {
+                encoder.do_finish()?;
+                encoder.get_mut().flush()
+            }
+        }
+    }
+
+    fn inner(&mut self) -> &mut dyn Write {
+        match self {
+            Self::Plain(dest) => dest,
+            Self::Gzip(encoder) => encoder,
+            Self::Zstd(encoder) => encoder,
+        }
+    }
+}
+
+impl Write for OutputStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.inner().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner().flush()
+    }
+}
+
 // --- JSON Lines ---
 
 pub struct JsonlWriter {
-    dest: Box<dyn Write>,
+    dest: OutputStream,
     flush_each_batch: bool,
+    int64: JsonInt64,
 }
 
 impl JsonlWriter {
-    pub fn new(output: Option<&Path>) -> Result<Self> {
+    pub fn new(output: Option<&Path>, compression: Option<OutputCompression>) -> Result<Self> {
         let flush_each_batch = output.is_none();
-        let dest: Box<dyn Write> = match output {
-            Some(path) => Box::new(BufWriter::new(fs::File::create(path)?)),
-            None => Box::new(BufWriter::new(io::stdout().lock())),
-        };
+        let dest = OutputStream::open(output, compression)?;
         Ok(Self {
             dest,
             flush_each_batch,
+            int64: JsonInt64::default(),
         })
     }
+
+    /// Select how 64-bit integer values are written.
+    pub fn with_int64(mut self, int64: JsonInt64) -> Self {
+        self.int64 = int64;
+        self
+    }
 }
 
 impl RecordBatchWriter for JsonlWriter {
     fn write_batch(&mut self, batch: RecordBatch) -> Result<()> {
+        let batch = match self.int64 {
+            JsonInt64::Number => batch,
+            JsonInt64::String => int64_columns_to_strings(&batch)?,
+        };
         let buf = Vec::new();
         let mut json_writer = arrow::json::LineDelimitedWriter::new(buf);
         json_writer.write(&batch)?;
@@ -48,26 +298,86 @@ impl RecordBatchWriter for JsonlWriter {
     }
 
     fn finish(&mut self) -> Result<()> {
-        self.dest.flush()?;
+        self.dest.finish()?;
         Ok(())
     }
 }
 
+/// Cast `Int64`/`UInt64` columns, including nested ones, to `Utf8` so JSON
+/// consumers that parse numbers as doubles keep every digit.
+fn int64_columns_to_strings(batch: &RecordBatch) -> Result<RecordBatch> {
+    let schema = batch.schema();
+    if !schema
+        .fields()
+        .iter()
+        .any(|field| int64_as_string_type(field.data_type()).is_some())
+    {
+        return Ok(batch.clone());
+    }
+    let mut fields = Vec::with_capacity(schema.fields().len());
+    let mut columns = Vec::with_capacity(fields.capacity());
+    for (field, column) in schema.fields().iter().zip(batch.columns()) {
+        match int64_as_string_type(field.data_type()) {
+            Some(data_type) => {
+                columns.push(cast(column, &data_type)?);
+                fields.push(Arc::new(field.as_ref().clone().with_data_type(data_type)));
+            }
+            None => {
+                columns.push(column.clone());
+                fields.push(field.clone());
+            }
+        }
+    }
+    Ok(RecordBatch::try_new(
+        Arc::new(Schema::new_with_metadata(fields, schema.metadata().clone())),
+        columns,
+    )?)
+}
+
+/// `data_type` with 64-bit integers replaced by `Utf8`, or `None` if it has none.
+fn int64_as_string_type(data_type: &DataType) -> Option<DataType> {
+    let field = |field: &FieldRef| {
+        int64_as_string_type(field.data_type())
+            .map(|data_type| Arc::new(field.as_ref().clone().with_data_type(data_type)))
+    };
+    match data_type {
+        DataType::Int64 | DataType::UInt64 => Some(DataType::Utf8),
+        DataType::Struct(fields) => {
+            let changed: Vec<_> = fields.iter().map(field).collect();
+            changed.iter().any(Option::is_some).then(|| {
+                DataType::Struct(
+                    changed
+                        .into_iter()
+                        .zip(fields.iter())
+                        .map(|(changed, field)| changed.unwrap_or_else(|| field.clone()))
+                        .collect(),
+                )
+            })
+        }
+        DataType::List(item) => field(item).map(DataType::List),
+        DataType::LargeList(item) => field(item).map(DataType::LargeList),
+        DataType::FixedSizeList(item, size) => {
+            field(item).map(|item| DataType::FixedSizeList(item, *size))
+        }
+        DataType::Map(entries, sorted) => {
+            field(entries).map(|entries| DataType::Map(entries, *sorted))
+        }
+        _ => None,
+    }
+}
+
 // --- CSV ---
 
 pub struct CsvWriter {
-    dest: Box<dyn Write>,
+    dest: OutputStream,
     header_written: bool,
     flush_each_batch: bool,
 }
 
 impl CsvWriter {
-    pub fn new(output: Option<&Path>) -> Result<Self> {
+    pub fn new(output: Option<&Path>, compression: Option<OutputCompression>) -> Result<Self> {
         let flush_each_batch = output.is_none();
-        let dest: Box<dyn Write> = match output {
-            Some(path) => Box::new(BufWriter::new(fs::File::create(path)?)),
-            None => Box::new(BufWriter::new(io::stdout().lock())),
-        };
+        let dest = OutputStream::open(output, compression)?;
         Ok(Self {
             dest,
             header_written: false,
@@ -97,7 +407,7 @@ impl RecordBatchWriter for CsvWriter {
     }
 
     fn finish(&mut self) -> Result<()> {
-        self.dest.flush()?;
+        self.dest.finish()?;
         Ok(())
     }
 }
@@ -108,6 +418,11 @@ pub struct ParquetWriter {
     output_path: PathBuf,
     inner: Option<parquet::arrow::ArrowWriter<fs::File>>,
     wrote_any_batch: bool,
+    append_to: Option<(PathBuf, SchemaRef, SchemaEvolution)>,
+    /// Schema merged with the existing dataset that batches are written
+    /// with, if it differs from theirs.
+    merged_schema: Option<SchemaRef>,
+    deterministic: bool,
 }
 
 impl ParquetWriter {
@@ -116,8 +431,30 @@ impl ParquetWriter {
             output_path: output.to_path_buf(),
             inner: None,
             wrote_any_batch: false,
+            append_to: None,
+            merged_schema: None,
+            deterministic: false,
         })
     }
+
+    /// Pin every writer property, including `created_by`, instead of
+    /// following the defaults of the `parquet` crate, so that the same input
+    /// is written to the same bytes by any build of this version.
+    pub fn with_deterministic(mut self) -> Self {
+        self.deterministic = true;
+        self
+    }
+
+    /// Refuse to write unless the output schema can be appended to the
+    /// dataset of the existing Parquet file at `path`; with
+    /// [`SchemaEvolution::AddColumns`], write the schema merged with the
+    /// dataset's instead (see [`merge_append_schema`]).
+    pub fn with_append_check(mut self, path: &Path, evolution: SchemaEvolution) -> Result<Self> {
+        let file = fs::File::open(path)?;
+        let builder = parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder::try_new(file)?;
+        self.append_to = Some((path.to_path_buf(), builder.schema().clone(), evolution));
+        Ok(self)
+    }
 }
 
 impl RecordBatchWriter for ParquetWriter {
@@ -125,15 +462,65 @@ impl RecordBatchWriter for ParquetWriter {
         self.wrote_any_batch = true;
 
         if self.inner.is_none() {
+            if let Some((path, existing, evolution)) = &self.append_to {
+                let merged = match merge_append_schema(existing, &batch.schema(), *evolution) {
+                    Ok(merged) => merged,
+                    Err(issues) => {
+                        let details: Vec<String> =
+                            issues.iter().map(|issue| format!("  - {issue}")).collect();
+                        bail!(
+                            "output schema cannot be appended to {}:\n{}",
+                            path.display(),
+                            details.join("\n")
+                        );
+                    }
+                };
+                let added: Vec<String> = check_append_compatibility(existing, &merged)
+                    .into_iter()
+                    .filter_map(|issue| match issue {
+                        SchemaIncompatibility::ExtraField { path } => Some(path),
+                        _ => None,
+                    })
+                    .collect();
+                if !added.is_empty() {
+                    eprintln!(
+                        "Adding {} column(s) to the dataset of {}: {}",
+                        added.len(),
+                        path.display(),
+                        added.join(", ")
+                    );
+                }
+                if merged != *batch.schema() {
+                    self.merged_schema = Some(Arc::new(merged));
+                }
+            }
             let file = fs::File::create(&self.output_path)?;
-            let props = parquet::file::properties::WriterProperties::builder().build();
+            // Schema metadata (e.g. `--provenance`) is also readable as
+            // plain Parquet key-value metadata.
+            let mut key_values: Vec<_> = batch
+                .schema()
+                .metadata()
+                .iter()
+                .map(|(key, value)| KeyValue::new(key.clone(), value.clone()))
+                .collect();
+            key_values.sort_by(|a, b| a.key.cmp(&b.key));
+            let mut props = WriterProperties::builder()
+                .set_key_value_metadata((!key_values.is_empty()).then_some(key_values));
+            if self.deterministic {
+                props = pin_writer_properties(props);
+            }
+            let props = props.build();
             self.inner = Some(parquet::arrow::ArrowWriter::try_new(
                 file,
-                batch.schema(),
+                self.merged_schema.clone().unwrap_or_else(|| batch.schema()),
                 Some(props),
             )?);
         }
 
+        let batch = match &self.merged_schema {
+            Some(schema) => conform_to_schema(&batch, schema.clone())?,
+            None => batch,
+        };
         self.inner.as_mut().unwrap().write(&batch)?;
         Ok(())
     }
@@ -151,3 +538,769 @@ impl RecordBatchWriter for ParquetWriter {
         Ok(())
     }
 }
+
+fn pin_writer_properties(props: WriterPropertiesBuilder) -> WriterPropertiesBuilder {
+    props
+        .set_writer_version(WriterVersion::PARQUET_1_0)
+        .set_compression(Compression::UNCOMPRESSED)
+        .set_dictionary_enabled(true)
+        .set_statistics_enabled(EnabledStatistics::Page)
+        .set_max_row_group_size(1024 * 1024)
+        .set_data_page_size_limit(1024 * 1024)
+        .set_dictionary_page_size_limit(1024 * 1024)
+        .set_data_page_row_count_limit(20_000)
+        .set_write_batch_size(1024)
+        .set_created_by(concat!("transmcap version ", env!("CARGO_PKG_VERSION")).to_string())
+}
+
+// --- Parquet per topic ---
+
+/// File listing the outputs of [`SplitParquetWriter`].
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Hive partition column holding the topic name with
+/// [`SplitParquetWriter::with_topic_partitions`].
+pub const TOPIC_PARTITION_COLUMN: &str = "@topic";
+
+/// Writes each topic to its own Parquet file in a directory (`/a/b` →
+/// `a_b.parquet`) and indexes them in [`MANIFEST_FILE`].
+///
+/// Until the manifest is written, a [`Checkpoint`] in the directory records
+/// the completed files.
+pub struct SplitParquetWriter {
+    dir: PathBuf,
+    input: PathBuf,
+    topic_partitions: bool,
+    template: OutputTemplate,
+    table_names: TableNames,
+    deterministic: bool,
+    /// Length of the `@log_time` windows each topic is split into.
+    window_ns: Option<i64>,
+    /// Topic whose batches are being written.
+    topic: Option<SplitTopic>,
+    /// Topic written to each file name so far, to detect collisions.
+    file_topics: HashMap<String, String>,
+    current: Option<SplitOutput>,
+    checkpoint: Checkpoint,
+}
+
+struct SplitTopic {
+    name: String,
+    rows: u64,
+    /// Messages of the topic written to completed files.
+    messages: u64,
+    /// Messages written since, whose file is still open.
+    pending: u64,
+    /// A file was completed since the checkpoint was last saved.
+    unsaved: bool,
+}
+
+struct SplitOutput {
+    topic: String,
+    file_name: String,
+    writer: ParquetWriter,
+    rows: u64,
+    time_range: Option<(i64, i64)>,
+    schema_fingerprint: Option<u64>,
+    /// Start of the `@log_time` window of the file, if split by time.
+    window_start: Option<i64>,
+}
+
+impl SplitParquetWriter {
+    /// Create `dir` if needed; `input` is recorded in the manifest.
+    pub fn new(dir: &Path, input: &Path) -> Result<Self> {
+        fs::create_dir_all(dir)?;
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            input: input.to_path_buf(),
+            topic_partitions: false,
+            template: OutputTemplate::default(),
+            table_names: TableNames::default(),
+            deterministic: false,
+            window_ns: None,
+            topic: None,
+            file_topics: HashMap::new(),
+            current: None,
+            checkpoint: Checkpoint::new(input),
+        })
+    }
+
+    /// Continue the conversion recorded in `checkpoint`, keeping its
+    /// completed files.
+    pub fn with_resume(mut self, checkpoint: Checkpoint) -> Self {
+        for entry in &checkpoint.outputs {
+            if let (Some(file), Some(topic)) = (entry["file"].as_str(), entry["topic"].as_str()) {
+                self.file_topics.insert(file.to_string(), topic.to_string());
+            }
+        }
+        self.checkpoint = checkpoint;
+        self
+    }
+
+    /// Lay the files out as a Hive-partitioned dataset instead
+    /// (`/a/b` → `@topic=%2Fa%2Fb/part-0.parquet`), so dataset readers derive
+    /// the [`TOPIC_PARTITION_COLUMN`] from the directory names rather than
+    /// from a constant column stored in every file.
+    pub fn with_topic_partitions(mut self) -> Self {
+        self.topic_partitions = true;
+        self
+    }
+
+    /// Name the files after `template` instead of `{topic}.{ext}`.
+    pub fn with_output_template(mut self, template: OutputTemplate) -> Self {
+        self.template = template;
+        self
+    }
+
+    /// Name the files after the `table_names` of the topics. Hive partitions
+    /// are named after the explicitly mapped names, the topic otherwise.
+    pub fn with_table_names(mut self, table_names: TableNames) -> Self {
+        self.table_names = table_names;
+        self
+    }
+
+    /// Write every file as [`ParquetWriter::with_deterministic`] does.
+    pub fn with_deterministic(mut self) -> Self {
+        self.deterministic = true;
+        self
+    }
+
+    /// Start a new file whenever `@log_time` enters the next `window` (aligned
+    /// to the Unix epoch), named after the window start. Rows are expected in
+    /// `@log_time` order.
+    pub fn with_time_windows(mut self, window: Duration) -> Self {
+        self.window_ns = Some(i64::try_from(window.as_nanos()).unwrap_or(i64::MAX).max(1));
+        self
+    }
+
+    fn open_output(&mut self, window_start: Option<i64>) -> Result<()> {
+        let Some(SplitTopic { name: topic, .. }) = &self.topic else {
+            bail!("split Parquet output requires a topic");
+        };
+        let window = window_start
+            .zip(self.window_ns)
+            .map(|(start, window_ns)| window_label(start, window_ns));
+        let file_name = if self.topic_partitions {
+            let value = self.table_names.explicit(topic).unwrap_or(topic);
+            let partition = format!("{TOPIC_PARTITION_COLUMN}={}", hive_escape(value));
+            fs::create_dir_all(self.dir.join(&partition))?;
+            match &window {
+                Some(window) => format!("{partition}/part-{window}.parquet"),
+                None => format!("{partition}/part-0.parquet"),
+            }
+        } else {
+            let table = self.table_names.name(topic);
+            let file_name = self
+                .template
+                .render(topic, &table, window.as_deref(), "parquet");
+            if let Some(parent) = Path::new(&file_name).parent() {
+                fs::create_dir_all(self.dir.join(parent))?;
+            }
+            file_name
+        };
+        if let Some(other) = self.file_topics.insert(file_name.clone(), topic.clone()) {
+            if other == *topic {
+                bail!("{file_name} was already written: {topic} is not in @log_time order");
+            }
+            bail!("Topics {other} and {topic} would both be written to {file_name}");
+        }
+        let mut writer = ParquetWriter::new(&self.dir.join(&file_name))?;
+        if self.deterministic {
+            writer = writer.with_deterministic();
+        }
+        self.current = Some(SplitOutput {
+            topic: topic.clone(),
+            writer,
+            file_name,
+            rows: 0,
+            time_range: None,
+            schema_fingerprint: None,
+            window_start,
+        });
+        Ok(())
+    }
+
+    fn close_output(&mut self) -> Result<()> {
+        let Some(mut output) = self.current.take() else {
+            return Ok(());
+        };
+        if output.rows == 0 {
+            return Ok(());
+        }
+        output.writer.finish()?;
+        let (start_time, end_time) = output.time_range.unzip();
+        let mut entry = serde_json::json!({
+            "file": output.file_name,
+            "topic": output.topic,
+            "rows": output.rows,
+            "start_time": start_time,
+            "end_time": end_time,
+            "schema_fingerprint": output.schema_fingerprint.map(|fp| format!("{fp:016x}")),
+        });
+        if let Some(window_start) = output.window_start {
+            entry["window_start"] = serde_json::json!(window_start);
+        }
+        self.checkpoint.outputs.push(entry);
+        // Saved by `messages_written` once the messages of the file are known.
+        if let Some(topic) = self.topic.as_mut() {
+            topic.messages += std::mem::take(&mut topic.pending);
+            topic.unsaved = true;
+        }
+        Ok(())
+    }
+
+    fn finish_topic(&mut self) -> Result<()> {
+        self.close_output()?;
+        let Some(topic) = self.topic.take() else {
+            return Ok(());
+        };
+        if topic.rows == 0 && topic.messages == 0 {
+            eprintln!("Warning: no messages written for topic {}", topic.name);
+        }
+        let progress = self.checkpoint.topics.entry(topic.name).or_default();
+        progress.messages = topic.messages;
+        progress.complete = true;
+        self.checkpoint.save(&self.dir)
+    }
+
+    fn write_to_output(&mut self, batch: RecordBatch) -> Result<()> {
+        let Some(output) = self.current.as_mut() else {
+            bail!("split Parquet output requires a topic");
+        };
+        if let Some(topic) = self.topic.as_mut() {
+            topic.rows += batch.num_rows() as u64;
+        }
+        output.rows += batch.num_rows() as u64;
+        output
+            .schema_fingerprint
+            .get_or_insert_with(|| schema_fingerprint(&batch.schema()));
+        if let Some(log_time) = batch
+            .column_by_name("@log_time")
+            .and_then(|col| col.as_primitive_opt::<TimestampNanosecondType>())
+        {
+            for time in log_time.iter().flatten() {
+                let (start, end) = output.time_range.get_or_insert((time, time));
+                *start = (*start).min(time);
+                *end = (*end).max(time);
+            }
+        }
+        output.writer.write_batch(batch)
+    }
+}
+
+impl RecordBatchWriter for SplitParquetWriter {
+    fn begin_topic(&mut self, topic: &str) -> Result<()> {
+        self.finish_topic()?;
+        let resumed = self
+            .checkpoint
+            .topics
+            .get(topic)
+            .map_or(0, |progress| progress.messages);
+        self.topic = Some(SplitTopic {
+            name: topic.to_string(),
+            rows: 0,
+            messages: resumed,
+            pending: 0,
+            unsaved: false,
+        });
+        if self.window_ns.is_none() {
+            self.open_output(None)?;
+        }
+        Ok(())
+    }
+
+    fn write_batch(&mut self, batch: RecordBatch) -> Result<()> {
+        if batch.num_rows() == 0 {
+            return Ok(());
+        }
+        let Some(window_ns) = self.window_ns else {
+            return self.write_to_output(batch);
+        };
+        let Some(log_time) = batch
+            .column_by_name("@log_time")
+            .and_then(|col| col.as_primitive_opt::<TimestampNanosecondType>())
+        else {
+            bail!("--split-by requires the @log_time column");
+        };
+        // Rows without a log time stay in the window of the preceding row.
+        let mut current = self.current.as_ref().and_then(|o| o.window_start);
+        let windows: Vec<Option<i64>> = log_time
+            .iter()
+            .map(|time| {
+                if let Some(time) = time {
+                    current = Some(time - time.rem_euclid(window_ns));
+                }
+                current
+            })
+            .collect();
+        let mut start = 0;
+        while start < windows.len() {
+            let window = windows[start];
+            let len = windows[start..]
+                .iter()
+                .take_while(|w| **w == window)
+                .count();
+            if self.current.is_none()
+                || self.current.as_ref().and_then(|o| o.window_start) != window
+            {
+                self.close_output()?;
+                self.open_output(window)?;
+            }
+            self.write_to_output(batch.slice(start, len))?;
+            start += len;
+        }
+        Ok(())
+    }
+
+    fn messages_written(&mut self, messages: &RecordBatch) -> Result<()> {
+        let Some(topic) = self.topic.as_mut() else {
+            return Ok(());
+        };
+        // Messages before the window of the open file were written to
+        // completed files, even if a transform left no rows for them.
+        let open_window = self.current.as_ref().and_then(|o| o.window_start);
+        let log_time = messages
+            .column_by_name("@log_time")
+            .and_then(|col| col.as_primitive_opt::<TimestampNanosecondType>());
+        match (self.window_ns, open_window, log_time) {
+            (Some(window_ns), Some(open_window), Some(log_time)) => {
+                for time in log_time.iter() {
+                    match time {
+                        Some(time) if time - time.rem_euclid(window_ns) < open_window => {
+                            topic.messages += 1;
+                        }
+                        _ => topic.pending += 1,
+                    }
+                }
+            }
+            _ => topic.pending += messages.num_rows() as u64,
+        }
+        if !std::mem::take(&mut topic.unsaved) {
+            return Ok(());
+        }
+        self.checkpoint
+            .topics
+            .entry(topic.name.clone())
+            .or_default()
+            .messages = topic.messages;
+        self.checkpoint.save(&self.dir)
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.finish_topic()?;
+        if self.checkpoint.outputs.is_empty() {
+            bail!("No messages found for the selected topics");
+        }
+        let mut manifest = serde_json::json!({
+            "input": self.input.display().to_string(),
+            "outputs": self.checkpoint.outputs,
+        });
+        if self.topic_partitions {
+            manifest["partition_columns"] = serde_json::json!([TOPIC_PARTITION_COLUMN]);
+        }
+        let path = self.dir.join(MANIFEST_FILE);
+        let mut file = BufWriter::new(fs::File::create(&path)?);
+        serde_json::to_writer_pretty(&mut file, &manifest)?;
+        writeln!(file)?;
+        file.flush()?;
+        Checkpoint::remove(&self.dir)?;
+        eprintln!("Written to {}", path.display());
+        Ok(())
+    }
+}
+
+/// File name label of the window starting at `start_ns`: its UTC time as
+/// `2024-05-01T12-00`, with seconds unless windows are whole minutes.
+fn window_label(start_ns: i64, window_ns: i64) -> String {
+    let Some(start) = timestamp_ns_to_datetime(start_ns) else {
+        return start_ns.to_string();
+    };
+    if window_ns % 60_000_000_000 == 0 {
+        start.format("%Y-%m-%dT%H-%M").to_string()
+    } else {
+        start.format("%Y-%m-%dT%H-%M-%S").to_string()
+    }
+}
+
+/// Percent-encode `value` for a Hive partition directory name, keeping only
+/// ASCII alphanumerics and `-_.`.
+fn hive_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-_.".contains(&byte) {
+            escaped.push(char::from(byte));
+        } else {
+            let _ = write!(escaped, "%{byte:02X}");
+        }
+    }
+    escaped
+}
+
+// --- InfluxDB line protocol ---
+
+/// Writes one line per row with the topic as measurement, the selected tag
+/// columns as tags, the remaining scalar columns as fields, and `@log_time`
+/// as nanosecond timestamp.
+pub struct InfluxLineWriter {
+    dest: OutputStream,
+    flush_each_batch: bool,
+    tag_columns: Vec<String>,
+    measurement: String,
+}
+
+impl InfluxLineWriter {
+    pub fn new(
+        output: Option<&Path>,
+        compression: Option<OutputCompression>,
+        tag_columns: Vec<String>,
+    ) -> Result<Self> {
+        let flush_each_batch = output.is_none();
+        let dest = OutputStream::open(output, compression)?;
+        Ok(Self {
+            dest,
+            flush_each_batch,
+            tag_columns,
+            measurement: String::new(),
+        })
+    }
+}
+
+impl RecordBatchWriter for InfluxLineWriter {
+    fn begin_topic(&mut self, topic: &str) -> Result<()> {
+        self.measurement = escape_influx(topic, &[',', ' ']);
+        Ok(())
+    }
+
+    fn write_batch(&mut self, batch: RecordBatch) -> Result<()> {
+        let schema = batch.schema();
+        let Some(log_time) = batch.column_by_name("@log_time") else {
+            bail!("line protocol output requires the '@log_time' column");
+        };
+        let log_time = cast(log_time, &DataType::Timestamp(TimeUnit::Nanosecond, None))?;
+        let log_time = log_time.as_primitive::<TimestampNanosecondType>();
+
+        let mut tags = Vec::with_capacity(self.tag_columns.len());
+        for name in &self.tag_columns {
+            let Some(col) = batch.column_by_name(name) else {
+                bail!("tag column '{name}' not found");
+            };
+            tags.push((
+                escape_influx(name, &[',', '=', ' ']),
+                InfluxColumn::tag(col)?,
+            ));
+        }
+        let mut fields = Vec::new();
+        for (field, col) in schema.fields().iter().zip(batch.columns()) {
+            let name = field.name();
+            if name.starts_with('@') || self.tag_columns.contains(name) {
+                continue;
+            }
+            if let Some(column) = InfluxColumn::field(col)? {
+                fields.push((escape_influx(name, &[',', '=', ' ']), column));
+            }
+        }
+
+        let mut line = String::new();
+        for row in 0..batch.num_rows() {
+            line.clear();
+            line.push_str(&self.measurement);
+            for (key, column) in &tags {
+                if let Some(value) = column.tag_value(row) {
+                    write!(line, ",{key}={value}")?;
+                }
+            }
+            let mut separator = ' ';
+            for (key, column) in &fields {
+                if let Some(value) = column.field_value(row) {
+                    write!(line, "{separator}{key}={value}")?;
+                    separator = ',';
+                }
+            }
+            // A point without fields is invalid line protocol.
+            if separator == ' ' || log_time.is_null(row) {
+                continue;
+            }
+            writeln!(line, " {}", log_time.value(row))?;
+            self.dest.write_all(line.as_bytes())?;
+        }
+        if self.flush_each_batch {
+            self.dest.flush()?;
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.dest.finish()?;
+        Ok(())
+    }
+}
+
+/// A batch column converted for line protocol encoding.
+enum InfluxColumn {
+    Float(Float64Array),
+    Int(Int64Array),
+    UInt(UInt64Array),
+    Bool(BooleanArray),
+    Text(Vec<Option<String>>),
+}
+
+impl InfluxColumn {
+    fn tag(col: &ArrayRef) -> Result<Self> {
+        Ok(Self::Text(format_column(col)?))
+    }
+
+    /// Returns `None` for nested and binary columns, which have no line
+    /// protocol field representation.
+    fn field(col: &ArrayRef) -> Result<Option<Self>> {
+        let dt = col.data_type();
+        let column = match dt {
+            DataType::Float16 | DataType::Float32 | DataType::Float64 => Self::Float(
+                cast(col, &DataType::Float64)?
+                    .as_primitive::<Float64Type>()
+                    .clone(),
+            ),
+            DataType::Int8 | DataType::Int16 | DataType::Int32 | DataType::Int64 => Self::Int(
+                cast(col, &DataType::Int64)?
+                    .as_primitive::<Int64Type>()
+                    .clone(),
+            ),
+            DataType::UInt8 | DataType::UInt16 | DataType::UInt32 | DataType::UInt64 => Self::UInt(
+                cast(col, &DataType::UInt64)?
+                    .as_primitive::<UInt64Type>()
+                    .clone(),
+            ),
+            DataType::Boolean => Self::Bool(col.as_boolean().clone()),
+            dt if dt.is_nested() || matches!(dt, DataType::Binary | DataType::LargeBinary) => {
+                return Ok(None);
+            }
+            _ => Self::Text(format_column(col)?),
+        };
+        Ok(Some(column))
+    }
+
+    fn tag_value(&self, row: usize) -> Option<String> {
+        match self {
+            Self::Text(values) => values[row]
+                .as_deref()
+                .filter(|v| !v.is_empty())
+                .map(|v| escape_influx(v, &[',', '=', ' '])),
+            _ => None,
+        }
+    }
+
+    fn field_value(&self, row: usize) -> Option<String> {
+        match self {
+            Self::Float(values) => (values.is_valid(row) && values.value(row).is_finite())
+                .then(|| values.value(row).to_string()),
+            Self::Int(values) => values
+                .is_valid(row)
+                .then(|| format!("{}i", values.value(row))),
+            Self::UInt(values) => values
+                .is_valid(row)
+                .then(|| format!("{}u", values.value(row))),
+            Self::Bool(values) => values.is_valid(row).then(|| values.value(row).to_string()),
+            Self::Text(values) => values[row]
+                .as_deref()
+                .map(|v| format!("\"{}\"", escape_influx(v, &['"', '\\']))),
+        }
+    }
+}
+
+fn format_column(col: &ArrayRef) -> Result<Vec<Option<String>>> {
+    let formatter = ArrayFormatter::try_new(col.as_ref(), &FormatOptions::default())?;
+    Ok((0..col.len())
+        .map(|i| col.is_valid(i).then(|| formatter.value(i).to_string()))
+        .collect())
+}
+
+/// Backslash-escape `special` characters in `raw`.
+fn escape_influx(raw: &str, special: &[char]) -> String {
+    let mut out = String::with_capacity(raw.len());
+    for c in raw.chars() {
+        if special.contains(&c) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+// --- ADBC ---
+
+/// Appends batches to database tables through an ADBC driver, creating each
+/// table from the first batch's schema.
+///
+/// Without an explicit table name, each topic is written to the table its
+/// [`TableNames`] entry names (`/a/b` → `a_b` by default).
+#[cfg(feature = "adbc")]
+pub struct AdbcWriter {
+    connection: adbc_driver_manager::ManagedConnection,
+    // Kept alive for the lifetime of `connection`.
+    _database: adbc_driver_manager::ManagedDatabase,
+    fixed_table: Option<String>,
+    table_names: TableNames,
+    table: Option<String>,
+    wrote_any_batch: bool,
+}
+
+#[cfg(feature = "adbc")]
+impl AdbcWriter {
+    /// `driver` is a driver library name (e.g. `adbc_driver_postgresql`) or path.
+    pub fn new(
+        driver: &str,
+        uri: &str,
+        table: Option<String>,
+        table_names: TableNames,
+    ) -> Result<Self> {
+        use adbc_core::{
+            Database, Driver,
+            options::{AdbcVersion, OptionDatabase},
+        };
+        use adbc_driver_manager::ManagedDriver;
+
+        let mut driver = if Path::new(driver).extension().is_some() || driver.contains('/') {
+            ManagedDriver::load_dynamic_from_filename(driver, None, AdbcVersion::V110)?
+        } else {
+            ManagedDriver::load_dynamic_from_name(driver, None, AdbcVersion::V110)?
+        };
+        let database = driver.new_database_with_opts([(OptionDatabase::Uri, uri.into())])?;
+        let connection = database.new_connection()?;
+        Ok(Self {
+            connection,
+            _database: database,
+            table: table.clone(),
+            fixed_table: table,
+            table_names,
+            wrote_any_batch: false,
+        })
+    }
+}
+
+#[cfg(feature = "adbc")]
+impl RecordBatchWriter for AdbcWriter {
+    fn begin_topic(&mut self, topic: &str) -> Result<()> {
+        if self.fixed_table.is_none() {
+            self.table = Some(self.table_names.name(topic));
+        }
+        Ok(())
+    }
+
+    fn write_batch(&mut self, batch: RecordBatch) -> Result<()> {
+        use adbc_core::{
+            Connection, Optionable, Statement,
+            options::{IngestMode, OptionStatement},
+        };
+
+        let Some(table) = &self.table else {
+            bail!("ADBC output requires a target table");
+        };
+        let mut statement = self.connection.new_statement()?;
+        statement.set_option(OptionStatement::TargetTable, table.as_str().into())?;
+        statement.set_option(OptionStatement::IngestMode, IngestMode::CreateAppend.into())?;
+        statement.bind(batch)?;
+        statement.execute_update()?;
+        self.wrote_any_batch = true;
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        if !self.wrote_any_batch {
+            bail!("No messages found for the selected topic");
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::{
+        array::{Float32Array, ListArray, TimestampNanosecondArray},
+        datatypes::{Field, Float32Type},
+    };
+    use mcapdecode::arrow::{LaserScanPoints, laser_scan_to_points};
+
+    use super::*;
+
+    const SECOND_NS: i64 = 1_000_000_000;
+
+    /// Laser scans with two points each, logged at `seconds`; the scan at
+    /// `empty` has no valid range.
+    fn scans(seconds: &[i64], empty: i64) -> RecordBatch {
+        let log_time: ArrayRef = Arc::new(TimestampNanosecondArray::from_iter_values(
+            seconds.iter().map(|s| s * SECOND_NS),
+        ));
+        let angle: ArrayRef = Arc::new(Float32Array::from(vec![0.5; seconds.len()]));
+        let ranges: ArrayRef = Arc::new(ListArray::from_iter_primitive::<Float32Type, _, _>(
+            seconds.iter().map(|s| {
+                let range = if *s == empty { f32::NAN } else { 1.0 };
+                Some(vec![Some(range), Some(range)])
+            }),
+        ));
+        let schema = Schema::new(vec![
+            Field::new(
+                "@log_time",
+                DataType::Timestamp(TimeUnit::Nanosecond, None),
+                false,
+            ),
+            Field::new("angle_min", DataType::Float32, false),
+            Field::new("angle_increment", DataType::Float32, false),
+            Field::new_list(
+                "ranges",
+                Field::new_list_field(DataType::Float32, true),
+                false,
+            ),
+        ]);
+        RecordBatch::try_new(
+            Arc::new(schema),
+            vec![log_time, angle.clone(), angle, ranges],
+        )
+        .unwrap()
+    }
+
+    fn write_exploded(writer: &mut SplitParquetWriter, messages: &RecordBatch) {
+        let points = laser_scan_to_points(messages, LaserScanPoints::Explode).unwrap();
+        writer.write_batch(points).unwrap();
+        writer.messages_written(messages).unwrap();
+    }
+
+    #[test]
+    fn split_writer_resumes_exploded_scans_after_their_messages() {
+        let dir =
+            std::env::temp_dir().join(format!("transmcap-resume-exploded-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let input = Path::new("scans.mcap");
+        let window = Duration::from_secs(10);
+
+        // Interrupted after the window of the first file is complete.
+        let mut writer = SplitParquetWriter::new(&dir, input)
+            .unwrap()
+            .with_time_windows(window)
+            .with_output_template(OutputTemplate::windowed());
+        writer.begin_topic("/scan").unwrap();
+        write_exploded(&mut writer, &scans(&[1, 2, 11, 12], 12));
+        drop(writer);
+
+        let checkpoint = Checkpoint::load(&dir, input).unwrap();
+        assert_eq!(checkpoint.resume_offset("/scan"), Some(2));
+
+        let mut writer = SplitParquetWriter::new(&dir, input)
+            .unwrap()
+            .with_time_windows(window)
+            .with_output_template(OutputTemplate::windowed())
+            .with_resume(checkpoint);
+        writer.begin_topic("/scan").unwrap();
+        write_exploded(&mut writer, &scans(&[11, 12, 21], 12));
+        writer.finish().unwrap();
+
+        let manifest: serde_json::Value =
+            serde_json::from_reader(fs::File::open(dir.join(MANIFEST_FILE)).unwrap()).unwrap();
+        let rows: Vec<u64> = manifest["outputs"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|entry| entry["rows"].as_u64().unwrap())
+            .collect();
+        assert_eq!(rows, [4, 2, 2]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}